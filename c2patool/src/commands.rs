@@ -0,0 +1,492 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Subcommands to inspect, sign, verify, compare and extract C2PA manifests.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Result;
+use c2pa::{
+    embed_from_json, get_signer_from_files, jumbf_io, JumbfTree, ManifestStore, ManifestStoreDiff,
+    ManifestStoreReport, Reader, StatusCodes, TrustConfig, TrustSource, ValidationResults,
+    VerifyOptions,
+};
+use structopt::StructOpt;
+
+/// Subcommands to inspect, sign, verify, compare and extract manifests
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Display the manifest store of an asset
+    Inspect {
+        /// Path to the asset
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// Output format, `tree` or `json`
+        #[structopt(short = "f", long = "format", default_value = "tree")]
+        format: Format,
+
+        /// Display the detailed internal manifest data as JSON
+        #[structopt(short = "d", long = "detailed")]
+        detailed: bool,
+
+        /// Display the raw JUMBF box tree of the manifest store
+        #[structopt(short = "j", long = "jumbf")]
+        jumbf: bool,
+    },
+
+    /// Sign an asset with a manifest described by a JSON manifest definition
+    Sign {
+        /// Path to the asset to sign
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// Path to the JSON manifest definition, files it references are relative to it
+        #[structopt(short = "m", long = "manifest", parse(from_os_str))]
+        manifest: PathBuf,
+
+        /// Path to write the signed asset to
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: PathBuf,
+
+        /// Path to the PEM signing certificate chain
+        #[structopt(long = "cert", parse(from_os_str))]
+        cert: PathBuf,
+
+        /// Path to the PEM private key
+        #[structopt(long = "key", parse(from_os_str))]
+        key: PathBuf,
+
+        /// Signing algorithm, one of ps256, ps384, ps512, es256, es384, es512 or ed25519
+        #[structopt(long = "alg", default_value = "ps256")]
+        alg: String,
+
+        /// URL of a time-stamp authority
+        #[structopt(long = "tsa-url")]
+        tsa_url: Option<String>,
+    },
+
+    /// Validate the manifest store of an asset, exiting with status 1 if it is not valid
+    Verify {
+        /// Path to the asset
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// Output format, `tree` or `json`
+        #[structopt(short = "f", long = "format", default_value = "tree")]
+        format: Format,
+
+        /// PEM trust anchors the signing credentials must chain to
+        #[structopt(long = "trust-anchors", parse(from_os_str))]
+        trust_anchors: Option<PathBuf>,
+
+        /// Signing certificates, or their SHA-256 hashes, trusted without a chain
+        #[structopt(long = "allowed-list", parse(from_os_str))]
+        allowed_list: Option<PathBuf>,
+
+        /// Extended key usage OIDs accepted in signing certificates
+        #[structopt(long = "eku-config", parse(from_os_str))]
+        eku_config: Option<PathBuf>,
+    },
+
+    /// Compare the manifest stores of two assets, exiting with status 1 if they differ
+    Diff {
+        /// Path to the asset before
+        #[structopt(parse(from_os_str))]
+        before: PathBuf,
+
+        /// Path to the asset after
+        #[structopt(parse(from_os_str))]
+        after: PathBuf,
+
+        /// Output format, `tree` or `json`
+        #[structopt(short = "f", long = "format", default_value = "tree")]
+        format: Format,
+    },
+
+    /// Write the manifest store of an asset, as JUMBF and JSON, and its thumbnails to a folder
+    Extract {
+        /// Path to the asset
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+
+        /// Folder to write to, created if needed
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Tree,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "tree" => Ok(Format::Tree),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format {}, must be tree or json", s)),
+        }
+    }
+}
+
+/// Runs a subcommand, returning whether the asset is valid or unchanged
+pub fn run(command: Command) -> Result<bool> {
+    match command {
+        Command::Inspect {
+            path,
+            format,
+            detailed,
+            jumbf,
+        } => inspect(&path, format, detailed, jumbf),
+        Command::Sign {
+            path,
+            manifest,
+            output,
+            cert,
+            key,
+            alg,
+            tsa_url,
+        } => sign(&path, &manifest, &output, &cert, &key, &alg, tsa_url),
+        Command::Verify {
+            path,
+            format,
+            trust_anchors,
+            allowed_list,
+            eku_config,
+        } => verify(&path, format, trust_anchors, allowed_list, eku_config),
+        Command::Diff {
+            before,
+            after,
+            format,
+        } => diff(&before, &after, format),
+        Command::Extract { path, output } => extract(&path, &output),
+    }
+}
+
+fn inspect(path: &Path, format: Format, detailed: bool, jumbf: bool) -> Result<bool> {
+    if jumbf {
+        let tree = JumbfTree::from_file(path)?;
+        match format {
+            Format::Json => println!("{}", serde_json::to_string_pretty(&tree)?),
+            Format::Tree => print!("{}", tree),
+        }
+        return Ok(true);
+    }
+    if detailed {
+        println!("{}", ManifestStoreReport::from_file(path)?);
+        return Ok(true);
+    }
+
+    let reader = Reader::from_file(path)?;
+    match format {
+        Format::Json => println!("{}", reader.json()?),
+        Format::Tree => print!(
+            "{}",
+            manifest_tree(reader.manifest_store(), reader.validation_results())
+        ),
+    }
+    Ok(true)
+}
+
+fn sign(
+    path: &Path,
+    manifest: &Path,
+    output: &Path,
+    cert: &Path,
+    key: &Path,
+    alg: &str,
+    tsa_url: Option<String>,
+) -> Result<bool> {
+    let json = fs::read_to_string(manifest)?;
+    let base_dir = manifest.parent().unwrap_or_else(|| Path::new("."));
+    let signer = get_signer_from_files(cert, key, alg, tsa_url)?;
+
+    let label = embed_from_json(&json, base_dir, path, output, &*signer)?;
+    println!("Signed {} with manifest {}", output.display(), label);
+    Ok(true)
+}
+
+fn verify(
+    path: &Path,
+    format: Format,
+    trust_anchors: Option<PathBuf>,
+    allowed_list: Option<PathBuf>,
+    eku_config: Option<PathBuf>,
+) -> Result<bool> {
+    let mut options = VerifyOptions::new();
+    if trust_anchors.is_some() || allowed_list.is_some() || eku_config.is_some() {
+        let trust_config = TrustConfig::new();
+        if let Some(path) = trust_anchors {
+            trust_config.add_trust_anchors(TrustSource::File(path))?;
+        }
+        if let Some(path) = allowed_list {
+            trust_config.add_allowed_list(TrustSource::File(path))?;
+        }
+        if let Some(path) = eku_config {
+            trust_config.add_eku_config(TrustSource::File(path))?;
+        }
+        options.set_trust_config(trust_config);
+    }
+
+    let reader = Reader::from_file_with_options(path, &options)?;
+    let results = reader.validation_results();
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(results)?),
+        Format::Tree => print!("{}", results_tree(results)),
+    }
+    Ok(results.is_valid())
+}
+
+fn diff(before: &Path, after: &Path, format: Format) -> Result<bool> {
+    let diff = ManifestStoreDiff::from_files(before, after)?;
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        Format::Tree => print!("{}", diff),
+    }
+    Ok(diff.is_empty())
+}
+
+fn extract(path: &Path, output: &Path) -> Result<bool> {
+    let jumbf = jumbf_io::load_jumbf_from_file(path)?;
+    let manifest_store = ManifestStore::from_file(path)?;
+
+    fs::create_dir_all(output)?;
+    fs::write(output.join("manifest_store.c2pa"), jumbf)?;
+    fs::write(
+        output.join("manifest_store.json"),
+        serde_json::to_string_pretty(&manifest_store)?,
+    )?;
+
+    for (label, manifest) in manifest_store.manifests() {
+        let name = file_name(label);
+        if let Some((format, data)) = manifest.asset().and_then(|asset| asset.thumbnail()) {
+            let thumbnail = format!("{}.{}", name, extension(format));
+            fs::write(output.join(thumbnail), data)?;
+        }
+        for (index, ingredient) in manifest.ingredients().iter().enumerate() {
+            if let Some((format, data)) = ingredient.thumbnail() {
+                let thumbnail = format!("{}_ingredient_{}.{}", name, index, extension(format));
+                fs::write(output.join(thumbnail), data)?;
+            }
+        }
+    }
+
+    println!("Extracted the manifest store to {}", output.display());
+    Ok(true)
+}
+
+// a node of the human readable tree output
+struct Node {
+    lines: Vec<String>,
+    children: Vec<Node>,
+}
+
+impl Node {
+    fn render(&self, out: &mut String, prefix: &str, is_root: bool, is_last: bool) {
+        let (branch, indent) = match (is_root, is_last) {
+            (true, _) => ("", ""),
+            (false, true) => ("└── ", "    "),
+            (false, false) => ("├── ", "│   "),
+        };
+        let child_prefix = format!("{}{}", prefix, indent);
+        let detail_indent = if self.children.is_empty() {
+            "    "
+        } else {
+            "│   "
+        };
+
+        for (index, line) in self.lines.iter().enumerate() {
+            if index == 0 {
+                out.push_str(&format!("{}{}{}\n", prefix, branch, line));
+            } else {
+                out.push_str(&format!("{}{}{}\n", child_prefix, detail_indent, line));
+            }
+        }
+        for (index, child) in self.children.iter().enumerate() {
+            child.render(out, &child_prefix, false, index + 1 == self.children.len());
+        }
+    }
+}
+
+// renders the active manifest and the manifests of its ingredients as a tree
+fn manifest_tree(store: &ManifestStore, results: &ValidationResults) -> String {
+    let mut out = String::new();
+    match store.active_label() {
+        Some(label) => {
+            let mut visited = Vec::new();
+            manifest_node(store, results, label, &mut visited).render(&mut out, "", true, true);
+        }
+        None => out.push_str("No active manifest\n"),
+    }
+    out
+}
+
+fn manifest_node(
+    store: &ManifestStore,
+    results: &ValidationResults,
+    label: &str,
+    visited: &mut Vec<String>,
+) -> Node {
+    let mut node = Node {
+        lines: vec![format!("manifest: {}", label)],
+        children: Vec::new(),
+    };
+    let manifest = match store.get(label) {
+        Some(manifest) => manifest,
+        None => {
+            node.lines
+                .push("(not found in the manifest store)".to_owned());
+            return node;
+        }
+    };
+    visited.push(label.to_owned());
+
+    if let Some(asset) = manifest.asset() {
+        node.lines.push(format!("title: {}", asset.title()));
+    }
+    node.lines
+        .push(format!("claim generator: {}", manifest.claim_generator()));
+    if let Some(issuer) = manifest.issuer() {
+        node.lines.push(format!("issuer: {}", issuer));
+    }
+    if let Some(time) = manifest.time() {
+        node.lines.push(format!("signed: {}", time));
+    }
+    let labels: Vec<&str> = manifest
+        .assertions()
+        .iter()
+        .map(|assertion| assertion.label.as_str())
+        .collect();
+    node.lines
+        .push(format!("assertions: {}", labels.join(", ")));
+    if let Some(codes) = results.manifest_codes(label) {
+        node.lines.push(format!("validation: {}", summary(codes)));
+    }
+
+    for ingredient in manifest.ingredients() {
+        let mut child = Node {
+            lines: vec![format!(
+                "ingredient: {}{}",
+                ingredient.title(),
+                if ingredient.is_parent() {
+                    " (parent)"
+                } else {
+                    ""
+                }
+            )],
+            children: Vec::new(),
+        };
+        child.lines.push(format!("format: {}", ingredient.format()));
+        if let Some(statuses) = ingredient.validation_status() {
+            for status in statuses {
+                child.lines.push(format!("status: {}", status.code()));
+            }
+        }
+        if let Some(active) = ingredient.active_manifest() {
+            if visited.iter().any(|seen| seen == active) {
+                child.lines.push(format!("manifest: {} (cycle)", active));
+            } else {
+                child
+                    .children
+                    .push(manifest_node(store, results, active, visited));
+            }
+        }
+        node.children.push(child);
+    }
+
+    visited.pop();
+    node
+}
+
+// renders the validation codes of each manifest, active manifest first
+fn results_tree(results: &ValidationResults) -> String {
+    let mut labels: Vec<&String> = results.manifests.keys().collect();
+    labels.sort_by_key(|label| {
+        (
+            Some(label.as_str()) != results.active_manifest.as_deref(),
+            *label,
+        )
+    });
+
+    let mut out = String::new();
+    for label in labels {
+        let codes = &results.manifests[label];
+        let mut node = Node {
+            lines: vec![format!("manifest: {} ({})", label, summary(codes))],
+            children: Vec::new(),
+        };
+        let categories = [
+            ("success", &codes.success),
+            ("informational", &codes.informational),
+            ("failure", &codes.failure),
+        ];
+        for (category, statuses) in categories.iter() {
+            for status in statuses.iter() {
+                let mut line = format!("{}: {}", category, status.code());
+                if let Some(explanation) = status.explanation() {
+                    line.push_str(&format!(" - {}", explanation));
+                }
+                node.children.push(Node {
+                    lines: vec![line],
+                    children: Vec::new(),
+                });
+            }
+        }
+        node.render(&mut out, "", true, true);
+    }
+    out.push_str(if results.is_valid() {
+        "valid\n"
+    } else {
+        "invalid\n"
+    });
+    out
+}
+
+fn summary(codes: &StatusCodes) -> String {
+    if codes.is_valid() {
+        "valid".to_owned()
+    } else {
+        format!("invalid, {} failure(s)", codes.failure.len())
+    }
+}
+
+// manifest labels are urns, which are not valid file names everywhere
+fn file_name(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn extension(format: &str) -> &str {
+    match format.rsplit('/').next().unwrap_or(format) {
+        "jpeg" => "jpg",
+        "svg+xml" => "svg",
+        ext => ext,
+    }
+}
@@ -30,6 +30,8 @@ use std::{
 use structopt::StructOpt;
 use tempfile::tempdir;
 
+mod commands;
+use commands::Command;
 pub mod config;
 use config::Config;
 mod signer;
@@ -64,6 +66,9 @@ struct CliArgs {
     /// The path to the file to read (jpg or json for adding claims)
     #[structopt(parse(from_os_str))]
     path: Option<std::path::PathBuf>,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
 }
 
 // converts any relative paths to absolute from base_path
@@ -256,6 +261,14 @@ fn main() -> Result<()> {
     }
     env_logger::init();
 
+    if let Some(command) = args.command {
+        // subcommands exit with status 1 if the asset is not valid or the manifests differ
+        if !commands::run(command)? {
+            exit(1);
+        }
+        return Ok(());
+    }
+
     let mut config = args.config;
     let mut base_dir = PathBuf::from(".");
 
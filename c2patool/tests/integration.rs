@@ -20,6 +20,7 @@ mod integration {
     use std::process::Command;
 
     const TEST_IMAGE: &str = "earth_apollo17.jpg";
+    const TEST_IMAGE_WITH_INGREDIENT: &str = "CA.jpg";
     //const TEST_IMAGE: &str = "libpng-test.png"; // save for png testing
     //const TEST_IMAGE_WITH_MANIFEST: &str = "C.jpg"; // save for manifest tests
 
@@ -79,6 +80,119 @@ mod integration {
         Ok(())
     }
 
+    #[test]
+    fn tool_inspect_tree() -> Result<(), Box<dyn std::error::Error>> {
+        Command::cargo_bin("c2patool")?
+            .arg("inspect")
+            .arg(fixture_path(TEST_IMAGE_WITH_INGREDIENT))
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("manifest: "))
+            .stdout(predicate::str::contains("ingredient: "));
+        Ok(())
+    }
+
+    #[test]
+    fn tool_inspect_jumbf() -> Result<(), Box<dyn std::error::Error>> {
+        Command::cargo_bin("c2patool")?
+            .arg("inspect")
+            .arg(fixture_path(TEST_IMAGE_WITH_INGREDIENT))
+            .arg("--jumbf")
+            .arg("-f")
+            .arg("json")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("c2pa.claim"));
+        Ok(())
+    }
+
+    #[test]
+    fn tool_sign_and_verify() -> Result<(), Box<dyn std::error::Error>> {
+        generate_x509_temp_keys();
+        let x509_path = x509_path();
+        let manifest = temp_path("sign_manifest.json");
+        std::fs::write(
+            &manifest,
+            r#"{ "claim_generator": "c2patool_test/1.0", "title": "Signed Title" }"#,
+        )?;
+        let output = temp_path("signed.jpg");
+
+        Command::cargo_bin("c2patool")?
+            .arg("sign")
+            .arg(fixture_path(TEST_IMAGE))
+            .arg("-m")
+            .arg(&manifest)
+            .arg("-o")
+            .arg(&output)
+            .arg("--cert")
+            .arg(x509_path.join("temp_key.pub"))
+            .arg("--key")
+            .arg(x509_path.join("temp_key.pem"))
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Signed"));
+
+        Command::cargo_bin("c2patool")?
+            .arg("inspect")
+            .arg(&output)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("c2patool_test/1.0"));
+
+        Command::cargo_bin("c2patool")?
+            .arg("verify")
+            .arg(&output)
+            .arg("-f")
+            .arg("json")
+            .assert()
+            .stdout(predicate::str::contains("claimSignature.validated"));
+        Ok(())
+    }
+
+    #[test]
+    fn tool_verify_no_manifest() -> Result<(), Box<dyn std::error::Error>> {
+        Command::cargo_bin("c2patool")?
+            .arg("verify")
+            .arg(fixture_path(TEST_IMAGE))
+            .assert()
+            .failure();
+        Ok(())
+    }
+
+    #[test]
+    fn tool_diff() -> Result<(), Box<dyn std::error::Error>> {
+        // an asset does not differ from itself
+        Command::cargo_bin("c2patool")?
+            .arg("diff")
+            .arg(fixture_path(TEST_IMAGE_WITH_INGREDIENT))
+            .arg(fixture_path(TEST_IMAGE_WITH_INGREDIENT))
+            .assert()
+            .success();
+
+        Command::cargo_bin("c2patool")?
+            .arg("diff")
+            .arg(fixture_path("C.jpg"))
+            .arg(fixture_path(TEST_IMAGE_WITH_INGREDIENT))
+            .assert()
+            .failure();
+        Ok(())
+    }
+
+    #[test]
+    fn tool_extract() -> Result<(), Box<dyn std::error::Error>> {
+        let output = temp_path("extracted");
+        Command::cargo_bin("c2patool")?
+            .arg("extract")
+            .arg(fixture_path(TEST_IMAGE_WITH_INGREDIENT))
+            .arg("-o")
+            .arg(&output)
+            .assert()
+            .success();
+        assert!(output.join("manifest_store.c2pa").exists());
+        assert!(output.join("manifest_store.json").exists());
+        Ok(())
+    }
+
     /* remove this until the c2patool supports .c2pa write again
     #[test]
     fn tool_manifest_report() -> Result<(), Box<dyn std::error::Error>> {
@@ -102,10 +216,15 @@ mod integration {
     }
     */
 
-    fn generate_x509_temp_keys() {
-        let mut x509_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    fn x509_path() -> PathBuf {
+        let mut x509_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         x509_path.pop();
         x509_path.push(".x509");
+        x509_path
+    }
+
+    fn generate_x509_temp_keys() {
+        let x509_path = x509_path();
 
         std::fs::create_dir_all(&x509_path).expect("Can't create .x509 dir in repo");
 
@@ -117,7 +236,7 @@ mod integration {
         if !(priv_key_path.exists() && sign_cert_path.exists()) {
             // Creating the signer (which we don't use) has the side effect of
             // creating temporary private key and signing certificate.
-            c2pa::openssl::temp_signer::get_signer(&x509_path);
+            c2pa::get_temp_signer(&x509_path);
         }
     }
 }
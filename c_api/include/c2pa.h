@@ -0,0 +1,117 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+
+#ifndef C2PA_H
+#define C2PA_H
+
+// This file is generated by cbindgen from the c2pa-c crate. Do not edit it by hand.
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// A signer that calls back into C to sign, so keys can stay in a platform key store.
+typedef struct C2paSigner C2paSigner;
+
+// Signs `len` bytes of `data`, writing the signature to `signature`, which holds
+// `signature_max_len` bytes. Returns the length of the signature or -1 on error.
+//
+// ECDSA signatures must be in the P1363 (`r || s`) format COSE uses.
+typedef intptr_t (*C2paSignCallback)(void *context,
+                                     const uint8_t *data,
+                                     uintptr_t len,
+                                     uint8_t *signature,
+                                     uintptr_t signature_max_len);
+
+// Reads up to `len` bytes into `buffer`, returning the number of bytes read,
+// 0 at the end of the stream, or -1 on error.
+typedef intptr_t (*C2paReadCallback)(void *context, uint8_t *buffer, uintptr_t len);
+
+// Writes `len` bytes from `data`, returning the number of bytes written or -1 on error.
+typedef intptr_t (*C2paWriteCallback)(void *context, const uint8_t *data, uintptr_t len);
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the version of the C2PA SDK.
+char *c2pa_version(void);
+
+// Returns the error of the last call that failed on this thread, or NULL if there
+// was none. The error is cleared once returned.
+char *c2pa_error(void);
+
+// Frees a string returned by the API.
+//
+// # Safety
+// `s` must be NULL or a string returned by the API that has not been freed.
+void c2pa_string_free(char *s);
+
+// Reads and validates the manifest store of the asset at `path`, returning it as JSON.
+//
+// # Safety
+// `path` must be a valid C string.
+char *c2pa_read_file(const char *path);
+
+// Reads and validates the manifest store of an asset read from a stream, returning
+// it as JSON. `format` is the MIME type or extension of the asset.
+//
+// # Safety
+// `format` must be a valid C string and `read` must be safe to call with `context`.
+char *c2pa_read_stream(const char *format, void *context, C2paReadCallback read);
+
+// Verifies the manifest store of the asset at `path`, returning 0 if it is valid.
+// Verification stops at the first failure, which is returned by `c2pa_error`.
+//
+// # Safety
+// `path` must be a valid C string.
+int c2pa_verify_file(const char *path);
+
+// Creates a signer that signs by calling `sign` with `context`.
+//
+// `alg` is the signing algorithm, such as "es256" or "ps256", and `certs_pem` the PEM
+// encoded certificate chain, starting with the signing certificate. `reserve_size` is
+// the space reserved for the signature, which must hold the signature, the certificates
+// and any time-stamp. `tsa_url` is the time authority to time-stamp with, or NULL.
+//
+// # Safety
+// The strings must be valid C strings or NULL where allowed, and `sign` must be safe
+// to call with `context` until the signer is freed.
+C2paSigner *c2pa_signer_create(void *context,
+                               C2paSignCallback sign,
+                               const char *alg,
+                               const char *certs_pem,
+                               uintptr_t reserve_size,
+                               const char *tsa_url);
+
+// Frees a signer.
+//
+// # Safety
+// `signer` must be NULL or a signer returned by `c2pa_signer_create` that has not been freed.
+void c2pa_signer_free(C2paSigner *signer);
+
+// Signs an asset read from a source stream with the manifest described by
+// `manifest_json`, writing the signed asset to a destination stream. `format` is
+// the MIME type or extension of the asset. Returns 0 on success.
+//
+// # Safety
+// The strings must be valid C strings, `signer` a signer from `c2pa_signer_create`,
+// and the callbacks must be safe to call with their contexts.
+int c2pa_sign_stream(const C2paSigner *signer,
+                     const char *manifest_json,
+                     const char *format,
+                     void *source_context,
+                     C2paReadCallback read,
+                     void *dest_context,
+                     C2paWriteCallback write);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif // C2PA_H
@@ -0,0 +1,74 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{convert::TryFrom, ffi::c_void};
+
+use c2pa::{CallbackSigner, Error, Result};
+
+/// Signs `len` bytes of `data`, writing the signature to `signature`, which holds
+/// `signature_max_len` bytes. Returns the length of the signature or -1 on error.
+///
+/// ECDSA signatures must be in the P1363 (`r || s`) format COSE uses.
+pub type C2paSignCallback = unsafe extern "C" fn(
+    context: *mut c_void,
+    data: *const u8,
+    len: usize,
+    signature: *mut u8,
+    signature_max_len: usize,
+) -> isize;
+
+/// A signer that calls back into C to sign, so keys can stay in a platform key store.
+pub struct C2paSigner {
+    // captures the C context, so unlike the signers of the other bindings it is not Send
+    pub(crate) signer: CallbackSigner<Box<dyn Fn(&[u8]) -> Result<Vec<u8>>>>,
+}
+
+impl C2paSigner {
+    pub(crate) fn new(
+        context: *mut c_void,
+        sign: C2paSignCallback,
+        alg: &str,
+        certs_pem: &str,
+        reserve_size: usize,
+        tsa_url: Option<String>,
+    ) -> Result<Self> {
+        let sign = move |data: &[u8]| {
+            let mut signature = vec![0u8; reserve_size];
+            let len = unsafe {
+                sign(
+                    context,
+                    data.as_ptr(),
+                    data.len(),
+                    signature.as_mut_ptr(),
+                    signature.len(),
+                )
+            };
+            match usize::try_from(len) {
+                Ok(len) if len <= signature.len() => {
+                    signature.truncate(len);
+                    Ok(signature)
+                }
+                _ => Err(Error::OtherError("sign callback failed".into())),
+            }
+        };
+
+        let signer = CallbackSigner::new(
+            Box::new(sign) as Box<dyn Fn(&[u8]) -> Result<Vec<u8>>>,
+            alg,
+            certs_pem.as_bytes(),
+            reserve_size,
+            tsa_url,
+        )?;
+        Ok(C2paSigner { signer })
+    }
+}
@@ -0,0 +1,66 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{
+    convert::TryFrom,
+    ffi::c_void,
+    io::{self, Read, Write},
+};
+
+/// Reads up to `len` bytes into `buffer`, returning the number of bytes read,
+/// 0 at the end of the stream, or -1 on error.
+pub type C2paReadCallback =
+    unsafe extern "C" fn(context: *mut c_void, buffer: *mut u8, len: usize) -> isize;
+
+/// Writes `len` bytes from `data`, returning the number of bytes written or -1 on error.
+pub type C2paWriteCallback =
+    unsafe extern "C" fn(context: *mut c_void, data: *const u8, len: usize) -> isize;
+
+/// A stream read through a callback, with a context owned by the caller
+pub(crate) struct CReader {
+    pub context: *mut c_void,
+    pub read: C2paReadCallback,
+}
+
+impl Read for CReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = unsafe { (self.read)(self.context, buf.as_mut_ptr(), buf.len()) };
+        match usize::try_from(len) {
+            Ok(len) if len <= buf.len() => Ok(len),
+            _ => Err(io::Error::new(io::ErrorKind::Other, "read callback failed")),
+        }
+    }
+}
+
+/// A stream written through a callback, with a context owned by the caller
+pub(crate) struct CWriter {
+    pub context: *mut c_void,
+    pub write: C2paWriteCallback,
+}
+
+impl Write for CWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = unsafe { (self.write)(self.context, buf.as_ptr(), buf.len()) };
+        match usize::try_from(len) {
+            Ok(len) if len <= buf.len() => Ok(len),
+            _ => Err(io::Error::new(
+                io::ErrorKind::Other,
+                "write callback failed",
+            )),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
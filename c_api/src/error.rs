@@ -0,0 +1,50 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::cell::RefCell;
+
+thread_local! {
+    // the error of the last call that failed on this thread
+    static LAST_ERROR: RefCell<Option<String>> = RefCell::new(None);
+}
+
+/// Errors are returned to C as messages, so both SDK errors and errors in the
+/// arguments passed from C are boxed.
+pub(crate) type FfiResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+pub(crate) fn set_error(message: String) {
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(message));
+}
+
+pub(crate) fn take_error() -> Option<String> {
+    LAST_ERROR.with(|e| e.borrow_mut().take())
+}
+
+/// Runs `f`, returning `on_error` if it fails or panics. The error is kept for
+/// `c2pa_error`, since panics must not unwind into C.
+pub(crate) fn guard<T, F>(on_error: T, f: F) -> T
+where
+    F: FnOnce() -> FfiResult<T>,
+{
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => value,
+        Ok(Err(err)) => {
+            set_error(err.to_string());
+            on_error
+        }
+        Err(_) => {
+            set_error("internal error".to_string());
+            on_error
+        }
+    }
+}
@@ -0,0 +1,362 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![deny(warnings)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+//! C API for the C2PA SDK.
+//!
+//! The functions here are declared in `include/c2pa.h`, which is generated from this
+//! crate when it is built. Strings are UTF-8 and NUL terminated. Strings returned by
+//! the API are owned by the caller and freed with `c2pa_string_free`; signers are
+//! freed with `c2pa_signer_free`.
+//!
+//! Functions returning a pointer return NULL on failure, and functions returning an
+//! int return -1. The reason for the last failure on the calling thread is returned
+//! by `c2pa_error`.
+
+use std::{
+    ffi::{c_void, CStr, CString},
+    os::raw::{c_char, c_int},
+};
+
+use c2pa::{Manifest, ManifestStore, VerifyOptions};
+
+mod c_signer;
+pub use c_signer::{C2paSignCallback, C2paSigner};
+
+mod c_stream;
+pub use c_stream::{C2paReadCallback, C2paWriteCallback};
+use c_stream::{CReader, CWriter};
+
+mod error;
+use error::{guard, take_error, FfiResult};
+
+// borrow a C string argument
+unsafe fn arg_str<'a>(s: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if s.is_null() {
+        return Err(format!("{} is NULL", name).into());
+    }
+    Ok(CStr::from_ptr(s).to_str()?)
+}
+
+// borrow an optional C string argument
+unsafe fn opt_arg_str<'a>(s: *const c_char, name: &str) -> FfiResult<Option<&'a str>> {
+    if s.is_null() {
+        Ok(None)
+    } else {
+        arg_str(s, name).map(Some)
+    }
+}
+
+// pass a string to C, which frees it with c2pa_string_free
+fn to_c_string(s: String) -> FfiResult<*mut c_char> {
+    Ok(CString::new(s)?.into_raw())
+}
+
+fn manifest_store_json(manifest_store: &ManifestStore) -> FfiResult<*mut c_char> {
+    to_c_string(serde_json::to_string_pretty(manifest_store)?)
+}
+
+/// Returns the version of the C2PA SDK.
+#[no_mangle]
+pub extern "C" fn c2pa_version() -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        to_c_string(format!("{}/{}", c2pa::NAME, c2pa::VERSION))
+    })
+}
+
+/// Returns the error of the last call that failed on this thread, or NULL if there
+/// was none. The error is cleared once returned.
+#[no_mangle]
+pub extern "C" fn c2pa_error() -> *mut c_char {
+    match take_error() {
+        Some(err) => guard(std::ptr::null_mut(), || to_c_string(err)),
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// Frees a string returned by the API.
+///
+/// # Safety
+/// `s` must be NULL or a string returned by the API that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Reads and validates the manifest store of the asset at `path`, returning it as JSON.
+///
+/// # Safety
+/// `path` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_read_file(path: *const c_char) -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        let manifest_store = ManifestStore::from_file(arg_str(path, "path")?)?;
+        manifest_store_json(&manifest_store)
+    })
+}
+
+/// Reads and validates the manifest store of an asset read from a stream, returning
+/// it as JSON. `format` is the MIME type or extension of the asset.
+///
+/// # Safety
+/// `format` must be a valid C string and `read` must be safe to call with `context`.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_read_stream(
+    format: *const c_char,
+    context: *mut c_void,
+    read: Option<C2paReadCallback>,
+) -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        let format = arg_str(format, "format")?;
+        let read = read.ok_or("read is NULL")?;
+
+        let mut bytes = Vec::new();
+        std::io::copy(&mut CReader { context, read }, &mut bytes)?;
+
+        let manifest_store =
+            ManifestStore::from_bytes_with_options(format, &bytes, &VerifyOptions::new())?;
+        manifest_store_json(&manifest_store)
+    })
+}
+
+/// Verifies the manifest store of the asset at `path`, returning 0 if it is valid.
+/// Verification stops at the first failure, which is returned by `c2pa_error`.
+///
+/// # Safety
+/// `path` must be a valid C string.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_verify_file(path: *const c_char) -> c_int {
+    guard(-1, || {
+        ManifestStore::verify_file_fail_fast(arg_str(path, "path")?)?;
+        Ok(0)
+    })
+}
+
+/// Creates a signer that signs by calling `sign` with `context`.
+///
+/// `alg` is the signing algorithm, such as "es256" or "ps256", and `certs_pem` the PEM
+/// encoded certificate chain, starting with the signing certificate. `reserve_size` is
+/// the space reserved for the signature, which must hold the signature, the certificates
+/// and any time-stamp. `tsa_url` is the time authority to time-stamp with, or NULL.
+///
+/// # Safety
+/// The strings must be valid C strings or NULL where allowed, and `sign` must be safe
+/// to call with `context` until the signer is freed.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_create(
+    context: *mut c_void,
+    sign: Option<C2paSignCallback>,
+    alg: *const c_char,
+    certs_pem: *const c_char,
+    reserve_size: usize,
+    tsa_url: *const c_char,
+) -> *mut C2paSigner {
+    guard(std::ptr::null_mut(), || {
+        let sign = sign.ok_or("sign is NULL")?;
+        let signer = C2paSigner::new(
+            context,
+            sign,
+            arg_str(alg, "alg")?,
+            arg_str(certs_pem, "certs_pem")?,
+            reserve_size,
+            opt_arg_str(tsa_url, "tsa_url")?.map(|s| s.to_owned()),
+        )
+        .map_err(|_err| "certs_pem contains no certificates")?;
+        Ok(Box::into_raw(Box::new(signer)))
+    })
+}
+
+/// Frees a signer.
+///
+/// # Safety
+/// `signer` must be NULL or a signer returned by `c2pa_signer_create` that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_signer_free(signer: *mut C2paSigner) {
+    if !signer.is_null() {
+        drop(Box::from_raw(signer));
+    }
+}
+
+/// Signs an asset read from a source stream with the manifest described by
+/// `manifest_json`, writing the signed asset to a destination stream. `format` is
+/// the MIME type or extension of the asset. Returns 0 on success.
+///
+/// # Safety
+/// The strings must be valid C strings, `signer` a signer from `c2pa_signer_create`,
+/// and the callbacks must be safe to call with their contexts.
+#[no_mangle]
+pub unsafe extern "C" fn c2pa_sign_stream(
+    signer: *const C2paSigner,
+    manifest_json: *const c_char,
+    format: *const c_char,
+    source_context: *mut c_void,
+    read: Option<C2paReadCallback>,
+    dest_context: *mut c_void,
+    write: Option<C2paWriteCallback>,
+) -> c_int {
+    guard(-1, || {
+        let signer = signer.as_ref().ok_or("signer is NULL")?;
+        let mut manifest = Manifest::from_json(arg_str(manifest_json, "manifest_json")?)?;
+        let format = arg_str(format, "format")?;
+        let mut source = CReader {
+            context: source_context,
+            read: read.ok_or("read is NULL")?,
+        };
+        let mut dest = CWriter {
+            context: dest_context,
+            write: write.ok_or("write is NULL")?,
+        };
+
+        manifest.embed_to_streams(format, &mut source, &signer.signer, &mut dest, None, None)?;
+        Ok(0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use std::io::{Cursor, Read, Write};
+
+    use c2pa::{get_temp_signer, Signer};
+
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/tests/fixtures/C.jpg");
+
+    unsafe extern "C" fn read_cursor(context: *mut c_void, buffer: *mut u8, len: usize) -> isize {
+        let cursor = &mut *(context as *mut Cursor<Vec<u8>>);
+        let buffer = std::slice::from_raw_parts_mut(buffer, len);
+        cursor.read(buffer).map(|n| n as isize).unwrap_or(-1)
+    }
+
+    unsafe extern "C" fn write_vec(context: *mut c_void, data: *const u8, len: usize) -> isize {
+        let vec = &mut *(context as *mut Vec<u8>);
+        vec.write(std::slice::from_raw_parts(data, len))
+            .map(|n| n as isize)
+            .unwrap_or(-1)
+    }
+
+    unsafe extern "C" fn sign_with_signer(
+        context: *mut c_void,
+        data: *const u8,
+        len: usize,
+        signature: *mut u8,
+        signature_max_len: usize,
+    ) -> isize {
+        let signer = &*(context as *const Box<dyn Signer>);
+        match signer.sign(std::slice::from_raw_parts(data, len)) {
+            Ok(sig) if sig.len() <= signature_max_len => {
+                std::ptr::copy_nonoverlapping(sig.as_ptr(), signature, sig.len());
+                sig.len() as isize
+            }
+            _ => -1,
+        }
+    }
+
+    unsafe fn take_string(s: *mut c_char) -> String {
+        assert!(!s.is_null());
+        let string = CStr::from_ptr(s).to_str().unwrap().to_owned();
+        c2pa_string_free(s);
+        string
+    }
+
+    #[test]
+    fn test_read_and_sign_streams() {
+        unsafe {
+            let version = take_string(c2pa_version());
+            assert!(version.ends_with(c2pa::VERSION));
+
+            let mut source = Cursor::new(std::fs::read(FIXTURE).unwrap());
+            let format = CString::new("image/jpeg").unwrap();
+            let json = take_string(c2pa_read_stream(
+                format.as_ptr(),
+                &mut source as *mut _ as *mut c_void,
+                Some(read_cursor),
+            ));
+            assert!(json.contains("active_manifest"));
+
+            // sign with a callback
+            let temp_dir = tempfile::tempdir().unwrap();
+            let (temp_signer, cert_path) = get_temp_signer(&temp_dir.path());
+            let boxed: Box<dyn Signer> = Box::new(temp_signer);
+            let certs_pem = CString::new(std::fs::read(&cert_path).unwrap()).unwrap();
+            let alg = CString::new("ps256").unwrap();
+            let signer = c2pa_signer_create(
+                &boxed as *const _ as *mut c_void,
+                Some(sign_with_signer),
+                alg.as_ptr(),
+                certs_pem.as_ptr(),
+                boxed.reserve_size(),
+                std::ptr::null(),
+            );
+            assert!(!signer.is_null());
+
+            let manifest_json = CString::new(r#"{"claim_generator": "c_api_test/0.1"}"#).unwrap();
+            let mut source = Cursor::new(std::fs::read(FIXTURE).unwrap());
+            let mut dest: Vec<u8> = Vec::new();
+            let result = c2pa_sign_stream(
+                signer,
+                manifest_json.as_ptr(),
+                format.as_ptr(),
+                &mut source as *mut _ as *mut c_void,
+                Some(read_cursor),
+                &mut dest as *mut _ as *mut c_void,
+                Some(write_vec),
+            );
+            assert_eq!(result, 0, "{:?}", take_error());
+            c2pa_signer_free(signer);
+
+            let signed_path = temp_dir.path().join("signed.jpg");
+            std::fs::write(&signed_path, &dest).unwrap();
+            let path = CString::new(signed_path.to_str().unwrap()).unwrap();
+            assert_eq!(c2pa_verify_file(path.as_ptr()), 0);
+            let json = take_string(c2pa_read_file(path.as_ptr()));
+            assert!(json.contains("c_api_test/0.1"));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        unsafe {
+            assert!(c2pa_error().is_null());
+
+            let path = CString::new("does/not/exist.jpg").unwrap();
+            assert!(c2pa_read_file(path.as_ptr()).is_null());
+            assert!(!take_string(c2pa_error()).is_empty());
+            assert!(c2pa_error().is_null());
+
+            assert!(c2pa_read_file(std::ptr::null()).is_null());
+            assert_eq!(take_string(c2pa_error()), "path is NULL");
+
+            let alg = CString::new("es256").unwrap();
+            let certs = CString::new("not a certificate").unwrap();
+            let signer = c2pa_signer_create(
+                std::ptr::null_mut(),
+                None,
+                alg.as_ptr(),
+                certs.as_ptr(),
+                0,
+                std::ptr::null(),
+            );
+            assert!(signer.is_null());
+            assert_eq!(take_string(c2pa_error()), "sign is NULL");
+        }
+    }
+}
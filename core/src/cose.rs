@@ -0,0 +1,529 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Creating and verifying the COSE_Sign1 signatures of claims.
+//!
+//! The claim is the detached payload of the COSE_Sign1, and the signing certificate
+//! chain is stored in its `x5chain` header as C2PA requires. C2PA 1.x places the chain
+//! in the unprotected header, 2.x in the protected header along with an `x5t`
+//! thumbprint of the signing certificate; both are accepted when verifying.
+
+use alloc::{
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use ciborium::value::Value;
+use coset::{
+    iana, sig_structure_data, CoseSign1, CoseSign1Builder, Header, HeaderBuilder, Label,
+    RegisteredLabelWithPrivate, SignatureContext, TaggedCborSerializable,
+};
+
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::{der, Error, RawSigner, Result, SignatureVerifier};
+
+const X5CHAIN: &str = "x5chain";
+const X5CHAIN_LABEL: i64 = iana::HeaderParameter::X5Chain as i64;
+const X5T_LABEL: i64 = iana::HeaderParameter::X5T as i64;
+const PAD: &str = "pad";
+const PAD2: &str = "pad2";
+const PAD_OFFSET: usize = 7;
+
+/// Where the `x5chain` header holding the signing certificate chain is placed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum X5ChainPlacement {
+    /// In the unprotected header, as in C2PA 1.x
+    Unprotected,
+    /// In the protected header with an `x5t` thumbprint of the signing certificate,
+    /// as in C2PA 2.x
+    Protected,
+}
+
+impl Default for X5ChainPlacement {
+    fn default() -> Self {
+        X5ChainPlacement::Unprotected
+    }
+}
+
+/// Information about a verified signature
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureInfo {
+    /// The C2PA name of the signing algorithm
+    pub alg: String,
+    /// The DER encoded certificate chain, starting with the signing certificate
+    pub certs: Vec<Vec<u8>>,
+}
+
+/// Returns the COSE_Sign1 bytes signing `data` with `signer`, padded to the signer's
+/// reserve size.
+pub fn sign(data: &[u8], signer: &dyn RawSigner) -> Result<Vec<u8>> {
+    let (mut sign1, tbs) = sign1_to_sign_with_placement(
+        signer.alg(),
+        signer.certs()?,
+        data,
+        signer.x5chain_placement(),
+    )?;
+    sign1.signature = signer.sign(&tbs)?;
+    pad_sign1(&mut sign1, signer.reserve_size())
+}
+
+/// Verifies COSE_Sign1 bytes signed over `data`, checking the signature with `verifier`
+/// against the public key of the signing certificate.
+///
+/// This only checks the signature. Certificate validity and trust are checked by the caller.
+pub fn verify(
+    cose_bytes: &[u8],
+    data: &[u8],
+    verifier: &dyn SignatureVerifier,
+) -> Result<SignatureInfo> {
+    let sign1 = parse_sign1(cose_bytes)?;
+    let alg = signing_alg(&sign1)?;
+    let certs = x5chain(&sign1)?;
+    let public_key = der::subject_public_key_info(&certs[0])?;
+
+    let tbs = sig_structure_data(
+        SignatureContext::CoseSign1,
+        sign1.protected.clone(),
+        None,
+        b"", // no additional data required here
+        data,
+    );
+    if verifier.verify(&alg, &sign1.signature, &tbs, public_key)? {
+        Ok(SignatureInfo { alg, certs })
+    } else {
+        Err(Error::CoseSignatureMismatch)
+    }
+}
+
+/// Decodes tagged COSE_Sign1 bytes.
+pub fn parse_sign1(cose_bytes: &[u8]) -> Result<CoseSign1> {
+    <CoseSign1 as TaggedCborSerializable>::from_tagged_slice(cose_bytes)
+        .map_err(|_err| Error::CoseSignature)
+}
+
+/// Returns the C2PA name of the signing algorithm of a COSE_Sign1, such as "es256".
+pub fn signing_alg(sign1: &CoseSign1) -> Result<String> {
+    let alg_str = match sign1.protected.header.alg {
+        Some(RegisteredLabelWithPrivate::PrivateUse(a)) => match a {
+            -39 => "ps512",
+            -38 => "ps384",
+            -37 => "ps256",
+            -36 => "es512",
+            -35 => "es384",
+            -7 => "es256",
+            -47 => "es256k",
+            // todo: deprecated  figure out lecacy support for RS signatures
+            -259 => "rs512",
+            -258 => "rs384",
+            -257 => "rs256",
+
+            -8 => "ed25519",
+            _ => "unknown",
+        },
+        Some(RegisteredLabelWithPrivate::Assigned(a)) => match a {
+            iana::Algorithm::PS512 => "ps512",
+            iana::Algorithm::PS384 => "ps384",
+            iana::Algorithm::PS256 => "ps256",
+            iana::Algorithm::ES512 => "es512",
+            iana::Algorithm::ES384 => "es384",
+            iana::Algorithm::ES256 => "es256",
+            iana::Algorithm::ES256K => "es256k",
+            // todo: deprecated  figure out lecacy support for RS signatures
+            iana::Algorithm::RS512 => "rs512",
+            iana::Algorithm::RS384 => "rs384",
+            iana::Algorithm::RS256 => "rs256",
+            iana::Algorithm::EdDSA => "ed25519",
+            _ => "unknown",
+        },
+        Some(RegisteredLabelWithPrivate::Text(ref a)) => a.as_str(),
+        None => return Err(Error::CoseSignatureAlgorithmNotSupported),
+    };
+    Ok(alg_str.to_string())
+}
+
+/// Returns the DER encoded certificates of the `x5chain` header of a COSE_Sign1,
+/// starting with the signing certificate.
+///
+/// The protected header is searched first. A chain found there must match the `x5t`
+/// thumbprint when one is present.
+pub fn x5chain(sign1: &CoseSign1) -> Result<Vec<Vec<u8>>> {
+    let protected = &sign1.protected.header;
+    if let Some(value) = find_x5chain(protected) {
+        let certs = x5chain_certs(value)?;
+        if let Some((_, x5t)) = protected
+            .rest
+            .iter()
+            .find(|(label, _)| *label == Label::Int(X5T_LABEL))
+        {
+            verify_x5t(x5t, &certs[0])?;
+        }
+        return Ok(certs);
+    }
+
+    let value = find_x5chain(&sign1.unprotected).ok_or(Error::CoseX5ChainMissing)?;
+    x5chain_certs(value)
+}
+
+/// Returns where the `x5chain` header of a COSE_Sign1 is placed, if it has one.
+pub fn x5chain_placement(sign1: &CoseSign1) -> Option<X5ChainPlacement> {
+    if find_x5chain(&sign1.protected.header).is_some() {
+        Some(X5ChainPlacement::Protected)
+    } else if find_x5chain(&sign1.unprotected).is_some() {
+        Some(X5ChainPlacement::Unprotected)
+    } else {
+        None
+    }
+}
+
+// the chain may use the registered label or the text label of earlier C2PA versions
+fn find_x5chain(header: &Header) -> Option<&Value> {
+    header
+        .rest
+        .iter()
+        .find(|(label, _)| {
+            *label == Label::Int(X5CHAIN_LABEL) || *label == Label::Text(X5CHAIN.to_string())
+        })
+        .map(|(_, value)| value)
+}
+
+fn x5chain_certs(value: &Value) -> Result<Vec<Vec<u8>>> {
+    let certs: Vec<Vec<u8>> = match value {
+        // handle array of certs
+        Value::Array(cert_chain) => cert_chain
+            .iter()
+            .filter_map(|c| match c {
+                Value::Bytes(der_bytes) => Some(der_bytes.clone()),
+                _ => None,
+            })
+            .collect(),
+        // handle single cert case
+        Value::Bytes(der_bytes) => vec![der_bytes.clone()],
+        _ => return Err(Error::CoseX5ChainMissing),
+    };
+
+    if certs.is_empty() {
+        return Err(Error::CoseX5ChainMissing);
+    }
+    Ok(certs)
+}
+
+/// Returns the `x5t` header value for a certificate, its SHA-256 thumbprint.
+pub fn x5t_value(cert: &[u8]) -> Value {
+    Value::Array(vec![
+        Value::Integer((iana::Algorithm::SHA_256 as i64).into()),
+        Value::Bytes(Sha256::digest(cert).to_vec()),
+    ])
+}
+
+// COSE_CertHash is [hashAlg, hashValue]
+fn verify_x5t(x5t: &Value, cert: &[u8]) -> Result<()> {
+    let (alg, hash) = match x5t {
+        Value::Array(items) => match items.as_slice() {
+            [Value::Integer(alg), Value::Bytes(hash)] => (i128::from(*alg), hash),
+            _ => return Err(Error::CoseX5tMismatch),
+        },
+        _ => return Err(Error::CoseX5tMismatch),
+    };
+
+    let cert_hash = match alg {
+        a if a == iana::Algorithm::SHA_256 as i128 => Sha256::digest(cert).to_vec(),
+        a if a == iana::Algorithm::SHA_384 as i128 => Sha384::digest(cert).to_vec(),
+        a if a == iana::Algorithm::SHA_512 as i128 => Sha512::digest(cert).to_vec(),
+        _ => return Err(Error::UnsupportedType),
+    };
+
+    if cert_hash == *hash {
+        Ok(())
+    } else {
+        Err(Error::CoseX5tMismatch)
+    }
+}
+
+/// Returns the protected and unprotected headers for signing with `alg`, with the
+/// `x5chain` of `certs` placed as set by `placement`.
+pub fn signing_headers(
+    alg: &str,
+    certs: Vec<Vec<u8>>,
+    placement: X5ChainPlacement,
+) -> Result<(Header, Header)> {
+    let mut protected = alg_header(alg)?;
+    let mut unprotected = Header::default();
+
+    match placement {
+        X5ChainPlacement::Unprotected => unprotected
+            .rest
+            .push((Label::Text(X5CHAIN.to_string()), x5chain_value(certs))),
+        X5ChainPlacement::Protected => {
+            let x5t = x5t_value(certs.first().ok_or(Error::CoseX5ChainMissing)?);
+            protected
+                .rest
+                .push((Label::Int(X5CHAIN_LABEL), x5chain_value(certs)));
+            protected.rest.push((Label::Int(X5T_LABEL), x5t));
+        }
+    }
+    Ok((protected, unprotected))
+}
+
+/// Returns the protected header for a signing algorithm.
+pub fn alg_header(alg: &str) -> Result<Header> {
+    let alg_id = match alg {
+        "ps256" => iana::Algorithm::PS256,
+        "ps384" => iana::Algorithm::PS384,
+        "ps512" => iana::Algorithm::PS512,
+        // RS algorithms are no longer supported by C2PA
+        "es256" => iana::Algorithm::ES256,
+        "es384" => iana::Algorithm::ES384,
+        "es512" => iana::Algorithm::ES512,
+        #[cfg(feature = "es256k")]
+        "es256k" => iana::Algorithm::ES256K,
+        "ed25519" => iana::Algorithm::EdDSA,
+        _ => return Err(Error::UnsupportedType),
+    };
+    Ok(HeaderBuilder::new().algorithm(alg_id).build())
+}
+
+/// Returns the `x5chain` header value for certificates, bytes for a single certificate
+/// or an array for a chain.
+pub fn x5chain_value(certs: Vec<Vec<u8>>) -> Value {
+    match certs.len() {
+        1 => Value::Bytes(certs.into_iter().next().unwrap_or_default()), // single cert
+        _ => Value::Array(certs.into_iter().map(Value::Bytes).collect()), // provide vec of certs when required
+    }
+}
+
+/// Returns an unsigned COSE_Sign1 for `data` along with the bytes to sign.
+///
+/// Once its signature is set, more unprotected headers may be added before passing
+/// it to [`pad_sign1`].
+pub fn sign1_to_sign(alg: &str, certs: Vec<Vec<u8>>, data: &[u8]) -> Result<(CoseSign1, Vec<u8>)> {
+    sign1_to_sign_with_placement(alg, certs, data, X5ChainPlacement::Unprotected)
+}
+
+/// Same as [`sign1_to_sign`] with the `x5chain` placed as set by `placement`.
+pub fn sign1_to_sign_with_placement(
+    alg: &str,
+    certs: Vec<Vec<u8>>,
+    data: &[u8],
+    placement: X5ChainPlacement,
+) -> Result<(CoseSign1, Vec<u8>)> {
+    let (protected, unprotected) = signing_headers(alg, certs, placement)?;
+
+    let sign1 = CoseSign1Builder::new()
+        .protected(protected)
+        .unprotected(unprotected)
+        .build();
+    let tbs = sig_structure_data(
+        SignatureContext::CoseSign1,
+        sign1.protected.clone(),
+        None,
+        b"", // no additional data required here
+        data,
+    );
+    Ok((sign1, tbs))
+}
+
+/// Returns the bytes of a COSE_Sign1 padded with 0s to `end_size`, the reserved box size.
+///
+/// There are some values lengths that are impossible to hit with a single padding so
+/// when that happens a second padding is added to change the remaining needed padding.
+/// The default initial guess works for almost all sizes, without the need for additional loops.
+pub fn pad_sign1(sign1: &mut CoseSign1, end_size: usize) -> Result<Vec<u8>> {
+    let mut sign1_clone = sign1.clone();
+    let cur_vec = sign1_clone
+        .to_tagged_vec()
+        .map_err(|_e| Error::CoseSignature)?;
+    let cur_size = cur_vec.len();
+
+    // check for box too small
+    match cur_size > end_size {
+        true => {
+            return Err(Error::CoseSigboxTooSmall);
+        }
+        false if cur_size == end_size => return Ok(cur_vec),
+        false => (),
+    }
+
+    let mut padding_found = false;
+    let mut last_pad = 0;
+    // start close to desired end_size accounting for label
+    let mut target_guess = (end_size - cur_size).saturating_sub(PAD_OFFSET);
+    loop {
+        // clone to use
+        sign1_clone = sign1.clone();
+
+        // replace padding with new estimate
+        for header_pair in &mut sign1_clone.unprotected.rest {
+            if header_pair.0 == Label::Text(PAD.to_string()) {
+                if let Value::Bytes(b) = &header_pair.1 {
+                    last_pad = b.len();
+                }
+                header_pair.1 = Value::Bytes(vec![0u8; target_guess]);
+                padding_found = true;
+                break;
+            }
+        }
+
+        // if there was no padding add it and call again
+        if !padding_found {
+            sign1_clone.unprotected.rest.push((
+                Label::Text(PAD.to_string()),
+                Value::Bytes(vec![0u8; target_guess]),
+            ));
+            return pad_sign1(&mut sign1_clone, end_size);
+        }
+
+        // get current cbor vec to size if we reached target size
+        let new_cbor = sign1_clone
+            .to_tagged_vec()
+            .map_err(|_e| Error::CoseSignature)?;
+
+        match new_cbor.len() < end_size {
+            true => target_guess += 1,
+            false if new_cbor.len() == end_size => return Ok(new_cbor),
+            false => break, // we couuld not match end_size in a single pad so break and add a second
+        }
+    }
+
+    // if we reach here we need a new second padding object to hit exact size
+    sign1.unprotected.rest.push((
+        Label::Text(PAD2.to_string()),
+        Value::Bytes(vec![0u8; last_pad.saturating_sub(10)]),
+    ));
+    pad_sign1(sign1, end_size)
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::der::tests::pem_cert;
+
+    // signs with a hash of the data, so tests do not need a crypto library
+    struct HashSigner {
+        certs: Vec<Vec<u8>>,
+        placement: X5ChainPlacement,
+    }
+
+    impl RawSigner for HashSigner {
+        fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(Sha256::digest(data).to_vec())
+        }
+
+        fn alg(&self) -> &str {
+            "es256"
+        }
+
+        fn certs(&self) -> Result<Vec<Vec<u8>>> {
+            Ok(self.certs.clone())
+        }
+
+        fn reserve_size(&self) -> usize {
+            2048 + self.certs.iter().map(|c| c.len()).sum::<usize>()
+        }
+
+        fn x5chain_placement(&self) -> X5ChainPlacement {
+            self.placement
+        }
+    }
+
+    struct HashVerifier {
+        public_key: Vec<u8>,
+    }
+
+    impl SignatureVerifier for HashVerifier {
+        fn verify(&self, alg: &str, sig: &[u8], data: &[u8], public_key: &[u8]) -> Result<bool> {
+            assert_eq!(alg, "es256");
+            assert_eq!(public_key, self.public_key.as_slice());
+            Ok(sig == &Sha256::digest(data)[..])
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let cert = pem_cert(include_bytes!("../../sdk/tests/fixtures/temp_cert.data"));
+        let signer = HashSigner {
+            certs: vec![cert.clone()],
+            placement: X5ChainPlacement::Unprotected,
+        };
+        let verifier = HashVerifier {
+            public_key: der::subject_public_key_info(&cert).unwrap().to_vec(),
+        };
+
+        let claim = b"claim bytes";
+        let cose_bytes = sign(claim, &signer).unwrap();
+        assert_eq!(cose_bytes.len(), signer.reserve_size());
+
+        let info = verify(&cose_bytes, claim, &verifier).unwrap();
+        assert_eq!(info.alg, "es256");
+        assert_eq!(info.certs, vec![cert]);
+
+        assert_eq!(
+            verify(&cose_bytes, b"other claim", &verifier),
+            Err(Error::CoseSignatureMismatch)
+        );
+        assert_eq!(
+            verify(&cose_bytes[1..], claim, &verifier),
+            Err(Error::CoseSignature)
+        );
+    }
+
+    #[test]
+    fn test_protected_x5chain() {
+        let cert = pem_cert(include_bytes!("../../sdk/tests/fixtures/temp_cert.data"));
+        let signer = HashSigner {
+            certs: vec![cert.clone()],
+            placement: X5ChainPlacement::Protected,
+        };
+        let verifier = HashVerifier {
+            public_key: der::subject_public_key_info(&cert).unwrap().to_vec(),
+        };
+
+        let claim = b"claim bytes";
+        let cose_bytes = sign(claim, &signer).unwrap();
+        let sign1 = parse_sign1(&cose_bytes).unwrap();
+        assert_eq!(x5chain_placement(&sign1), Some(X5ChainPlacement::Protected));
+        assert_eq!(
+            verify(&cose_bytes, claim, &verifier).unwrap().certs,
+            vec![cert]
+        );
+
+        // the thumbprint must match the signing certificate
+        let (mut sign1, _) = sign1_to_sign_with_placement(
+            "es256",
+            vec![vec![1u8; 100]],
+            claim,
+            X5ChainPlacement::Protected,
+        )
+        .unwrap();
+        for (label, value) in sign1.protected.header.rest.iter_mut() {
+            if *label == Label::Int(X5T_LABEL) {
+                *value = x5t_value(&[2u8; 100]);
+            }
+        }
+        assert_eq!(x5chain(&sign1), Err(Error::CoseX5tMismatch));
+    }
+
+    #[test]
+    fn test_pad_sign1() {
+        let (mut sign1, _) = sign1_to_sign("ps256", vec![vec![1u8; 100]], b"data").unwrap();
+        for size in 200..300 {
+            assert_eq!(pad_sign1(&mut sign1.clone(), size).unwrap().len(), size);
+        }
+        assert_eq!(pad_sign1(&mut sign1, 10), Err(Error::CoseSigboxTooSmall));
+    }
+}
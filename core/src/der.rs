@@ -0,0 +1,136 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Just enough DER parsing to find the public key of a certificate.
+//!
+//! Full X.509 parsing needs `std`, so certificate profiles and trust are checked by
+//! the `c2pa` crate. Parsing never panics on malformed input.
+
+use crate::{Error, Result};
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_VERSION: u8 = 0xa0; // [0] EXPLICIT, the optional version of a TBSCertificate
+
+// a DER element
+struct Element<'a> {
+    tag: u8,
+    // the complete encoding, including the tag and length
+    raw: &'a [u8],
+    contents: &'a [u8],
+}
+
+// read the element at the start of `input`, returning it and the bytes after it
+fn read_element(input: &[u8]) -> Result<(Element<'_>, &[u8])> {
+    let tag = *input.first().ok_or(Error::CoseInvalidCert)?;
+    let first_len = *input.get(1).ok_or(Error::CoseInvalidCert)?;
+
+    let (len, header_len) = if first_len < 0x80 {
+        (first_len as usize, 2)
+    } else {
+        // long form, the low bits give the number of length bytes
+        let num_bytes = (first_len & 0x7f) as usize;
+        if num_bytes == 0 || num_bytes > core::mem::size_of::<usize>() {
+            return Err(Error::CoseInvalidCert);
+        }
+        let len_bytes = input.get(2..2 + num_bytes).ok_or(Error::CoseInvalidCert)?;
+        let len = len_bytes
+            .iter()
+            .fold(0usize, |len, b| (len << 8) | *b as usize);
+        (len, 2 + num_bytes)
+    };
+
+    let end = header_len.checked_add(len).ok_or(Error::CoseInvalidCert)?;
+    let raw = input.get(..end).ok_or(Error::CoseInvalidCert)?;
+    let rest = input.get(end..).ok_or(Error::CoseInvalidCert)?;
+    Ok((
+        Element {
+            tag,
+            raw,
+            contents: &raw[header_len..],
+        },
+        rest,
+    ))
+}
+
+// read a SEQUENCE at the start of `input`
+fn read_sequence(input: &[u8]) -> Result<(Element<'_>, &[u8])> {
+    let (element, rest) = read_element(input)?;
+    if element.tag != TAG_SEQUENCE {
+        return Err(Error::CoseInvalidCert);
+    }
+    Ok((element, rest))
+}
+
+/// Returns the DER encoded SubjectPublicKeyInfo of a DER encoded X.509 certificate.
+pub fn subject_public_key_info(cert_der: &[u8]) -> Result<&[u8]> {
+    let (cert, _) = read_sequence(cert_der)?;
+    let (tbs, _) = read_sequence(cert.contents)?;
+
+    // skip the version if present, then serialNumber, signature, issuer, validity and subject
+    let mut fields = tbs.contents;
+    let (first, rest) = read_element(fields)?;
+    if first.tag == TAG_VERSION {
+        fields = rest;
+    }
+    for _ in 0..5 {
+        let (_, rest) = read_element(fields)?;
+        fields = rest;
+    }
+
+    let (spki, _) = read_sequence(fields)?;
+    Ok(spki.raw)
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    // DER bytes of the first certificate in a PEM file
+    pub(crate) fn pem_cert(pem: &[u8]) -> Vec<u8> {
+        let pem = std::str::from_utf8(pem).unwrap();
+        let b64: String = pem
+            .lines()
+            .skip_while(|l| !l.starts_with("-----BEGIN"))
+            .skip(1)
+            .take_while(|l| !l.starts_with("-----END"))
+            .collect();
+        base64::decode(b64).unwrap()
+    }
+
+    #[test]
+    fn test_subject_public_key_info() {
+        // the rsaEncryption algorithm identifier
+        const RSA_OID: &[u8] = &[0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 1, 1, 1];
+
+        // a v3 certificate, which has a version field
+        let cert = pem_cert(include_bytes!(
+            "../../sdk/tests/fixtures/rsa-pss256_key-expired.pub"
+        ));
+        let spki = subject_public_key_info(&cert).unwrap();
+        assert_eq!(spki[0], TAG_SEQUENCE);
+        assert!(spki[..20].windows(RSA_OID.len()).any(|w| w == RSA_OID));
+
+        // a v1 certificate, without a version field
+        let cert = pem_cert(include_bytes!("../../sdk/tests/fixtures/temp_cert.data"));
+        let spki = subject_public_key_info(&cert).unwrap();
+        assert!(spki[..20].windows(RSA_OID.len()).any(|w| w == RSA_OID));
+
+        // truncated and malformed certificates are errors
+        for len in 0..cert.len() {
+            assert!(subject_public_key_info(&cert[..len]).is_err());
+        }
+        assert!(subject_public_key_info(&[0x30, 0x89, 0xff]).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use core::fmt;
+
+/// `Error` enumerates errors returned by the C2PA core.
+///
+/// Without `std` there is no `std::error::Error` trait, so errors are described
+/// through `Display` only.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The COSE_Sign1 could not be decoded or encoded.
+    CoseSignature,
+
+    /// The signature does not match the signed data.
+    CoseSignatureMismatch,
+
+    /// The signing algorithm is missing or not supported.
+    CoseSignatureAlgorithmNotSupported,
+
+    /// The COSE_Sign1 does not contain the signing certificate.
+    CoseX5ChainMissing,
+
+    /// The `x5t` thumbprint does not match the signing certificate.
+    CoseX5tMismatch,
+
+    /// The signing certificate could not be parsed.
+    CoseInvalidCert,
+
+    /// The signature is larger than the space reserved for it.
+    CoseSigboxTooSmall,
+
+    /// The hash algorithm is not supported.
+    UnsupportedType,
+
+    /// An error reported by a signer or verifier.
+    Crypto(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CoseSignature => write!(f, "COSE signature could not be decoded"),
+            Error::CoseSignatureMismatch => write!(f, "COSE signature did not match"),
+            Error::CoseSignatureAlgorithmNotSupported => {
+                write!(f, "COSE signature algorithm not supported")
+            }
+            Error::CoseX5ChainMissing => write!(f, "COSE x5chain missing"),
+            Error::CoseX5tMismatch => write!(f, "COSE x5t does not match the certificate"),
+            Error::CoseInvalidCert => write!(f, "COSE certificate could not be parsed"),
+            Error::CoseSigboxTooSmall => write!(f, "COSE signature box too small"),
+            Error::UnsupportedType => write!(f, "type is unsupported"),
+            Error::Crypto(msg) => write!(f, "crypto error: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// A specialized `Result` type for C2PA core operations.
+pub type Result<T> = core::result::Result<T, Error>;
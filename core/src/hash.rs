@@ -0,0 +1,58 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Hashes used for hashed URIs and data bindings.
+
+use alloc::vec::Vec;
+
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use crate::{Error, Result};
+
+/// Returns the hash of `data` with the C2PA hash algorithm `alg`
+/// ("sha256", "sha384" or "sha512").
+pub fn hash_by_alg(alg: &str, data: &[u8]) -> Result<Vec<u8>> {
+    match alg {
+        "sha256" => Ok(Sha256::digest(data).to_vec()),
+        "sha384" => Ok(Sha384::digest(data).to_vec()),
+        "sha512" => Ok(Sha512::digest(data).to_vec()),
+        _ => Err(Error::UnsupportedType),
+    }
+}
+
+/// Returns true if `hash` is the hash of `data` with `alg`.
+pub fn verify_by_alg(alg: &str, hash: &[u8], data: &[u8]) -> bool {
+    match hash_by_alg(alg, data) {
+        Ok(data_hash) => data_hash == hash,
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_hash_by_alg() {
+        let data = b"some data to hash";
+        for (alg, len) in [("sha256", 32), ("sha384", 48), ("sha512", 64)] {
+            let hash = hash_by_alg(alg, data).unwrap();
+            assert_eq!(hash.len(), len);
+            assert!(verify_by_alg(alg, &hash, data));
+            assert!(!verify_by_alg(alg, &hash, b"other data"));
+        }
+        assert_eq!(hash_by_alg("md5", data), Err(Error::UnsupportedType));
+    }
+}
@@ -0,0 +1,64 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(warnings)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+//! The COSE signing and validation core of the C2PA SDK.
+//!
+//! This crate builds without `std`, needing only `alloc`, so that devices such as
+//! cameras can sign and verify claims in constrained firmware. It does no I/O and has
+//! no cryptography of its own: signatures are made and checked by implementations of
+//! [`RawSigner`] and [`SignatureVerifier`], typically backed by a hardware key store.
+//!
+//! Asset handling, manifest stores, time-stamps and certificate trust are provided by
+//! the `c2pa` crate, which uses this one for its COSE structures.
+//!
+//! # Example: Verifying a claim signature
+//!
+//! ```no_run
+//! use c2pa_core::{cose, Result, SignatureVerifier};
+//!
+//! struct DeviceVerifier;
+//!
+//! impl SignatureVerifier for DeviceVerifier {
+//!     fn verify(&self, alg: &str, sig: &[u8], data: &[u8], public_key: &[u8]) -> Result<bool> {
+//!         // hand the signature to the device's crypto engine
+//!         # let _ = (alg, sig, data, public_key);
+//!         Ok(true)
+//!     }
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! # let (cose_bytes, claim_bytes) = (Vec::new(), Vec::new());
+//! let info = cose::verify(&cose_bytes, &claim_bytes, &DeviceVerifier)?;
+//! println!("signed with {}", info.alg);
+//! # Ok(())
+//! # }
+//! ```
+
+extern crate alloc;
+
+pub mod cose;
+pub mod der;
+
+mod error;
+pub use error::{Error, Result};
+
+pub mod hash;
+
+mod signer;
+pub use signer::{RawSigner, SignatureVerifier};
@@ -0,0 +1,48 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use alloc::vec::Vec;
+
+use crate::{cose::X5ChainPlacement, Result};
+
+/// Creates signatures over the bytes of a COSE_Sign1 structure.
+///
+/// Implement this over the signing key of a device, for example one held by a
+/// secure element, to sign claims with [`cose::sign`](crate::cose::sign).
+pub trait RawSigner {
+    /// Returns the signature of `data`, in the format COSE uses for the algorithm
+    /// (P1363 `r || s` for ECDSA).
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>>;
+
+    /// Returns the C2PA name of the signing algorithm, such as "es256".
+    fn alg(&self) -> &str;
+
+    /// Returns the DER encoded certificate chain, starting with the signing certificate.
+    fn certs(&self) -> Result<Vec<Vec<u8>>>;
+
+    /// Returns the number of bytes reserved for the COSE_Sign1, which must be
+    /// large enough to hold the signature and certificates.
+    fn reserve_size(&self) -> usize;
+
+    /// Where the certificate chain is placed in the COSE_Sign1.
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        X5ChainPlacement::Unprotected
+    }
+}
+
+/// Checks signatures, so claims can be verified with [`cose::verify`](crate::cose::verify).
+pub trait SignatureVerifier {
+    /// Returns true if `sig` is a valid signature over `data` made with `alg` by
+    /// the key in `public_key`, a DER encoded SubjectPublicKeyInfo.
+    fn verify(&self, alg: &str, sig: &[u8], data: &[u8], public_key: &[u8]) -> Result<bool>;
+}
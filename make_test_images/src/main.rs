@@ -15,11 +15,36 @@
 //! To run this, use the following command in a terminal
 //! cargo run --release --bin make_test_images
 //!
+//! The images are written with the results their validation is expected to report.
+//! To check them, or any other set of test files with an expectations file, use
+//! cargo run --release --bin make_test_images -- --verify target/images/expected.json
+//!
 mod make_test_images;
-use anyhow::{Context, Result};
+mod verify_test_images;
+use anyhow::{bail, Context, Result};
 
 fn main() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
+
+    // set RUST_LOG=debug to get detailed debug logging
+    env_logger::init();
+
+    if args.len() > 1 && args[1] == "--verify" {
+        let path = if args.len() > 2 {
+            args[2].as_ref()
+        } else {
+            "target/images/expected.json"
+        };
+        let failures = verify_test_images::verify_test_images(std::path::Path::new(path))?;
+        if failures > 0 {
+            bail!(
+                "{} test images did not report the expected results",
+                failures
+            );
+        }
+        return Ok(());
+    }
+
     let path = if args.len() > 1 {
         args[1].as_ref()
     } else {
@@ -29,9 +54,6 @@ fn main() -> Result<()> {
     let config: make_test_images::Config =
         serde_json::from_str(&buf).context("Config file format")?;
 
-    // set RUST_LOG=debug to get detailed debug logging
-    env_logger::init();
-
     make_test_images::MakeTestImages::new(config).run()?;
 
     Ok(())
@@ -15,8 +15,10 @@
 //!
 use c2pa::{
     assertions::{c2pa_action, Action, Actions, CreativeWork, SchemaDotOrgPerson},
-    get_temp_signer_by_alg, jumbf_io, Error, Ingredient, IngredientOptions, Manifest,
-    ManifestStore,
+    jumbf_io,
+    temp_signer::{get_temp_chain_signer, ChainValidity, Validity},
+    CertRule, CertificateAcceptancePolicy, Error, Ingredient, IngredientOptions, Manifest,
+    ManifestStore, PolicySigner, Signer,
 };
 
 use anyhow::{Context, Result};
@@ -30,15 +32,23 @@ use std::{
 use tempfile::tempdir;
 use twoway::find_bytes;
 
+use crate::verify_test_images::{Expectation, Expectations, EXPECTATIONS_FILE};
+
 const IMAGE_WIDTH: u32 = 2048;
 const IMAGE_HEIGHT: u32 = 1365;
 
+// the file the trust anchors of the generated images are written to
+const TRUST_ANCHORS_FILE: &str = "trust_anchors.pem";
+
 /// Defines an operation for creating a test image
 #[derive(Debug, Deserialize)]
 pub struct Recipe {
     /// The operation to perform:
     ///
-    /// One of: "copy", "make", "ogp", "dat", "sig", "uri", "clm", "prv"
+    /// One of: "copy", "make", "exp", "unt", "ogp", "dat", "sig", "uri", "clm", "prv"
+    ///
+    /// "exp" and "unt" make an image like "make", signed with an expired certificate
+    /// or with a certificate from a root that is not trusted
     pub op: String,
     /// Path or filename of parent
     ///
@@ -52,6 +62,10 @@ pub struct Recipe {
     pub ingredients: Option<Vec<String>>,
     /// The folder to write files to, will create if it does not exist
     pub output: String,
+    /// The failure codes the validation of the output is expected to report
+    ///
+    /// The output is expected to be valid if there are none
+    pub expect: Option<Vec<String>>,
 }
 
 /// Configuration
@@ -86,6 +100,66 @@ impl Default for Config {
     }
 }
 
+/// The signers the test images are signed with
+///
+/// Each signing certificate is issued through an intermediate by a root, and the root
+/// of the valid signer is written with the images as their trust anchor.
+struct TestSigners {
+    valid: Box<dyn Signer>,
+    expired: PolicySigner<Box<dyn Signer>>,
+    untrusted: Box<dyn Signer>,
+    trust_anchors: Vec<u8>,
+}
+
+impl TestSigners {
+    /// Generates the certificates and keys of the signers in `path`
+    fn new(path: &Path, alg: &str, ta: Option<String>) -> Result<Self> {
+        let chain_dir = |name: &str| -> Result<PathBuf> {
+            let dir = path.join(name);
+            fs::create_dir_all(&dir)?;
+            Ok(dir)
+        };
+
+        let (valid, chain) = get_temp_chain_signer(
+            chain_dir("valid")?,
+            alg,
+            &ChainValidity::default(),
+            ta.clone(),
+        );
+        let trust_anchors = fs::read(&chain.root_path)?;
+
+        // the sdk only signs with an expired certificate when told to accept it
+        let validity = ChainValidity {
+            leaf: Validity::expired(),
+            ..Default::default()
+        };
+        let (expired, _) = get_temp_chain_signer(chain_dir("expired")?, alg, &validity, ta.clone());
+        let mut expired = PolicySigner::new(expired);
+        let mut policy = CertificateAcceptancePolicy::new();
+        policy.relax(CertRule::Validity);
+        expired.set_certificate_acceptance_policy(policy);
+
+        let (untrusted, _) =
+            get_temp_chain_signer(chain_dir("untrusted")?, alg, &ChainValidity::default(), ta);
+
+        Ok(Self {
+            valid,
+            expired,
+            untrusted,
+            trust_anchors,
+        })
+    }
+
+    /// The signer for the images made by `op`
+    fn for_op(&self, op: &str) -> &dyn Signer {
+        match op {
+            "exp" => &self.expired,
+            "unt" => self.untrusted.as_ref(),
+            _ => self.valid.as_ref(),
+        }
+    }
+}
+
 /// Tool for building test case images for C2PA
 pub struct MakeTestImages {
     config: Config,
@@ -148,7 +222,7 @@ impl MakeTestImages {
     }
 
     /// Creates a test image with optional source and ingredients, out to dest
-    fn make_image(&self, recipe: &Recipe) -> Result<PathBuf> {
+    fn make_image(&self, recipe: &Recipe, signer: &dyn Signer) -> Result<PathBuf> {
         let src = recipe.parent.as_deref();
         let dst_path = self.make_path(&recipe.output);
         println!("Creating {:?}", dst_path);
@@ -158,6 +232,7 @@ impl MakeTestImages {
         let options = IngredientOptions {
             make_hash: true,
             title: None,
+            ..Default::default()
         };
 
         let generator = format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
@@ -259,11 +334,7 @@ impl MakeTestImages {
         manifest.add_assertion(&actions)?; // extra get required here, since actions is an array
 
         // now create store; sign claim and embed in target
-        let temp_dir = tempdir()?;
-        let (signer, _) =
-            get_temp_signer_by_alg(&temp_dir.path(), &self.config.alg, self.config.ta.clone());
-
-        manifest.embed(&dst_path, &dst_path, signer.as_ref())?;
+        manifest.embed(&dst_path, &dst_path, signer)?;
 
         Ok(dst_path)
     }
@@ -346,15 +417,27 @@ impl MakeTestImages {
     }
 
     /// Runs a list of recipes
+    ///
+    /// The trust anchor of the images and the results their validation is expected to
+    /// report are written with them, so they can be checked with
+    /// [`verify_test_images`](crate::verify_test_images::verify_test_images).
     pub fn run(&self) -> Result<()> {
         if !self.output_dir.exists() {
             std::fs::create_dir_all(&self.output_dir).context("Can't create output folder")?;
         };
 
+        let temp_dir = tempdir()?;
+        let signers = TestSigners::new(temp_dir.path(), &self.config.alg, self.config.ta.clone())
+            .context("Creating signers")?;
+        let mut expectations = Expectations {
+            trust_anchors: Some(TRUST_ANCHORS_FILE.to_owned()),
+            vectors: Vec::new(),
+        };
+
         let recipes = &self.config.recipes;
         for recipe in recipes {
             let dst_path = match recipe.op.as_str() {
-                "make" => self.make_image(recipe)?,
+                "make" | "exp" | "unt" => self.make_image(recipe, signers.for_op(&recipe.op))?,
                 "ogp" => self.make_ogp(recipe)?,
                 "dat" | "sig" | "uri" | "clm" | "prv" => self.make_err(recipe)?,
                 "copy" => self.make_copy(recipe)?,
@@ -364,8 +447,23 @@ impl MakeTestImages {
 
             if recipe.op.as_str() != "copy" {
                 println!("{}", manifest_store?);
+
+                let file = dst_path.strip_prefix(&self.output_dir).unwrap_or(&dst_path);
+                expectations.vectors.push(Expectation {
+                    file: file.to_string_lossy().into_owned(),
+                    expect: recipe.expect.clone().unwrap_or_default(),
+                });
             }
         }
+
+        fs::write(
+            self.output_dir.join(TRUST_ANCHORS_FILE),
+            &signers.trust_anchors,
+        )?;
+        fs::write(
+            self.output_dir.join(EXPECTATIONS_FILE),
+            serde_json::to_string_pretty(&expectations)?,
+        )?;
         Ok(())
     }
 }
@@ -375,6 +473,8 @@ pub mod tests {
     #![allow(clippy::expect_used)]
 
     use super::*;
+    use crate::verify_test_images::verify_test_images;
+
     const TESTS: &str = r#"{
         "alg": "ps256",
         "tsa": "http://timestamp.digicert.com",
@@ -384,8 +484,10 @@ pub mod tests {
         "recipes": [
             { "op": "copy", "parent": "../sdk/tests/fixtures/IMG_0003.jpg", "output": "A.jpg" },
             { "op": "make", "output": "C" },
-            { "op": "ogp", "parent": "C", "output": "XC" },
-            { "op": "sig", "parent": "C", "output": "E-sig-C" } 
+            { "op": "ogp", "parent": "C", "output": "XC", "expect": ["assertion.dataHash.mismatch"] },
+            { "op": "sig", "parent": "C", "output": "E-sig-C", "expect": ["claimSignature.mismatch"] },
+            { "op": "exp", "parent": "A.jpg", "output": "E-exp-CA", "expect": ["signingCredential.expired"] },
+            { "op": "unt", "output": "E-unt-C", "expect": ["signingCredential.untrusted"] }
         ]
     }"#;
 
@@ -394,6 +496,11 @@ pub mod tests {
         let config: Config = serde_json::from_str(TESTS)
             .context("Config file format")
             .expect("serde_json");
+        let output_dir = PathBuf::from(&config.output_path);
         MakeTestImages::new(config).run().expect("running");
+
+        // the images report what their recipes expect
+        let failures = verify_test_images(&output_dir.join(EXPECTATIONS_FILE)).expect("verifying");
+        assert_eq!(failures, 0);
     }
 }
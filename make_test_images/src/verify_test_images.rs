@@ -0,0 +1,109 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Verifies a set of test images against the validation results they are expected to report
+//!
+//! The expectations are read from a JSON file listing each image with the failure codes
+//! its validation must report. [`MakeTestImages`](crate::make_test_images::MakeTestImages)
+//! writes one for the images it generates, and `make_test_images/public_testfiles.json`
+//! lists the public C2PA test files, once cloned to `target/public-testfiles`.
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use c2pa::{ManifestStore, TrustConfig, TrustSource, VerifyOptions};
+use serde::{Deserialize, Serialize};
+
+/// The name of the expectations file written with generated test images
+pub const EXPECTATIONS_FILE: &str = "expected.json";
+
+/// The validation results expected of one test image
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Expectation {
+    /// Path of the image, relative to the expectations file
+    pub file: String,
+    /// The failure codes the validation must report, the image must be valid when empty
+    #[serde(default)]
+    pub expect: Vec<String>,
+}
+
+/// The validation results expected of a set of test images
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Expectations {
+    /// PEM file of the trust anchors the images are verified with, relative to the
+    /// expectations file. Signing credentials are not checked for trust if there is none.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trust_anchors: Option<String>,
+    /// The expected results of each image
+    pub vectors: Vec<Expectation>,
+}
+
+/// Verifies each image listed in the expectations file at `path`, printing the result
+/// of each one. Returns the number of images that do not report what is expected.
+pub fn verify_test_images(path: &Path) -> Result<usize> {
+    let buf = std::fs::read_to_string(path).context(format!("Reading {:?}", path))?;
+    let expectations: Expectations =
+        serde_json::from_str(&buf).context("Expectations file format")?;
+    let base = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+    let mut options = VerifyOptions::new();
+    if let Some(trust_anchors) = expectations.trust_anchors.as_ref() {
+        let trust_config = TrustConfig::new();
+        trust_config
+            .add_trust_anchors(TrustSource::File(base.join(trust_anchors)))
+            .context(format!("loading trust anchors {}", trust_anchors))?;
+        options.set_trust_config(trust_config);
+    }
+
+    let mut failures = 0;
+    for vector in &expectations.vectors {
+        let codes = reported_codes(&base.join(&vector.file), &options);
+        // an image passes if it reports every expected code, and valid images report none
+        let passed = if vector.expect.is_empty() {
+            codes.is_empty()
+        } else {
+            vector.expect.iter().all(|code| codes.contains(code))
+        };
+
+        if passed {
+            println!("ok   {}", vector.file);
+        } else {
+            println!(
+                "FAIL {}: expected {:?}, reported {:?}",
+                vector.file, vector.expect, codes
+            );
+            failures += 1;
+        }
+    }
+
+    println!(
+        "{} of {} test images as expected",
+        expectations.vectors.len() - failures,
+        expectations.vectors.len()
+    );
+    Ok(failures)
+}
+
+// the failure codes reported by the validation of the image at `path`
+fn reported_codes(path: &Path, options: &VerifyOptions) -> Vec<String> {
+    match ManifestStore::from_file_with_options(path, options) {
+        Ok(manifest_store) => manifest_store
+            .validation_status()
+            .unwrap_or_default()
+            .iter()
+            // informational codes report what could not be checked, not failures
+            .filter(|status| !status.passed() && !status.code().is_informational())
+            .map(|status| status.code().to_string())
+            .collect(),
+        Err(e) => vec![format!("error: {}", e)],
+    }
+}
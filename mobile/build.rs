@@ -0,0 +1,20 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+// Generates the Rust scaffolding for the interface described in src/c2pa.udl.
+fn main() {
+    uniffi_build::generate_scaffolding("./src/c2pa.udl").unwrap_or_else(|err| {
+        println!("cargo:warning=could not generate scaffolding: {}", err);
+        std::process::exit(1);
+    });
+}
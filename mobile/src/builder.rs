@@ -0,0 +1,69 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{io::Cursor, path::Path, sync::Arc};
+
+use c2pa::Manifest;
+
+use crate::{CallbackSigner, Result};
+
+/// Builds signed assets from a manifest definition
+pub struct Builder {
+    // the definition is kept as JSON, since a Manifest cannot be shared across threads
+    manifest_json: String,
+}
+
+impl Builder {
+    /// Create a builder from the JSON definition of a manifest
+    pub fn new(manifest_json: String) -> Result<Self> {
+        // check the definition now, rather than when signing
+        Manifest::from_json(&manifest_json)?;
+        Ok(Builder { manifest_json })
+    }
+
+    /// Sign an asset held in memory, returning the signed asset
+    pub fn sign(
+        &self,
+        format: String,
+        source: Vec<u8>,
+        signer: Arc<CallbackSigner>,
+    ) -> Result<Vec<u8>> {
+        let mut manifest = Manifest::from_json(&self.manifest_json)?;
+        let mut dest = Vec::new();
+        manifest.embed_to_streams(
+            &format,
+            &mut Cursor::new(source),
+            &signer.signer,
+            &mut dest,
+            None,
+            None,
+        )?;
+        Ok(dest)
+    }
+
+    /// Sign the asset at `source_path`, writing the signed asset to `dest_path`
+    pub fn sign_file(
+        &self,
+        source_path: String,
+        dest_path: String,
+        signer: Arc<CallbackSigner>,
+    ) -> Result<()> {
+        let mut manifest = Manifest::from_json(&self.manifest_json)?;
+        manifest.embed(
+            Path::new(&source_path),
+            Path::new(&dest_path),
+            &signer.signer,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use c2pa::Error;
+
+use crate::{C2paError, Result};
+
+/// Signs with a key held by the platform. Implemented by the app.
+pub trait SignerCallback: Send + Sync {
+    /// Returns the signature of `data`, or an empty array if signing failed
+    fn sign(&self, data: Vec<u8>) -> Vec<u8>;
+}
+
+/// A signer that delegates signing to a [`SignerCallback`]
+pub struct CallbackSigner {
+    pub(crate) signer: c2pa::CallbackSigner,
+}
+
+impl CallbackSigner {
+    pub fn new(
+        callback: Box<dyn SignerCallback>,
+        alg: String,
+        certs_pem: String,
+        reserve_size: u32,
+        tsa_url: Option<String>,
+    ) -> Result<Self> {
+        let sign = move |data: &[u8]| match callback.sign(data.to_vec()) {
+            signature if signature.is_empty() => {
+                Err(Error::OtherError("signer callback failed".into()))
+            }
+            signature => Ok(signature),
+        };
+
+        let signer = <c2pa::CallbackSigner>::new(
+            Box::new(sign),
+            &alg,
+            certs_pem.as_bytes(),
+            reserve_size as usize,
+            tsa_url,
+        )
+        .map_err(|_err| C2paError::InvalidArgument {
+            reason: "certs_pem contains no certificates".to_string(),
+        })?;
+        Ok(CallbackSigner { signer })
+    }
+}
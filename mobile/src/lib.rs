@@ -0,0 +1,62 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![deny(warnings)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+//! Kotlin and Swift bindings for the C2PA SDK, generated with [uniffi](https://github.com/mozilla/uniffi-rs).
+//!
+//! The interface is described in `src/c2pa.udl`. Signing is delegated to a
+//! `SignerCallback` implemented by the app, so private keys can stay in the
+//! Android Keystore or the Secure Enclave.
+
+mod callback_signer;
+pub use callback_signer::{CallbackSigner, SignerCallback};
+
+mod builder;
+pub use builder::Builder;
+
+mod reader;
+pub use reader::Reader;
+
+/// Errors returned to Kotlin and Swift
+#[derive(Debug, thiserror::Error)]
+pub enum C2paError {
+    #[error("invalid argument: {reason}")]
+    InvalidArgument { reason: String },
+
+    #[error("signing failed: {reason}")]
+    Signer { reason: String },
+
+    #[error("{reason}")]
+    Sdk { reason: String },
+}
+
+impl From<c2pa::Error> for C2paError {
+    fn from(err: c2pa::Error) -> Self {
+        C2paError::Sdk {
+            reason: err.to_string(),
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, C2paError>;
+
+/// The version of the C2PA SDK
+pub fn sdk_version() -> String {
+    format!("{}/{}", c2pa::NAME, c2pa::VERSION)
+}
+
+uniffi_macros::include_scaffolding!("c2pa");
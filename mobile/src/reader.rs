@@ -0,0 +1,155 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use c2pa::ManifestStore;
+
+use crate::{C2paError, Result};
+
+/// Reads and validates the manifest store of an asset
+pub struct Reader {
+    json: String,
+    active_label: Option<String>,
+    validation_errors: Vec<String>,
+}
+
+impl Reader {
+    /// Read the manifest store of the asset at `path`
+    pub fn from_file(path: String) -> Result<Self> {
+        Self::from_manifest_store(&ManifestStore::from_file(&path)?)
+    }
+
+    /// Read the manifest store of an asset held in memory.
+    /// `format` is the MIME type or extension of the asset.
+    pub fn from_bytes(format: String, data: Vec<u8>) -> Result<Self> {
+        let manifest_store =
+            ManifestStore::from_bytes_with_options(&format, &data, &c2pa::VerifyOptions::new())?;
+        Self::from_manifest_store(&manifest_store)
+    }
+
+    // a ManifestStore cannot be shared across threads, so keep what the bindings need
+    fn from_manifest_store(manifest_store: &ManifestStore) -> Result<Self> {
+        let json = serde_json::to_string_pretty(manifest_store).map_err(|err| C2paError::Sdk {
+            reason: err.to_string(),
+        })?;
+        let validation_errors = manifest_store
+            .validation_status()
+            .unwrap_or_default()
+            .iter()
+            .filter(|s| !s.passed())
+            .map(|s| s.code().to_string())
+            .collect();
+
+        Ok(Reader {
+            json,
+            active_label: manifest_store.active_label().map(|l| l.to_owned()),
+            validation_errors,
+        })
+    }
+
+    /// The manifest store as JSON
+    pub fn json(&self) -> String {
+        self.json.clone()
+    }
+
+    /// The label of the active manifest
+    pub fn active_label(&self) -> Option<String> {
+        self.active_label.clone()
+    }
+
+    /// The codes of the validation failures found
+    pub fn validation_errors(&self) -> Vec<String> {
+        self.validation_errors.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use std::sync::Arc;
+
+    use c2pa::{get_temp_signer, Signer};
+
+    use super::*;
+    use crate::{Builder, CallbackSigner, SignerCallback};
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/tests/fixtures/C.jpg");
+
+    // stands in for a platform key store
+    struct KeyStoreCallback(Box<dyn Signer + Send + Sync>);
+
+    impl SignerCallback for KeyStoreCallback {
+        fn sign(&self, data: Vec<u8>) -> Vec<u8> {
+            self.0.sign(&data).unwrap_or_default()
+        }
+    }
+
+    #[test]
+    fn test_sign_and_read() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (temp_signer, cert_path) = get_temp_signer(&temp_dir.path());
+        let reserve_size = temp_signer.reserve_size() as u32;
+        let signer = CallbackSigner::new(
+            Box::new(KeyStoreCallback(Box::new(temp_signer))),
+            "ps256".to_string(),
+            std::fs::read_to_string(&cert_path).unwrap(),
+            reserve_size,
+            None,
+        )
+        .unwrap();
+
+        let builder =
+            Builder::new(r#"{"claim_generator": "mobile_test/0.1"}"#.to_string()).unwrap();
+        let signed = builder
+            .sign(
+                "image/jpeg".to_string(),
+                std::fs::read(FIXTURE).unwrap(),
+                Arc::new(signer),
+            )
+            .unwrap();
+
+        let reader = Reader::from_bytes("image/jpeg".to_string(), signed).unwrap();
+        assert!(reader.active_label().is_some());
+        assert!(reader.validation_errors().is_empty());
+        assert!(reader.json().contains("mobile_test/0.1"));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(
+            Builder::new("not json".to_string()),
+            Err(C2paError::Sdk { .. })
+        ));
+        assert!(matches!(
+            Reader::from_file("does/not/exist.jpg".to_string()),
+            Err(C2paError::Sdk { .. })
+        ));
+
+        struct FailingCallback;
+        impl SignerCallback for FailingCallback {
+            fn sign(&self, _data: Vec<u8>) -> Vec<u8> {
+                Vec::new()
+            }
+        }
+        assert!(matches!(
+            CallbackSigner::new(
+                Box::new(FailingCallback),
+                "es256".to_string(),
+                "not a certificate".to_string(),
+                1024,
+                None
+            ),
+            Err(C2paError::InvalidArgument { .. })
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::sync::{mpsc, Arc};
+
+use napi::{
+    bindgen_prelude::*,
+    threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode},
+    JsFunction,
+};
+use napi_derive::napi;
+
+use crate::js_err;
+
+// errors are passed to the sign function instead of aborting the process
+type SignFunction = ThreadsafeFunction<Vec<u8>, ErrorStrategy::CalleeHandled>;
+
+/// The signer used on the thread pool, shared by the tasks signing with a `CallbackSigner`
+pub(crate) type JsSigner = Arc<c2pa::CallbackSigner>;
+
+/// A signer that calls an async JavaScript function to sign.
+///
+/// The function is called Node style with an error, which is `null` when signing can go
+/// ahead, and a `Buffer` holding the bytes to sign. It resolves to the signature, so keys
+/// can be held by a remote signing service or a KMS.
+#[napi]
+pub struct CallbackSigner {
+    signer: JsSigner,
+}
+
+#[napi]
+impl CallbackSigner {
+    /// Creates a signer. `alg` is the signing algorithm, such as "es256", and
+    /// `certsPem` the PEM encoded certificate chain, starting with the signing
+    /// certificate. `reserveSize` is the space reserved for the signature,
+    /// certificates and any time-stamp.
+    #[napi(
+        constructor,
+        ts_args_type = "sign: (err: Error | null, data: Buffer) => Promise<Buffer>, alg: string, certsPem: string, reserveSize: number, tsaUrl?: string"
+    )]
+    pub fn new(
+        env: Env,
+        sign: JsFunction,
+        alg: String,
+        certs_pem: String,
+        reserve_size: u32,
+        tsa_url: Option<String>,
+    ) -> Result<Self> {
+        let mut sign: SignFunction =
+            sign.create_threadsafe_function(0, |ctx| Ok(vec![Buffer::from(ctx.value)]))?;
+        // an idle signer should not keep the process alive
+        sign.unref(&env)?;
+
+        let signer = <c2pa::CallbackSigner>::new(
+            Box::new(move |data: &[u8]| call_sign(&sign, data)),
+            &alg,
+            certs_pem.as_bytes(),
+            reserve_size as usize,
+            tsa_url,
+        )
+        .map_err(|_err| js_err("certsPem contains no certificates"))?;
+        Ok(CallbackSigner {
+            signer: Arc::new(signer),
+        })
+    }
+
+    // the signer used on the thread pool
+    pub(crate) fn js_signer(&self) -> JsSigner {
+        self.signer.clone()
+    }
+}
+
+// Signs on a thread pool thread by calling the JavaScript sign function on the main
+// thread and waiting for its promise to settle.
+fn call_sign(sign: &SignFunction, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+    // a rejection keeps the JavaScript error on the main thread, leaving no reason here
+    let to_c2pa = |err: Error| {
+        let reason = if err.reason.is_empty() {
+            "sign function rejected".to_owned()
+        } else {
+            err.reason
+        };
+        c2pa::Error::OtherError(reason.into())
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let status = sign.call_with_return_value(
+        Ok(data.to_vec()),
+        ThreadsafeFunctionCallMode::Blocking,
+        move |promise: Promise<Buffer>| {
+            // the receiver only goes away if signing already failed
+            let _ = tx.send(promise);
+            Ok(())
+        },
+    );
+    if status != Status::Ok {
+        return Err(to_c2pa(Error::from_status(status)));
+    }
+
+    // the sender is dropped without a promise if the call itself failed
+    let promise = rx
+        .recv()
+        .map_err(|_| to_c2pa(js_err("sign did not return a promise")))?;
+    let signature = futures::executor::block_on(promise).map_err(to_c2pa)?;
+    Ok(signature.to_vec())
+}
@@ -0,0 +1,196 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![deny(warnings)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+//! Node.js bindings for the C2PA SDK, built with [napi-rs](https://napi.rs).
+//!
+//! Reading and signing run on the libuv thread pool and return promises, so large
+//! assets are processed from disk without blocking the event loop. Signing calls an
+//! async JavaScript function, so keys can be held by a remote signing service.
+
+use std::path::Path;
+
+use c2pa::{Manifest, ManifestStore, VerifyOptions};
+use napi::{bindgen_prelude::*, Error};
+use napi_derive::napi;
+
+mod callback_signer;
+pub use callback_signer::CallbackSigner;
+use callback_signer::JsSigner;
+
+// convert SDK errors to JavaScript errors
+pub(crate) fn js_err<E: ToString>(err: E) -> Error {
+    Error::from_reason(err.to_string())
+}
+
+fn manifest_store_json(manifest_store: &ManifestStore) -> Result<String> {
+    serde_json::to_string_pretty(manifest_store).map_err(js_err)
+}
+
+/// Returns the version of the C2PA SDK.
+#[napi]
+pub fn version() -> String {
+    format!("{}/{}", c2pa::NAME, c2pa::VERSION)
+}
+
+pub struct ReadFileTask {
+    path: String,
+}
+
+impl Task for ReadFileTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        manifest_store_json(&ManifestStore::from_file(&self.path).map_err(js_err)?)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Reads and validates the manifest store of the asset at `path`, resolving to it as JSON.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn read_file(path: String) -> AsyncTask<ReadFileTask> {
+    AsyncTask::new(ReadFileTask { path })
+}
+
+pub struct ReadBufferTask {
+    format: String,
+    data: Vec<u8>,
+}
+
+impl Task for ReadBufferTask {
+    type Output = String;
+    type JsValue = String;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let manifest_store =
+            ManifestStore::from_bytes_with_options(&self.format, &self.data, &VerifyOptions::new())
+                .map_err(js_err)?;
+        manifest_store_json(&manifest_store)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Reads and validates the manifest store of an asset held in a buffer, resolving to
+/// it as JSON. `format` is the MIME type or extension of the asset.
+#[napi(ts_return_type = "Promise<string>")]
+pub fn read_buffer(format: String, data: Buffer) -> AsyncTask<ReadBufferTask> {
+    AsyncTask::new(ReadBufferTask {
+        format,
+        data: data.to_vec(),
+    })
+}
+
+pub struct SignFileTask {
+    manifest_json: String,
+    source_path: String,
+    dest_path: String,
+    signer: JsSigner,
+}
+
+impl Task for SignFileTask {
+    type Output = ();
+    type JsValue = ();
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let mut manifest = Manifest::from_json(&self.manifest_json).map_err(js_err)?;
+        manifest
+            .embed(
+                Path::new(&self.source_path),
+                Path::new(&self.dest_path),
+                self.signer.as_ref(),
+            )
+            .map_err(js_err)?;
+        Ok(())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output)
+    }
+}
+
+/// Signs the asset at `sourcePath` with the manifest described by `manifestJson`,
+/// writing the signed asset to `destPath`.
+#[napi(ts_return_type = "Promise<void>")]
+pub fn sign_file(
+    manifest_json: String,
+    source_path: String,
+    dest_path: String,
+    signer: &CallbackSigner,
+) -> AsyncTask<SignFileTask> {
+    AsyncTask::new(SignFileTask {
+        manifest_json,
+        source_path,
+        dest_path,
+        signer: signer.js_signer(),
+    })
+}
+
+pub struct SignBufferTask {
+    manifest_json: String,
+    format: String,
+    data: Vec<u8>,
+    signer: JsSigner,
+}
+
+impl Task for SignBufferTask {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        let mut manifest = Manifest::from_json(&self.manifest_json).map_err(js_err)?;
+        let mut dest = Vec::new();
+        manifest
+            .embed_to_streams(
+                &self.format,
+                &mut std::io::Cursor::new(&self.data),
+                self.signer.as_ref(),
+                &mut dest,
+                None,
+                None,
+            )
+            .map_err(js_err)?;
+        Ok(dest)
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into())
+    }
+}
+
+/// Signs an asset held in a buffer with the manifest described by `manifestJson`,
+/// resolving to the signed asset. `format` is the MIME type or extension of the asset.
+#[napi(ts_return_type = "Promise<Buffer>")]
+pub fn sign_buffer(
+    manifest_json: String,
+    format: String,
+    data: Buffer,
+    signer: &CallbackSigner,
+) -> AsyncTask<SignBufferTask> {
+    AsyncTask::new(SignBufferTask {
+        manifest_json,
+        format,
+        data: data.to_vec(),
+        signer: signer.js_signer(),
+    })
+}
@@ -0,0 +1,64 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+const assert = require('assert');
+const crypto = require('crypto');
+const fs = require('fs');
+const os = require('os');
+const path = require('path');
+const test = require('node:test');
+
+const c2pa = require('..');
+
+const fixtures = path.join(__dirname, '../../sdk/tests/fixtures');
+const key = fs.readFileSync(path.join(fixtures, 'rsa-pss256-expired.pem'));
+const certsPem = fs.readFileSync(path.join(fixtures, 'rsa-pss256_key-expired.pub'), 'utf8');
+const manifestJson = JSON.stringify({ claim_generator: 'node_test/0.1' });
+
+// stands in for a remote signing service
+async function sign(err, data) {
+  if (err) {
+    throw err;
+  }
+  return crypto.sign('sha256', data, {
+    key,
+    padding: crypto.constants.RSA_PKCS1_PSS_PADDING,
+    saltLength: 32,
+  });
+}
+
+test('signs and reads buffers and files', async () => {
+  const signer = new c2pa.CallbackSigner(sign, 'ps256', certsPem, 10000);
+
+  const source = fs.readFileSync(path.join(fixtures, 'C.jpg'));
+  const signed = await c2pa.signBuffer(manifestJson, 'image/jpeg', source, signer);
+  assert.ok(JSON.parse(await c2pa.readBuffer('image/jpeg', signed)).active_manifest);
+
+  const dest = path.join(fs.mkdtempSync(path.join(os.tmpdir(), 'c2pa-')), 'signed.jpg');
+  await c2pa.signFile(manifestJson, path.join(fixtures, 'C.jpg'), dest, signer);
+  assert.ok((await c2pa.readFile(dest)).includes('node_test/0.1'));
+});
+
+test('rejects on errors', async () => {
+  await assert.rejects(c2pa.readFile('does/not/exist.jpg'));
+  assert.throws(() => new c2pa.CallbackSigner(sign, 'ps256', 'not a certificate', 10000));
+
+  const failing = new c2pa.CallbackSigner(
+    async () => { throw new Error('signing service unavailable'); },
+    'ps256',
+    certsPem,
+    10000,
+  );
+  const source = fs.readFileSync(path.join(fixtures, 'C.jpg'));
+  await assert.rejects(c2pa.signBuffer(manifestJson, 'image/jpeg', source, failing));
+});
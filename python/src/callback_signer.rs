@@ -0,0 +1,61 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use pyo3::{prelude::*, types::PyBytes};
+
+use crate::py_err;
+
+/// A signer that calls a Python function to sign.
+///
+/// The function is called with the bytes to sign and returns the signature as bytes,
+/// so keys can be held by a Python crypto library or a hardware security module.
+#[pyclass]
+pub struct CallbackSigner {
+    pub(crate) signer: c2pa::CallbackSigner,
+}
+
+#[pymethods]
+impl CallbackSigner {
+    /// Creates a signer. `alg` is the signing algorithm, such as "es256", and
+    /// `certs_pem` the PEM encoded certificate chain, starting with the signing
+    /// certificate. `reserve_size` is the space reserved for the signature,
+    /// certificates and any time-stamp.
+    #[new]
+    #[args(tsa_url = "None")]
+    pub fn new(
+        callback: PyObject,
+        alg: &str,
+        certs_pem: &str,
+        reserve_size: usize,
+        tsa_url: Option<String>,
+    ) -> PyResult<Self> {
+        let sign = move |data: &[u8]| {
+            Python::with_gil(|py| {
+                let signature = callback.call1(py, (PyBytes::new(py, data),))?;
+                let signature: &PyBytes = signature.as_ref(py).downcast()?;
+                Ok(signature.as_bytes().to_vec())
+            })
+            .map_err(|err: PyErr| c2pa::Error::OtherError(err.to_string().into()))
+        };
+
+        let signer = <c2pa::CallbackSigner>::new(
+            Box::new(sign),
+            alg,
+            certs_pem.as_bytes(),
+            reserve_size,
+            tsa_url,
+        )
+        .map_err(|_err| py_err("certs_pem contains no certificates"))?;
+        Ok(CallbackSigner { signer })
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#![deny(warnings)]
+#![deny(clippy::expect_used)]
+#![deny(clippy::panic)]
+#![deny(clippy::unwrap_used)]
+
+//! Python bindings for the C2PA SDK, built with [pyo3](https://pyo3.rs).
+//!
+//! The `c2pa_python` module reads and validates manifest stores and signs assets
+//! with a `CallbackSigner`, which calls a Python function to sign, so pipelines can
+//! check provenance without running a separate tool for each asset.
+//!
+//! The GIL is released while assets are read and signed, so other Python threads keep
+//! running. A `CallbackSigner` takes it back only to call its function.
+
+use std::io::Cursor;
+
+use c2pa::{Manifest, ManifestStore, VerifyOptions};
+use pyo3::{create_exception, exceptions::PyException, prelude::*, types::PyBytes};
+
+mod callback_signer;
+pub use callback_signer::CallbackSigner;
+
+create_exception!(
+    c2pa_python,
+    C2paError,
+    PyException,
+    "Raised when the C2PA SDK fails"
+);
+
+// c2pa::Error and PyErr are both foreign, so errors are converted with this
+pub(crate) fn py_err<E: ToString>(err: E) -> PyErr {
+    C2paError::new_err(err.to_string())
+}
+
+fn manifest_store_json(manifest_store: c2pa::Result<ManifestStore>) -> c2pa::Result<String> {
+    Ok(serde_json::to_string_pretty(&manifest_store?)?)
+}
+
+/// Returns the version of the C2PA SDK.
+#[pyfunction]
+fn version() -> String {
+    format!("{}/{}", c2pa::NAME, c2pa::VERSION)
+}
+
+/// Reads and validates the manifest store of the asset at `path`, returning it as JSON.
+#[pyfunction]
+fn read_file(py: Python, path: &str) -> PyResult<String> {
+    py.allow_threads(|| manifest_store_json(ManifestStore::from_file(path)))
+        .map_err(py_err)
+}
+
+/// Reads and validates the manifest store of an asset read from a binary file object,
+/// returning it as JSON. `format` is the MIME type or extension of the asset.
+#[pyfunction]
+fn read_stream(py: Python, format: &str, stream: &PyAny) -> PyResult<String> {
+    let data: &PyBytes = stream.call_method0("read")?.downcast()?;
+    let data = data.as_bytes();
+    py.allow_threads(|| {
+        manifest_store_json(ManifestStore::from_bytes_with_options(
+            format,
+            data,
+            &VerifyOptions::new(),
+        ))
+    })
+    .map_err(py_err)
+}
+
+/// Signs the asset at `source_path` with the manifest described by `manifest_json`,
+/// writing the signed asset to `dest_path`.
+#[pyfunction]
+fn sign_file(
+    py: Python,
+    manifest_json: &str,
+    source_path: &str,
+    dest_path: &str,
+    signer: &CallbackSigner,
+) -> PyResult<()> {
+    let signer = &signer.signer;
+    py.allow_threads(|| -> c2pa::Result<()> {
+        let mut manifest = Manifest::from_json(manifest_json)?;
+        manifest.embed(source_path.as_ref(), dest_path.as_ref(), signer)?;
+        Ok(())
+    })
+    .map_err(py_err)
+}
+
+/// Signs an asset read from a binary file object with the manifest described by
+/// `manifest_json`, returning the signed asset. `format` is the MIME type or
+/// extension of the asset.
+#[pyfunction]
+fn sign_stream<'py>(
+    py: Python<'py>,
+    manifest_json: &str,
+    format: &str,
+    stream: &PyAny,
+    signer: &CallbackSigner,
+) -> PyResult<&'py PyBytes> {
+    let data: &PyBytes = stream.call_method0("read")?.downcast()?;
+    let data = data.as_bytes();
+    let signer = &signer.signer;
+    let dest = py
+        .allow_threads(|| -> c2pa::Result<Vec<u8>> {
+            let mut manifest = Manifest::from_json(manifest_json)?;
+            let mut dest = Vec::new();
+            manifest.embed_to_streams(
+                format,
+                &mut Cursor::new(data),
+                signer,
+                &mut dest,
+                None,
+                None,
+            )?;
+            Ok(dest)
+        })
+        .map_err(py_err)?;
+    Ok(PyBytes::new(py, &dest))
+}
+
+#[pymodule]
+fn c2pa_python(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add("C2paError", py.get_type::<C2paError>())?;
+    m.add_class::<CallbackSigner>()?;
+    m.add_function(wrap_pyfunction!(version, m)?)?;
+    m.add_function(wrap_pyfunction!(read_file, m)?)?;
+    m.add_function(wrap_pyfunction!(read_stream, m)?)?;
+    m.add_function(wrap_pyfunction!(sign_file, m)?)?;
+    m.add_function(wrap_pyfunction!(sign_stream, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use c2pa::{get_temp_signer, Signer};
+
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../sdk/tests/fixtures/C.jpg");
+
+    // a Python callable signing with a Rust signer, standing in for a Python signing function
+    #[pyclass(unsendable)]
+    struct SignFn(Box<dyn Signer>);
+
+    #[pymethods]
+    impl SignFn {
+        fn __call__<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<&'py PyBytes> {
+            let signature = self.0.sign(data).map_err(py_err)?;
+            Ok(PyBytes::new(py, &signature))
+        }
+    }
+
+    fn bytes_io<'py>(py: Python<'py>, data: &[u8]) -> &'py PyAny {
+        let bytes_io = py.import("io").unwrap().getattr("BytesIO").unwrap();
+        bytes_io.call1((PyBytes::new(py, data),)).unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_read() {
+        Python::with_gil(|py| {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let (temp_signer, cert_path) = get_temp_signer(&temp_dir.path());
+            let reserve_size = temp_signer.reserve_size();
+            let signer = CallbackSigner::new(
+                Py::new(py, SignFn(Box::new(temp_signer)))
+                    .unwrap()
+                    .into_py(py),
+                "ps256",
+                &std::fs::read_to_string(&cert_path).unwrap(),
+                reserve_size,
+                None,
+            )
+            .unwrap();
+            let manifest_json = r#"{"claim_generator": "python_test/0.1"}"#;
+
+            // sign and read a file
+            let dest = temp_dir.path().join("signed.jpg");
+            sign_file(manifest_json, FIXTURE, dest.to_str().unwrap(), &signer).unwrap();
+            let json = read_file(dest.to_str().unwrap()).unwrap();
+            assert!(json.contains("python_test/0.1"));
+
+            // sign and read a stream
+            let source = bytes_io(py, &std::fs::read(FIXTURE).unwrap());
+            let signed = sign_stream(py, manifest_json, "image/jpeg", source, &signer).unwrap();
+            let json = read_stream("image/jpeg", bytes_io(py, signed.as_bytes())).unwrap();
+            assert!(json.contains("python_test/0.1"));
+        });
+    }
+
+    #[test]
+    fn test_errors() {
+        Python::with_gil(|py| {
+            let err = read_file("does/not/exist.jpg").unwrap_err();
+            assert!(err.is_instance_of::<C2paError>(py));
+
+            // the stream must be binary
+            let string_io = py.import("io").unwrap().getattr("StringIO").unwrap();
+            let text = string_io.call1(("text",)).unwrap();
+            assert!(read_stream("image/jpeg", text).is_err());
+
+            let err = CallbackSigner::new(py.None(), "es256", "not a certificate", 1024, None)
+                .unwrap_err();
+            assert!(err.is_instance_of::<C2paError>(py));
+        });
+    }
+}
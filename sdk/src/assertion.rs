@@ -24,7 +24,7 @@ use serde_json::Value;
 use thiserror::Error;
 
 /// Check to see if this a label whose string can vary, if so return the root of the label and version if available
-fn get_mutable_label(var_label: &str) -> (String, Option<usize>) {
+pub(crate) fn get_mutable_label(var_label: &str) -> (String, Option<usize>) {
     if var_label.starts_with(labels::SCHEMA_ORG) {
         (var_label.to_string(), None)
     } else {
@@ -13,8 +13,9 @@
 
 use crate::{
     assertion::{Assertion, AssertionBase, AssertionCbor},
-    assertions::{labels, Actor, Metadata},
+    assertions::{labels, Actor, Metadata, RegionOfInterest},
     error::Result,
+    hashed_uri::HashedUri,
     Error,
 };
 
@@ -22,6 +23,11 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+const ASSERTION_CREATION_VERSION: usize = 2;
+
+// the action parameter holding the prompt given to a trained model
+const PROMPT_PARAMETER: &str = "prompt";
+
 /// Specification defined C2PA actions
 pub mod c2pa_action {
     /// Changes to tone, saturation, etc.
@@ -34,8 +40,14 @@ pub mod c2pa_action {
     pub const CROPPED: &str = "c2pa.cropped";
     /// Changes using drawing tools including brushes or eraser.
     pub const DRAWING: &str = "c2pa.drawing";
+    /// Changes to the language of the audio of the asset.
+    pub const DUBBED: &str = "c2pa.dubbed";
     /// Generalized actions that affect the "editorial" meaning of the content.
     pub const EDITED: &str = "c2pa.edited";
+    /// Changes to the metadata of the asset that do not affect its content.
+    pub const EDITED_METADATA: &str = "c2pa.edited.metadata";
+    /// Changes to the quality of the content, such as noise reduction or sharpening.
+    pub const ENHANCED: &str = "c2pa.enhanced";
     /// Changes to appearance with applied filters, styles, etc.
     pub const FILTERED: &str = "c2pa.filtered";
     /// An existing asset was opened and is being set as the `parentOf` ingredient.
@@ -44,18 +56,210 @@ pub mod c2pa_action {
     pub const ORIENTATION: &str = "c2pa.orientation";
     /// Added/Placed a `componentOf` ingredient into the asset.
     pub const PLACED: &str = "c2pa.placed";
+    /// The asset was produced from its ingredients, such as a video rendered from a timeline.
+    pub const PRODUCED: &str = "c2pa.produced";
     /// Asset is released to a wider audience.
     pub const PUBLISHED: &str = "c2pa.published";
     /// A conversion of one packaging or container format to another. Content may be repackaged without transcoding.
     /// Does not include any adjustments that would affect the "editorial" meaning of the content.
     pub const REPACKAGED: &str = "c2pa.repackaged";
+    /// One or more assertions were redacted from an ingredient's manifest.
+    /// The `redacted` parameter holds the URI of the redacted assertion.
+    pub const REDACTED: &str = "c2pa.redacted";
+    /// A `componentOf` ingredient was removed from the asset.
+    pub const REMOVED: &str = "c2pa.removed";
     /// Changes to content dimensions and/or file size
     pub const RESIZED: &str = "c2pa.resized";
     /// A direct conversion of one encoding to another, including resolution scaling, bitrate adjustment and encoding format change.
     /// Does not include any adjustments that would affect the "editorial" meaning of the content.
     pub const TRANSCODED: &str = "c2pa.transcoded";
+    /// Changes to the language of the content, such as its text or subtitles.
+    pub const TRANSLATED: &str = "c2pa.translated";
+    /// Removal of a temporal range of the content.
+    pub const TRIMMED: &str = "c2pa.trimmed";
     /// Something happened, but the claim_generator cannot specify what.
     pub const UNKNOWN: &str = "c2pa.unknown";
+    /// An invisible watermark was inserted into the content.
+    pub const WATERMARKED: &str = "c2pa.watermarked";
+    /// Characters or glyphs were added to the font.
+    pub const FONT_CHARACTERS_ADDED: &str = "c2pa.font.charactersAdded";
+    /// Characters or glyphs were deleted from the font.
+    pub const FONT_CHARACTERS_DELETED: &str = "c2pa.font.charactersDeleted";
+    /// Characters or glyphs of the font were modified.
+    pub const FONT_CHARACTERS_MODIFIED: &str = "c2pa.font.charactersModified";
+    /// The font was created from an instance of a variable font.
+    pub const FONT_CREATED_FROM_VARIABLE_FONT: &str = "c2pa.font.createdFromVariableFont";
+    /// Generalized changes to the font that are not covered by the other font actions.
+    pub const FONT_EDITED: &str = "c2pa.font.edited";
+    /// Hinting was added to or changed in the font.
+    pub const FONT_HINTED: &str = "c2pa.font.hinted";
+    /// The font was merged with other fonts.
+    pub const FONT_MERGED: &str = "c2pa.font.merged";
+    /// An OpenType feature was added to the font.
+    pub const FONT_OPEN_TYPE_FEATURE_ADDED: &str = "c2pa.font.openTypeFeatureAdded";
+    /// An OpenType feature of the font was modified.
+    pub const FONT_OPEN_TYPE_FEATURE_MODIFIED: &str = "c2pa.font.openTypeFeatureModified";
+    /// An OpenType feature was removed from the font.
+    pub const FONT_OPEN_TYPE_FEATURE_REMOVED: &str = "c2pa.font.openTypeFeatureRemoved";
+    /// The glyphs of the font were resized.
+    pub const FONT_RESIZED: &str = "c2pa.font.resized";
+    /// The font was subset to fewer characters or glyphs.
+    pub const FONT_SUBSET: &str = "c2pa.font.subset";
+
+    const ALL: &[&str] = &[
+        COLOR_ADJUSTMENTS,
+        CONVERTED,
+        CREATED,
+        CROPPED,
+        DRAWING,
+        DUBBED,
+        EDITED,
+        EDITED_METADATA,
+        ENHANCED,
+        FILTERED,
+        OPENED,
+        ORIENTATION,
+        PLACED,
+        PRODUCED,
+        PUBLISHED,
+        REPACKAGED,
+        REDACTED,
+        REMOVED,
+        RESIZED,
+        TRANSCODED,
+        TRANSLATED,
+        TRIMMED,
+        UNKNOWN,
+        WATERMARKED,
+        FONT_CHARACTERS_ADDED,
+        FONT_CHARACTERS_DELETED,
+        FONT_CHARACTERS_MODIFIED,
+        FONT_CREATED_FROM_VARIABLE_FONT,
+        FONT_EDITED,
+        FONT_HINTED,
+        FONT_MERGED,
+        FONT_OPEN_TYPE_FEATURE_ADDED,
+        FONT_OPEN_TYPE_FEATURE_MODIFIED,
+        FONT_OPEN_TYPE_FEATURE_REMOVED,
+        FONT_RESIZED,
+        FONT_SUBSET,
+    ];
+
+    /// Returns `true` if the label is one of the actions defined by the specification.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use c2pa::assertions::c2pa_action;
+    ///
+    /// assert!(c2pa_action::is_known(c2pa_action::CROPPED));
+    /// assert!(!c2pa_action::is_known("c2pa.folded"));
+    /// assert!(!c2pa_action::is_known("com.example.folded"));
+    /// ```
+    pub fn is_known(label: &str) -> bool {
+        ALL.contains(&label)
+    }
+}
+
+/// IPTC digital source types, describing how the content of an asset was made.
+///
+/// See <https://cv.iptc.org/newscodes/digitalsourcetype/>.
+pub mod digital_source_type {
+    /// The content was captured from a real-life source by a digital camera or recorder.
+    pub const DIGITAL_CAPTURE: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/digitalCapture";
+    /// The content was digitized from a negative on film.
+    pub const NEGATIVE_FILM: &str = "http://cv.iptc.org/newscodes/digitalsourcetype/negativeFilm";
+    /// The content was digitized from a positive on a transparency.
+    pub const POSITIVE_FILM: &str = "http://cv.iptc.org/newscodes/digitalsourcetype/positiveFilm";
+    /// The content was digitized from a print on a non-transparent medium.
+    pub const PRINT: &str = "http://cv.iptc.org/newscodes/digitalsourcetype/print";
+    /// The content was captured and then edited by a human without changing its meaning.
+    pub const MINOR_HUMAN_EDITS: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/minorHumanEdits";
+    /// The content was combined from several captures of real-life sources.
+    pub const COMPOSITE_CAPTURE: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/compositeCapture";
+    /// The content was captured and then enhanced by an algorithm.
+    pub const ALGORITHMICALLY_ENHANCED: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/algorithmicallyEnhanced";
+    /// The content was made by a human using digital tools, without capture.
+    pub const DIGITAL_CREATION: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/digitalCreation";
+    /// The content is a digital representation of data, such as a chart.
+    pub const DATA_DRIVEN_MEDIA: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/dataDrivenMedia";
+    /// The content was created by a model trained on sampled content.
+    pub const TRAINED_ALGORITHMIC_MEDIA: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/trainedAlgorithmicMedia";
+    /// The content was created purely by an algorithm, not based on sampled content.
+    pub const ALGORITHMIC_MEDIA: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/algorithmicMedia";
+    /// The content is a recording of a computer screen.
+    pub const SCREEN_CAPTURE: &str = "http://cv.iptc.org/newscodes/digitalsourcetype/screenCapture";
+    /// The content is a recording of a virtual event, such as a video game.
+    pub const VIRTUAL_RECORDING: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/virtualRecording";
+    /// The content was combined from several sources, some of them synthetic.
+    pub const COMPOSITE_SYNTHETIC: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/compositeSynthetic";
+    /// The content was combined from captured content and content created by a trained model.
+    pub const COMPOSITE_WITH_TRAINED_ALGORITHMIC_MEDIA: &str =
+        "http://cv.iptc.org/newscodes/digitalsourcetype/compositeWithTrainedAlgorithmicMedia";
+}
+
+/// Describes the software that generated a claim or performed an action.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct GeneratorInfo {
+    /// The name of the software
+    pub name: String,
+
+    /// The version of the software
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Any other fields describing the software, such as `icon`
+    #[serde(flatten)]
+    pub other: HashMap<String, Value>,
+}
+
+impl GeneratorInfo {
+    /// Create a description of the software with the given name
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            version: None,
+            other: HashMap::new(),
+        }
+    }
+
+    /// Sets the version of the software
+    pub fn set_version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_owned());
+        self
+    }
+}
+
+/// The software agent of an action.
+///
+/// Version 1 actions name the software, version 2 actions describe it.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum SoftwareAgent {
+    /// The name of the software, as in version 1 actions
+    String(String),
+    /// A description of the software, as in version 2 actions
+    ClaimGeneratorInfo(GeneratorInfo),
+}
+
+impl SoftwareAgent {
+    /// Returns the name of the software
+    pub fn name(&self) -> &str {
+        match self {
+            SoftwareAgent::String(name) => name,
+            SoftwareAgent::ClaimGeneratorInfo(info) => &info.name,
+        }
+    }
 }
 
 /// Defines a single action taken on an asset.
@@ -64,8 +268,8 @@ pub mod c2pa_action {
 /// along with possible other information such as what software performed
 /// the action.
 ///
-/// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_actions>.
-#[derive(Deserialize, Serialize, Debug, PartialEq)]
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_actions>.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct Action {
     /// The label associated with this action. See ([`c2pa_action`]).
     action: String,
@@ -76,7 +280,7 @@ pub struct Action {
 
     /// The software agent that performed the action.
     #[serde(rename = "softwareAgent", skip_serializing_if = "Option::is_none")]
-    software_agent: Option<String>,
+    software_agent: Option<SoftwareAgent>,
 
     /// A semicolon-delimited list of the parts of the resource that were changed since the previous event history.
     ///
@@ -86,6 +290,10 @@ pub struct Action {
     #[serde(skip_serializing_if = "Option::is_none")]
     changed: Option<String>,
 
+    /// The regions of interest of the asset that were changed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    changes: Option<Vec<RegionOfInterest>>,
+
     /// The value of the `xmpMM:InstanceID` property for the modified (output) resource.
     #[serde(rename = "InstanceId", skip_serializing_if = "Option::is_none")]
     instance_id: Option<String>,
@@ -97,6 +305,18 @@ pub struct Action {
     /// An array of the creators that undertook this action.
     #[serde(skip_serializing_if = "Option::is_none")]
     actors: Option<Vec<Actor>>,
+
+    /// A human readable description of the action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+
+    /// How the content resulting from the action was made. See ([`digital_source_type`]).
+    #[serde(rename = "digitalSourceType", skip_serializing_if = "Option::is_none")]
+    digital_source_type: Option<String>,
+
+    /// Why the action was taken, such as the reason for a redaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
 }
 
 impl Action {
@@ -110,12 +330,36 @@ impl Action {
             when: None,
             software_agent: None,
             changed: None,
+            changes: None,
             instance_id: None,
             parameters: None,
             actors: None,
+            description: None,
+            digital_source_type: None,
+            reason: None,
         }
     }
 
+    /// Create a `c2pa.created` action for content generated by a trained model.
+    ///
+    /// The model is recorded as the software agent and the digital source type is
+    /// [`digital_source_type::TRAINED_ALGORITHMIC_MEDIA`].
+    pub fn ai_generated(model: GeneratorInfo) -> Self {
+        Self::new(c2pa_action::CREATED)
+            .set_software_agent_info(SoftwareAgent::ClaimGeneratorInfo(model))
+            .set_digital_source_type(digital_source_type::TRAINED_ALGORITHMIC_MEDIA)
+    }
+
+    /// Create an action editing existing content with a trained model, such as inpainting.
+    ///
+    /// The model is recorded as the software agent and the digital source type is
+    /// [`digital_source_type::COMPOSITE_WITH_TRAINED_ALGORITHMIC_MEDIA`].
+    pub fn ai_edited(label: &str, model: GeneratorInfo) -> Self {
+        Self::new(label)
+            .set_software_agent_info(SoftwareAgent::ClaimGeneratorInfo(model))
+            .set_digital_source_type(digital_source_type::COMPOSITE_WITH_TRAINED_ALGORITHMIC_MEDIA)
+    }
+
     /// Returns the label for this action.
     ///
     /// This label is often one of the labels defined in [`c2pa_action`],
@@ -131,9 +375,14 @@ impl Action {
         self.when.as_deref()
     }
 
-    /// Returns the software agent that performed the action.
+    /// Returns the name of the software agent that performed the action.
     pub fn software_agent(&self) -> Option<&str> {
-        self.software_agent.as_deref()
+        self.software_agent.as_ref().map(|agent| agent.name())
+    }
+
+    /// Returns the software agent that performed the action.
+    pub fn software_agent_info(&self) -> Option<&SoftwareAgent> {
+        self.software_agent.as_ref()
     }
 
     /// Returns the value of the `xmpMM:InstanceID` property for the modified
@@ -157,11 +406,64 @@ impl Action {
         }
     }
 
+    /// Returns the ingredients the action was taken on.
+    ///
+    /// These are read from the `ingredients` parameter of version 2 actions, or the
+    /// `ingredient` parameter of version 1 actions.
+    pub fn ingredients(&self) -> Result<Vec<HashedUri>> {
+        let ingredients = match (
+            self.get_parameter("ingredients"),
+            self.get_parameter("ingredient"),
+        ) {
+            (Some(ingredients), _) => serde_json::from_value(ingredients.clone())?,
+            (None, Some(ingredient)) => vec![serde_json::from_value(ingredient.clone())?],
+            (None, None) => Vec::new(),
+        };
+        Ok(ingredients)
+    }
+
     /// An array of the [`Actor`]s that undertook this action.
     pub fn actors(&self) -> Option<&[Actor]> {
         self.actors.as_deref()
     }
 
+    /// Returns the regions of interest of the asset that were changed.
+    pub fn changes(&self) -> Option<&[RegionOfInterest]> {
+        self.changes.as_deref()
+    }
+
+    /// Returns the human readable description of the action.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Returns how the content resulting from the action was made.
+    ///
+    /// This is usually one of the types defined in [`digital_source_type`].
+    pub fn digital_source_type(&self) -> Option<&str> {
+        self.digital_source_type.as_deref()
+    }
+
+    /// Returns why the action was taken.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Returns the prompt given to a trained model, if recorded.
+    pub fn prompt(&self) -> Option<&str> {
+        self.get_parameter(PROMPT_PARAMETER)
+            .and_then(|prompt| prompt.as_str())
+    }
+
+    /// Returns `true` if the content resulting from the action was made by a trained model.
+    pub fn is_ai_generated(&self) -> bool {
+        matches!(
+            self.digital_source_type(),
+            Some(digital_source_type::TRAINED_ALGORITHMIC_MEDIA)
+                | Some(digital_source_type::COMPOSITE_WITH_TRAINED_ALGORITHMIC_MEDIA)
+        )
+    }
+
     /// Sets the timestamp for when the action occurred.
     ///
     /// This timestamp must be in ISO-8601 date.
@@ -170,9 +472,15 @@ impl Action {
         self
     }
 
-    /// Sets the software agent that performed the action.
+    /// Sets the name of the software agent that performed the action.
     pub fn set_software_agent(mut self, software_agent: &str) -> Self {
-        self.software_agent = Some(software_agent.to_owned());
+        self.software_agent = Some(SoftwareAgent::String(software_agent.to_owned()));
+        self
+    }
+
+    /// Sets the software agent that performed the action.
+    pub fn set_software_agent_info(mut self, software_agent: SoftwareAgent) -> Self {
+        self.software_agent = Some(software_agent);
         self
     }
 
@@ -209,11 +517,115 @@ impl Action {
         Ok(self)
     }
 
+    /// Sets the ingredients the action was taken on, replacing any `ingredient` parameter.
+    pub fn set_ingredients(mut self, ingredients: Vec<HashedUri>) -> Result<Self> {
+        if let Some(parameters) = self.parameters.as_mut() {
+            parameters.remove("ingredient");
+        }
+        self.set_parameter("ingredients".to_owned(), ingredients)
+    }
+
     /// Sets the array of [`Actor`]s that undertook this action.
     pub fn set_actors(mut self, actors: Option<&Vec<Actor>>) -> Self {
         self.actors = actors.cloned();
         self
     }
+
+    /// Sets the regions of interest of the asset that were changed.
+    pub fn set_changes(mut self, changes: Vec<RegionOfInterest>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    /// Sets the human readable description of the action.
+    pub fn set_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+
+    /// Sets how the content resulting from the action was made.
+    ///
+    /// This is usually one of the types defined in [`digital_source_type`].
+    pub fn set_digital_source_type(mut self, source_type: &str) -> Self {
+        self.digital_source_type = Some(source_type.to_owned());
+        self
+    }
+
+    /// Sets why the action was taken.
+    pub fn set_reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_owned());
+        self
+    }
+
+    /// Sets the prompt given to a trained model, recorded in the `prompt` parameter.
+    pub fn set_prompt(self, prompt: &str) -> Result<Self> {
+        self.set_parameter(PROMPT_PARAMETER.to_owned(), prompt)
+    }
+
+    // convert version 1 fields to their version 2 form
+    fn to_v2(&self) -> Result<Self> {
+        let mut action = self.clone();
+        if let Some(SoftwareAgent::String(name)) = &action.software_agent {
+            action.software_agent =
+                Some(SoftwareAgent::ClaimGeneratorInfo(GeneratorInfo::new(name)));
+        }
+        if action.get_parameter("ingredient").is_some() {
+            let ingredients = action.ingredients()?;
+            action = action.set_ingredients(ingredients)?;
+        }
+        Ok(action)
+    }
+}
+
+// checks the label of an action is in reverse-domain format, `c2pa.` labels that are not
+// known yet are accepted since later versions of the specification may define them
+fn check_action_label(label: &str) -> Result<()> {
+    if label.split('.').count() > 1 && label.split('.').all(|part| !part.is_empty()) {
+        Ok(())
+    } else {
+        Err(Error::AssertionActionsInvalid(format!(
+            "action label {} is not in reverse-domain format",
+            label
+        )))
+    }
+}
+
+/// Describes defaults shared by the actions with the same label.
+///
+/// Templates are part of version 2 actions assertions.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ActionTemplate {
+    /// The label of the actions the template applies to, or `*` for all actions
+    pub action: String,
+
+    /// The software agent that performed the actions
+    #[serde(rename = "softwareAgent", skip_serializing_if = "Option::is_none")]
+    pub software_agent: Option<SoftwareAgent>,
+
+    /// A human readable description of the actions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// How the content resulting from the actions was made
+    #[serde(rename = "digitalSourceType", skip_serializing_if = "Option::is_none")]
+    pub digital_source_type: Option<String>,
+
+    /// Parameters shared by the actions
+    #[serde(rename = "templateParameters", skip_serializing_if = "Option::is_none")]
+    pub template_parameters: Option<HashMap<String, Value>>,
+}
+
+impl ActionTemplate {
+    /// Create a template for the actions with the given label
+    pub fn new(action: &str) -> Self {
+        Self {
+            action: action.to_owned(),
+            software_agent: None,
+            description: None,
+            digital_source_type: None,
+            template_parameters: None,
+        }
+    }
 }
 
 /// An `Actions` assertion provides information on edits and other
@@ -223,12 +635,23 @@ impl Action {
 /// what took place on the asset, when it took place, along with possible
 /// other information such as what software performed the action.
 ///
-/// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_actions>.
-#[derive(Deserialize, Serialize, Debug, PartialEq)]
+/// Actions are written as version 2 assertions, converting version 1 fields to their
+/// version 2 form. Both versions are read.
+///
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_actions>.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub struct Actions {
     /// A list of [`Action`]s.
     pub actions: Vec<Action>,
 
+    /// Templates describing defaults shared by actions with the same label.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    templates: Option<Vec<ActionTemplate>>,
+
+    /// Whether the list contains every action taken on the asset.
+    #[serde(rename = "allActionsIncluded", skip_serializing_if = "Option::is_none")]
+    all_actions_included: Option<bool>,
+
     /// Additional information about the assertion.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<Metadata>,
@@ -237,13 +660,15 @@ pub struct Actions {
 impl Actions {
     /// Label prefix for an [`Actions`] assertion.
     ///
-    /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_actions>.
+    /// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_actions>.
     pub const LABEL: &'static str = labels::ACTIONS;
 
     /// Creates a new [`Actions`] assertion struct.
     pub fn new() -> Self {
         Self {
             actions: Vec::new(),
+            templates: None,
+            all_actions_included: None,
             metadata: None,
         }
     }
@@ -253,6 +678,21 @@ impl Actions {
         &self.actions
     }
 
+    /// Returns `true` if any of the actions made content with a trained model.
+    pub fn is_ai_generated(&self) -> bool {
+        self.actions.iter().any(Action::is_ai_generated)
+    }
+
+    /// Returns the list of [`ActionTemplate`]s.
+    pub fn templates(&self) -> Option<&[ActionTemplate]> {
+        self.templates.as_deref()
+    }
+
+    /// Returns whether the list contains every action taken on the asset, if it is stated.
+    pub fn all_actions_included(&self) -> Option<bool> {
+        self.all_actions_included
+    }
+
     /// Returns the assertion's [`Metadata`], if it exists.
     pub fn metadata(&self) -> Option<&Metadata> {
         self.metadata.as_ref()
@@ -264,6 +704,18 @@ impl Actions {
         self
     }
 
+    /// Adds an [`ActionTemplate`] to this assertion's list of templates.
+    pub fn add_template(&mut self, template: ActionTemplate) -> &mut Self {
+        self.templates.get_or_insert_with(Vec::new).push(template);
+        self
+    }
+
+    /// Sets whether the list contains every action taken on the asset.
+    pub fn set_all_actions_included(&mut self, all_included: bool) -> &mut Self {
+        self.all_actions_included = Some(all_included);
+        self
+    }
+
     /// Sets [`Metadata`] for the action.
     pub fn add_metadata(&mut self, metadata: Metadata) -> &mut Self {
         self.metadata = Some(metadata);
@@ -275,15 +727,57 @@ impl Actions {
         let actions: Actions = serde_json::from_value(json.clone())?;
         Ok(actions)
     }
+
+    /// Checks the assertion against the actions schema.
+    ///
+    /// There must be at least one action, and `c2pa.created` or `c2pa.opened` may only be
+    /// the first. Labels must be in reverse-domain format, `c2pa.` labels that are not
+    /// defined by the specification are accepted so that newer assertions still validate.
+    pub fn validate(&self) -> Result<()> {
+        if self.actions.is_empty() {
+            return Err(Error::AssertionActionsInvalid(
+                "no actions listed".to_owned(),
+            ));
+        }
+
+        for (index, action) in self.actions.iter().enumerate() {
+            check_action_label(action.action())?;
+
+            let is_origin = matches!(action.action(), c2pa_action::CREATED | c2pa_action::OPENED);
+            if is_origin && index > 0 {
+                return Err(Error::AssertionActionsInvalid(format!(
+                    "{} must be the first action",
+                    action.action()
+                )));
+            }
+        }
+
+        for template in self.templates.iter().flatten() {
+            if template.action != "*" {
+                check_action_label(&template.action)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl AssertionCbor for Actions {}
 
 impl AssertionBase for Actions {
     const LABEL: &'static str = labels::ACTIONS;
+    const VERSION: Option<usize> = Some(ASSERTION_CREATION_VERSION);
 
     fn to_assertion(&self) -> Result<Assertion> {
-        Self::to_cbor_assertion(self)
+        self.validate()?;
+
+        let mut actions = self.clone();
+        actions.actions = self
+            .actions
+            .iter()
+            .map(Action::to_v2)
+            .collect::<Result<_>>()?;
+        Self::to_cbor_assertion(&actions)
     }
 
     fn from_assertion(assertion: &Assertion) -> Result<Self> {
@@ -307,6 +801,7 @@ pub mod tests {
 
     use crate::assertion::{Assertion, AssertionData};
     use crate::assertions::metadata::{c2pa_source::GENERATOR_REE, DataSource, ReviewRating};
+    use crate::assertions::{Frame, Range};
     use crate::hashed_uri::HashedUri;
 
     fn make_hashed_uri1() -> HashedUri {
@@ -367,7 +862,7 @@ pub mod tests {
         assert_eq!(original.actions.len(), 2);
         let assertion = original.to_assertion().expect("build_assertion");
         assert_eq!(assertion.mime_type(), "application/cbor");
-        assert_eq!(assertion.label(), Actions::LABEL);
+        assert_eq!(assertion.label(), format!("{}.v2", Actions::LABEL));
 
         let result = Actions::from_assertion(&assertion).expect("extract_assertion");
         assert_eq!(result.actions.len(), 2);
@@ -388,6 +883,110 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_actions_v2() {
+        let mut original = Actions::new();
+        original
+            .add_action(
+                Action::new(c2pa_action::CREATED)
+                    .set_digital_source_type(digital_source_type::TRAINED_ALGORITHMIC_MEDIA)
+                    .set_description("generated from a prompt"),
+            )
+            .add_action(
+                make_action1().set_changes(vec![RegionOfInterest::new(Range::frame(Frame {
+                    start: Some(0),
+                    end: Some(10),
+                }))]),
+            )
+            .add_template(ActionTemplate::new("*"))
+            .set_all_actions_included(true);
+
+        // version 1 fields are written in their version 2 form
+        let assertion = original.to_assertion().unwrap();
+        let result = Actions::from_assertion(&assertion).unwrap();
+        assert_eq!(result.all_actions_included(), Some(true));
+        assert_eq!(result.templates().unwrap()[0].action, "*");
+        assert_eq!(
+            result.actions[0].digital_source_type(),
+            Some(digital_source_type::TRAINED_ALGORITHMIC_MEDIA)
+        );
+        assert_eq!(
+            result.actions[1].software_agent_info(),
+            Some(&SoftwareAgent::ClaimGeneratorInfo(GeneratorInfo::new(
+                "test"
+            )))
+        );
+        assert_eq!(result.actions[1].software_agent(), Some("test"));
+        assert_eq!(result.actions[1].changes(), original.actions[1].changes());
+        assert!(result.actions[1].get_parameter("ingredient").is_none());
+        assert_eq!(
+            result.actions[1].ingredients().unwrap(),
+            vec![make_hashed_uri1()]
+        );
+
+        // version 1 assertions are still read
+        let v1 = Assertion::from_data_cbor(Actions::LABEL, &serde_cbor::to_vec(&original).unwrap());
+        let result = Actions::from_assertion(&v1).unwrap();
+        assert_eq!(result.actions[1].software_agent(), Some("test"));
+        assert_eq!(result.actions[1].ingredients().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_ai_actions() {
+        let model = GeneratorInfo::new("Example Diffusion").set_version("2.1");
+        let mut actions = Actions::new();
+        actions
+            .add_action(
+                Action::ai_generated(model.clone())
+                    .set_prompt("a lighthouse at dusk")
+                    .unwrap(),
+            )
+            .add_action(Action::ai_edited(c2pa_action::EDITED, model));
+        assert!(actions.is_ai_generated());
+
+        let result = Actions::from_assertion(&actions.to_assertion().unwrap()).unwrap();
+        assert_eq!(result.actions[0].action(), c2pa_action::CREATED);
+        assert_eq!(result.actions[0].prompt(), Some("a lighthouse at dusk"));
+        assert_eq!(
+            result.actions[0].software_agent(),
+            Some("Example Diffusion")
+        );
+        assert_eq!(
+            result.actions[1].digital_source_type(),
+            Some(digital_source_type::COMPOSITE_WITH_TRAINED_ALGORITHMIC_MEDIA)
+        );
+        assert!(!Action::new(c2pa_action::CROPPED).is_ai_generated());
+    }
+
+    #[test]
+    fn test_actions_validate() {
+        assert!(Actions::new().validate().is_err());
+
+        let mut actions = Actions::new();
+        actions.add_action(Action::new(c2pa_action::CROPPED));
+        actions.add_action(Action::new("com.example.folded"));
+        assert!(actions.validate().is_ok());
+
+        actions.add_action(Action::new(c2pa_action::CREATED));
+        assert!(matches!(
+            actions.to_assertion(),
+            Err(Error::AssertionActionsInvalid(_))
+        ));
+
+        let mut actions = Actions::new();
+        actions.add_action(Action::new("c2pa.folded"));
+        assert!(actions.validate().is_ok());
+        assert!(c2pa_action::is_known(c2pa_action::FONT_SUBSET));
+
+        let mut actions = Actions::new();
+        actions.add_action(Action::new("c2pa..folded"));
+        assert!(actions.validate().is_err());
+
+        let mut actions = Actions::new();
+        actions.add_action(Action::new("folded"));
+        assert!(actions.validate().is_err());
+    }
+
     #[test]
     fn test_build_assertion() {
         let assertion = Actions::new()
@@ -0,0 +1,344 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{
+    assertion::get_mutable_label,
+    error::{Error, Result},
+    ManifestAssertion,
+};
+
+/// A schema for the data of a custom assertion.
+///
+/// Integrators register schemas for their assertion labels in an [`AssertionRegistry`]
+/// so assertions with those labels are checked during validation.
+pub trait AssertionSchema: Send + Sync {
+    /// The label of the assertions the schema applies to, without a version suffix
+    fn label(&self) -> &str;
+
+    /// Check the data of an assertion, decoded from CBOR or JSON, against the schema.
+    ///
+    /// Returns [`Error::AssertionSchemaMismatch`] describing the first difference found.
+    fn validate(&self, data: &Value) -> Result<()>;
+}
+
+/// A schema given by a Rust type. Data matches if it deserializes into the type.
+pub struct TypedAssertionSchema<T> {
+    label: String,
+    // fn() keeps the schema Send and Sync whatever the type
+    data_type: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> TypedAssertionSchema<T> {
+    /// Create a schema requiring assertions with `label` to deserialize into `T`
+    pub fn new(label: &str) -> Self {
+        TypedAssertionSchema {
+            label: label.to_owned(),
+            data_type: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> AssertionSchema for TypedAssertionSchema<T> {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn validate(&self, data: &Value) -> Result<()> {
+        serde_json::from_value::<T>(data.clone())
+            .map(|_| ())
+            .map_err(|e| Error::AssertionSchemaMismatch(e.to_string()))
+    }
+}
+
+/// A schema given as a JSON Schema document.
+///
+/// The `type`, `enum`, `const`, `properties`, `required`, `additionalProperties`, `items`,
+/// `minimum`, `maximum`, `minLength`, `maxLength`, `minItems` and `maxItems` keywords are
+/// supported. Other keywords, such as references, are ignored.
+pub struct JsonSchema {
+    label: String,
+    schema: Value,
+}
+
+impl JsonSchema {
+    /// Create a schema requiring assertions with `label` to match the JSON Schema `schema`
+    pub fn new(label: &str, schema: Value) -> Self {
+        JsonSchema {
+            label: label.to_owned(),
+            schema,
+        }
+    }
+}
+
+impl AssertionSchema for JsonSchema {
+    fn label(&self) -> &str {
+        &self.label
+    }
+
+    fn validate(&self, data: &Value) -> Result<()> {
+        check_json_schema(&self.schema, data, "$").map_err(Error::AssertionSchemaMismatch)
+    }
+}
+
+// checks a value against a JSON Schema, returning a description of the first mismatch
+fn check_json_schema(schema: &Value, value: &Value, path: &str) -> std::result::Result<(), String> {
+    let schema = match schema {
+        Value::Bool(true) => return Ok(()),
+        Value::Bool(false) => return Err(format!("{} is not allowed", path)),
+        Value::Object(schema) => schema,
+        _ => return Ok(()),
+    };
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.iter().any(|t| is_json_type(value, t)) {
+            return Err(format!("{} is not of type {}", path, allowed.join(" or ")));
+        }
+    }
+
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if !values.contains(value) {
+            return Err(format!("{} is not one of the allowed values", path));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            return Err(format!("{} is not {}", path, expected));
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            for name in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(name) {
+                    return Err(format!("{}.{} is missing", path, name));
+                }
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (name, property) in object {
+                let property_path = format!("{}.{}", path, name);
+                match properties.and_then(|p| p.get(name)) {
+                    Some(property_schema) => {
+                        check_json_schema(property_schema, property, &property_path)?
+                    }
+                    None => {
+                        if let Some(additional) = schema.get("additionalProperties") {
+                            check_json_schema(additional, property, &property_path)?;
+                        }
+                    }
+                }
+            }
+        }
+        Value::Array(items) => {
+            check_count(schema, "minItems", "maxItems", items.len(), path)?;
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check_json_schema(item_schema, item, &format!("{}[{}]", path, index))?;
+                }
+            }
+        }
+        Value::String(s) => {
+            check_count(schema, "minLength", "maxLength", s.chars().count(), path)?;
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < minimum {
+                    return Err(format!("{} is less than {}", path, minimum));
+                }
+            }
+            if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > maximum {
+                    return Err(format!("{} is greater than {}", path, maximum));
+                }
+            }
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn check_count(
+    schema: &serde_json::Map<String, Value>,
+    min_keyword: &str,
+    max_keyword: &str,
+    count: usize,
+    path: &str,
+) -> std::result::Result<(), String> {
+    if let Some(min) = schema.get(min_keyword).and_then(Value::as_u64) {
+        if (count as u64) < min {
+            return Err(format!("{} is shorter than {}", path, min));
+        }
+    }
+    if let Some(max) = schema.get(max_keyword).and_then(Value::as_u64) {
+        if (count as u64) > max {
+            return Err(format!("{} is longer than {}", path, max));
+        }
+    }
+    Ok(())
+}
+
+fn is_json_type(value: &Value, json_type: &str) -> bool {
+    match json_type {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+/// The schemas of custom assertions known to the validator
+#[derive(Clone, Default)]
+pub struct AssertionRegistry {
+    schemas: Vec<Arc<dyn AssertionSchema>>,
+}
+
+impl AssertionRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a schema, replacing any registered for the same label
+    pub fn register(&mut self, schema: Arc<dyn AssertionSchema>) -> &mut Self {
+        self.schemas.retain(|s| s.label() != schema.label());
+        self.schemas.push(schema);
+        self
+    }
+
+    /// Find the schema for an assertion label, ignoring any version suffix
+    pub fn get(&self, label: &str) -> Option<&dyn AssertionSchema> {
+        let root = get_mutable_label(label).0;
+        self.schemas
+            .iter()
+            .find(|s| s.label() == root)
+            .map(|s| s.as_ref())
+    }
+
+    /// Returns true if no schemas are registered
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    /// Check an assertion of a manifest against its registered schema and return its data
+    /// as a typed value. Assertions without a registered schema are only deserialized.
+    pub fn decode<T: DeserializeOwned>(&self, assertion: &ManifestAssertion) -> Result<T> {
+        if let Some(schema) = self.get(&assertion.label) {
+            schema.validate(&assertion.data)?;
+        }
+        assertion.to_assertion()
+    }
+}
+
+impl std::fmt::Debug for AssertionRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.schemas.iter().map(|s| s.label()))
+            .finish()
+    }
+}
+
+impl PartialEq for AssertionRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.schemas.len() == other.schemas.len()
+            && self
+                .schemas
+                .iter()
+                .zip(other.schemas.iter())
+                .all(|(a, b)| a.label() == b.label())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Rating {
+        stars: u8,
+    }
+
+    #[test]
+    fn test_assertion_registry() {
+        let mut registry = AssertionRegistry::new();
+        registry
+            .register(Arc::new(TypedAssertionSchema::<Rating>::new(
+                "com.example.rating",
+            )))
+            .register(Arc::new(JsonSchema::new(
+                "com.example.review",
+                json!({
+                    "type": "object",
+                    "required": ["text"],
+                    "properties": {
+                        "text": { "type": "string", "maxLength": 10 },
+                        "tags": { "type": "array", "items": { "enum": ["good", "bad"] } }
+                    },
+                    "additionalProperties": false
+                }),
+            )));
+
+        let rating = registry.get("com.example.rating.v2").unwrap();
+        assert!(rating.validate(&json!({ "stars": 4 })).is_ok());
+        assert!(rating.validate(&json!({ "stars": "four" })).is_err());
+
+        let review = registry.get("com.example.review").unwrap();
+        assert!(review
+            .validate(&json!({ "text": "fine", "tags": ["good"] }))
+            .is_ok());
+        for invalid in [
+            json!({ "tags": ["good"] }),
+            json!({ "text": "far too long a review" }),
+            json!({ "text": "fine", "tags": ["ugly"] }),
+            json!({ "text": "fine", "extra": 1 }),
+        ] {
+            assert!(matches!(
+                review.validate(&invalid),
+                Err(Error::AssertionSchemaMismatch(_))
+            ));
+        }
+        assert!(registry.get("com.example.other").is_none());
+
+        let assertion =
+            ManifestAssertion::from_labeled_assertion("com.example.rating", &json!({ "stars": 5 }))
+                .unwrap();
+        let decoded: Rating = registry.decode(&assertion).unwrap();
+        assert_eq!(decoded.stars, 5);
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+
+use crate::{
+    assertion::{Assertion, AssertionBase, AssertionCbor},
+    assertions::labels,
+    error::{Error, Result},
+    utils::hash_utils::hash_by_alg,
+};
+
+const ASSERTION_CREATION_VERSION: usize = 1;
+
+/// The hash of a single member of a collection.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UriHashedDataMap {
+    /// Relative URI identifying the member within the collection.
+    pub uri: String,
+
+    /// Hash of the member's content.
+    #[serde(with = "serde_bytes")]
+    pub hash: Vec<u8>,
+
+    /// Size of the member's content in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+
+    /// Media type of the member.
+    #[serde(rename = "dc:format", skip_serializing_if = "Option::is_none")]
+    pub dc_format: Option<String>,
+}
+
+/// A collection data hash binds a manifest to a set of separately hashed members,
+/// such as the body parts and attachments of an email message or the entries of a
+/// ZIP archive.
+///
+/// Each member is hashed on its own, so the binding survives changes to how the
+/// collection is packaged as long as the content of every member is unchanged.
+/// For ZIP archives the central directory is hashed as well, binding the metadata of
+/// the entries.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CollectionHash {
+    pub uris: Vec<UriHashedDataMap>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+
+    /// Hash of the central directory records of a ZIP archive, other than the one of the manifest
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zip_central_directory_hash: Option<ByteBuf>,
+}
+
+impl CollectionHash {
+    /// Label prefix for a collection data hash assertion.
+    ///
+    /// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_collection_data_hash>.
+    pub const LABEL: &'static str = labels::COLLECTION_HASH;
+
+    /// Creates an empty collection hash using the given hash algorithm.
+    pub fn new(alg: &str) -> Self {
+        CollectionHash {
+            uris: Vec::new(),
+            alg: Some(alg.to_string()),
+            zip_central_directory_hash: None,
+        }
+    }
+
+    fn alg_or_default(&self, alg: Option<&str>) -> String {
+        alg.map(str::to_owned)
+            .or_else(|| self.alg.clone())
+            .unwrap_or_else(|| "sha256".to_string())
+    }
+
+    /// Hashes a member of the collection and adds it under `uri`.
+    pub fn add_part(&mut self, uri: &str, format: Option<&str>, data: &[u8]) -> Result<()> {
+        if self.uris.iter().any(|u| u.uri == uri) {
+            return Err(Error::BadParam(format!(
+                "collection already contains {}",
+                uri
+            )));
+        }
+
+        let hash = hash_by_alg(&self.alg_or_default(None), data, None);
+        if hash.is_empty() {
+            return Err(Error::UnsupportedType);
+        }
+
+        self.uris.push(UriHashedDataMap {
+            uri: uri.to_string(),
+            hash,
+            size: Some(data.len() as u64),
+            dc_format: format.map(str::to_owned),
+        });
+        Ok(())
+    }
+
+    /// Hashes the central directory records of a ZIP archive collection.
+    pub fn set_zip_central_directory(&mut self, records: &[u8]) -> Result<()> {
+        let hash = hash_by_alg(&self.alg_or_default(None), records, None);
+        if hash.is_empty() {
+            return Err(Error::UnsupportedType);
+        }
+        self.zip_central_directory_hash = Some(ByteBuf::from(hash));
+        Ok(())
+    }
+
+    /// Verifies the central directory records of a ZIP archive against the recorded hash.
+    pub fn verify_zip_central_directory(&self, records: &[u8], alg: Option<&str>) -> Result<()> {
+        let expected = self
+            .zip_central_directory_hash
+            .as_ref()
+            .ok_or_else(|| Error::HashMismatch("central directory is not hashed".to_string()))?;
+        if hash_by_alg(&self.alg_or_default(alg), records, None) == expected.as_slice() {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch(
+                "central directory does not match".to_string(),
+            ))
+        }
+    }
+
+    /// Verifies the members of a collection against the recorded hashes.
+    ///
+    /// Every recorded member must be present with matching content and the collection
+    /// must not contain members that are not covered by the hash. The error lists every
+    /// member that is changed, missing or not covered.
+    pub fn verify_parts<'a, I>(&self, parts: I, alg: Option<&str>) -> Result<()>
+    where
+        I: IntoIterator<Item = (&'a str, &'a [u8])>,
+    {
+        let alg = self.alg_or_default(alg);
+
+        // report every member that diverges, to locate the changes
+        let mut changed = Vec::new();
+        let mut extra = Vec::new();
+        let mut found = HashSet::new();
+        for (uri, data) in parts {
+            match self.uris.iter().find(|u| u.uri == uri) {
+                Some(entry) => {
+                    found.insert(uri);
+                    if hash_by_alg(&alg, data, None) != entry.hash {
+                        changed.push(match entry.size {
+                            Some(size) if size != data.len() as u64 => {
+                                format!("{} ({} bytes, was {})", uri, data.len(), size)
+                            }
+                            _ => uri.to_owned(),
+                        });
+                    }
+                }
+                None => extra.push(uri),
+            }
+        }
+        let missing: Vec<&str> = self
+            .uris
+            .iter()
+            .map(|u| u.uri.as_str())
+            .filter(|uri| !found.contains(uri))
+            .collect();
+
+        let mut failures = Vec::new();
+        if !changed.is_empty() {
+            failures.push(format!("{} do not match", changed.join(", ")));
+        }
+        if !missing.is_empty() {
+            failures.push(format!("{} are missing", missing.join(", ")));
+        }
+        if !extra.is_empty() {
+            failures.push(format!("{} are not in the collection", extra.join(", ")));
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch(failures.join(", ")))
+        }
+    }
+
+    /// Create a new instance from Assertion
+    pub fn from_assertion(assertion: &Assertion) -> Result<Self> {
+        assertion.check_version_from_label(ASSERTION_CREATION_VERSION)?;
+        Self::from_cbor_assertion(assertion)
+    }
+}
+
+impl AssertionCbor for CollectionHash {}
+
+impl AssertionBase for CollectionHash {
+    const LABEL: &'static str = Self::LABEL;
+    const VERSION: Option<usize> = Some(ASSERTION_CREATION_VERSION);
+
+    fn to_assertion(&self) -> Result<Assertion> {
+        Self::to_cbor_assertion(self)
+    }
+
+    fn from_assertion(assertion: &Assertion) -> Result<Self> {
+        Self::from_cbor_assertion(assertion)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_collection_hash() {
+        let mut collection = CollectionHash::new("sha256");
+        collection
+            .add_part("1", Some("text/plain"), b"hello")
+            .unwrap();
+        collection
+            .add_part("2", Some("image/png"), b"not really a png")
+            .unwrap();
+        assert!(collection.add_part("1", None, b"again").is_err());
+
+        let assertion = collection.to_assertion().unwrap();
+        let restored = CollectionHash::from_assertion(&assertion).unwrap();
+        assert_eq!(restored, collection);
+
+        let parts: Vec<(&str, &[u8])> = vec![("1", b"hello"), ("2", b"not really a png")];
+        restored.verify_parts(parts, None).unwrap();
+
+        // changed, missing and extra members are all detected
+        let changed: Vec<(&str, &[u8])> = vec![("1", b"hello!"), ("2", b"not really a png")];
+        assert!(matches!(
+            restored.verify_parts(changed, None),
+            Err(Error::HashMismatch(_))
+        ));
+        let missing: Vec<(&str, &[u8])> = vec![("1", b"hello")];
+        assert!(restored.verify_parts(missing, None).is_err());
+        let extra: Vec<(&str, &[u8])> =
+            vec![("1", b"hello"), ("2", b"not really a png"), ("3", b"")];
+        assert!(restored.verify_parts(extra, None).is_err());
+
+        // a ZIP central directory is bound along with the members
+        assert!(restored
+            .verify_zip_central_directory(b"records", None)
+            .is_err());
+        let mut zip = CollectionHash::new("sha256");
+        zip.set_zip_central_directory(b"records").unwrap();
+        let zip = CollectionHash::from_assertion(&zip.to_assertion().unwrap()).unwrap();
+        zip.verify_zip_central_directory(b"records", None).unwrap();
+        assert!(zip.verify_zip_central_directory(b"changed", None).is_err());
+
+        // every divergent member is named
+        let divergent: Vec<(&str, &[u8])> = vec![("1", b"hello!"), ("3", b"")];
+        let err = restored.verify_parts(divergent, None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "hash verification( 1 (6 bytes, was 5) do not match, 2 are missing, 3 are not in the collection )"
+        );
+    }
+}
@@ -11,7 +11,7 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::{fs, path::*};
+use std::{fmt, fs, ops::Range, path::*};
 
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
@@ -21,11 +21,17 @@ use crate::{
     assertions::labels,
     cbor_types::UriT,
     error::{wrap_io_err, Error, Result},
-    utils::hash_utils::{hash_by_alg, verify_by_alg, Exclusion},
+    metrics::metrics,
+    progress::ProgressMonitor,
+    utils::hash_utils::{hash_file_by_alg, verify_by_alg, Exclusion, HashingOptions},
 };
 
 const ASSERTION_CREATION_VERSION: usize = 1;
 
+// the description box of the C2PA manifest store, "jumd" followed by its UUID
+const MANIFEST_STORE_DESCRIPTION: &[u8] =
+    b"jumdc2pa\x00\x11\x00\x10\x80\x00\x00\xaa\x00\x38\x9b\x71";
+
 /// Helper class to create DataHash assertion
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct DataHash {
@@ -99,7 +105,27 @@ impl DataHash {
 
     /// generate the hash value for the Asset using the range from the DataHash
     pub fn gen_hash(&mut self, asset_path: &Path) -> Result<()> {
-        self.hash = self.hash_from_asset(asset_path)?;
+        self.gen_hash_with_options(asset_path, &HashingOptions::default())
+    }
+
+    /// generate the hash value for the Asset using the range from the DataHash
+    /// and the supplied [HashingOptions]
+    pub fn gen_hash_with_options(
+        &mut self,
+        asset_path: &Path,
+        options: &HashingOptions,
+    ) -> Result<()> {
+        self.gen_hash_with_progress(asset_path, options, &ProgressMonitor::none())
+    }
+
+    // generate the hash value reporting progress to the monitor
+    pub(crate) fn gen_hash_with_progress(
+        &mut self,
+        asset_path: &Path,
+        options: &HashingOptions,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        self.hash = self.hash_from_asset(asset_path, options, monitor)?;
         self.path = PathBuf::from(asset_path);
         Ok(())
     }
@@ -142,25 +168,24 @@ impl DataHash {
 
     /// generate the asset hash from a file asset using the constructed
     /// start and length values
-    fn hash_from_asset(&mut self, asset_path: &Path) -> Result<Vec<u8>> {
+    fn hash_from_asset(
+        &mut self,
+        asset_path: &Path,
+        options: &HashingOptions,
+        monitor: &ProgressMonitor,
+    ) -> Result<Vec<u8>> {
         if self.is_remote_hash() {
             return Err(Error::BadParam(
                 "asset hash is remote, not yet supported".to_owned(),
             ));
         }
 
-        let data = fs::read(asset_path).map_err(wrap_io_err)?;
-
         let alg = match self.alg {
             Some(ref a) => a.clone(),
             None => "sha256".to_string(),
         };
 
-        // sort the exclusions
-        let hash = match self.exclusions {
-            Some(ref e) => hash_by_alg(&alg, &data, Some(e.clone())),
-            None => hash_by_alg(&alg, &data, None),
-        };
+        let hash = hash_file_by_alg(&alg, asset_path, self.exclusions.clone(), options, monitor)?;
 
         if hash.is_empty() {
             Err(Error::BadParam("could not generate data hash".to_string()))
@@ -171,6 +196,114 @@ impl DataHash {
 
     // verify data using currently set algorithm or default alg is none currently set
     pub fn verify_in_memory_hash(&self, data: &[u8], alg: Option<String>) -> Result<()> {
+        self.verify_with_exclusions(data, alg, self.exclusions.as_ref().cloned())
+    }
+
+    /// Verify against an asset whose manifest store was stripped. The excluded bytes are
+    /// no longer in `data`, so each exclusion shifts back by the bytes removed before it
+    /// and is empty.
+    pub(crate) fn verify_stripped_hash(&self, data: &[u8], alg: Option<String>) -> Result<()> {
+        let mut excluded: Vec<&Exclusion> = self.exclusions.iter().flatten().collect();
+        excluded.sort_by_key(|e| e.start());
+
+        let mut removed = 0usize;
+        let mut exclusions = Vec::new();
+        for e in excluded {
+            exclusions.push(Exclusion::new(e.start().saturating_sub(removed), 0));
+            removed = removed.saturating_add(e.length());
+        }
+        self.verify_with_exclusions(data, alg, Some(exclusions))
+    }
+
+    fn verify_with_exclusions(
+        &self,
+        data: &[u8],
+        alg: Option<String>,
+        exclusions: Option<Vec<Exclusion>>,
+    ) -> Result<()> {
+        if self.is_remote_hash() {
+            return Err(Error::BadParam("asset hash is remote".to_owned()));
+        }
+
+        let curr_alg = match alg {
+            Some(a) => a,
+            None => match self.alg {
+                Some(ref a) => a.clone(),
+                None => "sha256".to_string(),
+            },
+        };
+
+        let start = instant::Instant::now();
+        let verified = verify_by_alg(&curr_alg, &self.hash, data, exclusions);
+        metrics().hash_completed(data.len() as u64, start.elapsed());
+
+        if verified {
+            Ok(())
+        } else {
+            Err(Error::HashMismatch(format!(
+                "Hashes do not match, {}",
+                self.mismatch(data)
+            )))
+        }
+    }
+
+    /// Describes how `data` departs from the layout of the asset the hash was made for,
+    /// to explain a mismatch
+    pub fn mismatch(&self, data: &[u8]) -> DataHashMismatch {
+        let mut excluded: Vec<Range<usize>> = self
+            .exclusions
+            .iter()
+            .flatten()
+            .map(|e| e.start()..e.start().saturating_add(e.length()))
+            .collect();
+        excluded.sort_by_key(|r| r.start);
+
+        // the hashed ranges are what is left of the asset between the exclusions
+        let mut hashed = Vec::new();
+        let mut pos = 0;
+        for range in &excluded {
+            if range.start > pos && pos < data.len() {
+                hashed.push(pos..range.start.min(data.len()));
+            }
+            pos = pos.max(range.end);
+        }
+        if pos < data.len() {
+            hashed.push(pos..data.len());
+        }
+
+        // the manifest store box starts with its size and type, then the size of
+        // its description box
+        let manifest_offset = twoway::find_bytes(data, MANIFEST_STORE_DESCRIPTION)
+            .and_then(|pos| pos.checked_sub(12))
+            .filter(|offset| !excluded.iter().any(|r| r.contains(offset)));
+
+        let boxes = bmff_boxes(data)
+            .into_iter()
+            .filter(|b| {
+                hashed
+                    .iter()
+                    .any(|r| r.start < b.range.end && b.range.start < r.end)
+            })
+            .collect();
+
+        DataHashMismatch {
+            asset_len: data.len(),
+            excluded,
+            hashed,
+            boxes,
+            manifest_offset,
+        }
+    }
+
+    // verify the hash by streaming the asset file rather than reading it into memory
+    #[cfg(feature = "file_io")]
+    pub(crate) fn verify_stream_hash(
+        &self,
+        asset_path: &Path,
+        alg: Option<String>,
+        options: &HashingOptions,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
         if self.is_remote_hash() {
             return Err(Error::BadParam("asset hash is remote".to_owned()));
         }
@@ -183,9 +316,15 @@ impl DataHash {
             },
         };
 
-        let exclusions = self.exclusions.as_ref().cloned();
+        let hash = hash_file_by_alg(
+            &curr_alg,
+            asset_path,
+            self.exclusions.clone(),
+            options,
+            monitor,
+        )?;
 
-        if verify_by_alg(&curr_alg, &self.hash, data, exclusions) {
+        if !hash.is_empty() && hash == self.hash {
             Ok(())
         } else {
             Err(Error::HashMismatch("Hashes do not match".to_owned()))
@@ -206,6 +345,134 @@ impl DataHash {
     }
 }
 
+/// Where an asset departs from the layout of the asset a data hash was made for.
+///
+/// A data hash is a single digest, so a change to the hashed bytes can only be located
+/// within the hashed ranges. Changes to the layout of the asset, such as bytes inserted
+/// before the manifest store or a truncated asset, are located by their offsets.
+///
+/// For BMFF assets the hashed ranges are also located by the top-level boxes holding them.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DataHashMismatch {
+    /// The size of the asset
+    pub asset_len: usize,
+    /// The excluded byte ranges, in order
+    pub excluded: Vec<Range<usize>>,
+    /// The hashed byte ranges, one of which was changed if the layout is intact
+    pub hashed: Vec<Range<usize>>,
+    /// The top-level boxes holding hashed bytes, if the asset is a BMFF asset
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub boxes: Vec<AssetBox>,
+    /// The offset of the manifest store if it is not in an excluded range
+    pub manifest_offset: Option<usize>,
+}
+
+impl DataHashMismatch {
+    /// The excluded ranges that run past the end of the asset
+    pub fn out_of_bounds(&self) -> impl Iterator<Item = &Range<usize>> {
+        self.excluded.iter().filter(move |r| r.end > self.asset_len)
+    }
+
+    /// Returns true if the asset still has the layout the hash was made for, so the
+    /// change is in the hashed bytes
+    pub fn layout_intact(&self) -> bool {
+        self.manifest_offset.is_none() && self.out_of_bounds().next().is_none()
+    }
+}
+
+impl fmt::Display for DataHashMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "asset of {} bytes, hashed [{}], excluded [{}]",
+            self.asset_len,
+            format_ranges(self.hashed.iter()),
+            format_ranges(self.excluded.iter())
+        )?;
+        if self.out_of_bounds().next().is_some() {
+            write!(
+                f,
+                ", exclusions past the end of the asset [{}]",
+                format_ranges(self.out_of_bounds())
+            )?;
+        }
+        if let Some(offset) = self.manifest_offset {
+            write!(
+                f,
+                ", the manifest store at {} is outside the exclusions, bytes were added or removed before it",
+                offset
+            )?;
+        }
+        if self.layout_intact() {
+            write!(f, ", the hashed ranges were changed")?;
+            if !self.boxes.is_empty() {
+                let boxes: Vec<String> = self
+                    .boxes
+                    .iter()
+                    .map(|b| format!("{} {}..{}", b.box_type, b.range.start, b.range.end))
+                    .collect();
+                write!(f, " in boxes [{}]", boxes.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A top-level box of a BMFF asset
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AssetBox {
+    /// The four character type of the box
+    pub box_type: String,
+    /// The byte range of the box, including its header
+    pub range: Range<usize>,
+}
+
+// lists the top-level boxes of a BMFF asset, which starts with a file type box,
+// stopping at the first box that does not fit in the asset
+fn bmff_boxes(data: &[u8]) -> Vec<AssetBox> {
+    let mut boxes = Vec::new();
+    if data.get(4..8) != Some(b"ftyp") {
+        return boxes;
+    }
+
+    let mut pos = 0;
+    while let Some(header) = data.get(pos..pos + 8) {
+        let size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let size = match size {
+            // the box runs to the end of the asset
+            0 => (data.len() - pos) as u64,
+            // the size follows the type as a 64-bit value
+            1 => match data.get(pos + 8..pos + 16) {
+                Some(large) => {
+                    let mut bytes = [0u8; 8];
+                    bytes.copy_from_slice(large);
+                    u64::from_be_bytes(bytes)
+                }
+                None => break,
+            },
+            size => size,
+        };
+        if size < 8 || size > (data.len() - pos) as u64 {
+            break;
+        }
+        let end = pos + size as usize;
+
+        boxes.push(AssetBox {
+            box_type: String::from_utf8_lossy(&header[4..8]).into_owned(),
+            range: pos..end,
+        });
+        pos = end;
+    }
+    boxes
+}
+
+fn format_ranges<'a>(ranges: impl Iterator<Item = &'a Range<usize>>) -> String {
+    ranges
+        .map(|r| format!("{}..{}", r.start, r.end))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl AssertionCbor for DataHash {}
 
 impl AssertionBase for DataHash {
@@ -313,4 +580,107 @@ pub mod tests {
         assert_eq!(orig_bytes, assertion_from_binary.data());
         println!("Decoded binary matches");
     }
+
+    #[test]
+    fn test_threaded_hash_matches() {
+        let ap = fixture_path("earth_apollo17.jpg");
+
+        let mut data_hash = DataHash::new("Some data", "sha256", None);
+        data_hash.add_exclusion(Exclusion::new(0x2000, 0x1000));
+        data_hash.gen_hash(&ap).unwrap();
+
+        let options = HashingOptions {
+            threads: 4,
+            chunk_size: 1000,
+        };
+        let mut threaded_hash = DataHash::new("Some data", "sha256", None);
+        threaded_hash.add_exclusion(Exclusion::new(0x2000, 0x1000));
+        threaded_hash.gen_hash_with_options(&ap, &options).unwrap();
+
+        assert_eq!(data_hash.hash, threaded_hash.hash);
+        threaded_hash.verify_hash(&ap).unwrap();
+    }
+
+    #[test]
+    fn test_hash_mismatch() {
+        let ap = fixture_path("earth_apollo17.jpg");
+        let mut data = fs::read(&ap).unwrap();
+
+        let mut data_hash = DataHash::new("Some data", "sha256", None);
+        data_hash.add_exclusion(Exclusion::new(0x2000, 0x1000));
+        data_hash.gen_hash(&ap).unwrap();
+
+        // a change to the hashed bytes leaves the layout intact
+        data[0x100] ^= 0xff;
+        let mismatch = data_hash.mismatch(&data);
+        assert!(mismatch.layout_intact());
+        assert_eq!(mismatch.hashed, vec![0..0x2000, 0x3000..data.len()]);
+        assert!(matches!(
+            data_hash.verify_in_memory_hash(&data, None),
+            Err(Error::HashMismatch(desc)) if desc.ends_with("the hashed ranges were changed")
+        ));
+
+        // a manifest store outside the exclusions is located
+        data[0x100..0x100 + MANIFEST_STORE_DESCRIPTION.len()]
+            .copy_from_slice(MANIFEST_STORE_DESCRIPTION);
+        let mismatch = data_hash.mismatch(&data);
+        assert_eq!(mismatch.manifest_offset, Some(0x100 - 12));
+        assert!(!mismatch.layout_intact());
+
+        // so is a truncated asset
+        data.truncate(0x2800);
+        let mismatch = data_hash.mismatch(&data);
+        assert_eq!(mismatch.out_of_bounds().count(), 1);
+        assert_eq!(mismatch.hashed, vec![0..0x2000]);
+        assert!(mismatch.boxes.is_empty());
+    }
+
+    #[test]
+    fn test_hash_mismatch_bmff_boxes() {
+        let mut data = Vec::new();
+        for (box_type, size) in [(b"ftyp", 24u32), (b"uuid", 0x100), (b"mdat", 0x200)] {
+            data.extend_from_slice(&size.to_be_bytes());
+            data.extend_from_slice(box_type);
+            data.resize(data.len() + size as usize - 8, 0);
+        }
+
+        // the manifest store is in the uuid box
+        let mut data_hash = DataHash::new("Some data", "sha256", None);
+        data_hash.add_exclusion(Exclusion::new(24, 0x100));
+
+        let mismatch = data_hash.mismatch(&data);
+        let box_types: Vec<&str> = mismatch.boxes.iter().map(|b| b.box_type.as_str()).collect();
+        assert_eq!(box_types, vec!["ftyp", "mdat"]);
+        assert_eq!(mismatch.boxes[1].range, 0x118..0x318);
+        assert!(mismatch
+            .to_string()
+            .ends_with("in boxes [ftyp 0..24, mdat 280..792]"));
+
+        // the boxes are carried as structured log item fields
+        let json = serde_json::to_value(&mismatch).unwrap();
+        assert_eq!(json["boxes"][1]["box_type"], "mdat");
+        assert_eq!(json["boxes"][1]["range"]["start"], 0x118);
+    }
+
+    #[test]
+    fn test_cancelled_hash() {
+        use crate::progress::{CancellationToken, NoProgress};
+
+        let ap = fixture_path("earth_apollo17.jpg");
+        let token = CancellationToken::new();
+        token.cancel();
+        let monitor = ProgressMonitor::new(&NoProgress, &token);
+
+        for threads in [1, 4] {
+            let options = HashingOptions {
+                threads,
+                chunk_size: 1000,
+            };
+            let mut data_hash = DataHash::new("Some data", "sha256", None);
+            assert!(matches!(
+                data_hash.gen_hash_with_progress(&ap, &options, &monitor),
+                Err(Error::OperationCancelled)
+            ));
+        }
+    }
 }
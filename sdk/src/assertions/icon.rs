@@ -0,0 +1,97 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use crate::{
+    assertion::{Assertion, AssertionBase, AssertionData, AssertionDecodeError},
+    assertions::labels,
+    error::{Error, Result},
+};
+
+/// An icon of the claim generator, shown by credential viewers
+pub struct Icon {
+    pub data: Vec<u8>,
+    pub label: String,
+    pub content_type: String,
+}
+
+impl Icon {
+    /// Create an icon assertion, only SVG and PNG icons are supported
+    pub fn new(content_type: &str, data: Vec<u8>) -> Result<Self> {
+        let extension = Self::extension(content_type).ok_or(Error::UnsupportedType)?;
+        Ok(Icon {
+            data,
+            label: format!("{}.{}", labels::ICON, extension),
+            content_type: content_type.to_owned(),
+        })
+    }
+
+    fn extension(content_type: &str) -> Option<&'static str> {
+        match content_type {
+            "image/svg+xml" => Some("svg"),
+            "image/png" => Some("png"),
+            _ => None,
+        }
+    }
+
+    // the content type of the icon stored in an assertion with this label
+    fn content_type_of(label: &str) -> String {
+        match label.trim_start_matches(labels::ICON).split('_').next() {
+            Some(".svg") => "image/svg+xml",
+            Some(".png") => "image/png",
+            _ => "application/octet-stream",
+        }
+        .to_owned()
+    }
+}
+
+impl AssertionBase for Icon {
+    const LABEL: &'static str = labels::ICON;
+
+    fn to_assertion(&self) -> Result<Assertion> {
+        let data = AssertionData::Binary(self.data.to_owned());
+        Ok(Assertion::new(&self.label, None, data).set_content_type(&self.content_type))
+    }
+
+    fn from_assertion(assertion: &Assertion) -> Result<Icon> {
+        match assertion.decode_data() {
+            AssertionData::Binary(data) => Ok(Self {
+                data: data.to_owned(),
+                label: assertion.label(),
+                content_type: Self::content_type_of(&assertion.label()),
+            }),
+            ad => Err(AssertionDecodeError::from_assertion_unexpected_data_type(
+                assertion, ad, "binary",
+            )
+            .into()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn assertion_icon() {
+        let original = Icon::new("image/svg+xml", b"<svg/>".to_vec()).unwrap();
+        let assertion = original.to_assertion().unwrap();
+        assert_eq!(assertion.label(), "c2pa.icon.svg");
+        let result = Icon::from_assertion(&assertion).unwrap();
+        assert_eq!(result.content_type, "image/svg+xml");
+        assert_eq!(result.data, original.data);
+
+        assert!(Icon::new("image/gif", Vec::new()).is_err());
+    }
+}
@@ -39,6 +39,11 @@ pub const DATA_HASH: &str = "c2pa.hash.data";
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_bmff_based_hash>.
 pub const BMFF_HASH: &str = "c2pa.hash.bmff";
 
+/// Label prefix for a collection data hash assertion.
+///
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_collection_data_hash>.
+pub const COLLECTION_HASH: &str = "c2pa.hash.collection.data";
+
 /// Label prefix for a soft binding assertion.
 ///
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_soft_binding_2>.
@@ -49,6 +54,9 @@ pub const SOFT_BINDING: &str = "c2pa.soft-binding";
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_cloud_data>.
 pub const CLOUD_DATA: &str = "c2pa.cloud-data";
 
+/// Label prefix for a claim generator icon assertion.
+pub const ICON: &str = "c2pa.icon";
+
 /// Label prefix for a thumbnail assertion.
 ///
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_thumbnail>.
@@ -94,6 +102,11 @@ pub const ACTIONS: &str = "c2pa.actions";
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_ingredient>.
 pub const INGREDIENT: &str = "c2pa.ingredient";
 
+/// Label prefix for a training and data mining assertion.
+///
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_training_and_data_mining>.
+pub const TRAINING_MINING: &str = "c2pa.training-mining";
+
 /// Label prefix for a depthmap assertion.
 ///
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_depthmap>.
@@ -14,6 +14,7 @@
 use crate::{
     assertion::{Assertion, AssertionBase, AssertionCbor},
     assertions::labels,
+    assertions::RegionOfInterest,
     error::Result,
     hashed_uri::HashedUri,
 };
@@ -27,6 +28,7 @@ const ASSERTION_CREATION_VERSION: usize = 1;
 
 /// The Metadata structure can be used as part of other assertions or on its own to reference others
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct Metadata {
     #[serde(rename = "reviewRatings", skip_serializing_if = "Option::is_none")]
     reviews: Option<Vec<ReviewRating>>,
@@ -36,6 +38,8 @@ pub struct Metadata {
     reference: Option<HashedUri>,
     #[serde(skip_serializing_if = "Option::is_none")]
     data_source: Option<DataSource>,
+    #[serde(rename = "regionOfInterest", skip_serializing_if = "Option::is_none")]
+    region_of_interest: Option<RegionOfInterest>,
     #[serde(flatten)]
     other: HashMap<String, Value>,
 }
@@ -52,6 +56,7 @@ impl Metadata {
             date_time: Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
             reference: None,
             data_source: None,
+            region_of_interest: None,
             other: HashMap::new(),
         }
     }
@@ -71,6 +76,11 @@ impl Metadata {
         self.data_source.as_ref()
     }
 
+    /// Returns the [`RegionOfInterest`] of the asset this metadata applies to, if set.
+    pub fn region_of_interest(&self) -> Option<&RegionOfInterest> {
+        self.region_of_interest.as_ref()
+    }
+
     /// Adds a [`ReviewRating`] associated with the assertion.
     pub fn add_review(mut self, review: ReviewRating) -> Self {
         match &mut self.reviews {
@@ -95,7 +105,6 @@ impl Metadata {
     }
 
     /// Sets a [`HashedUri`] reference to another assertion to which this metadata applies.
-    #[cfg(test)] // only referenced from test code
     pub(crate) fn set_reference(mut self, reference: HashedUri) -> Self {
         self.reference = Some(reference);
         self
@@ -107,6 +116,12 @@ impl Metadata {
         self
     }
 
+    /// Sets the [`RegionOfInterest`] of the asset this metadata applies to.
+    pub fn set_region_of_interest(mut self, region: RegionOfInterest) -> Self {
+        self.region_of_interest = Some(region);
+        self
+    }
+
     /// Adds an additional key / value pair.
     pub fn insert(&mut self, key: &str, value: Value) -> &mut Self {
         self.other.insert(key.to_string(), value);
@@ -117,6 +132,22 @@ impl Metadata {
     pub fn get(&self, key: &str) -> Option<&Value> {
         self.other.get(key)
     }
+
+    // returns the referenced assertion uri and date-time if this metadata only
+    // records when another assertion was created
+    pub(crate) fn creation_time_of(&self) -> Option<(String, &str)> {
+        match (self.reference.as_ref(), self.date_time.as_deref()) {
+            (Some(reference), Some(date_time))
+                if self.reviews.is_none()
+                    && self.data_source.is_none()
+                    && self.region_of_interest.is_none()
+                    && self.other.is_empty() =>
+            {
+                Some((reference.url(), date_time))
+            }
+            _ => None,
+        }
+    }
 }
 
 impl Default for Metadata {
@@ -155,6 +186,7 @@ pub mod c2pa_source {
 
 /// A description of the source for assertion data
 #[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct DataSource {
     /// A value from among the enumerated list indicating the source of the assertion.
     #[serde(rename = "type")]
@@ -193,6 +225,7 @@ impl DataSource {
 
 /// Identifies a person responsible for an action.
 #[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct Actor {
     /// An identifier for a human actor, used when the "type" is `humanEntry.identified`.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -241,6 +274,7 @@ pub enum ReviewCode {
 ///
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_claim_review>.
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct ReviewRating {
     pub explanation: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -16,12 +16,24 @@
 mod actions;
 pub use actions::*;
 
+mod assertion_schema;
+pub use assertion_schema::{AssertionRegistry, AssertionSchema, JsonSchema, TypedAssertionSchema};
+
 #[allow(dead_code)] // will become public later
 mod data_hash;
 pub(crate) use data_hash::DataHash;
+pub use data_hash::{AssetBox, DataHashMismatch};
+
+mod collection_hash;
+pub use collection_hash::{CollectionHash, UriHashedDataMap};
 
 mod creative_work;
 pub use creative_work::CreativeWork;
+
+#[allow(dead_code)] // will become public later
+mod icon;
+pub(crate) use icon::Icon;
+
 #[allow(dead_code)] // will become public later
 mod ingredient;
 pub(crate) use ingredient::{Ingredient, Relationship};
@@ -31,12 +43,24 @@ pub mod labels;
 mod metadata;
 pub use metadata::{Actor, DataSource, Metadata, ReviewRating, *};
 
+mod region_of_interest;
+pub use region_of_interest::*;
+
 mod schema_org;
 pub use schema_org::{SchemaDotOrg, SchemaDotOrgPerson};
 
+mod soft_binding;
+pub use soft_binding::{
+    SoftBinding, SoftBindingAlgorithm, SoftBindingBlock, SoftBindingRegistry, SoftBindingScope,
+    SoftBindingTimespan,
+};
+
 mod thumbnail;
 pub(crate) use thumbnail::Thumbnail;
 
+mod training_mining;
+pub use training_mining::{c2pa_training, TrainingMining, TrainingMiningEntry, TrainingMiningUse};
+
 mod user;
 pub use user::User;
 
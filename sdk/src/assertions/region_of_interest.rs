@@ -0,0 +1,476 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Regions of interest, the parts of an asset an action or metadata applies to.
+//!
+//! See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_regions_of_interest>.
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of part of an asset a [`Range`] describes
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum RangeType {
+    /// An area of an image or video frame, described by a [`Shape`]
+    Spatial,
+    /// A span of time of audio or video, described by a [`Time`]
+    Temporal,
+    /// A span of video frames, described by a [`Frame`]
+    Frame,
+    /// A span of text, described by a [`Text`]
+    Textual,
+    /// A part of the asset identified by the asset format, described by an [`Item`]
+    Identified,
+}
+
+/// The geometry of a [`Shape`]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ShapeType {
+    /// A rectangle with its top left corner at the origin
+    Rectangle,
+    /// A circle with the origin as its center and the width as its diameter
+    Circle,
+    /// A polygon given by its vertices
+    Polygon,
+}
+
+/// The unit of the coordinates and sizes of a [`Shape`]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum UnitType {
+    /// Pixels of the image or video frame
+    Pixel,
+    /// Percent of the width or height of the image or video frame
+    Percent,
+}
+
+impl Default for UnitType {
+    fn default() -> Self {
+        UnitType::Pixel
+    }
+}
+
+/// A point of a [`Shape`]
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Coordinate {
+    /// The horizontal position, from the left edge
+    pub x: f64,
+    /// The vertical position, from the top edge
+    pub y: f64,
+}
+
+impl Coordinate {
+    /// Create a point at the given position
+    pub fn new(x: f64, y: f64) -> Self {
+        Coordinate { x, y }
+    }
+}
+
+/// An area of an image or video frame
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Shape {
+    /// The geometry of the shape
+    #[serde(rename = "type")]
+    pub shape_type: ShapeType,
+    /// The unit of the coordinates and sizes
+    pub unit: UnitType,
+    /// The top left corner of a rectangle or the center of a circle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<Coordinate>,
+    /// The width of a rectangle or the diameter of a circle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<f64>,
+    /// The height of a rectangle
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<f64>,
+    /// Whether the region is inside the shape, `true` when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inside: Option<bool>,
+    /// The vertices of a polygon
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertices: Option<Vec<Coordinate>>,
+}
+
+impl Shape {
+    /// Create a rectangle from its top left corner and size
+    pub fn rectangle(unit: UnitType, origin: Coordinate, width: f64, height: f64) -> Self {
+        Shape {
+            shape_type: ShapeType::Rectangle,
+            unit,
+            origin: Some(origin),
+            width: Some(width),
+            height: Some(height),
+            inside: None,
+            vertices: None,
+        }
+    }
+
+    /// Create a circle from its center and diameter
+    pub fn circle(unit: UnitType, center: Coordinate, diameter: f64) -> Self {
+        Shape {
+            shape_type: ShapeType::Circle,
+            unit,
+            origin: Some(center),
+            width: Some(diameter),
+            height: None,
+            inside: None,
+            vertices: None,
+        }
+    }
+
+    /// Create a polygon from its vertices
+    pub fn polygon(unit: UnitType, vertices: Vec<Coordinate>) -> Self {
+        Shape {
+            shape_type: ShapeType::Polygon,
+            unit,
+            origin: None,
+            width: None,
+            height: None,
+            inside: None,
+            vertices: Some(vertices),
+        }
+    }
+
+    /// Set whether the region is inside or outside the shape
+    pub fn set_inside(mut self, inside: bool) -> Self {
+        self.inside = Some(inside);
+        self
+    }
+}
+
+/// The format of the start and end of a [`Time`]
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum TimeType {
+    /// Normal Play Time, as in RFC 2326, such as `12.5` or `1:02:03.5`
+    Npt,
+}
+
+impl Default for TimeType {
+    fn default() -> Self {
+        TimeType::Npt
+    }
+}
+
+/// A span of time of audio or video
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Time {
+    /// The format of the start and end
+    #[serde(rename = "type", default)]
+    pub time_type: TimeType,
+    /// The start of the span, the start of the asset when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    /// The end of the span, the end of the asset when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+}
+
+impl Time {
+    /// Create a span between two Normal Play Times
+    pub fn new(start: Option<&str>, end: Option<&str>) -> Self {
+        Time {
+            time_type: TimeType::Npt,
+            start: start.map(str::to_owned),
+            end: end.map(str::to_owned),
+        }
+    }
+}
+
+/// A span of video frames
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Frame {
+    /// The first frame, the first frame of the asset when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    /// The last frame, the last frame of the asset when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+}
+
+/// Selects a position in text, by a fragment identifier and an optional character range
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct TextSelector {
+    /// A fragment identifier of the text, such as an element id
+    pub fragment: String,
+    /// The first character, counted from the start of the fragment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    /// The last character, counted from the start of the fragment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+}
+
+impl TextSelector {
+    /// Select the whole of a fragment
+    pub fn new(fragment: &str) -> Self {
+        TextSelector {
+            fragment: fragment.to_owned(),
+            start: None,
+            end: None,
+        }
+    }
+}
+
+/// A span of text, from one selector to an optional other
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct TextSelectorRange {
+    /// The start of the span
+    pub selector: TextSelector,
+    /// The end of the span, the end of the selector when not present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<TextSelector>,
+}
+
+/// Spans of text
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Text {
+    /// The spans of text
+    pub selectors: Vec<TextSelectorRange>,
+}
+
+/// A part of the asset identified by the asset format, such as a track or layer
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Item {
+    /// The kind of identifier, such as `track_id`
+    pub identifier: String,
+    /// The identifier of the part
+    pub value: String,
+}
+
+/// A part of an asset in one dimension, such as an area or a span of time
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct Range {
+    /// The kind of part described
+    #[serde(rename = "type")]
+    pub range_type: RangeType,
+    /// The area of a spatial range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shape: Option<Shape>,
+    /// The span of time of a temporal range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<Time>,
+    /// The span of frames of a frame range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<Frame>,
+    /// The spans of text of a textual range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<Text>,
+    /// The part of an identified range
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<Item>,
+}
+
+impl Range {
+    fn new(range_type: RangeType) -> Self {
+        Range {
+            range_type,
+            shape: None,
+            time: None,
+            frame: None,
+            text: None,
+            item: None,
+        }
+    }
+
+    /// Create a range for an area of an image or video frame
+    pub fn spatial(shape: Shape) -> Self {
+        Range {
+            shape: Some(shape),
+            ..Self::new(RangeType::Spatial)
+        }
+    }
+
+    /// Create a range for a span of time of audio or video
+    pub fn temporal(time: Time) -> Self {
+        Range {
+            time: Some(time),
+            ..Self::new(RangeType::Temporal)
+        }
+    }
+
+    /// Create a range for a span of video frames
+    pub fn frame(frame: Frame) -> Self {
+        Range {
+            frame: Some(frame),
+            ..Self::new(RangeType::Frame)
+        }
+    }
+
+    /// Create a range for spans of text
+    pub fn textual(text: Text) -> Self {
+        Range {
+            text: Some(text),
+            ..Self::new(RangeType::Textual)
+        }
+    }
+
+    /// Create a range for a part identified by the asset format
+    pub fn identified(item: Item) -> Self {
+        Range {
+            item: Some(item),
+            ..Self::new(RangeType::Identified)
+        }
+    }
+}
+
+/// Specification defined roles of a region of interest
+pub mod c2pa_role {
+    /// An area of the asset the claim generator draws attention to
+    pub const AREA_OF_INTEREST: &str = "c2pa.areaOfInterest";
+    /// The region was cropped out of the asset
+    pub const CROPPED: &str = "c2pa.cropped";
+    /// The region was deleted
+    pub const DELETED: &str = "c2pa.deleted";
+    /// The region was edited
+    pub const EDITED: &str = "c2pa.edited";
+    /// An ingredient was placed in the region
+    pub const PLACED: &str = "c2pa.placed";
+    /// The region was redacted
+    pub const REDACTED: &str = "c2pa.redacted";
+    /// The region had a style applied
+    pub const STYLED: &str = "c2pa.styled";
+    /// The region is the subject of the asset
+    pub const SUBJECT_AREA: &str = "c2pa.subjectArea";
+    /// The region was watermarked
+    pub const WATERMARKED: &str = "c2pa.watermarked";
+}
+
+/// A region of an asset, made of one or more ranges which all apply.
+///
+/// A video region may for example combine a spatial range with a temporal range.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct RegionOfInterest {
+    /// The ranges of the region
+    pub region: Vec<Range>,
+    /// A human readable name of the region
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// An identifier of the region, unique within the assertion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    /// The type of content in the region, such as an IPTC image region type
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub region_type: Option<String>,
+    /// The role of the region, see [`c2pa_role`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub role: Option<String>,
+    /// A human readable description of the region
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl RegionOfInterest {
+    /// Create a region from a single range
+    pub fn new(range: Range) -> Self {
+        RegionOfInterest {
+            region: vec![range],
+            name: None,
+            identifier: None,
+            region_type: None,
+            role: None,
+            description: None,
+        }
+    }
+
+    /// Add a range the region is restricted to
+    pub fn add_range(mut self, range: Range) -> Self {
+        self.region.push(range);
+        self
+    }
+
+    /// Set the role of the region, see [`c2pa_role`]
+    pub fn set_role(mut self, role: &str) -> Self {
+        self.role = Some(role.to_owned());
+        self
+    }
+
+    /// Set a human readable name of the region
+    pub fn set_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+
+    /// Set a human readable description of the region
+    pub fn set_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_owned());
+        self
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_region_of_interest() {
+        let region = RegionOfInterest::new(Range::spatial(Shape::rectangle(
+            UnitType::Percent,
+            Coordinate::new(10.0, 20.0),
+            30.0,
+            40.0,
+        )))
+        .add_range(Range::temporal(Time::new(Some("0"), Some("5.5"))))
+        .set_role(c2pa_role::EDITED);
+
+        // the JSON layout matches the specification
+        let json = serde_json::to_value(&region).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "region": [
+                    {
+                        "type": "spatial",
+                        "shape": {
+                            "type": "rectangle",
+                            "unit": "percent",
+                            "origin": { "x": 10.0, "y": 20.0 },
+                            "width": 30.0,
+                            "height": 40.0
+                        }
+                    },
+                    { "type": "temporal", "time": { "type": "npt", "start": "0", "end": "5.5" } }
+                ],
+                "role": "c2pa.edited"
+            })
+        );
+
+        let cbor = serde_cbor::to_vec(&region).unwrap();
+        let decoded: RegionOfInterest = serde_cbor::from_slice(&cbor).unwrap();
+        assert_eq!(decoded, region);
+
+        let text: Range = serde_json::from_value(serde_json::json!({
+            "type": "textual",
+            "text": { "selectors": [ { "selector": { "fragment": "p1", "start": 4 } } ] }
+        }))
+        .unwrap();
+        assert_eq!(text.text.unwrap().selectors[0].selector.start, Some(4));
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+
+use crate::{
+    assertion::{Assertion, AssertionBase, AssertionCbor},
+    assertions::labels,
+    error::Result,
+};
+
+/// The time range of a temporal asset covered by a soft binding block
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SoftBindingTimespan {
+    /// Start of the range, in milliseconds
+    pub start: u64,
+    /// End of the range, in milliseconds
+    pub end: u64,
+}
+
+/// The part of the asset a soft binding block applies to.
+/// An empty scope applies to the whole asset.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct SoftBindingScope {
+    /// Algorithm specific description of the spatial extent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extent: Option<ByteBuf>,
+    /// The time range covered
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timespan: Option<SoftBindingTimespan>,
+}
+
+/// A soft binding value for a scope of the asset
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SoftBindingBlock {
+    /// The part of the asset the value applies to
+    pub scope: SoftBindingScope,
+    /// The value computed by the soft binding algorithm
+    #[serde(with = "serde_bytes")]
+    pub value: Vec<u8>,
+}
+
+/// A soft binding assertion records watermark or fingerprint values that can
+/// be used to find the manifest of an asset after it has been stripped.
+///
+/// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_soft_binding_2>.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct SoftBinding {
+    /// Name of the soft binding algorithm
+    pub alg: String,
+    /// The values computed by the algorithm
+    pub blocks: Vec<SoftBindingBlock>,
+    #[serde(with = "serde_bytes")]
+    pad: Vec<u8>,
+    /// Human readable description of what this binding covers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Algorithm specific parameters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg_params: Option<ByteBuf>,
+}
+
+impl SoftBinding {
+    /// Label prefix for a soft binding assertion.
+    ///
+    /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_soft_binding_2>.
+    pub const LABEL: &'static str = labels::SOFT_BINDING;
+
+    /// Create a soft binding assertion for the named algorithm with no values
+    pub fn new(alg: &str) -> Self {
+        SoftBinding {
+            alg: alg.to_owned(),
+            blocks: Vec::new(),
+            pad: Vec::new(),
+            name: None,
+            alg_params: None,
+        }
+    }
+
+    /// Create a soft binding assertion covering the whole asset using `algorithm`.
+    ///
+    /// Returns `None` if the algorithm does not find a value in the asset.
+    pub fn from_algorithm(
+        algorithm: &dyn SoftBindingAlgorithm,
+        asset_data: &[u8],
+    ) -> Result<Option<Self>> {
+        Ok(algorithm.extract(asset_data)?.map(|value| {
+            let mut soft_binding = SoftBinding::new(algorithm.alg());
+            soft_binding.add_block(SoftBindingScope::default(), value);
+            soft_binding
+        }))
+    }
+
+    /// Add a value for a scope of the asset
+    pub fn add_block(&mut self, scope: SoftBindingScope, value: Vec<u8>) -> &mut Self {
+        self.blocks.push(SoftBindingBlock { scope, value });
+        self
+    }
+
+    /// Set a description of what this binding covers
+    pub fn set_name(&mut self, name: &str) -> &mut Self {
+        self.name = Some(name.to_owned());
+        self
+    }
+}
+
+impl AssertionCbor for SoftBinding {}
+
+impl AssertionBase for SoftBinding {
+    const LABEL: &'static str = Self::LABEL;
+
+    fn to_assertion(&self) -> Result<Assertion> {
+        Self::to_cbor_assertion(self)
+    }
+
+    fn from_assertion(assertion: &Assertion) -> Result<Self> {
+        Self::from_cbor_assertion(assertion)
+    }
+}
+
+/// A soft binding algorithm such as a watermark or fingerprint.
+///
+/// Vendors implement this trait and register it in a [`SoftBindingRegistry`]
+/// so soft binding assertions can be checked during validation.
+pub trait SoftBindingAlgorithm: Send + Sync {
+    /// The algorithm name recorded in the `alg` field of the assertion
+    fn alg(&self) -> &str;
+
+    /// Extract the soft binding value from the asset, or `None` if there is none
+    fn extract(&self, asset_data: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Returns true if the `extracted` value matches the `recorded` value.
+    ///
+    /// Fingerprint algorithms should override this to allow for near matches.
+    fn matches(&self, recorded: &[u8], extracted: &[u8]) -> bool {
+        recorded == extracted
+    }
+}
+
+/// The set of soft binding algorithms known to the validator
+#[derive(Clone, Default)]
+pub struct SoftBindingRegistry {
+    algorithms: Vec<Arc<dyn SoftBindingAlgorithm>>,
+}
+
+impl SoftBindingRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an algorithm, replacing any registered with the same name
+    pub fn register(&mut self, algorithm: Arc<dyn SoftBindingAlgorithm>) -> &mut Self {
+        self.algorithms.retain(|a| a.alg() != algorithm.alg());
+        self.algorithms.push(algorithm);
+        self
+    }
+
+    /// Find the algorithm with the given name
+    pub fn get(&self, alg: &str) -> Option<&dyn SoftBindingAlgorithm> {
+        self.algorithms
+            .iter()
+            .find(|a| a.alg() == alg)
+            .map(|a| a.as_ref())
+    }
+
+    /// Returns true if no algorithms are registered
+    pub fn is_empty(&self) -> bool {
+        self.algorithms.is_empty()
+    }
+
+    /// Compute the values of all registered algorithms for an asset,
+    /// returned as pairs of algorithm name and value
+    pub fn extract_all(&self, asset_data: &[u8]) -> Vec<(String, Vec<u8>)> {
+        self.algorithms
+            .iter()
+            .filter_map(|a| match a.extract(asset_data) {
+                Ok(Some(value)) => Some((a.alg().to_owned(), value)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for SoftBindingRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.algorithms.iter().map(|a| a.alg()))
+            .finish()
+    }
+}
+
+impl PartialEq for SoftBindingRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.algorithms.len() == other.algorithms.len()
+            && self
+                .algorithms
+                .iter()
+                .zip(other.algorithms.iter())
+                .all(|(a, b)| a.alg() == b.alg())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    // a toy algorithm that uses the last 4 bytes of the asset as the watermark
+    struct TailMark;
+
+    impl SoftBindingAlgorithm for TailMark {
+        fn alg(&self) -> &str {
+            "com.example.tailmark"
+        }
+
+        fn extract(&self, asset_data: &[u8]) -> Result<Option<Vec<u8>>> {
+            Ok(asset_data
+                .len()
+                .checked_sub(4)
+                .map(|start| asset_data[start..].to_vec()))
+        }
+    }
+
+    #[test]
+    fn test_soft_binding_round_trip() {
+        let mut registry = SoftBindingRegistry::new();
+        registry.register(Arc::new(TailMark));
+
+        let soft_binding = SoftBinding::from_algorithm(&TailMark, b"asset data 1234")
+            .unwrap()
+            .unwrap();
+        assert_eq!(soft_binding.blocks[0].value, b"1234".to_vec());
+
+        let assertion = soft_binding.to_assertion().unwrap();
+        let restored = SoftBinding::from_assertion(&assertion).unwrap();
+        assert_eq!(soft_binding, restored);
+
+        let algorithm = registry.get(&restored.alg).unwrap();
+        let extracted = algorithm.extract(b"other data 1234").unwrap().unwrap();
+        assert!(algorithm.matches(&restored.blocks[0].value, &extracted));
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assertion::{Assertion, AssertionBase, AssertionCbor},
+    assertions::{labels, Metadata},
+    error::{Error, Result},
+};
+
+const ASSERTION_CREATION_VERSION: usize = 1;
+
+/// Specification defined uses of an asset by training and data mining
+pub mod c2pa_training {
+    /// Training of generative AI models
+    pub const AI_GENERATIVE_TRAINING: &str = "c2pa.ai_generative_training";
+    /// Use as input to a trained AI model
+    pub const AI_INFERENCE: &str = "c2pa.ai_inference";
+    /// Training of any AI model
+    pub const AI_TRAINING: &str = "c2pa.ai_training";
+    /// Data mining
+    pub const DATA_MINING: &str = "c2pa.data_mining";
+
+    pub(super) const ALL: &[&str] = &[
+        AI_GENERATIVE_TRAINING,
+        AI_INFERENCE,
+        AI_TRAINING,
+        DATA_MINING,
+    ];
+}
+
+/// Whether a use of the asset is permitted
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TrainingMiningUse {
+    /// The use is permitted
+    Allowed,
+    /// The use is not permitted
+    NotAllowed,
+    /// The use is permitted under the conditions given in the entry's constraint info
+    Constrained,
+}
+
+/// Whether one use of the asset is permitted
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct TrainingMiningEntry {
+    /// Whether the use is permitted
+    #[serde(rename = "use")]
+    pub permission: TrainingMiningUse,
+    /// The conditions of a constrained use, such as a URL to a license
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub constraint_info: Option<String>,
+}
+
+impl TrainingMiningEntry {
+    /// Create an entry permitting or forbidding the use
+    pub fn new(allowed: bool) -> Self {
+        TrainingMiningEntry {
+            permission: if allowed {
+                TrainingMiningUse::Allowed
+            } else {
+                TrainingMiningUse::NotAllowed
+            },
+            constraint_info: None,
+        }
+    }
+
+    /// Create an entry permitting the use under the given conditions
+    pub fn constrained(constraint_info: &str) -> Self {
+        TrainingMiningEntry {
+            permission: TrainingMiningUse::Constrained,
+            constraint_info: Some(constraint_info.to_owned()),
+        }
+    }
+}
+
+/// A training and data mining assertion states whether the asset may be used
+/// to train AI models or be mined for data.
+///
+/// Entries are keyed by the use, one of [`c2pa_training`] or a custom use in
+/// reverse-domain format. Assertions are validated when they are read.
+///
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_training_and_data_mining>.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct TrainingMining {
+    /// The permitted uses of the asset
+    pub entries: HashMap<String, TrainingMiningEntry>,
+    /// Additional information about the assertion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+}
+
+impl TrainingMining {
+    /// Label prefix for a training and data mining assertion.
+    ///
+    /// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_training_and_data_mining>.
+    pub const LABEL: &'static str = labels::TRAINING_MINING;
+
+    /// Create an assertion without entries
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an assertion forbidding every use defined by the specification
+    pub fn do_not_train() -> Self {
+        let mut training_mining = Self::new();
+        for use_label in c2pa_training::ALL {
+            training_mining.add_entry(use_label, TrainingMiningEntry::new(false));
+        }
+        training_mining
+    }
+
+    /// Add or replace the entry for a use
+    pub fn add_entry(&mut self, use_label: &str, entry: TrainingMiningEntry) -> &mut Self {
+        self.entries.insert(use_label.to_owned(), entry);
+        self
+    }
+
+    /// Returns the entry for a use, if there is one
+    pub fn entry(&self, use_label: &str) -> Option<&TrainingMiningEntry> {
+        self.entries.get(use_label)
+    }
+
+    /// Returns `true` if the use is not permitted
+    pub fn is_forbidden(&self, use_label: &str) -> bool {
+        matches!(
+            self.entry(use_label),
+            Some(TrainingMiningEntry {
+                permission: TrainingMiningUse::NotAllowed,
+                ..
+            })
+        )
+    }
+
+    /// Checks the entries against the specification.
+    ///
+    /// Uses starting with `c2pa.` must be defined by the specification and other uses
+    /// must be in reverse-domain format. Only constrained uses may have constraint info.
+    pub fn validate(&self) -> Result<()> {
+        for (use_label, entry) in &self.entries {
+            let valid_label = if use_label.starts_with("c2pa.") {
+                c2pa_training::ALL.contains(&use_label.as_str())
+            } else {
+                use_label.split('.').count() > 1 && use_label.split('.').all(|p| !p.is_empty())
+            };
+            if !valid_label {
+                return Err(Error::AssertionTrainingMiningInvalid(format!(
+                    "unknown use {}",
+                    use_label
+                )));
+            }
+
+            if entry.constraint_info.is_some() && entry.permission != TrainingMiningUse::Constrained
+            {
+                return Err(Error::AssertionTrainingMiningInvalid(format!(
+                    "constraint info for a use that is not constrained: {}",
+                    use_label
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AssertionCbor for TrainingMining {}
+
+impl AssertionBase for TrainingMining {
+    const LABEL: &'static str = Self::LABEL;
+    const VERSION: Option<usize> = Some(ASSERTION_CREATION_VERSION);
+
+    fn to_assertion(&self) -> Result<Assertion> {
+        self.validate()?;
+        Self::to_cbor_assertion(self)
+    }
+
+    fn from_assertion(assertion: &Assertion) -> Result<Self> {
+        let training_mining = Self::from_cbor_assertion(assertion)?;
+        training_mining.validate()?;
+        Ok(training_mining)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_training_mining() {
+        let mut original = TrainingMining::do_not_train();
+        original.add_entry(
+            c2pa_training::AI_INFERENCE,
+            TrainingMiningEntry::constrained("https://example.com/license"),
+        );
+
+        let assertion = original.to_assertion().unwrap();
+        assert_eq!(assertion.label(), TrainingMining::LABEL);
+        let result = TrainingMining::from_assertion(&assertion).unwrap();
+        assert_eq!(result, original);
+        assert!(result.is_forbidden(c2pa_training::AI_GENERATIVE_TRAINING));
+        assert!(!result.is_forbidden(c2pa_training::AI_INFERENCE));
+
+        // the JSON layout matches the specification
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(
+            json["entries"][c2pa_training::DATA_MINING]["use"],
+            "notAllowed"
+        );
+
+        // invalid entries are rejected when read
+        let mut invalid = TrainingMining::new();
+        invalid.add_entry("c2pa.ai_dreaming", TrainingMiningEntry::new(true));
+        let assertion = TrainingMining::to_cbor_assertion(&invalid).unwrap();
+        assert!(matches!(
+            TrainingMining::from_assertion(&assertion),
+            Err(Error::AssertionTrainingMiningInvalid(_))
+        ));
+
+        let mut invalid = TrainingMining::new();
+        invalid.add_entry(
+            "com.example.indexing",
+            TrainingMiningEntry {
+                permission: TrainingMiningUse::Allowed,
+                constraint_info: Some("only on Tuesdays".to_owned()),
+            },
+        );
+        assert!(invalid.to_assertion().is_err());
+    }
+}
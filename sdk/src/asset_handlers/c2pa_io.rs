@@ -13,6 +13,7 @@
 
 use crate::asset_io::{AssetIO, CAILoader, CAIRead, HashObjectPositions};
 use crate::error::{Error, Result};
+use std::borrow::Cow;
 use std::fs::File;
 use std::path::Path;
 
@@ -27,6 +28,11 @@ impl CAILoader for C2paIO {
         Ok(cai_data)
     }
 
+    // the whole file is the CAI block
+    fn read_cai_from_slice<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        Ok(Cow::Borrowed(data))
+    }
+
     // C2PA files have no xmp data
     fn read_xmp(&self, _asset_reader: &mut dyn CAIRead) -> Option<String> {
         None
@@ -56,7 +62,7 @@ impl AssetIO for C2paIO {
 }
 
 #[cfg(test)]
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
 pub mod tests {
     #![allow(clippy::expect_used)]
     #![allow(clippy::unwrap_used)]
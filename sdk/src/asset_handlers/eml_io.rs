@@ -0,0 +1,668 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Asset handler for email messages with a multipart MIME body.
+//!
+//! The manifest store travels as a base64 encoded `application/c2pa` part appended to the
+//! top level multipart body. Mail transports are free to re-encode a message, so instead of
+//! hashing its raw bytes the handler binds the manifest with a [`CollectionHash`] over the
+//! decoded content of every other leaf part and the unfolded header block of every entity,
+//! leaving out the trace fields relays prepend in transit.
+
+use std::fs;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use crate::assertions::CollectionHash;
+use crate::asset_io::{AssetIO, CAILoader, CAIRead, HashBlockObjectType, HashObjectPositions};
+use crate::error::{Error, Result};
+
+const MANIFEST_CONTENT_TYPE: &str = "application/c2pa";
+const MANIFEST_FILENAME: &str = "manifest.c2pa";
+const BASE64_LINE_LEN: usize = 76;
+const HEADERS_CONTENT_TYPE: &str = "text/rfc822-headers";
+// deepest multipart nesting accepted, well beyond what mail clients produce
+const MAX_MULTIPART_DEPTH: usize = 16;
+// header fields added by relays after the message is sent (RFC 5321 section 4.4)
+const TRACE_HEADERS: [&str; 2] = ["received", "return-path"];
+
+fn eml_error(msg: &str) -> Error {
+    Error::BadParam(format!("EML invalid: {}", msg))
+}
+
+/// A decoded leaf part or the header block of an entity of a MIME message.
+pub(crate) struct MimePart {
+    /// Position of the part in the message tree, e.g. `1.2` for the second part of the first part,
+    /// with a `headers` suffix for header blocks
+    pub uri: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+// returns the end of the line content and the start of the next line
+fn next_line(data: &[u8], pos: usize, end: usize) -> (usize, usize) {
+    match data[pos..end].iter().position(|b| *b == b'\n') {
+        Some(n) => {
+            let lf = pos + n;
+            let content_end = if lf > pos && data[lf - 1] == b'\r' {
+                lf - 1
+            } else {
+                lf
+            };
+            (content_end, lf + 1)
+        }
+        None => (end, end),
+    }
+}
+
+// parses a header block, returning unfolded headers with lowercase names and the start of the body
+fn parse_headers(data: &[u8], start: usize, end: usize) -> (Vec<(String, String)>, usize) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut pos = start;
+    while pos < end {
+        let (line_end, next) = next_line(data, pos, end);
+        let line = String::from_utf8_lossy(&data[pos..line_end]);
+        pos = next;
+
+        if line.is_empty() {
+            return (headers, pos);
+        }
+        if line.starts_with(' ') || line.starts_with('\t') {
+            if let Some(last) = headers.last_mut() {
+                last.1.push(' ');
+                last.1.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_owned()));
+        }
+    }
+    (headers, end)
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+fn media_type(headers: &[(String, String)]) -> String {
+    header(headers, "content-type")
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "text/plain".to_string())
+}
+
+fn header_param(value: &str, param: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|p| {
+        let (name, value) = p.split_once('=')?;
+        if name.trim().eq_ignore_ascii_case(param) {
+            Some(value.trim().trim_matches('"').to_owned())
+        } else {
+            None
+        }
+    })
+}
+
+// a MIME entity: its headers, the start of its body and its end
+struct Entity {
+    body_start: usize,
+    end: usize,
+    headers: Vec<(String, String)>,
+}
+
+impl Entity {
+    fn parse(data: &[u8], start: usize, end: usize) -> Self {
+        let (headers, body_start) = parse_headers(data, start, end);
+        Entity {
+            body_start,
+            end,
+            headers,
+        }
+    }
+
+    fn boundary(&self) -> Option<String> {
+        if !media_type(&self.headers).starts_with("multipart/") {
+            return None;
+        }
+        header(&self.headers, "content-type").and_then(|v| header_param(v, "boundary"))
+    }
+
+    fn is_manifest(&self) -> bool {
+        media_type(&self.headers) == MANIFEST_CONTENT_TYPE
+    }
+
+    // the unfolded header fields as CRLF terminated lines, which survive re-encoding
+    fn header_block(&self) -> Vec<u8> {
+        let mut block = Vec::new();
+        for (name, value) in &self.headers {
+            if TRACE_HEADERS.contains(&name.as_str()) {
+                continue;
+            }
+            block.extend_from_slice(format!("{}: {}\r\n", name, value).as_bytes());
+        }
+        block
+    }
+
+    fn content(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let body = &data[self.body_start..self.end];
+        let encoding = header(&self.headers, "content-transfer-encoding")
+            .map(|v| v.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+        match encoding.as_str() {
+            "base64" => {
+                let text: Vec<u8> = body
+                    .iter()
+                    .copied()
+                    .filter(|b| !b.is_ascii_whitespace())
+                    .collect();
+                base64::decode(&text).map_err(|_err| eml_error("bad base64 content"))
+            }
+            "quoted-printable" => Ok(to_crlf(&decode_quoted_printable(body))),
+            _ => Ok(to_crlf(body)),
+        }
+    }
+}
+
+// the parts of a multipart body, each running from the start of its delimiter line to the
+// start of the next, along with the start of the closing delimiter line
+struct Multipart {
+    parts: Vec<(usize, Entity)>,
+    closing: usize,
+}
+
+impl Multipart {
+    fn parse(data: &[u8], entity: &Entity, boundary: &str) -> Result<Self> {
+        let delimiter = format!("--{}", boundary);
+        let mut delimiters: Vec<(usize, usize)> = Vec::new();
+        let mut pos = entity.body_start;
+        while pos < entity.end {
+            let (line_end, next) = next_line(data, pos, entity.end);
+            let line = &data[pos..line_end];
+            if line.starts_with(delimiter.as_bytes()) {
+                let rest = &line[delimiter.len()..];
+                if rest.starts_with(b"--") {
+                    let parts = Multipart::entities(data, &delimiters, pos);
+                    return Ok(Multipart {
+                        parts,
+                        closing: pos,
+                    });
+                }
+                if rest.iter().all(|b| b.is_ascii_whitespace()) {
+                    delimiters.push((pos, next));
+                }
+            }
+            pos = next;
+        }
+        Err(eml_error("missing closing boundary"))
+    }
+
+    fn entities(
+        data: &[u8],
+        delimiters: &[(usize, usize)],
+        closing: usize,
+    ) -> Vec<(usize, Entity)> {
+        delimiters
+            .iter()
+            .enumerate()
+            .map(|(i, (line_start, content_start))| {
+                let next = delimiters.get(i + 1).map_or(closing, |d| d.0);
+                // the line break before a delimiter belongs to the delimiter
+                let mut end = next.max(*content_start);
+                if end > *content_start && data[end - 1] == b'\n' {
+                    end -= 1;
+                    if end > *content_start && data[end - 1] == b'\r' {
+                        end -= 1;
+                    }
+                }
+                (*line_start, Entity::parse(data, *content_start, end))
+            })
+            .collect()
+    }
+
+    // range of the manifest part including its delimiter line
+    fn manifest_range(&self) -> Option<(usize, usize)> {
+        self.parts
+            .iter()
+            .enumerate()
+            .find_map(|(i, (line_start, e))| {
+                if e.is_manifest() {
+                    let next = self.parts.get(i + 1).map_or(self.closing, |p| p.0);
+                    Some((*line_start, next))
+                } else {
+                    None
+                }
+            })
+    }
+}
+
+fn top_level(data: &[u8]) -> Result<(Multipart, String)> {
+    let message = Entity::parse(data, 0, data.len());
+    let boundary = message
+        .boundary()
+        .ok_or_else(|| eml_error("message is not multipart"))?;
+    Ok((Multipart::parse(data, &message, &boundary)?, boundary))
+}
+
+fn to_crlf(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for (i, b) in data.iter().enumerate() {
+        if *b == b'\n' && (i == 0 || data[i - 1] != b'\r') {
+            out.push(b'\r');
+        }
+        out.push(*b);
+    }
+    out
+}
+
+fn decode_quoted_printable(data: &[u8]) -> Vec<u8> {
+    fn hex(b: u8) -> Option<u8> {
+        (b as char).to_digit(16).map(|d| d as u8)
+    }
+
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        if data[i] == b'=' {
+            // soft line break
+            if data[i + 1..].starts_with(b"\r\n") {
+                i += 3;
+                continue;
+            }
+            if data[i + 1..].starts_with(b"\n") {
+                i += 2;
+                continue;
+            }
+            if let (Some(h), Some(l)) = (
+                data.get(i + 1).copied().and_then(hex),
+                data.get(i + 2).copied().and_then(hex),
+            ) {
+                out.push(h << 4 | l);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(data[i]);
+        i += 1;
+    }
+    out
+}
+
+fn collect_parts(
+    data: &[u8],
+    entity: &Entity,
+    prefix: &str,
+    depth: usize,
+    parts: &mut Vec<MimePart>,
+) -> Result<()> {
+    parts.push(MimePart {
+        uri: if prefix.is_empty() {
+            "headers".to_owned()
+        } else {
+            format!("{}.headers", prefix)
+        },
+        content_type: HEADERS_CONTENT_TYPE.to_owned(),
+        content: entity.header_block(),
+    });
+
+    match entity.boundary() {
+        Some(boundary) => {
+            if depth >= MAX_MULTIPART_DEPTH {
+                return Err(eml_error("multipart nesting too deep"));
+            }
+            let multipart = Multipart::parse(data, entity, &boundary)?;
+            let children = multipart.parts.iter().filter(|(_, e)| !e.is_manifest());
+            for (i, (_, child)) in children.enumerate() {
+                let uri = if prefix.is_empty() {
+                    (i + 1).to_string()
+                } else {
+                    format!("{}.{}", prefix, i + 1)
+                };
+                collect_parts(data, child, &uri, depth + 1, parts)?;
+            }
+        }
+        None => parts.push(MimePart {
+            uri: prefix.to_owned(),
+            content_type: media_type(&entity.headers),
+            content: entity.content(data)?,
+        }),
+    }
+    Ok(())
+}
+
+/// Returns the header blocks and decoded leaf parts of a multipart message, leaving out the
+/// manifest part.
+pub(crate) fn mime_parts(data: &[u8]) -> Result<Vec<MimePart>> {
+    let message = Entity::parse(data, 0, data.len());
+    if message.boundary().is_none() {
+        return Err(eml_error("message is not multipart"));
+    }
+    let mut parts = Vec::new();
+    collect_parts(data, &message, "", 0, &mut parts)?;
+    Ok(parts)
+}
+
+/// Builds a collection hash covering every part of a multipart message except the manifest.
+pub fn collection_hash(data: &[u8], alg: &str) -> Result<CollectionHash> {
+    let mut collection = CollectionHash::new(alg);
+    for part in mime_parts(data)? {
+        collection.add_part(&part.uri, Some(&part.content_type), &part.content)?;
+    }
+    Ok(collection)
+}
+
+fn read_manifest(data: &[u8]) -> Result<Vec<u8>> {
+    let (multipart, _) = top_level(data)?;
+    let (_, entity) = multipart
+        .parts
+        .iter()
+        .find(|(_, e)| e.is_manifest())
+        .ok_or(Error::JumbfNotFound)?;
+    entity.content(data)
+}
+
+// Replaces any manifest part with one holding the new store, placed last in the top level body.
+fn write_manifest(data: &[u8], store_bytes: &[u8]) -> Result<Vec<u8>> {
+    let (multipart, boundary) = top_level(data)?;
+
+    let mut part = format!(
+        "--{}\r\nContent-Type: {}\r\nContent-Disposition: attachment; filename=\"{}\"\r\nContent-Transfer-Encoding: base64\r\n\r\n",
+        boundary, MANIFEST_CONTENT_TYPE, MANIFEST_FILENAME
+    )
+    .into_bytes();
+    let encoded = base64::encode(store_bytes);
+    for line in encoded.as_bytes().chunks(BASE64_LINE_LEN) {
+        part.extend_from_slice(line);
+        part.extend_from_slice(b"\r\n");
+    }
+    if encoded.is_empty() {
+        part.extend_from_slice(b"\r\n");
+    }
+
+    let mut out = Vec::with_capacity(data.len() + part.len());
+    match multipart.manifest_range() {
+        Some((start, end)) => {
+            out.extend_from_slice(&data[..start]);
+            out.extend_from_slice(&data[end..multipart.closing]);
+        }
+        None => out.extend_from_slice(&data[..multipart.closing]),
+    }
+    out.extend_from_slice(&part);
+    out.extend_from_slice(&data[multipart.closing..]);
+    Ok(out)
+}
+
+pub struct EmlIO {}
+
+impl CAILoader for EmlIO {
+    fn read_cai(&self, asset_reader: &mut dyn CAIRead) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        asset_reader.seek(SeekFrom::Start(0))?;
+        asset_reader.read_to_end(&mut data)?;
+        read_manifest(&data)
+    }
+
+    // the manifest part is found by its content type, there is no XMP to reference it
+    fn read_xmp(&self, _asset_reader: &mut dyn CAIRead) -> Option<String> {
+        None
+    }
+}
+
+impl AssetIO for EmlIO {
+    fn read_cai_store(&self, asset_path: &Path) -> Result<Vec<u8>> {
+        let data = fs::read(asset_path)?;
+        read_manifest(&data)
+    }
+
+    fn save_cai_store(&self, asset_path: &Path, store_bytes: &[u8]) -> Result<()> {
+        let data = fs::read(asset_path)?;
+        let output = write_manifest(&data, store_bytes)?;
+        fs::write(asset_path, output)?;
+        Ok(())
+    }
+
+    fn get_object_locations(&self, asset_path: &Path) -> Result<Vec<HashObjectPositions>> {
+        let mut data = fs::read(asset_path)?;
+
+        // add an empty manifest part so its position is known
+        if let Err(Error::JumbfNotFound) = read_manifest(&data) {
+            data = write_manifest(&data, &[])?;
+            fs::write(asset_path, &data)?;
+        }
+
+        let (multipart, _) = top_level(&data)?;
+        let (start, end) = multipart.manifest_range().ok_or(Error::JumbfNotFound)?;
+
+        Ok(vec![
+            HashObjectPositions {
+                offset: 0,
+                length: start,
+                htype: HashBlockObjectType::Other,
+            },
+            HashObjectPositions {
+                offset: start,
+                length: end - start,
+                htype: HashBlockObjectType::Cai,
+            },
+            HashObjectPositions {
+                offset: end,
+                length: data.len() - end,
+                htype: HashBlockObjectType::Other,
+            },
+        ])
+    }
+
+    fn supports_xmp(&self) -> bool {
+        false
+    }
+
+    fn collection_hash(&self, asset_path: &Path, alg: &str) -> Result<Option<CollectionHash>> {
+        let data = fs::read(asset_path)?;
+        collection_hash(&data, alg).map(Some)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        utils::test::{fixture_path, temp_dir_path},
+        validation_status::ValidationStatusCode,
+    };
+
+    #[test]
+    fn test_mime_parts() {
+        let data = std::fs::read(fixture_path("sample1.eml")).unwrap();
+        let parts = mime_parts(&data).unwrap();
+
+        let uris: Vec<&str> = parts.iter().map(|p| p.uri.as_str()).collect();
+        assert_eq!(
+            uris,
+            vec![
+                "headers",
+                "1.headers",
+                "1.1.headers",
+                "1.1",
+                "1.2.headers",
+                "1.2",
+                "2.headers",
+                "2"
+            ]
+        );
+        assert_eq!(parts[3].content_type, "text/plain");
+        assert_eq!(parts[7].content_type, "image/png");
+
+        // header blocks are unfolded
+        assert_eq!(parts[0].content_type, HEADERS_CONTENT_TYPE);
+        let headers = String::from_utf8(parts[0].content.clone()).unwrap();
+        assert!(headers.contains("subject: Signed message with an image\r\n"));
+        assert!(headers.contains("content-type: multipart/mixed; boundary=\"mixed-boundary\"\r\n"));
+
+        // quoted printable and base64 content is decoded
+        let text = String::from_utf8(parts[3].content.clone()).unwrap();
+        assert!(text.contains("café included"));
+        assert!(text.contains("long enough to be wrapped"));
+        let png = std::fs::read(fixture_path("libpng-test.png")).unwrap();
+        assert_eq!(parts[7].content, png[..300]);
+    }
+
+    #[test]
+    fn test_nesting_limit() {
+        let mut data = String::from("Content-Type: multipart/mixed; boundary=\"b0\"\r\n\r\n");
+        let mut closing = String::from("--b0--\r\n");
+        for depth in 1..=MAX_MULTIPART_DEPTH {
+            data.push_str(&format!(
+                "--b{}\r\nContent-Type: multipart/mixed; boundary=\"b{}\"\r\n\r\n",
+                depth - 1,
+                depth
+            ));
+            closing = format!("--b{}--\r\n{}", depth, closing);
+        }
+        data.push_str(&format!(
+            "--b{}\r\nContent-Type: text/plain\r\n\r\ntext\r\n",
+            MAX_MULTIPART_DEPTH
+        ));
+        data.push_str(&closing);
+
+        assert!(mime_parts(data.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_eml_save_and_read() {
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "sample1.eml");
+        std::fs::copy(fixture_path("sample1.eml"), &op).unwrap();
+
+        let eml_io = EmlIO {};
+        assert!(matches!(
+            eml_io.read_cai_store(&op),
+            Err(Error::JumbfNotFound)
+        ));
+        let original = collection_hash(&std::fs::read(&op).unwrap(), "sha256").unwrap();
+
+        let store_bytes: Vec<u8> = (0..200u8).collect();
+        eml_io.save_cai_store(&op, &store_bytes).unwrap();
+        assert_eq!(eml_io.read_cai_store(&op).unwrap(), store_bytes);
+
+        // replacing the store keeps a single manifest part
+        eml_io.save_cai_store(&op, b"replacement").unwrap();
+        let data = std::fs::read(&op).unwrap();
+        let (multipart, _) = top_level(&data).unwrap();
+        assert_eq!(
+            multipart
+                .parts
+                .iter()
+                .filter(|(_, e)| e.is_manifest())
+                .count(),
+            1
+        );
+        assert_eq!(read_manifest(&data).unwrap(), b"replacement");
+
+        // the manifest part is not part of the collection
+        assert_eq!(collection_hash(&data, "sha256").unwrap(), original);
+
+        let positions = eml_io.get_object_locations(&op).unwrap();
+        let cai = positions
+            .iter()
+            .find(|p| p.htype == HashBlockObjectType::Cai)
+            .unwrap();
+        assert!(
+            data[cai.offset..].starts_with(b"--mixed-boundary\r\nContent-Type: application/c2pa")
+        );
+        assert!(data[cai.offset + cai.length..].starts_with(b"--mixed-boundary--"));
+    }
+
+    #[test]
+    fn test_collection_survives_reencoding() {
+        let data = std::fs::read(fixture_path("sample1.eml")).unwrap();
+        let original = collection_hash(&data, "sha256").unwrap();
+
+        // a transport converting line endings does not change the members
+        let text = String::from_utf8(data).unwrap();
+        let unix = text.replace("\r\n", "\n");
+        let parts = mime_parts(unix.as_bytes()).unwrap();
+        original
+            .verify_parts(
+                parts.iter().map(|p| (p.uri.as_str(), p.content.as_slice())),
+                None,
+            )
+            .unwrap();
+
+        // nor does a relay adding trace fields
+        let relayed = format!("Received: from relay.example.com\r\n{}", text);
+        let parts = mime_parts(relayed.as_bytes()).unwrap();
+        original
+            .verify_parts(
+                parts.iter().map(|p| (p.uri.as_str(), p.content.as_slice())),
+                None,
+            )
+            .unwrap();
+
+        // but a changed attachment or header is detected
+        for tampered in [
+            text.replacen("iVBORw0KGgo", "iVBORw0KGgp", 1),
+            text.replacen("Subject: Signed", "Subject: Forged", 1),
+            text.replacen("filename=\"trip.png\"", "filename=\"trip.exe\"", 1),
+        ] {
+            let parts = mime_parts(tampered.as_bytes()).unwrap();
+            assert!(original
+                .verify_parts(
+                    parts.iter().map(|p| (p.uri.as_str(), p.content.as_slice())),
+                    None,
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_eml_sign_and_verify() {
+        use crate::{
+            openssl::temp_signer::get_temp_signer, status_tracker::DetailedStatusTracker,
+            store::Store, utils::test::create_test_claim,
+        };
+
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "signed.eml");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+
+        let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        store
+            .save_to_asset(&fixture_path("sample1.eml"), &signer, &op)
+            .unwrap();
+
+        let claim = store.provenance_claim().unwrap();
+        assert_eq!(claim.collection_hash_assertions().len(), 1);
+        assert!(claim.data_hash_assertions().is_empty());
+
+        let mut report = DetailedStatusTracker::new();
+        Store::load_from_asset(&op, true, &mut report).unwrap();
+        assert!(report.get_log().is_empty());
+
+        // changing an attachment breaks the binding
+        let text = std::fs::read_to_string(&op).unwrap();
+        std::fs::write(&op, text.replacen("iVBORw0KGgo", "iVBORw0KGgp", 1)).unwrap();
+        let mut report = DetailedStatusTracker::new();
+        let _ = Store::load_from_asset(&op, true, &mut report);
+        assert!(report
+            .get_log()
+            .iter()
+            .any(|item| item.validation_status.as_ref()
+                == Some(&ValidationStatusCode::AssertionCollectionHashMismatch)));
+    }
+}
@@ -0,0 +1,430 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Asset handler for TrueType and OpenType fonts.
+//!
+//! The manifest store is kept in a `C2PA` table placed after all other table data. The
+//! table ends with a word that brings its checksum to zero, so neither its table record
+//! nor the `checkSumAdjustment` of the `head` table depend on the manifest content and a
+//! single exclusion over the table data covers everything that changes when signing.
+//!
+//! WOFF2 fonts are not supported since their table data is Brotli compressed.
+
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use crate::asset_io::{AssetIO, CAILoader, CAIRead, HashBlockObjectType, HashObjectPositions};
+use crate::error::{Error, Result};
+
+const C2PA_TAG: [u8; 4] = *b"C2PA";
+const HEAD_TAG: [u8; 4] = *b"head";
+const SFNT_VERSIONS: [[u8; 4]; 3] = [[0, 1, 0, 0], *b"OTTO", *b"true"];
+const SFNT_HEADER_LEN: usize = 12;
+const TABLE_RECORD_LEN: usize = 16;
+const C2PA_HEADER_LEN: usize = 20;
+const C2PA_MAJOR_VERSION: u16 = 0;
+const C2PA_MINOR_VERSION: u16 = 1;
+const HEAD_ADJUSTMENT_OFFSET: usize = 8;
+const CHECKSUM_MAGIC: u32 = 0xb1b0_afba;
+
+fn font_error(msg: &str) -> Error {
+    Error::BadParam(format!("font invalid: {}", msg))
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u16> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+        .ok_or_else(|| font_error("truncated"))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| font_error("truncated"))
+}
+
+fn to_u32(value: usize) -> Result<u32> {
+    u32::try_from(value).map_err(|_err| font_error("table too large"))
+}
+
+// table checksum, the sum of the big endian words of the zero padded data
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn padded_len(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+struct TableRecord {
+    tag: [u8; 4],
+    checksum: u32,
+    offset: usize,
+    length: usize,
+}
+
+struct Font {
+    version: [u8; 4],
+    tables: Vec<TableRecord>,
+}
+
+impl Font {
+    fn parse(data: &[u8]) -> Result<Self> {
+        let mut version = [0u8; 4];
+        version.copy_from_slice(data.get(..4).ok_or_else(|| font_error("truncated"))?);
+        if !SFNT_VERSIONS.contains(&version) {
+            return Err(Error::UnsupportedType);
+        }
+
+        let count = read_u16(data, 4)? as usize;
+        let mut tables = Vec::with_capacity(count);
+        for i in 0..count {
+            let pos = SFNT_HEADER_LEN + i * TABLE_RECORD_LEN;
+            let mut tag = [0u8; 4];
+            tag.copy_from_slice(
+                data.get(pos..pos + 4)
+                    .ok_or_else(|| font_error("truncated"))?,
+            );
+            let record = TableRecord {
+                tag,
+                checksum: read_u32(data, pos + 4)?,
+                offset: read_u32(data, pos + 8)? as usize,
+                length: read_u32(data, pos + 12)? as usize,
+            };
+            match record.offset.checked_add(record.length) {
+                Some(end) if end <= data.len() => (),
+                _ => return Err(font_error("table out of range")),
+            }
+            tables.push(record);
+        }
+
+        Ok(Font { version, tables })
+    }
+
+    fn table(&self, tag: &[u8; 4]) -> Option<&TableRecord> {
+        self.tables.iter().find(|t| &t.tag == tag)
+    }
+}
+
+// C2PA table holding the manifest store, without an active manifest URI
+fn c2pa_table(store_bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut table = Vec::with_capacity(C2PA_HEADER_LEN + padded_len(store_bytes.len()) + 4);
+    table.extend_from_slice(&C2PA_MAJOR_VERSION.to_be_bytes());
+    table.extend_from_slice(&C2PA_MINOR_VERSION.to_be_bytes());
+    table.extend_from_slice(&0u32.to_be_bytes()); // active manifest URI offset
+    table.extend_from_slice(&0u16.to_be_bytes()); // active manifest URI length
+    table.extend_from_slice(&0u16.to_be_bytes()); // reserved
+    table.extend_from_slice(&to_u32(C2PA_HEADER_LEN)?.to_be_bytes());
+    table.extend_from_slice(&to_u32(store_bytes.len())?.to_be_bytes());
+    table.extend_from_slice(store_bytes);
+    table.resize(padded_len(table.len()), 0);
+
+    // balance the checksum so it is zero whatever the manifest holds
+    let balance = 0u32.wrapping_sub(checksum(&table));
+    table.extend_from_slice(&balance.to_be_bytes());
+    Ok(table)
+}
+
+fn read_manifest(data: &[u8]) -> Result<&[u8]> {
+    let font = Font::parse(data)?;
+    let record = font.table(&C2PA_TAG).ok_or(Error::JumbfNotFound)?;
+    let table = &data[record.offset..record.offset + record.length];
+
+    if read_u16(table, 0)? != C2PA_MAJOR_VERSION {
+        return Err(font_error("unsupported C2PA table version"));
+    }
+    let start = read_u32(table, 12)? as usize;
+    let end = start
+        .checked_add(read_u32(table, 16)? as usize)
+        .ok_or_else(|| font_error("manifest store out of range"))?;
+    table
+        .get(start..end)
+        .ok_or_else(|| font_error("manifest store out of range"))
+}
+
+// Rebuild the font with the manifest store in a C2PA table placed after all other tables.
+fn write_manifest(data: &[u8], store_bytes: &[u8]) -> Result<Vec<u8>> {
+    let font = Font::parse(data)?;
+
+    // keep the table data in its original order
+    let mut tables: Vec<&TableRecord> = font.tables.iter().filter(|t| t.tag != C2PA_TAG).collect();
+    tables.sort_by_key(|t| t.offset);
+
+    let count = tables.len() + 1;
+    let num_tables = u16::try_from(count).map_err(|_err| font_error("too many tables"))?;
+    let dir_len = SFNT_HEADER_LEN + count * TABLE_RECORD_LEN;
+
+    let mut body = Vec::with_capacity(data.len() + store_bytes.len());
+    let mut records = Vec::with_capacity(count);
+    for table in tables {
+        records.push(TableRecord {
+            tag: table.tag,
+            checksum: table.checksum,
+            offset: dir_len + body.len(),
+            length: table.length,
+        });
+        body.extend_from_slice(&data[table.offset..table.offset + table.length]);
+        body.resize(padded_len(body.len()), 0);
+    }
+
+    let c2pa = c2pa_table(store_bytes)?;
+    records.push(TableRecord {
+        tag: C2PA_TAG,
+        checksum: checksum(&c2pa),
+        offset: dir_len + body.len(),
+        length: c2pa.len(),
+    });
+    body.extend_from_slice(&c2pa);
+
+    // table records are sorted by tag
+    records.sort_by_key(|r| r.tag);
+
+    let mut entry_selector = 0u16;
+    while 2usize.pow(entry_selector as u32 + 1) <= count {
+        entry_selector += 1;
+    }
+    // the directory fields only hold up to 4095 tables
+    let search_range = 2usize.pow(entry_selector as u32) * TABLE_RECORD_LEN;
+    let range_shift = count * TABLE_RECORD_LEN - search_range;
+    let search_range = u16::try_from(search_range).map_err(|_err| font_error("too many tables"))?;
+    let range_shift = u16::try_from(range_shift).map_err(|_err| font_error("too many tables"))?;
+
+    let mut out = Vec::with_capacity(dir_len + body.len());
+    out.extend_from_slice(&font.version);
+    out.extend_from_slice(&num_tables.to_be_bytes());
+    out.extend_from_slice(&search_range.to_be_bytes());
+    out.extend_from_slice(&entry_selector.to_be_bytes());
+    out.extend_from_slice(&range_shift.to_be_bytes());
+    for record in &records {
+        out.extend_from_slice(&record.tag);
+        out.extend_from_slice(&record.checksum.to_be_bytes());
+        out.extend_from_slice(&to_u32(record.offset)?.to_be_bytes());
+        out.extend_from_slice(&to_u32(record.length)?.to_be_bytes());
+    }
+    out.extend_from_slice(&body);
+
+    // update the whole font checksum, which does not depend on the C2PA table
+    if let Some(head) = records
+        .iter()
+        .find(|r| r.tag == HEAD_TAG && r.length >= HEAD_ADJUSTMENT_OFFSET + 4)
+    {
+        let pos = head.offset + HEAD_ADJUSTMENT_OFFSET;
+        out[pos..pos + 4].copy_from_slice(&[0u8; 4]);
+        let adjustment = CHECKSUM_MAGIC.wrapping_sub(checksum(&out));
+        out[pos..pos + 4].copy_from_slice(&adjustment.to_be_bytes());
+    }
+
+    Ok(out)
+}
+
+pub struct FontIO {}
+
+impl CAILoader for FontIO {
+    fn read_cai(&self, asset_reader: &mut dyn CAIRead) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        asset_reader.seek(SeekFrom::Start(0))?;
+        asset_reader.read_to_end(&mut data)?;
+        read_manifest(&data).map(|store| store.to_vec())
+    }
+
+    // the manifest store is borrowed from the C2PA table
+    fn read_cai_from_slice<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        read_manifest(data).map(Cow::Borrowed)
+    }
+
+    // fonts have no XMP packet to reference the manifest
+    fn read_xmp(&self, _asset_reader: &mut dyn CAIRead) -> Option<String> {
+        None
+    }
+}
+
+impl AssetIO for FontIO {
+    fn read_cai_store(&self, asset_path: &Path) -> Result<Vec<u8>> {
+        let data = fs::read(asset_path)?;
+        read_manifest(&data).map(|store| store.to_vec())
+    }
+
+    fn save_cai_store(&self, asset_path: &Path, store_bytes: &[u8]) -> Result<()> {
+        let data = fs::read(asset_path)?;
+        let output = write_manifest(&data, store_bytes)?;
+        fs::write(asset_path, output)?;
+        Ok(())
+    }
+
+    fn get_object_locations(&self, asset_path: &Path) -> Result<Vec<HashObjectPositions>> {
+        let data = fs::read(asset_path)?;
+
+        // lay the font out with the C2PA table last, using an empty table if there is none yet
+        let store_bytes = match read_manifest(&data) {
+            Ok(store_bytes) => store_bytes,
+            Err(Error::JumbfNotFound) => &[],
+            Err(e) => return Err(e),
+        };
+        let output = write_manifest(&data, store_bytes)?;
+        if output != data {
+            fs::write(asset_path, &output)?;
+        }
+
+        let font = Font::parse(&output)?;
+        let record = font.table(&C2PA_TAG).ok_or(Error::JumbfNotFound)?;
+
+        Ok(vec![
+            HashObjectPositions {
+                offset: 0,
+                length: record.offset,
+                htype: HashBlockObjectType::Other,
+            },
+            HashObjectPositions {
+                offset: record.offset,
+                length: record.length,
+                htype: HashBlockObjectType::Cai,
+            },
+        ])
+    }
+
+    fn supports_xmp(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::utils::test::{fixture_path, temp_dir_path};
+
+    #[test]
+    fn test_font_too_many_tables() {
+        // a font with 4095 empty tables, the most the directory fields hold, has no room left
+        let count = 4095;
+        let mut data = vec![0, 1, 0, 0];
+        data.extend_from_slice(&(count as u16).to_be_bytes());
+        data.extend_from_slice(&[0u8; 6]);
+        for i in 0..count {
+            data.extend_from_slice(&(i as u32).to_be_bytes()); // tag
+            data.extend_from_slice(&0u32.to_be_bytes()); // checksum
+            data.extend_from_slice(&0u32.to_be_bytes()); // offset
+            data.extend_from_slice(&0u32.to_be_bytes()); // length
+        }
+
+        assert!(matches!(
+            write_manifest(&data, b"manifest"),
+            Err(Error::BadParam(_))
+        ));
+    }
+
+    #[test]
+    fn test_font_save_and_read() {
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "font.ttf");
+        std::fs::copy(fixture_path("font.ttf"), &op).unwrap();
+
+        let font_io = FontIO {};
+        assert!(matches!(
+            font_io.read_cai_store(&op),
+            Err(Error::JumbfNotFound)
+        ));
+
+        font_io.save_cai_store(&op, b"first manifest").unwrap();
+        assert_eq!(font_io.read_cai_store(&op).unwrap(), b"first manifest");
+        font_io.save_cai_store(&op, b"another manifest").unwrap();
+
+        let data = std::fs::read(&op).unwrap();
+        assert_eq!(read_manifest(&data).unwrap(), b"another manifest");
+        assert!(matches!(
+            font_io.read_cai_from_slice(&data).unwrap(),
+            Cow::Borrowed(b"another manifest")
+        ));
+
+        // the original tables are unchanged and the font checksum is valid
+        let original = std::fs::read(fixture_path("font.ttf")).unwrap();
+        let original_font = Font::parse(&original).unwrap();
+        let font = Font::parse(&data).unwrap();
+        assert_eq!(font.tables.len(), original_font.tables.len() + 1);
+        for table in original_font.tables.iter().filter(|t| t.tag != HEAD_TAG) {
+            let copy = font.table(&table.tag).unwrap();
+            assert_eq!(
+                &data[copy.offset..copy.offset + copy.length],
+                &original[table.offset..table.offset + table.length]
+            );
+        }
+        assert_eq!(checksum(&data), CHECKSUM_MAGIC);
+        assert_eq!(font.table(&C2PA_TAG).unwrap().checksum, 0);
+    }
+
+    #[test]
+    fn test_font_object_locations() {
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "font.ttf");
+        std::fs::copy(fixture_path("font.ttf"), &op).unwrap();
+
+        let font_io = FontIO {};
+        font_io.save_cai_store(&op, &[1u8; 100]).unwrap();
+        let positions = font_io.get_object_locations(&op).unwrap();
+        let before = std::fs::read(&op).unwrap();
+
+        // replacing the manifest with one of the same size only changes the excluded range
+        font_io.save_cai_store(&op, &[2u8; 100]).unwrap();
+        let after = std::fs::read(&op).unwrap();
+        assert_eq!(before.len(), after.len());
+
+        let cai = positions
+            .iter()
+            .find(|p| p.htype == HashBlockObjectType::Cai)
+            .unwrap();
+        assert_eq!(cai.offset + cai.length, after.len());
+        assert_eq!(before[..cai.offset], after[..cai.offset]);
+        assert_ne!(before[cai.offset..], after[cai.offset..]);
+    }
+
+    #[test]
+    fn test_woff2_unsupported() {
+        let mut data = std::fs::read(fixture_path("font.ttf")).unwrap();
+        data[..4].copy_from_slice(b"wOF2");
+        assert!(matches!(read_manifest(&data), Err(Error::UnsupportedType)));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_font_sign_and_verify() {
+        use crate::{
+            openssl::temp_signer::get_temp_signer, status_tracker::DetailedStatusTracker,
+            store::Store, utils::test::create_test_claim,
+        };
+
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "signed.ttf");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+
+        let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        store
+            .save_to_asset(&fixture_path("font.ttf"), &signer, &op)
+            .unwrap();
+
+        let mut report = DetailedStatusTracker::new();
+        let restored = Store::load_from_asset(&op, true, &mut report).unwrap();
+        assert!(report.get_log().is_empty());
+        assert_eq!(restored.provenance_label(), store.provenance_label());
+    }
+}
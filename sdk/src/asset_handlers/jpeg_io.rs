@@ -11,11 +11,12 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use std::borrow::Cow;
 use std::fs::{read, File};
 use std::io::Cursor;
 use std::path::*;
 
-use byteorder::{BigEndian, ReadBytesExt};
+use byteorder::{BigEndian, ByteOrder, ReadBytesExt};
 
 use img_parts::jpeg::{markers, Jpeg, JpegSegment};
 use img_parts::Bytes;
@@ -23,6 +24,7 @@ use img_parts::DynImage;
 
 use crate::asset_io::{AssetIO, CAILoader, CAIRead, HashBlockObjectType, HashObjectPositions};
 use crate::error::{wrap_io_err, Error, Result};
+use crate::xmp_inmemory_utils::decode_xmp_packet;
 
 const XMP_SIGNATURE: &[u8] = b"http://ns.adobe.com/xap/1.0/";
 const XMP_SIGNATURE_BUFFER_SIZE: usize = XMP_SIGNATURE.len() + 1; // skip null or space char at end
@@ -31,6 +33,10 @@ const MAX_JPEG_MARKER_SIZE: usize = 64000; // technically it's 64K but a bit sma
 
 const C2PA_MARKER: [u8; 4] = [0x63, 0x32, 0x70, 0x61];
 
+// position of the marker in the first segment, after the JPEG XT header,
+// the superbox header and the description box header
+const C2PA_MARKER_RANGE: std::ops::Range<usize> = 24..28;
+
 fn vec_compare(va: &[u8], vb: &[u8]) -> bool {
     (va.len() == vb.len()) &&  // zip stops at the shortest
      va.iter()
@@ -38,16 +44,84 @@ fn vec_compare(va: &[u8], vb: &[u8]) -> bool {
        .all(|(a,b)| a == b)
 }
 
+// Returns the parts of the CAI block held by the contents of APP11 segments, in order.
+// The first segment holds the JUMBF from its superbox header, the ones continuing it
+// repeat that header, so their part starts after it.
+fn cai_parts<'a>(segments: impl Iterator<Item = &'a [u8]>) -> Vec<&'a [u8]> {
+    let mut parts = Vec::new();
+    let mut cai_en: &[u8] = &[];
+    let mut cai_seg_cnt: u32 = 0;
+    for raw_bytes in segments {
+        // we need at least 16 bytes in each segment for CAI
+        if raw_bytes.len() <= 16 {
+            continue;
+        }
+        let en = &raw_bytes[2..4];
+        let z = BigEndian::read_u32(&raw_bytes[4..8]);
+
+        if cai_seg_cnt > 0 && vec_compare(cai_en, en) {
+            // make sure this is a cai segment for additional segments,
+            if z <= cai_seg_cnt {
+                // this a non contiguous segment with same "en"" so a bad set of data
+                // reset and continue to search
+                cai_en = &[];
+                continue;
+            }
+            // take out LBox & TBox
+            parts.push(&raw_bytes[16..]);
+            cai_seg_cnt += 1;
+        } else if is_c2pa_first_segment(raw_bytes) {
+            // check if this is a CAI JUMBF block
+            parts.push(&raw_bytes[8..]);
+            cai_seg_cnt = 1;
+            cai_en = en; // store the identifier
+        }
+    }
+    parts
+}
+
+// Returns the contents of the APP11 segments of the JPEG `data`, borrowed from it.
+// Only the segments before the scan are read, which is where the CAI block is stored.
+fn app11_segments(data: &[u8]) -> Result<Vec<&[u8]>> {
+    let parse_error = || Error::BadParam("Could not parse input image".to_owned());
+    if data.get(..2) != Some(&[0xff, markers::SOI][..]) {
+        return Err(parse_error());
+    }
+
+    let mut segments = Vec::new();
+    let mut pos = 2;
+    while let Some(&[0xff, marker]) = data.get(pos..pos + 2) {
+        match marker {
+            0xff => pos += 1, // fill byte
+            markers::SOS | markers::EOI => break,
+            0x01 | markers::RST0..=markers::RST7 => pos += 2, // TEM and RSTn have no length
+            _ => {
+                let len = data
+                    .get(pos + 2..pos + 4)
+                    .map(BigEndian::read_u16)
+                    .ok_or_else(parse_error)? as usize;
+                let contents = data.get(pos + 4..pos + 2 + len).ok_or_else(parse_error)?;
+                if marker == markers::APP11 {
+                    segments.push(contents);
+                }
+                pos += 2 + len;
+            }
+        }
+    }
+    Ok(segments)
+}
+
+// segments are untrusted, so one too short to hold the marker is not a C2PA segment
+fn is_c2pa_first_segment(raw_bytes: &[u8]) -> bool {
+    raw_bytes.get(C2PA_MARKER_RANGE) == Some(&C2PA_MARKER[..])
+}
+
 // todo decide if want to keep this just for in-memory use cases
 fn extract_xmp(seg: &JpegSegment) -> Option<String> {
     let contents = seg.contents();
-    if contents.starts_with(XMP_SIGNATURE) {
+    if contents.starts_with(XMP_SIGNATURE) && contents.len() >= XMP_SIGNATURE_BUFFER_SIZE {
         let rest = contents.slice(XMP_SIGNATURE_BUFFER_SIZE..);
-        if let Ok(c) = String::from_utf8(rest.to_vec()) {
-            Some(c)
-        } else {
-            None
-        }
+        Some(decode_xmp_packet(&rest))
     } else {
         None
     }
@@ -113,8 +187,7 @@ fn get_cai_segments(jpeg: &img_parts::jpeg::Jpeg) -> Result<Vec<usize>> {
                 cai_segs.push(i);
             } else {
                 // check if this is a CAI JUMBF block
-                let jumb_type = raw_vec.as_mut_slice()[24..28].to_vec();
-                let is_cai = vec_compare(&C2PA_MARKER, &jumb_type);
+                let is_cai = is_c2pa_first_segment(&raw_vec);
                 if is_cai {
                     cai_segs.push(i);
                     cai_seg_cnt = 1;
@@ -141,8 +214,6 @@ pub struct JpegIO {}
 
 impl CAILoader for JpegIO {
     fn read_cai(&self, asset_reader: &mut dyn CAIRead) -> Result<Vec<u8>> {
-        let mut buffer: Vec<u8> = Vec::new();
-
         // load the bytes
         let mut buf: Vec<u8> = Vec::new();
         asset_reader.read_to_end(&mut buf).map_err(Error::IoError)?;
@@ -150,56 +221,18 @@ impl CAILoader for JpegIO {
         let dimg_opt = DynImage::from_bytes(buf.into())
             .map_err(|_err| Error::BadParam("Could not parse input image".to_owned()))?;
 
-        if let Some(dimg) = dimg_opt {
-            match dimg {
-                DynImage::Jpeg(jpeg) => {
-                    let app11 = jpeg.segments_by_marker(markers::APP11);
-                    let mut cai_en: Vec<u8> = Vec::new();
-                    let mut cai_seg_cnt: u32 = 0;
-                    for (_i, segment) in app11.enumerate() {
-                        let raw_bytes = segment.contents();
-                        if raw_bytes.len() > 16 {
-                            // we need at least 16 bytes in each segment for CAI
-                            let mut raw_vec = raw_bytes.to_vec();
-                            let _ci = raw_vec.as_mut_slice()[0..2].to_vec();
-                            let en = raw_vec.as_mut_slice()[2..4].to_vec();
-                            let mut z_vec = Cursor::new(raw_vec.as_mut_slice()[4..8].to_vec());
-                            let z = z_vec.read_u32::<BigEndian>()?;
-
-                            let is_cai_continuation = vec_compare(&cai_en, &en);
-
-                            if cai_seg_cnt > 0 && is_cai_continuation {
-                                // make sure this is a cai segment for additional segments,
-                                if z <= cai_seg_cnt {
-                                    // this a non contiguous segment with same "en"" so a bad set of data
-                                    // reset and continue to search
-                                    cai_en = Vec::new();
-                                    continue;
-                                }
-                                // take out LBox & TBox
-                                buffer.append(&mut raw_vec.as_mut_slice()[16..].to_vec());
-
-                                cai_seg_cnt += 1;
-                            } else {
-                                // check if this is a CAI JUMBF block
-                                let jumb_type = raw_vec.as_mut_slice()[24..28].to_vec();
-                                let is_cai = vec_compare(&C2PA_MARKER, &jumb_type);
-                                if is_cai {
-                                    buffer.append(&mut raw_vec.as_mut_slice()[8..].to_vec());
-                                    cai_seg_cnt = 1;
-                                    cai_en = en.clone(); // store the identifier
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => return Err(Error::BadParam("Unknown image format".to_owned())),
-            };
-        } else {
-            return Err(Error::BadParam(
-                "Image type not supported by handler".to_owned(),
-            ));
-        }
+        let buffer = match dimg_opt {
+            Some(DynImage::Jpeg(jpeg)) => {
+                let app11 = jpeg.segments_by_marker(markers::APP11);
+                cai_parts(app11.map(|segment| &segment.contents()[..])).concat()
+            }
+            Some(_) => return Err(Error::BadParam("Unknown image format".to_owned())),
+            None => {
+                return Err(Error::BadParam(
+                    "Image type not supported by handler".to_owned(),
+                ))
+            }
+        };
 
         if buffer.is_empty() {
             return Err(Error::JumbfNotFound);
@@ -208,6 +241,15 @@ impl CAILoader for JpegIO {
         Ok(buffer)
     }
 
+    // the CAI block is borrowed from the asset when a single segment holds it
+    fn read_cai_from_slice<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        match cai_parts(app11_segments(data)?.into_iter()).as_slice() {
+            [] => Err(Error::JumbfNotFound),
+            &[part] => Ok(Cow::Borrowed(part)),
+            parts => Ok(Cow::Owned(parts.concat())),
+        }
+    }
+
     // Get XMP block
     fn read_xmp(&self, asset_reader: &mut dyn CAIRead) -> Option<String> {
         // load the bytes
@@ -337,8 +379,7 @@ impl AssetIO for JpegIO {
                                     positions.push(v);
                                 } else {
                                     // check if this is a CAI JUMBF block
-                                    let jumb_type = raw_vec.as_mut_slice()[24..28].to_vec();
-                                    let is_cai = vec_compare(&C2PA_MARKER, &jumb_type);
+                                    let is_cai = is_c2pa_first_segment(&raw_vec);
                                     if is_cai {
                                         cai_seg_cnt = 1;
                                         cai_en = en.clone(); // store the identifier
@@ -416,5 +457,57 @@ pub mod tests {
         let seg = JpegSegment::new_with_contents(markers::APP1, contents);
         let result = extract_xmp(&seg);
         assert_eq!(result, None);
+
+        let contents = Bytes::from_static(XMP_SIGNATURE);
+        let seg = JpegSegment::new_with_contents(markers::APP1, contents);
+        let result = extract_xmp(&seg);
+        assert_eq!(result, None);
+    }
+
+    #[test]
+    fn test_short_app11_segment() {
+        let image = include_bytes!("../../tests/fixtures/earth_apollo17.jpg");
+        let mut jpeg = Jpeg::from_bytes(Bytes::from_static(image)).unwrap();
+
+        // long enough for the JPEG XT header but too short to hold the C2PA marker
+        let contents = Bytes::from_static(&[
+            0x4A, 0x50, 0x02, 0x11, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]);
+        let seg = JpegSegment::new_with_contents(markers::APP11, contents);
+        jpeg.segments_mut().insert(1, seg);
+        let mut output = Vec::new();
+        jpeg.encoder().write_to(&mut output).unwrap();
+
+        assert!(matches!(
+            JpegIO {}.read_cai_from_slice(&output),
+            Err(Error::JumbfNotFound)
+        ));
+        assert!(matches!(
+            JpegIO {}.read_cai(&mut Cursor::new(output)),
+            Err(Error::JumbfNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_read_cai_from_slice() {
+        // a CAI block in a single segment is borrowed from the asset
+        let image = include_bytes!("../../tests/fixtures/C.jpg");
+        let cai = JpegIO {}.read_cai_from_slice(image).unwrap();
+        assert!(matches!(cai, Cow::Borrowed(_)));
+        assert_eq!(
+            cai.as_ref(),
+            JpegIO {}.read_cai(&mut Cursor::new(&image[..])).unwrap()
+        );
+
+        // one split across segments is joined
+        let image = include_bytes!("../../tests/fixtures/CA.jpg");
+        let cai = JpegIO {}.read_cai_from_slice(image).unwrap();
+        assert!(matches!(cai, Cow::Owned(_)));
+        assert_eq!(
+            cai.as_ref(),
+            JpegIO {}.read_cai(&mut Cursor::new(&image[..])).unwrap()
+        );
+
+        assert!(JpegIO {}.read_cai_from_slice(b"not a jpeg").is_err());
     }
 }
@@ -12,5 +12,8 @@
 // each license.
 
 pub mod c2pa_io;
+pub mod eml_io;
+pub mod font_io;
 pub mod jpeg_io;
 pub mod png_io;
+pub mod zip_io;
@@ -11,6 +11,7 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use std::borrow::Cow;
 use std::fs::File;
 use std::io::{Cursor, SeekFrom};
 use std::path::*;
@@ -104,13 +105,16 @@ fn get_png_chunk_positions(f: &mut dyn CAIRead) -> Result<Vec<PngChunkPos>> {
     Ok(chunk_positions)
 }
 
-fn get_cai_data(f: &mut dyn CAIRead) -> Result<Vec<u8>> {
+fn get_cai_chunk(f: &mut dyn CAIRead) -> Result<PngChunkPos> {
     let ps = get_png_chunk_positions(f)?;
 
-    let pcp = ps
-        .into_iter()
+    ps.into_iter()
         .find(|pcp| pcp.name == CAI_CHUNK)
-        .ok_or(Error::JumbfNotFound)?;
+        .ok_or(Error::JumbfNotFound)
+}
+
+fn get_cai_data(f: &mut dyn CAIRead) -> Result<Vec<u8>> {
+    let pcp = get_cai_chunk(f)?;
 
     let length: usize = pcp.length as usize;
 
@@ -143,6 +147,16 @@ impl CAILoader for PngIO {
         Ok(cai_data)
     }
 
+    // the CAI block is the data of a single chunk
+    fn read_cai_from_slice<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        let pcp = get_cai_chunk(&mut Cursor::new(data))?;
+
+        let start = pcp.start as usize + 8; // skip chunk length(4) + name(4)
+        data.get(start..start + pcp.length as usize)
+            .map(Cow::Borrowed)
+            .ok_or_else(|| Error::BadParam("PNG out of range".to_string()))
+    }
+
     // Get XMP block
     fn read_xmp(&self, asset_reader: &mut dyn CAIRead) -> Option<String> {
         let chunks = png_pong::Decoder::new(asset_reader).ok()?.into_chunks();
@@ -281,6 +295,7 @@ pub mod tests {
     use twoway::find_bytes;
 
     use super::*;
+    use crate::utils::test::{fixture_path, temp_dir_path};
 
     #[test]
     fn test_png_parse() {
@@ -306,4 +321,31 @@ pub mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_read_cai_from_slice() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let ap = temp_dir_path(&temp_dir, "libpng-test.png");
+        std::fs::copy(fixture_path("libpng-test.png"), &ap).unwrap();
+
+        let png_io = PngIO {};
+        png_io.save_cai_store(&ap, b"manifest store").unwrap();
+        let png_bytes = std::fs::read(&ap).unwrap();
+
+        // the CAI block is borrowed from the asset rather than copied
+        let cai = png_io.read_cai_from_slice(&png_bytes).unwrap();
+        assert!(matches!(cai, Cow::Borrowed(_)));
+        assert_eq!(cai.as_ref(), b"manifest store");
+        assert_eq!(
+            png_io.read_cai(&mut Cursor::new(&png_bytes[..])).unwrap(),
+            cai.into_owned()
+        );
+
+        // a PNG without a CAI chunk has no CAI block to borrow
+        let png_bytes = std::fs::read(fixture_path("libpng-test.png")).unwrap();
+        assert!(matches!(
+            png_io.read_cai_from_slice(&png_bytes),
+            Err(Error::JumbfNotFound)
+        ));
+    }
 }
@@ -0,0 +1,634 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Asset handler for ZIP based formats such as OOXML documents and EPUB publications.
+//!
+//! The manifest store is kept uncompressed in the archive entry [`MANIFEST_ENTRY`].
+//! When the store is written the archive is laid out so that the manifest entry is
+//! the last entry and its central directory record is the first one, so the other
+//! entries and their records do not move when the manifest changes.
+//!
+//! The manifest is bound to the archive by a collection hash: each other entry is
+//! hashed from its local file header to the next entry, and the central directory
+//! records of those entries are hashed together.
+
+use std::convert::TryFrom;
+use std::fs;
+use std::io::SeekFrom;
+use std::path::Path;
+
+use crate::assertions::CollectionHash;
+use crate::asset_io::{AssetIO, CAILoader, CAIRead, HashBlockObjectType, HashObjectPositions};
+use crate::error::{Error, Result};
+
+/// Name of the archive entry holding the manifest store
+pub const MANIFEST_ENTRY: &str = "META-INF/content_credential.c2pa";
+
+const LOCAL_HEADER_SIG: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIG: u32 = 0x0201_4b50;
+const END_OF_DIR_SIG: u32 = 0x0605_4b50;
+const LOCAL_HEADER_LEN: usize = 30;
+const CENTRAL_HEADER_LEN: usize = 46;
+const END_OF_DIR_LEN: usize = 22;
+const MAX_COMMENT_LEN: usize = 0xffff;
+const VERSION_STORED: u16 = 10;
+const METHOD_STORED: u16 = 0;
+const DOS_DATE_1980: u16 = 0x0021;
+
+fn zip_error(msg: &str) -> Error {
+    Error::BadParam(format!("ZIP invalid: {}", msg))
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u16> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        .ok_or_else(|| zip_error("truncated"))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .ok_or_else(|| zip_error("truncated"))
+}
+
+fn to_u16(value: usize) -> Result<u16> {
+    u16::try_from(value).map_err(|_err| Error::BadParam("ZIP64 is not supported".to_string()))
+}
+
+fn to_u32(value: usize) -> Result<u32> {
+    u32::try_from(value).map_err(|_err| Error::BadParam("ZIP64 is not supported".to_string()))
+}
+
+// CRC-32 as used by ZIP (reflected, polynomial 0xedb88320)
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (!(crc & 1)).wrapping_add(1);
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// a central directory record along with the fields needed to relocate its entry
+struct CentralRecord {
+    name: Vec<u8>,
+    method: u16,
+    compressed_size: usize,
+    local_offset: usize,
+    raw: Vec<u8>,
+}
+
+impl CentralRecord {
+    fn is_manifest(&self) -> bool {
+        self.name == MANIFEST_ENTRY.as_bytes()
+    }
+
+    // copy of the record pointing at a new local header offset
+    fn relocated(&self, local_offset: usize) -> Result<Vec<u8>> {
+        let mut raw = self.raw.clone();
+        raw[42..46].copy_from_slice(&to_u32(local_offset)?.to_le_bytes());
+        Ok(raw)
+    }
+}
+
+struct ZipDirectory {
+    records: Vec<CentralRecord>,
+    offset: usize,
+    comment: Vec<u8>,
+}
+
+impl ZipDirectory {
+    fn parse(data: &[u8]) -> Result<Self> {
+        // the end of central directory record is followed by a comment of up to 64K
+        if data.len() < END_OF_DIR_LEN {
+            return Err(zip_error("missing end of central directory"));
+        }
+        let last = data.len() - END_OF_DIR_LEN;
+        let first = last.saturating_sub(MAX_COMMENT_LEN);
+        let end = (first..=last)
+            .rev()
+            .find(|pos| {
+                read_u32(data, *pos).ok() == Some(END_OF_DIR_SIG)
+                    && read_u16(data, pos + 20).ok().map(|len| len as usize)
+                        == Some(data.len() - pos - END_OF_DIR_LEN)
+            })
+            .ok_or_else(|| zip_error("missing end of central directory"))?;
+
+        let count = read_u16(data, end + 10)?;
+        let size = read_u32(data, end + 12)?;
+        let offset = read_u32(data, end + 16)?;
+        if count == 0xffff || size == 0xffff_ffff || offset == 0xffff_ffff {
+            return Err(Error::BadParam("ZIP64 is not supported".to_string()));
+        }
+        let offset = offset as usize;
+        if offset + size as usize > end {
+            return Err(zip_error("central directory out of range"));
+        }
+
+        let mut records = Vec::with_capacity(count as usize);
+        let mut pos = offset;
+        for _ in 0..count {
+            if read_u32(data, pos)? != CENTRAL_HEADER_SIG {
+                return Err(zip_error("bad central directory record"));
+            }
+            let name_len = read_u16(data, pos + 28)? as usize;
+            let extra_len = read_u16(data, pos + 30)? as usize;
+            let comment_len = read_u16(data, pos + 32)? as usize;
+            let record_end = pos + CENTRAL_HEADER_LEN + name_len + extra_len + comment_len;
+            if record_end > end {
+                return Err(zip_error("central directory out of range"));
+            }
+
+            records.push(CentralRecord {
+                name: data[pos + CENTRAL_HEADER_LEN..pos + CENTRAL_HEADER_LEN + name_len].to_vec(),
+                method: read_u16(data, pos + 10)?,
+                compressed_size: read_u32(data, pos + 20)? as usize,
+                local_offset: read_u32(data, pos + 42)? as usize,
+                raw: data[pos..record_end].to_vec(),
+            });
+            pos = record_end;
+        }
+
+        Ok(ZipDirectory {
+            records,
+            offset,
+            comment: data[end + END_OF_DIR_LEN..].to_vec(),
+        })
+    }
+
+    fn manifest(&self) -> Option<&CentralRecord> {
+        self.records.iter().find(|r| r.is_manifest())
+    }
+}
+
+// range of the entry data following its local header
+fn entry_data_range(data: &[u8], record: &CentralRecord) -> Result<(usize, usize)> {
+    let pos = record.local_offset;
+    if read_u32(data, pos)? != LOCAL_HEADER_SIG {
+        return Err(zip_error("bad local file header"));
+    }
+    let name_len = read_u16(data, pos + 26)? as usize;
+    let extra_len = read_u16(data, pos + 28)? as usize;
+    let start = pos + LOCAL_HEADER_LEN + name_len + extra_len;
+    let end = start + record.compressed_size;
+    if end > data.len() {
+        return Err(zip_error("entry out of range"));
+    }
+    Ok((start, end))
+}
+
+fn read_manifest(data: &[u8]) -> Result<Vec<u8>> {
+    let dir = ZipDirectory::parse(data)?;
+    let record = dir.manifest().ok_or(Error::JumbfNotFound)?;
+    if record.method != METHOD_STORED {
+        return Err(Error::BadParam(
+            "ZIP manifest entry must be stored uncompressed".to_string(),
+        ));
+    }
+    let (start, end) = entry_data_range(data, record)?;
+    Ok(data[start..end].to_vec())
+}
+
+// header fields shared by the local header and central directory record of the manifest
+fn manifest_header_fields(out: &mut Vec<u8>, store_bytes: &[u8]) -> Result<()> {
+    let len = to_u32(store_bytes.len())?;
+    out.extend_from_slice(&0u16.to_le_bytes()); // flags
+    out.extend_from_slice(&METHOD_STORED.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // modification time
+    out.extend_from_slice(&DOS_DATE_1980.to_le_bytes());
+    out.extend_from_slice(&crc32(store_bytes).to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(&to_u16(MANIFEST_ENTRY.len())?.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+    Ok(())
+}
+
+// an entry other than the manifest, with its central directory record relocated to where
+// the entry is placed when the manifest is written
+struct LaidOutEntry<'a> {
+    record: &'a CentralRecord,
+    // from the local header up to the next entry, keeping any data descriptor
+    bytes: &'a [u8],
+    relocated: Vec<u8>,
+}
+
+impl LaidOutEntry<'_> {
+    fn uri(&self) -> String {
+        String::from_utf8_lossy(&self.record.name).into_owned()
+    }
+}
+
+// The entries other than the manifest in archive order, and the length of anything
+// preceding the first entry, as laid out when the manifest is written
+fn layout<'a>(data: &'a [u8], dir: &'a ZipDirectory) -> Result<(usize, Vec<LaidOutEntry<'a>>)> {
+    // an entry runs from its local header up to the next entry or the central directory,
+    // which keeps any data descriptor with the entry
+    let mut boundaries: Vec<usize> = dir.records.iter().map(|r| r.local_offset).collect();
+    boundaries.push(dir.offset);
+    boundaries.sort_unstable();
+
+    let mut entries: Vec<&CentralRecord> =
+        dir.records.iter().filter(|r| !r.is_manifest()).collect();
+    entries.sort_by_key(|r| r.local_offset);
+
+    // keep anything preceding the first entry, e.g. a self extracting stub
+    let prefix_len = boundaries[0];
+
+    let mut offset = prefix_len;
+    let mut laid_out = Vec::with_capacity(entries.len());
+    for record in entries {
+        let end = boundaries
+            .iter()
+            .copied()
+            .find(|b| *b > record.local_offset)
+            .unwrap_or(dir.offset);
+        // entries must start before the central directory
+        if record.local_offset >= end || end > data.len() {
+            return Err(zip_error("entry out of range"));
+        }
+        laid_out.push(LaidOutEntry {
+            record,
+            bytes: &data[record.local_offset..end],
+            relocated: record.relocated(offset)?,
+        });
+        offset += end - record.local_offset;
+    }
+    Ok((prefix_len, laid_out))
+}
+
+/// Builds a collection hash covering every entry of a ZIP archive except the manifest,
+/// and the central directory records of those entries as they are laid out once the
+/// manifest is written.
+pub fn collection_hash(data: &[u8], alg: &str) -> Result<CollectionHash> {
+    let dir = ZipDirectory::parse(data)?;
+    let (_, entries) = layout(data, &dir)?;
+
+    let mut collection = CollectionHash::new(alg);
+    for entry in &entries {
+        collection.add_part(&entry.uri(), None, entry.bytes)?;
+    }
+    let records: Vec<u8> = entries
+        .iter()
+        .flat_map(|e| e.relocated.iter().copied())
+        .collect();
+    collection.set_zip_central_directory(&records)?;
+    Ok(collection)
+}
+
+/// Verifies a collection hash against the entries and central directory of a ZIP archive.
+pub(crate) fn verify_collection_hash(
+    collection_hash: &CollectionHash,
+    data: &[u8],
+    alg: &str,
+) -> Result<()> {
+    let dir = ZipDirectory::parse(data)?;
+    let (_, entries) = layout(data, &dir)?;
+
+    let uris: Vec<String> = entries.iter().map(LaidOutEntry::uri).collect();
+    collection_hash.verify_parts(
+        uris.iter()
+            .map(String::as_str)
+            .zip(entries.iter().map(|e| e.bytes)),
+        Some(alg),
+    )?;
+
+    // the records as stored, which are the relocated ones in an archive laid out for the manifest
+    let records: Vec<u8> = dir
+        .records
+        .iter()
+        .filter(|r| !r.is_manifest())
+        .flat_map(|r| r.raw.iter().copied())
+        .collect();
+    collection_hash.verify_zip_central_directory(&records, Some(alg))
+}
+
+// Rebuild the archive with the manifest store as its last entry and the manifest record
+// at the front of the central directory. Any existing manifest entry is dropped.
+fn write_manifest(data: &[u8], store_bytes: &[u8]) -> Result<Vec<u8>> {
+    let dir = ZipDirectory::parse(data)?;
+    let (prefix_len, entries) = layout(data, &dir)?;
+
+    let mut out = Vec::with_capacity(data.len() + store_bytes.len() + 2 * MANIFEST_ENTRY.len());
+    out.extend_from_slice(&data[..prefix_len]);
+    for entry in &entries {
+        out.extend_from_slice(entry.bytes);
+    }
+
+    // manifest entry
+    let manifest_offset = out.len();
+    out.extend_from_slice(&LOCAL_HEADER_SIG.to_le_bytes());
+    out.extend_from_slice(&VERSION_STORED.to_le_bytes());
+    manifest_header_fields(&mut out, store_bytes)?;
+    out.extend_from_slice(MANIFEST_ENTRY.as_bytes());
+    out.extend_from_slice(store_bytes);
+
+    // central directory, starting with the manifest record
+    let dir_offset = out.len();
+    out.extend_from_slice(&CENTRAL_HEADER_SIG.to_le_bytes());
+    out.extend_from_slice(&VERSION_STORED.to_le_bytes()); // version made by
+    out.extend_from_slice(&VERSION_STORED.to_le_bytes());
+    manifest_header_fields(&mut out, store_bytes)?;
+    out.extend_from_slice(&0u16.to_le_bytes()); // comment length
+    out.extend_from_slice(&0u16.to_le_bytes()); // disk number
+    out.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+    out.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+    out.extend_from_slice(&to_u32(manifest_offset)?.to_le_bytes());
+    out.extend_from_slice(MANIFEST_ENTRY.as_bytes());
+    for entry in &entries {
+        out.extend_from_slice(&entry.relocated);
+    }
+    let dir_size = out.len() - dir_offset;
+
+    // end of central directory
+    let count = to_u16(entries.len() + 1)?;
+    out.extend_from_slice(&END_OF_DIR_SIG.to_le_bytes());
+    out.extend_from_slice(&0u16.to_le_bytes()); // disk number
+    out.extend_from_slice(&0u16.to_le_bytes()); // disk with central directory
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&count.to_le_bytes());
+    out.extend_from_slice(&to_u32(dir_size)?.to_le_bytes());
+    out.extend_from_slice(&to_u32(dir_offset)?.to_le_bytes());
+    out.extend_from_slice(&to_u16(dir.comment.len())?.to_le_bytes());
+    out.extend_from_slice(&dir.comment);
+
+    Ok(out)
+}
+
+pub struct ZipIO {}
+
+impl CAILoader for ZipIO {
+    fn read_cai(&self, asset_reader: &mut dyn CAIRead) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+        asset_reader.seek(SeekFrom::Start(0))?;
+        asset_reader.read_to_end(&mut data)?;
+        read_manifest(&data)
+    }
+
+    // ZIP archives have no XMP packet to reference the manifest
+    fn read_xmp(&self, _asset_reader: &mut dyn CAIRead) -> Option<String> {
+        None
+    }
+}
+
+impl AssetIO for ZipIO {
+    fn read_cai_store(&self, asset_path: &Path) -> Result<Vec<u8>> {
+        let data = fs::read(asset_path)?;
+        read_manifest(&data)
+    }
+
+    fn save_cai_store(&self, asset_path: &Path, store_bytes: &[u8]) -> Result<()> {
+        let data = fs::read(asset_path)?;
+        let output = write_manifest(&data, store_bytes)?;
+        fs::write(asset_path, output)?;
+        Ok(())
+    }
+
+    fn get_object_locations(&self, asset_path: &Path) -> Result<Vec<HashObjectPositions>> {
+        let data = fs::read(asset_path)?;
+
+        // the locations once the archive is laid out with the manifest in place, using an
+        // empty entry if there is none yet; the file itself is not changed
+        let store_bytes = match read_manifest(&data) {
+            Ok(store_bytes) => store_bytes,
+            Err(Error::JumbfNotFound) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        let output = write_manifest(&data, &store_bytes)?;
+
+        let dir = ZipDirectory::parse(&output)?;
+        let record = dir.records.first().ok_or(Error::JumbfNotFound)?;
+        let start = record.local_offset;
+        let end = dir.offset + record.raw.len();
+
+        Ok(vec![
+            HashObjectPositions {
+                offset: 0,
+                length: start,
+                htype: HashBlockObjectType::Other,
+            },
+            HashObjectPositions {
+                offset: start,
+                length: end - start,
+                htype: HashBlockObjectType::Cai,
+            },
+            HashObjectPositions {
+                offset: end,
+                length: output.len() - end,
+                htype: HashBlockObjectType::Other,
+            },
+        ])
+    }
+
+    fn supports_xmp(&self) -> bool {
+        false
+    }
+
+    fn collection_hash(&self, asset_path: &Path, alg: &str) -> Result<Option<CollectionHash>> {
+        let data = fs::read(asset_path)?;
+        collection_hash(&data, alg).map(Some)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::utils::test::{fixture_path, temp_dir_path};
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_zip_save_and_read() {
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "sample1.docx");
+        std::fs::copy(fixture_path("sample1.docx"), &op).unwrap();
+
+        let zip_io = ZipIO {};
+        assert!(matches!(
+            zip_io.read_cai_store(&op),
+            Err(Error::JumbfNotFound)
+        ));
+
+        let entries = ZipDirectory::parse(&std::fs::read(&op).unwrap())
+            .unwrap()
+            .records
+            .len();
+
+        zip_io.save_cai_store(&op, b"first manifest").unwrap();
+        assert_eq!(zip_io.read_cai_store(&op).unwrap(), b"first manifest");
+
+        // replacing the store keeps a single manifest entry
+        zip_io
+            .save_cai_store(&op, b"second, longer manifest")
+            .unwrap();
+        assert_eq!(
+            zip_io.read_cai_store(&op).unwrap(),
+            b"second, longer manifest"
+        );
+
+        let data = std::fs::read(&op).unwrap();
+        let dir = ZipDirectory::parse(&data).unwrap();
+        assert_eq!(dir.records.len(), entries + 1);
+        assert!(dir.records[0].is_manifest());
+
+        // the other entries are still readable
+        for record in dir.records.iter().skip(1) {
+            let (start, end) = entry_data_range(&data, record).unwrap();
+            assert!(start <= end);
+        }
+
+        let mut reader = std::io::Cursor::new(data.as_slice());
+        assert_eq!(
+            zip_io.read_cai(&mut reader).unwrap(),
+            b"second, longer manifest"
+        );
+    }
+
+    #[test]
+    fn test_zip_entry_out_of_range() {
+        let mut data = std::fs::read(fixture_path("sample1.docx")).unwrap();
+        let dir = ZipDirectory::parse(&data).unwrap();
+
+        // point the first entry past the central directory
+        let bad_offset = to_u32(dir.offset + 1).unwrap().to_le_bytes();
+        data[dir.offset + 42..dir.offset + 46].copy_from_slice(&bad_offset);
+        assert!(matches!(
+            write_manifest(&data, b"manifest"),
+            Err(Error::BadParam(_))
+        ));
+    }
+
+    #[test]
+    fn test_zip_object_locations() {
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "sample1.docx");
+        std::fs::copy(fixture_path("sample1.docx"), &op).unwrap();
+
+        let zip_io = ZipIO {};
+        let original = std::fs::read(&op).unwrap();
+        let positions = zip_io.get_object_locations(&op).unwrap();
+
+        // the file is not changed, the locations are those of the archive laid out with an
+        // empty manifest entry
+        assert_eq!(std::fs::read(&op).unwrap(), original);
+        let data = write_manifest(&original, b"").unwrap();
+
+        let cai: Vec<_> = positions
+            .iter()
+            .filter(|p| p.htype == HashBlockObjectType::Cai)
+            .collect();
+        assert_eq!(cai.len(), 1);
+        assert_eq!(
+            positions.iter().map(|p| p.length).sum::<usize>(),
+            data.len()
+        );
+
+        // the excluded range covers the manifest entry and its record, nothing else
+        let dir = ZipDirectory::parse(&data).unwrap();
+        assert_eq!(cai[0].offset, dir.records[0].local_offset);
+        assert_eq!(
+            cai[0].offset + cai[0].length,
+            dir.offset + dir.records[0].raw.len()
+        );
+
+        // locations are those of the file once the layout is in place
+        zip_io.save_cai_store(&op, b"").unwrap();
+        assert_eq!(std::fs::read(&op).unwrap(), data);
+        let ranges = |positions: &[HashObjectPositions]| -> Vec<(usize, usize)> {
+            positions.iter().map(|p| (p.offset, p.length)).collect()
+        };
+        assert_eq!(
+            ranges(&zip_io.get_object_locations(&op).unwrap()),
+            ranges(&positions)
+        );
+    }
+
+    #[test]
+    fn test_zip_collection_hash() {
+        let data = std::fs::read(fixture_path("sample1.docx")).unwrap();
+        let collection = collection_hash(&data, "sha256").unwrap();
+        let entries = ZipDirectory::parse(&data).unwrap().records.len();
+        assert_eq!(collection.uris.len(), entries);
+        assert!(collection.zip_central_directory_hash.is_some());
+
+        // the hash is computed before the manifest is written and verified after
+        let signed = write_manifest(&data, b"manifest").unwrap();
+        verify_collection_hash(&collection, &signed, "sha256").unwrap();
+        let resigned = write_manifest(&signed, b"a longer manifest").unwrap();
+        verify_collection_hash(&collection, &resigned, "sha256").unwrap();
+
+        // a changed entry is detected
+        let dir = ZipDirectory::parse(&signed).unwrap();
+        let mut changed = signed.clone();
+        let record = dir
+            .records
+            .iter()
+            .max_by_key(|r| r.compressed_size)
+            .unwrap();
+        let (start, _end) = entry_data_range(&changed, record).unwrap();
+        changed[start] ^= 0xff;
+        assert!(matches!(
+            verify_collection_hash(&collection, &changed, "sha256"),
+            Err(Error::HashMismatch(_))
+        ));
+
+        // and so is changed entry metadata in the central directory
+        let mut changed = signed.clone();
+        let record = dir.offset + dir.records[0].raw.len();
+        changed[record + 38] ^= 0xff; // external attributes
+        assert!(matches!(
+            verify_collection_hash(&collection, &changed, "sha256"),
+            Err(Error::HashMismatch(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_zip_sign_and_verify() {
+        use crate::{
+            openssl::temp_signer::get_temp_signer, status_tracker::DetailedStatusTracker,
+            store::Store, utils::test::create_test_claim,
+        };
+
+        let temp_dir = tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "signed.docx");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+
+        let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        store
+            .save_to_asset(&fixture_path("sample1.docx"), &signer, &op)
+            .unwrap();
+
+        let mut report = DetailedStatusTracker::new();
+        let restored = Store::load_from_asset(&op, true, &mut report).unwrap();
+        assert!(report.get_log().is_empty());
+        assert_eq!(restored.provenance_label(), store.provenance_label());
+
+        // the archive is bound by a collection hash rather than a data hash
+        let claim = restored.provenance_claim().unwrap();
+        assert_eq!(claim.collection_hash_assertions().len(), 1);
+        assert!(claim.data_hash_assertions().is_empty());
+    }
+}
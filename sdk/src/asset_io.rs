@@ -11,8 +11,12 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::io::{Read, Seek};
+use std::{
+    borrow::Cow,
+    io::{Cursor, Read, Seek},
+};
 
+use crate::assertions::CollectionHash;
 use crate::error::Result;
 use std::{fmt, path::Path};
 #[derive(Clone, Debug, PartialEq)]
@@ -45,6 +49,12 @@ pub trait CAILoader {
     // Return entire CAI block as Vec<u8>
     fn read_cai(&self, asset_reader: &mut dyn CAIRead) -> Result<Vec<u8>>;
 
+    // Return entire CAI block from the bytes of an asset. Formats that store it
+    // contiguously borrow it from `data` instead of copying it.
+    fn read_cai_from_slice<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        self.read_cai(&mut Cursor::new(data)).map(Cow::Owned)
+    }
+
     // Get XMP block
     fn read_xmp(&self, asset_reader: &mut dyn CAIRead) -> Option<String>;
 }
@@ -61,4 +71,15 @@ pub trait AssetIO {
     /// return the calculated location of when it should start.  There may still be a
     /// length if the format contains extra header information for example.
     fn get_object_locations(&self, asset_path: &Path) -> Result<Vec<HashObjectPositions>>;
+
+    /// Whether the format can carry an embedded XMP packet referencing the manifest
+    fn supports_xmp(&self) -> bool {
+        true
+    }
+
+    /// Collection hash binding the manifest to the asset, for formats made up of separately
+    /// hashed members. When present it is used in place of a data hash.
+    fn collection_hash(&self, _asset_path: &Path, _alg: &str) -> Result<Option<CollectionHash>> {
+        Ok(None)
+    }
 }
@@ -0,0 +1,262 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Compare an original signed asset with a derivative to see which bindings survived.
+//!
+//! Platforms that strip metadata or re-mux assets can use this to find out which
+//! binding strategies hold up under their processing.
+
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use crate::{
+    assertion::{Assertion, AssertionBase, AssertionData},
+    assertions::{CollectionHash, DataHash, SoftBinding},
+    claim::Claim,
+    jumbf,
+    jumbf_io::load_jumbf_from_memory,
+    status_tracker::OneShotStatusTracker,
+    store::Store,
+    Error, Result, VerifyOptions,
+};
+
+/// The kind of binding between a manifest and its asset
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BindingKind {
+    /// A hash over the asset bytes (`c2pa.hash.data`)
+    DataHash,
+    /// Hashes over the members of a collection (`c2pa.hash.collection.data`)
+    CollectionHash,
+    /// A watermark or fingerprint (`c2pa.soft-binding`)
+    SoftBinding,
+}
+
+/// The state of a binding in the derivative asset
+#[derive(Clone, Debug, PartialEq)]
+pub enum BindingState {
+    /// The binding still matches the derivative
+    Survived,
+    /// The binding no longer matches, with the reason
+    Broken(String),
+    /// The binding could not be checked, with the reason
+    Unchecked(String),
+}
+
+/// The result of checking one binding assertion against the derivative
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindingCheck {
+    /// URI of the binding assertion in the original manifest
+    pub assertion_uri: String,
+    /// The kind of binding
+    pub kind: BindingKind,
+    /// The hash or soft binding algorithm
+    pub alg: String,
+    /// Whether the binding survived
+    pub state: BindingState,
+}
+
+/// Reports which bindings of an original asset's active manifest survived in a derivative
+#[derive(Clone, Debug, PartialEq)]
+pub struct BindingReport {
+    /// Label of the active manifest of the original asset
+    pub manifest_label: String,
+    /// True if the derivative still embeds the same active manifest
+    pub manifest_preserved: bool,
+    /// The result for each binding assertion of the active manifest
+    pub bindings: Vec<BindingCheck>,
+}
+
+impl BindingReport {
+    /// Check the bindings of the manifest in `original` against `derivative`.
+    ///
+    /// Soft bindings are only checked for algorithms registered in `options`.
+    pub fn from_bytes(
+        original_format: &str,
+        original: &[u8],
+        derivative_format: &str,
+        derivative: &[u8],
+        options: &VerifyOptions,
+    ) -> Result<Self> {
+        let jumbf = load_jumbf_from_memory(original_format, original)?;
+        let store = Store::from_jumbf(&jumbf, &mut OneShotStatusTracker::new())?;
+        let claim = store.provenance_claim().ok_or(Error::ProvenanceMissing)?;
+
+        // the manifest is preserved if the derivative has the same signed claim
+        let manifest_preserved = load_jumbf_from_memory(derivative_format, derivative)
+            .and_then(|d| Store::from_jumbf(&d, &mut OneShotStatusTracker::new()))
+            .ok()
+            .and_then(|d| {
+                d.get_claim(claim.label())
+                    .map(|c| c.hash() == claim.hash() && c.signature_val() == claim.signature_val())
+            })
+            .unwrap_or(false);
+
+        let mut bindings = data_hash_checks(claim, derivative)?;
+        bindings.append(&mut collection_hash_checks(claim, derivative)?);
+        bindings.append(&mut soft_binding_checks(claim, derivative, options)?);
+
+        Ok(BindingReport {
+            manifest_label: claim.label().to_owned(),
+            manifest_preserved,
+            bindings,
+        })
+    }
+
+    /// Check the bindings of the manifest in the `original` file against the `derivative` file.
+    #[cfg(feature = "file_io")]
+    pub fn from_files<P: AsRef<Path>>(
+        original: P,
+        derivative: P,
+        options: &VerifyOptions,
+    ) -> Result<Self> {
+        let format_of = |path: &Path| {
+            crate::jumbf_io::get_supported_file_extension(path).ok_or(Error::UnsupportedType)
+        };
+        let original = original.as_ref();
+        let derivative = derivative.as_ref();
+        Self::from_bytes(
+            &format_of(original)?,
+            &std::fs::read(original)?,
+            &format_of(derivative)?,
+            &std::fs::read(derivative)?,
+            options,
+        )
+    }
+
+    /// Returns true if every checked binding survived
+    pub fn all_survived(&self) -> bool {
+        self.bindings
+            .iter()
+            .all(|b| !matches!(b.state, BindingState::Broken(_)))
+    }
+}
+
+fn data_hash_checks(claim: &Claim, derivative: &[u8]) -> Result<Vec<BindingCheck>> {
+    let mut checks = Vec::new();
+    for assertion in claim.data_hash_assertions() {
+        let dh = DataHash::from_assertion(&assertion)?;
+        let state = if dh.is_remote_hash() {
+            BindingState::Unchecked("asset hash is remote".to_owned())
+        } else {
+            match dh.verify_in_memory_hash(derivative, Some(claim.alg().to_owned())) {
+                Ok(()) => BindingState::Survived,
+                Err(e) => BindingState::Broken(e.to_string()),
+            }
+        };
+        checks.push(BindingCheck {
+            assertion_uri: claim.assertion_uri(&assertion.label()),
+            kind: BindingKind::DataHash,
+            alg: dh.alg.unwrap_or_else(|| claim.alg().to_owned()),
+            state,
+        });
+    }
+    Ok(checks)
+}
+
+fn collection_hash_checks(claim: &Claim, derivative: &[u8]) -> Result<Vec<BindingCheck>> {
+    let mut checks = Vec::new();
+    for assertion in claim.collection_hash_assertions() {
+        let ch = CollectionHash::from_assertion(&assertion)?;
+        let state = match Claim::verify_collection_hash(&ch, derivative, claim.alg()) {
+            Ok(()) => BindingState::Survived,
+            Err(e) => BindingState::Broken(e.to_string()),
+        };
+        checks.push(BindingCheck {
+            assertion_uri: claim.assertion_uri(&assertion.label()),
+            kind: BindingKind::CollectionHash,
+            alg: ch.alg.unwrap_or_else(|| claim.alg().to_owned()),
+            state,
+        });
+    }
+    Ok(checks)
+}
+
+// also used when validating, to report soft bindings that match or not
+pub(crate) fn soft_binding_checks(
+    claim: &Claim,
+    derivative: &[u8],
+    options: &VerifyOptions,
+) -> Result<Vec<BindingCheck>> {
+    let dummy_soft_binding =
+        Assertion::new(SoftBinding::LABEL, None, AssertionData::Cbor(Vec::new()));
+
+    let mut checks = Vec::new();
+    for (index, assertion) in claim
+        .assertions_by_type(&dummy_soft_binding)
+        .iter()
+        .enumerate()
+    {
+        let soft_binding = SoftBinding::from_assertion(assertion)?;
+        let state = match options.soft_bindings.get(&soft_binding.alg) {
+            Some(algorithm) => match algorithm.extract(derivative) {
+                Ok(Some(extracted)) => {
+                    if soft_binding
+                        .blocks
+                        .iter()
+                        .any(|b| algorithm.matches(&b.value, &extracted))
+                    {
+                        BindingState::Survived
+                    } else {
+                        BindingState::Broken("soft binding does not match".to_owned())
+                    }
+                }
+                Ok(None) => BindingState::Broken("soft binding not found".to_owned()),
+                Err(e) => BindingState::Broken(e.to_string()),
+            },
+            None => BindingState::Unchecked("soft binding algorithm not registered".to_owned()),
+        };
+
+        let label = Claim::label_with_instance(SoftBinding::LABEL, index);
+        checks.push(BindingCheck {
+            assertion_uri: jumbf::labels::to_assertion_uri(claim.label(), &label),
+            kind: BindingKind::SoftBinding,
+            alg: soft_binding.alg,
+            state,
+        });
+    }
+    Ok(checks)
+}
+
+#[cfg(test)]
+#[cfg(feature = "file_io")]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    use crate::utils::test::fixture_path;
+
+    #[test]
+    fn test_binding_report() {
+        let original = std::fs::read(fixture_path("CA.jpg")).unwrap();
+
+        let report =
+            BindingReport::from_bytes("jpg", &original, "jpg", &original, &VerifyOptions::new())
+                .unwrap();
+        assert!(report.manifest_preserved);
+        assert!(!report.bindings.is_empty());
+        assert!(report.all_survived());
+
+        // change a byte of the image data, leaving the manifest in place
+        let mut derivative = original.clone();
+        let pos = derivative.len() - 10;
+        derivative[pos] ^= 0xff;
+
+        let report =
+            BindingReport::from_bytes("jpg", &original, "jpg", &derivative, &VerifyOptions::new())
+                .unwrap();
+        assert!(report.manifest_preserved);
+        assert!(!report.all_survived());
+        assert_eq!(report.bindings[0].kind, BindingKind::DataHash);
+    }
+}
@@ -0,0 +1,1105 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! The profile a certificate must follow to sign C2PA claims.
+//!
+//! Validation rejects a signing certificate at the first rule it breaks. Certificate
+//! authorities can lint a candidate certificate to see every rule it breaks before
+//! issuing it.
+
+use conv::*;
+use serde::Serialize;
+use x509_parser::der_parser::ber::parse_ber_sequence;
+use x509_parser::der_parser::oid;
+use x509_parser::oid_registry::Oid;
+use x509_parser::prelude::*;
+
+use crate::{validation_status::ValidationStatusCode, Error, Result};
+
+const RSA_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .1);
+const EC_PUBLICKEY_OID: Oid<'static> = oid!(1.2.840 .10045 .2 .1);
+const ECDSA_WITH_SHA256_OID: Oid<'static> = oid!(1.2.840 .10045 .4 .3 .2);
+const ECDSA_WITH_SHA384_OID: Oid<'static> = oid!(1.2.840 .10045 .4 .3 .3);
+const ECDSA_WITH_SHA512_OID: Oid<'static> = oid!(1.2.840 .10045 .4 .3 .4);
+const RSASSA_PSS_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .10);
+const SHA256_WITH_RSAENCRYPTION_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .11);
+const SHA384_WITH_RSAENCRYPTION_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .12);
+const SHA512_WITH_RSAENCRYPTION_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .13);
+const ED25519_OID: Oid<'static> = oid!(1.3.101 .112);
+const SHA256_OID: Oid<'static> = oid!(2.16.840 .1 .101 .3 .4 .2 .1);
+const SHA384_OID: Oid<'static> = oid!(2.16.840 .1 .101 .3 .4 .2 .2);
+const SHA512_OID: Oid<'static> = oid!(2.16.840 .1 .101 .3 .4 .2 .3);
+const SECP521R1_OID: Oid<'static> = oid!(1.3.132 .0 .35);
+const SECP384R1_OID: Oid<'static> = oid!(1.3.132 .0 .34);
+const PRIME256V1_OID: Oid<'static> = oid!(1.2.840 .10045 .3 .1 .7);
+#[cfg(feature = "es256k")]
+const SECP256K1_OID: Oid<'static> = oid!(1.3.132 .0 .10);
+
+/// A rule of the signing certificate profile
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum CertRule {
+    /// The certificate is X.509 version 3
+    Version,
+    /// The certificate is valid at the time it is checked
+    Validity,
+    /// The certificate is signed with a supported algorithm
+    SignatureAlgorithm,
+    /// RSASSA-PSS parameters use the same supported hash for the signature and MGF1
+    PssParameters,
+    /// EC keys use P-256, P-384, P-521, or secp256k1 with the `es256k` feature
+    EcCurve,
+    /// RSA keys have a modulus of at least 2048 bits
+    RsaKeySize,
+    /// Issuer and subject unique identifiers are only used by self signed certificates
+    UniqueIdentifiers,
+    /// The certificate is an end entity or a self signed CA
+    EndEntity,
+    /// The extended key usages are an allowed set
+    ExtendedKeyUsage,
+    /// The key usage allows digital signatures
+    KeyUsage,
+    /// The key usage extension is marked critical. This is a soft rule.
+    KeyUsageCritical,
+    /// The authority key identifier extension is present
+    AuthorityKeyIdentifier,
+    /// CA certificates have the subject key identifier extension
+    SubjectKeyIdentifier,
+    /// Every critical extension is understood
+    CriticalExtensions,
+}
+
+impl CertRule {
+    /// Returns true if the specification only recommends the rule. Breaking a soft
+    /// rule is a warning unless certificates are checked strictly.
+    pub fn is_soft(&self) -> bool {
+        matches!(self, CertRule::KeyUsageCritical)
+    }
+}
+
+/// The result of checking a certificate against one rule
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CertRuleResult {
+    /// The rule checked
+    pub rule: CertRule,
+    /// Why the certificate breaks the rule, `None` if it follows it
+    pub failure: Option<String>,
+}
+
+impl CertRuleResult {
+    /// Returns true if the certificate follows the rule
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// Returns the error for the rule the certificate breaks, `None` if it follows it
+    pub fn error(&self) -> Option<Error> {
+        let failure = self.failure.as_deref()?;
+        Some(match self.rule {
+            CertRule::Version => Error::CoseCertInvalidVersion,
+            CertRule::Validity => Error::CoseCertExpiration,
+            CertRule::SignatureAlgorithm => Error::CoseCertUnsupportedAlgorithm,
+            CertRule::PssParameters => Error::CoseCertInvalidPssParameters,
+            CertRule::EcCurve => Error::CoseCertUnsupportedCurve,
+            CertRule::RsaKeySize => Error::CoseCertKeyTooShort,
+            CertRule::UniqueIdentifiers => Error::CoseCertInvalidUniqueIdentifiers,
+            CertRule::EndEntity => Error::CoseCertNotEndEntity,
+            CertRule::ExtendedKeyUsage
+                if failure == EKU_MISSING || failure == EKU_REQUIRED_MISSING =>
+            {
+                Error::CoseCertMissingEku
+            }
+            CertRule::ExtendedKeyUsage => Error::CoseCertInvalidEku,
+            CertRule::KeyUsage => Error::CoseCertInvalidKeyUsage,
+            CertRule::KeyUsageCritical => Error::CoseCertKeyUsageNotCritical,
+            CertRule::AuthorityKeyIdentifier => Error::CoseCertMissingAuthorityKeyId,
+            CertRule::SubjectKeyIdentifier => Error::CoseCertMissingSubjectKeyId,
+            CertRule::CriticalExtensions => Error::CoseCertUnhandledCriticalExtension,
+        })
+    }
+
+    /// Returns the validation status of the rule the certificate breaks, `None` if it
+    /// follows it
+    pub fn validation_status(&self) -> Option<ValidationStatusCode> {
+        self.failure.as_ref()?;
+        Some(match self.rule {
+            CertRule::Validity => ValidationStatusCode::SigningCredentialExpired,
+            CertRule::KeyUsageCritical => {
+                ValidationStatusCode::SigningCredentialKeyUsageNotCritical
+            }
+            _ => ValidationStatusCode::SigningCredentialInvalid,
+        })
+    }
+}
+
+// the failures of the extended key usage rule when none of the required EKUs is present
+const EKU_MISSING: &str = "certificate missing EKU";
+const EKU_REQUIRED_MISSING: &str =
+    "certificate missing emailProtection, OCSPSigning or timeStamping EKU";
+
+/// The results of linting a certificate against the signing certificate profile
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CertLintReport {
+    /// The result of each rule that applies to the certificate, in the order
+    /// validation checks them
+    pub results: Vec<CertRuleResult>,
+}
+
+impl CertLintReport {
+    /// Returns true if the certificate follows every rule
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed())
+    }
+
+    /// The results of the rules the certificate breaks
+    pub fn failures(&self) -> impl Iterator<Item = &CertRuleResult> {
+        self.results.iter().filter(|r| !r.passed())
+    }
+}
+
+impl std::fmt::Display for CertLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for result in &self.results {
+            match &result.failure {
+                None => writeln!(f, "  pass {:?}", result.rule)?,
+                Some(failure) if result.rule.is_soft() => {
+                    writeln!(f, "  warn {:?}: {}", result.rule, failure)?
+                }
+                Some(failure) => writeln!(f, "  fail {:?}: {}", result.rule, failure)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lint a PEM or DER encoded certificate against the signing certificate profile.
+///
+/// Validity is checked against the current time. Returns an error if the
+/// certificate cannot be parsed.
+pub fn lint_certificate(cert: &[u8]) -> Result<CertLintReport> {
+    let der = if cert.starts_with(b"-----BEGIN") {
+        let (_rem, pem) =
+            x509_parser::pem::parse_x509_pem(cert).map_err(|_err| Error::CoseInvalidCert)?;
+        pem.contents
+    } else {
+        cert.to_vec()
+    };
+
+    let (_rem, signcert) =
+        X509Certificate::from_der(&der).map_err(|_err| Error::CoseInvalidCert)?;
+
+    Ok(CertLintReport {
+        results: check_rules(&signcert, now()?),
+    })
+}
+
+/// A certificate of a chain and whether it is issued by the next one
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ChainCertReport {
+    /// Position of the certificate in the chain, the signing certificate is 0
+    pub index: usize,
+    /// The subject of the certificate
+    pub subject: String,
+    /// The issuer of the certificate
+    pub issuer: String,
+    /// Why the certificate breaks the chain, `None` if it does not
+    pub failure: Option<String>,
+}
+
+/// Why a signing certificate chain meets or fails the requirements of C2PA
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CertChainReport {
+    /// The lint of the signing certificate against the signing certificate profile
+    pub signing_certificate: CertLintReport,
+    /// The result of each certificate of the chain, starting with the signing certificate
+    pub chain: Vec<ChainCertReport>,
+}
+
+impl CertChainReport {
+    /// Returns true if the chain meets every requirement that is not soft
+    pub fn passed(&self) -> bool {
+        self.signing_certificate
+            .failures()
+            .all(|r| r.rule.is_soft())
+            && self.chain.iter().all(|c| c.failure.is_none())
+    }
+}
+
+impl std::fmt::Display for CertChainReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(signcert) = self.chain.first() {
+            writeln!(f, "signing certificate {}", signcert.subject)?;
+        }
+        write!(f, "{}", self.signing_certificate)?;
+        writeln!(f, "chain")?;
+        for cert in &self.chain {
+            write!(
+                f,
+                "  {} {} issued by {}",
+                cert.index, cert.subject, cert.issuer
+            )?;
+            match &cert.failure {
+                None => writeln!(f, ": ok")?,
+                Some(failure) => writeln!(f, ": {}", failure)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Explain why a certificate chain would pass or fail the requirements of C2PA.
+///
+/// `certs` are PEM encoded, or DER encoded one after the other, starting with the
+/// signing certificate. The signing certificate is linted against its profile, and
+/// each certificate must be issued by the next one. Validity is checked against the
+/// current time. Returns an error if a certificate cannot be parsed.
+///
+/// # Example
+///
+/// ```no_run
+/// # use c2pa::Result;
+/// use c2pa::explain_cert_chain;
+/// # fn main() -> Result<()> {
+/// let report = explain_cert_chain(&std::fs::read("es256_certs.pem")?)?;
+/// print!("{}", report);
+/// # Ok(())
+/// # }
+/// ```
+pub fn explain_cert_chain(certs: &[u8]) -> Result<CertChainReport> {
+    let ders = split_certs(certs)?;
+    let parsed = ders
+        .iter()
+        .map(|der| {
+            X509Certificate::from_der(der)
+                .map(|(_rem, cert)| cert)
+                .map_err(|_err| Error::CoseInvalidCert)
+        })
+        .collect::<Result<Vec<X509Certificate>>>()?;
+    let time = now()?;
+
+    let chain = check_chain(&ders, time)
+        .into_iter()
+        .zip(&parsed)
+        .map(|(result, cert)| {
+            // the validity of the signing certificate is checked by its profile
+            let profile_failure = result.index == 0 && result.expired;
+            ChainCertReport {
+                index: result.index,
+                subject: cert.subject().to_string(),
+                issuer: cert.issuer().to_string(),
+                failure: result.failure.filter(|_| !profile_failure),
+            }
+        })
+        .collect();
+
+    Ok(CertChainReport {
+        signing_certificate: CertLintReport {
+            results: check_rules(&parsed[0], time),
+        },
+        chain,
+    })
+}
+
+// split PEM or concatenated DER encoded certificates into DER certificates
+pub(crate) fn split_certs(certs: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut ders = Vec::new();
+    let mut rem = certs;
+    if certs.starts_with(b"-----BEGIN") {
+        while let Ok((next, pem)) = x509_parser::pem::parse_x509_pem(rem) {
+            ders.push(pem.contents);
+            rem = next;
+        }
+    } else {
+        while !rem.is_empty() {
+            let (next, _cert) =
+                X509Certificate::from_der(rem).map_err(|_err| Error::CoseInvalidCert)?;
+            ders.push(rem[..rem.len() - next.len()].to_vec());
+            rem = next;
+        }
+    }
+
+    if ders.is_empty() {
+        Err(Error::CoseInvalidCert)
+    } else {
+        Ok(ders)
+    }
+}
+
+/// Which rules of the signing certificate profile a signer's certificate must follow
+/// before it signs.
+///
+/// Signing checks the signing certificate against the rules validation uses, so a
+/// certificate verifiers would reject fails before anything is signed, with the rules
+/// it breaks. Rules can be relaxed for certificates knowingly outside the profile, such
+/// as test certificates, but verifiers will still reject their signatures.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CertificateAcceptancePolicy {
+    relaxed: Vec<CertRule>,
+    accept_any: bool,
+}
+
+impl CertificateAcceptancePolicy {
+    /// A policy enforcing every rule that is not soft
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A policy accepting any certificate, skipping the check
+    pub fn accept_any() -> Self {
+        CertificateAcceptancePolicy {
+            relaxed: Vec::new(),
+            accept_any: true,
+        }
+    }
+
+    /// Accept certificates that break `rule`
+    pub fn relax(&mut self, rule: CertRule) -> &mut Self {
+        if !self.relaxed.contains(&rule) {
+            self.relaxed.push(rule);
+        }
+        self
+    }
+
+    /// Returns true if certificates breaking `rule` are accepted
+    pub fn is_relaxed(&self, rule: CertRule) -> bool {
+        self.accept_any || rule.is_soft() || self.relaxed.contains(&rule)
+    }
+
+    // true if the policy accepts certificates that validation rejects
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    pub(crate) fn relaxes_validation(&self) -> bool {
+        self.accept_any || self.relaxed.iter().any(|rule| !rule.is_soft())
+    }
+
+    // check the signing certificate, the first of `certs`, at `time` in seconds
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    pub(crate) fn check(&self, certs: &[Vec<u8>], time: i64) -> Result<()> {
+        if self.accept_any {
+            return Ok(());
+        }
+
+        let signcert = certs.first().ok_or(Error::CoseX5ChainMissing)?;
+        let (_rem, signcert) =
+            X509Certificate::from_der(signcert).map_err(|_err| Error::CoseInvalidCert)?;
+
+        let failures: Vec<String> = check_rules(&signcert, time)
+            .into_iter()
+            .filter(|r| !self.is_relaxed(r.rule))
+            .filter_map(|r| r.failure)
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::SigningCertificateRejected(failures.join(", ")))
+        }
+    }
+}
+
+// fail before a signature is embedded if verifiers would reject the signing certificate,
+// the first of `certs`, or the certificate of one of the co-signers, so every way of
+// signing a claim checks the same rules
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+pub(crate) fn check_signing_certs(
+    policy: &CertificateAcceptancePolicy,
+    certs: &[Vec<u8>],
+    cosigners: &[Box<dyn crate::Signer>],
+) -> Result<()> {
+    let now = now()?;
+    policy.check(certs, now)?;
+    for cosigner in cosigners {
+        cosigner
+            .certificate_acceptance_policy()
+            .check(&cosigner.certs()?, now)?;
+    }
+    Ok(())
+}
+
+// the current time in seconds, use instant to avoid wasm issues
+pub(crate) fn now() -> Result<i64> {
+    let now_f64 = instant::now() / 1000.0;
+    now_f64
+        .approx_as::<i64>()
+        .map_err(|_e| Error::BadParam("system time invalid".to_string()))
+}
+
+// check each rule that applies to the certificate, `time` is the validity time in seconds
+pub(crate) fn check_rules(signcert: &X509Certificate, time: i64) -> Vec<CertRuleResult> {
+    let mut results = Vec::new();
+    let mut check = |rule: CertRule, outcome: std::result::Result<(), &str>| {
+        results.push(CertRuleResult {
+            rule,
+            failure: outcome.err().map(|e| e.to_owned()),
+        })
+    };
+
+    // cert version must be 3
+    check(
+        CertRule::Version,
+        if signcert.version() == X509Version::V3 {
+            Ok(())
+        } else {
+            Err("certificate version incorrect")
+        },
+    );
+
+    check(
+        CertRule::Validity,
+        if signcert
+            .validity()
+            .is_valid_at(x509_parser::time::ASN1Time::from_timestamp(time))
+        {
+            Ok(())
+        } else {
+            Err("certificate expired")
+        },
+    );
+
+    let cert_alg = &signcert.signature_algorithm.algorithm;
+
+    // cert must be signed with one the following algorithm
+    check(
+        CertRule::SignatureAlgorithm,
+        if *cert_alg == SHA256_WITH_RSAENCRYPTION_OID
+            || *cert_alg == SHA384_WITH_RSAENCRYPTION_OID
+            || *cert_alg == SHA512_WITH_RSAENCRYPTION_OID
+            || *cert_alg == ECDSA_WITH_SHA256_OID
+            || *cert_alg == ECDSA_WITH_SHA384_OID
+            || *cert_alg == ECDSA_WITH_SHA512_OID
+            || *cert_alg == RSASSA_PSS_OID
+            || *cert_alg == ED25519_OID
+        {
+            Ok(())
+        } else {
+            Err("certificate algorithm not supported")
+        },
+    );
+
+    if *cert_alg == RSASSA_PSS_OID {
+        check(CertRule::PssParameters, check_pss_parameters(signcert));
+    }
+
+    // check curves for SPKI EC algorithms
+    let skpi_alg = &signcert.public_key().algorithm;
+    if skpi_alg.algorithm == EC_PUBLICKEY_OID {
+        check(CertRule::EcCurve, check_ec_curve(signcert));
+    }
+
+    // check modulus minumum length (for RSA & PSS algorithms)
+    if skpi_alg.algorithm == RSA_OID || skpi_alg.algorithm == RSASSA_PSS_OID {
+        check(CertRule::RsaKeySize, check_rsa_key_size(signcert));
+    }
+
+    // check cert values
+    let tbscert = &signcert.tbs_certificate;
+
+    let is_self_signed = tbscert.is_ca() && tbscert.issuer_uid == tbscert.subject_uid;
+
+    // only allowable for self signed
+    check(
+        CertRule::UniqueIdentifiers,
+        if !is_self_signed && tbscert.issuer_uid.is_some() || tbscert.subject_uid.is_some() {
+            Err("certificate issuer and subject cannot be the same")
+        } else {
+            Ok(())
+        },
+    );
+
+    // non self signed CA certs are not allowed, must be an end entity (leaf) cert
+    check(
+        CertRule::EndEntity,
+        if tbscert.is_ca() && !is_self_signed {
+            Err("certificate is not an end entity certificate")
+        } else {
+            Ok(())
+        },
+    );
+
+    check(
+        CertRule::ExtendedKeyUsage,
+        match tbscert.extended_key_usage() {
+            Some((_critical, eku)) => {
+                if eku.any {
+                    Err("certificate 'any' EKU not allowed")
+                } else if !(eku.email_protection || eku.ocsp_signing || eku.time_stamping) {
+                    Err(EKU_REQUIRED_MISSING)
+                } else if (eku.ocsp_signing && eku.time_stamping)
+                    || ((eku.ocsp_signing ^ eku.time_stamping)
+                        && (eku.client_auth
+                            | eku.code_signing
+                            | eku.email_protection
+                            | eku.server_auth))
+                {
+                    // one or the other || either of these two, and no others field
+                    Err("certificate invalid set of EKUs")
+                } else {
+                    Ok(())
+                }
+            }
+            // if is not ca it must be present
+            None if tbscert.is_ca() => Ok(()),
+            None => Err(EKU_MISSING),
+        },
+    );
+
+    let mut aki_good = false;
+    let mut ski_good = false;
+    let mut key_usage: std::result::Result<(), &str> = Err("certificate missing key usage");
+    let mut key_usage_critical = true;
+    let mut handled_all_critical = true;
+
+    // popluate needed extension info
+    for e in signcert.extensions() {
+        match e.parsed_extension() {
+            ParsedExtension::AuthorityKeyIdentifier(_aki) => {
+                aki_good = true;
+            }
+            ParsedExtension::SubjectKeyIdentifier(_spki) => {
+                ski_good = true;
+            }
+            ParsedExtension::KeyUsage(ku) => {
+                key_usage = if ku.digital_signature() && ku.key_cert_sign() && !tbscert.is_ca() {
+                    Err("certificate keyCertSign usage not allowed")
+                } else if ku.digital_signature() || ku.key_cert_sign() {
+                    Ok(())
+                } else {
+                    Err("certificate missing digitalSignature key usage")
+                };
+                key_usage_critical = e.critical;
+            }
+            ParsedExtension::CertificatePolicies(_) => (),
+            ParsedExtension::PolicyMappings(_) => (),
+            ParsedExtension::SubjectAlternativeName(_) => (),
+            ParsedExtension::BasicConstraints(_) => (),
+            ParsedExtension::NameConstraints(_) => (),
+            ParsedExtension::PolicyConstraints(_) => (),
+            ParsedExtension::ExtendedKeyUsage(_) => (),
+            ParsedExtension::CRLDistributionPoints(_) => (),
+            ParsedExtension::InhibitAnyPolicy(_) => (),
+            ParsedExtension::AuthorityInfoAccess(_) => (),
+            ParsedExtension::NSCertType(_) => (),
+            ParsedExtension::CRLNumber(_) => (),
+            ParsedExtension::ReasonCode(_) => (),
+            ParsedExtension::InvalidityDate(_) => (),
+            _ => {
+                if e.critical {
+                    // unhandled critical extension
+                    handled_all_critical = false;
+                }
+            }
+        }
+    }
+
+    let has_key_usage = key_usage.is_ok();
+    check(CertRule::KeyUsage, key_usage);
+    if has_key_usage {
+        check(
+            CertRule::KeyUsageCritical,
+            if key_usage_critical {
+                Ok(())
+            } else {
+                Err("certificate key usage not marked critical")
+            },
+        );
+    }
+    check(
+        CertRule::AuthorityKeyIdentifier,
+        if aki_good {
+            Ok(())
+        } else {
+            Err("certificate missing authority key identifier")
+        },
+    );
+    // if cert is a CA must have valid SubjectKeyIdentifier
+    check(
+        CertRule::SubjectKeyIdentifier,
+        if ski_good || !tbscert.is_ca() {
+            Ok(())
+        } else {
+            Err("certificate missing subject key identifier")
+        },
+    );
+    check(
+        CertRule::CriticalExtensions,
+        if handled_all_critical {
+            Ok(())
+        } else {
+            Err("certificate has unsupported critical extension")
+        },
+    );
+
+    results
+}
+
+/// The result of checking one certificate of a chain
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ChainCertResult {
+    /// Position of the certificate in the chain, the signing certificate is 0
+    pub index: usize,
+    /// The certificate is outside its validity at the time it is checked
+    pub expired: bool,
+    /// Why the certificate fails, `None` if it is valid
+    pub failure: Option<String>,
+}
+
+/// Check the certification path of `certs`, an `x5chain` starting with the signing
+/// certificate, at `time` in seconds.
+///
+/// Each certificate must be issued by the next one: the names chain, the issuer is a
+/// CA allowed to sign certificates within its path length, and the signature verifies
+/// with its key. Certificates other than the signing certificate, which is checked by
+/// its profile, must be valid at `time`, and a final self-issued certificate must verify
+/// with its own key. Returns one result per certificate.
+pub(crate) fn check_chain(certs: &[Vec<u8>], time: i64) -> Vec<ChainCertResult> {
+    let parsed: Vec<Option<X509Certificate>> = certs
+        .iter()
+        .map(|der| X509Certificate::from_der(der).ok().map(|(_rem, cert)| cert))
+        .collect();
+
+    (0..certs.len())
+        .map(|index| {
+            let (expired, failure) = match check_chain_link(certs, &parsed, index, time) {
+                Ok(()) => (false, None),
+                Err((expired, failure)) => (expired, Some(failure)),
+            };
+            ChainCertResult {
+                index,
+                expired,
+                failure,
+            }
+        })
+        .collect()
+}
+
+// check the certificate at `index` and its link to the next one
+fn check_chain_link(
+    certs: &[Vec<u8>],
+    parsed: &[Option<X509Certificate>],
+    index: usize,
+    time: i64,
+) -> std::result::Result<(), (bool, String)> {
+    let fail = |description: &str| Err((false, description.to_string()));
+
+    let cert = match &parsed[index] {
+        Some(cert) => cert,
+        None => return fail("certificate could not be parsed"),
+    };
+
+    if index > 0
+        && !cert
+            .validity()
+            .is_valid_at(x509_parser::time::ASN1Time::from_timestamp(time))
+    {
+        return Err((true, "certificate expired".to_string()));
+    }
+
+    match parsed.get(index + 1) {
+        Some(Some(issuer)) => {
+            if cert.issuer().as_raw() != issuer.subject().as_raw() {
+                return fail("certificate is not issued by the next certificate in the chain");
+            }
+
+            let mut is_ca = false;
+            let mut path_len = None;
+            let mut can_sign_certs = true;
+            for e in issuer.extensions() {
+                match e.parsed_extension() {
+                    ParsedExtension::BasicConstraints(bc) => {
+                        is_ca = bc.ca;
+                        path_len = bc.path_len_constraint;
+                    }
+                    ParsedExtension::KeyUsage(ku) => can_sign_certs = ku.key_cert_sign(),
+                    _ => (),
+                }
+            }
+            if !is_ca {
+                return fail("issuer is not a CA certificate");
+            }
+            if !can_sign_certs {
+                return fail("issuer key usage does not allow signing certificates");
+            }
+            // the CA certificates between the issuer and the signing certificate
+            if path_len.map_or(false, |len| (len as usize) < index) {
+                return fail("issuer path length constraint exceeded");
+            }
+
+            match verify_cert_signature(&certs[index], &certs[index + 1]) {
+                Some(false) => fail("certificate signature does not verify with issuer key"),
+                _ => Ok(()),
+            }
+        }
+        Some(None) => fail("issuer certificate could not be parsed"),
+        // the last certificate only needs to verify if it claims to be self-issued
+        None if cert.issuer().as_raw() == cert.subject().as_raw() => {
+            match verify_cert_signature(&certs[index], &certs[index]) {
+                Some(false) => fail("self-signed certificate signature does not verify"),
+                _ => Ok(()),
+            }
+        }
+        None => Ok(()),
+    }
+}
+
+// Returns whether the signature of `cert` verifies with the key of `issuer`,
+// `None` where signatures cannot be checked
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+fn verify_cert_signature(cert: &[u8], issuer: &[u8]) -> Option<bool> {
+    use openssl::x509::X509;
+
+    let verified = X509::from_der(issuer)
+        .and_then(|issuer| issuer.public_key())
+        .and_then(|key| X509::from_der(cert).and_then(|cert| cert.verify(&key)));
+    Some(verified.unwrap_or(false))
+}
+
+#[cfg(not(all(feature = "file_io", not(target_os = "wasi"))))]
+fn verify_cert_signature(_cert: &[u8], _issuer: &[u8]) -> Option<bool> {
+    None
+}
+
+// verify rsassa_pss parameters
+fn check_pss_parameters(signcert: &X509Certificate) -> std::result::Result<(), &'static str> {
+    const INCORRECT: &str = "certificate incorrect rsapss algorithm";
+
+    let parameters = signcert
+        .signature_algorithm
+        .parameters
+        .as_ref()
+        .ok_or("certificate missing algorithm parameters")?;
+    let seq = parameters.as_sequence().map_err(|_err| INCORRECT)?;
+    if seq.len() < 3 {
+        return Err(INCORRECT);
+    }
+
+    // get hash algorithm
+    let (_b, ha_alg) =
+        AlgorithmIdentifier::from_der(seq[0].content.as_slice().map_err(|_err| INCORRECT)?)
+            .map_err(|_err| INCORRECT)?;
+
+    let (_b, mgf_ai) =
+        AlgorithmIdentifier::from_der(seq[1].content.as_slice().map_err(|_err| INCORRECT)?)
+            .map_err(|_err| INCORRECT)?;
+
+    let mgf_ai_parameters = mgf_ai.parameters.ok_or(INCORRECT)?;
+    let s = mgf_ai_parameters.as_sequence().map_err(|_err| INCORRECT)?;
+    let mfg_ai_params_algorithm = s
+        .first()
+        .and_then(|t0| t0.as_oid_val().ok())
+        .ok_or(INCORRECT)?;
+
+    // must be the same
+    if ha_alg.algorithm != mfg_ai_params_algorithm {
+        return Err("certificate algorithm error");
+    }
+
+    // check for one of the mandatory types
+    if !(ha_alg.algorithm == SHA256_OID
+        || ha_alg.algorithm == SHA384_OID
+        || ha_alg.algorithm == SHA512_OID)
+    {
+        return Err("certificate hash algorithm not supported");
+    }
+
+    Ok(())
+}
+
+fn check_ec_curve(signcert: &X509Certificate) -> std::result::Result<(), &'static str> {
+    const UNSUPPORTED: &str = "certificate unsupported EC curve";
+
+    let named_curve_oid = signcert
+        .public_key()
+        .algorithm
+        .parameters
+        .as_ref()
+        .and_then(|p| p.as_oid_val().ok())
+        .ok_or(UNSUPPORTED)?;
+
+    #[cfg(feature = "es256k")]
+    if named_curve_oid == SECP256K1_OID {
+        return Ok(());
+    }
+
+    // must be one of these named curves
+    if named_curve_oid == PRIME256V1_OID
+        || named_curve_oid == SECP384R1_OID
+        || named_curve_oid == SECP521R1_OID
+    {
+        Ok(())
+    } else {
+        Err(UNSUPPORTED)
+    }
+}
+
+fn check_rsa_key_size(signcert: &X509Certificate) -> std::result::Result<(), &'static str> {
+    const INVALID: &str = "certificate RSA key could not be parsed";
+
+    let (_, skpi_ber) = parse_ber_sequence(signcert.public_key().subject_public_key.data)
+        .map_err(|_err| INVALID)?;
+    let seq = skpi_ber.as_sequence().map_err(|_err| INVALID)?;
+    if seq.len() < 2 {
+        return Err(INVALID);
+    }
+
+    let modulus = seq[0].as_bigint().ok_or(INVALID)?;
+    if modulus.bits() < 2048 {
+        return Err("certificate key length too short");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use openssl::{
+        pkey::{PKey, Private},
+        rsa::Rsa,
+    };
+
+    use super::*;
+    use crate::{openssl::temp_signer, utils::test::TestCert};
+
+    #[test]
+    fn test_lint_certificate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (_, cert_path) = temp_signer::get_ec_signer(&temp_dir.path(), "es256", None);
+        let pem = std::fs::read(&cert_path).unwrap();
+
+        let report = lint_certificate(&pem).unwrap();
+        assert!(report.passed());
+        assert!(report.results.iter().any(|r| r.rule == CertRule::EcCurve));
+        assert!(report
+            .results
+            .iter()
+            .any(|r| r.rule == CertRule::KeyUsageCritical));
+
+        // the DER form gives the same results
+        let der = openssl::x509::X509::from_pem(&pem)
+            .unwrap()
+            .to_der()
+            .unwrap();
+        assert_eq!(lint_certificate(&der).unwrap(), report);
+
+        // every broken rule is reported
+        let expired = std::fs::read(crate::utils::test::fixture_path(
+            "rsa-pss256_key-expired.pub",
+        ))
+        .unwrap();
+        let report = lint_certificate(&expired).unwrap();
+        assert!(!report.passed());
+        assert!(report.failures().any(|r| r.rule == CertRule::Validity));
+
+        // each broken rule has the status validation logs for it
+        let expired_result = report
+            .failures()
+            .find(|r| r.rule == CertRule::Validity)
+            .unwrap();
+        assert_eq!(
+            expired_result.validation_status(),
+            Some(ValidationStatusCode::SigningCredentialExpired)
+        );
+        let key_usage = CertRuleResult {
+            rule: CertRule::KeyUsageCritical,
+            failure: Some("certificate key usage not marked critical".to_owned()),
+        };
+        assert_eq!(
+            key_usage.validation_status(),
+            Some(ValidationStatusCode::SigningCredentialKeyUsageNotCritical)
+        );
+        assert!(report
+            .results
+            .iter()
+            .filter(|r| r.passed())
+            .all(|r| r.validation_status().is_none()));
+
+        assert!(lint_certificate(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn test_explain_cert_chain() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, cert_path) = temp_signer::get_ec_signer(&temp_dir.path(), "es256", None);
+        let pem = std::fs::read(&cert_path).unwrap();
+
+        let report = explain_cert_chain(&pem).unwrap();
+        assert!(report.passed());
+        assert!(report.chain.len() > 1);
+        assert_eq!(report.chain[0].issuer, report.chain[1].subject);
+        let text = report.to_string();
+        assert!(text.starts_with("signing certificate "));
+        assert!(text.contains("  pass ExtendedKeyUsage"));
+        assert!(text.contains("  0 "));
+
+        // DER certificates one after the other give the same report
+        let mut certs = crate::Signer::certs(&signer).unwrap();
+        assert_eq!(explain_cert_chain(&certs.concat()).unwrap(), report);
+
+        // out of order, the signing certificate is not issued by the next one
+        certs.reverse();
+        let report = explain_cert_chain(&certs.concat()).unwrap();
+        assert!(!report.passed());
+        assert!(report.chain[0].failure.is_some());
+
+        let expired = std::fs::read(crate::utils::test::fixture_path(
+            "rsa-pss256_key-expired.pub",
+        ))
+        .unwrap();
+        let report = explain_cert_chain(&expired).unwrap();
+        assert!(!report.passed());
+        assert!(report
+            .to_string()
+            .contains("  fail Validity: certificate expired"));
+
+        assert!(explain_cert_chain(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn test_certificate_acceptance_policy() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = temp_signer::get_ec_signer(&temp_dir.path(), "es256", None);
+        let certs = crate::Signer::certs(&signer).unwrap();
+        let now = now().unwrap();
+        assert!(CertificateAcceptancePolicy::new()
+            .check(&certs, now)
+            .is_ok());
+
+        let expired = std::fs::read(crate::utils::test::fixture_path(
+            "rsa-pss256_key-expired.pub",
+        ))
+        .unwrap();
+        let (_rem, pem) = x509_parser::pem::parse_x509_pem(&expired).unwrap();
+        let certs = vec![pem.contents];
+
+        // the reason is reported
+        let mut policy = CertificateAcceptancePolicy::new();
+        assert!(matches!(
+            policy.check(&certs, now),
+            Err(Error::SigningCertificateRejected(reason)) if reason == "certificate expired"
+        ));
+
+        policy.relax(CertRule::Validity);
+        assert!(policy.is_relaxed(CertRule::Validity));
+        assert!(policy.relaxes_validation());
+        assert!(policy.check(&certs, now).is_ok());
+
+        assert!(CertificateAcceptancePolicy::accept_any()
+            .check(&[b"not a certificate".to_vec()], now)
+            .is_ok());
+        assert!(!CertificateAcceptancePolicy::new().relaxes_validation());
+    }
+
+    #[test]
+    fn test_cert_rule_error() {
+        let result = |rule, failure: Option<&str>| CertRuleResult {
+            rule,
+            failure: failure.map(str::to_owned),
+        };
+        assert!(result(CertRule::EcCurve, None).error().is_none());
+        assert!(matches!(
+            result(CertRule::EcCurve, Some("certificate unsupported EC curve")).error(),
+            Some(Error::CoseCertUnsupportedCurve)
+        ));
+        assert!(matches!(
+            result(
+                CertRule::ExtendedKeyUsage,
+                Some("certificate 'any' EKU not allowed")
+            )
+            .error(),
+            Some(Error::CoseCertInvalidEku)
+        ));
+
+        // an end entity certificate without extensions is missing its EKUs first
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let leaf = TestCert::new("Leaf", &key).build().to_der().unwrap();
+        let (_rem, leaf) = X509Certificate::from_der(&leaf).unwrap();
+        let results = check_rules(&leaf, now().unwrap());
+        let first = results.iter().find(|r| !r.passed()).unwrap();
+        assert!(matches!(first.error(), Some(Error::CoseCertMissingEku)));
+        assert!(results
+            .iter()
+            .any(|r| matches!(r.error(), Some(Error::CoseCertMissingAuthorityKeyId))));
+    }
+
+    #[test]
+    fn test_check_chain() {
+        let keys: Vec<PKey<Private>> = (0..3)
+            .map(|_| PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap())
+            .collect();
+        let now = now().unwrap();
+        let failures = |certs: &[Vec<u8>]| -> Vec<usize> {
+            check_chain(certs, now)
+                .iter()
+                .filter(|r| r.failure.is_some())
+                .map(|r| r.index)
+                .collect()
+        };
+
+        let root_x509 = TestCert::new("Root", &keys[0])
+            .ca(None)
+            .key_cert_sign()
+            .build();
+        let root = root_x509.to_der().unwrap();
+        let ca_x509 = TestCert::new("CA", &keys[1])
+            .issuer(&root_x509, &keys[0])
+            .ca(None)
+            .key_cert_sign()
+            .build();
+        let ca = ca_x509.to_der().unwrap();
+        let leaf_x509 = TestCert::new("Leaf", &keys[2])
+            .issuer(&ca_x509, &keys[1])
+            .build();
+        let leaf = leaf_x509.to_der().unwrap();
+
+        let chain = vec![leaf.clone(), ca.clone(), root.clone()];
+        assert!(failures(&chain).is_empty());
+        assert_eq!(check_chain(&chain, now).len(), 3);
+
+        // out of order
+        assert_eq!(
+            failures(&[leaf.clone(), root.clone(), ca.clone()]),
+            vec![0, 1]
+        );
+
+        // issued by a cert that is not a CA
+        let other = TestCert::new("Other", &keys[0])
+            .issuer(&leaf_x509, &keys[2])
+            .build()
+            .to_der()
+            .unwrap();
+        assert_eq!(failures(&[other, leaf.clone()]), vec![0]);
+
+        // the root only allows end entity certs below it
+        let strict_root = TestCert::new("Root", &keys[0])
+            .ca(Some(0))
+            .key_cert_sign()
+            .build()
+            .to_der()
+            .unwrap();
+        assert_eq!(failures(&[leaf.clone(), ca.clone(), strict_root]), vec![1]);
+
+        // signed with a different key than the issuer's
+        let forged_ca = TestCert::new("CA", &keys[0])
+            .issuer(&root_x509, &keys[0])
+            .ca(None)
+            .key_cert_sign()
+            .build()
+            .to_der()
+            .unwrap();
+        assert_eq!(failures(&[leaf, forged_ca, root]), vec![0]);
+
+        // chain certs are checked at the signing time
+        let results = check_chain(&chain, now + 3 * 24 * 60 * 60);
+        assert!(results[1].expired && results[2].expired);
+        assert!(!results[0].expired);
+    }
+}
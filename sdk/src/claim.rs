@@ -22,26 +22,40 @@ use crate::assertion::{
     get_thumbnail_image_type, get_thumbnail_instance, get_thumbnail_type, Assertion, AssertionBase,
     AssertionData,
 };
-use crate::assertions::{self, labels, DataHash};
-use crate::cose_validator::{get_signing_info, verify_cose, verify_cose_async};
+use crate::assertions::{self, labels, CollectionHash, DataHash};
+use crate::cose_validator::{get_signing_info, verify_cose, verify_cose_async, CertCheckOptions};
+use crate::databox::DataBox;
 use crate::hashed_uri::HashedUri;
 use crate::jumbf::{
     self,
     boxes::{CAICBORAssertionBox, CAIJSONAssertionBox, CAIUUIDAssertionBox, JumbfEmbeddedFileBox},
 };
-use crate::salt::{SaltGenerator, NO_SALT};
+use crate::salt::{SaltGenerator, SaltPolicy};
 use crate::utils::hash_utils::{hash_by_alg, vec_compare, verify_by_alg};
 
 use crate::error::{Error, Result};
 use crate::status_tracker::{log_item, OneShotStatusTracker, StatusTracker};
-use crate::validation_status;
+use crate::validation_status::ValidationStatusCode;
 use crate::validator::ValidationInfo;
 
-const BUILD_HASH_ALG: &str = "sha256";
+pub(crate) const BUILD_HASH_ALG: &str = "sha256";
 
 /// JSON structure representing an Assertion reference in a Claim's "assertions" list
 use HashedUri as C2PAAssertion;
 
+// signature algorithms allowed in C2PA 2.x claims
+const V2_SIGNING_ALGS: [&str; 7] = [
+    "es256", "es384", "es512", "ps256", "ps384", "ps512", "ed25519",
+];
+
+// actions allowed in update manifests, which can only change metadata
+const UPDATE_MANIFEST_ACTIONS: [&str; 4] = [
+    assertions::c2pa_action::EDITED_METADATA,
+    assertions::c2pa_action::OPENED,
+    assertions::c2pa_action::PUBLISHED,
+    assertions::c2pa_action::REDACTED,
+];
+
 const GH_FULL_VERSION_LIST: &str = "Sec-CH-UA-Full-Version-List";
 const GH_UA: &str = "Sec-CH-UA";
 
@@ -138,6 +152,79 @@ impl fmt::Debug for ClaimAssertion {
         write!(f, "{:?}, instance: {}", self.assertion, self.instance)
     }
 }
+/// The version of the C2PA specification a claim conforms to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClaimVersion {
+    /// C2PA 1.x claims (`c2pa.claim`)
+    V1,
+    /// C2PA 2.x claims (`c2pa.claim.v2`) with created and gathered assertions
+    V2,
+}
+
+impl ClaimVersion {
+    // the label of the JUMBF claim box for this version
+    pub(crate) fn box_label(&self) -> &'static str {
+        match self {
+            ClaimVersion::V1 => jumbf::labels::CLAIM,
+            ClaimVersion::V2 => jumbf::labels::CLAIM_V2,
+        }
+    }
+
+    // the version of a claim stored in a JUMBF claim box with this label
+    pub(crate) fn from_box_label(label: &str) -> Self {
+        match assertions::labels::version(label) {
+            Some(version) if version >= 2 => ClaimVersion::V2,
+            _ => ClaimVersion::V1,
+        }
+    }
+
+    /// Returns true if claims of this version may be signed with `alg`
+    pub fn allows_signing_alg(&self, alg: &str) -> bool {
+        match self {
+            ClaimVersion::V1 => true,
+            ClaimVersion::V2 => V2_SIGNING_ALGS.contains(&alg.to_lowercase().as_str()),
+        }
+    }
+}
+
+impl Default for ClaimVersion {
+    fn default() -> Self {
+        ClaimVersion::V1
+    }
+}
+
+// the generator of a claim, required by C2PA 2.x claims
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct ClaimGeneratorInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    icon: Option<HashedUri>,
+    #[serde(flatten)]
+    other: HashMap<String, Value>,
+}
+
+// the fields of a claim as serialized in C2PA 2.x claims
+#[derive(Deserialize, Serialize)]
+struct ClaimDataV2 {
+    #[serde(rename = "instanceID")]
+    instance_id: String,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "dc:title", default)]
+    title: Option<String>,
+    claim_generator_info: ClaimGeneratorInfo,
+    signature: String,
+    created_assertions: Vec<C2PAAssertion>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    gathered_assertions: Vec<C2PAAssertion>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    redacted_assertions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    alg: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    alg_soft: Option<String>,
+}
+
 /// A `Claim` gathers together all the `Assertion`s about an asset
 /// from an actor at a given time, and may also include one or more
 /// hashes of the asset itself, and a reference to the previous `Claim`.
@@ -190,6 +277,10 @@ pub struct Claim {
     #[serde(skip_deserializing, skip_serializing)]
     vc_store: Vec<AssertionData>,
 
+    // databoxes referenced by the assertions of this claim
+    #[serde(skip_deserializing, skip_serializing)]
+    databox_store: Vec<ClaimAssertion>,
+
     claim_generator: String, // generator of this claim
 
     signature: String,              // link to signature box
@@ -210,6 +301,26 @@ pub struct Claim {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     claim_generator_hints: Option<HashMap<String, Value>>,
+
+    // only written to C2PA 1.x claims with an icon, C2PA 2.x claims always have this
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    claim_generator_info: Option<Vec<ClaimGeneratorInfo>>,
+
+    // the specification version used to serialize this claim
+    #[serde(skip_deserializing, skip_serializing)]
+    claim_version: ClaimVersion,
+
+    // urls of assertions gathered by the claim generator rather than created by it
+    #[serde(skip_deserializing, skip_serializing)]
+    gathered_assertions: Vec<String>,
+
+    // labels and instances of assertions redacted from this claim by later claims
+    #[serde(skip_deserializing, skip_serializing)]
+    redacted_by_others: Vec<(String, usize)>,
+
+    // which assertions added without an explicit salt generator are salted
+    #[serde(skip_deserializing, skip_serializing)]
+    salt_policy: SaltPolicy,
 }
 
 /// Enum to define how assertions are are stored when output to json
@@ -265,12 +376,18 @@ impl Claim {
             claim_generator: claim_generator.to_string(),
             assertion_store: Vec::new(),
             vc_store: Vec::new(),
+            databox_store: Vec::new(),
             assertions: Vec::new(),
             original_bytes: None,
             redacted_assertions: None,
             alg: Some(BUILD_HASH_ALG.to_string()),
             alg_soft: None,
             claim_generator_hints: None,
+            claim_version: ClaimVersion::V1,
+            gathered_assertions: Vec::new(),
+            redacted_by_others: Vec::new(),
+            salt_policy: SaltPolicy::default(),
+            claim_generator_info: None,
 
             title: None,
             format: "".to_string(),
@@ -290,9 +407,24 @@ impl Claim {
         Ok(())
     }
 
-    /// return version this claim supports
+    /// return the newest claim version this library supports
     pub fn build_version() -> &'static str {
-        Self::LABEL
+        jumbf::labels::CLAIM_V2
+    }
+
+    /// Return the specification version this claim is serialized with
+    pub fn claim_version(&self) -> ClaimVersion {
+        self.claim_version
+    }
+
+    /// Set the specification version this claim is serialized with
+    pub fn set_claim_version(&mut self, version: ClaimVersion) {
+        self.claim_version = version;
+    }
+
+    /// Set which assertions added with `add_assertion` are salted
+    pub fn set_salt_policy(&mut self, policy: SaltPolicy) {
+        self.salt_policy = policy;
     }
 
     /// Return the JUMBF label for this claim.
@@ -300,6 +432,11 @@ impl Claim {
         &self.label
     }
 
+    // rename the claim, used when its label collides with another manifest of a store
+    pub(crate) fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+    }
+
     /// Return the JUMBF URI for this claim.
     pub fn uri(&self) -> String {
         jumbf::labels::to_manifest_uri(&self.label)
@@ -415,6 +552,27 @@ impl Claim {
         self.claim_generator_hints.as_ref()
     }
 
+    /// Set the reference to the icon assertion of the claim generator.
+    ///
+    /// C2PA 1.x claims have no other place for the icon, so it is written to their
+    /// optional `claim_generator_info` list next to `claim_generator`, which readers
+    /// that do not know the field ignore.
+    pub fn set_claim_generator_icon(&mut self, icon: HashedUri) {
+        self.claim_generator_info = Some(vec![ClaimGeneratorInfo {
+            name: self.claim_generator.clone(),
+            version: None,
+            icon: Some(icon),
+            other: HashMap::new(),
+        }]);
+    }
+
+    /// Returns the reference to the icon assertion of the claim generator
+    pub fn claim_generator_icon(&self) -> Option<&HashedUri> {
+        self.claim_generator_info
+            .as_ref()
+            .and_then(|infos| infos.iter().find_map(|info| info.icon.as_ref()))
+    }
+
     pub fn calc_box_hash(
         label: &str,
         assertion: &Assertion,
@@ -465,12 +623,13 @@ impl Claim {
         Ok(hash_by_alg(alg, &hash_bytes, None))
     }
 
-    /// Add an assertion to this claim and verify
+    /// Add an assertion to this claim and verify, salting it if the salt policy applies to it
     pub fn add_assertion(
         &mut self,
         assertion_builder: &impl AssertionBase,
     ) -> Result<C2PAAssertion> {
-        self.add_assertion_with_salt(assertion_builder, NO_SALT)
+        let salt_policy = self.salt_policy.for_instance(&self.instance_id);
+        self.add_assertion_with_salt(assertion_builder, &salt_policy)
     }
 
     /// Add an assertion to this claim and verify with a salted assertion store
@@ -488,7 +647,7 @@ impl Claim {
         let as_label = self.make_assertion_instance_label(assertion.label().as_ref());
 
         // Get salted hash of the assertion's contents.
-        let salt = salt_generator.generate_salt();
+        let salt = salt_generator.generate_salt_for(&as_label);
 
         let hash = Claim::calc_box_hash(&as_label, &assertion, salt.clone(), self.alg())?;
 
@@ -507,6 +666,29 @@ impl Claim {
         Ok(c2pa_assertion)
     }
 
+    /// Add an assertion gathered by the claim generator rather than created by it.
+    /// C2PA 1.x claims do not make this distinction and list it with the other assertions.
+    pub fn add_gathered_assertion(
+        &mut self,
+        assertion_builder: &impl AssertionBase,
+    ) -> Result<C2PAAssertion> {
+        let c2pa_assertion = self.add_assertion(assertion_builder)?;
+        self.set_assertion_gathered(&c2pa_assertion);
+        Ok(c2pa_assertion)
+    }
+
+    // mark an assertion already added to this claim as gathered
+    pub(crate) fn set_assertion_gathered(&mut self, c2pa_assertion: &C2PAAssertion) {
+        self.gathered_assertions.push(c2pa_assertion.url());
+    }
+
+    /// Returns true if the assertion with this label (including instance) was gathered
+    pub fn is_gathered(&self, assertion_label: &str) -> bool {
+        self.gathered_assertions.iter().any(|url| {
+            jumbf::labels::assertion_label_from_uri(url).as_deref() == Some(assertion_label)
+        })
+    }
+
     pub(crate) fn vc_id(vc_json: &str) -> Result<String> {
         let vc: Value =
             serde_json::from_str(vc_json).map_err(|_err| Error::VerifiableCredentialInvalid)?; // check for json validity
@@ -553,6 +735,92 @@ impl Claim {
         &self.vc_store
     }
 
+    /// Add a databox to this claim and return a hashed URI referencing it.
+    /// Adding a databox with the same content as an existing one returns the existing reference.
+    pub fn add_databox(&mut self, databox: &DataBox) -> Result<HashedUri> {
+        let alg = self.alg().to_string();
+        Claim::add_databox_to_store(&mut self.databox_store, databox, &alg)
+    }
+
+    /// Return the databoxes of this claim
+    pub fn databox_store(&self) -> &Vec<ClaimAssertion> {
+        &self.databox_store
+    }
+
+    /// Return the databox a hashed URI references, checking that its hash matches
+    pub fn get_databox(&self, hashed_uri: &HashedUri) -> Result<DataBox> {
+        Claim::find_databox(&self.databox_store, hashed_uri, self.alg())
+    }
+
+    // adds a databox to a databox store unless one with the same content is already there
+    pub(crate) fn add_databox_to_store(
+        databox_store: &mut Vec<ClaimAssertion>,
+        databox: &DataBox,
+        alg: &str,
+    ) -> Result<HashedUri> {
+        let cbor = serde_cbor::to_vec(databox)?;
+        let assertion = Assertion::from_data_cbor(jumbf::labels::DATABOX, &cbor);
+
+        // the stored hashes identify the content, so only databoxes of the same size are
+        // hashed again under their own label rather than compared byte by byte
+        for existing in databox_store
+            .iter()
+            .filter(|d| d.assertion().data().len() == cbor.len())
+        {
+            let hash =
+                Claim::calc_box_hash(&existing.label(), &assertion, None, existing.hash_alg())?;
+            if vec_compare(&hash, existing.hash()) {
+                return Ok(Claim::databox_hashed_uri(existing));
+            }
+        }
+
+        let instance = databox_store.len();
+        let label = Claim::label_with_instance(jumbf::labels::DATABOX, instance);
+        let hash = Claim::calc_box_hash(&label, &assertion, None, alg)?;
+
+        let entry = ClaimAssertion::new(assertion, instance, &hash, alg, None);
+        let hashed_uri = Claim::databox_hashed_uri(&entry);
+        databox_store.push(entry);
+
+        Ok(hashed_uri)
+    }
+
+    // relative hashed URI referencing a databox
+    fn databox_hashed_uri(databox: &ClaimAssertion) -> HashedUri {
+        let link = jumbf::labels::to_relative_databox_uri(&databox.label());
+        HashedUri::new(link, None, databox.hash())
+    }
+
+    // finds the databox a hashed URI references in a databox store, checking its hash
+    pub(crate) fn find_databox(
+        databox_store: &[ClaimAssertion],
+        hashed_uri: &HashedUri,
+        alg: &str,
+    ) -> Result<DataBox> {
+        let label =
+            jumbf::labels::databox_label_from_uri(&hashed_uri.url()).ok_or(Error::NotFound)?;
+        let databox = databox_store
+            .iter()
+            .find(|d| d.label() == label)
+            .ok_or(Error::NotFound)?;
+
+        let alg = hashed_uri.alg().unwrap_or_else(|| alg.to_string());
+        let hash = Claim::calc_box_hash(&label, databox.assertion(), None, &alg)?;
+        if !vec_compare(&hash, &hashed_uri.hash()) {
+            return Err(Error::HashMismatch(format!(
+                "databox hash does not match: {}",
+                label
+            )));
+        }
+
+        Ok(serde_cbor::from_slice(databox.assertion().data())?)
+    }
+
+    /// Add a databox directly to the store during a reload of a claim
+    pub(crate) fn put_databox_store(&mut self, databox: ClaimAssertion) {
+        self.databox_store.push(databox);
+    }
+
     /// Add directly to store during a reload of a claim
     pub(crate) fn put_assertion_store(&mut self, assertion: ClaimAssertion) {
         self.assertion_store.push(assertion);
@@ -609,13 +877,53 @@ impl Claim {
         }
     }
 
+    // replace the content of the assertion with this label (including instance),
+    // the new content must have the same size so the claim size does not change
+    #[cfg(feature = "file_io")]
+    pub(crate) fn replace_assertion(
+        &mut self,
+        assertion_label: &str,
+        replacement_assertion: Assertion,
+    ) -> Result<()> {
+        let claim_assertion = self
+            .assertion_store
+            .iter_mut()
+            .find(|ca| ca.label() == assertion_label)
+            .ok_or(Error::NotFound)?;
+        if claim_assertion.assertion().data().len() != replacement_assertion.data().len() {
+            return Err(Error::JumbfCreationError);
+        }
+
+        let original_hash = claim_assertion.hash().to_vec();
+        let replacement_hash = Claim::calc_box_hash(
+            assertion_label,
+            &replacement_assertion,
+            claim_assertion.salt().clone(),
+            claim_assertion.hash_alg(),
+        )?;
+        claim_assertion.update_assertion(replacement_assertion, replacement_hash.clone())?;
+
+        // fix up hashed uri
+        let hashed_uri = self
+            .assertions
+            .iter_mut()
+            .find(|f| {
+                jumbf::labels::assertion_label_from_uri(&f.url()).as_deref()
+                    == Some(assertion_label)
+                    && vec_compare(&f.hash(), &original_hash)
+            })
+            .ok_or(Error::NotFound)?;
+        hashed_uri.update_hash(replacement_hash);
+        Ok(())
+    }
+
     /// Not ready for use!!!!!
     /// Redact an assertion from a prior claim.
     /// This will remove the assertion from the JUMBF
     fn redact_assertion(&mut self, assertion_uri: &str) -> Result<()> {
         // cannot redact action assertions per the spec
         let (label, _instance) = Claim::assertion_label_from_link(assertion_uri);
-        if label == assertions::labels::ACTIONS {
+        if label.starts_with(assertions::labels::ACTIONS) {
             return Err(Error::AssertionInvalidRedaction);
         }
 
@@ -632,6 +940,19 @@ impl Claim {
         }
     }
 
+    // record that a later claim redacted one of this claim's assertions
+    pub(crate) fn add_redaction_by_other(&mut self, assertion_uri: &str) {
+        self.redacted_by_others
+            .push(Claim::assertion_label_from_link(assertion_uri));
+    }
+
+    /// Returns true if a later claim redacted the assertion with this label and instance
+    pub fn is_redacted(&self, assertion_label: &str, instance: usize) -> bool {
+        self.redacted_by_others
+            .iter()
+            .any(|(label, i)| label == assertion_label && *i == instance)
+    }
+
     /// Return a hash of this claim.
     pub fn hash(&self) -> Vec<u8> {
         match self.data() {
@@ -649,6 +970,18 @@ impl Claim {
         }
     }
 
+    /// Return the issuers of the co-signatures of this claim
+    pub fn cosigning_issuers(&self) -> Vec<String> {
+        self.signature_info()
+            .map(|info| {
+                info.cosigners
+                    .into_iter()
+                    .filter_map(|c| c.issuer_org)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Return the signing date and time for this claim, if there is one.
     pub fn signing_issuer(&self) -> Option<String> {
         if let Some(validation_data) = self.signature_info() {
@@ -674,6 +1007,7 @@ impl Claim {
         claim: &Claim,
         asset_bytes: &[u8],
         is_provenance: bool,
+        cert_check: CertCheckOptions,
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
         // Parse COSE signed data (signature) and validate it.
@@ -697,7 +1031,7 @@ impl Claim {
                 "verify_claim_async"
             )
             .error(Error::ClaimMissingSignatureBox)
-            .validation_status(validation_status::CLAIM_SIGNATURE_MISSING);
+            .validation_status(ValidationStatusCode::ClaimSignatureMissing);
 
             validation_log.log(log_item, Some(Error::ClaimMissingSignatureBox))?;
         }
@@ -707,19 +1041,55 @@ impl Claim {
             claim_data,
             additional_bytes,
             !is_provenance,
+            cert_check,
             validation_log,
         )
         .await;
-        Claim::verify_internal(claim, asset_bytes, is_provenance, verified, validation_log)
+        Claim::verify_internal(
+            claim,
+            Some(asset_bytes),
+            is_provenance,
+            verified,
+            validation_log,
+        )
     }
 
     /// Verify claim signature, assertion store and asset hashes
     /// claim - claim to be verified
     /// asset_bytes - reference to bytes of the asset
+    /// cert_check - how the signing certificate is checked
     pub fn verify_claim(
         claim: &Claim,
         asset_bytes: &[u8],
         is_provenance: bool,
+        cert_check: CertCheckOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        Claim::verify_claim_impl(
+            claim,
+            Some(asset_bytes),
+            is_provenance,
+            cert_check,
+            validation_log,
+        )
+    }
+
+    /// Verify claim signature and assertion store, the asset hashes are
+    /// left for the caller to check
+    pub(crate) fn verify_claim_without_asset(
+        claim: &Claim,
+        is_provenance: bool,
+        cert_check: CertCheckOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        Claim::verify_claim_impl(claim, None, is_provenance, cert_check, validation_log)
+    }
+
+    fn verify_claim_impl(
+        claim: &Claim,
+        asset_bytes: Option<&[u8]>,
+        is_provenance: bool,
+        cert_check: CertCheckOptions,
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
         // Parse COSE signed data (signature) and validate it.
@@ -738,7 +1108,7 @@ impl Claim {
         if sig_box_err {
             let log_item = log_item!(claim.signature_uri(), "signature missing", "verify_claim")
                 .error(Error::ClaimMissingSignatureBox)
-                .validation_status(validation_status::CLAIM_SIGNATURE_MISSING);
+                .validation_status(ValidationStatusCode::ClaimSignatureMissing);
             validation_log.log(log_item, Some(Error::ClaimMissingSignatureBox))?;
         }
 
@@ -747,21 +1117,21 @@ impl Claim {
             &claim.data()?,
             &additional_bytes,
             !is_provenance,
+            cert_check,
             validation_log,
         );
 
         Claim::verify_internal(claim, asset_bytes, is_provenance, verified, validation_log)
     }
 
+    // asset hashes are only checked when `asset_bytes` is supplied
     fn verify_internal(
         claim: &Claim,
-        asset_bytes: &[u8],
+        asset_bytes: Option<&[u8]>,
         is_provenance: bool,
         verified: Result<ValidationInfo>,
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
-        let default_str = |s: &String| s.clone();
-
         match verified {
             Ok(vi) => {
                 if !vi.validated {
@@ -771,17 +1141,50 @@ impl Claim {
                         "verify_internal"
                     )
                     .error(Error::CoseSignature)
-                    .validation_status(validation_status::CLAIM_SIGNATURE_MISMATCH);
+                    .validation_status(ValidationStatusCode::ClaimSignatureMismatch);
                     validation_log.log(log_item, Some(Error::CoseSignature))?;
+                } else if !claim.claim_version().allows_signing_alg(&vi.alg) {
+                    let log_item = log_item!(
+                        claim.signature_uri(),
+                        "signature algorithm not allowed for claim version",
+                        "verify_internal"
+                    )
+                    .error(Error::CoseSignatureAlgorithmNotSupported)
+                    .validation_status(ValidationStatusCode::AlgorithmUnsupported);
+                    validation_log
+                        .log(log_item, Some(Error::CoseSignatureAlgorithmNotSupported))?;
                 } else {
                     let log_item = log_item!(
                         claim.signature_uri(),
                         "claim signature valid",
                         "verify_internal"
                     )
-                    .validation_status(validation_status::CLAIM_SIGNATURE_VALIDATED);
+                    .validation_status(ValidationStatusCode::ClaimSignatureValidated);
                     validation_log.log_silent(log_item);
                 }
+
+                // report each co-signer separately
+                for cosigner in &vi.cosigners {
+                    let issuer = cosigner.issuer_org.as_deref().unwrap_or("unknown issuer");
+                    if cosigner.validated {
+                        let log_item = log_item!(
+                            claim.signature_uri(),
+                            format!("co-signature by {} valid", issuer),
+                            "verify_internal"
+                        )
+                        .validation_status(ValidationStatusCode::CosignatureValidated);
+                        validation_log.log_silent(log_item);
+                    } else {
+                        let log_item = log_item!(
+                            claim.signature_uri(),
+                            format!("co-signature by {} is not valid", issuer),
+                            "verify_internal"
+                        )
+                        .error(Error::CoseSignature)
+                        .validation_status(ValidationStatusCode::CosignatureMismatch);
+                        validation_log.log(log_item, Some(Error::CoseSignature))?;
+                    }
+                }
             }
             Err(parse_err) => {
                 let log_item = log_item!(
@@ -790,7 +1193,7 @@ impl Claim {
                     "verify_internal"
                 )
                 .error(parse_err)
-                .validation_status(validation_status::CLAIM_SIGNATURE_MISMATCH);
+                .validation_status(ValidationStatusCode::ClaimSignatureMismatch);
                 validation_log.log(log_item, Some(Error::CoseSignature))?;
             }
         };
@@ -807,7 +1210,7 @@ impl Claim {
                         "verify_internal"
                     )
                     .error(Error::ClaimSelfRedact)
-                    .validation_status(validation_status::ASSERTION_SELF_REDACTED);
+                    .validation_status(ValidationStatusCode::AssertionSelfRedacted);
                     validation_log.log(log_item, Some(Error::ClaimSelfRedact))?;
                 }
 
@@ -818,23 +1221,53 @@ impl Claim {
                         "verify_internal"
                     )
                     .error(Error::ClaimDisallowedRedaction)
-                    .validation_status(validation_status::ACTION_ASSERTION_REDACTED);
+                    .validation_status(ValidationStatusCode::ActionAssertionRedacted);
                     validation_log.log(log_item, Some(Error::ClaimDisallowedRedaction))?;
                 }
             }
         }
 
-        // make sure UpdateManifests do not contain actions
-        if claim.update_manifest() && claim.label().contains(assertions::labels::ACTIONS) {
-            let log_item = log_item!(
-                claim.uri(),
-                "update manifests cannot contain actions",
-                "verify_internal"
-            )
-            .error(Error::UpdateManifestInvalid)
-            .validation_status(validation_status::MANIFEST_UPDATE_INVALID);
-            validation_log.log(log_item, Some(Error::UpdateManifestInvalid))?;
+        // make sure update manifests only contain the assertions allowed in them
+        if claim.update_manifest() {
+            if let Err(reason) = claim.check_update_manifest_assertions() {
+                let log_item = log_item!(claim.uri(), reason, "verify_internal")
+                    .error(Error::UpdateManifestInvalid)
+                    .validation_status(ValidationStatusCode::ManifestUpdateInvalid);
+                validation_log.log(log_item, Some(Error::UpdateManifestInvalid))?;
+            }
+        }
+
+        // check actions and training-mining assertions against their schemas
+        for ca in claim.claim_assertion_store() {
+            let assertion = ca.assertion();
+            let (result, code, invalid): (_, _, fn(String) -> Error) =
+                match assertion.label_root().as_str() {
+                    assertions::labels::ACTIONS => (
+                        assertions::Actions::from_assertion(assertion)
+                            .and_then(|actions| actions.validate()),
+                        ValidationStatusCode::AssertionActionMalformed,
+                        Error::AssertionActionsInvalid,
+                    ),
+                    assertions::labels::TRAINING_MINING => (
+                        assertions::TrainingMining::from_assertion(assertion).map(|_| ()),
+                        ValidationStatusCode::AssertionMalformed,
+                        Error::AssertionTrainingMiningInvalid,
+                    ),
+                    _ => continue,
+                };
+            if let Err(e) = result {
+                let message = e.to_string();
+                let log_item = log_item!(
+                    jumbf::labels::to_assertion_uri(claim.label(), &ca.label()),
+                    format!("assertion is malformed: {}", message),
+                    "verify_internal"
+                )
+                .error(e)
+                .validation_status(code);
+                validation_log.log(log_item, Some(invalid(message)))?;
+            }
         }
+
         // verify assertion structure comparing hashes from assertion list to contents of assertion store
         for assertion in claim.assertions() {
             let (label, instance) = Claim::assertion_label_from_link(&assertion.url());
@@ -851,7 +1284,7 @@ impl Claim {
                             "Assertion hash failure: {}",
                             assertion.url()
                         )))
-                        .validation_status(validation_status::ASSERTION_HASHEDURI_MISMATCH);
+                        .validation_status(ValidationStatusCode::AssertionHashedUriMismatch);
                         validation_log.log(
                             log_item,
                             Some(Error::HashMismatch(format!(
@@ -865,10 +1298,18 @@ impl Claim {
                             format!("hashed uri matched: {}", assertion.url()),
                             "verify_internal"
                         )
-                        .validation_status(validation_status::ASSERTION_HASHEDURI_MATCH);
+                        .validation_status(ValidationStatusCode::AssertionHashedUriMatch);
                         validation_log.log_silent(log_item);
                     }
                 }
+                None if claim.is_redacted(&label, instance) => {
+                    let log_item = log_item!(
+                        assertion.url(),
+                        format!("assertion redacted: {}", assertion.url()),
+                        "verify_internal"
+                    );
+                    validation_log.log_silent(log_item);
+                }
                 None => {
                     let log_item = log_item!(
                         assertion.url(),
@@ -878,7 +1319,7 @@ impl Claim {
                     .error(Error::AssertionMissing {
                         url: assertion.url(),
                     })
-                    .validation_status(validation_status::ASSERTION_MISSING);
+                    .validation_status(ValidationStatusCode::AssertionMissing);
                     validation_log.log(
                         log_item,
                         Some(Error::AssertionMissing {
@@ -891,68 +1332,172 @@ impl Claim {
 
         // verify data hashes for provenance claims
         if is_provenance {
-            // must have at least one hard binding for normal manifests
-            if claim.data_hash_assertions().is_empty() && !claim.update_manifest() {
-                let log_item = log_item!(
-                    &claim.uri(),
-                    "claim missing data binding",
-                    "verify_internal"
-                )
-                .error(Error::ClaimMissingHardBinding)
-                .validation_status(validation_status::HARD_BINDINGS_MISSING);
-                validation_log.log(log_item, Some(Error::ClaimMissingHardBinding))?;
-            }
+            Claim::verify_hard_bindings(claim, asset_bytes, validation_log)?;
+        }
+        Ok(())
+    }
 
-            // update manifests cannot have data hashes
-            if !claim.data_hash_assertions().is_empty() && claim.update_manifest() {
-                let log_item = log_item!(
-                    &claim.uri(),
-                    "update manifests cannot contain data hash assertions",
-                    "verify_internal"
-                )
-                .error(Error::UpdateManifestInvalid)
-                .validation_status(validation_status::MANIFEST_UPDATE_INVALID);
-                validation_log.log(log_item, Some(Error::UpdateManifestInvalid))?;
+    /// Verify the hard bindings of a provenance claim to the asset, without checking
+    /// its signature. The hashes are only checked when `asset_bytes` is supplied.
+    pub(crate) fn verify_hard_bindings(
+        claim: &Claim,
+        asset_bytes: Option<&[u8]>,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        // must have at least one hard binding for normal manifests,
+        // C2PA 2.x claims must list it in the created assertions
+        let created_hard_bindings = claim
+            .data_hash_assertions()
+            .iter()
+            .chain(claim.collection_hash_assertions().iter())
+            .filter(|a| claim.claim_version() == ClaimVersion::V1 || !claim.is_gathered(&a.label()))
+            .count();
+        if created_hard_bindings == 0 && !claim.update_manifest() {
+            let log_item = log_item!(
+                &claim.uri(),
+                "claim missing data binding",
+                "verify_hard_bindings"
+            )
+            .error(Error::ClaimMissingHardBinding)
+            .validation_status(ValidationStatusCode::HardBindingsMissing);
+            validation_log.log(log_item, Some(Error::ClaimMissingHardBinding))?;
+        }
+
+        // update manifests cannot have data hashes
+        if !claim.data_hash_assertions().is_empty() && claim.update_manifest() {
+            let log_item = log_item!(
+                &claim.uri(),
+                "update manifests cannot contain data hash assertions",
+                "verify_hard_bindings"
+            )
+            .error(Error::UpdateManifestInvalid)
+            .validation_status(ValidationStatusCode::ManifestUpdateInvalid);
+            validation_log.log(log_item, Some(Error::UpdateManifestInvalid))?;
+        }
+
+        match asset_bytes {
+            Some(asset_bytes) => {
+                Claim::verify_binding_hashes(claim, asset_bytes, false, validation_log)
             }
+            None => Ok(()),
+        }
+    }
 
-            for dh_assertion in claim.data_hash_assertions() {
-                let dh = DataHash::from_assertion(&dh_assertion)?;
-                let name = dh.name.as_ref().map_or("unnamed".to_string(), default_str);
-                if !dh.is_remote_hash() {
-                    // only verify local hashes here
-                    match dh.verify_in_memory_hash(asset_bytes, Some(claim.alg().to_string())) {
-                        Ok(_a) => {
-                            let log_item = log_item!(
-                                claim.assertion_uri(&dh_assertion.label()),
-                                "data hash valid",
-                                "verify_internal"
-                            )
-                            .validation_status(validation_status::ASSERTION_DATAHASH_MATCH);
-                            validation_log.log_silent(log_item);
-
-                            continue;
-                        }
-                        Err(e) => {
-                            let log_item = log_item!(
-                                claim.assertion_uri(&dh_assertion.label()),
-                                format!("asset hash error, name: {}, error: {}", name, e),
-                                "verify_internal"
-                            )
-                            .error(Error::HashMismatch(format!("Asset hash failure: {}", e)))
-                            .validation_status(validation_status::ASSERTION_DATAHASH_MISMATCH);
-
-                            validation_log.log(
-                                log_item,
-                                Some(Error::HashMismatch(format!("Asset hash failure: {}", e))),
-                            )?;
+    /// Verify the hashes of the hard bindings of a claim to the asset. When `stripped` is
+    /// set the manifest store was removed from the asset, so the ranges the data hashes
+    /// exclude are no longer in `asset_bytes`.
+    pub(crate) fn verify_binding_hashes(
+        claim: &Claim,
+        asset_bytes: &[u8],
+        stripped: bool,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        let default_str = |s: &String| s.clone();
+
+        for dh_assertion in claim.data_hash_assertions() {
+            let dh = DataHash::from_assertion(&dh_assertion)?;
+            let name = dh.name.as_ref().map_or("unnamed".to_string(), default_str);
+            if !dh.is_remote_hash() {
+                // only verify local hashes here
+                let alg = Some(claim.alg().to_string());
+                let verified = if stripped {
+                    dh.verify_stripped_hash(asset_bytes, alg)
+                } else {
+                    dh.verify_in_memory_hash(asset_bytes, alg)
+                };
+                match verified {
+                    Ok(_a) => {
+                        let log_item = log_item!(
+                            claim.assertion_uri(&dh_assertion.label()),
+                            "data hash valid",
+                            "verify_hard_bindings"
+                        )
+                        .validation_status(ValidationStatusCode::AssertionDataHashMatch);
+                        validation_log.log_silent(log_item);
+
+                        continue;
+                    }
+                    Err(e) => {
+                        let mut log_item = log_item!(
+                            claim.assertion_uri(&dh_assertion.label()),
+                            format!("asset hash error, name: {}, error: {}", name, e),
+                            "verify_hard_bindings"
+                        )
+                        .error(Error::HashMismatch(format!("Asset hash failure: {}", e)))
+                        .validation_status(ValidationStatusCode::AssertionDataHashMismatch);
+                        // the exclusions describe the layout of the asset with its manifest store
+                        if !stripped && matches!(e, Error::HashMismatch(_)) {
+                            log_item = log_item.hash_mismatch(dh.mismatch(asset_bytes));
                         }
+
+                        validation_log.log(
+                            log_item,
+                            Some(Error::HashMismatch(format!("Asset hash failure: {}", e))),
+                        )?;
                     }
                 }
             }
         }
+
+        for ch_assertion in claim.collection_hash_assertions() {
+            let ch = CollectionHash::from_assertion(&ch_assertion)?;
+            match Claim::verify_collection_hash(&ch, asset_bytes, claim.alg()) {
+                Ok(_a) => {
+                    let log_item = log_item!(
+                        claim.assertion_uri(&ch_assertion.label()),
+                        "collection hash valid",
+                        "verify_hard_bindings"
+                    )
+                    .validation_status(ValidationStatusCode::AssertionCollectionHashMatch);
+                    validation_log.log_silent(log_item);
+                }
+                Err(e) => {
+                    let log_item = log_item!(
+                        claim.assertion_uri(&ch_assertion.label()),
+                        format!("collection hash error: {}", e),
+                        "verify_hard_bindings"
+                    )
+                    .error(Error::HashMismatch(format!(
+                        "Collection hash failure: {}",
+                        e
+                    )))
+                    .validation_status(ValidationStatusCode::AssertionCollectionHashMismatch);
+
+                    validation_log.log(
+                        log_item,
+                        Some(Error::HashMismatch(format!(
+                            "Collection hash failure: {}",
+                            e
+                        ))),
+                    )?;
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Verify a collection hash against the members of a collection, the entries of a
+    /// ZIP archive if it hashes a central directory or the parts of a MIME message otherwise
+    pub(crate) fn verify_collection_hash(
+        collection_hash: &CollectionHash,
+        asset_bytes: &[u8],
+        alg: &str,
+    ) -> Result<()> {
+        if collection_hash.zip_central_directory_hash.is_some() {
+            return crate::asset_handlers::zip_io::verify_collection_hash(
+                collection_hash,
+                asset_bytes,
+                alg,
+            );
+        }
+
+        let parts = crate::asset_handlers::eml_io::mime_parts(asset_bytes)?;
+        collection_hash.verify_parts(
+            parts.iter().map(|p| (p.uri.as_str(), p.content.as_slice())),
+            Some(alg),
+        )
+    }
+
     /// Verify hash against self.  True if match,
     /// false if no match or unsupported
     pub fn verify_hash(&self, hash: &[u8]) -> bool {
@@ -980,6 +1525,74 @@ impl Claim {
         data_hashes
     }
 
+    /// Return list of collection hash assertions
+    pub fn collection_hash_assertions(&self) -> Vec<Assertion> {
+        let dummy_data = AssertionData::Cbor(Vec::new());
+        let dummy_hash = Assertion::new(CollectionHash::LABEL, None, dummy_data);
+        self.assertions_by_type(&dummy_hash)
+    }
+
+    /// Check that an update manifest only has assertions allowed in update manifests.
+    ///
+    /// Update manifests record metadata changes without binding the asset again, so they
+    /// cannot have hard bindings or a claim thumbnail and their actions can only change
+    /// metadata. Returns why the claim is not a valid update manifest.
+    pub(crate) fn check_update_manifest_assertions(&self) -> std::result::Result<(), String> {
+        for label in [
+            labels::DATA_HASH,
+            labels::BMFF_HASH,
+            labels::COLLECTION_HASH,
+        ] {
+            if self.has_assertion_type(label) {
+                return Err(format!(
+                    "update manifests cannot contain {} assertions",
+                    label
+                ));
+            }
+        }
+        if self.has_assertion_type(labels::CLAIM_THUMBNAIL) {
+            return Err("update manifests cannot contain a claim thumbnail".to_string());
+        }
+
+        let dummy_actions = Assertion::new(labels::ACTIONS, None, AssertionData::Cbor(Vec::new()));
+        for assertion in self.assertions_by_type(&dummy_actions) {
+            let actions = assertions::Actions::from_assertion(&assertion)
+                .map_err(|_e| "update manifest actions are invalid".to_string())?;
+            if let Some(action) = actions
+                .actions()
+                .iter()
+                .find(|action| !UPDATE_MANIFEST_ACTIONS.contains(&action.action()))
+            {
+                return Err(format!(
+                    "update manifests cannot contain {} actions",
+                    action.action()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the hashed URI of a claim or ingredient thumbnail in this claim with the
+    /// same image bytes, so an identical thumbnail can be referenced instead of added again.
+    pub(crate) fn find_thumbnail(&self, image: &[u8]) -> Option<C2PAAssertion> {
+        let claim_assertion = self.assertion_store.iter().find(|ca| {
+            let label = ca.label_raw();
+            (label.starts_with(labels::CLAIM_THUMBNAIL)
+                || label.starts_with(labels::INGREDIENT_THUMBNAIL))
+                && ca.assertion().data() == image
+        })?;
+
+        let label = claim_assertion.label();
+        self.assertions()
+            .iter()
+            .find(|hashed_uri| {
+                jumbf::labels::assertion_label_from_uri(&hashed_uri.url()).as_deref()
+                    == Some(label.as_str())
+            })
+            .cloned()
+    }
+
     /// Return list of ingredient assertions. This function
     /// is only useful on commited or loaded claims since ingredients
     /// are resolved at commit time.
@@ -1027,8 +1640,12 @@ impl Claim {
             }
         }
 
-        // all have been removed (if necessary) so replace redaction list
-        self.redacted_assertions = redactions_opt;
+        // all have been removed (if necessary) so add them to the redaction list
+        if let Some(mut redactions) = redactions_opt {
+            self.redacted_assertions
+                .get_or_insert_with(Vec::new)
+                .append(&mut redactions);
+        }
 
         // add ingredients
         self.ingredients_store
@@ -1079,13 +1696,35 @@ impl Claim {
     pub fn data(&self) -> Result<Vec<u8>> {
         match self.original_bytes {
             Some(ref ob) => Ok(ob.clone()),
-            None => Ok(serde_cbor::ser::to_vec(&self).map_err(|_err| Error::ClaimEncoding)?),
+            None => match self.claim_version {
+                ClaimVersion::V1 => serde_cbor::ser::to_vec(&self),
+                ClaimVersion::V2 => serde_cbor::ser::to_vec(&self.to_data_v2()),
+            }
+            .map_err(|_err| Error::ClaimEncoding),
         }
     }
 
     /// Create claim from binary data (not including assertions).
     pub fn from_data(label: &str, data: &[u8]) -> Result<Claim> {
-        let mut claim: Claim = serde_cbor::from_slice(data).map_err(|_err| Error::ClaimDecoding)?;
+        Claim::from_data_with_version(label, data, ClaimVersion::V1)
+    }
+
+    /// Create claim from binary data serialized with the given specification version.
+    pub fn from_data_with_version(
+        label: &str,
+        data: &[u8],
+        version: ClaimVersion,
+    ) -> Result<Claim> {
+        let mut claim = match version {
+            ClaimVersion::V1 => {
+                serde_cbor::from_slice(data).map_err(|_err| Error::ClaimDecoding)?
+            }
+            ClaimVersion::V2 => {
+                let data_v2: ClaimDataV2 =
+                    serde_cbor::from_slice(data).map_err(|_err| Error::ClaimDecoding)?;
+                Claim::from_data_v2(data_v2)
+            }
+        };
 
         claim.label = label.to_string();
         claim.original_bytes = Some(data.to_owned());
@@ -1093,6 +1732,64 @@ impl Claim {
         Ok(claim)
     }
 
+    // split the assertions into created and gathered lists for a C2PA 2.x claim
+    fn to_data_v2(&self) -> ClaimDataV2 {
+        let (gathered, created): (Vec<C2PAAssertion>, Vec<C2PAAssertion>) = self
+            .assertions
+            .iter()
+            .cloned()
+            .partition(|a| self.gathered_assertions.contains(&a.url()));
+
+        ClaimDataV2 {
+            instance_id: self.instance_id.clone(),
+            title: self.title.clone(),
+            claim_generator_info: ClaimGeneratorInfo {
+                name: self.claim_generator.clone(),
+                version: None,
+                icon: self.claim_generator_icon().cloned(),
+                other: self.claim_generator_hints.clone().unwrap_or_default(),
+            },
+            signature: self.signature.clone(),
+            created_assertions: created,
+            gathered_assertions: gathered,
+            redacted_assertions: self.redacted_assertions.clone(),
+            alg: self.alg.clone(),
+            alg_soft: self.alg_soft.clone(),
+        }
+    }
+
+    fn from_data_v2(data_v2: ClaimDataV2) -> Claim {
+        let info = data_v2.claim_generator_info;
+        let claim_generator = match info.version {
+            Some(version) => format!("{}/{}", info.name, version),
+            None => info.name,
+        };
+
+        let mut claim = Claim::new(&claim_generator, None);
+        claim.claim_version = ClaimVersion::V2;
+        claim.instance_id = data_v2.instance_id;
+        claim.title = data_v2.title;
+        claim.signature = data_v2.signature;
+        claim.redacted_assertions = data_v2.redacted_assertions;
+        claim.alg = data_v2.alg;
+        claim.alg_soft = data_v2.alg_soft;
+        if let Some(icon) = info.icon {
+            claim.set_claim_generator_icon(icon);
+        }
+        if !info.other.is_empty() {
+            claim.claim_generator_hints = Some(info.other);
+        }
+        claim.gathered_assertions = data_v2
+            .gathered_assertions
+            .iter()
+            .map(|a| a.url())
+            .collect();
+        claim.assertions = data_v2.created_assertions;
+        claim.assertions.extend(data_v2.gathered_assertions);
+
+        claim
+    }
+
     /// Generate a JSON representation of the Claim
     /// returns Result as a String
     pub fn to_json(
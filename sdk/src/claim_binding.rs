@@ -0,0 +1,112 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Recompute the values that bind a claim to its signature.
+//!
+//! These let external systems, such as a job re-verifying signatures with a KMS,
+//! check a manifest without running the full validation pipeline.
+
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use crate::{
+    jumbf_io::load_jumbf_from_memory, status_tracker::OneShotStatusTracker, store::Store, Result,
+};
+
+/// The signed parts of a claim signature (COSE_Sign1)
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureBinding {
+    /// The signature algorithm, e.g. "es256", if it is supported
+    pub alg: Option<String>,
+    /// The COSE `Sig_structure` bytes the signature was computed over
+    pub to_be_signed: Vec<u8>,
+    /// The raw signature
+    pub signature: Vec<u8>,
+    /// The DER encoded certificate chain, signing certificate first
+    pub certs: Vec<Vec<u8>>,
+}
+
+/// The hash and signature binding of a claim
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimBinding {
+    /// Label of the manifest containing the claim
+    pub manifest_label: String,
+    /// Hash algorithm of the claim, used for references to the claim
+    pub claim_hash_alg: String,
+    /// Hash of the claim bytes
+    pub claim_hash: Vec<u8>,
+    /// The serialized claim, this is the payload of the signature
+    pub claim_bytes: Vec<u8>,
+    /// The signature over the claim
+    pub signature: SignatureBinding,
+}
+
+impl ClaimBinding {
+    /// Compute the binding of a manifest in a JUMBF manifest store.
+    ///
+    /// Uses the active manifest when `manifest_label` is `None`.
+    pub fn from_jumbf(jumbf: &[u8], manifest_label: Option<&str>) -> Result<Self> {
+        let store = Store::from_jumbf(jumbf, &mut OneShotStatusTracker::new())?;
+        store.claim_binding(manifest_label)
+    }
+
+    /// Compute the binding of a manifest embedded in an in-memory asset.
+    ///
+    /// Uses the active manifest when `manifest_label` is `None`.
+    pub fn from_bytes(format: &str, data: &[u8], manifest_label: Option<&str>) -> Result<Self> {
+        let jumbf = load_jumbf_from_memory(format, data)?;
+        Self::from_jumbf(&jumbf, manifest_label)
+    }
+
+    /// Compute the binding of a manifest embedded in an asset file.
+    ///
+    /// Uses the active manifest when `manifest_label` is `None`.
+    #[cfg(feature = "file_io")]
+    pub fn from_file<P: AsRef<Path>>(path: P, manifest_label: Option<&str>) -> Result<Self> {
+        let jumbf = crate::jumbf_io::load_jumbf_from_file(path.as_ref())?;
+        Self::from_jumbf(&jumbf, manifest_label)
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    use crate::{utils::hash_utils::hash_by_alg, utils::test::fixture_path, validator};
+
+    #[test]
+    fn test_claim_binding() {
+        let binding = ClaimBinding::from_file(fixture_path("CA.jpg"), None).unwrap();
+
+        assert_eq!(
+            binding.claim_hash,
+            hash_by_alg(&binding.claim_hash_alg, &binding.claim_bytes, None)
+        );
+
+        // the signature must validate over the recomputed bytes with the signing certificate
+        let alg = binding.signature.alg.as_deref().unwrap();
+        let cert = openssl::x509::X509::from_der(&binding.signature.certs[0]).unwrap();
+        let public_key = cert.public_key().unwrap().public_key_to_der().unwrap();
+        let validator = validator::get_validator(alg).unwrap();
+        assert!(validator
+            .validate(
+                &binding.signature.signature,
+                &binding.signature.to_be_signed,
+                &public_key
+            )
+            .unwrap());
+    }
+}
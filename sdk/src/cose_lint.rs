@@ -0,0 +1,297 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Lints claim signatures produced outside the SDK.
+//!
+//! Vendors that bring their own signing service assemble the COSE_Sign1 themselves.
+//! Linting one reports every way it departs from what C2PA requires, rather than
+//! the first error validation stops at.
+
+use c2pa_core::cose::{parse_sign1, signing_alg, x5chain, x5chain_placement, X5ChainPlacement};
+use coset::{CoseSign1, Label};
+use x509_parser::prelude::*;
+
+use crate::{
+    cert_profile::{self, CertLintReport},
+    time_stamp::TimeStampStorage,
+    Result,
+};
+
+/// A C2PA requirement of claim signatures
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CoseRule {
+    /// The algorithm is in the protected header and is one C2PA allows
+    Algorithm,
+    /// The signing certificate chain is in an `x5chain` header, matching its `x5t`
+    /// thumbprint when there is one
+    X5Chain,
+    /// The `x5chain` is in the protected header, as C2PA 2.x requires. This is a soft rule.
+    X5ChainProtected,
+    /// The chain starts with the signing certificate and each certificate is issued
+    /// by the next one
+    CertChainOrder,
+    /// The signing certificate follows the signing certificate profile
+    SigningCertificate,
+    /// The signature has a `sigTst` or `sigTst2` time-stamp. This is a soft rule.
+    TimeStamp,
+    /// The payload is detached, the claim is not embedded in the signature
+    DetachedPayload,
+}
+
+impl CoseRule {
+    /// Returns true if the specification only recommends the rule. Breaking a soft
+    /// rule is a warning.
+    pub fn is_soft(&self) -> bool {
+        matches!(self, CoseRule::X5ChainProtected | CoseRule::TimeStamp)
+    }
+}
+
+/// The result of checking a signature against one rule
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseRuleResult {
+    /// The rule checked
+    pub rule: CoseRule,
+    /// Why the signature breaks the rule, `None` if it follows it
+    pub failure: Option<String>,
+}
+
+impl CoseRuleResult {
+    /// Returns true if the signature follows the rule
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// The results of linting a claim signature
+#[derive(Clone, Debug, PartialEq)]
+pub struct CoseLintReport {
+    /// The result of each rule that applies to the signature
+    pub results: Vec<CoseRuleResult>,
+    /// The lint of the signing certificate, `None` if the signature has none that
+    /// could be parsed
+    pub certificate: Option<CertLintReport>,
+}
+
+impl CoseLintReport {
+    /// Returns true if the signature follows every rule
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed())
+    }
+
+    /// Returns true if the signature follows every rule that is not soft
+    pub fn passed_required(&self) -> bool {
+        self.results.iter().all(|r| r.passed() || r.rule.is_soft())
+    }
+
+    /// The results of the rules the signature breaks
+    pub fn failures(&self) -> impl Iterator<Item = &CoseRuleResult> {
+        self.results.iter().filter(|r| !r.passed())
+    }
+}
+
+// the algorithms C2PA allows claims to be signed with
+const ALLOWED_ALGS: [&str; 7] = [
+    "ps256", "ps384", "ps512", "es256", "es384", "es512", "ed25519",
+];
+
+/// Lint tagged COSE_Sign1 bytes signing a claim against the requirements of C2PA.
+///
+/// Only the structure of the signature is checked. The signature itself is not
+/// verified, since that needs the claim, and certificate validity is checked
+/// against the current time. Returns an error if the bytes are not a COSE_Sign1.
+pub fn cose_lint(cose_bytes: &[u8]) -> Result<CoseLintReport> {
+    let sign1 = parse_sign1(cose_bytes)?;
+    Ok(lint_sign1(&sign1, cert_profile::now()?))
+}
+
+// check each rule against `sign1`, `time` is the validity time in seconds
+fn lint_sign1(sign1: &CoseSign1, time: i64) -> CoseLintReport {
+    let mut results = Vec::new();
+    let mut check = |rule: CoseRule, outcome: std::result::Result<(), String>| {
+        results.push(CoseRuleResult {
+            rule,
+            failure: outcome.err(),
+        })
+    };
+
+    check(CoseRule::Algorithm, check_algorithm(sign1));
+
+    let mut certificate = None;
+    match x5chain(sign1) {
+        Ok(certs) => {
+            check(CoseRule::X5Chain, Ok(()));
+            check(
+                CoseRule::X5ChainProtected,
+                match x5chain_placement(sign1) {
+                    Some(X5ChainPlacement::Protected) => Ok(()),
+                    _ => Err("x5chain should be in the protected header".to_owned()),
+                },
+            );
+
+            // the validity of the signing certificate is checked by its profile
+            let chain_failures: Vec<String> = cert_profile::check_chain(&certs, time)
+                .into_iter()
+                .filter(|r| !(r.index == 0 && r.expired))
+                .filter_map(|r| {
+                    r.failure
+                        .map(|failure| format!("certificate {}: {}", r.index, failure))
+                })
+                .collect();
+            check(
+                CoseRule::CertChainOrder,
+                if chain_failures.is_empty() {
+                    Ok(())
+                } else {
+                    Err(chain_failures.join(", "))
+                },
+            );
+
+            match X509Certificate::from_der(&certs[0]) {
+                Ok((_rem, signcert)) => {
+                    let report = CertLintReport {
+                        results: cert_profile::check_rules(&signcert, time),
+                    };
+                    let failures: Vec<&str> = report
+                        .failures()
+                        .filter(|r| !r.rule.is_soft())
+                        .filter_map(|r| r.failure.as_deref())
+                        .collect();
+                    check(
+                        CoseRule::SigningCertificate,
+                        if failures.is_empty() {
+                            Ok(())
+                        } else {
+                            Err(failures.join(", "))
+                        },
+                    );
+                    certificate = Some(report);
+                }
+                Err(_) => check(
+                    CoseRule::SigningCertificate,
+                    Err("signing certificate could not be parsed".to_owned()),
+                ),
+            }
+        }
+        Err(e) => check(CoseRule::X5Chain, Err(e.to_string())),
+    }
+
+    let has_timestamp = [TimeStampStorage::V2SigTst2, TimeStampStorage::V1SigTst]
+        .iter()
+        .any(|tss| {
+            sign1
+                .unprotected
+                .rest
+                .iter()
+                .any(|(label, _)| *label == Label::Text(tss.header_label().to_string()))
+        });
+    check(
+        CoseRule::TimeStamp,
+        if has_timestamp {
+            Ok(())
+        } else {
+            Err("no time-stamp, certificates are checked at the time of validation".to_owned())
+        },
+    );
+
+    check(
+        CoseRule::DetachedPayload,
+        if sign1.payload.is_none() {
+            Ok(())
+        } else {
+            Err("the payload must be detached".to_owned())
+        },
+    );
+
+    CoseLintReport {
+        results,
+        certificate,
+    }
+}
+
+fn check_algorithm(sign1: &CoseSign1) -> std::result::Result<(), String> {
+    match signing_alg(sign1) {
+        Ok(alg) if ALLOWED_ALGS.contains(&alg.as_str()) => Ok(()),
+        Ok(alg) if alg.starts_with("rs") => Err(format!(
+            "{} is deprecated, use an RSASSA-PSS, ECDSA or EdDSA algorithm",
+            alg
+        )),
+        Ok(alg) => Err(format!("{} is not an algorithm C2PA allows", alg)),
+        Err(_) if sign1.unprotected.alg.is_some() => {
+            Err("the algorithm must be in the protected header".to_owned())
+        }
+        Err(_) => Err("the algorithm header is missing".to_owned()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "file_io")]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use coset::TaggedCborSerializable;
+
+    use super::*;
+    use crate::{cose_sign::cose_sign, openssl::temp_signer::get_temp_signer_by_alg, Signer};
+
+    #[test]
+    fn test_cose_lint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = get_temp_signer_by_alg(&temp_dir.path(), "es256", None);
+
+        let data = b"some sample content to sign";
+        let cose = cose_sign(
+            &*signer,
+            data,
+            signer.reserve_size(),
+            TimeStampStorage::V2SigTst2,
+        )
+        .unwrap();
+
+        // only the soft rules are broken, there is no time-stamp authority and the
+        // chain is placed as in 1.x
+        let report = cose_lint(&cose).unwrap();
+        assert!(!report.passed());
+        assert!(report.passed_required());
+        assert!(report.failures().all(|r| r.rule.is_soft()));
+        assert!(report.failures().any(|r| r.rule == CoseRule::TimeStamp));
+        assert!(report.certificate.unwrap().passed());
+
+        // an attached payload is reported
+        let mut sign1 = parse_sign1(&cose).unwrap();
+        sign1.payload = Some(data.to_vec());
+        sign1.protected.original_data = None;
+        let report = cose_lint(&sign1.to_tagged_vec().unwrap()).unwrap();
+        assert!(!report.passed_required());
+        assert!(report
+            .failures()
+            .any(|r| r.rule == CoseRule::DetachedPayload));
+
+        // as is ES256K, which C2PA does not allow
+        let mut sign1 = parse_sign1(&cose).unwrap();
+        sign1.protected.header.alg = Some(coset::RegisteredLabelWithPrivate::Assigned(
+            coset::iana::Algorithm::ES256K,
+        ));
+        sign1.protected.original_data = None;
+        let report = cose_lint(&sign1.to_tagged_vec().unwrap()).unwrap();
+        assert!(report.failures().any(|r| r.rule == CoseRule::Algorithm));
+
+        // so is a missing chain
+        let mut sign1 = parse_sign1(&cose).unwrap();
+        sign1.unprotected.rest.clear();
+        let report = cose_lint(&sign1.to_tagged_vec().unwrap()).unwrap();
+        assert!(report.failures().any(|r| r.rule == CoseRule::X5Chain));
+        assert!(report.certificate.is_none());
+
+        assert!(cose_lint(b"not a signature").is_err());
+    }
+}
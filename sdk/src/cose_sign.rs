@@ -11,14 +11,42 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use crate::time_stamp::{cose_timestamp_countersign, make_cose_timestamp};
-use crate::{Error, Result, Signer}; // enable when TimeStamp Authority is ready
+#[cfg(feature = "file_io")]
+use crate::time_stamp::{
+    cose_timestamp_countersign, make_cose_timestamp, v1_countersign_header, TimeStampStorage,
+};
+use crate::Result;
+#[cfg(feature = "file_io")]
+use crate::{signer::operation_signer, TimeStampPolicy}; // enable when TimeStamp Authority is ready
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use crate::{Error, Signer};
 
+#[cfg(feature = "async_signer")]
+use crate::X5ChainPlacement;
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use c2pa_core::cose::{alg_header, pad_sign1, signing_headers, x5chain_value};
 use ciborium::value::Value;
-use coset::{iana, CoseSign1, CoseSign1Builder, HeaderBuilder, Label, TaggedCborSerializable};
+#[cfg(feature = "async_signer")]
+use coset::{sig_structure_data, SignatureContext};
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use coset::{
+    AsCborValue, CoseSign1Builder, CoseSignature, HeaderBuilder, ProtectedHeader,
+    TaggedCborSerializable,
+};
+use coset::{CoseSign1, Label};
+
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use crate::cose_validator::{cosignature_tbs, cosigners_value, COSIGNERS, COSIGS};
 
 /// Returns signed Cose_Sign1 bytes for "data".  The Cose_Sign1 will be signed with the algorithm from `Signer`.
-pub fn cose_sign(signer: &dyn Signer, data: &[u8], box_size: usize) -> Result<Vec<u8>> {
+/// A time-stamp from the signer's time authority is stored as described by `tss`.
+#[cfg(feature = "file_io")]
+pub fn cose_sign(
+    signer: &dyn Signer,
+    data: &[u8],
+    box_size: usize,
+    tss: TimeStampStorage,
+) -> Result<Vec<u8>> {
     // 13.2.1. X.509 Certificates
     //
     // X.509 Certificates are stored in a header named x5chain draft-ietf-cose-x509.
@@ -41,181 +69,259 @@ pub fn cose_sign(signer: &dyn Signer, data: &[u8], box_size: usize) -> Result<Ve
            string.
     */
 
+    let signer = operation_signer(signer)?;
     let alg = signer.alg().ok_or(Error::UnsupportedType)?;
 
-    let alg_id = match alg.as_ref() {
-        "ps256" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::PS256)
-            .build(),
-        "ps384" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::PS384)
-            .build(),
-        "ps512" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::PS512)
-            .build(),
-        /* No longer supported by C2PA
-        "rs256" => {
-            HeaderBuilder::new()
-                .algorithm(iana::Algorithm::RS256)
-                .build()
-        }
-        "rs384" => {
-            HeaderBuilder::new()
-                .algorithm(iana::Algorithm::RS384)
-                .build()
-        }
-        "rs512" => {
-            HeaderBuilder::new()
-                .algorithm(iana::Algorithm::RS512)
-                .build()
-        }
-        */
-        "es256" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::ES256)
-            .build(),
-        "es384" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::ES384)
-            .build(),
-        "es512" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::ES512)
-            .build(),
-        "ed25519" => HeaderBuilder::new()
-            .algorithm(iana::Algorithm::EdDSA)
-            .build(),
-        _ => return Err(Error::UnsupportedType),
-    };
+    // Get the public CAs for the Signer, limited to the chain the signer wants embedded,
+    // and place them in the protected or unprotected header as the signer wants
+    let certs = signer.cert_chain_policy().apply(signer.certs()?);
+    let (mut protected, mut unprotected) =
+        signing_headers(&alg, certs, signer.x5chain_placement())?;
 
-    // Get the public CAs for the Signer
-    let certs = signer.certs()?;
-    let sc_der_array_or_bytes = match certs.len() {
-        1 => Value::Bytes(certs[0].clone()), // single cert
-        _ => {
-            let mut sc_der_array: Vec<Value> = Vec::new();
-            for cert in certs {
-                sc_der_array.push(Value::Bytes(cert));
-            }
-            Value::Array(sc_der_array) // provide vec of certs when required
-        }
-    };
+    // commit to the co-signers so their co-signatures can't be stripped
+    if !signer.cosigners().is_empty() {
+        protected.rest.push(cosigners_header(signer.cosigners())?);
+    }
 
-    let mut unprotected = match signer.time_authority_url() {
-        Some(url) => {
-            let cts = cose_timestamp_countersign(data, &alg, &url)?;
+    let ts_policy = signer.time_stamp_policy();
+    let (ts_label, ts_value) = match (!ts_policy.urls.is_empty()).then(|| ts_policy) {
+        Some(ts_policy) => {
+            // 1.x time-stamps only cover the algorithm, 2.x cover the whole protected header
+            let ts_header = match tss {
+                TimeStampStorage::V1SigTst => v1_countersign_header(&alg),
+                TimeStampStorage::V2SigTst2 => ProtectedHeader {
+                    original_data: None,
+                    header: protected.clone(),
+                },
+            };
+            let cts = cose_timestamp_countersign(data, &ts_header, &ts_policy)?;
             let sigtst_vec = serde_cbor::to_vec(&make_cose_timestamp(&cts))?;
             let sigtst_cbor = serde_cbor::from_slice(&sigtst_vec)?;
 
-            HeaderBuilder::new()
-                .text_value("x5chain".to_string(), sc_der_array_or_bytes)
-                .text_value("sigTst".to_string(), sigtst_cbor)
+            (tss.header_label().to_string(), sigtst_cbor)
         }
         None => {
             let sign_time = chrono::Utc::now().to_rfc3339(); // todo: remove when switch to cose_timestamp
-            HeaderBuilder::new()
-                .text_value("x5chain".to_string(), sc_der_array_or_bytes)
-                .text_value("temp_signing_time".to_string(), Value::Text(sign_time))
+            ("temp_signing_time".to_string(), Value::Text(sign_time))
         }
     };
+    unprotected.rest.push((Label::Text(ts_label), ts_value));
 
-    // set the ocsp responder response if available
-    if let Some(ocsp) = signer.ocsp_val() {
+    // set the ocsp responder response if available and wanted
+    if let Some(ocsp) = signer.include_ocsp().then(|| signer.ocsp_val()).flatten() {
         let mut ocsp_vec: Vec<Value> = Vec::new();
         let mut r_vals: Vec<(Value, Value)> = vec![];
 
         ocsp_vec.push(Value::Bytes(ocsp));
         r_vals.push((Value::Text("ocspVals".to_string()), Value::Array(ocsp_vec)));
 
-        unprotected = unprotected.text_value("rVals".to_string(), Value::Map(r_vals));
+        unprotected
+            .rest
+            .push((Label::Text("rVals".to_string()), Value::Map(r_vals)));
     }
 
-    // build complete header
-    let unprotected_header = unprotected.build();
-
     let aad = b""; // no additional data required here
 
     let sign1_builder = CoseSign1Builder::new()
-        .protected(alg_id)
-        .unprotected(unprotected_header)
+        .protected(protected)
+        .unprotected(unprotected)
         .payload(data.to_vec())
         .try_create_signature(aad, |bytes| signer.sign(bytes))?;
 
     let mut sign1 = sign1_builder.build();
     sign1.payload = None; // clear the payload since it is known
 
-    let c2pa_sig_data = pad_cose_sig(&mut sign1, box_size)?;
+    add_cosignatures(&mut sign1, signer.cosigners(), data)?;
+
+    let c2pa_sig_data = pad_sign1(&mut sign1, box_size)?;
 
     // println!("sig: {}", Hexlify(&c2pa_sig_data));
 
     Ok(c2pa_sig_data)
 }
 
-const PAD: &str = "pad";
-const PAD2: &str = "pad2";
-const PAD_OFFSET: usize = 7;
-
-// Pad the CoseSign1 structure with 0s to match the reserved box size.
-// There are some values lengths that are impossible to hit with a single padding so
-// when that happens a second padding is added to change the remaining needed padding.
-// The default initial guess works for almost all sizes, without the need for additional loops.
-fn pad_cose_sig(sign1: &mut CoseSign1, end_size: usize) -> Result<Vec<u8>> {
-    let mut sign1_clone = sign1.clone();
-    let cur_vec = sign1_clone
-        .to_tagged_vec()
+// the protected header entry committing a signature to `cosigners`
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+fn cosigners_header(cosigners: &[Box<dyn Signer>]) -> Result<(Label, Value)> {
+    let certs = cosigners
+        .iter()
+        .map(|cosigner| {
+            cosigner
+                .certs()?
+                .into_iter()
+                .next()
+                .ok_or(Error::CoseX5ChainMissing)
+        })
+        .collect::<Result<Vec<Vec<u8>>>>()?;
+    Ok((
+        Label::Text(COSIGNERS.to_string()),
+        cosigners_value(certs.iter().map(|cert| cert.as_slice())),
+    ))
+}
+
+// co-signers countersign the claim and the signature of "sign1" over "data"
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+fn add_cosignatures(
+    sign1: &mut CoseSign1,
+    cosigners: &[Box<dyn Signer>],
+    data: &[u8],
+) -> Result<()> {
+    let cosigs = cosigners
+        .iter()
+        .map(|cosigner| cose_cosign(sign1, cosigner.as_ref(), data))
+        .collect::<Result<Vec<Value>>>()?;
+    if !cosigs.is_empty() {
+        sign1
+            .unprotected
+            .rest
+            .push((Label::Text(COSIGS.to_string()), Value::Array(cosigs)));
+    }
+    Ok(())
+}
+
+/// Adds the co-signatures of `cosigners` to Cose_Sign1 bytes signed over "data" by an
+/// [`AsyncSigner`](crate::AsyncSigner), keeping their size.
+///
+/// The signature must commit to the co-signers, as the ones from [`cose_sign1_to_sign`] do.
+#[cfg(feature = "async_signer")]
+pub(crate) fn cose_add_cosignatures(
+    cose_bytes: &[u8],
+    cosigners: &[Box<dyn Signer>],
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    let mut sign1 = <CoseSign1 as TaggedCborSerializable>::from_tagged_slice(cose_bytes)
         .map_err(|_e| Error::CoseSignature)?;
-    let cur_size = cur_vec.len();
 
-    // check for box too small
-    match cur_size > end_size {
-        true => {
-            return Err(Error::CoseSigboxTooSmall);
-        }
-        false if cur_size == end_size => return Ok(cur_vec),
-        false => (),
+    let commitment = cosigners_header(cosigners)?;
+    if !sign1.protected.header.rest.contains(&commitment) {
+        return Err(Error::BadParam(
+            "the signature does not commit to its co-signers".to_string(),
+        ));
     }
 
-    let mut padding_found = false;
-    let mut last_pad = 0;
-    let mut target_guess = end_size - cur_size - PAD_OFFSET; // start close to desired end_size accounting for label
-    loop {
-        // clone to use
-        sign1_clone = sign1.clone();
-
-        // replace padding with new estimate
-        for header_pair in &mut sign1_clone.unprotected.rest {
-            if header_pair.0 == Label::Text("pad".to_string()) {
-                if let Value::Bytes(b) = &header_pair.1 {
-                    last_pad = b.len();
-                }
-                header_pair.1 = Value::Bytes(vec![0u8; target_guess]);
-                padding_found = true;
-                break;
-            }
-        }
+    // the co-signatures take the place of the padding
+    sign1.unprotected.rest.retain(|(label, _)| {
+        *label != Label::Text(PAD.to_string()) && *label != Label::Text(PAD2.to_string())
+    });
+    sign1.payload = None;
+    add_cosignatures(&mut sign1, cosigners, data)?;
 
-        // if there was no padding add it and call again
-        if !padding_found {
-            sign1_clone.unprotected.rest.push((
-                Label::Text(PAD.to_string()),
-                Value::Bytes(vec![0u8; target_guess]),
-            ));
-            return pad_cose_sig(&mut sign1_clone, end_size);
-        }
+    Ok(pad_sign1(&mut sign1, cose_bytes.len())?)
+}
 
-        // get current cbor vec to size if we reached target size
-        let new_cbor = sign1_clone
-            .to_tagged_vec()
-            .map_err(|_e| Error::CoseSignature)?;
+// returns a COSE_Signature of `cosigner` countersigning "sign1" over "data"
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+fn cose_cosign(sign1: &CoseSign1, cosigner: &dyn Signer, data: &[u8]) -> Result<Value> {
+    let alg = cosigner.alg().ok_or(Error::UnsupportedType)?;
+    let protected = ProtectedHeader {
+        original_data: None,
+        header: alg_header(&alg)?,
+    };
 
-        match new_cbor.len() < end_size {
-            true => target_guess += 1,
-            false if new_cbor.len() == end_size => return Ok(new_cbor),
-            false => break, // we couuld not match end_size in a single pad so break and add a second
-        }
+    let certs = cosigner.cert_chain_policy().apply(cosigner.certs()?);
+    let unprotected = HeaderBuilder::new()
+        .text_value("x5chain".to_string(), x5chain_value(certs))
+        .build();
+
+    let signature = cosigner.sign(&cosignature_tbs(sign1, &protected, data))?;
+
+    CoseSignature {
+        protected,
+        unprotected,
+        signature,
+    }
+    .to_cbor_value()
+    .map_err(|_err| Error::CoseSignature)
+}
+
+/// Returns an unsigned Cose_Sign1 for "data" along with the bytes to sign, for signers
+/// that cannot sign synchronously. Once its signature is set, pass it to [`pad_sign1`].
+///
+/// The certificate chain is placed as `placement` says, and the Cose_Sign1 commits to
+/// `cosigners`, whose co-signatures are added once it is signed.
+#[cfg(feature = "async_signer")]
+pub(crate) fn cose_sign1_to_sign(
+    alg: &str,
+    certs: Vec<Vec<u8>>,
+    placement: X5ChainPlacement,
+    cosigners: &[Box<dyn Signer>],
+    data: &[u8],
+) -> Result<(CoseSign1, Vec<u8>)> {
+    let (mut protected, unprotected) = signing_headers(alg, certs, placement)?;
+    if !cosigners.is_empty() {
+        protected.rest.push(cosigners_header(cosigners)?);
     }
 
-    // if we reach here we need a new second padding object to hit exact size
+    let mut sign1 = CoseSign1Builder::new()
+        .protected(protected)
+        .unprotected(unprotected)
+        .build();
+    let tbs = sig_structure_data(
+        SignatureContext::CoseSign1,
+        sign1.protected.clone(),
+        None,
+        b"", // no additional data required here
+        data,
+    );
+
+    let sign_time = chrono::Utc::now().to_rfc3339(); // todo: remove when switch to cose_timestamp
     sign1.unprotected.rest.push((
-        Label::Text(PAD2.to_string()),
-        Value::Bytes(vec![0u8; last_pad - 10]),
+        Label::Text("temp_signing_time".to_string()),
+        Value::Text(sign_time),
     ));
-    pad_cose_sig(sign1, end_size)
+    Ok((sign1, tbs))
 }
+
+/// Adds a time-stamp to Cose_Sign1 bytes signed over "data" without one.
+/// The time-stamp replaces the padding so the signature keeps its size, and
+/// the claim and its bindings stay valid.
+#[cfg(feature = "file_io")]
+pub(crate) fn cose_add_timestamp(
+    cose_bytes: &[u8],
+    data: &[u8],
+    tss: TimeStampStorage,
+    policy: &TimeStampPolicy,
+) -> Result<Vec<u8>> {
+    let mut sign1 = <CoseSign1 as TaggedCborSerializable>::from_tagged_slice(cose_bytes)
+        .map_err(|_e| Error::CoseSignature)?;
+
+    let is_label = |label: &Label, names: &[&str]| {
+        names
+            .iter()
+            .any(|name| *label == Label::Text((*name).to_string()))
+    };
+    if sign1
+        .unprotected
+        .rest
+        .iter()
+        .any(|(label, _)| is_label(label, &["sigTst", "sigTst2"]))
+    {
+        return Err(Error::CoseTimeStampExists);
+    }
+
+    // 1.x time-stamps only cover the algorithm, 2.x cover the whole protected header
+    let ts_header = match tss {
+        TimeStampStorage::V1SigTst => {
+            v1_countersign_header(&crate::cose_validator::get_validator_str(&sign1)?)
+        }
+        TimeStampStorage::V2SigTst2 => sign1.protected.clone(),
+    };
+    let cts = cose_timestamp_countersign(data, &ts_header, policy)?;
+    let sigtst_vec = serde_cbor::to_vec(&make_cose_timestamp(&cts))?;
+    let sigtst_cbor = serde_cbor::from_slice(&sigtst_vec)?;
+
+    // the time-stamp takes the place of the padding
+    sign1
+        .unprotected
+        .rest
+        .retain(|(label, _)| !is_label(label, &[PAD, PAD2]));
+    sign1
+        .unprotected
+        .rest
+        .push((Label::Text(tss.header_label().to_string()), sigtst_cbor));
+
+    Ok(pad_sign1(&mut sign1, cose_bytes.len())?)
+}
+
+const PAD: &str = "pad";
+const PAD2: &str = "pad2";
@@ -13,45 +13,35 @@
 
 use crate::error::{Error, Result};
 use crate::status_tracker::{log_item, StatusTracker};
-use crate::time_stamp::gt_to_datetime;
-use crate::validation_status;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(feature = "file_io")]
+use crate::time_stamp::TstContainer;
+use crate::time_stamp::{gt_to_datetime, v1_countersign_header, TimeStampStorage};
+use crate::utils::hash_utils::hash_by_alg;
+use crate::validation_status::ValidationStatusCode;
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
 use crate::validator::get_validator;
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
 use crate::validator::CoseValidator;
 use crate::validator::ValidationInfo;
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 use crate::wasm::webcrypto_validator::validate_async;
 
 use crate::asn1::rfc3161::TstInfo;
+use crate::cert_profile;
+use crate::claim_binding::SignatureBinding;
+use crate::status_tracker::Severity;
+#[cfg(feature = "file_io")]
+use crate::TrustConfig;
+use crate::{CertCheckMode, VerifyOptions};
 use ciborium::value::Value;
-use conv::*;
-use coset::{sig_structure_data, Label, TaggedCborSerializable};
+use coset::{sig_structure_data, AsCborValue, Label, ProtectedHeader, TaggedCborSerializable};
 
 use std::str::FromStr;
 
-use x509_parser::der_parser::ber::parse_ber_sequence;
-use x509_parser::der_parser::oid;
-use x509_parser::oid_registry::Oid;
-use x509_parser::prelude::*;
+use tracing::{field, instrument, Span};
 
-const RSA_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .1);
-const EC_PUBLICKEY_OID: Oid<'static> = oid!(1.2.840 .10045 .2 .1);
-const ECDSA_WITH_SHA256_OID: Oid<'static> = oid!(1.2.840 .10045 .4 .3 .2);
-const ECDSA_WITH_SHA384_OID: Oid<'static> = oid!(1.2.840 .10045 .4 .3 .3);
-const ECDSA_WITH_SHA512_OID: Oid<'static> = oid!(1.2.840 .10045 .4 .3 .4);
-const RSASSA_PSS_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .10);
-const SHA256_WITH_RSAENCRYPTION_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .11);
-const SHA384_WITH_RSAENCRYPTION_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .12);
-const SHA512_WITH_RSAENCRYPTION_OID: Oid<'static> = oid!(1.2.840 .113549 .1 .1 .13);
-const ED25519_OID: Oid<'static> = oid!(1.3.101 .112);
-const SHA256_OID: Oid<'static> = oid!(2.16.840 .1 .101 .3 .4 .2 .1);
-const SHA384_OID: Oid<'static> = oid!(2.16.840 .1 .101 .3 .4 .2 .2);
-const SHA512_OID: Oid<'static> = oid!(2.16.840 .1 .101 .3 .4 .2 .3);
-const SECP521R1_OID: Oid<'static> = oid!(1.3.132 .0 .35);
-const SECP384R1_OID: Oid<'static> = oid!(1.3.132 .0 .34);
-const PRIME256V1_OID: Oid<'static> = oid!(1.2.840 .10045 .3 .1 .7);
+use x509_parser::prelude::*;
 
 /********************** Supported Valiators ***************************************
     RS256	RSASSA-PKCS1-v1_5 using SHA-256 - not recommended
@@ -63,9 +53,35 @@ const PRIME256V1_OID: Oid<'static> = oid!(1.2.840 .10045 .3 .1 .7);
     ES256	ECDSA using P-256 and SHA-256
     ES384	ECDSA using P-384 and SHA-384
     ES512	ECDSA using P-521 and SHA-512
+    ES256K	ECDSA using secp256k1 and SHA-256 (with the `es256k` feature)
     ED25519 Edwards Curve 25519
 **********************************************************************************/
 
+/// Unprotected header label of the co-signatures countersigning a claim signature
+pub(crate) const COSIGS: &str = "cosigs";
+
+/// Protected header label of the co-signers a claim signature commits to, the SHA-256
+/// hashes of their signing certificates, so their co-signatures can't be stripped
+pub(crate) const COSIGNERS: &str = "cosigners";
+
+/// How the signing certificate is checked
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct CertCheckOptions {
+    /// Time in seconds the certificate is checked at instead of the time-stamp or now
+    pub validation_time: Option<i64>,
+    /// Whether soft findings reject the certificate
+    pub mode: CertCheckMode,
+}
+
+impl From<&VerifyOptions> for CertCheckOptions {
+    fn from(options: &VerifyOptions) -> Self {
+        CertCheckOptions {
+            validation_time: options.validation_time.map(|t| t.timestamp()),
+            mode: options.cert_check_mode,
+        }
+    }
+}
+
 fn get_cose_sign1(
     cose_bytes: &[u8],
     data: &[u8],
@@ -84,7 +100,7 @@ fn get_cose_sign1(
                 "get_cose_sign1"
             )
             .error(Error::InvalidCoseSignature { coset_error })
-            .validation_status(validation_status::CLAIM_SIGNATURE_MISMATCH);
+            .validation_status(ValidationStatusCode::ClaimSignatureMismatch);
 
             validation_log.log_silent(log_item);
 
@@ -93,10 +109,11 @@ fn get_cose_sign1(
     }
 }
 fn check_cert(
-    _alg: &str,
+    alg: &str,
     ca_der_bytes: &[u8],
     validation_log: &mut impl StatusTracker,
     _tst_info_opt: Option<&TstInfo>,
+    cert_check: CertCheckOptions,
 ) -> Result<()> {
     // get the cert in der format
     let (_rem, signcert) = X509Certificate::from_der(ca_der_bytes).map_err(|_err| {
@@ -106,485 +123,313 @@ fn check_cert(
             "check_cert_alg"
         )
         .error(Error::CoseInvalidCert)
-        .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
+        .validation_status(ValidationStatusCode::SigningCredentialInvalid);
         validation_log.log_silent(log_item);
         Error::CoseInvalidCert
     })?;
 
-    // cert version must be 3
-    if signcert.version() != X509Version::V3 {
-        let log_item = log_item!(
-            "Cose_Sign1",
-            "certificate version incorrect",
-            "check_cert_alg"
-        )
-        .error(Error::CoseInvalidCert)
-        .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-        validation_log.log_silent(log_item);
+    let time = cert_check_time(_tst_info_opt, cert_check)?;
 
-        return Err(Error::CoseInvalidCert);
-    }
+    let results = cert_profile::check_rules(&signcert, time);
 
-    // check for cert expiration
-    if let Some(tst_info) = _tst_info_opt {
-        // was there a time stamp associtation with this signature, is verify against that time
-        let signing_time = gt_to_datetime(tst_info.gen_time.clone());
-        if !signcert
-            .validity()
-            .is_valid_at(x509_parser::time::ASN1Time::from_timestamp(
-                signing_time.timestamp(),
-            ))
-        {
-            let log_item = log_item!("Cose_Sign1", "certificate expired", "check_cert_alg")
-                .error(Error::CoseCertExpiration)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_EXPIRED);
-            validation_log.log_silent(log_item);
+    // the first hard rule broken rejects the certificate
+    if let Some(result) = results.iter().find(|r| !r.passed() && !r.rule.is_soft()) {
+        let err = || result.error().unwrap_or(Error::CoseInvalidCert);
+        let status = result
+            .validation_status()
+            .unwrap_or(ValidationStatusCode::SigningCredentialInvalid);
 
-            return Err(Error::CoseCertExpiration);
-        }
-    } else {
-        // no timestamp so check against current time
-        // use instant to avoid wasm issues
-        let now_f64 = instant::now() / 1000.0;
-        let now: i64 = now_f64
-            .approx_as::<i64>()
-            .map_err(|_e| Error::BadParam("system time invalid".to_string()))?;
-
-        if !signcert
-            .validity()
-            .is_valid_at(x509_parser::time::ASN1Time::from_timestamp(now))
-        {
-            let log_item = log_item!("Cose_Sign1", "certificate expired", "check_cert_alg")
-                .error(Error::CoseCertExpiration)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_EXPIRED);
-            validation_log.log_silent(log_item);
-
-            return Err(Error::CoseCertExpiration);
-        }
-    }
-
-    let cert_alg = signcert.signature_algorithm.algorithm.clone();
-
-    // check algorithm needed from cert
-
-    // cert must be signed with one the following algorithm
-    if !(cert_alg == SHA256_WITH_RSAENCRYPTION_OID
-        || cert_alg == SHA384_WITH_RSAENCRYPTION_OID
-        || cert_alg == SHA512_WITH_RSAENCRYPTION_OID
-        || cert_alg == ECDSA_WITH_SHA256_OID
-        || cert_alg == ECDSA_WITH_SHA384_OID
-        || cert_alg == ECDSA_WITH_SHA512_OID
-        || cert_alg == RSASSA_PSS_OID
-        || cert_alg == ED25519_OID)
-    {
-        let log_item = log_item!(
-            "Cose_Sign1",
-            "certificate algorithm not supported",
-            "check_cert_alg"
-        )
-        .error(Error::CoseInvalidCert)
-        .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
+        let description = result.failure.clone().unwrap_or_default();
+        let log_item = log_item!("Cose_Sign1", description, "check_cert_alg")
+            .error(err())
+            .validation_status(status);
         validation_log.log_silent(log_item);
 
-        return Err(Error::CoseInvalidCert);
+        return Err(err());
     }
 
-    // verify rsassa_pss parameters
-    if cert_alg == RSASSA_PSS_OID {
-        if let Some(parameters) = &signcert.signature_algorithm.parameters {
-            let seq = parameters
-                .as_sequence()
-                .map_err(|_err| Error::CoseInvalidCert)?;
-            if seq.len() < 3 {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate incorrect rsapss algorithm",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-
-            // get hash algorithm
-            let (_b, ha_alg) = AlgorithmIdentifier::from_der(
-                seq[0]
-                    .content
-                    .as_slice()
-                    .map_err(|_err| Error::CoseInvalidCert)?,
-            )
-            .map_err(|_err| Error::CoseInvalidCert)?;
-
-            let (_b, mgf_ai) = AlgorithmIdentifier::from_der(
-                seq[1]
-                    .content
-                    .as_slice()
-                    .map_err(|_err| Error::CoseInvalidCert)?,
-            )
-            .map_err(|_err| Error::CoseInvalidCert)?;
-
-            let mgf_ai_parameters = mgf_ai.parameters.ok_or(Error::CoseInvalidCert)?;
-            let s = mgf_ai_parameters
-                .as_sequence()
-                .map_err(|_err| Error::CoseInvalidCert)?;
-            let t0 = &s[0];
-            //let _t1 = &s[1];
-            let mfg_ai_params_algorithm = t0.as_oid_val().map_err(|_err| Error::CoseInvalidCert)?;
-
-            // must be the same
-            if ha_alg.algorithm != mfg_ai_params_algorithm {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate algorithm error",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-
-            // check for one of the mandatory types
-            if !(ha_alg.algorithm == SHA256_OID
-                || ha_alg.algorithm == SHA384_OID
-                || ha_alg.algorithm == SHA512_OID)
-            {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate hash algorithm not supported",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-        } else {
-            let log_item = log_item!(
-                "Cose_Sign1",
-                "certificate missing algorithm parameters",
-                "check_cert_alg"
-            )
-            .error(Error::CoseInvalidCert)
-            .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-            validation_log.log_silent(log_item);
-
-            return Err(Error::CoseInvalidCert);
-        }
+    for result in results.iter().filter(|r| !r.passed()) {
+        soft_finding(
+            validation_log,
+            cert_check.mode,
+            result.failure.as_deref().unwrap_or_default(),
+            result
+                .validation_status()
+                .unwrap_or(ValidationStatusCode::SigningCredentialInvalid),
+            || result.error().unwrap_or(Error::CoseInvalidCert),
+            ValidationStatusCode::SigningCredentialInvalid,
+        )?;
     }
 
-    // check curves for SPKI EC algorithms
-    let pk = signcert.public_key();
-    let skpi_alg = &pk.algorithm;
-
-    if skpi_alg.algorithm == EC_PUBLICKEY_OID {
-        if let Some(parameters) = &skpi_alg.parameters {
-            let named_curve_oid = parameters
-                .as_oid_val()
-                .map_err(|_err| Error::CoseInvalidCert)?;
-
-            // must be one of these named curves
-            if !(named_curve_oid == PRIME256V1_OID
-                || named_curve_oid == SECP384R1_OID
-                || named_curve_oid == SECP521R1_OID)
-            {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate unsupported EC curve",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-        } else {
-            return Err(Error::CoseInvalidCert);
-        }
+    // RSASSA-PKCS1-v1_5 is not in the specification's list of algorithms
+    if alg.starts_with("rs") {
+        soft_finding(
+            validation_log,
+            cert_check.mode,
+            "signature algorithm is deprecated",
+            ValidationStatusCode::AlgorithmDeprecated,
+            || Error::CoseSignatureAlgorithmNotSupported,
+            ValidationStatusCode::AlgorithmUnsupported,
+        )?;
     }
 
-    // check modulus minumum length (for RSA & PSS algorithms)
-    if skpi_alg.algorithm == RSA_OID || skpi_alg.algorithm == RSASSA_PSS_OID {
-        let (_, skpi_ber) = parse_ber_sequence(pk.subject_public_key.data)
-            .map_err(|_err| Error::CoseInvalidCert)?;
-
-        let seq = skpi_ber
-            .as_sequence()
-            .map_err(|_err| Error::CoseInvalidCert)?;
-        if seq.len() < 2 {
-            return Err(Error::CoseInvalidCert);
-        }
+    Ok(())
+}
 
-        let modulus = seq[0].as_bigint().ok_or(Error::CoseInvalidCert)?;
+// certs are checked at the requested time, otherwise against the time stamp
+// if there is one, otherwise against now
+fn cert_check_time(tst_info_opt: Option<&TstInfo>, cert_check: CertCheckOptions) -> Result<i64> {
+    match (cert_check.validation_time, tst_info_opt) {
+        (Some(time), _) => Ok(time),
+        (None, Some(tst_info)) => Ok(gt_to_datetime(tst_info.gen_time.clone()).timestamp()),
+        (None, None) => cert_profile::now(),
+    }
+}
 
-        if modulus.bits() < 2048 {
-            let log_item = log_item!(
-                "Cose_Sign1",
-                "certificate key length too short",
-                "check_cert_alg"
-            )
-            .error(Error::CoseInvalidCert)
-            .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-            validation_log.log_silent(log_item);
+// validate the certification path of the x5chain, logging every certificate that fails,
+// the first failing certificate decides the error
+fn check_cert_chain(
+    certs: &[Vec<u8>],
+    validation_log: &mut impl StatusTracker,
+    tst_info_opt: Option<&TstInfo>,
+    cert_check: CertCheckOptions,
+) -> Result<()> {
+    let time = cert_check_time(tst_info_opt, cert_check)?;
 
-            return Err(Error::CoseInvalidCert);
+    let chain_err = |expired: bool| {
+        if expired {
+            Error::CoseCertExpiration
+        } else {
+            Error::CoseCertChainInvalid
         }
-    }
-
-    // check cert values
-    let tbscert = &signcert.tbs_certificate;
+    };
 
-    let is_self_signed = tbscert.is_ca() && tbscert.issuer_uid == tbscert.subject_uid;
+    let mut first_failure = None;
+    for result in cert_profile::check_chain(certs, time) {
+        let failure = match &result.failure {
+            Some(failure) => failure,
+            None => continue,
+        };
+        let status = if result.expired {
+            ValidationStatusCode::SigningCredentialExpired
+        } else {
+            ValidationStatusCode::SigningCredentialInvalid
+        };
 
-    // only allowable for self sigbed
-    if !is_self_signed && tbscert.issuer_uid.is_some() || tbscert.subject_uid.is_some() {
-        let log_item = log_item!(
-            "Cose_Sign1",
-            "certificate issuer and subject cannot be the same",
-            "check_cert_alg"
-        )
-        .error(Error::CoseInvalidCert)
-        .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
+        let description = format!("chain certificate {}: {}", result.index, failure);
+        let log_item = log_item!("Cose_Sign1", description, "check_cert_chain")
+            .error(chain_err(result.expired))
+            .validation_status(status);
         validation_log.log_silent(log_item);
 
-        return Err(Error::CoseInvalidCert);
+        first_failure.get_or_insert(result.expired);
     }
 
-    // non self signed CA certs are not allowed, must be an end entity (leaf) cert
-    if tbscert.is_ca() && !is_self_signed {
-        return Err(Error::CoseInvalidCert);
+    match first_failure {
+        Some(expired) => Err(chain_err(expired)),
+        None => Ok(()),
     }
+}
 
-    let mut aki_good = false;
-    let mut ski_good = false;
-    let mut key_usage_good = false;
-    let mut handled_all_critical = true;
-    let extended_key_usage_good = match tbscert.extended_key_usage() {
-        Some((_critical, eku)) => {
-            if eku.any {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate 'any' EKU not allowed",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-
-            if !(eku.email_protection || eku.ocsp_signing || eku.time_stamping) {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate missing required EKU",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-
-            // one or the other || either of these two, and no others field
-            if (eku.ocsp_signing && eku.time_stamping)
-                || ((eku.ocsp_signing ^ eku.time_stamping)
-                    && (eku.client_auth
-                        | eku.code_signing
-                        | eku.email_protection
-                        | eku.server_auth))
-            {
-                let log_item = log_item!(
-                    "Cose_Sign1",
-                    "certificate invalid set of EKUs",
-                    "check_cert_alg"
-                )
-                .error(Error::CoseInvalidCert)
-                .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                validation_log.log_silent(log_item);
-
-                return Err(Error::CoseInvalidCert);
-            }
-
-            true
+// log a finding the specification recommends against, as an error when checking strictly
+// or as a warning with an informational code otherwise
+fn soft_finding(
+    validation_log: &mut impl StatusTracker,
+    mode: CertCheckMode,
+    description: &str,
+    info_status: ValidationStatusCode,
+    err: impl Fn() -> Error,
+    err_status: ValidationStatusCode,
+) -> Result<()> {
+    match mode {
+        CertCheckMode::Strict => {
+            let log_item = log_item!("Cose_Sign1", description, "check_cert_alg")
+                .error(err())
+                .validation_status(err_status);
+            validation_log.log_silent(log_item);
+            Err(err())
         }
-        None => tbscert.is_ca(), // if is not ca it must be present
-    };
-
-    // popluate needed extension info
-    for e in signcert.extensions() {
-        match e.parsed_extension() {
-            ParsedExtension::AuthorityKeyIdentifier(_aki) => {
-                aki_good = true;
-            }
-            ParsedExtension::SubjectKeyIdentifier(_spki) => {
-                ski_good = true;
-            }
-            ParsedExtension::KeyUsage(ku) => {
-                if ku.digital_signature() {
-                    if ku.key_cert_sign() && !tbscert.is_ca() {
-                        let log_item = log_item!(
-                            "Cose_Sign1",
-                            "certificate missing digitalSignature EKU",
-                            "check_cert_alg"
-                        )
-                        .error(Error::CoseInvalidCert)
-                        .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-                        validation_log.log_silent(log_item);
-
-                        return Err(Error::CoseInvalidCert);
-                    }
-                    key_usage_good = true;
-                }
-                if ku.key_cert_sign() {
-                    key_usage_good = true;
-                }
-                // todo: warn if not marked critical
-                // if !e.critical { // warn here somehow}
-            }
-            ParsedExtension::CertificatePolicies(_) => (),
-            ParsedExtension::PolicyMappings(_) => (),
-            ParsedExtension::SubjectAlternativeName(_) => (),
-            ParsedExtension::BasicConstraints(_) => (),
-            ParsedExtension::NameConstraints(_) => (),
-            ParsedExtension::PolicyConstraints(_) => (),
-            ParsedExtension::ExtendedKeyUsage(_) => (),
-            ParsedExtension::CRLDistributionPoints(_) => (),
-            ParsedExtension::InhibitAnyPolicy(_) => (),
-            ParsedExtension::AuthorityInfoAccess(_) => (),
-            ParsedExtension::NSCertType(_) => (),
-            ParsedExtension::CRLNumber(_) => (),
-            ParsedExtension::ReasonCode(_) => (),
-            ParsedExtension::InvalidityDate(_) => (),
-            ParsedExtension::Unparsed => {
-                if e.critical {
-                    // unhandled critical extension
-                    handled_all_critical = false;
-                }
-            }
-            _ => {
-                if e.critical {
-                    // unhandled critical extension
-                    handled_all_critical = false;
-                }
-            }
+        CertCheckMode::Lenient => {
+            let log_item = log_item!("Cose_Sign1", description, "check_cert_alg")
+                .validation_status(info_status)
+                .severity(Severity::Warning);
+            validation_log.log_silent(log_item);
+            Ok(())
         }
     }
-
-    // if cert is a CA must have valid SubjectKeyIdentifier
-    ski_good = if tbscert.is_ca() { ski_good } else { true };
-
-    // check all flags
-    if aki_good && ski_good && key_usage_good && extended_key_usage_good && handled_all_critical {
-        Ok(())
-    } else {
-        let log_item = log_item!(
-            "Cose_Sign1",
-            "certificate params incorrect",
-            "check_cert_alg"
-        )
-        .error(Error::CoseInvalidCert)
-        .validation_status(validation_status::SIGNING_CREDENTIAL_INVALID);
-        validation_log.log_silent(log_item);
-
-        Err(Error::CoseInvalidCert)
-    }
 }
 
 pub(crate) fn get_validator_str(cs1: &coset::CoseSign1) -> Result<String> {
     // find the supported handler for the algorithm
-    let validator_str = match cs1.protected.header.alg {
-        Some(ref alg) => {
-            let alg_str = match alg {
-                coset::RegisteredLabelWithPrivate::PrivateUse(a) => match a {
-                    -39 => "ps512",
-                    -38 => "ps384",
-                    -37 => "ps256",
-                    -36 => "es512",
-                    -35 => "es384",
-                    -7 => "es256",
-                    // todo: deprecated  figure out lecacy support for RS signatures
-                    -259 => "rs512",
-                    -258 => "rs384",
-                    -257 => "rs256",
-
-                    -8 => "ed25519",
-                    _ => "unknown",
-                },
-                coset::RegisteredLabelWithPrivate::Assigned(a) => match a {
-                    coset::iana::Algorithm::PS512 => "ps512",
-                    coset::iana::Algorithm::PS384 => "ps384",
-                    coset::iana::Algorithm::PS256 => "ps256",
-                    coset::iana::Algorithm::ES512 => "es512",
-                    coset::iana::Algorithm::ES384 => "es384",
-                    coset::iana::Algorithm::ES256 => "es256",
-                    // todo: deprecated  figure out lecacy support for RS signatures
-                    coset::iana::Algorithm::RS512 => "rs512",
-                    coset::iana::Algorithm::RS384 => "rs384",
-                    coset::iana::Algorithm::RS256 => "rs256",
-                    coset::iana::Algorithm::EdDSA => "ed25519",
-                    _ => "unknown",
-                },
-                coset::RegisteredLabelWithPrivate::Text(a) => a,
-            };
+    Ok(c2pa_core::cose::signing_alg(cs1)?)
+}
 
-            Some(alg_str.to_owned())
-        }
-        None => None,
-    }
-    .ok_or(Error::CoseSignatureAlgorithmNotSupported)?;
+/// Return the "to be signed" bytes of a COSE_SIGN1 over `data` along with the signature parts
+/// cose_bytes - byte array containing the raw COSE_SIGN1 data
+/// data:  data that was used to create the cose_bytes
+pub(crate) fn get_signature_binding(cose_bytes: &[u8], data: &[u8]) -> Result<SignatureBinding> {
+    let sign1 = <coset::CoseSign1 as TaggedCborSerializable>::from_tagged_slice(cose_bytes)
+        .map_err(|coset_error| Error::InvalidCoseSignature { coset_error })?;
 
-    Ok(validator_str)
+    let to_be_signed = sig_structure_data(
+        coset::SignatureContext::CoseSign1,
+        sign1.protected.clone(),
+        None,
+        &[],
+        data,
+    );
+
+    Ok(SignatureBinding {
+        alg: get_validator_str(&sign1).ok(),
+        to_be_signed,
+        signature: sign1.signature.clone(),
+        certs: get_sign_certs(&sign1).unwrap_or_default(),
+    })
 }
 
 fn get_sign_cert(sign1: &coset::CoseSign1) -> Result<Vec<u8>> {
     // element 0 is the signing cert
-    let certs = get_sign_certs(sign1)?;
-    Ok(certs[0].clone())
+    get_sign_certs(sign1)?
+        .into_iter()
+        .next()
+        .ok_or(Error::CoseX5ChainMissing)
+}
+/// Returns the bytes a co-signer signs for a Cose_Sign1 over `data`.
+///
+/// This is a countersignature structure over the claim, with the signature being
+/// countersigned as external data so the co-signature covers it too.
+pub(crate) fn cosignature_tbs(
+    sign1: &coset::CoseSign1,
+    cosig_protected: &ProtectedHeader,
+    data: &[u8],
+) -> Vec<u8> {
+    sig_structure_data(
+        coset::SignatureContext::CounterSignature,
+        sign1.protected.clone(),
+        Some(cosig_protected.clone()),
+        &sign1.signature,
+        data,
+    )
 }
-// get the public key der
-fn get_sign_certs(sign1: &coset::CoseSign1) -> Result<Vec<Vec<u8>>> {
-    let mut certs: Vec<Vec<u8>> = Vec::new();
 
-    // get the public key der
-    if let Some(der) = sign1
+/// Returns the value of the [`COSIGNERS`] header committing to co-signers with the
+/// signing certificates `certs`.
+pub(crate) fn cosigners_value<'a>(certs: impl Iterator<Item = &'a [u8]>) -> Value {
+    Value::Array(certs.map(|cert| Value::Bytes(cosigner_id(cert))).collect())
+}
+
+// identifies a co-signer by the hash of its signing certificate
+fn cosigner_id(cert: &[u8]) -> Vec<u8> {
+    hash_by_alg("sha256", cert, None)
+}
+
+// a co-signature with the bytes it signs
+struct Cosignature {
+    alg: String,
+    cert: Vec<u8>,
+    tbs: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl Cosignature {
+    fn info(&self, validated: bool) -> ValidationInfo {
+        ValidationInfo {
+            alg: self.alg.clone(),
+            issuer_org: X509Certificate::from_der(&self.cert)
+                .ok()
+                .and_then(|(_rem, cert)| extract_subject_from_cert(&cert).ok()),
+            validated,
+            ..Default::default()
+        }
+    }
+}
+
+// get the co-signatures of a Cose_Sign1 over `data`, an entry that can't be read is an error
+fn get_cosignatures(sign1: &coset::CoseSign1, data: &[u8]) -> Vec<Result<Cosignature>> {
+    let cosigs = match sign1
         .unprotected
         .rest
         .iter()
-        .find_map(|x: &(Label, Value)| {
-            if x.0 == Label::Text("x5chain".to_string()) {
-                Some(x.1.clone())
-            } else {
-                None
-            }
+        .find(|(label, _)| *label == Label::Text(COSIGS.to_string()))
+    {
+        Some((_, Value::Array(cosigs))) => cosigs,
+        Some(_) => return vec![Err(Error::CoseSignature)],
+        None => return Vec::new(),
+    };
+
+    cosigs
+        .iter()
+        .map(|value| {
+            let cosig = coset::CoseSignature::from_cbor_value(value.clone())
+                .map_err(|_err| Error::CoseSignature)?;
+            // view the co-signature as a Cose_Sign1 so the header helpers apply
+            let cosig_sign1 = coset::CoseSign1 {
+                protected: cosig.protected,
+                unprotected: cosig.unprotected,
+                ..Default::default()
+            };
+            Ok(Cosignature {
+                alg: get_validator_str(&cosig_sign1).unwrap_or_default(),
+                cert: get_sign_cert(&cosig_sign1)?,
+                tbs: cosignature_tbs(sign1, &cosig_sign1.protected, data),
+                signature: cosig.signature,
+            })
         })
+        .collect()
+}
+
+// get the ids of the co-signers the protected header of a Cose_Sign1 commits to
+fn get_committed_cosigners(sign1: &coset::CoseSign1) -> Vec<Vec<u8>> {
+    match sign1
+        .protected
+        .header
+        .rest
+        .iter()
+        .find(|(label, _)| *label == Label::Text(COSIGNERS.to_string()))
     {
-        match der {
-            Value::Array(cert_chain) => {
-                // handle array of certs
-                for c in cert_chain {
-                    if let Value::Bytes(der_bytes) = c {
-                        certs.push(der_bytes.clone());
+        Some((_, Value::Array(ids))) => ids
+            .iter()
+            .filter_map(|id| match id {
+                Value::Bytes(id) => Some(id.clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Returns the info of each co-signer of a Cose_Sign1 from its co-signatures with whether
+// their checks passed. A co-signature the signature does not commit to is not valid, and
+// each committed co-signer without a co-signature is reported as a co-signer that is not valid.
+fn cosigner_infos(
+    sign1: &coset::CoseSign1,
+    checked: Vec<(Result<Cosignature>, bool)>,
+) -> Vec<ValidationInfo> {
+    let mut missing = get_committed_cosigners(sign1);
+    let mut infos: Vec<ValidationInfo> = checked
+        .into_iter()
+        .map(|(cosig, passed)| match cosig {
+            Ok(cosig) => {
+                let id = cosigner_id(&cosig.cert);
+                let committed = match missing.iter().position(|m| *m == id) {
+                    Some(index) => {
+                        missing.remove(index);
+                        true
                     }
-                }
-                Ok(certs)
-            }
-            Value::Bytes(ref der_bytes) => {
-                // handle single cert case
-                certs.push(der_bytes.clone());
-                Ok(certs)
+                    None => false,
+                };
+                cosig.info(passed && committed)
             }
-            _ => Err(Error::CoseX5ChainMissing),
-        }
-    } else {
-        Err(Error::CoseX5ChainMissing)
-    }
+            Err(_) => ValidationInfo::default(),
+        })
+        .collect();
+    infos.extend(missing.iter().map(|_| ValidationInfo::default()));
+    infos
+}
+
+// get the certificate chain, starting with the signing cert
+fn get_sign_certs(sign1: &coset::CoseSign1) -> Result<Vec<Vec<u8>>> {
+    Ok(c2pa_core::cose::x5chain(sign1)?)
 }
 
 // Note: this function is only used to get the display string and not for cert validation.
@@ -597,21 +442,8 @@ fn get_signing_time(
 
     if let Ok(tst_info) = get_timestamp_info(sign1, data) {
         Some(gt_to_datetime(tst_info.gen_time))
-    } else if let Some(t) = &sign1
-        .unprotected
-        .rest
-        .iter()
-        .find_map(|x: &(Label, Value)| {
-            if x.0 == Label::Text("temp_signing_time".to_string()) {
-                Some(x.1.clone())
-            } else {
-                None
-            }
-        })
-    {
-        let time_cbor = serde_cbor::to_vec(t).ok()?;
-        let dt_string: String = serde_cbor::from_slice(&time_cbor).ok()?;
-        chrono::DateTime::<chrono::Utc>::from_str(&dt_string).ok()
+    } else if let Some(t) = &get_temp_signing_time(sign1) {
+        parse_temp_signing_time(t)
     } else {
         let log_item = log_item!(
             "Cose_Sign1",
@@ -619,7 +451,7 @@ fn get_signing_time(
             "get_signing_time"
         )
         .error(Error::CoseTimeStampMismatch)
-        .validation_status(validation_status::TIMESTAMP_MISMATCH);
+        .validation_status(ValidationStatusCode::TimeStampMismatch);
         validation_log
             .log(log_item, Some(Error::CoseTimeStampMismatch))
             .ok()?;
@@ -628,33 +460,209 @@ fn get_signing_time(
     }
 }
 
-// return appropriate TstInfo if available
-fn get_timestamp_info(sign1: &coset::CoseSign1, data: &[u8]) -> Result<TstInfo> {
-    // parse the temp timestamp
-    if let Some(t) = &sign1
+// return the time of a signature and whether it was read from a time-stamp, without
+// validating the signature or checking the time-stamp authority
+fn get_signing_time_unverified(
+    sign1: &coset::CoseSign1,
+    data: &[u8],
+) -> Option<(chrono::DateTime<chrono::Utc>, bool)> {
+    match get_timestamp_info(sign1, data) {
+        Ok(tst_info) => Some((gt_to_datetime(tst_info.gen_time), true)),
+        Err(_) => get_temp_signing_time(sign1)
+            .as_ref()
+            .and_then(parse_temp_signing_time)
+            .map(|time| (time, false)),
+    }
+}
+
+// return the temp_signing_time header value, the time recorded by signers without a time-stamp
+fn get_temp_signing_time(sign1: &coset::CoseSign1) -> Option<Value> {
+    sign1
         .unprotected
         .rest
         .iter()
         .find_map(|x: &(Label, Value)| {
-            if x.0 == Label::Text("sigTst".to_string()) {
+            if x.0 == Label::Text("temp_signing_time".to_string()) {
                 Some(x.1.clone())
             } else {
                 None
             }
         })
-    {
-        let alg = get_validator_str(sign1)?;
+}
+
+fn parse_temp_signing_time(t: &Value) -> Option<chrono::DateTime<chrono::Utc>> {
+    let time_cbor = serde_cbor::to_vec(t).ok()?;
+    let dt_string: String = serde_cbor::from_slice(&time_cbor).ok()?;
+    chrono::DateTime::<chrono::Utc>::from_str(&dt_string).ok()
+}
+
+// return the time-stamp header value and its storage if present,
+// preferring the 2.x sigTst2 header over the 1.x sigTst header
+pub(crate) fn get_sigtst(sign1: &coset::CoseSign1) -> Option<(Value, TimeStampStorage)> {
+    [TimeStampStorage::V2SigTst2, TimeStampStorage::V1SigTst]
+        .iter()
+        .find_map(|tss| {
+            sign1
+                .unprotected
+                .rest
+                .iter()
+                .find_map(|x: &(Label, Value)| {
+                    if x.0 == Label::Text(tss.header_label().to_string()) {
+                        Some((x.1.clone(), *tss))
+                    } else {
+                        None
+                    }
+                })
+        })
+}
+
+// return appropriate TstInfo if available
+fn get_timestamp_info(sign1: &coset::CoseSign1, data: &[u8]) -> Result<TstInfo> {
+    // parse the temp timestamp
+    if let Some((t, tss)) = &get_sigtst(sign1) {
+        // 1.x time-stamps only cover the algorithm, 2.x cover the whole protected header
+        let p_header = match tss {
+            TimeStampStorage::V1SigTst => v1_countersign_header(&get_validator_str(sign1)?),
+            TimeStampStorage::V2SigTst2 => sign1.protected.clone(),
+        };
         let time_cbor = serde_cbor::to_vec(t)?;
-        let tst_infos = crate::time_stamp::cose_sigtst_to_tstinfos(&time_cbor, data, &alg)?;
+        let tst_infos = crate::time_stamp::cose_sigtst_to_tstinfos(&time_cbor, data, &p_header)?;
 
         // there should only be one but consider handling more in the future since it is technically ok
-        if !tst_infos.is_empty() {
-            return Ok(tst_infos[0].clone());
+        if let Some(tst_info) = tst_infos.into_iter().next() {
+            return Ok(tst_info);
         }
     }
     Err(Error::NotFound)
 }
 
+// record which header a validated time-stamp was read from
+fn log_timestamp_storage(sign1: &coset::CoseSign1, validation_log: &mut impl StatusTracker) {
+    if let Some((_, tss)) = get_sigtst(sign1) {
+        let log_item = log_item!(
+            "Cose_Sign1",
+            format!("timestamp validated from {}", tss.header_label()),
+            "verify_cose"
+        );
+        validation_log.log_silent(log_item);
+    }
+}
+
+/// Check that the time-stamps of a COSE_SIGN1 were signed by a TSA whose certificate
+/// chains to one of `trust_anchors`, DER encoded certificates.
+/// Time-stamps that do not are logged as `timeStamp.untrusted`.
+#[cfg(feature = "file_io")]
+pub(crate) fn check_timestamp_trust(
+    cose_bytes: &[u8],
+    data: &[u8],
+    trust_anchors: &[Vec<u8>],
+    validation_log: &mut impl StatusTracker,
+) -> Result<()> {
+    let sign1 = get_cose_sign1(cose_bytes, data, validation_log)?;
+
+    let sigtst = match get_sigtst(&sign1) {
+        Some((t, _)) => t,
+        None => return Ok(()),
+    };
+    let tst_container: TstContainer = serde_cbor::to_vec(&sigtst)
+        .and_then(|cbor| serde_cbor::from_slice(&cbor))
+        .map_err(|_err| Error::CoseInvalidTimeStamp)?;
+
+    for token in &tst_container.tst_tokens {
+        match crate::time_stamp::verify_timestamp_trust(&token.val, trust_anchors) {
+            Ok(()) => {
+                let log_item =
+                    log_item!("Cose_Sign1", "timestamp trusted", "check_timestamp_trust")
+                        .validation_status(ValidationStatusCode::TimeStampTrusted);
+                validation_log.log_silent(log_item);
+            }
+            Err(e) => {
+                let log_item = log_item!(
+                    "Cose_Sign1",
+                    format!("timestamp not trusted: {}", e),
+                    "check_timestamp_trust"
+                )
+                .error(Error::CoseTimeStampUntrusted)
+                .validation_status(ValidationStatusCode::TimeStampUntrusted);
+                validation_log.log(log_item, Some(Error::CoseTimeStampUntrusted))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the signing certificate of a COSE_SIGN1 is trusted by `trust_config`, at
+/// the same time the certificate itself is checked at.
+/// Certificates that are not trusted are logged as `signingCredential.untrusted`.
+#[cfg(feature = "file_io")]
+pub(crate) fn check_signing_trust(
+    cose_bytes: &[u8],
+    data: &[u8],
+    trust_config: &TrustConfig,
+    cert_check: CertCheckOptions,
+    validation_log: &mut impl StatusTracker,
+) -> Result<()> {
+    let sign1 = get_cose_sign1(cose_bytes, data, validation_log)?;
+    let certs = get_sign_certs(&sign1)?;
+    let time = cert_check_time(get_timestamp_info(&sign1, data).ok().as_ref(), cert_check)?;
+
+    match trust_config.check_certs(&certs, time) {
+        Ok(()) => {
+            let log_item = log_item!(
+                "Cose_Sign1",
+                "signing certificate trusted",
+                "check_signing_trust"
+            )
+            .validation_status(ValidationStatusCode::SigningCredentialTrusted);
+            validation_log.log_silent(log_item);
+        }
+        Err(e) => {
+            let log_item = log_item!(
+                "Cose_Sign1",
+                format!("signing certificate not trusted: {}", e),
+                "check_signing_trust"
+            )
+            .error(Error::CoseCertUntrusted)
+            .validation_status(ValidationStatusCode::SigningCredentialUntrusted);
+            validation_log.log(log_item, Some(Error::CoseCertUntrusted))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fetch the OCSP status of the signing certificate of a COSE_SIGN1 from the responder
+/// named in the certificate and check it at the time the certificate itself is checked at.
+/// Revoked certificates are logged as `signingCredential.revoked`, a response that cannot
+/// be fetched or interpreted is ignored.
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub(crate) fn check_signing_revocation(
+    cose_bytes: &[u8],
+    data: &[u8],
+    fetcher: &dyn crate::OcspFetcher,
+    cert_check: CertCheckOptions,
+    validation_log: &mut impl StatusTracker,
+) -> Result<()> {
+    let sign1 = get_cose_sign1(cose_bytes, data, validation_log)?;
+    let certs = get_sign_certs(&sign1)?;
+    let time = cert_check_time(get_timestamp_info(&sign1, data).ok().as_ref(), cert_check)?;
+
+    let ocsp_data = match crate::ocsp_utils::fetch_ocsp_response(&certs, fetcher) {
+        Some(ocsp_data) => ocsp_data,
+        None => return Ok(()),
+    };
+    let signing_time = chrono::NaiveDateTime::from_timestamp_opt(time, 0)
+        .map(|t| chrono::DateTime::<chrono::Utc>::from_utc(t, chrono::Utc));
+
+    crate::ocsp_utils::check_ocsp_response(
+        &ocsp_data.ocsp_der,
+        &certs,
+        signing_time,
+        validation_log,
+    )
+}
+
 fn extract_subject_from_cert(cert: &X509Certificate) -> Result<String> {
     cert.subject()
         .iter_organization()
@@ -669,12 +677,15 @@ fn extract_subject_from_cert(cert: &X509Certificate) -> Result<String> {
 /// cose_bytes - byte array containing the raw COSE_SIGN1 data
 /// data:  data that was used to create the cose_bytes, these must match
 /// addition_data: additional optional data that may have been used during signing
+/// cert_check: how the signing certificate is checked
 /// returns - Ok on success
+#[instrument(skip_all, fields(alg = field::Empty))]
 pub async fn verify_cose_async(
     cose_bytes: Vec<u8>,
     data: Vec<u8>,
     additional_data: Vec<u8>,
     signature_only: bool,
+    cert_check: CertCheckOptions,
     validation_log: &mut impl StatusTracker,
 ) -> Result<ValidationInfo> {
     let mut sign1 = get_cose_sign1(&cose_bytes, &data, validation_log)?;
@@ -688,32 +699,43 @@ pub async fn verify_cose_async(
                 "verify_cose_async"
             )
             .error(Error::CoseSignatureAlgorithmNotSupported)
-            .validation_status(validation_status::ALGORITHM_UNSUPPORTED);
+            .validation_status(ValidationStatusCode::AlgorithmUnsupported);
             validation_log.log(log_item, Some(Error::CoseSignatureAlgorithmNotSupported))?;
 
             // one of these must exist
             return Err(Error::CoseSignatureAlgorithmNotSupported);
         }
     };
+    Span::current().record("alg", &validator_str.as_str());
 
     // build result structure
     let mut result = ValidationInfo::default();
 
-    // get the public key der
-    let der_bytes = get_sign_cert(&sign1)?;
+    // get the cert chain and the public key der
+    let certs = get_sign_certs(&sign1)?;
+    let der_bytes = certs.first().cloned().ok_or(Error::CoseX5ChainMissing)?;
 
     // verify cert matches requested algorithm
     if !signature_only {
         // verify certs
         match get_timestamp_info(&sign1, &data) {
             Ok(tst_info) => {
-                check_cert(&validator_str, &der_bytes, validation_log, Some(&tst_info))?
+                log_timestamp_storage(&sign1, validation_log);
+                check_cert(
+                    &validator_str,
+                    &der_bytes,
+                    validation_log,
+                    Some(&tst_info),
+                    cert_check,
+                )?;
+                check_cert_chain(&certs, validation_log, Some(&tst_info), cert_check)?
             }
             Err(e) => {
                 // log timestamp errors
                 match e {
                     Error::NotFound => {
-                        check_cert(&validator_str, &der_bytes, validation_log, None)?
+                        check_cert(&validator_str, &der_bytes, validation_log, None, cert_check)?;
+                        check_cert_chain(&certs, validation_log, None, cert_check)?
                     }
                     Error::CoseTimeStampMismatch => {
                         let log_item = log_item!(
@@ -722,14 +744,14 @@ pub async fn verify_cose_async(
                             "verify_cose"
                         )
                         .error(Error::CoseTimeStampMismatch)
-                        .validation_status(validation_status::TIMESTAMP_MISMATCH);
+                        .validation_status(ValidationStatusCode::TimeStampMismatch);
                         validation_log.log(log_item, Some(Error::CoseTimeStampMismatch))?;
                     }
                     Error::CoseTimeStampValidity => {
                         let log_item =
                             log_item!("Cose_Sign1", "timestamp outside of validity", "verify_cose")
                                 .error(Error::CoseTimeStampValidity)
-                                .validation_status(validation_status::TIMESTAMP_OUTSIDE_VALIDITY);
+                                .validation_status(ValidationStatusCode::TimeStampOutsideValidity);
                         validation_log.log(log_item, Some(Error::CoseTimeStampValidity))?;
                     }
                     _ => {
@@ -770,40 +792,87 @@ pub async fn verify_cose_async(
         result.date = get_signing_time(&sign1, &data, validation_log);
     }
 
+    // a co-signer that fails its checks does not fail the signature, it is reported on its own
+    let mut checked = Vec::new();
+    for cosig in get_cosignatures(&sign1, &data) {
+        let passed = match &cosig {
+            Ok(cosig) => {
+                (signature_only
+                    || check_cert(&cosig.alg, &cosig.cert, validation_log, None, cert_check)
+                        .is_ok())
+                    && validate_with_cert_async(
+                        &cosig.alg,
+                        &cosig.signature,
+                        &cosig.tbs,
+                        &cosig.cert,
+                    )
+                    .await
+                    .is_ok()
+            }
+            Err(_) => false,
+        };
+        checked.push((cosig, passed));
+    }
+    result.cosigners = cosigner_infos(&sign1, checked);
+
     Ok(result)
 }
 
+/// The signer of a claim signature, read without validating the signature or its certificates
+pub(crate) struct SignatureDetails {
+    pub alg: Option<String>,
+    pub issuer_org: Option<String>,
+    // the certificate chain, starting with the signing certificate
+    pub certs: Vec<Vec<u8>>,
+    // the time of the signature and whether it was read from a time-stamp
+    pub time: Option<(chrono::DateTime<chrono::Utc>, bool)>,
+    pub cosigners: Vec<ValidationInfo>,
+}
+
+/// Reads the signer of a COSE_Sign1 over `data` without validating it.
+///
+/// This is the fast path for showing who signed a claim: only the COSE_Sign1 and its
+/// certificates are parsed, nothing is verified.
+pub(crate) fn get_signature_details(
+    cose_bytes: &[u8],
+    data: &[u8],
+    validation_log: &mut impl StatusTracker,
+) -> Result<SignatureDetails> {
+    let sign1 = get_cose_sign1(cose_bytes, data, validation_log)?;
+    let certs = get_sign_certs(&sign1).unwrap_or_default();
+    let issuer_org = certs
+        .first()
+        .and_then(|der| X509Certificate::from_der(der).ok())
+        .and_then(|(_rem, signcert)| extract_subject_from_cert(&signcert).ok());
+
+    let unchecked = get_cosignatures(&sign1, data)
+        .into_iter()
+        .map(|cosig| (cosig, false))
+        .collect();
+
+    Ok(SignatureDetails {
+        alg: get_validator_str(&sign1).ok(),
+        issuer_org,
+        certs,
+        time: get_signing_time_unverified(&sign1, data),
+        cosigners: cosigner_infos(&sign1, unchecked),
+    })
+}
+
 pub fn get_signing_info(
     cose_bytes: &[u8],
     data: &[u8],
     validation_log: &mut impl StatusTracker,
 ) -> ValidationInfo {
-    let mut date = None;
-    let mut issuer_org = None;
-    let mut alg = "".to_string();
-
-    let _ = get_cose_sign1(cose_bytes, data, validation_log).and_then(|sign1| {
-        // get the public key der
-        let der_bytes = get_sign_cert(&sign1)?;
-
-        let _ = X509Certificate::from_der(&der_bytes).map(|(_rem, signcert)| {
-            date = get_signing_time(&sign1, data, validation_log);
-            issuer_org = extract_subject_from_cert(&signcert).ok();
-            if let Ok(a) = get_validator_str(&sign1) {
-                alg = a;
-            }
-
-            (_rem, signcert)
-        });
-
-        Ok(sign1)
-    });
-
-    ValidationInfo {
-        issuer_org,
-        date,
-        alg,
-        validated: false,
+    match get_signature_details(cose_bytes, data, validation_log) {
+        Ok(details) => ValidationInfo {
+            issuer_org: details.issuer_org,
+            date: details.time.map(|(time, _time_stamped)| time),
+            alg: details.alg.unwrap_or_default(),
+            validated: false,
+            cosigners: details.cosigners,
+        },
+        Err(_) => ValidationInfo::default(),
     }
 }
 
@@ -811,13 +880,16 @@ pub fn get_signing_info(
 /// cose_bytes - byte array containing the raw COSE_SIGN1 data
 /// data:  data that was used to create the cose_bytes, these must match
 /// addition_data: additional optional data that may have been used during signing
+/// cert_check: how the signing certificate is checked
 /// returns - Ok on success
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
+#[instrument(skip_all, fields(alg = field::Empty))]
 pub fn verify_cose(
     cose_bytes: &[u8],
     data: &[u8],
     additional_data: &[u8],
     signature_only: bool,
+    cert_check: CertCheckOptions,
     validation_log: &mut impl StatusTracker,
 ) -> Result<ValidationInfo> {
     let sign1 = get_cose_sign1(cose_bytes, data, validation_log)?;
@@ -831,13 +903,14 @@ pub fn verify_cose(
                 "verify_cose"
             )
             .error(Error::CoseSignatureAlgorithmNotSupported)
-            .validation_status(validation_status::ALGORITHM_UNSUPPORTED);
+            .validation_status(ValidationStatusCode::AlgorithmUnsupported);
 
             validation_log.log(log_item, Some(Error::CoseSignatureAlgorithmNotSupported))?;
 
             return Err(Error::CoseSignatureAlgorithmNotSupported);
         }
     };
+    Span::current().record("alg", &validator_str.as_str());
 
     let validator =
         get_validator(&validator_str).ok_or(Error::CoseSignatureAlgorithmNotSupported)?;
@@ -849,16 +922,29 @@ pub fn verify_cose(
     let certs = get_sign_certs(&sign1)?;
 
     // get the public key der
-    let der_bytes = &certs[0];
+    let der_bytes = certs.first().ok_or(Error::CoseX5ChainMissing)?;
 
     if !signature_only {
         // verify certs
         match get_timestamp_info(&sign1, data) {
-            Ok(tst_info) => check_cert(&validator_str, der_bytes, validation_log, Some(&tst_info))?,
+            Ok(tst_info) => {
+                log_timestamp_storage(&sign1, validation_log);
+                check_cert(
+                    &validator_str,
+                    der_bytes,
+                    validation_log,
+                    Some(&tst_info),
+                    cert_check,
+                )?;
+                check_cert_chain(&certs, validation_log, Some(&tst_info), cert_check)?
+            }
             Err(e) => {
                 // log timestamp errors
                 match e {
-                    Error::NotFound => check_cert(&validator_str, der_bytes, validation_log, None)?,
+                    Error::NotFound => {
+                        check_cert(&validator_str, der_bytes, validation_log, None, cert_check)?;
+                        check_cert_chain(&certs, validation_log, None, cert_check)?
+                    }
                     Error::CoseTimeStampMismatch => {
                         let log_item = log_item!(
                             "Cose_Sign1",
@@ -866,14 +952,14 @@ pub fn verify_cose(
                             "verify_cose"
                         )
                         .error(Error::CoseTimeStampMismatch)
-                        .validation_status(validation_status::TIMESTAMP_MISMATCH);
+                        .validation_status(ValidationStatusCode::TimeStampMismatch);
                         validation_log.log(log_item, Some(Error::CoseTimeStampMismatch))?;
                     }
                     Error::CoseTimeStampValidity => {
                         let log_item =
                             log_item!("Cose_Sign1", "timestamp outside of validity", "verify_cose")
                                 .error(Error::CoseTimeStampValidity)
-                                .validation_status(validation_status::TIMESTAMP_OUTSIDE_VALIDITY);
+                                .validation_status(ValidationStatusCode::TimeStampOutsideValidity);
                         validation_log.log(log_item, Some(Error::CoseTimeStampValidity))?;
                     }
                     _ => {
@@ -904,21 +990,43 @@ pub fn verify_cose(
         Ok(())
     })?;
 
+    // co-signers are checked at the current or requested time, their signatures are not time-stamped.
+    // A co-signer that fails its checks does not fail the signature, it is reported on its own.
+    let checked = get_cosignatures(&sign1, data)
+        .into_iter()
+        .map(|cosig| {
+            let passed = cosig.as_ref().map_or(false, |cosig| {
+                (signature_only
+                    || check_cert(&cosig.alg, &cosig.cert, validation_log, None, cert_check)
+                        .is_ok())
+                    && get_validator(&cosig.alg)
+                        .ok_or(Error::CoseSignatureAlgorithmNotSupported)
+                        .and_then(|validator| {
+                            validate_with_cert(validator, &cosig.signature, &cosig.tbs, &cosig.cert)
+                        })
+                        .is_ok()
+            });
+            (cosig, passed)
+        })
+        .collect();
+    result.cosigners = cosigner_infos(&sign1, checked);
+
     Ok(result)
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 pub fn verify_cose(
     _cose_bytes: &[u8],
     _data: &[u8],
     _additional_data: &[u8],
     _signature_only: bool,
+    _cert_check: CertCheckOptions,
     _validation_log: &mut impl StatusTracker,
 ) -> Result<ValidationInfo> {
     Err(Error::CoseVerifier)
 }
 
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
 fn validate_with_cert(
     validator: Box<dyn CoseValidator>,
     sig: &[u8],
@@ -938,7 +1046,7 @@ fn validate_with_cert(
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 async fn validate_with_cert_async(
     validator_str: &str,
     sig: &[u8],
@@ -957,6 +1065,19 @@ async fn validate_with_cert_async(
     }
 }
 
+// WASI has no async crypto API, so validate with the Rust validators
+#[cfg(target_os = "wasi")]
+async fn validate_with_cert_async(
+    validator_str: &str,
+    sig: &[u8],
+    data: &[u8],
+    der_bytes: &[u8],
+) -> Result<String> {
+    let validator =
+        get_validator(validator_str).ok_or(Error::CoseSignatureAlgorithmNotSupported)?;
+    validate_with_cert(validator, sig, data, der_bytes)
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 async fn validate_with_cert_async(
     _validator_str: &str,
@@ -967,7 +1088,7 @@ async fn validate_with_cert_async(
     Err(Error::CoseSignatureAlgorithmNotSupported)
 }
 #[allow(unused_imports)]
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
 #[cfg(test)]
 pub mod tests {
     #![allow(clippy::unwrap_used)]
@@ -978,6 +1099,203 @@ pub mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_sigtst_storage() {
+        let sign1 = coset::CoseSign1Builder::new()
+            .unprotected(
+                coset::HeaderBuilder::new()
+                    .text_value("sigTst".to_string(), Value::Text("v1".to_string()))
+                    .build(),
+            )
+            .build();
+        assert_eq!(
+            get_sigtst(&sign1),
+            Some((Value::Text("v1".to_string()), TimeStampStorage::V1SigTst))
+        );
+
+        let sign1 = coset::CoseSign1Builder::new()
+            .unprotected(
+                coset::HeaderBuilder::new()
+                    .text_value("sigTst".to_string(), Value::Text("v1".to_string()))
+                    .text_value("sigTst2".to_string(), Value::Text("v2".to_string()))
+                    .build(),
+            )
+            .build();
+        assert_eq!(
+            get_sigtst(&sign1),
+            Some((Value::Text("v2".to_string()), TimeStampStorage::V2SigTst2))
+        );
+    }
+
+    #[test]
+    fn test_timestamp_trust() {
+        use crate::{store::Store, utils::test::fixture_path};
+
+        let mut log = DetailedStatusTracker::new();
+        let store = Store::load_from_asset(&fixture_path("CA.jpg"), false, &mut log).unwrap();
+        let claim = store.provenance_claim().unwrap();
+        let sig = claim.signature_val();
+        let data = claim.data().unwrap();
+
+        // an unrelated trust list does not trust the time-stamp
+        let mut cert_path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        cert_path.push("tests/fixtures/rsa-pss256_key-expired.pub");
+        let unrelated = openssl::x509::X509::from_pem(&std::fs::read(&cert_path).unwrap())
+            .unwrap()
+            .to_der()
+            .unwrap();
+        let mut validation_log = DetailedStatusTracker::new();
+        check_timestamp_trust(sig, &data, &[unrelated], &mut validation_log).unwrap();
+        assert!(validation_log
+            .get_log()
+            .iter()
+            .any(|i| i.validation_status == Some(ValidationStatusCode::TimeStampUntrusted)));
+
+        // trusting the TSA certificate of the time-stamp
+        let sign1 = get_cose_sign1(sig, &data, &mut log).unwrap();
+        let sigtst = serde_cbor::to_vec(&get_sigtst(&sign1).unwrap().0).unwrap();
+        let container: TstContainer = serde_cbor::from_slice(&sigtst).unwrap();
+        let token = crate::time_stamp::timestamp_token_der(&container.tst_tokens[0].val).unwrap();
+        let pkcs7 = openssl::pkcs7::Pkcs7::from_der(&token).unwrap();
+        let tsa_certs = pkcs7
+            .signers(
+                &openssl::stack::Stack::new().unwrap(),
+                openssl::pkcs7::Pkcs7Flags::empty(),
+            )
+            .unwrap();
+        let tsa_cert = tsa_certs.get(0).unwrap().to_der().unwrap();
+
+        let mut validation_log = DetailedStatusTracker::new();
+        check_timestamp_trust(sig, &data, &[tsa_cert], &mut validation_log).unwrap();
+        assert!(validation_log.get_log().iter().all(|i| i.err_val.is_none()));
+        assert!(validation_log
+            .get_log()
+            .iter()
+            .any(|i| i.validation_status == Some(ValidationStatusCode::TimeStampTrusted)));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_cosigned_signature() {
+        use crate::{
+            cose_sign::cose_sign, openssl::temp_signer::get_temp_signer_by_alg, CosignedSigner,
+            Signer,
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (vendor, _) = get_temp_signer_by_alg(&temp_dir.path(), "ps256", None);
+        let (publisher, _) = get_temp_signer_by_alg(&temp_dir.path(), "es256", None);
+        let mut signer = CosignedSigner::new(vendor);
+        signer.add_cosigner(publisher);
+
+        let data = b"some sample content to sign";
+        let cose = cose_sign(
+            &signer,
+            data,
+            signer.reserve_size(),
+            TimeStampStorage::V2SigTst2,
+        )
+        .unwrap();
+
+        let mut validation_log = DetailedStatusTracker::new();
+        let info = verify_cose(
+            &cose,
+            data,
+            b"",
+            false,
+            Default::default(),
+            &mut validation_log,
+        )
+        .unwrap();
+        assert!(info.validated);
+        assert_eq!(info.cosigners.len(), 1);
+        assert!(info.cosigners[0].validated);
+        assert_eq!(info.cosigners[0].alg, "es256");
+
+        // neither signature covers other data
+        let info = verify_cose(
+            &cose,
+            b"other content",
+            b"",
+            true,
+            Default::default(),
+            &mut validation_log,
+        )
+        .unwrap();
+        assert!(!info.validated);
+        assert!(!info.cosigners[0].validated);
+
+        // the signature commits to its co-signers, so stripping the co-signature is detected
+        let mut sign1 = c2pa_core::cose::parse_sign1(&cose).unwrap();
+        sign1
+            .unprotected
+            .rest
+            .retain(|(label, _)| *label != Label::Text(COSIGS.to_string()));
+        let stripped = sign1.to_tagged_vec().unwrap();
+        let info = verify_cose(
+            &stripped,
+            data,
+            b"",
+            false,
+            Default::default(),
+            &mut validation_log,
+        )
+        .unwrap();
+        assert!(info.validated);
+        assert_eq!(info.cosigners.len(), 1);
+        assert!(!info.cosigners[0].validated);
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_cosigner_failure_is_reported_alone() {
+        use crate::{
+            cose_sign::cose_sign, get_signer_from_files, openssl::temp_signer::get_temp_signer,
+            CosignedSigner, Signer,
+        };
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (vendor, _) = get_temp_signer(&temp_dir.path());
+        let fixtures = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let expired = get_signer_from_files(
+            fixtures.join("rsa-pss256_key-expired.pub"),
+            fixtures.join("rsa-pss256-expired.pem"),
+            "ps256",
+            None,
+        )
+        .unwrap();
+        let mut signer = CosignedSigner::new(Box::new(vendor));
+        signer.add_cosigner(expired);
+
+        let data = b"some sample content to sign";
+        let cose = cose_sign(
+            &signer,
+            data,
+            signer.reserve_size(),
+            TimeStampStorage::V2SigTst2,
+        )
+        .unwrap();
+
+        // the expired co-signer is not valid but the signature still is
+        let mut validation_log = DetailedStatusTracker::new();
+        let info = verify_cose(
+            &cose,
+            data,
+            b"",
+            false,
+            Default::default(),
+            &mut validation_log,
+        )
+        .unwrap();
+        assert!(info.validated);
+        assert_eq!(info.cosigners.len(), 1);
+        assert!(!info.cosigners[0].validated);
+        assert!(validation_log
+            .get_log()
+            .iter()
+            .any(|i| i.validation_status == Some(ValidationStatusCode::SigningCredentialExpired)));
+    }
+
     #[test]
     #[cfg(feature = "file_io")]
     fn test_expired_cert() {
@@ -990,13 +1308,20 @@ pub mod tests {
 
         if let Ok(signcert) = openssl::x509::X509::from_pem(&expired_cert) {
             let der_bytes = signcert.to_der().unwrap();
-            assert!(check_cert("ps256", &der_bytes, &mut validation_log, None).is_err());
+            assert!(check_cert(
+                "ps256",
+                &der_bytes,
+                &mut validation_log,
+                None,
+                Default::default()
+            )
+            .is_err());
 
             assert!(!validation_log.get_log().is_empty());
 
             assert_eq!(
                 validation_log.get_log()[0].validation_status,
-                Some(validation_status::SIGNING_CREDENTIAL_EXPIRED.to_string())
+                Some(ValidationStatusCode::SigningCredentialExpired)
             );
         }
     }
@@ -1081,22 +1406,132 @@ pub mod tests {
 
         if let Ok(signcert) = openssl::x509::X509::from_pem(&es256_cert) {
             let der_bytes = signcert.to_der().unwrap();
-            assert!(check_cert("es256", &der_bytes, &mut validation_log, None).is_ok());
+            assert!(check_cert(
+                "es256",
+                &der_bytes,
+                &mut validation_log,
+                None,
+                Default::default()
+            )
+            .is_ok());
         }
 
         if let Ok(signcert) = openssl::x509::X509::from_pem(&es384_cert) {
             let der_bytes = signcert.to_der().unwrap();
-            assert!(check_cert("es384", &der_bytes, &mut validation_log, None).is_ok());
+            assert!(check_cert(
+                "es384",
+                &der_bytes,
+                &mut validation_log,
+                None,
+                Default::default()
+            )
+            .is_ok());
         }
 
         if let Ok(signcert) = openssl::x509::X509::from_pem(&es512_cert) {
             let der_bytes = signcert.to_der().unwrap();
-            assert!(check_cert("es512", &der_bytes, &mut validation_log, None).is_ok());
+            assert!(check_cert(
+                "es512",
+                &der_bytes,
+                &mut validation_log,
+                None,
+                Default::default()
+            )
+            .is_ok());
         }
 
         if let Ok(signcert) = openssl::x509::X509::from_pem(&rsa_pss256_cert) {
             let der_bytes = signcert.to_der().unwrap();
-            assert!(check_cert("ps256", &der_bytes, &mut validation_log, None).is_ok());
+            assert!(check_cert(
+                "ps256",
+                &der_bytes,
+                &mut validation_log,
+                None,
+                Default::default()
+            )
+            .is_ok());
         }
     }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_check_cert_validation_time() {
+        use crate::openssl::temp_signer;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (_, cert_path) = temp_signer::get_ec_signer(&temp_dir.path(), "es256", None);
+        let cert = openssl::x509::X509::from_pem(&std::fs::read(&cert_path).unwrap()).unwrap();
+        let der_bytes = cert.to_der().unwrap();
+
+        let at = |time| CertCheckOptions {
+            validation_time: Some(time),
+            ..Default::default()
+        };
+        let now = chrono::Utc::now().timestamp();
+        let mut validation_log = DetailedStatusTracker::new();
+        assert!(check_cert("es256", &der_bytes, &mut validation_log, None, at(now)).is_ok());
+
+        // the certificate was not yet valid a year ago
+        let year_ago = now - 365 * 24 * 60 * 60;
+        assert!(matches!(
+            check_cert("es256", &der_bytes, &mut validation_log, None, at(year_ago)),
+            Err(Error::CoseCertExpiration)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_check_cert_mode() {
+        use crate::openssl::temp_signer;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (_, cert_path) = temp_signer::get_ec_signer(&temp_dir.path(), "es256", None);
+        let cert = openssl::x509::X509::from_pem(&std::fs::read(&cert_path).unwrap()).unwrap();
+        let der_bytes = cert.to_der().unwrap();
+
+        // a legacy algorithm is a warning by default
+        let mut validation_log = DetailedStatusTracker::new();
+        assert!(check_cert(
+            "rs256",
+            &der_bytes,
+            &mut validation_log,
+            None,
+            Default::default()
+        )
+        .is_ok());
+        let warnings = validation_log.items_with_severity(Severity::Warning);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].validation_status.as_ref(),
+            Some(&ValidationStatusCode::AlgorithmDeprecated)
+        );
+
+        // and rejects the signature when checking strictly
+        let strict = CertCheckOptions {
+            mode: CertCheckMode::Strict,
+            ..Default::default()
+        };
+        let mut validation_log = DetailedStatusTracker::new();
+        assert!(matches!(
+            check_cert("rs256", &der_bytes, &mut validation_log, None, strict),
+            Err(Error::CoseSignatureAlgorithmNotSupported)
+        ));
+        assert!(check_cert("es256", &der_bytes, &mut validation_log, None, strict).is_ok());
+    }
+
+    #[test]
+    fn test_check_cert_chain_logs_every_failure() {
+        let certs = vec![b"not a cert".to_vec(), b"nor this".to_vec()];
+
+        let mut validation_log = DetailedStatusTracker::new();
+        assert!(matches!(
+            check_cert_chain(&certs, &mut validation_log, None, Default::default()),
+            Err(Error::CoseCertChainInvalid)
+        ));
+
+        let errors = validation_log.items_with_severity(Severity::Error);
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].description.starts_with("chain certificate 0"));
+        assert!(errors[1].description.starts_with("chain certificate 1"));
+    }
 }
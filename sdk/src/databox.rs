@@ -0,0 +1,63 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::io::{Cursor, Read};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Data stored in the databox store of a manifest.
+///
+/// Assertions reference databoxes by hashed URI, so large binary resources such as model
+/// cards or sidecar files do not have to be embedded in the assertions themselves.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct DataBox {
+    format: String,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+impl DataBox {
+    /// Create a databox holding `data` of MIME type `format`
+    pub fn new(format: &str, data: Vec<u8>) -> Self {
+        DataBox {
+            format: format.to_owned(),
+            data,
+        }
+    }
+
+    /// Create a databox holding the remaining content of `stream`.
+    ///
+    /// Databoxes are embedded in the manifest, so the whole content is read into memory.
+    pub fn from_stream(format: &str, stream: &mut dyn Read) -> Result<Self> {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+        Ok(Self::new(format, data))
+    }
+
+    /// Returns the MIME type of the data
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    /// Returns the data
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns a stream reading the data
+    pub fn into_stream(self) -> impl Read {
+        Cursor::new(self.data)
+    }
+}
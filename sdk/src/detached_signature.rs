@@ -0,0 +1,190 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signatures over claims stored apart from the asset.
+//!
+//! A claim signature is a COSE_Sign1 whose payload, the claim bytes, is detached.
+//! Registries that keep claims in a database can sign and verify those bytes directly,
+//! so only the signature has to travel with the asset.
+
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "async_signer")]
+use crate::AsyncSigner;
+#[cfg(feature = "file_io")]
+use crate::{
+    cert_profile::check_signing_certs, cose_sign::cose_sign, signer::operation_signer, Signer,
+    TimeStampStorage,
+};
+use crate::{
+    cose_validator::{verify_cose, verify_cose_async, CertCheckOptions},
+    status_tracker::{log_item, StatusTracker},
+    validation_status::ValidationStatusCode,
+    validator::ValidationInfo,
+    Error, Result, VerifyOptions,
+};
+
+/// A verified detached claim signature
+#[derive(Clone, Debug, PartialEq)]
+pub struct DetachedSignatureInfo {
+    /// The signature algorithm, e.g. "es256"
+    pub alg: String,
+    /// The time the claim was signed, from its time-stamp if it has one
+    pub time: Option<DateTime<Utc>>,
+    /// The organization of the signing certificate's subject
+    pub issuer: Option<String>,
+}
+
+impl DetachedSignatureInfo {
+    // the signature is only valid if it, and any co-signatures, matched the claim bytes
+    fn from_validation(
+        info: ValidationInfo,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Self> {
+        if !info.validated || info.cosigners.iter().any(|c| !c.validated) {
+            let log_item = log_item!(
+                "Cose_Sign1",
+                "claim signature is not valid",
+                "verify_detached"
+            )
+            .error(Error::CoseSignature)
+            .validation_status(ValidationStatusCode::ClaimSignatureMismatch);
+            validation_log.log(log_item, Some(Error::CoseSignature))?;
+            return Err(Error::CoseSignature);
+        }
+
+        Ok(DetachedSignatureInfo {
+            alg: info.alg,
+            time: info.date,
+            issuer: info.issuer_org,
+        })
+    }
+}
+
+/// Sign claim bytes stored apart from the asset.
+///
+/// Returns a COSE_Sign1 without the claim bytes, padded to the signer's reserve size.
+/// A time-stamp from the signer's time authority is stored as described by `tss`.
+/// Fails if the signing certificate breaks the signer's certificate acceptance policy.
+#[cfg(feature = "file_io")]
+pub fn sign_detached(
+    claim_bytes: &[u8],
+    signer: &dyn Signer,
+    tss: TimeStampStorage,
+) -> Result<Vec<u8>> {
+    let signer = operation_signer(signer)?;
+    check_signing_certs(
+        &signer.certificate_acceptance_policy(),
+        &signer.certs()?,
+        signer.cosigners(),
+    )?;
+    cose_sign(signer, claim_bytes, signer.reserve_size(), tss)
+}
+
+/// Sign claim bytes stored apart from the asset with an async signer.
+///
+/// Returns a COSE_Sign1 without the claim bytes.
+#[cfg(feature = "async_signer")]
+pub async fn sign_detached_async(claim_bytes: &[u8], signer: &dyn AsyncSigner) -> Result<Vec<u8>> {
+    signer.sign(claim_bytes).await
+}
+
+/// Verify a detached COSE_Sign1 signature over claim bytes stored apart from it.
+///
+/// The signing certificate is checked as set in `options`. Findings are added to
+/// `validation_log`, and an invalid signature returns an error. In the browser use
+/// [`verify_detached_async`] instead.
+pub fn verify_detached(
+    cose_bytes: &[u8],
+    claim_bytes: &[u8],
+    options: &VerifyOptions,
+    validation_log: &mut impl StatusTracker,
+) -> Result<DetachedSignatureInfo> {
+    let info = verify_cose(
+        cose_bytes,
+        claim_bytes,
+        b"",
+        false,
+        CertCheckOptions::from(options),
+        validation_log,
+    )?;
+    DetachedSignatureInfo::from_validation(info, validation_log)
+}
+
+/// Verify a detached COSE_Sign1 signature over claim bytes stored apart from it.
+///
+/// See [`verify_detached`].
+pub async fn verify_detached_async(
+    cose_bytes: &[u8],
+    claim_bytes: &[u8],
+    options: &VerifyOptions,
+    validation_log: &mut impl StatusTracker,
+) -> Result<DetachedSignatureInfo> {
+    let info = verify_cose_async(
+        cose_bytes.to_vec(),
+        claim_bytes.to_vec(),
+        Vec::new(),
+        false,
+        CertCheckOptions::from(options),
+        validation_log,
+    )
+    .await?;
+    DetachedSignatureInfo::from_validation(info, validation_log)
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        openssl::temp_signer::get_temp_signer,
+        status_tracker::{DetailedStatusTracker, OneShotStatusTracker},
+    };
+
+    #[test]
+    fn test_detached_signature() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+
+        let claim_bytes = b"claim bytes kept in a registry";
+        let cose = sign_detached(claim_bytes, &signer, TimeStampStorage::V2SigTst2).unwrap();
+        assert_eq!(cose.len(), signer.reserve_size());
+
+        let options = VerifyOptions::new();
+        let info = verify_detached(
+            &cose,
+            claim_bytes,
+            &options,
+            &mut OneShotStatusTracker::new(),
+        )
+        .unwrap();
+        assert_eq!(info.alg, signer.alg().unwrap());
+
+        // the signature does not cover other claim bytes
+        let mut validation_log = DetailedStatusTracker::new();
+        let result = verify_detached(
+            &cose,
+            b"tampered claim bytes",
+            &options,
+            &mut validation_log,
+        );
+        assert!(matches!(result, Err(Error::CoseSignature)));
+        assert!(validation_log
+            .get_log()
+            .iter()
+            .any(|item| item.validation_status.as_ref()
+                == Some(&ValidationStatusCode::ClaimSignatureMismatch)));
+    }
+}
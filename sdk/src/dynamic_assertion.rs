@@ -0,0 +1,149 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Assertions whose content is only known when the claim is signed.
+//!
+//! A signer returns these from `dynamic_assertions`. Space for each one is
+//! reserved in the claim, then its content is generated once the hashes of
+//! every other assertion are final, so it can refer to them.
+
+#[cfg(feature = "file_io")]
+use serde_cbor::Value;
+
+#[cfg(feature = "file_io")]
+use crate::Error;
+use crate::{hashed_uri::HashedUri, Result};
+
+/// The claim as it is when dynamic assertions are generated
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PartialClaim {
+    pub(crate) label: String,
+    pub(crate) alg: String,
+    pub(crate) claim_bytes: Vec<u8>,
+    pub(crate) hash: Vec<u8>,
+    pub(crate) assertions: Vec<HashedUri>,
+}
+
+impl PartialClaim {
+    /// Label of the manifest being signed
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// The hash algorithm of the claim, such as "sha256"
+    pub fn alg(&self) -> &str {
+        &self.alg
+    }
+
+    /// The CBOR bytes of the claim, with the dynamic assertions still referenced by the
+    /// hashes of their placeholders, so they differ from the bytes finally signed
+    pub fn claim_bytes(&self) -> &[u8] {
+        &self.claim_bytes
+    }
+
+    /// The hash of [`claim_bytes`](Self::claim_bytes) with the claim's algorithm
+    pub fn hash(&self) -> &[u8] {
+        &self.hash
+    }
+
+    /// References to the other assertions of the claim, with their final hashes
+    pub fn assertions(&self) -> impl Iterator<Item = &HashedUri> {
+        self.assertions.iter()
+    }
+}
+
+/// An assertion generated while signing, such as an identity assertion
+/// that signs over the rest of the claim.
+pub trait DynamicAssertion: Send + Sync {
+    /// Label of the assertion
+    fn label(&self) -> String;
+
+    /// The largest size in bytes of the CBOR content of the assertion
+    fn reserve_size(&self) -> usize;
+
+    /// Generate the CBOR content of the assertion.
+    ///
+    /// `label` is the label with instance the assertion is stored under and `size`
+    /// is the space reserved for it. If the content is a map smaller than `size`
+    /// a `pad` field is added to fill the reserved space.
+    fn content(&self, label: &str, size: usize, claim: &PartialClaim) -> Result<Vec<u8>>;
+}
+
+// the CBOR content reserving space for a dynamic assertion
+#[cfg(feature = "file_io")]
+pub(crate) fn placeholder(reserve_size: usize) -> Result<Vec<u8>> {
+    Ok(serde_cbor::to_vec(&pad_map(Vec::new(), reserve_size))?)
+}
+
+// pad CBOR content to exactly `size` bytes
+#[cfg(feature = "file_io")]
+pub(crate) fn pad_content(content: Vec<u8>, size: usize) -> Result<Vec<u8>> {
+    if content.len() == size {
+        return Ok(content);
+    }
+
+    let entries = match serde_cbor::from_slice(&content) {
+        Ok(Value::Map(map)) if content.len() < size => map
+            .into_iter()
+            .filter(|(k, _)| k != &Value::Text("pad".to_owned()))
+            .collect::<Vec<_>>(),
+        _ => return Err(Error::DynamicAssertionSize),
+    };
+
+    // the length of the pad header varies with its size, so try the lengths around the estimate
+    let unpadded = serde_cbor::to_vec(&pad_map(entries.clone(), 0))?.len();
+    let estimate = size.saturating_sub(unpadded);
+    for pad_len in (estimate.saturating_sub(8)..=estimate).rev() {
+        let padded = serde_cbor::to_vec(&pad_map(entries.clone(), pad_len))?;
+        if padded.len() == size {
+            return Ok(padded);
+        }
+    }
+    Err(Error::DynamicAssertionSize)
+}
+
+#[cfg(feature = "file_io")]
+fn pad_map(mut entries: Vec<(Value, Value)>, pad_len: usize) -> Value {
+    entries.push((
+        Value::Text("pad".to_owned()),
+        Value::Bytes(vec![0; pad_len]),
+    ));
+    Value::Map(entries.into_iter().collect())
+}
+
+#[cfg(test)]
+#[cfg(feature = "file_io")]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_pad_content() {
+        let size = placeholder(100).unwrap().len();
+
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("name", "value");
+        let content = serde_cbor::to_vec(&map).unwrap();
+
+        let padded = pad_content(content, size).unwrap();
+        assert_eq!(padded.len(), size);
+        let value: std::collections::BTreeMap<String, Value> =
+            serde_cbor::from_slice(&padded).unwrap();
+        assert_eq!(value["name"], Value::Text("value".to_owned()));
+
+        // content larger than the reserved space is rejected
+        let large = serde_cbor::to_vec(&vec![0u8; 200]).unwrap();
+        assert!(pad_content(large, size).is_err());
+    }
+}
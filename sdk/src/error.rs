@@ -40,6 +40,15 @@ pub enum Error {
     #[error("could not find the assertion to redact")]
     AssertionRedactionNotFound,
 
+    #[error("invalid actions assertion: {0}")]
+    AssertionActionsInvalid(String),
+
+    #[error("invalid training and data mining assertion: {0}")]
+    AssertionTrainingMiningInvalid(String),
+
+    #[error("assertion does not match its schema: {0}")]
+    AssertionSchemaMismatch(String),
+
     #[error("bad parameter: {0}")]
     BadParam(String),
 
@@ -78,6 +87,18 @@ pub enum Error {
     #[error("update manifest is invalid")]
     UpdateManifestInvalid,
 
+    /// A manifest archive was signed for an asset without an embedded manifest store, so its
+    /// hard binding no longer matches once embedded. Sign the manifest for the asset instead.
+    #[error(
+        "the manifest archive does not match the asset once embedded, it must be signed again"
+    )]
+    ArchiveNotEmbeddable,
+
+    /// A manifest definition does not describe a valid manifest. `path` locates the
+    /// offending value, such as `$.assertions[1].label`.
+    #[error("invalid manifest definition at {path}: {reason}")]
+    ManifestDefinitionInvalid { path: String, reason: String },
+
     /// The COSE Sign1 structure can not be parsed.
     #[error("COSE Sign1 structure can not be parsed: {coset_error}")]
     InvalidCoseSignature {
@@ -100,6 +121,67 @@ pub enum Error {
     #[error("COSE error parsing certificate")]
     CoseInvalidCert,
 
+    /// The signing certificate is not X.509 version 3.
+    #[error("COSE certificate version incorrect")]
+    CoseCertInvalidVersion,
+
+    /// The signing certificate is signed with an algorithm C2PA does not allow.
+    #[error("COSE certificate algorithm not supported")]
+    CoseCertUnsupportedAlgorithm,
+
+    /// The RSASSA-PSS parameters of the signing certificate are not supported.
+    #[error("COSE certificate has invalid RSASSA-PSS parameters")]
+    CoseCertInvalidPssParameters,
+
+    /// The EC key of the signing certificate is not on a supported curve.
+    #[error("COSE certificate key uses an unsupported curve")]
+    CoseCertUnsupportedCurve,
+
+    /// The RSA key of the signing certificate is shorter than 2048 bits.
+    #[error("COSE certificate key length too short")]
+    CoseCertKeyTooShort,
+
+    /// The signing certificate has unique identifiers but is not self signed.
+    #[error("COSE certificate has issuer or subject unique identifiers")]
+    CoseCertInvalidUniqueIdentifiers,
+
+    /// The signing certificate is a CA certificate that is not self signed.
+    #[error("COSE certificate is not an end entity certificate")]
+    CoseCertNotEndEntity,
+
+    /// The signing certificate has none of the extended key usages C2PA requires.
+    #[error("COSE certificate missing required EKU")]
+    CoseCertMissingEku,
+
+    /// The signing certificate has an extended key usage or a set of them that C2PA does not allow.
+    #[error("COSE certificate has an invalid set of EKUs")]
+    CoseCertInvalidEku,
+
+    /// The key usage of the signing certificate does not allow digital signatures.
+    #[error("COSE certificate key usage does not allow signing")]
+    CoseCertInvalidKeyUsage,
+
+    /// The key usage extension of the signing certificate is not critical, rejected when
+    /// certificates are checked strictly.
+    #[error("COSE certificate key usage not marked critical")]
+    CoseCertKeyUsageNotCritical,
+
+    /// The signing certificate has no authority key identifier.
+    #[error("COSE certificate missing authority key identifier")]
+    CoseCertMissingAuthorityKeyId,
+
+    /// The CA signing certificate has no subject key identifier.
+    #[error("COSE certificate missing subject key identifier")]
+    CoseCertMissingSubjectKeyId,
+
+    /// The signing certificate has a critical extension that is not understood.
+    #[error("COSE certificate has an unhandled critical extension")]
+    CoseCertUnhandledCriticalExtension,
+
+    /// A certificate of the chain is not issued by the next one.
+    #[error("COSE certificate chain is invalid")]
+    CoseCertChainInvalid,
+
     #[error("COSE signature invalid")]
     CoseSignature,
 
@@ -112,6 +194,10 @@ pub enum Error {
     #[error("COSE certificate has been revoked")]
     CoseCertRevoked,
 
+    /// The signing certificate is not trusted by the configured trust anchors or allowed list.
+    #[error("COSE certificate is not trusted")]
+    CoseCertUntrusted,
+
     /// Unable to parse the time stamp from this signature.
     #[error("COSE time stamp could not be parsed")]
     CoseInvalidTimeStamp,
@@ -127,15 +213,42 @@ pub enum Error {
     #[error("could not generate a trusted time stamp")]
     CoseTimeStampGeneration,
 
+    /// The time stamp was not signed by a trusted time stamp authority.
+    #[error("COSE time stamp is not trusted")]
+    CoseTimeStampUntrusted,
+
     #[error("COSE TimeStamp Authority failure")]
     CoseTimeStampAuthority,
 
+    #[error("COSE signature already has a time-stamp")]
+    CoseTimeStampExists,
+
     #[error("COSE Signature too big for JUMBF box")]
     CoseSigboxTooSmall,
 
+    /// None of the credentials of a `RotatingSigner` is valid at the signing time.
+    #[error("no signing credential is valid at {0}")]
+    SigningCredentialNotValid(String),
+
+    /// The signing credential expires within the renewal window and no other credential takes over.
+    #[error("signing credential expires at {0} and no credential replaces it")]
+    SigningCredentialExpiring(String),
+
+    /// The signing certificate breaks rules of the signing certificate profile that the
+    /// signer's certificate acceptance policy enforces.
+    #[error("signing certificate rejected: {0}")]
+    SigningCertificateRejected(String),
+
+    /// A remote signing service could not be reached or refused to sign.
+    #[error("remote signing failed: {0}")]
+    RemoteSigning(String),
+
     #[error("WASM verifier error")]
     WasmVerifier,
 
+    #[error("WASM signer error")]
+    WasmSigner,
+
     #[error("WASM crypto key error")]
     WasmKey,
 
@@ -149,6 +262,10 @@ pub enum Error {
     #[error("could not create valid JUMBF for claim")]
     JumbfCreationError,
 
+    /// The content of a dynamic assertion does not fit in the space reserved for it.
+    #[error("dynamic assertion does not fit in its reserved size")]
+    DynamicAssertionSize,
+
     /// No JUMBF data found.
     /// TODO before merging PR: Does this error case need to be part of the public API?
     #[error("no JUMBF data found")]
@@ -169,6 +286,14 @@ pub enum Error {
     #[error("embedding error")]
     EmbeddingError,
 
+    /// The operation was cancelled using a `CancellationToken`.
+    #[error("operation cancelled")]
+    OperationCancelled,
+
+    /// A manifest store exceeded one of the configured `ResourceLimits`.
+    #[error("resource limit exceeded: {0}")]
+    ResourceLimitExceeded(String),
+
     // Working claim errors
     #[error("ingredient file not found")]
     IngredientNotFound,
@@ -203,6 +328,10 @@ pub enum Error {
     #[error("The Verifiable Content structure is not valid")]
     VerifiableCredentialInvalid,
 
+    /// The proof of a Verifiable Credential did not validate.
+    #[error("The Verifiable Credential proof does not match")]
+    VerifiableCredentialProofMismatch,
+
     /// Could not parse ECDSA signature. (Only appears when using WASM web crypto.)
     #[error("could not parse ECDSA signature")]
     InvalidEcdsaSignature,
@@ -221,7 +350,7 @@ pub enum Error {
     CborError(#[from] serde_cbor::Error),
 
     #[error(transparent)]
-    #[cfg(feature = "file_io")]
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
     OpenSslError(#[from] openssl::error::ErrorStack),
 
     #[error(transparent)]
@@ -234,11 +363,30 @@ pub enum Error {
 /// A specialized `Result` type for C2PA toolkit operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl From<c2pa_core::Error> for Error {
+    fn from(err: c2pa_core::Error) -> Self {
+        match err {
+            c2pa_core::Error::CoseSignature
+            | c2pa_core::Error::CoseSignatureMismatch
+            | c2pa_core::Error::Crypto(_) => Error::CoseSignature,
+            c2pa_core::Error::CoseSignatureAlgorithmNotSupported => {
+                Error::CoseSignatureAlgorithmNotSupported
+            }
+            c2pa_core::Error::CoseX5ChainMissing => Error::CoseX5ChainMissing,
+            c2pa_core::Error::CoseInvalidCert | c2pa_core::Error::CoseX5tMismatch => {
+                Error::CoseInvalidCert
+            }
+            c2pa_core::Error::CoseSigboxTooSmall => Error::CoseSigboxTooSmall,
+            c2pa_core::Error::UnsupportedType => Error::UnsupportedType,
+        }
+    }
+}
+
 pub(crate) fn wrap_io_err(err: std::io::Error) -> Error {
     Error::IoError(err)
 }
 
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 pub(crate) fn wrap_openssl_err(err: openssl::error::ErrorStack) -> Error {
     Error::OpenSslError(err)
 }
@@ -0,0 +1,297 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signing claims in two phases, with a signature made outside the SDK.
+//!
+//! The first phase embeds the manifest with a placeholder for its signature and returns
+//! the bytes to be signed. The signature can then be made at any later time, such as in
+//! an HSM ceremony, and the second phase patches it into the asset in place of the
+//! placeholder.
+
+use std::path::Path;
+
+use c2pa_core::cose::{pad_sign1, parse_sign1, sign1_to_sign_with_placement};
+use ciborium::value::Value;
+use coset::{Label, TaggedCborSerializable};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cert_profile::check_signing_certs, claim::Claim, store::Store, CertificateAcceptancePolicy,
+    Error, Result, X5ChainPlacement,
+};
+
+/// The signing credentials of a signing service outside the SDK.
+///
+/// The algorithm and certificate chain are needed before the claim is signed, since
+/// they are part of the signed headers.
+#[derive(Clone, Debug)]
+pub struct ExternalSigner {
+    alg: String,
+    certs: Vec<Vec<u8>>,
+    reserve_size: usize,
+    x5chain_placement: X5ChainPlacement,
+    certificate_acceptance_policy: CertificateAcceptancePolicy,
+}
+
+impl ExternalSigner {
+    /// Creates an external signer for the C2PA algorithm `alg`, such as "es256", with
+    /// `certs`, the DER encoded certificate chain starting with the signing certificate.
+    /// `reserve_size` bytes are reserved in the asset for the signature.
+    pub fn new(alg: &str, certs: Vec<Vec<u8>>, reserve_size: usize) -> Self {
+        ExternalSigner {
+            alg: alg.to_owned(),
+            certs,
+            reserve_size,
+            x5chain_placement: X5ChainPlacement::default(),
+            certificate_acceptance_policy: CertificateAcceptancePolicy::default(),
+        }
+    }
+
+    /// Sets where the `x5chain` header with the certificate chain is placed
+    pub fn set_x5chain_placement(&mut self, placement: X5ChainPlacement) -> &mut Self {
+        self.x5chain_placement = placement;
+        self
+    }
+
+    /// Sets which rules of the signing certificate profile the signing certificate must
+    /// follow before the manifest is embedded, every rule that is not soft by default
+    pub fn set_certificate_acceptance_policy(
+        &mut self,
+        policy: CertificateAcceptancePolicy,
+    ) -> &mut Self {
+        self.certificate_acceptance_policy = policy;
+        self
+    }
+
+    /// Returns the signing algorithm
+    pub fn alg(&self) -> &str {
+        &self.alg
+    }
+
+    /// Returns the size reserved for the signature
+    pub fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+}
+
+/// A manifest embedded in an asset that is waiting for its signature.
+///
+/// It serializes, so it can be kept until the signature is made.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct PendingSignature {
+    manifest_label: String,
+    #[serde(with = "serde_bytes")]
+    claim: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    unsigned_sign1: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    to_be_signed: Vec<u8>,
+}
+
+impl PendingSignature {
+    pub(crate) fn new(claim: &Claim, signer: &ExternalSigner) -> Result<Self> {
+        // C2PA 2.x restricts the signature algorithms that may be used
+        if !claim.claim_version().allows_signing_alg(&signer.alg) {
+            return Err(Error::CoseSignatureAlgorithmNotSupported);
+        }
+
+        // fail before embedding if verifiers would reject the certificate
+        check_signing_certs(&signer.certificate_acceptance_policy, &signer.certs, &[])?;
+
+        let claim_bytes = claim.data()?;
+        let (sign1, to_be_signed) = sign1_to_sign_with_placement(
+            &signer.alg,
+            signer.certs.clone(),
+            &claim_bytes,
+            signer.x5chain_placement,
+        )?;
+        let unsigned_sign1 = sign1.to_tagged_vec().map_err(|_e| Error::CoseSignature)?;
+
+        Ok(PendingSignature {
+            manifest_label: claim.label().to_owned(),
+            claim: claim_bytes,
+            unsigned_sign1,
+            to_be_signed,
+        })
+    }
+
+    /// Returns the label of the manifest waiting for its signature
+    pub fn manifest_label(&self) -> &str {
+        &self.manifest_label
+    }
+
+    /// Returns the claim bytes, the detached payload of the COSE_Sign1
+    pub fn claim_bytes(&self) -> &[u8] {
+        &self.claim
+    }
+
+    /// Returns the bytes to be signed, the COSE Sig_structure over the claim with the
+    /// protected header of the external signer
+    pub fn to_be_signed(&self) -> &[u8] {
+        &self.to_be_signed
+    }
+
+    /// Returns the COSE_Sign1 for a raw `signature` over the bytes to be signed, for
+    /// signing services that do not produce COSE themselves.
+    pub fn cose_sign1(&self, signature: Vec<u8>) -> Result<Vec<u8>> {
+        let mut sign1 = parse_sign1(&self.unsigned_sign1)?;
+        sign1.signature = signature;
+
+        let sign_time = chrono::Utc::now().to_rfc3339(); // todo: remove when switch to cose_timestamp
+        sign1.unprotected.rest.push((
+            Label::Text("temp_signing_time".to_string()),
+            Value::Text(sign_time),
+        ));
+        sign1.to_tagged_vec().map_err(|_e| Error::CoseSignature)
+    }
+
+    // pad the externally produced COSE_Sign1 to `size`, the size of its placeholder
+    pub(crate) fn padded_signature(&self, cose_bytes: &[u8], size: usize) -> Result<Vec<u8>> {
+        let mut sign1 = parse_sign1(cose_bytes)?;
+        sign1.payload = None; // the claim is known
+        Ok(pad_sign1(&mut sign1, size)?)
+    }
+
+    /// Embed `cose_bytes`, the COSE_Sign1 signing the claim, into the asset at
+    /// `asset_path` in place of the placeholder.
+    ///
+    /// The signature must verify over the claim bytes and fit in the reserved size.
+    /// Returns [`Error::ClaimMissing`] if the asset does not have the pending manifest.
+    pub fn embed_signature<P: AsRef<Path>>(&self, asset_path: P, cose_bytes: &[u8]) -> Result<()> {
+        Store::embed_external_signature(asset_path.as_ref(), self, cose_bytes, false)
+    }
+
+    /// Same as [`embed_signature`](Self::embed_signature), but only the placeholder is
+    /// overwritten in the file rather than the file being rewritten, which avoids
+    /// copying large assets.
+    ///
+    /// The file is not replaced atomically, so it may be left partly patched if the
+    /// write is interrupted. Assets whose format splits the placeholder across
+    /// segments are rewritten instead.
+    pub fn embed_signature_in_place<P: AsRef<Path>>(
+        &self,
+        asset_path: P,
+        cose_bytes: &[u8],
+    ) -> Result<()> {
+        Store::embed_external_signature(asset_path.as_ref(), self, cose_bytes, true)
+    }
+}
+
+#[cfg(test)]
+#[cfg(not(target_os = "wasi"))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        openssl::temp_signer::get_temp_signer_by_alg,
+        utils::test::{fixture_path, temp_dir_path},
+        Manifest, ManifestStore, Signer,
+    };
+
+    #[test]
+    fn test_external_signing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = get_temp_signer_by_alg(&temp_dir.path(), "ps256", None);
+        let external = ExternalSigner::new("ps256", signer.certs().unwrap(), signer.reserve_size());
+
+        let source = fixture_path("earth_apollo17.jpg");
+        let output = temp_dir_path(&temp_dir, "external.jpg");
+
+        // phase one embeds the manifest with a placeholder
+        let mut manifest = Manifest::new("test".to_owned());
+        let pending = manifest
+            .embed_for_external_signing(&source, &output, &external)
+            .unwrap();
+
+        // the pending signature can be kept until the signature is made
+        let json = serde_json::to_string(&pending).unwrap();
+        let pending: PendingSignature = serde_json::from_str(&json).unwrap();
+
+        // a signature over other data is rejected
+        let wrong = pending.cose_sign1(signer.sign(b"other").unwrap()).unwrap();
+        assert!(pending.embed_signature(&output, &wrong).is_err());
+
+        // phase two patches in the signature
+        let signature = signer.sign(pending.to_be_signed()).unwrap();
+        let cose = pending.cose_sign1(signature).unwrap();
+        pending.embed_signature(&output, &cose).unwrap();
+
+        let manifest_store = ManifestStore::from_file(&output).unwrap();
+        assert!(manifest_store.validation_status().is_none());
+        assert_eq!(
+            manifest_store.active_label(),
+            Some(pending.manifest_label())
+        );
+    }
+
+    #[test]
+    fn test_external_signing_in_place() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = get_temp_signer_by_alg(&temp_dir.path(), "es256", None);
+        let external = ExternalSigner::new("es256", signer.certs().unwrap(), signer.reserve_size());
+
+        let source = fixture_path("libpng-test.png");
+        let output = temp_dir_path(&temp_dir, "external.png");
+
+        let mut manifest = Manifest::new("test".to_owned());
+        let pending = manifest
+            .embed_for_external_signing(&source, &output, &external)
+            .unwrap();
+        let size = std::fs::metadata(&output).unwrap().len();
+
+        // the signature is patched over the placeholder, the file keeps its size
+        let signature = signer.sign(pending.to_be_signed()).unwrap();
+        let cose = pending.cose_sign1(signature).unwrap();
+        pending.embed_signature_in_place(&output, &cose).unwrap();
+        assert_eq!(std::fs::metadata(&output).unwrap().len(), size);
+
+        let manifest_store = ManifestStore::from_file(&output).unwrap();
+        assert!(manifest_store.validation_status().is_none());
+
+        // the placeholder is gone, so the signature cannot be embedded again
+        assert!(pending.embed_signature_in_place(&output, &cose).is_err());
+    }
+
+    #[test]
+    fn test_external_signing_rejected_certificate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let signer = crate::get_signer_from_files(
+            fixture_path("rsa-pss256_key-expired.pub"),
+            fixture_path("rsa-pss256-expired.pem"),
+            "ps256",
+            None,
+        )
+        .unwrap();
+        let mut external =
+            ExternalSigner::new("ps256", signer.certs().unwrap(), signer.reserve_size());
+
+        let source = fixture_path("earth_apollo17.jpg");
+        let output = temp_dir_path(&temp_dir, "external.jpg");
+
+        // nothing is embedded for a certificate verifiers would reject
+        let mut manifest = Manifest::new("test".to_owned());
+        assert!(matches!(
+            manifest.embed_for_external_signing(&source, &output, &external),
+            Err(Error::SigningCertificateRejected(_))
+        ));
+        assert!(!output.exists());
+
+        // unless the policy accepts it
+        external.set_certificate_acceptance_policy(CertificateAcceptancePolicy::accept_any());
+        let mut manifest = Manifest::new("test".to_owned());
+        assert!(manifest
+            .embed_for_external_signing(&source, &output, &external)
+            .is_ok());
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Entry points for the fuzz targets in `sdk/fuzz`.
+//!
+//! Only built with the `fuzzing` feature, which the fuzz crate enables, so the targets
+//! can reach parsers that are not part of the public API.
+
+use crate::{
+    claim::{Claim, ClaimVersion},
+    status_tracker::DetailedStatusTracker,
+    store::Store,
+};
+
+/// Parse a claim from CBOR as either claim version
+pub fn parse_claim(data: &[u8]) {
+    let _ = Claim::from_data("fuzz", data);
+    let _ = Claim::from_data_with_version("fuzz", data, ClaimVersion::V2);
+}
+
+/// Parse a manifest store from its JUMBF boxes, logging every error found
+pub fn parse_jumbf(data: &[u8]) {
+    let mut validation_log = DetailedStatusTracker::new();
+    let _ = Store::from_jumbf(data, &mut validation_log);
+}
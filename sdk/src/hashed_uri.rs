@@ -17,11 +17,13 @@ use std::fmt;
 /// Hashed Uri stucture as defined by C2PA spec
 /// It is annotated to produce the correctly tagged cbor serialization
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct HashedUri {
     url: String, // URI stored as tagged cbor
     #[serde(skip_serializing_if = "Option::is_none")]
     alg: Option<String>,
     #[serde(with = "serde_bytes")]
+    #[cfg_attr(feature = "json_schema", schemars(with = "Vec<u8>"))]
     hash: Vec<u8>, // hash stored as cbor byte string
 }
 
@@ -19,21 +19,28 @@ use crate::{
     cbor_types::BytesT,
     claim::Claim,
     error::{Error, Result},
+    hash_utils::{hash_by_alg, verify_by_alg},
     hashed_uri::HashedUri,
     jumbf,
     store::Store,
-    validation_status::{self, ValidationStatus},
+    validation_status::{ValidationStatus, ValidationStatusCode},
 };
 use std::ops::Deref;
 
+use crate::utils::thumbnail::{ThumbnailGenerator, ThumbnailOptions};
 #[cfg(feature = "file_io")]
-use crate::{error::wrap_io_err, validation_status::status_for_store, xmp_inmemory_utils::XmpInfo};
-use log::{debug, error};
+use crate::{
+    error::wrap_io_err, utils::thumbnail::ImageThumbnailGenerator, xmp_inmemory_utils::XmpInfo,
+    ValidatedAsset,
+};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, error};
 
 #[cfg(feature = "file_io")]
 use std::path::Path;
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 /// An `Ingredient` is any external asset that has been used in the creation of an image.
 pub struct Ingredient {
     /// A human-readable title, generally source filename.
@@ -57,8 +64,13 @@ pub struct Ingredient {
     ///
     /// A tuple of thumbnail MIME format (i.e. `image/jpeg`) and binary bits of the image.
     #[serde(skip_serializing)]
+    #[cfg_attr(feature = "json_schema", schemars(skip))]
     thumbnail: Option<(String, BytesT)>,
 
+    /// A reference to a thumbnail stored outside the manifest store.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thumbnail_ref: Option<HashedUri>,
+
     /// An optional hash of the asset to prevent duplicates.
     #[serde(skip_serializing_if = "Option::is_none")]
     hash: Option<String>,
@@ -122,6 +134,7 @@ impl Ingredient {
             instance_id: instance_id.into(),
             provenance: None,
             thumbnail: None,
+            thumbnail_ref: None,
             hash: None,
             is_parent: None,
             validation_status: None,
@@ -163,6 +176,11 @@ impl Ingredient {
             .map(|(format, image)| (format.as_str(), image.deref()))
     }
 
+    /// Returns the reference to a thumbnail stored outside the manifest store, if any.
+    pub fn thumbnail_ref(&self) -> Option<&HashedUri> {
+        self.thumbnail_ref.as_ref()
+    }
+
     /// Returns an optional Blake3 hash made from the bits of the original image.
     pub fn hash(&self) -> Option<&str> {
         self.hash.as_deref()
@@ -240,6 +258,25 @@ impl Ingredient {
         self
     }
 
+    /// Sets a reference to a thumbnail stored outside the manifest store, such as on a
+    /// web server, instead of embedding the `thumbnail` image.
+    ///
+    /// The reference records a SHA-256 hash of `thumbnail`, so a reader can check the
+    /// image it fetches from `url` with [`Ingredient::verify_thumbnail_ref`].
+    pub fn set_thumbnail_ref<S: Into<String>>(&mut self, url: S, thumbnail: &[u8]) -> &mut Self {
+        let hash = hash_by_alg("sha256", thumbnail, None);
+        self.thumbnail_ref = Some(HashedUri::new(url.into(), Some("sha256".to_owned()), &hash));
+        self
+    }
+
+    /// Returns `true` if `thumbnail` matches the hash of the thumbnail reference.
+    pub fn verify_thumbnail_ref(&self, thumbnail: &[u8]) -> bool {
+        self.thumbnail_ref.as_ref().map_or(false, |hashed_uri| {
+            let alg = hashed_uri.alg().unwrap_or_else(|| "sha256".to_owned());
+            verify_by_alg(&alg, &hashed_uri.hash(), thumbnail, None)
+        })
+    }
+
     /// Sets the hash value generated from the entire asset.
     pub fn set_hash<S: Into<String>>(&mut self, hash: S) -> &mut Self {
         self.hash = Some(hash.into());
@@ -266,7 +303,7 @@ impl Ingredient {
 
     /// Gathers filename, extension, and format from a file path.
     #[cfg(feature = "file_io")]
-    fn get_path_info(path: &std::path::Path) -> (String, String, String) {
+    pub(crate) fn get_path_info(path: &std::path::Path) -> (String, String, String) {
         let title = path
             .file_name()
             .map(|name| name.to_string_lossy().into_owned())
@@ -278,7 +315,17 @@ impl Ingredient {
             .unwrap_or_else(|| "".into())
             .to_lowercase();
 
-        let format = match extension.as_ref() {
+        let format = Self::format_from_extension(&extension);
+        (title, extension, format)
+    }
+
+    // returns the MIME type for a file extension, MIME types are returned unchanged
+    #[cfg(feature = "file_io")]
+    pub(crate) fn format_from_extension(extension: &str) -> String {
+        if extension.contains('/') {
+            return extension.to_owned();
+        }
+        match extension.to_lowercase().as_ref() {
             "jpg" | "jpeg" => "image/jpeg",
             "png" => "image/png",
             "gif" => "image/gif",
@@ -288,10 +335,25 @@ impl Ingredient {
             "ico" => "image/vnd.microsoft.icon",
             "bmp" => "image/bmp",
             "webp" => "image/webp",
+            "zip" => "application/zip",
+            "epub" => "application/epub+zip",
+            "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+            "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            "eml" => "message/rfc822",
+            "ttf" => "font/ttf",
+            "otf" => "font/otf",
             _ => "application/octet-stream",
         }
-        .to_owned();
-        (title, extension, format)
+        .to_owned()
+    }
+
+    #[cfg(feature = "file_io")]
+    fn make_id(id_type: &str) -> String {
+        use uuid::Uuid;
+        let uuid = Uuid::new_v4();
+        //warn!("Generating fake id {}", uuid);
+        format!("xmp:{}id:{}", id_type, uuid)
     }
 
     /// Generates an `Ingredient` from a file path, including XMP info
@@ -302,13 +364,6 @@ impl Ingredient {
     /// [`ManifestStore`]: crate::ManifestStore
     #[cfg(feature = "file_io")]
     pub fn from_file_info<P: AsRef<Path>>(path: P) -> Self {
-        fn make_id(id_type: &str) -> String {
-            use uuid::Uuid;
-            let uuid = Uuid::new_v4();
-            //warn!("Generating fake id {}", uuid);
-            format!("xmp:{}id:{}", id_type, uuid)
-        }
-
         // get required information from the file path
         let (title, _, format) = Self::get_path_info(path.as_ref());
 
@@ -318,10 +373,15 @@ impl Ingredient {
             Err(_) => XmpInfo::default(),
         };
 
+        Self::from_xmp_info(&title, &format, xmp_info)
+    }
+
+    #[cfg(feature = "file_io")]
+    fn from_xmp_info(title: &str, format: &str, xmp_info: XmpInfo) -> Self {
         // instance id is required so generate one if we don't have one
-        let instance_id = xmp_info.instance_id.unwrap_or_else(|| make_id("i"));
+        let instance_id = xmp_info.instance_id.unwrap_or_else(|| Self::make_id("i"));
 
-        let mut ingredient = Self::new(&title, &format, &instance_id);
+        let mut ingredient = Self::new(title, format, &instance_id);
         ingredient.document_id = xmp_info.document_id; // use document id if one exists
         ingredient.provenance = xmp_info.provenance;
 
@@ -357,10 +417,6 @@ impl Ingredient {
     // Internal implementation to avoid code bloat.
     #[cfg(feature = "file_io")]
     fn from_file_impl(path: &Path, options: &IngredientOptions) -> Result<Self> {
-        // these are declared inside this function in order to isolate them for wasm builds
-        use crate::jumbf_io;
-        use crate::status_tracker::{DetailedStatusTracker, StatusTracker};
-
         #[cfg(feature = "diagnostics")]
         let _t = crate::utils::time_it::TimeIt::new("Ingredient:from_file_with_options");
 
@@ -368,87 +424,107 @@ impl Ingredient {
         debug!("ingredient {:?}", path);
 
         // get required information from the file path
-        let mut ingredient = Self::from_file_info(path);
+        let (title, _, format) = Self::get_path_info(path);
 
         if !path.exists() {
-            return Err(Error::FileNotFound(ingredient.title));
+            return Err(Error::FileNotFound(title));
         }
 
+        // read the file into a buffer for processing
+        let buf = std::fs::read(path).map_err(wrap_io_err)?;
+
+        let asset = ValidatedAsset::new(&title, &format, buf, options.make_hash);
+        Ok(Self::from_validated_asset(&asset, options))
+    }
+
+    /// Creates an `Ingredient` from a stream of an asset.
+    ///
+    /// `format` is the MIME type or file extension of the asset.
+    /// The title is taken from the options, or is `untitled` if none is given.
+    #[cfg(feature = "file_io")]
+    pub fn from_stream(format: &str, stream: &mut dyn std::io::Read) -> Result<Self> {
+        Self::from_stream_with_options(format, stream, &IngredientOptions::default())
+    }
+
+    /// Creates an `Ingredient` from a stream of an asset and options.
+    ///
+    /// This extracts the active manifest, validation status and XMP info of the asset
+    /// and generates a thumbnail if the manifest does not provide one.
+    #[cfg(feature = "file_io")]
+    pub fn from_stream_with_options(
+        format: &str,
+        stream: &mut dyn std::io::Read,
+        options: &IngredientOptions,
+    ) -> Result<Self> {
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).map_err(wrap_io_err)?;
+
+        let asset = ValidatedAsset::new("untitled", format, buf, options.make_hash);
+        Ok(Self::from_validated_asset(&asset, options))
+    }
+
+    /// Creates an `Ingredient` from an asset that has already been validated.
+    ///
+    /// The hash, manifest store and validation status of the asset are reused
+    /// rather than computed again.
+    #[cfg(feature = "file_io")]
+    pub fn from_validated_asset(asset: &ValidatedAsset, options: &IngredientOptions) -> Self {
+        let mut ingredient =
+            Self::from_xmp_info(asset.title(), asset.format(), asset.xmp_info().clone());
+
         // if options includes a title, use it
         if let Some(opt_title) = options.title {
             ingredient.title = opt_title.to_string();
         }
-        // read the file into a buffer for processing
-        let buf = std::fs::read(path).map_err(wrap_io_err)?;
 
-        // generate a hash so we know if the file has changed
+        // use the hash so we know if the file has changed, unless the asset was not hashed
         // todo:: make hash algorithm an option fn taking stream
-        ingredient.hash = options
-            .make_hash
-            .then(|| blake3::hash(&buf).to_hex().as_str().to_owned());
-
-        let mut report = DetailedStatusTracker::new();
-
-        // generate a store from the buffer and then validate from the asset path
-        // load and verify store in single call - no need to call low level jumbf_io functions
-        match Store::load_from_memory(&ingredient.format, &buf, true, &mut report) {
-            Ok(store) => {
-                // generate ValidationStatus from ValidationItems filtering for only errors
-                let statuses = status_for_store(&store, &mut report);
-
-                if let Some(claim) = store.provenance_claim() {
-                    // if the parent claim is valid and has a thumbnail, use it
-                    if statuses.is_empty() {
-                        //todo: need a better test here
-                        if let Some(claim_assertion) =
-                            claim.get_claim_assertion(labels::JPEG_CLAIM_THUMBNAIL, 0)
-                        {
-                            let (format, image) =
-                                Self::thumbnail_from_assertion(claim_assertion.assertion());
-                            ingredient.set_thumbnail(format, image);
-                        }
+        ingredient.hash = match options.make_hash {
+            true => Some(asset.hash().map_or_else(
+                || blake3::hash(asset.data()).to_hex().as_str().to_owned(),
+                |hash| hash.to_owned(),
+            )),
+            false => None,
+        };
+
+        ingredient.validation_status = asset.validation_status().map(|s| s.to_vec());
+
+        if let Some(store) = asset.store() {
+            if let Some(claim) = store.provenance_claim() {
+                // if the parent claim is valid and has a thumbnail, use it
+                if ingredient.validation_status.is_none() {
+                    //todo: need a better test here
+                    if let Some(claim_assertion) =
+                        claim.get_claim_assertion(labels::JPEG_CLAIM_THUMBNAIL, 0)
+                    {
+                        let (format, image) =
+                            Self::thumbnail_from_assertion(claim_assertion.assertion());
+                        ingredient.set_thumbnail(format, image);
                     }
-                    ingredient.active_manifest = Some(claim.label().to_string());
                 }
-                ingredient.manifest_data =
-                    jumbf_io::load_jumbf_from_memory(&ingredient.format, &buf).ok();
-                ingredient.validation_status = if statuses.is_empty() {
-                    None
-                } else {
-                    Some(statuses)
-                };
-            }
-            Err(Error::JumbfNotFound)
-            | Err(Error::ProvenanceMissing)
-            | Err(Error::UnsupportedType) => {} // no claims but valid file
-            Err(Error::BadParam(desc)) if desc == *"unrecognized file type" => {}
-            Err(e) => {
-                // we can ignore the error here because it should have a log entry corresponding to it
-                debug!("ingredient {:?}", e);
-                // convert any other error to a validation status
-                let statuses: Vec<ValidationStatus> = report
-                    .get_log()
-                    .iter()
-                    .filter_map(ValidationStatus::from_validation_item)
-                    .filter(|s| !validation_status::is_success(s.code()))
-                    .collect();
-                ingredient.validation_status = if statuses.is_empty() {
-                    None
-                } else {
-                    Some(statuses)
-                };
+                ingredient.active_manifest = Some(claim.label().to_string());
             }
+            ingredient.manifest_data = asset.manifest_data().map(|data| data.to_vec());
         }
 
         // create a thumbnail if we don't already have a claim with a thumb we can use
         if ingredient.thumbnail.is_none() {
-            use crate::utils::thumbnail::make_thumbnail;
-            if let Ok((format, image)) = make_thumbnail(path) {
-                ingredient.set_thumbnail(format, image);
+            let buf = asset.data();
+            let generated = match options.thumbnail_generator.as_ref() {
+                Some(generator) => generator.generate(&ingredient.format, buf),
+                None => ImageThumbnailGenerator::new(options.thumbnail.clone())
+                    .generate(&ingredient.format, buf),
+            };
+            match generated {
+                Ok(Some((format, image))) => {
+                    ingredient.set_thumbnail(format, image);
+                }
+                Ok(None) => {}
+                Err(e) => debug!("ingredient thumbnail {:?}", e),
             }
         }
 
-        Ok(ingredient)
+        ingredient
     }
 
     /// Creates an Ingredient from a store and a URI to an ingredient assertion.
@@ -471,18 +547,34 @@ impl Ingredient {
             Relationship::ComponentOf => None,
         };
 
-        let active_manifest = ingredient_assertion
-            .c2pa_manifest
-            .and_then(|hash_url| jumbf::labels::manifest_label_from_uri(&hash_url.url()));
+        // the label the manifest is stored under may differ from the URI if it collided
+        let active_manifest = ingredient_assertion.c2pa_manifest.and_then(|hash_url| {
+            match store.resolve_ingredient(&hash_url) {
+                Some(claim) => Some(claim.label().to_owned()),
+                None => jumbf::labels::manifest_label_from_uri(&hash_url.url()),
+            }
+        });
 
-        let thumbnail = ingredient_assertion.thumbnail.and_then(|hashed_uri| {
-            // if we have a relative thumbnail pass in URI and Claim to search
-            match store.get_assertion_from_uri_and_claim(&hashed_uri.url(), ingredient_uri) {
+        // thumbnails outside the manifest store are kept as references
+        let (thumbnail_ref, thumbnail_uri) = match ingredient_assertion.thumbnail {
+            Some(hashed_uri) if !jumbf::labels::is_jumbf_uri(&hashed_uri.url()) => {
+                (Some(hashed_uri), None)
+            }
+            thumbnail_uri => (None, thumbnail_uri),
+        };
+
+        let thumbnail = thumbnail_uri.and_then(|hashed_uri| {
+            // absolute URIs can reference a thumbnail in another manifest, such as the parent's
+            let assertion = match hashed_uri.is_relative_url() {
+                true => store.get_assertion_from_uri_and_claim(&hashed_uri.url(), ingredient_uri),
+                false => store.get_assertion_from_uri(&hashed_uri.url()),
+            };
+            match assertion {
                 Some(assertion) => Some(Self::thumbnail_from_assertion(assertion)),
                 None => {
                     error!("failed to get {} from {}", hashed_uri.url(), ingredient_uri);
                     validation_status.push(
-                        ValidationStatus::new(validation_status::ASSERTION_MISSING.to_string())
+                        ValidationStatus::new(ValidationStatusCode::AssertionMissing)
                             .set_url(hashed_uri.url()),
                     );
                     None
@@ -505,6 +597,7 @@ impl Ingredient {
         if let Some((format, image)) = thumbnail {
             ingredient.set_thumbnail(format, image);
         }
+        ingredient.thumbnail_ref = thumbnail_ref;
 
         ingredient.is_parent = is_parent;
         ingredient.active_manifest = active_manifest;
@@ -563,20 +656,27 @@ impl Ingredient {
                                         .find(|hashed_uri| {
                                             hashed_uri.url().contains(labels::CLAIM_THUMBNAIL)
                                         })
-                                        .map(|t| {
-                                            // convert ingredient uris to absolute when adding them
-                                            // since this uri references a different manifest
-                                            let assertion_label =
-                                                jumbf::labels::assertion_label_from_uri(&t.url())
-                                                    .unwrap_or_default();
-                                            let url = jumbf::labels::to_assertion_uri(
-                                                &manifest_label,
-                                                &assertion_label,
-                                            );
-                                            HashedUri::new(url, t.alg(), &t.hash())
-                                        });
+                                        .cloned();
                                 }
                             }
+                            // otherwise reference a thumbnail of the manifest with the same image
+                            if thumbnail.is_none() {
+                                thumbnail = self.thumbnail().and_then(|(_, image)| {
+                                    ingredient_active_claim.find_thumbnail(image)
+                                });
+                            }
+                            thumbnail = thumbnail.map(|t| {
+                                // convert ingredient uris to absolute when adding them
+                                // since this uri references a different manifest
+                                let assertion_label =
+                                    jumbf::labels::assertion_label_from_uri(&t.url())
+                                        .unwrap_or_default();
+                                let url = jumbf::labels::to_assertion_uri(
+                                    &manifest_label,
+                                    &assertion_label,
+                                );
+                                HashedUri::new(url, t.alg(), &t.hash())
+                            });
                             // generate c2pa_manifest hashed_uri
                             Some(crate::hashed_uri::HashedUri::new(
                                 uri,
@@ -599,13 +699,22 @@ impl Ingredient {
             Relationship::ComponentOf
         };
 
+        // a thumbnail stored outside the manifest store is only referenced
+        if thumbnail.is_none() {
+            thumbnail = self.thumbnail_ref.clone();
+        }
+
         // add ingredient thumbnail assertion if one is given and we don't already have one from the parent claim
         if thumbnail.is_none() {
             if let Some((format, image)) = &self.thumbnail() {
-                let hash_url = claim.add_assertion(&Thumbnail::new(
-                    &labels::add_thumbnail_format(labels::INGREDIENT_THUMBNAIL, format),
-                    image.to_vec(),
-                ))?;
+                // reference an identical thumbnail already in the claim, such as the claim thumbnail
+                let hash_url = match claim.find_thumbnail(image) {
+                    Some(hash_url) => hash_url,
+                    None => claim.add_assertion(&Thumbnail::new(
+                        &labels::add_thumbnail_format(labels::INGREDIENT_THUMBNAIL, format),
+                        image.to_vec(),
+                    ))?,
+                };
 
                 thumbnail = Some(hash_url);
             }
@@ -643,6 +752,12 @@ pub struct IngredientOptions {
     /// If `true`, then generate a Blake3 hash over the source asset and store it in the ingredient.
     /// This can be used to test for duplicate ingredients or if a source file has changed.
     pub make_hash: bool,
+
+    /// Controls the thumbnail generated when the asset's manifest does not provide one.
+    pub thumbnail: ThumbnailOptions,
+
+    /// Generates the thumbnail instead of the default generator, ignoring `thumbnail`.
+    pub thumbnail_generator: Option<Arc<dyn ThumbnailGenerator>>,
 }
 
 #[cfg(test)]
@@ -701,6 +816,50 @@ mod tests {
         assert_eq!(ingredient.manifest_data(), Some("data".as_bytes()));
     }
 
+    #[test]
+    fn test_thumbnail_dedup() {
+        let mut claim = Claim::new("test", None);
+        let image = b"thumbnail".to_vec();
+
+        let mut ingredient = Ingredient::new("a.jpg", "image/jpeg", "xmp.iid:a");
+        ingredient.set_thumbnail("image/jpeg", image.clone());
+        ingredient.add_to_claim(&mut claim, None).unwrap();
+        let mut ingredient = Ingredient::new("b.jpg", "image/jpeg", "xmp.iid:b");
+        ingredient.set_thumbnail("image/jpeg", image.clone());
+        ingredient.add_to_claim(&mut claim, None).unwrap();
+
+        // identical thumbnails are only stored once
+        let thumbnails = claim
+            .assertions()
+            .iter()
+            .filter(|hashed_uri| hashed_uri.url().contains(labels::INGREDIENT_THUMBNAIL))
+            .count();
+        assert_eq!(thumbnails, 1);
+        assert!(claim.find_thumbnail(&image).is_some());
+        assert!(claim.find_thumbnail(b"other").is_none());
+    }
+
+    #[test]
+    fn test_thumbnail_ref() {
+        let mut claim = Claim::new("test", None);
+        let image = b"thumbnail";
+
+        let mut ingredient = Ingredient::new("a.jpg", "image/jpeg", "xmp.iid:a");
+        ingredient.set_thumbnail_ref("https://example.com/thumbnail.jpg", image);
+        assert!(ingredient.verify_thumbnail_ref(image));
+        assert!(!ingredient.verify_thumbnail_ref(b"other"));
+        ingredient.add_to_claim(&mut claim, None).unwrap();
+
+        // the external thumbnail is referenced, not stored
+        assert!(claim.find_thumbnail(image).is_none());
+        let ingredient_assertion: assertions::Ingredient =
+            assertions::Ingredient::from_assertion(&claim.ingredient_assertions()[0]).unwrap();
+        assert_eq!(
+            ingredient_assertion.thumbnail.map(|t| t.url()),
+            Some("https://example.com/thumbnail.jpg".to_owned())
+        );
+    }
+
     #[test]
     fn test_psd() {
         // std::env::set_var("RUST_LOG", "debug");
@@ -736,6 +895,7 @@ mod tests {
         let options = IngredientOptions {
             make_hash: true,
             title: Some("MyTitle"),
+            ..Default::default()
         };
 
         let ap = fixture_path(MANIFEST_JPEG);
@@ -752,6 +912,37 @@ mod tests {
         assert!(ingredient.metadata.is_none());
     }
 
+    #[test]
+    fn test_from_stream() {
+        use image::GenericImageView;
+
+        let mut options = IngredientOptions {
+            title: Some("streamed"),
+            ..Default::default()
+        };
+        options.thumbnail.longest_edge = 64;
+        options.thumbnail.format = crate::ThumbnailFormat::Png;
+
+        let mut file = std::fs::File::open(fixture_path(MANIFEST_JPEG)).unwrap();
+        let ingredient = Ingredient::from_stream_with_options("jpg", &mut file, &options).unwrap();
+        stats(&ingredient);
+
+        assert_eq!(ingredient.title(), "streamed");
+        assert_eq!(ingredient.format(), "image/jpeg");
+        assert!(ingredient.active_manifest().is_some());
+        assert!(ingredient.manifest_data().is_some());
+        assert!(ingredient.provenance().is_some());
+
+        let mut file = std::fs::File::open(fixture_path("libpng-test.png")).unwrap();
+        let ingredient =
+            Ingredient::from_stream_with_options("image/png", &mut file, &options).unwrap();
+        assert!(ingredient.manifest_data().is_none());
+        let (format, image) = ingredient.thumbnail().unwrap();
+        assert_eq!(format, "image/png");
+        let thumbnail = image::load_from_memory(image).unwrap();
+        assert!(thumbnail.width() <= 64 && thumbnail.height() <= 64);
+    }
+
     #[test]
     fn test_png_no_claim() {
         let ap = fixture_path("libpng-test.png");
@@ -783,7 +974,7 @@ mod tests {
             .validation_status
             .unwrap()
             .iter()
-            .any(|s| s.code() == validation_status::CLAIM_SIGNATURE_MISMATCH));
+            .any(|s| s.code() == &ValidationStatusCode::ClaimSignatureMismatch));
     }
 
     #[test]
@@ -801,7 +992,7 @@ mod tests {
         assert!(ingredient.validation_status.is_some());
         assert_eq!(
             ingredient.validation_status.unwrap()[0].code(),
-            validation_status::STATUS_PRERELEASE
+            &ValidationStatusCode::Prerelease
         );
     }
 
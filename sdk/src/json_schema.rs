@@ -0,0 +1,86 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! JSON Schemas of the JSON formats the crate reads and writes.
+//!
+//! Downstream consumers can validate the output of the crate against these schemas
+//! or generate client types from them. The schemas are generated from the Rust types,
+//! so they always match the formats of this version of the crate.
+//!
+//! ```
+//! let schema = c2pa::json_schema::manifest_store_schema();
+//! let json = serde_json::to_string_pretty(&schema).unwrap();
+//! assert!(json.contains("active_manifest"));
+//! ```
+
+pub use schemars::schema::RootSchema;
+use schemars::schema_for;
+
+use crate::{Ingredient, ManifestDefinition, ManifestStore, ValidationResults};
+
+/// The schema of the manifest store JSON, such as the output of [`ManifestStore`]'s `Display`
+pub fn manifest_store_schema() -> RootSchema {
+    schema_for!(ManifestStore)
+}
+
+/// The schema of the JSON of an [`Ingredient`]
+pub fn ingredient_schema() -> RootSchema {
+    schema_for!(Ingredient)
+}
+
+/// The schema of the validation report JSON of [`ValidationResults`]
+pub fn validation_results_schema() -> RootSchema {
+    schema_for!(ValidationResults)
+}
+
+/// The schema of the JSON [`ManifestDefinition`]s are read from
+pub fn manifest_definition_schema() -> RootSchema {
+    schema_for!(ManifestDefinition)
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    // the names of the properties of the root object of a schema
+    fn properties(schema: &RootSchema) -> Vec<String> {
+        schema
+            .schema
+            .object
+            .as_ref()
+            .map(|object| object.properties.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_json_schemas() {
+        let store = manifest_store_schema();
+        assert!(properties(&store).contains(&"manifests".to_string()));
+        assert!(store.definitions.contains_key("Manifest"));
+        assert!(store.definitions.contains_key("ValidationStatus"));
+
+        // binary and internal fields are not part of the JSON
+        let ingredient = properties(&ingredient_schema());
+        assert!(ingredient.contains(&"title".to_string()));
+        assert!(!ingredient.contains(&"thumbnail".to_string()));
+
+        let results = validation_results_schema();
+        assert!(properties(&results).contains(&"active_manifest".to_string()));
+        assert!(results.definitions.contains_key("StatusCodes"));
+
+        let definition = properties(&manifest_definition_schema());
+        assert!(definition.contains(&"claim_generator".to_string()));
+    }
+}
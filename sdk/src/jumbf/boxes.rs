@@ -31,10 +31,14 @@ use std::{
 };
 
 use hex::FromHex;
-use log::debug;
 use thiserror::Error;
+use tracing::debug;
 
-use crate::jumbf::{boxio, labels};
+use crate::{
+    assertions::labels::THUMBNAIL,
+    jumbf::{boxio, labels},
+    ResourceLimits,
+};
 
 /// `JumbfParseError` enumerates errors detected while parsing JUMBF data structures.
 #[derive(Debug, Error)]
@@ -77,6 +81,9 @@ pub enum JumbfParseError {
     #[error("invalid embedded file box")]
     InvalidEmbeddedFileBox,
 
+    #[error("invalid Brotli compressed box")]
+    InvalidBrobBox,
+
     #[error("invalid box of unknown type")]
     InvalidUnknownBox,
 
@@ -91,6 +98,9 @@ pub enum JumbfParseError {
 
     #[error("invalid JUMD box")]
     InvalidDescriptionBox,
+
+    #[error("resource limit exceeded: {0}")]
+    ResourceLimitExceeded(String),
 }
 
 /// A specialized `JumbfParseResult` type for JUMBF parsing operations.
@@ -341,6 +351,16 @@ impl JUMBFSuperBox {
             .as_any()
             .downcast_ref::<JUMBFEmbeddedFileDescriptionBox>()
     }
+
+    pub fn data_box_as_brob_box(&self, index: usize) -> Option<&BrotliContentBox> {
+        let da_box = &self.data_boxes[index];
+        da_box.as_ref().as_any().downcast_ref::<BrotliContentBox>()
+    }
+
+    // replace a data box, taking ownership of the new box
+    pub fn replace_data_box(&mut self, index: usize, b: Box<dyn BMFFBox>) {
+        self.data_boxes[index] = b
+    }
 }
 
 impl BMFFBox for JUMBFSuperBox {
@@ -575,6 +595,62 @@ impl JUMBFPaddingContentBox {
     }
 }
 
+// ANCHOR Brotli compressed box
+/// Brotli compressed box (ISO 18181-2:2021, Section 4), holding the payload of a box
+/// of `original_type` compressed with Brotli
+#[derive(Debug, Default)]
+pub struct BrotliContentBox {
+    original_type: [u8; 4],
+    data: Vec<u8>,
+}
+
+impl BMFFBox for BrotliContentBox {
+    fn box_type(&self) -> &'static [u8; 4] {
+        b"brob"
+    }
+
+    fn box_uuid(&self) -> &'static str {
+        "" // base JUMBF boxes don't have any...
+    }
+
+    fn box_payload_size(&self) -> IoResult<u32> {
+        let size = self.original_type.len() + self.data.len();
+        Ok(size as u32)
+    }
+
+    fn write_box_payload(&self, writer: &mut dyn Write) -> IoResult<()> {
+        write_all!(writer, &self.original_type);
+        if !self.data.is_empty() {
+            write_all!(writer, &self.data);
+        }
+        Ok(())
+    }
+
+    // Necessary method to enable conversion between types...
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl BrotliContentBox {
+    // new box with compressed data
+    pub fn new(original_type: &[u8; 4], data_in: Vec<u8>) -> Self {
+        BrotliContentBox {
+            original_type: *original_type,
+            data: data_in,
+        }
+    }
+
+    // getters
+    pub fn original_type(&self) -> &[u8; 4] {
+        &self.original_type
+    }
+
+    pub fn data(&self) -> &Vec<u8> {
+        &self.data
+    }
+}
+
 // ANCHOR JUMBF JSON Content box
 /// JUMBF JSON Content box (ISO 19566-5:2019, Annex B.4)
 #[derive(Debug, Default)]
@@ -777,6 +853,7 @@ impl JUMBFUUIDContentBox {
 pub const CAI_BLOCK_UUID: &str = "6332706100110010800000AA00389B71"; // c2pa
 pub const CAI_STORE_UUID: &str = "63326D6100110010800000AA00389B71"; // c2ma
 pub const CAI_UPDATE_MANIFEST_UUID: &str = "6332756D00110010800000AA00389B71"; // c2um
+pub const CAI_COMPRESSED_MANIFEST_UUID: &str = "6332636D00110010800000AA00389B71"; // c2cm
 pub const CAI_ASSERTION_STORE_UUID: &str = "6332617300110010800000AA00389B71"; // c2as
 pub const CAI_INGREDIENT_STORE_UUID: &str = "6361697300110010800000AA00389B71"; //cais
 pub const CAI_JSON_ASSERTION_UUID: &str = "6A736F6E00110010800000AA00389B71"; // json
@@ -789,6 +866,7 @@ pub const CAI_EMBEDDED_FILE_UUID: &str = "40CB0C32BB8A489DA70B2AD6F47F4369";
 pub const CAI_EMBEDDED_FILE_DESCRIPTION_UUID: &str = "6266646200110010800000AA00389B71"; // bfdb
 pub const CAI_EMBEDED_FILE_DATA_UUID: &str = "6269646200110010800000AA00389B71"; // bidb
 pub const CAI_VERIFIABLE_CREDENTIALS_STORE_UUID: &str = "6332766300110010800000AA00389B71"; //c2vc
+pub const CAI_DATABOX_STORE_UUID: &str = "6332646200110010800000AA00389B71"; // c2db
 pub const CAI_UUID_ASSERTION_UUID: &str = "7575696400110010800000AA00389B71"; // uuid
 
 // ANCHOR Salt Content Box
@@ -954,8 +1032,13 @@ impl BMFFBox for CAIClaimBox {
 
 impl CAIClaimBox {
     pub fn new() -> Self {
+        Self::new_with_label(labels::CLAIM)
+    }
+
+    // create a claim box with a versioned label such as `c2pa.claim.v2`
+    pub fn new_with_label(label: &str) -> Self {
         CAIClaimBox {
-            claim_box: JUMBFSuperBox::new(labels::CLAIM, Some(CAI_CLAIM_UUID)),
+            claim_box: JUMBFSuperBox::new(label, Some(CAI_CLAIM_UUID)),
         }
     }
 
@@ -1299,6 +1382,60 @@ impl Default for CAIVerifiableCredentialStore {
     }
 }
 
+// ANCHOR Databox Store
+/// Databox Store
+#[derive(Debug)]
+pub struct CAIDataboxStore {
+    store: JUMBFSuperBox,
+}
+
+impl BMFFBox for CAIDataboxStore {
+    fn box_type(&self) -> &'static [u8; 4] {
+        b"    "
+    }
+
+    fn box_uuid(&self) -> &'static str {
+        CAI_DATABOX_STORE_UUID
+    }
+
+    fn box_payload_size(&self) -> IoResult<u32> {
+        let size = boxio::ByteCounter::calculate(|w| self.write_box_payload(w))?;
+        Ok(size as u32)
+    }
+
+    fn write_box_payload(&self, writer: &mut dyn Write) -> IoResult<()> {
+        self.store.write_box(writer)
+    }
+
+    // Necessary method to enable conversion between types...
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl CAIDataboxStore {
+    pub fn new() -> Self {
+        CAIDataboxStore {
+            store: JUMBFSuperBox::new(labels::DATABOXES, Some(CAI_DATABOX_STORE_UUID)),
+        }
+    }
+
+    pub fn from(in_box: JUMBFSuperBox) -> Self {
+        CAIDataboxStore { store: in_box }
+    }
+
+    // add a databox box *WITHOUT* taking ownership of the box
+    pub fn add_databox(&mut self, b: Box<dyn BMFFBox>) {
+        self.store.add_data_box(b)
+    }
+}
+
+impl Default for CAIDataboxStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 // ANCHOR CAI Store
 /// CAI Store
 #[derive(Debug)]
@@ -1708,6 +1845,27 @@ impl JUMBFEmbeddedFileDescriptionBox {
 const HEADER_SIZE: u64 = 8;
 const TOGGLE_SIZE: u64 = 1;
 
+// by default superboxes nested deeper than this are rejected instead of exhausting the stack
+pub(crate) const MAX_SUPER_BOX_DEPTH: usize = 64;
+
+// the size left in a box once `used` bytes are read, boxes are untrusted so a
+// size too small for what it must hold is an error rather than an underflow
+fn remaining_size(size: u64, used: u64) -> JumbfParseResult<u64> {
+    size.checked_sub(used)
+        .ok_or(JumbfParseError::InvalidBoxRange)
+}
+
+// read `len` bytes of box data, the buffer only grows with the data actually
+// read so a corrupt size cannot exhaust memory
+fn read_box_data<R: Read>(reader: &mut R, len: u64) -> JumbfParseResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader.by_ref().take(len).read_to_end(&mut buf)?;
+    if (buf.len() as u64) < len {
+        return Err(JumbfParseError::UnexpectedEof);
+    }
+    Ok(buf)
+}
+
 /// method for getting the current position
 pub fn current_pos<R: Seek>(seeker: &mut R) -> JumbfParseResult<u64> {
     Ok(seeker.seek(SeekFrom::Current(0))?)
@@ -1715,7 +1873,10 @@ pub fn current_pos<R: Seek>(seeker: &mut R) -> JumbfParseResult<u64> {
 
 /// method for seeking back to the start of the box (header)
 pub fn box_start<R: Seek>(seeker: &mut R) -> JumbfParseResult<u64> {
-    Ok(current_pos(seeker).map_err(|_| JumbfParseError::InvalidBoxStart)? - HEADER_SIZE)
+    current_pos(seeker)
+        .map_err(|_| JumbfParseError::InvalidBoxStart)?
+        .checked_sub(HEADER_SIZE)
+        .ok_or(JumbfParseError::InvalidBoxStart)
 }
 
 /// method for skipping over `size` bytes
@@ -1733,7 +1894,10 @@ pub fn skip_bytes_to<S: Seek>(seeker: &mut S, pos: u64) -> JumbfParseResult<()>
 // method to skip over an entire box
 pub fn skip_box<S: Seek>(seeker: &mut S, size: u64) -> JumbfParseResult<()> {
     let start = box_start(seeker)?;
-    skip_bytes_to(seeker, start + size)?;
+    let end = start
+        .checked_add(size)
+        .ok_or(JumbfParseError::InvalidBoxRange)?;
+    skip_bytes_to(seeker, end)?;
     Ok(())
 }
 
@@ -1776,7 +1940,8 @@ boxtype! {
     Jp2c => 0x6A70_3263,
     Cbor => 0x6362_6F72,
     EmbedMediaDesc => 0x6266_6462,
-    EmbedContent => 0x6269_6462
+    EmbedContent => 0x6269_6462,
+    Brob => 0x6272_6F62
 }
 
 // ANCHOR BlockHeader
@@ -1847,11 +2012,11 @@ impl BoxReader {
             // end of file!
             return Ok(JUMBFDescriptionBox::new("", None));
         }
-        bytes_left -= bytes_read as u64;
+        bytes_left = remaining_size(bytes_left, bytes_read as u64)?;
 
         let mut togs = [0u8]; // 1 byte of toggles
         reader.read_exact(&mut togs)?;
-        bytes_left -= 1;
+        bytes_left = remaining_size(bytes_left, TOGGLE_SIZE)?;
 
         if togs[0] & 0x03 == 0x03 {
             // must be requestable and labeled
@@ -1860,7 +2025,7 @@ impl BoxReader {
             loop {
                 let mut buf = [0; 1];
                 reader.read_exact(&mut buf)?;
-                bytes_left -= 1;
+                bytes_left = remaining_size(bytes_left, 1)?;
                 if buf[0] == 0x00 {
                     break;
                 } else {
@@ -1872,7 +2037,7 @@ impl BoxReader {
             let sig = if togs[0] & 0x08 == 0x08 {
                 let mut sigbuf: [u8; 32] = [0; 32];
                 reader.read_exact(&mut sigbuf)?;
-                bytes_left -= 32;
+                bytes_left = remaining_size(bytes_left, 32)?;
                 Some(sigbuf)
             } else {
                 None
@@ -1885,17 +2050,16 @@ impl BoxReader {
                 if header.size == 0 {
                     // bad read,
                     return Err(JumbfParseError::InvalidBoxHeader);
-                } else if header.size != bytes_left - HEADER_SIZE {
+                } else if Some(header.size) != bytes_left.checked_sub(HEADER_SIZE) {
                     // this means that we started w/o the header...
                     unread_bytes(reader, HEADER_SIZE)?;
                 }
 
                 if header.name == BoxType::SaltHash {
-                    let data_len = header.size - HEADER_SIZE;
-                    let mut buf = vec![0u8; data_len as usize];
-                    reader.read_exact(&mut buf)?;
+                    let data_len = remaining_size(header.size, HEADER_SIZE)?;
+                    let buf = read_box_data(reader, data_len)?;
 
-                    bytes_left -= header.size;
+                    bytes_left = remaining_size(bytes_left, header.size)?;
 
                     Some(CAISaltContentBox::new(buf))
                 } else {
@@ -1931,9 +2095,8 @@ impl BoxReader {
             unread_bytes(reader, HEADER_SIZE)?;
         }
 
-        let json_len = size - HEADER_SIZE;
-        let mut buf = vec![0u8; json_len as usize];
-        reader.read_exact(&mut buf)?;
+        let json_len = remaining_size(size, HEADER_SIZE)?;
+        let buf = read_box_data(reader, json_len)?;
 
         Ok(JUMBFJSONContentBox::new(buf))
     }
@@ -1952,9 +2115,8 @@ impl BoxReader {
             unread_bytes(reader, HEADER_SIZE)?;
         }
 
-        let cbor_len = size - HEADER_SIZE;
-        let mut buf = vec![0u8; cbor_len as usize];
-        reader.read_exact(&mut buf)?;
+        let cbor_len = remaining_size(size, HEADER_SIZE)?;
+        let buf = read_box_data(reader, cbor_len)?;
 
         Ok(JUMBFCBORContentBox::new(buf))
     }
@@ -1973,13 +2135,37 @@ impl BoxReader {
             unread_bytes(reader, HEADER_SIZE)?;
         }
 
-        let padding_len = size - HEADER_SIZE;
-        let mut buf = vec![0u8; padding_len as usize];
-        reader.read_exact(&mut buf)?;
+        let padding_len = remaining_size(size, HEADER_SIZE)?;
+        let buf = read_box_data(reader, padding_len)?;
 
         Ok(JUMBFPaddingContentBox::new_with_vec(buf))
     }
 
+    pub fn read_brob_box<R: Read + Seek>(
+        reader: &mut R,
+        size: u64,
+    ) -> JumbfParseResult<BrotliContentBox> {
+        let header =
+            BoxReader::read_header(reader).map_err(|_| JumbfParseError::InvalidBoxHeader)?;
+        if header.size == 0 {
+            // bad read, return empty box...
+            return Ok(BrotliContentBox::default());
+        } else if header.size != size {
+            // this means that we started w/o the header...
+            unread_bytes(reader, HEADER_SIZE)?;
+        }
+
+        // the type of the compressed box comes first
+        let mut original_type = [0u8; 4];
+        reader.read_exact(&mut original_type)?;
+
+        // and then the compressed data
+        let data_len = remaining_size(size, HEADER_SIZE + 4 /*type*/)?;
+        let buf = read_box_data(reader, data_len)?;
+
+        Ok(BrotliContentBox::new(&original_type, buf))
+    }
+
     pub fn read_jp2c_box<R: Read + Seek>(
         reader: &mut R,
         size: u64,
@@ -1995,9 +2181,8 @@ impl BoxReader {
         }
 
         // read the data itself...
-        let data_len = size - HEADER_SIZE;
-        let mut buf = vec![0u8; data_len as usize];
-        reader.read_exact(&mut buf)?;
+        let data_len = remaining_size(size, HEADER_SIZE)?;
+        let buf = read_box_data(reader, data_len)?;
 
         Ok(JUMBFCodestreamContentBox::new(buf))
     }
@@ -2021,9 +2206,8 @@ impl BoxReader {
         reader.read_exact(&mut uuid)?;
 
         // and finally the data itself...
-        let data_len = size - HEADER_SIZE - 16 /*UUID*/;
-        let mut buf = vec![0u8; data_len as usize];
-        reader.read_exact(&mut buf)?;
+        let data_len = remaining_size(size, HEADER_SIZE + 16 /*UUID*/)?;
+        let buf = read_box_data(reader, data_len)?;
 
         Ok(JUMBFUUIDContentBox::new(&uuid, buf))
     }
@@ -2051,9 +2235,8 @@ impl BoxReader {
         reader.read_exact(&mut togs)?;
 
         // read the data itself...
-        let data_len = size - HEADER_SIZE - TOGGLE_SIZE;
-        let mut buf = vec![0u8; data_len as usize];
-        reader.read_exact(&mut buf)?;
+        let data_len = remaining_size(size, HEADER_SIZE + TOGGLE_SIZE)?;
+        let buf = read_box_data(reader, data_len)?;
 
         let (media_type, file_name) = match togs[0] {
             1 => {
@@ -2093,14 +2276,44 @@ impl BoxReader {
         }
 
         // read data itself...
-        let data_len = size - HEADER_SIZE;
-        let mut buf = vec![0u8; data_len as usize];
-        reader.read_exact(&mut buf)?;
+        let data_len = remaining_size(size, HEADER_SIZE)?;
+        let buf = read_box_data(reader, data_len)?;
 
         Ok(JUMBFEmbeddedFileContentBox::new(buf))
     }
 
     pub fn read_super_box<R: Read + Seek>(reader: &mut R) -> JumbfParseResult<JUMBFSuperBox> {
+        BoxReader::read_super_box_with_max_depth(reader, MAX_SUPER_BOX_DEPTH)
+    }
+
+    /// Read a superbox, rejecting superboxes nested `max_depth` or more levels deep
+    pub fn read_super_box_with_max_depth<R: Read + Seek>(
+        reader: &mut R,
+        max_depth: usize,
+    ) -> JumbfParseResult<JUMBFSuperBox> {
+        let mut limits = ResourceLimits::unlimited();
+        limits.set_max_nesting(max_depth);
+        BoxReader::read_super_box_with_limits(reader, &limits)
+    }
+
+    /// Read a manifest store superbox, failing as soon as it exceeds the nesting, assertion
+    /// count or thumbnail size limits, before the rest of it is read
+    pub fn read_super_box_with_limits<R: Read + Seek>(
+        reader: &mut R,
+        limits: &ResourceLimits,
+    ) -> JumbfParseResult<JUMBFSuperBox> {
+        BoxReader::read_super_box_at_depth(reader, 0, limits)
+    }
+
+    fn read_super_box_at_depth<R: Read + Seek>(
+        reader: &mut R,
+        depth: usize,
+        limits: &ResourceLimits,
+    ) -> JumbfParseResult<JUMBFSuperBox> {
+        if depth >= limits.max_nesting {
+            return Err(JumbfParseError::InvalidJumbBox);
+        }
+
         // find out where we're starting...
         let start_pos = current_pos(reader).map_err(|_| JumbfParseError::InvalidBoxRange)?;
 
@@ -2114,7 +2327,7 @@ impl BoxReader {
         }
 
         // figure out where this particular box ends...
-        let dest_pos = start_pos + jumb_header.size;
+        let dest_pos = start_pos.saturating_add(jumb_header.size);
 
         // now let's load the jumd
         let jumd_header =
@@ -2138,6 +2351,18 @@ impl BoxReader {
         );
         let mut sbox = JUMBFSuperBox::from(jdesc);
 
+        // the limits that depend on the box are checked before its content is read
+        let max_boxes = if box_label == labels::ASSERTIONS {
+            limits.max_assertions
+        } else {
+            usize::MAX
+        };
+        let max_content_size = if box_label.starts_with(THUMBNAIL) {
+            limits.max_thumbnail_size as u64
+        } else {
+            u64::MAX
+        };
+
         // read each following box and add it to the sbox
         let mut found = true;
         while found {
@@ -2146,11 +2371,30 @@ impl BoxReader {
             if box_header.name == BoxType::Empty {
                 found = false;
             } else {
+                if sbox.data_box_count() >= max_boxes {
+                    return Err(JumbfParseError::ResourceLimitExceeded(format!(
+                        "{} has more than the maximum of {} boxes",
+                        box_label, max_boxes
+                    )));
+                }
+                if box_header.name != BoxType::Jumb
+                    && box_header.size.saturating_sub(HEADER_SIZE) > max_content_size
+                {
+                    return Err(JumbfParseError::ResourceLimitExceeded(format!(
+                        "{} of {} bytes exceeds the maximum of {}",
+                        box_label, box_header.size, max_content_size
+                    )));
+                }
+
                 unread_bytes(reader, HEADER_SIZE)?; // seek back to the beginning of the box
                 let next_box: Box<dyn BMFFBox> = match box_header.name {
                     BoxType::Jumb => Box::new(
-                        BoxReader::read_super_box(reader)
-                            .map_err(|_| JumbfParseError::InvalidJumbBox)?,
+                        BoxReader::read_super_box_at_depth(reader, depth + 1, limits).map_err(
+                            |e| match e {
+                                JumbfParseError::ResourceLimitExceeded(_) => e,
+                                _ => JumbfParseError::InvalidJumbBox,
+                            },
+                        )?,
                     ),
                     BoxType::Json => Box::new(
                         BoxReader::read_json_box(reader, box_header.size)
@@ -2181,6 +2425,10 @@ impl BoxReader {
                         BoxReader::read_embedded_content_box(reader, box_header.size)
                             .map_err(|_| JumbfParseError::InvalidEmbeddedFileBox)?,
                     ),
+                    BoxType::Brob => Box::new(
+                        BoxReader::read_brob_box(reader, box_header.size)
+                            .map_err(|_| JumbfParseError::InvalidBrobBox)?,
+                    ),
                     _ => {
                         debug!("{}", format!("Unknown Boxtype: {:?}", box_header.name));
                         // per the jumbf spec ignore unknown boxes so skip by if possible
@@ -2195,9 +2443,8 @@ impl BoxReader {
                         }
 
                         // read data itself...
-                        let data_len = box_header.size - HEADER_SIZE;
-                        let mut buf = vec![0u8; data_len as usize];
-                        reader.read_exact(&mut buf)?;
+                        let data_len = remaining_size(box_header.size, HEADER_SIZE)?;
+                        read_box_data(reader, data_len)?;
                         continue;
                     }
                 };
@@ -2670,6 +2917,29 @@ pub mod tests {
         assert_eq!(json_box.json().len(), 30);
     }
 
+    // ANCHOR: Malformed Box Reader
+    #[test]
+    fn malformed_box_reader() {
+        const JSON_BOX: &str ="0000005a6a756d620000002d6a756d646a736f6e00110010800000aa00389b7103633270612e6c6f636174696f6e2e62726f616400000000266a736f6e7b20226c6f636174696f6e223a202253616e204672616e636973636f227d";
+
+        // a box smaller than its header, or larger than the data, is an error
+        for json_header in ["000000026a736f6e", "ffffffff6a736f6e"] {
+            let buffer = hex::decode(JSON_BOX.replace("000000266a736f6e", json_header)).unwrap();
+            assert!(BoxReader::read_super_box(&mut Cursor::new(buffer)).is_err());
+        }
+
+        // superboxes nested too deeply are rejected
+        let nested = "ffffffff6a756d62".to_owned()
+            + "0000001b6a756d64"
+            + "6332706100110010800000aa00389b71"
+            + "036100";
+        let buffer = hex::decode(nested.repeat(MAX_SUPER_BOX_DEPTH + 1)).unwrap();
+        assert!(matches!(
+            BoxReader::read_super_box(&mut Cursor::new(buffer)),
+            Err(JumbfParseError::InvalidJumbBox)
+        ));
+    }
+
     #[allow(dead_code)]
     fn check_one_box(
         parent_box: &JUMBFSuperBox,
@@ -35,6 +35,11 @@ pub const ASSERTIONS: &str = "c2pa.assertions";
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_c2pa_box_details>.
 pub const CLAIM: &str = "c2pa.claim";
 
+/// Label for the C2PA 2.x claim box.
+///
+/// See <https://c2pa.org/specifications/specifications/2.0/specs/C2PA_Specification.html#_c2pa_box_details>.
+pub const CLAIM_V2: &str = "c2pa.claim.v2";
+
 /// Label for the C2PA claim signature box.
 ///
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_c2pa_box_details>.
@@ -45,6 +50,16 @@ pub const SIGNATURE: &str = "c2pa.signature";
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_credential_storage>.
 pub const CREDENTIALS: &str = "c2pa.credentials";
 
+/// Label for the databox store box.
+///
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_data_boxes>.
+pub const DATABOXES: &str = "c2pa.databoxes";
+
+/// Label for a databox in the databox store.
+///
+/// See <https://c2pa.org/specifications/specifications/1.3/specs/C2PA_Specification.html#_data_boxes>.
+pub const DATABOX: &str = "c2pa.data";
+
 const JUMBF_PREFIX: &str = "self#jumbf";
 
 // Converts a manifest label to a JUMBF URI.
@@ -79,6 +94,21 @@ pub(crate) fn to_verifiable_credential_uri(manifest_label: &str, vc_id: &str) ->
     )
 }
 
+// Converts a manifest label and a databox label to a JUMBF URI.
+pub(crate) fn to_databox_uri(manifest_label: &str, databox_label: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        to_manifest_uri(manifest_label),
+        DATABOXES,
+        databox_label
+    )
+}
+
+// Converts a databox label to a JUMBF URI relative to its manifest.
+pub(crate) fn to_relative_databox_uri(databox_label: &str) -> String {
+    format!("{}={}/{}", JUMBF_PREFIX, DATABOXES, databox_label)
+}
+
 // Split off JUMBF prefix.
 pub(crate) fn to_normalized_uri(uri: &str) -> String {
     let uri_parts: Vec<&str> = uri.split('=').collect();
@@ -113,6 +143,11 @@ pub(crate) fn to_relative_uri(uri: &str) -> String {
     }
 }
 
+// Returns true if the URI references a box in the manifest store, rather than an external resource.
+pub(crate) fn is_jumbf_uri(uri: &str) -> bool {
+    uri.starts_with(JUMBF_PREFIX)
+}
+
 // Given a JUMBF URI, return the manifest label contained within it.
 pub(crate) fn manifest_label_from_uri(uri: &str) -> Option<String> {
     let raw_uri = to_normalized_uri(uri);
@@ -137,6 +172,19 @@ pub(crate) fn assertion_label_from_uri(uri: &str) -> Option<String> {
     }
 }
 
+// Extract a databox label from a JUMBF URI.
+pub(crate) fn databox_label_from_uri(uri: &str) -> Option<String> {
+    let raw_uri = to_normalized_uri(uri);
+    let parts: Vec<&str> = raw_uri.split('/').collect();
+    if parts.len() > 4 && parts[1] == MANIFEST_STORE && parts[3] == DATABOXES {
+        Some(parts[4].to_string())
+    } else if parts.len() > 1 && parts[0] == DATABOXES {
+        Some(parts[1].to_string())
+    } else {
+        None
+    }
+}
+
 // Extract the box the label points to.
 pub(crate) fn box_name_from_uri(uri: &str) -> Option<String> {
     let raw_uri = to_normalized_uri(uri);
@@ -183,6 +231,25 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_databox_uri() {
+        let uri = to_databox_uri("acme::urn:uuid::123:456:789", "c2pa.data__1");
+        assert_eq!(
+            uri,
+            "self#jumbf=/c2pa/acme::urn:uuid::123:456:789/c2pa.databoxes/c2pa.data__1"
+        );
+        assert_eq!(databox_label_from_uri(&uri).unwrap(), "c2pa.data__1");
+        assert_eq!(
+            to_relative_uri(&uri),
+            to_relative_databox_uri("c2pa.data__1")
+        );
+        assert_eq!(
+            databox_label_from_uri(&to_relative_databox_uri("c2pa.data__1")).unwrap(),
+            "c2pa.data__1"
+        );
+        assert!(databox_label_from_uri("self#jumbf=c2pa.assertions/c2pa.data").is_none());
+    }
+
     #[test]
     fn test_relative_uri() {
         assert_eq!(
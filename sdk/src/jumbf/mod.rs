@@ -14,3 +14,6 @@
 pub mod boxes;
 pub mod boxio;
 pub mod labels;
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+#[cfg_attr(not(feature = "file_io"), allow(dead_code))] // sizes are only counted when saving files
+pub mod writer;
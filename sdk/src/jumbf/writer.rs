@@ -0,0 +1,190 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Streaming JUMBF serializer
+//!
+//! Writes superboxes to a stream as their content is produced, so a manifest store
+//! does not have to be assembled in memory before it is written. The size of each
+//! superbox is written into its header once the superbox is ended.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Seek, SeekFrom, Write},
+};
+
+use byteorder::{BigEndian, WriteBytesExt};
+
+use crate::{
+    error::{Error, Result},
+    jumbf::boxes::{BMFFBox, JUMBFDescriptionBox},
+};
+
+/// Writes JUMBF superboxes incrementally to a stream.
+pub struct JumbfStreamWriter<'a, W: Write + Seek> {
+    writer: &'a mut W,
+    open: Vec<u64>, // stream positions of the headers of the open superboxes
+}
+
+impl<'a, W: Write + Seek> JumbfStreamWriter<'a, W> {
+    pub fn new(writer: &'a mut W) -> Self {
+        JumbfStreamWriter {
+            writer,
+            open: Vec::new(),
+        }
+    }
+
+    /// Starts a superbox, writing its header and description box.
+    /// Boxes written until it is ended are its data boxes.
+    pub fn start_superbox(&mut self, box_label: &str, a_type: Option<&str>) -> Result<()> {
+        let start = self.writer.stream_position()?;
+
+        // the size is not known yet
+        self.writer.write_u32::<BigEndian>(0)?;
+        self.writer.write_all(b"jumb")?;
+        JUMBFDescriptionBox::new(box_label, a_type).write_box(self.writer)?;
+
+        self.open.push(start);
+        Ok(())
+    }
+
+    /// Writes a complete box into the open superbox.
+    pub fn write_box(&mut self, b: &dyn BMFFBox) -> Result<()> {
+        b.write_box(self.writer)?;
+        Ok(())
+    }
+
+    /// Ends the innermost open superbox, writing its size into its header.
+    pub fn end_superbox(&mut self) -> Result<()> {
+        let start = self.open.pop().ok_or(Error::JumbfCreationError)?;
+        let end = self.writer.stream_position()?;
+        let size = u32::try_from(end - start).map_err(|_| Error::JumbfCreationError)?;
+
+        self.writer.seek(SeekFrom::Start(start))?;
+        self.writer.write_u32::<BigEndian>(size)?;
+        self.writer.seek(SeekFrom::Start(end))?;
+        Ok(())
+    }
+
+    /// Checks that every superbox has been ended and flushes the stream.
+    pub fn finish(self) -> Result<()> {
+        if !self.open.is_empty() {
+            return Err(Error::JumbfCreationError);
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Counts the bytes written to it without keeping them, so the size of a store is known
+/// without writing it to memory.
+#[derive(Default)]
+pub(crate) struct SizeCounter {
+    pos: u64,
+    size: u64,
+}
+
+impl SizeCounter {
+    /// Returns the number of bytes written
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Write for SizeCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pos += buf.len() as u64;
+        self.size = self.size.max(self.pos);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for SizeCounter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => offset_by(self.size, offset),
+            SeekFrom::Current(offset) => offset_by(self.pos, offset),
+        };
+        self.pos = new_pos
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid seek position"))?;
+        Ok(self.pos)
+    }
+}
+
+// `pos` moved by `offset`, if it stays in range
+fn offset_by(pos: u64, offset: i64) -> Option<u64> {
+    if offset < 0 {
+        pos.checked_sub(offset.unsigned_abs())
+    } else {
+        pos.checked_add(offset as u64)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::jumbf::boxes::{JUMBFCBORContentBox, JUMBFSuperBox, CAI_ASSERTION_STORE_UUID};
+
+    #[test]
+    fn test_stream_matches_boxes() {
+        // the boxes assembled in memory
+        let mut inner = JUMBFSuperBox::new("c2pa.assertions", Some(CAI_ASSERTION_STORE_UUID));
+        inner.add_data_box(Box::new(JUMBFCBORContentBox::new(vec![1, 2, 3])));
+        let mut outer = JUMBFSuperBox::new("c2pa", None);
+        outer.add_data_box(Box::new(inner));
+        outer.add_data_box(Box::new(JUMBFCBORContentBox::new(vec![4; 100])));
+        let mut expected = Vec::new();
+        outer.write_box(&mut expected).unwrap();
+
+        // the same boxes streamed
+        let mut stream = Cursor::new(Vec::new());
+        let mut writer = JumbfStreamWriter::new(&mut stream);
+        writer.start_superbox("c2pa", None).unwrap();
+        writer
+            .start_superbox("c2pa.assertions", Some(CAI_ASSERTION_STORE_UUID))
+            .unwrap();
+        writer
+            .write_box(&JUMBFCBORContentBox::new(vec![1, 2, 3]))
+            .unwrap();
+        writer.end_superbox().unwrap();
+        writer
+            .write_box(&JUMBFCBORContentBox::new(vec![4; 100]))
+            .unwrap();
+        writer.end_superbox().unwrap();
+        writer.finish().unwrap();
+
+        assert_eq!(stream.into_inner(), expected);
+
+        // the size is known without keeping the bytes
+        let mut counter = SizeCounter::default();
+        outer.write_box(&mut counter).unwrap();
+        assert_eq!(counter.size(), expected.len() as u64);
+    }
+
+    #[test]
+    fn test_unbalanced_superboxes() {
+        let mut stream = Cursor::new(Vec::new());
+        let mut writer = JumbfStreamWriter::new(&mut stream);
+        assert!(writer.end_superbox().is_err());
+        writer.start_superbox("c2pa", None).unwrap();
+        assert!(writer.finish().is_err());
+    }
+}
@@ -11,31 +11,57 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use std::borrow::Cow;
 use std::fs;
-use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
-use crate::asset_handlers::{c2pa_io::C2paIO, jpeg_io::JpegIO, png_io::PngIO};
+use tracing::instrument;
+
+use crate::asset_handlers::{
+    c2pa_io::C2paIO, eml_io::EmlIO, font_io::FontIO, jpeg_io::JpegIO, png_io::PngIO, zip_io::ZipIO,
+};
 use crate::asset_io::{AssetIO, CAILoader, HashObjectPositions};
 use crate::error::{Error, Result};
 use crate::status_tracker::StatusTracker;
 use crate::store::Store;
+use crate::ResourceLimits;
 
-static SUPPORTED_TYPES: &[&str; 6] = &[
+static SUPPORTED_TYPES: &[&str; 22] = &[
     "c2pa", // stand-alone manifest file
     "jpg",
     "jpeg",
     "png",
+    "zip",
+    "docx",
+    "xlsx",
+    "pptx",
+    "epub",
+    "eml",
+    "ttf",
+    "otf",
     "image/jpeg",
     "image/png",
+    "application/zip",
+    "application/epub+zip",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "message/rfc822",
+    "font/ttf",
+    "font/otf",
 ];
 
 /// Return jumbf block from in memory asset
+#[instrument(skip_all, fields(format = asset_type, len = data.len()))]
 pub fn load_jumbf_from_memory(asset_type: &str, data: &[u8]) -> Result<Vec<u8>> {
-    let mut buf_reader = Cursor::new(data);
+    load_jumbf_from_slice(asset_type, data).map(Cow::into_owned)
+}
 
+// Return jumbf block from in memory asset, borrowed from `data` when the format
+// stores it contiguously so it is not copied
+pub(crate) fn load_jumbf_from_slice<'a>(asset_type: &str, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
     let cai_block = match get_cailoader_handler(asset_type) {
-        Some(asset_handler) => asset_handler.read_cai(&mut buf_reader)?,
+        Some(asset_handler) => asset_handler.read_cai_from_slice(data)?,
         None => return Err(Error::UnsupportedType),
     };
     if cai_block.is_empty() {
@@ -50,50 +76,94 @@ pub fn load_cai_from_memory(
     data: &[u8],
     validation_log: &mut impl StatusTracker,
 ) -> Result<Store> {
-    load_jumbf_from_memory(asset_type, data).and_then(|cai_block| {
+    load_cai_from_memory_with_limits(asset_type, data, &ResourceLimits::default(), validation_log)
+}
+
+/// Return Store from in memory asset, enforcing the resource limits while it is parsed
+pub fn load_cai_from_memory_with_limits(
+    asset_type: &str,
+    data: &[u8],
+    limits: &ResourceLimits,
+    validation_log: &mut impl StatusTracker,
+) -> Result<Store> {
+    load_jumbf_from_slice(asset_type, data).and_then(|cai_block| {
         // load and validate with CAI toolkit and dump if desired
-        Store::from_jumbf(&cai_block, validation_log)
+        Store::from_jumbf_with_limits(&cai_block, limits, validation_log)
     })
 }
 
 // TODO [scouten]: Find a cleaner way to opt in or out of PDF IO.
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
 pub fn get_assetio_handler(ext: &str) -> Option<Box<dyn AssetIO>> {
     match ext {
         "c2pa" => Some(Box::new(C2paIO {})),
         "jpg" | "jpeg" => Some(Box::new(JpegIO {})),
         "png" => Some(Box::new(PngIO {})),
+        "zip" | "docx" | "xlsx" | "pptx" | "epub" => Some(Box::new(ZipIO {})),
+        "eml" => Some(Box::new(EmlIO {})),
+        "ttf" | "otf" => Some(Box::new(FontIO {})),
         _ => None,
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 pub fn get_assetio_handler(ext: &str) -> Option<Box<dyn AssetIO>> {
     match ext {
         "c2pa" => Some(Box::new(C2paIO {})),
         "jpg" | "jpeg" => Some(Box::new(JpegIO {})),
         "png" => Some(Box::new(PngIO {})),
+        "zip" | "docx" | "xlsx" | "pptx" | "epub" => Some(Box::new(ZipIO {})),
+        "eml" => Some(Box::new(EmlIO {})),
+        "ttf" | "otf" => Some(Box::new(FontIO {})),
         _ => None,
     }
 }
 
 // TODO [scouten]: Find a cleaner way to opt in or out of PDF IO.
-#[cfg(not(target_arch = "wasm32"))]
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
 pub fn get_cailoader_handler(asset_type: &str) -> Option<Box<dyn CAILoader>> {
     match asset_type {
         "c2pa" | "application/c2pa" => Some(Box::new(C2paIO {})),
         "jpg" | "jpeg" | "image/jpeg" => Some(Box::new(JpegIO {})),
         "png" | "image/png" => Some(Box::new(PngIO {})),
+        "zip"
+        | "docx"
+        | "xlsx"
+        | "pptx"
+        | "epub"
+        | "application/zip"
+        | "application/epub+zip"
+        | "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        | "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        | "application/vnd.openxmlformats-officedocument.presentationml.presentation" => {
+            Some(Box::new(ZipIO {}))
+        }
+        "eml" | "message/rfc822" => Some(Box::new(EmlIO {})),
+        "ttf" | "otf" | "font/ttf" | "font/otf" => Some(Box::new(FontIO {})),
         _ => None,
     }
 }
 
-#[cfg(target_arch = "wasm32")]
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 pub fn get_cailoader_handler(asset_type: &str) -> Option<Box<dyn CAILoader>> {
     match asset_type {
         "c2pa" | "application/c2pa" => Some(Box::new(C2paIO {})),
         "jpg" | "jpeg" | "image/jpeg" => Some(Box::new(JpegIO {})),
         "png" | "image/png" => Some(Box::new(PngIO {})),
+        "zip"
+        | "docx"
+        | "xlsx"
+        | "pptx"
+        | "epub"
+        | "application/zip"
+        | "application/epub+zip"
+        | "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        | "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        | "application/vnd.openxmlformats-officedocument.presentationml.presentation" => {
+            Some(Box::new(ZipIO {}))
+        }
+        "eml" | "message/rfc822" => Some(Box::new(EmlIO {})),
+        "ttf" | "otf" | "font/ttf" | "font/otf" => Some(Box::new(FontIO {})),
         _ => None,
     }
 }
@@ -116,11 +186,33 @@ pub fn get_supported_file_extension(path: &Path) -> Option<String> {
     }
 }
 
+/// Returns the file extension of the asset handler for `format`, a MIME type or extension,
+/// or `None` if no handler supports it
+pub(crate) fn get_handler_extension(format: &str) -> Option<String> {
+    let format = format.to_lowercase();
+    let ext = match format.as_str() {
+        "application/c2pa" => "c2pa",
+        "image/jpeg" => "jpg",
+        "image/png" => "png",
+        "application/zip" => "zip",
+        "application/epub+zip" => "epub",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => "docx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet" => "xlsx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation" => "pptx",
+        "message/rfc822" => "eml",
+        "font/ttf" => "ttf",
+        "font/otf" => "otf",
+        ext => ext,
+    };
+    get_assetio_handler(ext).map(|_| ext.to_owned())
+}
+
 /// save_jumbf to a file
 /// in_path - path is source file
 /// out_path - path to the output file
 /// If no output file is given an new file will be created with "-c2pa" appending to file name e.g. "test.jpg" => "test-c2pa.jpg"
 /// If input == output then the input file will be overwritten.
+#[instrument(skip_all, fields(asset = %in_path.display(), len = data.len()))]
 pub fn save_jumbf_to_file(data: &[u8], in_path: &Path, out_path: Option<&Path>) -> Result<()> {
     let ext = get_file_extension(in_path).ok_or(Error::UnsupportedType)?;
 
@@ -175,6 +267,7 @@ pub fn update_file_jumbf(
 }
 
 /// load the JUMBF block from an asset if available
+#[instrument(skip_all, fields(asset = %in_path.display()))]
 pub fn load_jumbf_from_file(in_path: &Path) -> Result<Vec<u8>> {
     let ext = get_file_extension(in_path).ok_or(Error::UnsupportedType)?;
 
@@ -191,6 +284,15 @@ pub fn load_jumbf_from_file(in_path: &Path) -> Result<Vec<u8>> {
 pub fn load_cai_from_file(
     in_path: &Path,
     validation_log: &mut impl StatusTracker,
+) -> Result<Store> {
+    load_cai_from_file_with_limits(in_path, &ResourceLimits::default(), validation_log)
+}
+
+/// load a CAI store from a file, enforcing the resource limits while it is parsed
+pub fn load_cai_from_file_with_limits(
+    in_path: &Path,
+    limits: &ResourceLimits,
+    validation_log: &mut impl StatusTracker,
 ) -> Result<Store> {
     // get jumbf block
     load_jumbf_from_file(in_path).and_then(|buffer| {
@@ -199,10 +301,11 @@ pub fn load_cai_from_file(
         }
 
         // load and validate with CAI toolkit and dump if desired
-        Store::from_jumbf(&buffer, validation_log)
+        Store::from_jumbf_with_limits(&buffer, limits, validation_log)
     })
 }
 
+#[instrument(skip_all, fields(asset = %in_path.display()))]
 pub fn object_locations(in_path: &Path) -> Result<Vec<HashObjectPositions>> {
     let ext = get_file_extension(in_path).ok_or(Error::UnsupportedType)?;
 
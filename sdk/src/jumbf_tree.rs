@@ -0,0 +1,337 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Dumps the raw JUMBF box tree of a manifest store, for debugging.
+
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "file_io")]
+use crate::jumbf_io::load_jumbf_from_file;
+use crate::{jumbf::boxes::JumbfParseError, jumbf_io::load_jumbf_from_memory, Result};
+
+// superboxes nested deeper than this are rejected instead of exhausting the stack
+const MAX_DEPTH: usize = 64;
+
+// description box toggles
+const TOGGLE_LABEL: u8 = 0x02;
+const TOGGLE_ID: u8 = 0x04;
+const TOGGLE_HASH: u8 = 0x08;
+const TOGGLE_PRIVATE: u8 = 0x10;
+
+// UUIDs of the ISO 19566-5 and C2PA content types end with this suffix,
+// the first four bytes are then the ASCII name of the type
+const ISO_UUID_SUFFIX: &str = "00110010800000AA00389B71";
+
+/// A box of the JUMBF tree of a manifest store, as it is laid out in the bytes.
+///
+/// Boxes are reported as they are found, whether or not the SDK understands them,
+/// so the tree can be compared with the output of other implementations.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct JumbfBox {
+    /// The four character type of the box, such as `jumb`, `jumd` or `cbor`
+    pub box_type: String,
+    /// Offset of the box from the start of the manifest store
+    pub offset: u64,
+    /// Length of the box, including its header
+    pub length: u64,
+    /// SHA-256 hash of the box, including its header, in hex
+    pub hash: String,
+    /// Content type UUID of a superbox or description box, in hex
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Label of a superbox or description box
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Toggles of a description box
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub toggles: Option<u8>,
+    /// ID of a description box
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub box_id: Option<u32>,
+    /// SHA-256 hash recorded in a description box, in hex
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_hash: Option<String>,
+    /// Boxes held by a superbox, or the private box of a description box
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<JumbfBox>,
+}
+
+impl JumbfBox {
+    /// Returns the four character name of the content type, if it is an ISO or C2PA type
+    pub fn content_name(&self) -> Option<String> {
+        let content_type = self.content_type.as_deref()?;
+        if !content_type.ends_with(ISO_UUID_SUFFIX) {
+            return None;
+        }
+        let name = hex::decode(&content_type[..8]).ok()?;
+        String::from_utf8(name).ok()
+    }
+
+    fn write_tree(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        write!(
+            f,
+            "{:indent$}{} @{} len {}",
+            "",
+            self.box_type,
+            self.offset,
+            self.length,
+            indent = depth * 2
+        )?;
+        if let Some(label) = self.label.as_ref() {
+            write!(f, " \"{}\"", label)?;
+        }
+        if let Some(content_type) = self.content_type.as_ref() {
+            match self.content_name() {
+                Some(name) => write!(f, " [{}]", name)?,
+                None => write!(f, " [{}]", content_type)?,
+            }
+        }
+        writeln!(f, " sha256 {}", self.hash)?;
+        for child in &self.children {
+            child.write_tree(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The JUMBF box tree of a manifest store, with the offset, length and hash of each box.
+///
+/// Serialize it to JSON, or display it for an indented text tree.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct JumbfTree {
+    /// Length of the manifest store
+    pub length: u64,
+    /// The top level boxes of the manifest store
+    pub boxes: Vec<JumbfBox>,
+}
+
+impl JumbfTree {
+    /// Parses the box tree of the JUMBF manifest store `jumbf`
+    pub fn from_jumbf(jumbf: &[u8]) -> Result<Self> {
+        Ok(JumbfTree {
+            length: jumbf.len() as u64,
+            boxes: parse_boxes(jumbf, 0, 0)?,
+        })
+    }
+
+    /// Parses the box tree of the manifest store embedded in an asset held in memory.
+    /// `format` is the MIME type or extension of the asset.
+    pub fn from_bytes(format: &str, data: &[u8]) -> Result<Self> {
+        Self::from_jumbf(&load_jumbf_from_memory(format, data)?)
+    }
+
+    /// Parses the box tree of the manifest store embedded in the asset at `path`
+    #[cfg(feature = "file_io")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_jumbf(&load_jumbf_from_file(path.as_ref())?)
+    }
+}
+
+impl std::fmt::Display for JumbfTree {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for jumbf_box in &self.boxes {
+            jumbf_box.write_tree(f, 0)?;
+        }
+        Ok(())
+    }
+}
+
+// parse the sequence of boxes filling `data`, which starts at `base` in the store
+fn parse_boxes(data: &[u8], base: u64, depth: usize) -> Result<Vec<JumbfBox>> {
+    if depth > MAX_DEPTH {
+        return Err(JumbfParseError::InvalidJumbBox.into());
+    }
+
+    let mut boxes = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let (header_len, box_len, box_type) = read_header(&data[pos..])?;
+        let bytes = &data[pos..pos + box_len];
+        let content = &bytes[header_len..];
+        let offset = base + pos as u64;
+
+        let mut jumbf_box = JumbfBox {
+            box_type,
+            offset,
+            length: box_len as u64,
+            hash: hex::encode(Sha256::digest(bytes)),
+            content_type: None,
+            label: None,
+            toggles: None,
+            box_id: None,
+            description_hash: None,
+            children: Vec::new(),
+        };
+
+        match jumbf_box.box_type.as_str() {
+            "jumb" => {
+                jumbf_box.children = parse_boxes(content, offset + header_len as u64, depth + 1)?;
+                if let Some(desc) = jumbf_box.children.first().filter(|b| b.box_type == "jumd") {
+                    jumbf_box.content_type = desc.content_type.clone();
+                    jumbf_box.label = desc.label.clone();
+                }
+            }
+            "jumd" => parse_description(
+                &mut jumbf_box,
+                content,
+                offset + header_len as u64,
+                depth + 1,
+            )?,
+            _ => (),
+        }
+
+        boxes.push(jumbf_box);
+        pos += box_len;
+    }
+    Ok(boxes)
+}
+
+// returns the header length, box length and type of the box starting `data`
+fn read_header(data: &[u8]) -> Result<(usize, usize, String)> {
+    if data.len() < 8 {
+        return Err(JumbfParseError::InvalidBoxHeader.into());
+    }
+    let mut size = [0u8; 4];
+    size.copy_from_slice(&data[0..4]);
+    let box_type = String::from_utf8_lossy(&data[4..8]).into_owned();
+
+    let (header_len, box_len) = match u32::from_be_bytes(size) {
+        // the box extends to the end of its container
+        0 => (8, data.len() as u64),
+        1 => {
+            if data.len() < 16 {
+                return Err(JumbfParseError::InvalidBoxHeader.into());
+            }
+            let mut large_size = [0u8; 8];
+            large_size.copy_from_slice(&data[8..16]);
+            (16, u64::from_be_bytes(large_size))
+        }
+        size => (8, size as u64),
+    };
+
+    if box_len < header_len as u64 || box_len > data.len() as u64 {
+        return Err(JumbfParseError::InvalidBoxRange.into());
+    }
+    Ok((header_len, box_len as usize, box_type))
+}
+
+// fill in the fields of a description box from its content
+fn parse_description(desc: &mut JumbfBox, content: &[u8], base: u64, depth: usize) -> Result<()> {
+    if content.len() < 17 {
+        return Err(JumbfParseError::InvalidDescriptionBox.into());
+    }
+    desc.content_type = Some(hex::encode_upper(&content[..16]));
+    let toggles = content[16];
+    desc.toggles = Some(toggles);
+    let mut pos = 17;
+
+    if toggles & TOGGLE_LABEL != 0 {
+        let len = content[pos..]
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or(JumbfParseError::InvalidDescriptionBox)?;
+        desc.label = Some(String::from_utf8_lossy(&content[pos..pos + len]).into_owned());
+        pos += len + 1;
+    }
+    if toggles & TOGGLE_ID != 0 {
+        let id = content
+            .get(pos..pos + 4)
+            .ok_or(JumbfParseError::InvalidDescriptionBox)?;
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(id);
+        desc.box_id = Some(u32::from_be_bytes(buf));
+        pos += 4;
+    }
+    if toggles & TOGGLE_HASH != 0 {
+        let hash = content
+            .get(pos..pos + 32)
+            .ok_or(JumbfParseError::InvalidDescriptionBox)?;
+        desc.description_hash = Some(hex::encode(hash));
+        pos += 32;
+    }
+    if toggles & TOGGLE_PRIVATE != 0 {
+        desc.children = parse_boxes(&content[pos..], base + pos as u64, depth)?;
+    } else if pos != content.len() {
+        return Err(JumbfParseError::InvalidDescriptionBox.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::utils::test::fixture_path;
+
+    // every box lies within its parent and children fill their superbox
+    fn check_layout(jumbf_box: &JumbfBox) {
+        if jumbf_box.box_type == "jumb" {
+            let header_len = 8;
+            let mut pos = jumbf_box.offset + header_len;
+            for child in &jumbf_box.children {
+                assert_eq!(child.offset, pos);
+                pos += child.length;
+                check_layout(child);
+            }
+            assert_eq!(pos, jumbf_box.offset + jumbf_box.length);
+        }
+    }
+
+    #[test]
+    fn test_jumbf_tree() {
+        let data = std::fs::read(fixture_path("CA.jpg")).unwrap();
+        let tree = JumbfTree::from_bytes("jpg", &data).unwrap();
+
+        assert_eq!(tree.boxes.len(), 1);
+        let root = &tree.boxes[0];
+        assert_eq!(root.box_type, "jumb");
+        assert_eq!(root.offset, 0);
+        assert_eq!(root.length, tree.length);
+        assert_eq!(root.label.as_deref(), Some("c2pa"));
+        assert_eq!(root.content_name().as_deref(), Some("c2pa"));
+        check_layout(root);
+
+        // the store holds manifests, which hold a claim, a signature and an assertion store
+        let manifest = &root.children[1];
+        assert_eq!(manifest.content_name().as_deref(), Some("c2ma"));
+        let names: Vec<String> = manifest.children[1..]
+            .iter()
+            .filter_map(|b| b.content_name())
+            .collect();
+        assert!(names.contains(&"c2as".to_owned()));
+        assert!(names.contains(&"c2cl".to_owned()));
+        assert!(names.contains(&"c2cs".to_owned()));
+
+        let text = tree.to_string();
+        assert!(text.starts_with("jumb @0 len"));
+        assert!(text.contains("\"c2pa.claim\" [c2cl]"));
+        assert!(serde_json::to_string(&tree)
+            .unwrap()
+            .contains("\"box_type\":\"jumd\""));
+    }
+
+    #[test]
+    fn test_jumbf_tree_invalid() {
+        let data = std::fs::read(fixture_path("CA.jpg")).unwrap();
+        let jumbf = load_jumbf_from_memory("jpg", &data).unwrap();
+
+        // a truncated store is reported rather than read past its end
+        assert!(JumbfTree::from_jumbf(&jumbf[..jumbf.len() - 1]).is_err());
+        assert!(JumbfTree::from_jumbf(&jumbf[..5]).is_err());
+    }
+}
@@ -65,42 +65,180 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Diagnostics
+//!
+//! Loading, validating, signing and saving are instrumented with [`tracing`](https://docs.rs/tracing)
+//! spans, and each entry added to a validation log is emitted as an event. The spans nest
+//! under the caller's current span, so fields such as a request id recorded there correlate
+//! the timing and failures of each asset. Without a `tracing` subscriber the events are
+//! forwarded to the `log` crate.
 
 pub use assertion::{Assertion, AssertionBase, AssertionCbor, AssertionJson};
 pub mod assertions;
 
+mod binding_check;
+pub use binding_check::{BindingCheck, BindingKind, BindingReport, BindingState};
+
+mod cert_profile;
+pub use cert_profile::{
+    explain_cert_chain, lint_certificate, CertChainReport, CertLintReport, CertRule,
+    CertRuleResult, CertificateAcceptancePolicy, ChainCertReport,
+};
+
+mod cose_lint;
+pub use cose_lint::{cose_lint, CoseLintReport, CoseRule, CoseRuleResult};
+
 mod cose_validator;
 
+pub use claim::ClaimVersion;
+
+mod claim_binding;
+pub use claim_binding::{ClaimBinding, SignatureBinding};
+
+mod databox;
+pub use databox::DataBox;
+
+mod detached_signature;
+#[cfg(feature = "file_io")]
+pub use detached_signature::sign_detached;
+#[cfg(feature = "async_signer")]
+pub use detached_signature::sign_detached_async;
+pub use detached_signature::{verify_detached, verify_detached_async, DetachedSignatureInfo};
+
+mod dynamic_assertion;
+pub use dynamic_assertion::{DynamicAssertion, PartialClaim};
+
+#[cfg(feature = "file_io")]
+mod external_signing;
+#[cfg(feature = "file_io")]
+pub use external_signing::{ExternalSigner, PendingSignature};
+
 mod error;
 pub use error::{Error, Result};
 
+#[cfg(feature = "json_schema")]
+pub mod json_schema;
+
 mod ingredient;
 pub use ingredient::{Ingredient, IngredientOptions};
+pub use utils::thumbnail::{
+    ImageThumbnailGenerator, ThumbnailFormat, ThumbnailGenerator, ThumbnailOptions,
+};
 pub mod jumbf_io;
+
+mod jumbf_tree;
+pub use jumbf_tree::{JumbfBox, JumbfTree};
+mod live_stream;
+pub use live_stream::{InterimSignature, LiveStream, SegmentHash};
 mod manifest;
 pub use manifest::{Manifest, ManifestAssertion};
 
+mod manifest_definition;
+#[cfg(feature = "file_io")]
+pub use manifest_definition::embed_from_json;
+pub use manifest_definition::{
+    AssertionDefinition, IngredientDefinition, ManifestDefinition, ResourceRef,
+};
+
+mod manifest_archive;
+pub use manifest_archive::ManifestArchive;
+
+mod manifest_repository;
+pub use manifest_repository::ManifestRepository;
+
 mod manifest_store;
 pub use manifest_store::ManifestStore;
 
 mod manifest_store_report;
 pub use manifest_store_report::ManifestStoreReport;
 
+mod manifest_diff;
+pub use manifest_diff::{ManifestDiff, ManifestStoreDiff, ValueChange};
+
+mod reader;
+pub use reader::{ManifestSignature, Reader};
+
+mod provenance_graph;
+pub use provenance_graph::{
+    IngredientRelationship, ProvenanceEdge, ProvenanceGraph, ProvenanceNode,
+};
+
+#[cfg(feature = "metadata_extraction")]
+mod metadata_extraction;
+#[cfg(feature = "metadata_extraction")]
+pub use metadata_extraction::{
+    extract_metadata, MetadataExtractionOptions, DEFAULT_ALLOWED_FIELDS,
+};
+
+pub mod metrics;
+
+pub mod progress;
+pub use progress::{CancellationToken, NoProgress, ProgressPhase, ProgressReporter};
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub mod remote_signing;
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub use remote_signing::RemoteSignerClient;
+
+mod resource_limits;
+pub use resource_limits::ResourceLimits;
+
+mod settings;
+pub use settings::{Settings, SignerSettings, ThumbnailSettings, TrustSettings, VerifySettings};
+
 #[cfg(feature = "file_io")]
-pub(crate) mod ocsp_utils;
+mod validated_asset;
 #[cfg(feature = "file_io")]
+pub use validated_asset::ValidatedAsset;
+
+mod validation_policy;
+pub use validation_policy::{
+    FnRule, PolicyDecision, PolicyReason, PolicyRule, RequireActions, RequireDigitalSourceType,
+    SignerAllowList, ValidationPolicy,
+};
+
+mod validation_results;
+pub use validation_results::{StatusCodes, ValidationResults, ValidationState, ValidationTree};
+
+mod verifiable_credential;
+pub use verifiable_credential::{
+    CredentialVerifier, DidResolver, JcsCanonicalizer, VcCanonicalizer, VerificationKey,
+};
+
+mod trust_config;
+pub use trust_config::{TrustConfig, TrustSource};
+
+mod verify_options;
+pub use verify_options::{CertCheckMode, IngredientValidation, ValidationScope, VerifyOptions};
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub(crate) mod ocsp_utils;
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub use ocsp_utils::{HttpOcspFetcher, OcspCache, OcspFetcher, OcspSigner};
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 mod openssl;
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "test_signing", not(target_os = "wasi")))]
+pub use crate::openssl::temp_signer;
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 pub use crate::openssl::{
     signer::{get_signer, get_signer_from_files},
     temp_signer::{get_temp_signer, get_temp_signer_by_alg},
+    CertChainBuilder,
 };
-#[cfg(feature = "file_io")]
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+#[cfg_attr(not(feature = "file_io"), allow(dead_code))]
 mod signer;
+pub use c2pa_core::cose::X5ChainPlacement;
 #[cfg(feature = "async_signer")]
 pub use signer::AsyncSigner;
 #[cfg(feature = "file_io")]
-pub use signer::Signer;
+pub use signer::{
+    CallbackSigner, CertChainPolicy, CosignedSigner, PolicySigner, RemoteSigner, RotatingSigner,
+    Signer, SignerPool, TimeStampPolicy,
+};
+mod signing_info;
+pub use signing_info::{CertSummary, SigningInfo};
 /// crate private declarations
 #[allow(dead_code, clippy::enum_variant_names)]
 pub(crate) mod asn1;
@@ -111,25 +249,39 @@ pub(crate) mod claim;
 pub mod validation_status;
 // TODO: Make this a private module again once we no longer need
 // access to this from claims signer.
-#[cfg(feature = "file_io")]
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+#[allow(dead_code)] // async signers only package signatures on wasm
 pub(crate) mod cose_sign;
 
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 pub(crate) mod embedded_xmp;
 
 pub(crate) mod hashed_uri;
+pub use hashed_uri::HashedUri;
+#[cfg(feature = "fuzzing")]
+#[doc(hidden)]
+pub mod fuzzing;
 #[allow(dead_code)]
 pub(crate) mod jumbf;
 pub(crate) mod salt;
-pub(crate) mod status_tracker;
+pub use salt::SaltPolicy;
+pub mod status_tracker;
 pub(crate) mod store;
 pub(crate) mod time_stamp;
+pub use time_stamp::TimeStampStorage;
 pub(crate) mod utils;
+#[cfg(feature = "file_io")]
+pub use utils::atomic_file::{remove_orphaned_temp_files, FsyncPolicy};
 pub(crate) use utils::cbor_types;
+pub use utils::compression::ManifestCompression;
 pub(crate) use utils::hash_utils;
+#[cfg(feature = "file_io")]
+pub use utils::hash_utils::HashingOptions;
 pub(crate) use utils::xmp_inmemory_utils;
-pub(crate) mod validator;
 #[cfg(target_arch = "wasm32")]
+pub(crate) mod rust_crypto;
+pub(crate) mod validator;
+#[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
 pub mod wasm;
 
 /// The internal name of the C2PA SDK
@@ -0,0 +1,301 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Incremental signing of live recordings.
+//!
+//! A live recording grows a segment at a time, as fragmented MP4 or MPEG-TS chunks. Each
+//! segment is hashed as it arrives and, when the stream ends, the manifest is signed as a
+//! standalone archive bound to the recording by a collection hash over its segments.
+//! Interim signatures over the segments received so far can be emitted along the way, so
+//! a recording can be checked while it is still being made.
+
+use std::io::Read;
+
+use crate::{
+    assertions::CollectionHash,
+    detached_signature::{verify_detached, DetachedSignatureInfo},
+    status_tracker::StatusTracker,
+    utils::hash_utils::hash_by_alg,
+    Error, Manifest, Result, VerifyOptions,
+};
+#[cfg(feature = "file_io")]
+use crate::{detached_signature::sign_detached, ManifestArchive, Signer, TimeStampStorage};
+
+// prefix of the bytes signed by an interim signature
+const INTERIM_LABEL: &[u8] = b"c2pa.live.interim";
+
+/// The hash of a segment of a live recording.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentHash {
+    /// Position of the segment in the recording, starting at 0
+    pub index: usize,
+    /// URI of the segment within the recording
+    pub uri: String,
+    /// Hash of the segment content
+    pub hash: Vec<u8>,
+    /// Hash chaining the segment to all of the segments before it
+    pub chain: Vec<u8>,
+}
+
+/// A signature over the first segments of a live recording.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterimSignature {
+    /// Number of segments covered
+    pub segment_count: usize,
+    /// Hash algorithm of the segment chain
+    pub alg: String,
+    /// Chain hash of the covered segments
+    pub chain: Vec<u8>,
+    /// Detached COSE_Sign1 over the segment count and chain hash
+    pub signature: Vec<u8>,
+}
+
+fn chain_hash(alg: &str, previous: &[u8], segment_hash: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(previous.len() + segment_hash.len());
+    data.extend_from_slice(previous);
+    data.extend_from_slice(segment_hash);
+    hash_by_alg(alg, &data, None)
+}
+
+fn interim_payload(segment_count: usize, chain: &[u8]) -> Vec<u8> {
+    let mut payload = INTERIM_LABEL.to_vec();
+    payload.extend_from_slice(&(segment_count as u64).to_be_bytes());
+    payload.extend_from_slice(chain);
+    payload
+}
+
+impl InterimSignature {
+    /// Verifies the signature against the segments of a recording, in order.
+    ///
+    /// Segments past those covered by the signature are ignored, so a signature made
+    /// during the stream can be checked against the finished recording.
+    pub fn verify<'a, I>(
+        &self,
+        segments: I,
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<DetachedSignatureInfo>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut chain = Vec::new();
+        let mut count = 0;
+        for segment in segments.into_iter().take(self.segment_count) {
+            let hash = hash_by_alg(&self.alg, segment, None);
+            chain = chain_hash(&self.alg, &chain, &hash);
+            count += 1;
+        }
+        if count != self.segment_count || chain != self.chain {
+            return Err(Error::HashMismatch(
+                "segments do not match the interim signature".to_owned(),
+            ));
+        }
+
+        verify_detached(
+            &self.signature,
+            &interim_payload(self.segment_count, &self.chain),
+            options,
+            validation_log,
+        )
+    }
+}
+
+/// Signs the manifest of a live recording incrementally, as its segments arrive.
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::{get_temp_signer, LiveStream, Manifest, VerifyOptions};
+/// # fn main() -> Result<()> {
+/// # let temp_dir = tempfile::tempdir()?;
+/// let (signer, _) = get_temp_signer(&temp_dir.path());
+///
+/// let mut stream = LiveStream::new(Manifest::new("my_app".to_owned()));
+/// stream.add_segment("segment1.m4s", b"first segment")?;
+/// let interim = stream.sign_interim(&signer)?;
+/// stream.add_segment("segment2.m4s", b"second segment")?;
+/// let archive = stream.finish(&signer)?;
+///
+/// let segments: Vec<(&str, &[u8])> = vec![
+///     ("segment1.m4s", b"first segment"),
+///     ("segment2.m4s", b"second segment"),
+/// ];
+/// let manifest_store = archive.verify_collection(&segments, &VerifyOptions::default())?;
+/// assert!(manifest_store.validation_status().is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub struct LiveStream {
+    manifest: Manifest,
+    alg: String,
+    segments: CollectionHash,
+    chain: Vec<u8>,
+}
+
+impl LiveStream {
+    /// Starts a live recording described by `manifest`, hashing its segments with sha256.
+    pub fn new(manifest: Manifest) -> Self {
+        Self::with_alg(manifest, "sha256")
+    }
+
+    /// Starts a live recording described by `manifest`, hashing its segments with `alg`.
+    pub fn with_alg(manifest: Manifest, alg: &str) -> Self {
+        LiveStream {
+            manifest,
+            alg: alg.to_owned(),
+            segments: CollectionHash::new(alg),
+            chain: Vec::new(),
+        }
+    }
+
+    /// Hashes the next segment of the recording, identified by `uri`.
+    pub fn add_segment(&mut self, uri: &str, data: &[u8]) -> Result<SegmentHash> {
+        self.segments.add_part(uri, None, data)?;
+        let hash = self
+            .segments
+            .uris
+            .last()
+            .map(|u| u.hash.clone())
+            .unwrap_or_default();
+        self.chain = chain_hash(&self.alg, &self.chain, &hash);
+
+        Ok(SegmentHash {
+            index: self.segments.uris.len() - 1,
+            uri: uri.to_owned(),
+            hash,
+            chain: self.chain.clone(),
+        })
+    }
+
+    /// Hashes the next segment of the recording, read from `stream`.
+    pub fn add_segment_from_stream(
+        &mut self,
+        uri: &str,
+        stream: &mut dyn Read,
+    ) -> Result<SegmentHash> {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+        self.add_segment(uri, &data)
+    }
+
+    /// Returns the number of segments hashed so far.
+    pub fn segment_count(&self) -> usize {
+        self.segments.uris.len()
+    }
+
+    /// Returns the chain hash of the segments hashed so far.
+    pub fn chain_hash(&self) -> &[u8] {
+        &self.chain
+    }
+
+    /// Signs the segments hashed so far.
+    #[cfg(feature = "file_io")]
+    pub fn sign_interim(&self, signer: &dyn Signer) -> Result<InterimSignature> {
+        if self.segments.uris.is_empty() {
+            return Err(Error::BadParam("no segments to sign".to_owned()));
+        }
+
+        let segment_count = self.segment_count();
+        let signature = sign_detached(
+            &interim_payload(segment_count, &self.chain),
+            signer,
+            TimeStampStorage::V2SigTst2,
+        )?;
+
+        Ok(InterimSignature {
+            segment_count,
+            alg: self.alg.clone(),
+            chain: self.chain.clone(),
+            signature,
+        })
+    }
+
+    /// Ends the recording, signing the manifest as a standalone archive bound to all of
+    /// its segments.
+    #[cfg(feature = "file_io")]
+    pub fn finish(mut self, signer: &dyn Signer) -> Result<ManifestArchive> {
+        if self.segments.uris.is_empty() {
+            return Err(Error::BadParam("no segments to sign".to_owned()));
+        }
+        self.manifest.sign_collection(&self.segments, signer)
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        openssl::temp_signer::get_temp_signer, status_tracker::OneShotStatusTracker,
+        validation_status::ValidationStatusCode,
+    };
+
+    #[test]
+    fn test_live_stream() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+
+        let segments: Vec<(&str, &[u8])> = vec![
+            ("init.mp4", b"init segment"),
+            ("segment1.m4s", b"first segment"),
+            ("segment2.m4s", b"second segment"),
+        ];
+
+        let mut stream = LiveStream::new(Manifest::new("test".to_owned()));
+        let first = stream.add_segment(segments[0].0, segments[0].1).unwrap();
+        let second = stream
+            .add_segment_from_stream(segments[1].0, &mut segments[1].1.clone())
+            .unwrap();
+        assert_eq!(second.index, 1);
+        assert_ne!(first.chain, second.chain);
+        assert!(stream.add_segment("init.mp4", b"again").is_err());
+
+        let interim = stream.sign_interim(&signer).unwrap();
+        assert_eq!(interim.segment_count, 2);
+
+        stream.add_segment(segments[2].0, segments[2].1).unwrap();
+        let archive = stream.finish(&signer).unwrap();
+
+        // the interim signature still covers the start of the finished recording
+        let options = VerifyOptions::default();
+        interim
+            .verify(
+                segments.iter().map(|s| s.1),
+                &options,
+                &mut OneShotStatusTracker::new(),
+            )
+            .unwrap();
+        assert!(matches!(
+            interim.verify(
+                segments.iter().rev().map(|s| s.1),
+                &options,
+                &mut OneShotStatusTracker::new()
+            ),
+            Err(Error::HashMismatch(_))
+        ));
+
+        let manifest_store = archive.verify_collection(&segments, &options).unwrap();
+        assert!(manifest_store.validation_status().is_none());
+
+        // a missing segment breaks the binding
+        let manifest_store = archive.verify_collection(&segments[..2], &options).unwrap();
+        assert!(manifest_store
+            .validation_status()
+            .unwrap()
+            .iter()
+            .any(|s| s.code() == &ValidationStatusCode::AssertionCollectionHashMismatch));
+    }
+}
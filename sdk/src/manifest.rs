@@ -12,30 +12,45 @@
 // each license.
 
 #[cfg(feature = "file_io")]
-use crate::utils::thumbnail::make_thumbnail;
+use crate::utils::thumbnail::{ImageThumbnailGenerator, ThumbnailGenerator};
 use crate::{
     assertion::{AssertionBase, AssertionData, AssertionDecodeError},
-    assertions::{labels, Actions, CreativeWork, SchemaDotOrg, Thumbnail, UserCbor},
-    claim::Claim,
+    assertions::{
+        c2pa_action, labels, Action, Actions, CreativeWork, Icon, Metadata, SchemaDotOrg,
+        Thumbnail, TrainingMining, UserCbor,
+    },
+    claim::{Claim, ClaimAssertion, ClaimVersion, BUILD_HASH_ALG},
     error::{Error, Result},
     jumbf,
     store::Store,
-    Ingredient,
+    DataBox, HashedUri, Ingredient, SaltPolicy,
 };
 
 #[cfg(feature = "file_io")]
-use crate::Signer;
-use log::{debug, error, warn};
+use crate::{
+    assertions::CollectionHash,
+    progress::{CancellationToken, NoProgress, ProgressMonitor, ProgressReporter},
+    status_tracker::OneShotStatusTracker,
+    ExternalSigner, FsyncPolicy, HashingOptions, ManifestArchive, ManifestCompression,
+    ManifestStore, PendingSignature, RemoteSigner, Signer, TimeStampPolicy, TimeStampStorage,
+    VerifyOptions,
+};
+
+#[cfg(all(feature = "file_io", feature = "metadata_extraction"))]
+use crate::{extract_metadata, jumbf_io::get_supported_file_extension, MetadataExtractionOptions};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::io::Read;
 #[cfg(feature = "file_io")]
-use std::path::Path;
+use std::{io::Write, path::Path, sync::Arc};
+use tracing::{debug, error, warn};
 
 const GH_UA: &str = "Sec-CH-UA";
 
 /// A Manifest represents all the information in a c2pa manifest
 #[derive(Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct Manifest {
     /// Optional prefix added to the generated Manifest Label
     /// This is typically Internet domain name for the vendor (i.e. `adobe`)
@@ -70,6 +85,65 @@ pub struct Manifest {
     /// Signature data (only used for reporting)
     #[serde(skip_serializing_if = "Option::is_none")]
     signature_info: Option<SignatureInfo>,
+
+    /// The raw COSE_Sign1 bytes from the signature box (only set when read from an asset)
+    #[serde(skip)]
+    signature_bytes: Option<Vec<u8>>,
+
+    /// The exact CBOR claim bytes that were signed (only set when read from an asset)
+    #[serde(skip)]
+    claim_bytes: Option<Vec<u8>>,
+
+    /// The specification version the claim is written with
+    #[serde(skip)]
+    claim_version: ClaimVersion,
+
+    /// MIME format and bytes of the claim generator icon
+    #[serde(skip)]
+    claim_generator_icon: Option<(String, Vec<u8>)>,
+
+    /// Which assertions of the claim are salted
+    #[serde(skip)]
+    salt_policy: SaltPolicy,
+
+    /// Databoxes referenced by the assertions
+    #[serde(skip)]
+    databox_store: Vec<ClaimAssertion>,
+
+    /// Options used to hash the asset when embedding
+    #[cfg(feature = "file_io")]
+    #[serde(skip)]
+    hashing_options: HashingOptions,
+
+    /// How the signature time-stamp is stored, defaults to the storage of the claim version
+    #[cfg(feature = "file_io")]
+    #[serde(skip)]
+    timestamp_storage: Option<TimeStampStorage>,
+
+    /// How the embedded asset is flushed to storage before it replaces the target
+    #[cfg(feature = "file_io")]
+    #[serde(skip)]
+    fsync_policy: FsyncPolicy,
+
+    /// Options used to verify the output after signing
+    #[cfg(feature = "file_io")]
+    #[serde(skip)]
+    verify_options: VerifyOptions,
+
+    /// How the manifests are compressed when embedding
+    #[cfg(feature = "file_io")]
+    #[serde(skip)]
+    manifest_compression: ManifestCompression,
+
+    /// Generates the claim thumbnail from the asset
+    #[cfg(feature = "file_io")]
+    #[serde(skip)]
+    thumbnail_generator: Option<Arc<dyn ThumbnailGenerator>>,
+
+    /// Extracts metadata assertions from the source asset when embedding
+    #[cfg(all(feature = "file_io", feature = "metadata_extraction"))]
+    #[serde(skip)]
+    metadata_extraction: Option<MetadataExtractionOptions>,
 }
 
 impl Manifest {
@@ -86,13 +160,90 @@ impl Manifest {
             redactions: None,
             credentials: None,
             signature_info: None,
+            signature_bytes: None,
+            claim_bytes: None,
+            claim_version: ClaimVersion::V1,
+            claim_generator_icon: None,
+            salt_policy: SaltPolicy::default(),
+            databox_store: Vec::new(),
+            #[cfg(feature = "file_io")]
+            hashing_options: HashingOptions::default(),
+            #[cfg(feature = "file_io")]
+            timestamp_storage: None,
+            #[cfg(feature = "file_io")]
+            fsync_policy: FsyncPolicy::default(),
+            #[cfg(feature = "file_io")]
+            verify_options: VerifyOptions::default(),
+            #[cfg(feature = "file_io")]
+            manifest_compression: ManifestCompression::default(),
+            #[cfg(feature = "file_io")]
+            thumbnail_generator: None,
+            #[cfg(all(feature = "file_io", feature = "metadata_extraction"))]
+            metadata_extraction: None,
+        }
+    }
+
+    /// Creates a Manifest from its JSON form, such as the output of formatting a Manifest.
+    ///
+    /// The asset, ingredients, assertions, credentials and redactions are restored so
+    /// the manifest can be edited and signed again. Signature information is dropped,
+    /// and ingredients have no manifest stores since those are not part of the JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let mut manifest: Manifest = serde_json::from_str(json)?;
+        manifest.signature_info = None;
+
+        // the library identifier is added again when the manifest is signed
+        let identifier = format!(" {}/", crate::NAME);
+        if let Some(pos) = manifest.claim_generator.rfind(&identifier) {
+            if !manifest.claim_generator[pos + 1..].contains(' ') {
+                manifest.claim_generator.truncate(pos);
+            }
         }
+
+        Ok(manifest)
+    }
+
+    /// Creates a Manifest from the active manifest of a [`ManifestStore`] in JSON form.
+    ///
+    /// See [`Manifest::from_json`] for what is restored.
+    ///
+    /// [`ManifestStore`]: crate::ManifestStore
+    pub fn from_manifest_store_json(json: &str) -> Result<Self> {
+        let report: Value = serde_json::from_str(json)?;
+        let label = report
+            .get("active_manifest")
+            .and_then(|l| l.as_str())
+            .ok_or(Error::ProvenanceMissing)?;
+        let manifest = report
+            .get("manifests")
+            .and_then(|m| m.get(label))
+            .ok_or_else(|| Error::ClaimMissing {
+                label: label.to_owned(),
+            })?;
+        Self::from_json(&manifest.to_string())
     }
 
     pub fn claim_generator(&self) -> &str {
         self.claim_generator.as_str()
     }
 
+    /// Returns the claim generator hints, keyed by hint name
+    pub fn claim_generator_hints(&self) -> Option<&HashMap<String, Value>> {
+        self.claim_generator_hints.as_ref()
+    }
+
+    /// Returns the claim generator hint `key` deserialized into a type
+    pub fn claim_generator_hint<T: DeserializeOwned>(&self, key: &str) -> Result<T> {
+        match self
+            .claim_generator_hints
+            .as_ref()
+            .and_then(|hints| hints.get(key))
+        {
+            Some(value) => Ok(serde_json::from_value(value.clone())?),
+            None => Err(Error::NotFound),
+        }
+    }
+
     /// Returns an [Ingredient] reference to the asset associated with this manifest
     pub fn asset(&self) -> Option<&Ingredient> {
         self.asset.as_ref()
@@ -128,6 +279,101 @@ impl Manifest {
         self
     }
 
+    /// Sets a claim generator hint, such as a preferred viewer URL or a vendor specific value.
+    /// Hints are stored in the `claim_generator_hints` of the claim.
+    pub fn set_claim_generator_hint<T: Serialize>(
+        &mut self,
+        key: &str,
+        value: &T,
+    ) -> Result<&mut Self> {
+        self.claim_generator_hints
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_owned(), serde_json::to_value(value)?);
+        Ok(self)
+    }
+
+    /// Sets which assertions of the claim are salted and how the salts are made
+    /// Salt the assertions that may be redacted by later manifests
+    pub fn set_salt_policy(&mut self, policy: SaltPolicy) -> &mut Self {
+        self.salt_policy = policy;
+        self
+    }
+
+    /// Sets the options used to hash the asset when embedding
+    /// Use this to hash large assets with multiple threads
+    #[cfg(feature = "file_io")]
+    pub fn set_hashing_options(&mut self, options: HashingOptions) -> &mut Self {
+        self.hashing_options = options;
+        self
+    }
+
+    /// Sets how the signature time-stamp is stored.
+    /// By default 1.x claims use `sigTst` and 2.x claims use `sigTst2`.
+    #[cfg(feature = "file_io")]
+    pub fn set_timestamp_storage(&mut self, storage: TimeStampStorage) -> &mut Self {
+        self.timestamp_storage = Some(storage);
+        self
+    }
+
+    /// Sets how the embedded asset is flushed to storage before it replaces the target.
+    /// Use [`FsyncPolicy::FileAndDirectory`] if the target must survive a power loss.
+    #[cfg(feature = "file_io")]
+    pub fn set_fsync_policy(&mut self, policy: FsyncPolicy) -> &mut Self {
+        self.fsync_policy = policy;
+        self
+    }
+
+    /// Sets the options used to verify the output after signing.
+    /// The output is only verified when [`VerifyOptions::verify_after_sign`] is set.
+    #[cfg(feature = "file_io")]
+    pub fn set_verify_options(&mut self, options: VerifyOptions) -> &mut Self {
+        self.verify_options = options;
+        self
+    }
+
+    /// Sets how the manifests are compressed when embedding.
+    /// Compression shrinks manifests with large assertions such as thumbnails,
+    /// but readers must support compressed manifests to validate the asset.
+    #[cfg(feature = "file_io")]
+    pub fn set_manifest_compression(&mut self, compression: ManifestCompression) -> &mut Self {
+        self.manifest_compression = compression;
+        self
+    }
+
+    /// Sets the generator used to create the claim thumbnail when embedding
+    /// Use this to change the thumbnail size or to render thumbnails with another library
+    #[cfg(feature = "file_io")]
+    pub fn set_thumbnail_generator(&mut self, generator: Arc<dyn ThumbnailGenerator>) -> &mut Self {
+        self.thumbnail_generator = Some(generator);
+        self
+    }
+
+    /// Extracts EXIF, IPTC and XMP metadata from the source asset into `stds.exif` and
+    /// `stds.iptc.photo-metadata` assertions when embedding.
+    /// Assertions already in the manifest with the same label are kept instead.
+    #[cfg(all(feature = "file_io", feature = "metadata_extraction"))]
+    pub fn set_metadata_extraction(&mut self, options: MetadataExtractionOptions) -> &mut Self {
+        self.metadata_extraction = Some(options);
+        self
+    }
+
+    // adds the metadata assertions extracted from the source asset if extraction is enabled
+    #[cfg(all(feature = "file_io", feature = "metadata_extraction"))]
+    fn add_extracted_metadata(&mut self, source_path: &Path) -> Result<()> {
+        let options = match self.metadata_extraction.as_ref() {
+            Some(options) => options,
+            None => return Ok(()),
+        };
+        let format = get_supported_file_extension(source_path).ok_or(Error::UnsupportedType)?;
+        let data = std::fs::read(source_path)?;
+        for assertion in extract_metadata(&format, &data, options)? {
+            if self.assertions.iter().all(|a| a.label != assertion.label) {
+                self.assertions.push(assertion);
+            }
+        }
+        Ok(())
+    }
+
     /// Sets an ingredient as the container asset
     pub fn set_asset(&mut self, ingredient: Ingredient) -> &mut Self {
         self.asset = Some(ingredient);
@@ -138,6 +384,24 @@ impl Manifest {
         self.signature_info.as_ref()
     }
 
+    /// Returns the raw COSE_Sign1 signature bytes for this manifest
+    ///
+    /// This is the exact content of the claim signature box, allowing the
+    /// signature to be independently verified with external tooling.
+    /// Only available for manifests read from an asset.
+    pub fn signature_bytes(&self) -> Option<&[u8]> {
+        self.signature_bytes.as_deref()
+    }
+
+    /// Returns the canonical CBOR claim bytes covered by the signature
+    ///
+    /// These are the exact bytes read from the claim box and are the
+    /// detached payload of the COSE_Sign1 returned by [`Manifest::signature_bytes`].
+    /// Only available for manifests read from an asset.
+    pub fn claim_bytes(&self) -> Option<&[u8]> {
+        self.claim_bytes.as_deref()
+    }
+
     /// Sets the parent ingredient, assuring it is first and setting the is_parent flag
     pub fn set_parent(&mut self, mut ingredient: Ingredient) -> Result<&mut Self> {
         // there should only be one parent so return an error if we already have one
@@ -198,6 +462,31 @@ impl Manifest {
         Ok(self)
     }
 
+    /// Adds an assertion with the ISO 8601 date-time when it was created.
+    ///
+    /// The time is recorded in a `c2pa.assertion.metadata` assertion referencing this one.
+    pub fn add_assertion_with_time<T: Serialize + AssertionBase>(
+        &mut self,
+        data: &T,
+        date_time: &str,
+    ) -> Result<&mut Self> {
+        let mut manifest_assertion = ManifestAssertion::from_assertion(data)?;
+        manifest_assertion.set_created(date_time);
+        self.assertions.push(manifest_assertion);
+        Ok(self)
+    }
+
+    /// Adds an assertion gathered by the claim generator rather than created by it
+    pub fn add_gathered_assertion<T: Serialize + AssertionBase>(
+        &mut self,
+        data: &T,
+    ) -> Result<&mut Self> {
+        let mut manifest_assertion = ManifestAssertion::from_assertion(data)?;
+        manifest_assertion.set_gathered(true);
+        self.assertions.push(manifest_assertion);
+        Ok(self)
+    }
+
     /// Retrieves an assertion by label if it exists or Error::NotFound
     pub fn find_assertion<T: DeserializeOwned>(&self, label: &str) -> Result<T> {
         if let Some(manifest_assertion) = self.assertions.iter().find(|a| a.label == label) {
@@ -207,10 +496,18 @@ impl Manifest {
         }
     }
 
-    // keep this private until we support it externally
-    #[allow(dead_code)]
-    pub(crate) fn add_redaction(&mut self, label: &str) -> Result<&mut Self> {
-        // todo: any way to verify if this assertion exists in the parent claim here?
+    /// Redacts an assertion from the active manifest of the parent ingredient.
+    ///
+    /// `label` is the label of the assertion in that manifest, including any instance
+    /// suffix, e.g. `stds.exif`. The assertion is removed from the parent manifest
+    /// when embedding and a `c2pa.redacted` action is recorded for it.
+    /// Actions assertions cannot be redacted.
+    pub fn add_redaction(&mut self, label: &str) -> Result<&mut Self> {
+        let (base_label, _instance) = Claim::assertion_label_from_link(label);
+        if base_label.starts_with(labels::ACTIONS) {
+            return Err(Error::AssertionInvalidRedaction);
+        }
+
         match self.redactions.as_mut() {
             Some(redactions) => redactions.push(label.to_string()),
             None => self.redactions = Some([label.to_string()].to_vec()),
@@ -228,11 +525,43 @@ impl Manifest {
         Ok(self)
     }
 
+    /// Adds a databox of MIME type `format` and returns a hashed URI for assertions to reference it.
+    /// Adding data identical to an existing databox returns the existing reference.
+    pub fn add_databox(&mut self, format: &str, data: Vec<u8>) -> Result<HashedUri> {
+        Claim::add_databox_to_store(
+            &mut self.databox_store,
+            &DataBox::new(format, data),
+            BUILD_HASH_ALG,
+        )
+    }
+
+    /// Adds a databox holding the remaining content of `stream`, see [`Manifest::add_databox`]
+    pub fn add_databox_from_stream(
+        &mut self,
+        format: &str,
+        stream: &mut dyn Read,
+    ) -> Result<HashedUri> {
+        let databox = DataBox::from_stream(format, stream)?;
+        Claim::add_databox_to_store(&mut self.databox_store, &databox, BUILD_HASH_ALG)
+    }
+
+    /// Returns the databox a hashed URI references.
+    /// Returns an error if there is no such databox or its hash does not match.
+    pub fn databox(&self, hashed_uri: &HashedUri) -> Result<DataBox> {
+        Claim::find_databox(&self.databox_store, hashed_uri, BUILD_HASH_ALG)
+    }
+
+    /// Returns a stream reading the data of the databox a hashed URI references
+    pub fn databox_stream(&self, hashed_uri: &HashedUri) -> Result<impl Read> {
+        Ok(self.databox(hashed_uri)?.into_stream())
+    }
+
     /// Sets the signature information for the report
     pub fn set_signature(&mut self, issuer: Option<&String>, time: Option<&String>) -> &mut Self {
         self.signature_info = Some(SignatureInfo {
             issuer: issuer.cloned(),
             time: time.cloned(),
+            cosigners: Vec::new(),
         });
         self
     }
@@ -247,6 +576,14 @@ impl Manifest {
         self.signature_info.to_owned().and_then(|sig| sig.time)
     }
 
+    /// Returns the issuers of the signers that co-signed the manifest
+    pub fn cosigners(&self) -> Vec<String> {
+        self.signature_info
+            .as_ref()
+            .map(|sig| sig.cosigners.clone())
+            .unwrap_or_default()
+    }
+
     // Generates a Manifest given a store and a manifest label
     pub(crate) fn from_store(store: &Store, manifest_label: &str) -> Result<Self> {
         let claim = store
@@ -260,6 +597,14 @@ impl Manifest {
         let mut manifest = Manifest::new(claim_generator);
 
         manifest.claim_generator_hints = claim.get_claim_generator_hint_map().cloned();
+        manifest.claim_version = claim.claim_version();
+        manifest.databox_store = claim.databox_store().clone();
+
+        // keep the raw signature and claim bytes so they can be re-verified externally
+        if !claim.signature_val().is_empty() {
+            manifest.signature_bytes = Some(claim.signature_val().clone());
+            manifest.claim_bytes = Some(claim.data()?);
+        }
 
         // get credentials converting from AssertionData to Value
         manifest.credentials = Some(
@@ -285,9 +630,14 @@ impl Manifest {
 
         let mut asset = Ingredient::new(&title, &format, &instance_id);
 
+        // maps assertion labels with instance to their index in the manifest assertions
+        let mut assertion_index = HashMap::new();
+        let mut creation_times = Vec::new();
+
         for claim_assertion in claim.claim_assertion_store().iter() {
             let assertion = claim_assertion.assertion();
             let label = assertion.label();
+            let count = manifest.assertions.len();
             debug!("assertion = {}", label);
             match label.as_ref() {
                 labels::INGREDIENT => {
@@ -295,7 +645,7 @@ impl Manifest {
                     let ingredient = Ingredient::from_ingredient_uri(store, &assertion_uri)?;
                     manifest.add_ingredient(ingredient);
                 }
-                Actions::LABEL => {
+                _ if assertion.label_root() == Actions::LABEL => {
                     let actions = Actions::from_assertion(assertion)?;
                     manifest.add_assertion(&actions)?; // assertion.as_json_object()?)?;
                 }
@@ -303,6 +653,25 @@ impl Manifest {
                     let thumbnail = Thumbnail::from_assertion(assertion)?;
                     asset.set_thumbnail(thumbnail.content_type, thumbnail.data);
                 }
+                label if label.starts_with(labels::ICON) => {
+                    let icon = Icon::from_assertion(assertion)?;
+                    manifest.claim_generator_icon = Some((icon.content_type, icon.data));
+                }
+                label if label.starts_with(labels::ASSERTION_METADATA) => {
+                    // report creation times on the assertion they refer to
+                    let metadata = Metadata::from_assertion(assertion)?;
+                    match metadata.creation_time_of() {
+                        Some((uri, date_time)) => {
+                            if let Some(target) = jumbf::labels::assertion_label_from_uri(&uri) {
+                                creation_times.push((target, date_time.to_owned()));
+                            }
+                        }
+                        None => {
+                            let value = assertion.as_json_object()?;
+                            manifest.add_labeled_assertion(label, &value)?;
+                        }
+                    }
+                }
                 _ => {
                     // inject assertions for all json data
                     match assertion.decode_data() {
@@ -322,6 +691,17 @@ impl Manifest {
                     }
                 }
             }
+            if manifest.assertions.len() > count {
+                manifest.assertions[count]
+                    .set_gathered(claim.is_gathered(&claim_assertion.label()));
+                assertion_index.insert(claim_assertion.label(), count);
+            }
+        }
+
+        for (target, date_time) in creation_times {
+            if let Some(index) = assertion_index.get(&target) {
+                manifest.assertions[*index].set_created(&date_time);
+            }
         }
 
         manifest.set_asset(asset);
@@ -337,11 +717,51 @@ impl Manifest {
                 issuer, signing_time
             );
             manifest.set_signature(issuer.as_ref(), signing_time.as_ref());
+            if let Some(info) = manifest.signature_info.as_mut() {
+                info.cosigners = claim.cosigning_issuers();
+            }
         }
 
         Ok(manifest)
     }
 
+    /// Returns the specification version of the claim
+    pub fn claim_version(&self) -> ClaimVersion {
+        self.claim_version
+    }
+
+    /// Returns the MIME format and bytes of the claim generator icon
+    pub fn claim_generator_icon(&self) -> Option<(&str, &[u8])> {
+        self.claim_generator_icon
+            .as_ref()
+            .map(|(format, icon)| (format.as_str(), icon.as_slice()))
+    }
+
+    /// Sets an icon for the claim generator that credential viewers can display.
+    /// The format must be `image/svg+xml` or `image/png`.
+    ///
+    /// C2PA 1.x claims reference the icon from an optional `claim_generator_info`
+    /// entry, which viewers that predate it ignore.
+    pub fn set_claim_generator_icon<S: Into<String>>(
+        &mut self,
+        format: S,
+        icon: Vec<u8>,
+    ) -> Result<&mut Self> {
+        let format = format.into();
+        if format != "image/svg+xml" && format != "image/png" {
+            return Err(Error::UnsupportedType);
+        }
+        self.claim_generator_icon = Some((format, icon));
+        Ok(self)
+    }
+
+    /// Sets the specification version used to write the claim.
+    /// Use `ClaimVersion::V2` to produce C2PA 2.x conformant manifests.
+    pub fn set_claim_version(&mut self, version: ClaimVersion) -> &mut Self {
+        self.claim_version = version;
+        self
+    }
+
     /// Sets the asset field from data in a file
     /// the information in the claim should reflect the state of the asset it is embedded in
     /// this method can be used to ensure that data is correct
@@ -351,7 +771,15 @@ impl Manifest {
         // Gather the information we need from the target path
         let mut ingredient = Ingredient::from_file_info(path.as_ref());
 
-        if let Ok((format, image)) = make_thumbnail(path.as_ref()) {
+        // the generator decodes the asset from the file rather than reading it all first
+        let generated = std::fs::File::open(path.as_ref())
+            .map_err(Error::IoError)
+            .and_then(|mut file| match self.thumbnail_generator.as_ref() {
+                Some(generator) => generator.generate_from_stream(ingredient.format(), &mut file),
+                None => ImageThumbnailGenerator::default()
+                    .generate_from_stream(ingredient.format(), &mut file),
+            });
+        if let Ok(Some((format, image))) = generated {
             ingredient.set_thumbnail(format, image);
         }
 
@@ -364,8 +792,8 @@ impl Manifest {
         self.asset = Some(ingredient);
     }
 
-    // Convert a Manifest into a Store
-    pub(crate) fn to_store(&self) -> Result<Store> {
+    // Convert a Manifest into a Claim
+    pub(crate) fn to_claim(&self) -> Result<Claim> {
         // add library identifier to claim_generator
         let generator = format!(
             "{} {}/{}",
@@ -374,6 +802,13 @@ impl Manifest {
             crate::VERSION
         );
         let mut claim = Claim::new(&generator, self.vendor.as_deref());
+        claim.set_claim_version(self.claim_version);
+        claim.set_salt_policy(self.salt_policy.clone());
+
+        // databoxes are referenced by the assertions, so their labels and hashes must not change
+        for databox in &self.databox_store {
+            claim.put_databox_store(databox.clone());
+        }
 
         // add any verified credentials - needs to happen early so we can reference them
         let mut vc_table = HashMap::new();
@@ -403,17 +838,51 @@ impl Manifest {
             ingredient.add_to_claim(&mut claim, self.redactions.clone())?;
         }
 
+        // record a c2pa.redacted action for each assertion redacted from the parent
+        let mut redaction_actions = Vec::new();
+        for uri in claim.redactions().into_iter().flatten() {
+            redaction_actions.push(
+                Action::new(c2pa_action::REDACTED).set_parameter("redacted".to_owned(), uri)?,
+            );
+        }
+
+        // add the claim generator hints, dropping the library entry of a manifest read back
+        let lib_prefix = format!("\"{}\";", crate::NAME);
+        for (key, value) in self.claim_generator_hints.iter().flatten() {
+            match (key.as_str(), value.as_str()) {
+                (GH_UA, Some(ua)) => {
+                    let entries: Vec<&str> = ua
+                        .split(", ")
+                        .filter(|entry| !entry.starts_with(&lib_prefix))
+                        .collect();
+                    if !entries.is_empty() {
+                        claim.add_claim_generator_hint(key, Value::from(entries.join(", ")));
+                    }
+                }
+                _ => claim.add_claim_generator_hint(key, value.clone()),
+            }
+        }
+
         // add a claim_generator_hint for the version of the library used to create the claim
         let lib_hint = format!("\"{}\";v=\"{}\"", crate::NAME, crate::VERSION);
         claim.add_claim_generator_hint(GH_UA, Value::from(lib_hint));
 
+        if let Some((format, icon)) = self.claim_generator_icon.as_ref() {
+            let icon_uri = claim.add_assertion(&Icon::new(format, icon.clone())?)?;
+            claim.set_claim_generator_icon(icon_uri);
+        }
+
         // add any additional assertions
         for assertion in &self.assertions {
-            match assertion.label.as_str() {
+            let hashed_uri = match assertion.label.as_str() {
                 Actions::LABEL => {
                     // todo: fixup parameters field from instance_id to ingredient uri for
                     // c2pa.transcoded, c2pa.repackaged, and c2pa.placed action
-                    claim.add_assertion(&Actions::from_json_value(&assertion.data)?)
+                    let mut actions = Actions::from_json_value(&assertion.data)?;
+                    for action in redaction_actions.drain(..) {
+                        actions.add_action(action);
+                    }
+                    claim.add_assertion(&actions)
                 }
                 CreativeWork::LABEL => {
                     let mut cw = CreativeWork::from_json_str(&assertion.data.to_string())?;
@@ -440,6 +909,11 @@ impl Manifest {
                 labels::CLAIM_REVIEW => {
                     claim.add_assertion(&SchemaDotOrg::from_json_str(&assertion.data.to_string())?)
                 }
+                TrainingMining::LABEL => {
+                    let training_mining: TrainingMining =
+                        serde_json::from_value(assertion.data.clone())?;
+                    claim.add_assertion(&training_mining)
+                }
                 _ => {
                     // default to creating UserCbor assertions
                     claim.add_assertion(&UserCbor::new(
@@ -450,15 +924,64 @@ impl Manifest {
                     //claim.add_assertion(&User::new(&assertion.label, &assertion.data.to_string()), &NoSalt{})?;
                 }
             }?;
+
+            if assertion.is_gathered() {
+                claim.set_assertion_gathered(&hashed_uri);
+            }
+
+            // record the creation time in metadata referencing the assertion
+            if let Some(created) = assertion.created() {
+                let mut metadata = Metadata::new();
+                metadata.set_date_time(created.to_owned());
+                claim.add_assertion(&metadata.set_reference(hashed_uri))?;
+            }
         }
 
+        // add the redaction actions if there was no actions assertion to add them to
+        if !redaction_actions.is_empty() {
+            let mut actions = Actions::new();
+            for action in redaction_actions {
+                actions.add_action(action);
+            }
+            claim.add_assertion(&actions)?;
+        }
+
+        Ok(claim)
+    }
+
+    // Convert a Manifest into a Store
+    pub(crate) fn to_store(&self) -> Result<Store> {
         // commit the claim
         let mut store = Store::new();
-        let _provenance = store.commit_claim(claim)?;
+        let _provenance = store.commit_claim(self.to_claim()?)?;
 
         Ok(store)
     }
 
+    /// Estimates the size in bytes of the manifest store that `embed` would write
+    /// for this manifest, including the space reserved for the signature by `signer`.
+    ///
+    /// Like `embed`, this sets the asset information from `source_path`.
+    /// The estimate does not include the framing the asset format adds around the
+    /// manifest store, so it is also the size needed to host the manifest remotely.
+    #[cfg(feature = "file_io")]
+    pub fn estimate_embedded_size(
+        &mut self,
+        source_path: &Path,
+        signer: &dyn Signer,
+    ) -> Result<usize> {
+        if !source_path.exists() {
+            let path = source_path.to_string_lossy().into_owned();
+            return Err(Error::FileNotFound(path));
+        }
+
+        self.set_asset_from_path(source_path);
+        let mut store = self.to_store()?;
+        store.set_manifest_compression(self.manifest_compression);
+
+        store.estimate_jumbf_size(signer.reserve_size())
+    }
+
     /// Embed a signed manifest into the target file using a supplied signer
     #[cfg(feature = "file_io")]
     pub fn embed(
@@ -467,25 +990,279 @@ impl Manifest {
         dest_path: &Path,
         signer: &dyn Signer,
     ) -> Result<Store> {
+        self.embed_with_progress(
+            source_path,
+            dest_path,
+            signer,
+            &NoProgress,
+            &CancellationToken::new(),
+        )
+    }
+
+    /// Embed a signed manifest into the target file using a supplied signer,
+    /// reporting progress to `reporter`.
+    ///
+    /// Returns [`Error::OperationCancelled`] if `cancel` is cancelled before the operation completes.
+    #[cfg(feature = "file_io")]
+    pub fn embed_with_progress(
+        &mut self,
+        source_path: &Path,
+        dest_path: &Path,
+        signer: &dyn Signer,
+        reporter: &dyn ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<Store> {
+        let monitor = ProgressMonitor::new(reporter, cancel);
+        monitor.check_cancelled()?;
+
+        let mut store = self.store_for_embedding(source_path, dest_path)?;
+        // sign and write our store to to the output image file
+        store.save_to_asset_with_progress(source_path, signer, dest_path.as_ref(), &monitor)?;
+
+        // todo: update xmp
+        Ok(store)
+    }
+
+    /// Embed a manifest signed by a remote signing service into the target file
+    #[cfg(feature = "file_io")]
+    pub fn embed_remote_signed(
+        &mut self,
+        source_path: &Path,
+        dest_path: &Path,
+        signer: &dyn RemoteSigner,
+    ) -> Result<Store> {
+        let mut store = self.store_for_embedding(source_path, dest_path)?;
+        store.save_to_asset_remote(source_path, signer, dest_path)?;
+        Ok(store)
+    }
+
+    /// Embed the manifest into the target file with a placeholder for a signature made
+    /// outside the SDK, such as in an HSM ceremony.
+    ///
+    /// Returns the pending signature with the bytes to be signed. Once they are signed,
+    /// [`PendingSignature::embed_signature`] patches the signature into the target file.
+    /// Manifest compression is not supported, since the placeholder must be patched.
+    #[cfg(feature = "file_io")]
+    pub fn embed_for_external_signing(
+        &mut self,
+        source_path: &Path,
+        dest_path: &Path,
+        signer: &ExternalSigner,
+    ) -> Result<PendingSignature> {
+        let mut store = self.store_for_embedding(source_path, dest_path)?;
+        store.save_to_asset_for_external_signing(source_path, signer, dest_path)
+    }
+
+    // the store to embed into `dest_path`, with the asset information of `source_path`
+    #[cfg(feature = "file_io")]
+    fn store_for_embedding(&mut self, source_path: &Path, dest_path: &Path) -> Result<Store> {
         if !source_path.exists() {
             let path = source_path.to_string_lossy().into_owned();
             return Err(Error::FileNotFound(path));
         }
-        // we need to copy the source to target before setting the asset info
-        if !dest_path.exists() {
-            std::fs::copy(&source_path, &dest_path)?;
-        }
+        // the asset info is read from a copy of the source named like the target,
+        // so nothing appears at the target until it is signed
+        let temp_dir = tempfile::tempdir()?;
+        let asset_info_path = if dest_path.exists() {
+            dest_path.to_path_buf()
+        } else {
+            let file_name = dest_path
+                .file_name()
+                .ok_or(Error::BadParam("invalid destination path".to_string()))?;
+            let path = temp_dir.path().join(file_name);
+            std::fs::copy(&source_path, &path)?;
+            path
+        };
         // first add the information about the target file
-        self.set_asset_from_path(dest_path);
+        self.set_asset_from_path(&asset_info_path);
+        #[cfg(feature = "metadata_extraction")]
+        self.add_extracted_metadata(source_path)?;
         // convert the manifest to a store
         let mut store = self.to_store()?;
-        // sign and write our store to to the output image file
-        store.save_to_asset(source_path, signer, dest_path.as_ref())?;
+        store.set_hashing_options(self.hashing_options.clone());
+        store.set_timestamp_storage(self.timestamp_storage);
+        store.set_fsync_policy(self.fsync_policy);
+        store.set_verify_options(self.verify_options.clone());
+        store.set_manifest_compression(self.manifest_compression);
+        Ok(store)
+    }
+
+    /// Embed this manifest as an update manifest into the target file using a supplied signer.
+    ///
+    /// Update manifests record metadata-only changes to an asset that already has a manifest,
+    /// so the asset content is not hashed again. The active manifest of `source_path` is
+    /// added as the parent ingredient, so this manifest must not have ingredients of its own.
+    /// Only non-binding assertions are allowed and actions are limited to
+    /// `c2pa.edited.metadata`, `c2pa.opened`, `c2pa.published` and `c2pa.redacted`.
+    ///
+    /// Returns [`Error::ProvenanceMissing`] if `source_path` has no manifest and
+    /// [`Error::UpdateManifestInvalid`] if the manifest cannot be an update manifest.
+    #[cfg(feature = "file_io")]
+    pub fn embed_update(
+        &mut self,
+        source_path: &Path,
+        dest_path: &Path,
+        signer: &dyn Signer,
+    ) -> Result<Store> {
+        if !source_path.exists() {
+            let path = source_path.to_string_lossy().into_owned();
+            return Err(Error::FileNotFound(path));
+        }
+        if !self.ingredients.is_empty() {
+            return Err(Error::UpdateManifestInvalid);
+        }
+
+        let mut store =
+            Store::load_from_asset(source_path, false, &mut OneShotStatusTracker::new())?;
+        let parent_uri = match (store.provenance_claim(), store.provenance_path()) {
+            (Some(pc), Some(path)) => HashedUri::new(path, Some(pc.alg().to_owned()), &pc.hash()),
+            _ => return Err(Error::ProvenanceMissing),
+        };
+
+        // the asset is unchanged, so describe it without generating a thumbnail
+        let asset = Ingredient::from_file_info(source_path);
+        let parent = crate::assertions::Ingredient::new(
+            asset.title(),
+            asset.format(),
+            asset.instance_id(),
+            asset.document_id(),
+        )
+        .set_parent()
+        .set_c2pa_manifest_from_hashed_uri(Some(parent_uri));
+        self.asset = Some(asset);
+
+        let mut claim = self.to_claim()?;
+        claim.add_assertion(&parent)?;
+        store.commit_update_manifest(claim)?;
+
+        store.set_hashing_options(self.hashing_options.clone());
+        store.set_timestamp_storage(self.timestamp_storage);
+        store.set_fsync_policy(self.fsync_policy);
+        store.set_verify_options(self.verify_options.clone());
+        store.set_manifest_compression(self.manifest_compression);
+        store.save_to_asset_with_progress(
+            source_path,
+            signer,
+            dest_path,
+            &ProgressMonitor::none(),
+        )?;
 
-        // todo: update xmp
         Ok(store)
     }
 
+    /// Sign the manifest as a standalone `.c2pa` archive for the asset at `asset_path`.
+    ///
+    /// The asset is not changed and the archive is kept separately from it,
+    /// so the hard binding of the manifest covers the whole asset.
+    #[cfg(feature = "file_io")]
+    pub fn sign_archive<P: AsRef<Path>>(
+        &mut self,
+        asset_path: P,
+        signer: &dyn Signer,
+    ) -> Result<ManifestArchive> {
+        let asset_path = asset_path.as_ref();
+        self.set_asset_from_path(asset_path);
+        #[cfg(feature = "metadata_extraction")]
+        self.add_extracted_metadata(asset_path)?;
+
+        let mut store = self.to_store()?;
+        store.set_hashing_options(self.hashing_options.clone());
+        store.set_timestamp_storage(self.timestamp_storage);
+        ManifestArchive::from_bytes(store.save_to_archive(asset_path, signer)?)
+    }
+
+    /// Sign the manifest with an async signer as a standalone `.c2pa` archive for the
+    /// asset read from `asset`.
+    ///
+    /// Like [`Manifest::sign_archive`] the asset is not changed, but nothing is read from
+    /// or written to files, so this works without the `file_io` feature, such as in
+    /// browsers. `format` is the MIME type or extension of the asset. The asset title is
+    /// kept if it is already set, otherwise it is `untitled`.
+    #[cfg(feature = "async_signer")]
+    pub async fn sign_archive_async(
+        &mut self,
+        format: &str,
+        asset: &mut dyn Read,
+        signer: &dyn crate::signer::AsyncSigner,
+    ) -> Result<crate::ManifestArchive> {
+        let mut data = Vec::new();
+        asset.read_to_end(&mut data)?;
+
+        if self.asset.is_none() {
+            self.asset = Some(Ingredient::new("untitled", format, ""));
+        }
+        let mut store = self.to_store()?;
+        crate::ManifestArchive::from_bytes(store.save_to_archive_async(&data, signer).await?)
+    }
+
+    /// Sign the manifest as a standalone `.c2pa` archive bound to the members of a
+    /// collection by `collection_hash`, such as the segments of a live recording.
+    #[cfg(feature = "file_io")]
+    pub fn sign_collection(
+        &mut self,
+        collection_hash: &CollectionHash,
+        signer: &dyn Signer,
+    ) -> Result<ManifestArchive> {
+        let mut store = self.to_store()?;
+        store.set_hashing_options(self.hashing_options.clone());
+        store.set_timestamp_storage(self.timestamp_storage);
+        ManifestArchive::from_bytes(store.save_to_collection(collection_hash, signer)?)
+    }
+
+    /// Embed a signed manifest into an asset read from `source`, writing the signed asset
+    /// to `asset` and, when given, the manifest store to `manifest` as a `.c2pa` sidecar
+    /// and the JSON report of the manifest store to `report`.
+    ///
+    /// `format` is the MIME type or extension of the source, any format with an asset
+    /// handler is supported. All outputs come from one signing pass, so the source is only
+    /// read once. The asset title is kept if it is already set, otherwise it is `untitled`.
+    #[cfg(feature = "file_io")]
+    pub fn embed_to_streams(
+        &mut self,
+        format: &str,
+        source: &mut dyn Read,
+        signer: &dyn Signer,
+        asset: &mut dyn Write,
+        manifest: Option<&mut dyn Write>,
+        report: Option<&mut dyn Write>,
+    ) -> Result<Store> {
+        let ext = crate::jumbf_io::get_handler_extension(format).ok_or(Error::UnsupportedType)?;
+
+        // the asset handlers work on files, so stage the source in a temporary directory
+        let temp_dir = tempfile::tempdir()?;
+        let source_path = temp_dir.path().join(format!("source.{}", ext));
+        let dest_path = temp_dir.path().join(format!("signed.{}", ext));
+        std::io::copy(source, &mut std::fs::File::create(&source_path)?)?;
+
+        if self.asset.is_none() {
+            self.asset = Some(Ingredient::new("untitled", "", ""));
+        }
+        let store = self.embed(&source_path, &dest_path, signer)?;
+
+        std::io::copy(&mut std::fs::File::open(&dest_path)?, asset)?;
+        if let Some(manifest) = manifest {
+            manifest.write_all(&store.to_jumbf(signer)?)?;
+        }
+        if let Some(report) = report {
+            let manifest_store =
+                ManifestStore::from_store(&store, &mut OneShotStatusTracker::new());
+            serde_json::to_writer_pretty(report, &manifest_store)?;
+        }
+
+        Ok(store)
+    }
+
+    /// Add a time-stamp to the active manifest of an asset that was signed without one,
+    /// such as by a signer that could not reach a time authority.
+    ///
+    /// The time-stamp is requested as described by `policy` and stored in the existing
+    /// signature, so nothing else in the manifest or asset changes. Fails if the manifest
+    /// already has a time-stamp or its signature was not reserved enough space for one.
+    #[cfg(feature = "file_io")]
+    pub fn add_timestamp<P: AsRef<Path>>(asset_path: P, policy: &TimeStampPolicy) -> Result<()> {
+        Store::add_timestamp_to_asset(asset_path.as_ref(), policy)
+    }
+
     /// Embed a signed manifest into the target file using a supplied async signer
     #[cfg(feature = "file_io")]
     #[cfg(feature = "async_signer")]
@@ -494,13 +1271,45 @@ impl Manifest {
         target_path: &P,
         signer: &dyn crate::signer::AsyncSigner,
     ) -> Result<Store> {
+        self.embed_async_with_progress(target_path, signer, &NoProgress, &CancellationToken::new())
+            .await
+    }
+
+    /// Embed a signed manifest into the target file using a supplied async signer,
+    /// reporting progress to `reporter`.
+    ///
+    /// Returns [`Error::OperationCancelled`] if `cancel` is cancelled before the operation completes.
+    #[cfg(feature = "file_io")]
+    #[cfg(feature = "async_signer")]
+    pub async fn embed_async_with_progress<P: AsRef<Path>>(
+        &mut self,
+        target_path: &P,
+        signer: &dyn crate::signer::AsyncSigner,
+        reporter: &(dyn ProgressReporter + Sync),
+        cancel: &CancellationToken,
+    ) -> Result<Store> {
+        ProgressMonitor::new(reporter, cancel).check_cancelled()?;
+
         // first add the information about the target file
         self.set_asset_from_path(target_path);
+        #[cfg(feature = "metadata_extraction")]
+        self.add_extracted_metadata(target_path.as_ref())?;
         // convert the manifest to a store
         let mut store = self.to_store()?;
+        store.set_hashing_options(self.hashing_options.clone());
+        store.set_timestamp_storage(self.timestamp_storage);
+        store.set_fsync_policy(self.fsync_policy);
+        store.set_verify_options(self.verify_options.clone());
+        store.set_manifest_compression(self.manifest_compression);
         // sign and write our store to to the output image file
         store
-            .save_to_asset_async(target_path.as_ref(), signer, target_path.as_ref())
+            .save_to_asset_async_with_progress(
+                target_path.as_ref(),
+                signer,
+                target_path.as_ref(),
+                reporter,
+                cancel,
+            )
             .await?;
 
         // todo: update xmp
@@ -515,27 +1324,74 @@ impl std::fmt::Display for Manifest {
     }
 }
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 /// A labeled container for an Assertion value in a Manifest
+///
+/// Fields may be added to this struct, so create it with [`new`](ManifestAssertion::new)
+/// or one of the `from_` functions rather than a struct literal.
+#[non_exhaustive]
 pub struct ManifestAssertion {
     /// An assertion label in reverse domain format
     pub label: String,
     /// The data of the assertion as Value
     pub data: Value,
+    /// ISO 8601 date-time when the assertion was created, if recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created: Option<String>,
+    /// True if the assertion was gathered rather than created by the claim generator
+    #[serde(default, skip_serializing_if = "is_false")]
+    gathered: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
 
 impl ManifestAssertion {
+    /// Create an assertion with `label` holding `data`
+    pub fn new(label: String, data: Value) -> Self {
+        Self {
+            label,
+            data,
+            created: None,
+            gathered: false,
+        }
+    }
+
     pub fn from_labeled_assertion<T: Serialize>(label: &str, data: &T) -> Result<Self> {
-        Ok(Self {
-            label: label.to_owned(),
-            data: serde_json::to_value(data).map_err(|_err| Error::AssertionEncoding)?,
-        })
+        Ok(Self::new(
+            label.to_owned(),
+            serde_json::to_value(data).map_err(|_err| Error::AssertionEncoding)?,
+        ))
     }
 
     pub fn from_assertion<T: Serialize + AssertionBase>(data: &T) -> Result<Self> {
-        Ok(Self {
-            label: data.label().to_owned(),
-            data: serde_json::to_value(data).map_err(|_err| Error::AssertionEncoding)?,
-        })
+        Ok(Self::new(
+            data.label().to_owned(),
+            serde_json::to_value(data).map_err(|_err| Error::AssertionEncoding)?,
+        ))
+    }
+
+    /// Returns the ISO 8601 date-time when the assertion was created, if recorded
+    pub fn created(&self) -> Option<&str> {
+        self.created.as_deref()
+    }
+
+    /// Returns true if the assertion was gathered rather than created by the claim generator
+    pub fn is_gathered(&self) -> bool {
+        self.gathered
+    }
+
+    /// Sets the ISO 8601 date-time when the assertion was created
+    pub fn set_created(&mut self, date_time: &str) -> &mut Self {
+        self.created = Some(date_time.to_owned());
+        self
+    }
+
+    /// Sets whether the assertion was gathered rather than created by the claim generator
+    pub fn set_gathered(&mut self, gathered: bool) -> &mut Self {
+        self.gathered = gathered;
+        self
     }
 
     pub fn to_assertion<T: DeserializeOwned>(&self) -> Result<T> {
@@ -551,6 +1407,7 @@ impl ManifestAssertion {
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 /// Holds information about a signature
 pub struct SignatureInfo {
     /// human readable issuing authority for this signature
@@ -559,26 +1416,30 @@ pub struct SignatureInfo {
     /// the time the signature was created
     #[serde(skip_serializing_if = "Option::is_none")]
     time: Option<String>,
+    /// human readable issuing authorities of the co-signatures
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cosigners: Vec<String>,
 }
 #[cfg(test)]
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
 pub(crate) mod tests {
     #![allow(clippy::expect_used)]
     #![allow(clippy::unwrap_used)]
 
-    use super::{Ingredient, Manifest, Store};
+    use super::{ClaimVersion, Ingredient, Manifest, Store, GH_UA};
 
     use crate::{
-        assertions::{c2pa_action, Action, Actions},
+        assertions::{c2pa_action, labels, Action, Actions, CreativeWork},
         openssl::temp_signer::get_temp_signer,
-        status_tracker::{DetailedStatusTracker, StatusTracker},
+        status_tracker::{report_split_errors, DetailedStatusTracker, StatusTracker},
         utils::test::{fixture_path, temp_dir_path, temp_fixture_path, TEST_SMALL_JPEG, TEST_VC},
+        Error, FsyncPolicy, TimeStampPolicy,
     };
 
     use tempfile::tempdir;
 
     // example of random data structure as an assertion
-    #[derive(serde::Serialize)]
+    #[derive(serde::Serialize, serde::Deserialize)]
     struct MyStruct {
         l1: String,
         l2: u32,
@@ -652,6 +1513,507 @@ pub(crate) mod tests {
         assert!(ingredient.active_manifest().is_some());
     }
 
+    #[test]
+    fn test_claim_version_v2() {
+        let source_path = fixture_path(TEST_SMALL_JPEG);
+        let dir = tempdir().expect("temp dir");
+        let test_output = dir.path().join("claim_v2_test.jpg");
+        let (signer, _) = get_temp_signer(&dir.path());
+
+        let mut manifest = test_manifest();
+        manifest.set_claim_version(ClaimVersion::V2);
+        manifest
+            .add_gathered_assertion(&CreativeWork::new())
+            .expect("add_gathered_assertion");
+        manifest
+            .embed(&source_path, &test_output, &signer)
+            .expect("embed");
+
+        let manifest_store = crate::ManifestStore::from_file(&test_output).expect("from_file");
+        assert!(manifest_store.validation_status().is_none());
+
+        let manifest = manifest_store.get_active().expect("active manifest");
+        assert_eq!(manifest.claim_version(), ClaimVersion::V2);
+        let creative_work = manifest
+            .assertions()
+            .iter()
+            .find(|a| a.label == CreativeWork::LABEL)
+            .expect("creative work");
+        assert!(creative_work.is_gathered());
+    }
+
+    #[test]
+    fn test_claim_generator_icon() {
+        let source_path = fixture_path(TEST_SMALL_JPEG);
+        let dir = tempdir().expect("temp dir");
+        let test_output = dir.path().join("icon_test.jpg");
+        let (signer, _) = get_temp_signer(&dir.path());
+
+        let icon = b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>".to_vec();
+        let mut manifest = test_manifest();
+        manifest
+            .set_claim_generator_icon("image/svg+xml", icon.clone())
+            .expect("set_claim_generator_icon");
+        assert!(manifest
+            .set_claim_generator_icon("image/gif", Vec::new())
+            .is_err());
+        let store = manifest
+            .embed(&source_path, &test_output, &signer)
+            .expect("embed");
+        let icon_uri = store.provenance_claim().unwrap().claim_generator_icon();
+        assert!(icon_uri.unwrap().url().contains(labels::ICON));
+
+        let manifest_store = crate::ManifestStore::from_file(&test_output).expect("from_file");
+        assert!(manifest_store.validation_status().is_none());
+        let manifest = manifest_store.get_active().expect("active manifest");
+        assert_eq!(
+            manifest.claim_generator_icon(),
+            Some(("image/svg+xml", icon.as_slice()))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_from_manifest_store_json() {
+        let dir = tempdir().expect("temp dir");
+        let test_output = dir.path().join("json_test.jpg");
+        let (signer, _) = get_temp_signer(&dir.path());
+
+        let source_path = fixture_path("CA.jpg");
+        let original = crate::ManifestStore::from_file(&source_path).expect("from_file");
+        let original_manifest = original.get_active().expect("active manifest");
+
+        let mut manifest = Manifest::from_manifest_store_json(&original.to_string())
+            .expect("from_manifest_store_json");
+        assert!(manifest.signature_info().is_none());
+        let claim_generator = manifest.claim_generator().to_owned();
+        assert!(!claim_generator.contains(crate::NAME));
+        assert_eq!(
+            manifest.ingredients().len(),
+            original_manifest.ingredients().len()
+        );
+
+        // edit the manifest and sign it again
+        manifest
+            .add_labeled_assertion(
+                "org.test.edited",
+                &MyStruct {
+                    l1: "edited".to_owned(),
+                    l2: 1,
+                },
+            )
+            .expect("add_labeled_assertion");
+        manifest
+            .embed(&source_path, &test_output, &signer)
+            .expect("embed");
+
+        let manifest_store = crate::ManifestStore::from_file(&test_output).expect("from_file");
+        let manifest = manifest_store.get_active().expect("active manifest");
+        assert!(manifest.claim_generator().starts_with(&claim_generator));
+        for assertion in original_manifest.assertions() {
+            assert!(manifest
+                .assertions()
+                .iter()
+                .any(|a| a.label == assertion.label));
+        }
+        assert!(manifest
+            .assertions()
+            .iter()
+            .any(|a| a.label == "org.test.edited"));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_embed_to_streams() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+
+        let source = std::fs::read(fixture_path(TEST_SMALL_JPEG)).expect("read");
+        let mut asset = Vec::new();
+        let mut sidecar = Vec::new();
+        let mut report = Vec::new();
+
+        let mut manifest = test_manifest();
+        let store = manifest
+            .embed_to_streams(
+                "image/jpeg",
+                &mut source.as_slice(),
+                &signer,
+                &mut asset,
+                Some(&mut sidecar),
+                Some(&mut report),
+            )
+            .expect("embed_to_streams");
+        assert_eq!(manifest.asset().map(|a| a.title()), Some("untitled"));
+
+        let manifest_store =
+            crate::ManifestStore::from_bytes("image/jpeg", asset, true).expect("from_bytes");
+        assert!(manifest_store.validation_status().is_none());
+        assert_eq!(
+            manifest_store.active_label(),
+            store.provenance_label().as_deref()
+        );
+
+        let sidecar_store =
+            crate::ManifestStore::from_bytes("c2pa", sidecar, false).expect("from_bytes");
+        assert_eq!(sidecar_store.active_label(), manifest_store.active_label());
+
+        let report: serde_json::Value = serde_json::from_slice(&report).expect("report");
+        assert_eq!(
+            report["active_manifest"].as_str(),
+            manifest_store.active_label()
+        );
+
+        // any format with an asset handler can be embedded
+        let source = std::fs::read(fixture_path("sample1.docx")).expect("read");
+        let docx = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+        let mut asset = Vec::new();
+        test_manifest()
+            .embed_to_streams(
+                docx,
+                &mut source.as_slice(),
+                &signer,
+                &mut asset,
+                None,
+                None,
+            )
+            .expect("embed_to_streams");
+        let manifest_store =
+            crate::ManifestStore::from_bytes(docx, asset, true).expect("from_bytes");
+        assert!(manifest_store.validation_status().is_none());
+
+        assert!(matches!(
+            test_manifest().embed_to_streams(
+                "image/gif",
+                &mut source.as_slice(),
+                &signer,
+                &mut Vec::new(),
+                None,
+                None
+            ),
+            Err(Error::UnsupportedType)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_add_timestamp() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+        let output = dir.path().join("deferred.jpg");
+        let policy = TimeStampPolicy::new(vec!["http://127.0.0.1:1/tsa".to_owned()]);
+
+        let mut manifest = test_manifest();
+        manifest
+            .embed(&fixture_path(TEST_SMALL_JPEG), &output, &signer)
+            .expect("embed");
+        let signed = std::fs::read(&output).expect("read");
+
+        // an unreachable time authority leaves the asset unchanged
+        assert!(Manifest::add_timestamp(&output, &policy).is_err());
+        assert_eq!(std::fs::read(&output).expect("read"), signed);
+        let manifest_store = crate::ManifestStore::from_file(&output).expect("from_file");
+        assert!(manifest_store.validation_status().is_none());
+
+        // a manifest that already has a time-stamp cannot get another
+        let stamped = dir.path().join("stamped.jpg");
+        std::fs::copy(fixture_path("CA.jpg"), &stamped).expect("copy");
+        assert!(matches!(
+            Manifest::add_timestamp(&stamped, &policy),
+            Err(Error::CoseTimeStampExists)
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    fn test_add_timestamp_from_tsa() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+        let output = dir.path().join("deferred.jpg");
+        let tsa = crate::utils::test::TestTsa::start(1);
+        let policy = TimeStampPolicy::new(vec![tsa.url().to_owned()]);
+
+        let mut manifest = test_manifest();
+        manifest
+            .embed(&fixture_path(TEST_SMALL_JPEG), &output, &signer)
+            .expect("embed");
+        let info = crate::SigningInfo::from_file(&output).expect("signing info");
+        assert!(!info.time_stamped);
+
+        // the time-stamp is stored in the signature and the asset still validates
+        Manifest::add_timestamp(&output, &policy).expect("add_timestamp");
+        let manifest_store = crate::ManifestStore::from_file(&output).expect("from_file");
+        assert!(manifest_store.validation_status().is_none());
+        let info = crate::SigningInfo::from_file(&output).expect("signing info");
+        assert!(info.time_stamped);
+
+        assert!(matches!(
+            Manifest::add_timestamp(&output, &policy),
+            Err(Error::CoseTimeStampExists)
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "file_io", feature = "metadata_extraction"))]
+    fn test_embed_with_metadata_extraction() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+        let output = dir.path().join("extracted.jpg");
+
+        let mut manifest = test_manifest();
+        manifest.set_metadata_extraction(crate::MetadataExtractionOptions::default());
+        manifest
+            .embed(&fixture_path("earth_apollo17.jpg"), &output, &signer)
+            .expect("embed");
+
+        let manifest_store = crate::ManifestStore::from_file(&output).expect("from_file");
+        let manifest = manifest_store.get_active().expect("active manifest");
+        let exif: Value = manifest.find_assertion(labels::EXIF).expect("exif");
+        assert!(exif.get("@context").is_some());
+        assert!(exif.get("exif:GPSLatitude").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_embed_with_databoxes() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+        let output = dir.path().join("databoxes.jpg");
+
+        let model_card = b"{\"model\": \"example\"}".to_vec();
+        let mut manifest = test_manifest();
+        let uri = manifest
+            .add_databox_from_stream("application/json", &mut std::io::Cursor::new(&model_card))
+            .expect("add_databox_from_stream");
+        let duplicate = manifest
+            .add_databox("application/json", model_card.clone())
+            .expect("add_databox");
+        assert_eq!(uri, duplicate);
+        let other = manifest
+            .add_databox("text/plain", b"notes".to_vec())
+            .expect("add_databox");
+        assert_ne!(uri, other);
+        manifest
+            .add_labeled_assertion("org.cai.model", &serde_json::json!({ "card": uri }))
+            .expect("add_labeled_assertion");
+        manifest
+            .embed(&fixture_path("earth_apollo17.jpg"), &output, &signer)
+            .expect("embed");
+
+        let manifest_store = crate::ManifestStore::from_file(&output).expect("from_file");
+        assert!(manifest_store.validation_status().is_none());
+        let manifest = manifest_store.get_active().expect("active manifest");
+        let reference: Value = manifest.find_assertion("org.cai.model").expect("model");
+        let uri: HashedUri = serde_json::from_value(reference["card"].clone()).expect("uri");
+
+        let databox = manifest.databox(&uri).expect("databox");
+        assert_eq!(databox.format(), "application/json");
+        let mut data = Vec::new();
+        manifest
+            .databox_stream(&uri)
+            .expect("databox_stream")
+            .read_to_end(&mut data)
+            .expect("read");
+        assert_eq!(data, model_card);
+        assert_eq!(manifest.databox(&other).expect("databox").data(), b"notes");
+
+        // a reference with the wrong hash is rejected
+        let tampered = HashedUri::new(uri.url(), None, &other.hash());
+        assert!(matches!(
+            manifest.databox(&tampered),
+            Err(Error::HashMismatch(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_embed_leaves_no_temp_files() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+        let output_dir = tempdir().expect("temp dir");
+        let output = output_dir.path().join("in_place.jpg");
+        std::fs::copy(fixture_path(TEST_SMALL_JPEG), &output).expect("copy");
+
+        let mut manifest = test_manifest();
+        manifest.set_fsync_policy(FsyncPolicy::FileAndDirectory);
+        manifest.embed(&output, &output, &signer).expect("embed");
+
+        let manifest_store = crate::ManifestStore::from_file(&output).expect("from_file");
+        assert!(manifest_store.validation_status().is_none());
+        let files: Vec<_> = std::fs::read_dir(output_dir.path())
+            .expect("read_dir")
+            .map(|e| e.expect("entry").file_name())
+            .collect();
+        assert_eq!(files, vec![std::ffi::OsString::from("in_place.jpg")]);
+
+        // a failed embed does not create the target or leave temporary files
+        let bad_source = dir.path().join("bad.jpg");
+        std::fs::write(&bad_source, b"not a jpeg").expect("write");
+        let missing = output_dir.path().join("missing.jpg");
+        let mut manifest = test_manifest();
+        assert!(manifest.embed(&bad_source, &missing, &signer).is_err());
+        assert_eq!(
+            std::fs::read_dir(output_dir.path())
+                .expect("read_dir")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_claim_generator_hints() {
+        let dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&dir.path());
+        let source_path = fixture_path(TEST_SMALL_JPEG);
+        let first_output = dir.path().join("hints.jpg");
+        let second_output = dir.path().join("hints_resigned.jpg");
+
+        let mut manifest = test_manifest();
+        manifest
+            .set_claim_generator_hint("org.test.viewer", &"https://viewer.example.com")
+            .expect("set_claim_generator_hint")
+            .set_claim_generator_hint(
+                "org.test.vendor",
+                &MyStruct {
+                    l1: "vendor".to_owned(),
+                    l2: 2,
+                },
+            )
+            .expect("set_claim_generator_hint");
+        manifest
+            .embed(&source_path, &first_output, &signer)
+            .expect("embed");
+
+        let manifest_store = crate::ManifestStore::from_file(&first_output).expect("from_file");
+        let manifest = manifest_store.get_active().expect("active manifest");
+        let viewer: String = manifest
+            .claim_generator_hint("org.test.viewer")
+            .expect("viewer hint");
+        assert_eq!(viewer, "https://viewer.example.com");
+        let vendor: MyStruct = manifest
+            .claim_generator_hint("org.test.vendor")
+            .expect("vendor hint");
+        assert_eq!(vendor.l2, 2);
+        assert!(manifest.claim_generator_hint::<String>("missing").is_err());
+
+        // hints survive re-signing without repeating the library entry
+        let mut manifest = Manifest::from_manifest_store_json(&manifest_store.to_string())
+            .expect("from_manifest_store_json");
+        manifest
+            .embed(&source_path, &second_output, &signer)
+            .expect("embed");
+        let manifest_store = crate::ManifestStore::from_file(&second_output).expect("from_file");
+        let manifest = manifest_store.get_active().expect("active manifest");
+        let viewer: String = manifest
+            .claim_generator_hint("org.test.viewer")
+            .expect("viewer hint");
+        assert_eq!(viewer, "https://viewer.example.com");
+        let ua: String = manifest.claim_generator_hint(GH_UA).expect("ua hint");
+        assert_eq!(ua.matches(crate::NAME).count(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_estimate_embedded_size() {
+        let source_path = fixture_path(TEST_SMALL_JPEG);
+        let dir = tempdir().expect("temp dir");
+        let test_output = dir.path().join("estimate_test.jpg");
+        let (signer, _) = get_temp_signer(&dir.path());
+
+        let mut manifest = test_manifest();
+        manifest.add_ingredient(Ingredient::from_file(&source_path).expect("from_file"));
+
+        let estimate = manifest
+            .estimate_embedded_size(&source_path, &signer)
+            .expect("estimate");
+
+        manifest
+            .embed(&source_path, &test_output, &signer)
+            .expect("embed");
+        let jumbf = crate::jumbf_io::load_jumbf_from_file(&test_output).expect("load_jumbf");
+
+        assert!(estimate >= jumbf.len());
+        // the estimate only over counts the size of the data hash exclusion
+        assert!(estimate - jumbf.len() < 16);
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_embed_update() {
+        let source_path = fixture_path(TEST_SMALL_JPEG);
+        let dir = tempdir().expect("temp dir");
+        let signed_path = dir.path().join("update_source.jpg");
+        let update_path = dir.path().join("update_test.jpg");
+        let (signer, _) = get_temp_signer(&dir.path());
+
+        test_manifest()
+            .embed(&source_path, &signed_path, &signer)
+            .expect("embed");
+
+        // only metadata changes are allowed in update manifests
+        let mut manifest = test_manifest();
+        let mut actions = Actions::new();
+        actions.add_action(Action::new(c2pa_action::CROPPED));
+        manifest.add_assertion(&actions).expect("add_assertion");
+        assert!(matches!(
+            manifest.embed_update(&signed_path, &update_path, &signer),
+            Err(Error::UpdateManifestInvalid)
+        ));
+
+        let mut manifest = test_manifest();
+        let mut actions = Actions::new();
+        actions.add_action(Action::new(c2pa_action::EDITED_METADATA));
+        manifest.add_assertion(&actions).expect("add_assertion");
+        manifest
+            .embed_update(&signed_path, &update_path, &signer)
+            .expect("embed_update");
+
+        let mut report = crate::status_tracker::OneShotStatusTracker::new();
+        let store = Store::load_from_asset(&update_path, true, &mut report).expect("load");
+        let pc = store.provenance_claim().expect("provenance claim");
+        assert!(pc.update_manifest());
+        assert_eq!(pc.ingredient_assertions().len(), 1);
+        assert_eq!(store.claims().len(), 2);
+    }
+
+    // a generator that returns the same placeholder for any asset
+    struct FixedThumbnail;
+
+    impl crate::ThumbnailGenerator for FixedThumbnail {
+        fn generate(
+            &self,
+            _format: &str,
+            _data: &[u8],
+        ) -> crate::Result<Option<(String, Vec<u8>)>> {
+            Ok(Some(("image/png".to_owned(), b"thumbnail".to_vec())))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_thumbnail_generator() {
+        let source_path = fixture_path(TEST_SMALL_JPEG);
+
+        let mut manifest = test_manifest();
+        manifest.set_thumbnail_generator(std::sync::Arc::new(FixedThumbnail));
+        manifest.set_asset_from_path(&source_path);
+        assert_eq!(
+            manifest.asset().unwrap().thumbnail(),
+            Some(("image/png", b"thumbnail".as_ref()))
+        );
+
+        let options = crate::IngredientOptions {
+            thumbnail_generator: Some(std::sync::Arc::new(FixedThumbnail)),
+            ..Default::default()
+        };
+        let ingredient = Ingredient::from_file_with_options(&source_path, &options).unwrap();
+        assert_eq!(
+            ingredient.thumbnail(),
+            Some(("image/png", b"thumbnail".as_ref()))
+        );
+    }
+
     #[test]
     #[cfg(feature = "file_io")]
     /// test assertion validation on actions, should generate an error
@@ -682,6 +2044,56 @@ pub(crate) mod tests {
         assert!(result.is_err())
     }
 
+    #[test]
+    fn test_assertion_creation_time() {
+        const CREATED: &str = "2022-06-01T12:00:00.000Z";
+        let mut manifest = test_manifest();
+        let mut actions = Actions::new();
+        actions.add_action(Action::new(c2pa_action::EDITED));
+        manifest
+            .add_assertion_with_time(&actions, CREATED)
+            .expect("add_assertion_with_time");
+
+        let store = manifest.to_store().expect("to_store");
+        let label = store.provenance_label().unwrap();
+        let manifest = Manifest::from_store(&store, &label).expect("from_store");
+
+        // the metadata is reported on the assertion rather than as its own assertion
+        assert_eq!(manifest.assertions().len(), 1);
+        assert_eq!(manifest.assertions()[0].created(), Some(CREATED));
+    }
+
+    #[test]
+    fn test_salt_policy() {
+        let mut manifest = test_manifest();
+        let mut actions = Actions::new();
+        actions.add_action(Action::new(c2pa_action::EDITED));
+        manifest.add_assertion(&actions).expect("add_assertion");
+        manifest
+            .add_labeled_assertion("org.cai.test", &serde_json::json!({ "a": 1 }))
+            .expect("add_labeled_assertion");
+
+        let mut policy = SaltPolicy::labels(&["org.cai.test"]);
+        policy.set_deterministic(b"test seed");
+        manifest.set_salt_policy(policy);
+
+        let hashes = |store: &Store| -> Vec<(String, Vec<u8>, bool)> {
+            let claim = store.provenance_claim().unwrap();
+            claim
+                .claim_assertion_store()
+                .iter()
+                .map(|a| (a.label(), a.hash().to_vec(), a.salt().is_some()))
+                .collect()
+        };
+
+        let first = hashes(&manifest.to_store().expect("to_store"));
+        let second = hashes(&manifest.to_store().expect("to_store"));
+        assert_eq!(first, second);
+        for (label, _, salted) in first {
+            assert_eq!(salted, label == "org.cai.test", "{}", label);
+        }
+    }
+
     #[test]
     #[cfg(feature = "file_io")]
     fn test_verifiable_credential() {
@@ -760,10 +2172,8 @@ pub(crate) mod tests {
             .set_parent(Ingredient::from_file(&output).expect("from_file"))
             .expect("set_parent");
 
-        // todo: add a test to validate that actions assertions cannot be redacted
-        // let mut actions = Actions::new();
-        // actions.add_action(Action::new(C2PA_ACTION_EDITED).parameters("gaussian_blur"));
-        // ws.add_assertion("c2pa.actions", &actions).expect("add_assertion"); // must use .get() with Actions
+        // actions assertions cannot be redacted
+        assert!(manifest2.add_redaction(labels::ACTIONS).is_err());
 
         // redact the assertion
         manifest2
@@ -786,10 +2196,15 @@ pub(crate) mod tests {
 
         assert!(claim2.redactions().is_some());
         assert!(!claim2.redactions().unwrap().is_empty());
-        assert!(!report.get_log().is_empty());
+        assert!(report_split_errors(report.get_log_mut()).is_empty());
         let redacted_uri = &claim2.redactions().unwrap()[0];
 
         let claim1 = store3.get_claim(&claim1_label).unwrap();
         assert!(claim1.get_claim_assertion(redacted_uri, 0).is_none());
+
+        // the redaction is recorded as an action
+        let manifest3 = Manifest::from_store(&store3, &claim2.label()).expect("from_store");
+        let actions: Actions = manifest3.find_assertion(Actions::LABEL).expect("actions");
+        assert_eq!(actions.actions()[0].action(), c2pa_action::REDACTED);
     }
 }
@@ -0,0 +1,278 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Standalone `.c2pa` manifest store files, kept separately from the assets they describe.
+
+use std::io::Read;
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use crate::{
+    jumbf_io::load_jumbf_from_memory,
+    status_tracker::{DetailedStatusTracker, OneShotStatusTracker},
+    store::Store,
+    ManifestStore, Result, VerifyOptions,
+};
+#[cfg(feature = "file_io")]
+use crate::{
+    jumbf_io::{load_jumbf_from_file, save_jumbf_to_file},
+    status_tracker::StatusTracker,
+    utils::atomic_file::write_atomically,
+    validation_status::ValidationStatusCode,
+    Error, FsyncPolicy,
+};
+
+/// A manifest store held as a standalone `.c2pa` file, with no asset.
+///
+/// Archival systems keep manifests apart from the essence files they describe. An archive
+/// is created by signing a [`Manifest`](crate::Manifest) with
+/// [`Manifest::sign_archive`](crate::Manifest::sign_archive) or by extracting the manifest
+/// store embedded in an asset, and is validated against an asset supplied separately.
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::{ManifestArchive, VerifyOptions};
+/// # fn main() -> Result<()> {
+/// let asset = std::fs::read("tests/fixtures/CA.jpg")?;
+/// let archive = ManifestArchive::from_asset_bytes("jpg", &asset)?;
+/// let manifest_store = archive.verify_asset_bytes(&asset, &VerifyOptions::default())?;
+/// assert!(manifest_store.validation_status().is_none());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManifestArchive {
+    jumbf: Vec<u8>,
+}
+
+impl ManifestArchive {
+    /// Creates an archive from the bytes of a `.c2pa` file.
+    /// Returns an error if the bytes are not a manifest store.
+    pub fn from_bytes(jumbf: Vec<u8>) -> Result<Self> {
+        Store::from_jumbf(&jumbf, &mut OneShotStatusTracker::new())?;
+        Ok(ManifestArchive { jumbf })
+    }
+
+    /// Reads an archive from a stream holding a `.c2pa` file
+    pub fn from_stream(stream: &mut dyn Read) -> Result<Self> {
+        let mut jumbf = Vec::new();
+        stream.read_to_end(&mut jumbf)?;
+        Self::from_bytes(jumbf)
+    }
+
+    /// Extracts the manifest store embedded in an asset held in memory.
+    /// `format` is the MIME type or extension of the asset.
+    pub fn from_asset_bytes(format: &str, data: &[u8]) -> Result<Self> {
+        Self::from_bytes(load_jumbf_from_memory(format, data)?)
+    }
+
+    /// Reads an archive from the `.c2pa` file at `path`
+    #[cfg(feature = "file_io")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(std::fs::read(path)?)
+    }
+
+    /// Extracts the manifest store embedded in the asset at `path`
+    #[cfg(feature = "file_io")]
+    pub fn from_asset_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_bytes(load_jumbf_from_file(path.as_ref())?)
+    }
+
+    /// Returns the bytes of the `.c2pa` file
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.jumbf
+    }
+
+    /// Returns the bytes of the `.c2pa` file, consuming the archive
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.jumbf
+    }
+
+    /// Writes the archive to a `.c2pa` file at `path`.
+    /// The file is only replaced once it is completely written.
+    #[cfg(feature = "file_io")]
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        write_atomically(path.as_ref(), FsyncPolicy::default(), |temp_path| {
+            std::fs::write(temp_path, &self.jumbf).map_err(Into::into)
+        })
+    }
+
+    /// Validates the archive against the asset it describes, held in memory
+    pub fn verify_asset_bytes(
+        &self,
+        asset: &[u8],
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+        let store = Store::load_from_archive(&self.jumbf, asset, options, &mut validation_log)?;
+        Ok(ManifestStore::from_store(&store, &mut validation_log))
+    }
+
+    /// Validates the archive against the asset it describes, read from `asset`.
+    /// The asset is read into memory to be hashed.
+    pub fn verify_asset(
+        &self,
+        asset: &mut dyn Read,
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut data = Vec::new();
+        asset.read_to_end(&mut data)?;
+        self.verify_asset_bytes(&data, options)
+    }
+
+    /// Validates the archive against the members of the collection it describes,
+    /// given as pairs of member URI and content.
+    /// The active manifest must bind the members with a collection hash.
+    pub fn verify_collection(
+        &self,
+        members: &[(&str, &[u8])],
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+        let store =
+            Store::load_from_collection(&self.jumbf, members, options, &mut validation_log)?;
+        Ok(ManifestStore::from_store(&store, &mut validation_log))
+    }
+
+    /// Embeds the archive in a copy of the asset at `asset_path` written to `output_path`.
+    ///
+    /// The manifest store is embedded unchanged, so its hard binding must still match once
+    /// embedded. This is the case for an archive extracted from an asset whose manifest
+    /// store was later removed, which restores the original asset.
+    ///
+    /// An archive signed for an asset with no manifest store embedded, such as one from
+    /// [`Manifest::sign_archive`](crate::Manifest::sign_archive), hashes the asset without
+    /// room for the manifest store and does not match once embedded. Returns
+    /// [`Error::ArchiveNotEmbeddable`] for these, and `output_path` is left unchanged.
+    #[cfg(feature = "file_io")]
+    pub fn embed_in_file<P: AsRef<Path>>(&self, asset_path: P, output_path: P) -> Result<()> {
+        let asset_path = asset_path.as_ref();
+        write_atomically(output_path.as_ref(), FsyncPolicy::default(), |temp_path| {
+            save_jumbf_to_file(&self.jumbf, asset_path, Some(temp_path))?;
+
+            // check the hard bindings before the embedded asset replaces the output
+            let mut validation_log = DetailedStatusTracker::new();
+            Store::load_from_asset(temp_path, true, &mut validation_log)?;
+            let mismatch = validation_log.get_log().iter().any(|item| {
+                matches!(
+                    item.validation_status,
+                    Some(ValidationStatusCode::AssertionDataHashMismatch)
+                        | Some(ValidationStatusCode::AssertionCollectionHashMismatch)
+                )
+            });
+            if mismatch {
+                Err(Error::ArchiveNotEmbeddable)
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use tempfile::tempdir;
+
+    use super::*;
+    use crate::{
+        openssl::temp_signer::get_temp_signer,
+        utils::test::{fixture_path, temp_dir_path},
+        Manifest,
+    };
+
+    #[test]
+    fn test_sign_archive() {
+        let temp_dir = tempdir().unwrap();
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let asset_path = fixture_path("earth_apollo17.jpg");
+
+        let mut manifest = Manifest::new("test".to_owned());
+        let archive = manifest.sign_archive(&asset_path, &signer).unwrap();
+
+        // the archive hashes the asset without its manifest store, so it cannot be embedded
+        let output_path = temp_dir_path(&temp_dir, "embedded.jpg");
+        assert!(matches!(
+            archive.embed_in_file(&asset_path, &output_path),
+            Err(Error::ArchiveNotEmbeddable)
+        ));
+        assert!(!output_path.exists());
+
+        let archive_path = temp_dir_path(&temp_dir, "earth_apollo17.c2pa");
+        archive.write_to_file(&archive_path).unwrap();
+        let archive = ManifestArchive::from_file(&archive_path).unwrap();
+
+        let mut asset = std::fs::read(&asset_path).unwrap();
+        let manifest_store = archive
+            .verify_asset(&mut asset.as_slice(), &VerifyOptions::default())
+            .unwrap();
+        assert!(manifest_store.validation_status().is_none());
+        assert!(manifest_store.get_active().is_some());
+
+        // the archive does not match a changed asset
+        let last = asset.len() - 3;
+        asset[last] ^= 0xff;
+        let manifest_store = archive
+            .verify_asset_bytes(&asset, &VerifyOptions::default())
+            .unwrap();
+        assert!(manifest_store
+            .validation_status()
+            .unwrap()
+            .iter()
+            .any(|s| s.code() == &ValidationStatusCode::AssertionDataHashMismatch));
+
+        // a data hash does not bind the members of a collection
+        let manifest_store = archive
+            .verify_collection(
+                &[("earth_apollo17.jpg", asset.as_slice())],
+                &VerifyOptions::default(),
+            )
+            .unwrap();
+        assert!(manifest_store
+            .validation_status()
+            .unwrap()
+            .iter()
+            .any(|s| s.code() == &ValidationStatusCode::HardBindingsMissing));
+
+        assert!(ManifestArchive::from_bytes(asset).is_err());
+    }
+
+    #[test]
+    fn test_archive_from_asset() {
+        let temp_dir = tempdir().unwrap();
+        let asset_path = fixture_path("CA.jpg");
+
+        let archive = ManifestArchive::from_asset_file(&asset_path).unwrap();
+        let asset = std::fs::read(&asset_path).unwrap();
+        assert_eq!(
+            archive,
+            ManifestArchive::from_asset_bytes("image/jpeg", &asset).unwrap()
+        );
+        let manifest_store = archive
+            .verify_asset_bytes(&asset, &VerifyOptions::default())
+            .unwrap();
+        assert!(manifest_store.validation_status().is_none());
+
+        // embedding the archive in the asset it came from gives the same manifest store
+        let output_path = temp_dir_path(&temp_dir, "embedded.jpg");
+        archive.embed_in_file(&asset_path, &output_path).unwrap();
+        assert_eq!(
+            ManifestArchive::from_asset_file(&output_path).unwrap(),
+            archive
+        );
+    }
+}
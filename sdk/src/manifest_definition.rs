@@ -0,0 +1,467 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Declarative manifest definitions.
+//!
+//! A [`ManifestDefinition`] describes the manifest to sign in JSON: the title, assertions
+//! and ingredients of the asset, with ingredients and thumbnails given as references to
+//! files. Tools that do not link against Rust can write a definition and have it signed
+//! in one call with [`embed_from_json`].
+
+#[cfg(feature = "file_io")]
+use std::sync::Arc;
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    assertions::{labels, Actions},
+    Error, Result,
+};
+#[cfg(feature = "file_io")]
+use crate::{utils::thumbnail::ThumbnailGenerator, Ingredient, Manifest, Signer};
+
+/// A file referenced by a manifest definition
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ResourceRef {
+    /// The MIME type or extension of the file, taken from the file name when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The path of the file, relative to the directory of the definition and inside it
+    pub path: PathBuf,
+}
+
+#[cfg(feature = "file_io")]
+impl ResourceRef {
+    // read the MIME type and bytes of the file
+    fn load(&self, base_dir: &Path, json_path: &str) -> Result<(String, Vec<u8>)> {
+        let extension = match &self.format {
+            Some(format) => format.to_lowercase(),
+            None => self
+                .path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+        };
+        let data = std::fs::read(base_dir.join(&self.path))
+            .map_err(|e| invalid(&format!("{}.path", json_path), &e.to_string()))?;
+        Ok((Ingredient::format_from_extension(&extension), data))
+    }
+}
+
+/// An ingredient of a manifest definition
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct IngredientDefinition {
+    /// The path of the ingredient asset, relative to the directory of the definition and
+    /// inside it
+    pub path: PathBuf,
+    /// The title of the ingredient, the file name when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// True if the asset was derived from this ingredient, at most one ingredient is a parent
+    #[serde(default)]
+    pub is_parent: bool,
+    /// The thumbnail of the ingredient, replacing the one generated from the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<ResourceRef>,
+}
+
+/// An assertion of a manifest definition
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct AssertionDefinition {
+    /// The assertion label, such as `c2pa.actions` or `stds.schema-org.CreativeWork`
+    pub label: String,
+    /// The assertion data, in the JSON form of the assertion
+    pub data: Value,
+}
+
+/// The JSON description of a manifest to sign.
+///
+/// ```json
+/// {
+///     "claim_generator": "my_app/1.0",
+///     "title": "sunset.jpg",
+///     "thumbnail": { "path": "sunset_thumb.jpg" },
+///     "ingredients": [ { "path": "original.jpg", "is_parent": true } ],
+///     "assertions": [
+///         {
+///             "label": "c2pa.actions",
+///             "data": { "actions": [ { "action": "c2pa.edited" } ] }
+///         }
+///     ]
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ManifestDefinition {
+    /// Optional prefix added to the generated manifest label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<String>,
+    /// A User Agent formatted string identifying the software that produced the claim
+    pub claim_generator: String,
+    /// The title of the asset, the file name of the signed asset when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The thumbnail of the asset, replacing the one generated from the asset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<ResourceRef>,
+    /// The ingredients of the asset
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ingredients: Vec<IngredientDefinition>,
+    /// The assertions of the manifest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<AssertionDefinition>,
+}
+
+fn invalid(path: &str, reason: &str) -> Error {
+    Error::ManifestDefinitionInvalid {
+        path: path.to_owned(),
+        reason: reason.to_owned(),
+    }
+}
+
+// referenced files must stay inside the directory of the definition
+fn check_path(path: &Path, json_path: &str) -> Result<()> {
+    if path.as_os_str().is_empty() {
+        return Err(invalid(json_path, "is empty"));
+    }
+    if !path
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(invalid(
+            json_path,
+            "must be a relative path without `..` components",
+        ));
+    }
+    Ok(())
+}
+
+impl ManifestDefinition {
+    /// Parse and validate a definition.
+    ///
+    /// Returns [`Error::ManifestDefinitionInvalid`] locating the first problem found,
+    /// such as a misspelled field or an actions assertion that does not parse.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let definition: ManifestDefinition =
+            serde_json::from_str(json).map_err(|e| invalid("$", &e.to_string()))?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Check that the definition describes a manifest that can be signed
+    pub fn validate(&self) -> Result<()> {
+        if self.claim_generator.is_empty() || self.claim_generator.contains(char::is_whitespace) {
+            return Err(invalid(
+                "$.claim_generator",
+                "must be a non-empty product/version string without spaces",
+            ));
+        }
+
+        if self.ingredients.iter().filter(|i| i.is_parent).count() > 1 {
+            return Err(invalid(
+                "$.ingredients",
+                "only one ingredient can be the parent",
+            ));
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            check_path(&thumbnail.path, "$.thumbnail.path")?;
+        }
+        for (index, ingredient) in self.ingredients.iter().enumerate() {
+            let path = format!("$.ingredients[{}]", index);
+            check_path(&ingredient.path, &format!("{}.path", path))?;
+            if let Some(thumbnail) = &ingredient.thumbnail {
+                check_path(&thumbnail.path, &format!("{}.thumbnail.path", path))?;
+            }
+        }
+
+        for (index, assertion) in self.assertions.iter().enumerate() {
+            let path = format!("$.assertions[{}]", index);
+            let label = assertion.label.as_str();
+
+            // these are made from the asset and ingredients when signing
+            if label.is_empty() {
+                return Err(invalid(&format!("{}.label", path), "is empty"));
+            } else if label.starts_with("c2pa.hash")
+                || label.starts_with(labels::INGREDIENT)
+                || label.starts_with(labels::CLAIM_THUMBNAIL)
+            {
+                return Err(invalid(
+                    &format!("{}.label", path),
+                    &format!("{} assertions are added when signing", label),
+                ));
+            }
+
+            if label.starts_with(labels::ACTIONS) {
+                Actions::from_json_value(&assertion.data)
+                    .and_then(|actions| actions.validate())
+                    .map_err(|e| invalid(&format!("{}.data", path), &e.to_string()))?;
+            } else if !assertion.data.is_object() {
+                return Err(invalid(&format!("{}.data", path), "must be a JSON object"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create the [`Manifest`] described by the definition, reading the referenced files
+    /// relative to `base_dir`
+    #[cfg(feature = "file_io")]
+    pub fn to_manifest(&self, base_dir: &Path) -> Result<Manifest> {
+        self.validate()?;
+
+        let mut manifest = Manifest::new(self.claim_generator.clone());
+        manifest.vendor = self.vendor.clone();
+
+        // the title of an asset is kept when it is signed
+        if let Some(title) = &self.title {
+            manifest.set_asset(Ingredient::new(title.as_str(), "", ""));
+        }
+        if let Some(thumbnail) = &self.thumbnail {
+            let (format, data) = thumbnail.load(base_dir, "$.thumbnail")?;
+            manifest.set_thumbnail_generator(Arc::new(FixedThumbnail { format, data }));
+        }
+
+        for (index, definition) in self.ingredients.iter().enumerate() {
+            let path = format!("$.ingredients[{}]", index);
+            let mut ingredient = Ingredient::from_file(base_dir.join(&definition.path))
+                .map_err(|e| invalid(&format!("{}.path", path), &e.to_string()))?;
+            if let Some(title) = &definition.title {
+                ingredient.set_title(title.as_str());
+            }
+            if let Some(thumbnail) = &definition.thumbnail {
+                let (format, data) = thumbnail.load(base_dir, &format!("{}.thumbnail", path))?;
+                ingredient.set_thumbnail(format, data);
+            }
+
+            if definition.is_parent {
+                manifest.set_parent(ingredient)?;
+            } else {
+                manifest.add_ingredient(ingredient);
+            }
+        }
+
+        for assertion in &self.assertions {
+            manifest.add_labeled_assertion(&assertion.label, &assertion.data)?;
+        }
+
+        Ok(manifest)
+    }
+
+    /// Sign the manifest described by the definition into `dest_path`, a copy of
+    /// `source_path`, reading the referenced files relative to `base_dir`.
+    ///
+    /// Returns the label of the signed manifest.
+    #[cfg(feature = "file_io")]
+    pub fn embed(
+        &self,
+        base_dir: &Path,
+        source_path: &Path,
+        dest_path: &Path,
+        signer: &dyn Signer,
+    ) -> Result<String> {
+        let mut manifest = self.to_manifest(base_dir)?;
+        let store = manifest.embed(source_path, dest_path, signer)?;
+        store.provenance_label().ok_or(Error::ClaimEncoding)
+    }
+}
+
+/// Parse the JSON manifest definition `json` and sign the manifest it describes into
+/// `dest_path`, a copy of `source_path`. Files referenced by the definition are read
+/// relative to `base_dir`.
+///
+/// Returns the label of the signed manifest, or [`Error::ManifestDefinitionInvalid`]
+/// if the definition is not valid.
+#[cfg(feature = "file_io")]
+pub fn embed_from_json(
+    json: &str,
+    base_dir: &Path,
+    source_path: &Path,
+    dest_path: &Path,
+    signer: &dyn Signer,
+) -> Result<String> {
+    ManifestDefinition::from_json(json)?.embed(base_dir, source_path, dest_path, signer)
+}
+
+// supplies the thumbnail referenced by a definition instead of generating one
+#[cfg(feature = "file_io")]
+struct FixedThumbnail {
+    format: String,
+    data: Vec<u8>,
+}
+
+#[cfg(feature = "file_io")]
+impl ThumbnailGenerator for FixedThumbnail {
+    fn generate(&self, _format: &str, _data: &[u8]) -> Result<Option<(String, Vec<u8>)>> {
+        Ok(Some((self.format.clone(), self.data.clone())))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::panic)]
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    // the JSON path of the error of an invalid definition
+    fn error_path(json: &str) -> String {
+        match ManifestDefinition::from_json(json) {
+            Err(Error::ManifestDefinitionInvalid { path, .. }) => path,
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_definition_errors() {
+        let definition = ManifestDefinition::from_json(
+            r#"{
+                "claim_generator": "test_app/1.0",
+                "title": "image.jpg",
+                "ingredients": [ { "path": "parent.jpg", "is_parent": true } ],
+                "assertions": [
+                    {
+                        "label": "c2pa.actions",
+                        "data": { "actions": [ { "action": "c2pa.edited" } ] }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(definition.title.as_deref(), Some("image.jpg"));
+        assert!(definition.ingredients[0].is_parent);
+
+        // misspelled fields are reported rather than ignored
+        let err = ManifestDefinition::from_json(r#"{ "claim_generator": "a/1", "titel": "x" }"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown field `titel`"));
+
+        assert_eq!(
+            error_path(r#"{ "claim_generator": "my app" }"#),
+            "$.claim_generator"
+        );
+        assert_eq!(
+            error_path(
+                r#"{ "claim_generator": "a/1", "ingredients": [
+                    { "path": "a.jpg", "is_parent": true }, { "path": "b.jpg", "is_parent": true }
+                ] }"#
+            ),
+            "$.ingredients"
+        );
+
+        // referenced files cannot be outside the directory of the definition
+        assert_eq!(
+            error_path(r#"{ "claim_generator": "a/1", "thumbnail": { "path": "/etc/passwd" } }"#),
+            "$.thumbnail.path"
+        );
+        assert_eq!(
+            error_path(
+                r#"{ "claim_generator": "a/1", "ingredients": [
+                    { "path": "a.jpg", "thumbnail": { "path": "thumbs/../../b.jpg" } }
+                ] }"#
+            ),
+            "$.ingredients[0].thumbnail.path"
+        );
+        assert_eq!(
+            error_path(
+                r#"{ "claim_generator": "a/1", "assertions": [
+                    { "label": "my.assertion", "data": {} },
+                    { "label": "c2pa.hash.data", "data": {} }
+                ] }"#
+            ),
+            "$.assertions[1].label"
+        );
+        assert_eq!(
+            error_path(
+                r#"{ "claim_generator": "a/1", "assertions": [
+                    { "label": "c2pa.actions", "data": { "actions": [] } }
+                ] }"#
+            ),
+            "$.assertions[0].data"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+    fn test_embed_from_json() {
+        use crate::{
+            openssl::temp_signer::get_temp_signer,
+            utils::test::{fixture_path, TEST_SMALL_JPEG},
+            Reader,
+        };
+
+        let json = r#"{
+            "claim_generator": "test_app/1.0",
+            "title": "declared.jpg",
+            "thumbnail": { "path": "earth_apollo17.jpg" },
+            "ingredients": [ { "path": "CA.jpg", "title": "parent", "is_parent": true } ],
+            "assertions": [
+                {
+                    "label": "c2pa.actions",
+                    "data": { "actions": [ { "action": "c2pa.edited" } ] }
+                },
+                { "label": "org.example.custom", "data": { "value": 1 } }
+            ]
+        }"#;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join("signed.jpg");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let base_dir = fixture_path(TEST_SMALL_JPEG)
+            .parent()
+            .unwrap()
+            .to_path_buf();
+
+        let label = embed_from_json(
+            json,
+            &base_dir,
+            &fixture_path(TEST_SMALL_JPEG),
+            &dest,
+            &signer,
+        )
+        .unwrap();
+
+        let reader = Reader::from_file(&dest).unwrap();
+        let manifest = reader.active_manifest().unwrap();
+        assert_eq!(reader.manifest_store().active_label(), Some(label.as_str()));
+        assert_eq!(manifest.asset().unwrap().title(), "declared.jpg");
+        assert_eq!(manifest.ingredients()[0].title(), "parent");
+        assert!(manifest.ingredients()[0].is_parent());
+        assert!(manifest
+            .assertions()
+            .iter()
+            .any(|a| a.label == "org.example.custom"));
+
+        // references are resolved against the base directory
+        let missing = embed_from_json(
+            json,
+            temp_dir.path(),
+            &fixture_path(TEST_SMALL_JPEG),
+            &dest,
+            &signer,
+        );
+        assert!(matches!(
+            missing,
+            Err(Error::ManifestDefinitionInvalid { path, .. }) if path == "$.thumbnail.path"
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Compares two manifest stores, for regression testing and interop triage.
+
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{manifest_store_report::ManifestReport, ManifestStoreReport, Result};
+
+/// A value that was added, removed or changed
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ValueChange {
+    /// Path of the value, its keys and array indexes separated by `/`
+    pub path: String,
+    /// The value before, `None` if it was added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    /// The value after, `None` if it was removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+impl std::fmt::Display for ValueChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.before.as_ref(), self.after.as_ref()) {
+            (None, Some(after)) => write!(f, "+ {}: {}", self.path, after),
+            (Some(before), None) => write!(f, "- {}: {}", self.path, before),
+            (Some(before), Some(after)) => write!(f, "~ {}: {} -> {}", self.path, before, after),
+            (None, None) => write!(f, "  {}", self.path),
+        }
+    }
+}
+
+/// The differences between two manifests
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ManifestDiff {
+    /// Label of the manifest before
+    pub before_label: String,
+    /// Label of the manifest after
+    pub after_label: String,
+    /// Labels of the assertions only found after
+    pub added_assertions: Vec<String>,
+    /// Labels of the assertions only found before
+    pub removed_assertions: Vec<String>,
+    /// Changes within assertions found in both, their paths start with the assertion label
+    pub assertion_changes: Vec<ValueChange>,
+    /// Changes to the claim
+    pub claim_changes: Vec<ValueChange>,
+    /// Changes to the signature fields: `alg`, `issuer`, `time` and `cosigners`
+    pub signature_changes: Vec<ValueChange>,
+}
+
+impl ManifestDiff {
+    fn new(
+        before_label: &str,
+        before: &ManifestReport,
+        after_label: &str,
+        after: &ManifestReport,
+    ) -> Self {
+        let mut diff = ManifestDiff {
+            before_label: before_label.to_owned(),
+            after_label: after_label.to_owned(),
+            ..Default::default()
+        };
+
+        let mut labels: Vec<&String> = before
+            .assertion_store
+            .keys()
+            .chain(after.assertion_store.keys())
+            .collect();
+        labels.sort();
+        labels.dedup();
+        for label in labels {
+            match (
+                before.assertion_store.get(label),
+                after.assertion_store.get(label),
+            ) {
+                (Some(before), Some(after)) => {
+                    diff_values(label, before, after, &mut diff.assertion_changes)
+                }
+                (Some(_), None) => diff.removed_assertions.push(label.clone()),
+                (None, Some(_)) => diff.added_assertions.push(label.clone()),
+                (None, None) => (),
+            }
+        }
+
+        diff_values("", &before.claim, &after.claim, &mut diff.claim_changes);
+
+        let before_signature = serde_json::to_value(&before.signature).unwrap_or_default();
+        let after_signature = serde_json::to_value(&after.signature).unwrap_or_default();
+        diff_values(
+            "",
+            &before_signature,
+            &after_signature,
+            &mut diff.signature_changes,
+        );
+
+        diff
+    }
+
+    /// Returns true if the manifests do not differ
+    pub fn is_empty(&self) -> bool {
+        self.added_assertions.is_empty()
+            && self.removed_assertions.is_empty()
+            && self.assertion_changes.is_empty()
+            && self.claim_changes.is_empty()
+            && self.signature_changes.is_empty()
+    }
+}
+
+impl std::fmt::Display for ManifestDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.before_label == self.after_label {
+            writeln!(f, "manifest {}", self.before_label)?;
+        } else {
+            writeln!(f, "manifest {} -> {}", self.before_label, self.after_label)?;
+        }
+        for label in &self.added_assertions {
+            writeln!(f, "  + assertion {}", label)?;
+        }
+        for label in &self.removed_assertions {
+            writeln!(f, "  - assertion {}", label)?;
+        }
+        for change in &self.assertion_changes {
+            writeln!(f, "  assertion {}", change)?;
+        }
+        for change in &self.claim_changes {
+            writeln!(f, "  claim {}", change)?;
+        }
+        for change in &self.signature_changes {
+            writeln!(f, "  signature {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+/// The differences between two manifest stores.
+///
+/// The active manifests are compared with each other, even if their labels differ,
+/// and the other manifests are compared with the manifest of the same label.
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::{ManifestStoreDiff, ManifestStoreReport};
+/// # fn main() -> Result<()> {
+/// let before = ManifestStoreReport::from_bytes("jpg", &std::fs::read("tests/fixtures/C.jpg")?)?;
+/// let after = ManifestStoreReport::from_bytes("jpg", &std::fs::read("tests/fixtures/CA.jpg")?)?;
+/// let diff = ManifestStoreDiff::new(&before, &after);
+/// println!("{}", diff);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ManifestStoreDiff {
+    /// Labels of the manifests only found after
+    pub added_manifests: Vec<String>,
+    /// Labels of the manifests only found before
+    pub removed_manifests: Vec<String>,
+    /// The manifests found in both that differ, the active manifests first
+    pub manifests: Vec<ManifestDiff>,
+}
+
+impl ManifestStoreDiff {
+    /// Compares the manifest stores reported by `before` and `after`
+    pub fn new(before: &ManifestStoreReport, after: &ManifestStoreReport) -> Self {
+        let mut pairs = Vec::new();
+        if let (Some(before_label), Some(after_label)) = (
+            before.active_manifest.as_ref(),
+            after.active_manifest.as_ref(),
+        ) {
+            pairs.push((before_label.clone(), after_label.clone()));
+        }
+
+        let mut before_labels: Vec<&String> = before.manifests.keys().collect();
+        before_labels.sort();
+        for label in before_labels {
+            let paired = pairs.iter().any(|(b, a)| b == label || a == label);
+            if !paired && after.manifests.contains_key(label) {
+                pairs.push((label.clone(), label.clone()));
+            }
+        }
+
+        let mut diff = ManifestStoreDiff::default();
+        for label in before.manifests.keys() {
+            if !pairs.iter().any(|(b, _)| b == label) {
+                diff.removed_manifests.push(label.clone());
+            }
+        }
+        for label in after.manifests.keys() {
+            if !pairs.iter().any(|(_, a)| a == label) {
+                diff.added_manifests.push(label.clone());
+            }
+        }
+        diff.removed_manifests.sort();
+        diff.added_manifests.sort();
+
+        for (before_label, after_label) in pairs {
+            if let (Some(before_manifest), Some(after_manifest)) = (
+                before.manifests.get(&before_label),
+                after.manifests.get(&after_label),
+            ) {
+                let manifest_diff =
+                    ManifestDiff::new(&before_label, before_manifest, &after_label, after_manifest);
+                if !manifest_diff.is_empty() {
+                    diff.manifests.push(manifest_diff);
+                }
+            }
+        }
+        diff
+    }
+
+    /// Compares the manifest stores of the assets at `before` and `after`
+    #[cfg(feature = "file_io")]
+    pub fn from_files<P: AsRef<Path>>(before: P, after: P) -> Result<Self> {
+        Ok(Self::new(
+            &ManifestStoreReport::from_file(before)?,
+            &ManifestStoreReport::from_file(after)?,
+        ))
+    }
+
+    /// Compares the manifest stores of two assets held in memory
+    pub fn from_bytes(
+        before_format: &str,
+        before: &[u8],
+        after_format: &str,
+        after: &[u8],
+    ) -> Result<Self> {
+        Ok(Self::new(
+            &ManifestStoreReport::from_bytes(before_format, before)?,
+            &ManifestStoreReport::from_bytes(after_format, after)?,
+        ))
+    }
+
+    /// Returns true if the manifest stores do not differ
+    pub fn is_empty(&self) -> bool {
+        self.added_manifests.is_empty()
+            && self.removed_manifests.is_empty()
+            && self.manifests.is_empty()
+    }
+}
+
+impl std::fmt::Display for ManifestStoreDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for label in &self.added_manifests {
+            writeln!(f, "+ manifest {}", label)?;
+        }
+        for label in &self.removed_manifests {
+            writeln!(f, "- manifest {}", label)?;
+        }
+        for manifest in &self.manifests {
+            write!(f, "{}", manifest)?;
+        }
+        Ok(())
+    }
+}
+
+// byte strings serialize to arrays of numbers, they are compared as a whole
+fn is_bytes(values: &[Value]) -> bool {
+    !values.is_empty() && values.iter().all(Value::is_number)
+}
+
+fn child_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_owned()
+    } else {
+        format!("{}/{}", path, key)
+    }
+}
+
+// add the changes between `before` and `after` to `changes`
+fn diff_values(path: &str, before: &Value, after: &Value, changes: &mut Vec<ValueChange>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let mut keys: Vec<&String> = before.keys().chain(after.keys()).collect();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = child_path(path, key);
+                match (before.get(key), after.get(key)) {
+                    (Some(before), Some(after)) => diff_values(&path, before, after, changes),
+                    (before, after) => changes.push(ValueChange {
+                        path,
+                        before: before.cloned(),
+                        after: after.cloned(),
+                    }),
+                }
+            }
+        }
+        (Value::Array(before), Value::Array(after)) if !is_bytes(before) || !is_bytes(after) => {
+            for index in 0..before.len().max(after.len()) {
+                let path = child_path(path, &index.to_string());
+                match (before.get(index), after.get(index)) {
+                    (Some(before), Some(after)) => diff_values(&path, before, after, changes),
+                    (before, after) => changes.push(ValueChange {
+                        path,
+                        before: before.cloned(),
+                        after: after.cloned(),
+                    }),
+                }
+            }
+        }
+        _ => {
+            if before != after {
+                changes.push(ValueChange {
+                    path: path.to_owned(),
+                    before: Some(before.clone()),
+                    after: Some(after.clone()),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::utils::test::fixture_path;
+
+    fn report(name: &str) -> ManifestStoreReport {
+        let data = std::fs::read(fixture_path(name)).unwrap();
+        ManifestStoreReport::from_bytes("jpg", &data).unwrap()
+    }
+
+    #[test]
+    fn test_diff_values() {
+        let before = json!({
+            "actions": [{ "action": "c2pa.opened" }],
+            "hash": [1, 2, 3],
+            "title": "a.jpg",
+        });
+        let after = json!({
+            "actions": [{ "action": "c2pa.opened" }, { "action": "c2pa.edited" }],
+            "hash": [1, 2, 4],
+            "format": "image/jpeg",
+        });
+
+        let mut changes = Vec::new();
+        diff_values("c2pa.actions", &before, &after, &mut changes);
+
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "c2pa.actions/actions/1",
+                "c2pa.actions/format",
+                "c2pa.actions/hash",
+                "c2pa.actions/title"
+            ]
+        );
+        assert_eq!(changes[0].before, None);
+        assert_eq!(changes[2].after, Some(json!([1, 2, 4])));
+        assert_eq!(changes[3].after, None);
+        assert_eq!(changes[3].to_string(), "- c2pa.actions/title: \"a.jpg\"");
+    }
+
+    #[test]
+    fn test_manifest_store_diff() {
+        let before = report("C.jpg");
+        let after = report("CA.jpg");
+
+        assert!(ManifestStoreDiff::new(&after, &after).is_empty());
+
+        // the active manifests are compared even though their labels differ
+        let diff = ManifestStoreDiff::new(&before, &after);
+        assert!(!diff.is_empty());
+        let active = &diff.manifests[0];
+        assert_eq!(Some(&active.before_label), before.active_manifest.as_ref());
+        assert_eq!(Some(&active.after_label), after.active_manifest.as_ref());
+        assert!(!active.claim_changes.is_empty());
+        assert!(diff.to_string().contains("manifest "));
+        assert!(serde_json::to_string(&diff)
+            .unwrap()
+            .contains("claim_changes"));
+    }
+}
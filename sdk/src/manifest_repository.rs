@@ -0,0 +1,33 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Lookup of manifests for assets whose embedded manifest was removed.
+
+use crate::Result;
+
+/// A service that stores manifests so they can be found again from the asset content.
+///
+/// Lookups return the JUMBF bytes of a manifest store, or `None` if nothing matched.
+/// Both methods default to finding nothing so repositories only implement the
+/// lookups they support.
+pub trait ManifestRepository: Send + Sync {
+    /// Find a manifest store by a soft binding value computed with algorithm `alg`
+    fn find_by_soft_binding(&self, _alg: &str, _value: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Find a manifest store by the hash of the whole asset computed with algorithm `alg`
+    fn find_by_content_hash(&self, _alg: &str, _hash: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
@@ -15,15 +15,23 @@ use crate::{
     status_tracker::{DetailedStatusTracker, StatusTracker},
     store::Store,
     validation_status::{status_for_store, ValidationStatus},
-    Manifest, Result,
+    Manifest, ManifestRepository, ProvenanceGraph, Result, VerifyOptions,
 };
 use serde::Serialize;
 use std::collections::HashMap;
 
+#[cfg(feature = "mmap")]
+use crate::{error::wrap_io_err, jumbf_io::get_supported_file_extension, Error};
+#[cfg(feature = "file_io")]
+use crate::{
+    progress::{CancellationToken, ProgressMonitor, ProgressReporter},
+    HashingOptions,
+};
 #[cfg(feature = "file_io")]
 use std::path::Path;
 
 #[derive(Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 /// A Container for a set of Manifests and a ValidationStatus list
 ///
 pub struct ManifestStore {
@@ -76,6 +84,11 @@ impl ManifestStore {
         self.validation_status.as_deref()
     }
 
+    /// Returns the provenance graph of the manifests in the store
+    pub fn provenance_graph(&self) -> ProvenanceGraph {
+        ProvenanceGraph::from_manifest_store(self)
+    }
+
     /// creates a ManifestStore from a Store
     pub(crate) fn from_store(
         store: &Store,
@@ -124,6 +137,37 @@ impl ManifestStore {
         }
     }
 
+    /// Creates a ManifestStore from in-memory asset bytes, verifying it with the supplied options
+    pub fn from_bytes_with_options(
+        format: &str,
+        image_bytes: &[u8],
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+
+        let store = Store::load_from_memory_with_options(
+            format,
+            image_bytes,
+            true,
+            options,
+            &mut validation_log,
+        )?;
+        Ok(Self::from_store(&store, &mut validation_log))
+    }
+
+    /// Recovers the ManifestStore of an asset whose embedded manifest was removed
+    /// by looking it up in a [`ManifestRepository`].
+    pub fn recover_from_bytes(
+        image_bytes: &[u8],
+        repository: &dyn ManifestRepository,
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+
+        let store = Store::recover_manifest(image_bytes, repository, options, &mut validation_log)?;
+        Ok(Self::from_store(&store, &mut validation_log))
+    }
+
     #[cfg(feature = "file_io")]
     /// Loads a ManifestStore from a file
     /// Example:
@@ -144,6 +188,102 @@ impl ManifestStore {
         Ok(Self::from_store(&store, &mut validation_log))
     }
 
+    #[cfg(feature = "file_io")]
+    /// Loads a ManifestStore from a file, verifying it with the supplied options
+    pub fn from_file_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+
+        let store = Store::load_from_asset_with_progress(
+            path.as_ref(),
+            true,
+            options,
+            &mut validation_log,
+            &ProgressMonitor::none(),
+        )?;
+        Ok(Self::from_store(&store, &mut validation_log))
+    }
+
+    #[cfg(feature = "mmap")]
+    /// Loads and verifies a ManifestStore from a memory-mapped file, with the supplied options
+    ///
+    /// The hashed ranges are read in place from the mapping rather than copied into
+    /// memory first. The manifest store is also borrowed from the mapping for JPEG
+    /// assets with a single APP11 segment, PNG, font and `.c2pa` files; the parsed
+    /// JUMBF boxes still hold copies of their content.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other process,
+    /// until this function returns. Doing so is undefined behavior.
+    #[allow(unsafe_code)]
+    pub unsafe fn from_file_mapped<P: AsRef<Path>>(
+        path: P,
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let path = path.as_ref();
+        let ext = get_supported_file_extension(path).ok_or(Error::UnsupportedType)?;
+
+        let file = std::fs::File::open(path).map_err(wrap_io_err)?;
+        // the caller guarantees the file is not changed while it is mapped
+        let map = memmap2::Mmap::map(&file).map_err(wrap_io_err)?;
+
+        Self::from_bytes_with_options(&ext, &map, options)
+    }
+
+    #[cfg(feature = "file_io")]
+    /// Loads and verifies a ManifestStore from a file, reporting progress to `reporter`
+    ///
+    /// Returns [`crate::Error::OperationCancelled`] if `cancel` is cancelled before verification completes.
+    pub fn from_file_with_progress<P: AsRef<Path>>(
+        path: P,
+        reporter: &dyn ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+        let monitor = ProgressMonitor::new(reporter, cancel);
+
+        let store = Store::load_from_asset_with_progress(
+            path.as_ref(),
+            true,
+            &VerifyOptions::default(),
+            &mut validation_log,
+            &monitor,
+        )?;
+        Ok(Self::from_store(&store, &mut validation_log))
+    }
+
+    #[cfg(feature = "file_io")]
+    /// Verifies the active manifest of a file, returning the first hard failure.
+    ///
+    /// The signature and claim are checked before the asset is hashed, and hashing stops
+    /// at the first data hash mismatch. No report is produced, so this suits pre-screening
+    /// large assets. Use [`ManifestStore::from_file`] for a full validation report.
+    pub fn verify_file_fail_fast<P: AsRef<Path>>(path: P) -> Result<()> {
+        Store::verify_asset_fail_fast(
+            path.as_ref(),
+            &HashingOptions::default(),
+            &ProgressMonitor::none(),
+        )
+    }
+
+    #[cfg(feature = "file_io")]
+    /// Verifies the active manifest of a file like [`ManifestStore::verify_file_fail_fast`],
+    /// hashing with the supplied options and reporting progress to `reporter`
+    ///
+    /// Returns [`crate::Error::OperationCancelled`] if `cancel` is cancelled before verification completes.
+    pub fn verify_file_fail_fast_with_progress<P: AsRef<Path>>(
+        path: P,
+        options: &HashingOptions,
+        reporter: &dyn ProgressReporter,
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        let monitor = ProgressMonitor::new(reporter, cancel);
+        Store::verify_asset_fail_fast(path.as_ref(), options, &monitor)
+    }
+
     /// Loads a ManifestStore from a file
     pub async fn from_bytes_async(
         format: &str,
@@ -158,6 +298,26 @@ impl ManifestStore {
             Err(_err) => None,
         }
     }
+
+    /// Creates a ManifestStore from in-memory asset bytes asynchronously, verifying it with
+    /// the supplied options
+    pub async fn from_bytes_async_with_options(
+        format: &str,
+        image_bytes: &[u8],
+        options: &VerifyOptions,
+    ) -> Result<ManifestStore> {
+        let mut validation_log = DetailedStatusTracker::new();
+
+        let store = Store::load_from_memory_async_with_options(
+            format,
+            image_bytes,
+            true,
+            options,
+            &mut validation_log,
+        )
+        .await?;
+        Ok(Self::from_store(&store, &mut validation_log))
+    }
 }
 
 impl Default for ManifestStore {
@@ -222,15 +382,18 @@ mod tests {
     use super::*;
     use crate::{status_tracker::OneShotStatusTracker, utils::test::create_test_store};
 
-    #[cfg(target_arch = "wasm32")]
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
     use wasm_bindgen_test::*;
 
-    #[cfg(target_arch = "wasm32")]
+    #[cfg(all(target_arch = "wasm32", not(target_os = "wasi")))]
     wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
 
     // #[cfg_attr(not(target_arch = "wasm32"), test)]
     // #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
-    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(
+        all(target_arch = "wasm32", not(target_os = "wasi")),
+        wasm_bindgen_test
+    )]
     #[test]
     fn manifest_report() {
         let store = create_test_store().expect("creating test store");
@@ -263,5 +426,46 @@ mod tests {
         assert!(!manifest.ingredients().is_empty());
         assert_eq!(manifest.issuer().unwrap(), "Some Company");
         assert!(manifest.time().is_some());
+        assert!(manifest.signature_bytes().is_some());
+        assert!(manifest.claim_bytes().is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn verify_file_fail_fast() {
+        use crate::utils::test::{fixture_path, temp_dir_path};
+
+        let ap = fixture_path("CA.jpg");
+        ManifestStore::verify_file_fail_fast(&ap).unwrap();
+
+        // change a byte of the image data, leaving the manifest in place
+        let temp_dir = tempfile::tempdir().unwrap();
+        let op = temp_dir_path(&temp_dir, "CA.jpg");
+        let mut data = std::fs::read(&ap).unwrap();
+        let pos = data.len() - 10;
+        data[pos] ^= 0xff;
+        std::fs::write(&op, &data).unwrap();
+
+        assert!(matches!(
+            ManifestStore::verify_file_fail_fast(&op),
+            Err(crate::Error::HashMismatch(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "mmap")]
+    #[allow(unsafe_code)]
+    fn from_file_mapped() {
+        use crate::utils::test::fixture_path;
+
+        let ap = fixture_path("CA.jpg");
+        // the fixture is not modified while the test runs
+        let mapped =
+            unsafe { ManifestStore::from_file_mapped(&ap, &VerifyOptions::default()) }.unwrap();
+        let read = ManifestStore::from_file(&ap).unwrap();
+
+        assert_eq!(mapped.active_label(), read.active_label());
+        assert_eq!(mapped.manifests().len(), read.manifests().len());
+        assert!(mapped.validation_status().is_none());
     }
 }
@@ -30,8 +30,8 @@ use std::path::Path;
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct ManifestStoreReport {
     #[serde(skip_serializing_if = "Option::is_none")]
-    active_manifest: Option<String>,
-    manifests: HashMap<String, ManifestReport>,
+    pub(crate) active_manifest: Option<String>,
+    pub(crate) manifests: HashMap<String, ManifestReport>,
     #[serde(skip_serializing_if = "Option::is_none")]
     validation_status: Option<Vec<ValidationStatus>>,
 }
@@ -63,7 +63,7 @@ impl ManifestStoreReport {
         for item in validation_log.get_log() {
             if let Some(status) = item.validation_status.as_ref() {
                 statuses.push(
-                    ValidationStatus::new(status.to_string())
+                    ValidationStatus::new(status.clone())
                         .set_url(item.label.to_string())
                         .set_explanation(item.description.to_string()),
                 );
@@ -108,12 +108,12 @@ impl std::fmt::Display for ManifestStoreReport {
 }
 
 #[derive(Serialize, Deserialize, Debug, Default)]
-struct ManifestReport {
-    claim: Value,
-    assertion_store: HashMap<String, Value>,
+pub(crate) struct ManifestReport {
+    pub(crate) claim: Value,
+    pub(crate) assertion_store: HashMap<String, Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     credential_store: Option<Vec<Value>>,
-    signature: SignatureReport,
+    pub(crate) signature: SignatureReport,
 }
 
 impl ManifestReport {
@@ -153,6 +153,11 @@ impl ManifestReport {
                 alg: info.alg,
                 issuer: info.issuer_org,
                 time: info.date.map(|d| d.to_rfc3339()),
+                cosigners: info
+                    .cosigners
+                    .into_iter()
+                    .filter_map(|c| c.issuer_org)
+                    .collect(),
             },
             None => SignatureReport::default(),
         };
@@ -182,7 +187,7 @@ impl std::fmt::Display for ManifestReport {
 
 // used to report information from signature data
 #[derive(Default, Debug, Deserialize, Serialize)]
-struct SignatureReport {
+pub(crate) struct SignatureReport {
     alg: String,
     // human readable issuing authority for this signature
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -190,6 +195,9 @@ struct SignatureReport {
     // the time the signature was created
     #[serde(skip_serializing_if = "Option::is_none")]
     time: Option<String>,
+    // human readable issuing authorities of the co-signatures
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    cosigners: Vec<String>,
 }
 
 // replace the value of any field in the json string with a given key with the string <omitted>
@@ -0,0 +1,779 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Extraction of EXIF, IPTC and XMP metadata from assets into metadata assertions.
+//!
+//! EXIF and TIFF fields become a `stds.exif` assertion and IPTC fields a
+//! `stds.iptc.photo-metadata` assertion, both in the JSON-LD layout of the specification.
+//! Only fields in the allow-list of [`MetadataExtractionOptions`] are extracted, so
+//! private details such as the location or the camera serial number are left out
+//! unless they are allowed explicitly.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::Cursor,
+};
+
+use img_parts::{
+    jpeg::{markers, Jpeg},
+    Bytes, DynImage, ImageEXIF,
+};
+use quick_xml::{events::Event, Reader};
+use serde_json::{json, Map, Value};
+
+use crate::{assertions::labels, jumbf_io::get_cailoader_handler, ManifestAssertion, Result};
+
+/// Fields extracted when no allow-list is given. These describe the capture but not
+/// where it happened or who made it.
+pub const DEFAULT_ALLOWED_FIELDS: &[&str] = &[
+    "tiff:Make",
+    "tiff:Model",
+    "tiff:Orientation",
+    "tiff:Software",
+    "tiff:ImageWidth",
+    "tiff:ImageLength",
+    "tiff:XResolution",
+    "tiff:YResolution",
+    "tiff:ResolutionUnit",
+    "exif:DateTimeOriginal",
+    "exif:DateTimeDigitized",
+    "exif:ExposureTime",
+    "exif:FNumber",
+    "exif:ExposureProgram",
+    "exif:ExposureBiasValue",
+    "exif:ISOSpeedRatings",
+    "exif:MeteringMode",
+    "exif:Flash",
+    "exif:FocalLength",
+    "exif:FocalLengthIn35mmFilm",
+    "exif:DigitalZoomRatio",
+    "exif:WhiteBalance",
+    "exif:ColorSpace",
+    "exif:PixelXDimension",
+    "exif:PixelYDimension",
+    "exifEX:LensModel",
+    "dc:title",
+    "dc:description",
+    "dc:rights",
+    "dc:subject",
+    "photoshop:Credit",
+    "photoshop:Source",
+    "photoshop:DateCreated",
+];
+
+// namespaces of the fields written to each assertion
+const EXIF_CONTEXT: &[(&str, &str)] = &[
+    ("exif", "http://ns.adobe.com/exif/1.0/"),
+    ("exifEX", "http://cipa.jp/exif/1.0/"),
+    ("tiff", "http://ns.adobe.com/tiff/1.0/"),
+];
+const IPTC_CONTEXT: &[(&str, &str)] = &[
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    (
+        "Iptc4xmpCore",
+        "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/",
+    ),
+    ("Iptc4xmpExt", "http://iptc.org/std/Iptc4xmpExt/2008-02-29/"),
+    ("photoshop", "http://ns.adobe.com/photoshop/1.0/"),
+];
+
+// EXIF tags of the image IFD, the Exif IFD and the GPS IFD with their XMP names
+const IFD0_TAGS: &[(u16, &str)] = &[
+    (0x0100, "tiff:ImageWidth"),
+    (0x0101, "tiff:ImageLength"),
+    (0x010e, "tiff:ImageDescription"),
+    (0x010f, "tiff:Make"),
+    (0x0110, "tiff:Model"),
+    (0x0112, "tiff:Orientation"),
+    (0x011a, "tiff:XResolution"),
+    (0x011b, "tiff:YResolution"),
+    (0x0128, "tiff:ResolutionUnit"),
+    (0x0131, "tiff:Software"),
+    (0x0132, "tiff:DateTime"),
+    (0x013b, "tiff:Artist"),
+    (0x8298, "tiff:Copyright"),
+];
+const EXIF_IFD_TAGS: &[(u16, &str)] = &[
+    (0x829a, "exif:ExposureTime"),
+    (0x829d, "exif:FNumber"),
+    (0x8822, "exif:ExposureProgram"),
+    (0x8827, "exif:ISOSpeedRatings"),
+    (0x9003, "exif:DateTimeOriginal"),
+    (0x9004, "exif:DateTimeDigitized"),
+    (0x9204, "exif:ExposureBiasValue"),
+    (0x9207, "exif:MeteringMode"),
+    (0x9209, "exif:Flash"),
+    (0x920a, "exif:FocalLength"),
+    (0xa001, "exif:ColorSpace"),
+    (0xa002, "exif:PixelXDimension"),
+    (0xa003, "exif:PixelYDimension"),
+    (0xa403, "exif:WhiteBalance"),
+    (0xa404, "exif:DigitalZoomRatio"),
+    (0xa405, "exif:FocalLengthIn35mmFilm"),
+    (0xa431, "exifEX:BodySerialNumber"),
+    (0xa434, "exifEX:LensModel"),
+];
+const GPS_IFD_TAGS: &[(u16, &str)] = &[
+    (0x0000, "exif:GPSVersionID"),
+    (0x0005, "exif:GPSAltitudeRef"),
+    (0x0006, "exif:GPSAltitude"),
+    (0x001d, "exif:GPSDateStamp"),
+];
+const EXIF_IFD_POINTER: u16 = 0x8769;
+const GPS_IFD_POINTER: u16 = 0x8825;
+
+// IPTC IIM datasets of the application record with their XMP names, and whether they repeat
+const IIM_DATASETS: &[(u8, &str, bool)] = &[
+    (5, "dc:title", false),
+    (25, "dc:subject", true),
+    (55, "photoshop:DateCreated", false),
+    (80, "dc:creator", true),
+    (90, "photoshop:City", false),
+    (95, "photoshop:State", false),
+    (101, "photoshop:Country", false),
+    (110, "photoshop:Credit", false),
+    (115, "photoshop:Source", false),
+    (116, "dc:rights", false),
+    (120, "dc:description", false),
+];
+const PHOTOSHOP_SIGNATURE: &[u8] = b"Photoshop 3.0\0";
+const IPTC_RESOURCE_ID: u16 = 0x0404;
+
+// guards against IFDs with absurd entry counts in malformed files
+const MAX_IFD_ENTRIES: usize = 1000;
+
+/// Options for extracting metadata assertions from an asset
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetadataExtractionOptions {
+    allowed_fields: HashSet<String>,
+}
+
+impl MetadataExtractionOptions {
+    /// Create options extracting only the given fields, named as in XMP, such as
+    /// `exif:FNumber` or `dc:creator`
+    pub fn new<S: AsRef<str>>(allowed_fields: &[S]) -> Self {
+        MetadataExtractionOptions {
+            allowed_fields: allowed_fields
+                .iter()
+                .map(|f| f.as_ref().to_owned())
+                .collect(),
+        }
+    }
+
+    /// Allow another field to be extracted, such as `exif:GPSLatitude`
+    pub fn allow(&mut self, field: &str) -> &mut Self {
+        self.allowed_fields.insert(field.to_owned());
+        self
+    }
+
+    /// Returns `true` if the field may be extracted
+    pub fn is_allowed(&self, field: &str) -> bool {
+        self.allowed_fields.contains(field)
+    }
+}
+
+impl Default for MetadataExtractionOptions {
+    fn default() -> Self {
+        Self::new(DEFAULT_ALLOWED_FIELDS)
+    }
+}
+
+/// Extract the metadata of an asset as `stds.exif` and `stds.iptc.photo-metadata` assertions
+/// that can be added to a [`Manifest`](crate::Manifest).
+///
+/// `format` is the extension or MIME type of the asset. Fields found in the EXIF and IPTC
+/// blocks of the asset take precedence over the same fields in its XMP. Assertions without
+/// any allowed field are not returned.
+pub fn extract_metadata(
+    format: &str,
+    data: &[u8],
+    options: &MetadataExtractionOptions,
+) -> Result<Vec<ManifestAssertion>> {
+    let mut fields = BTreeMap::new();
+
+    if let Ok(Some(image)) = DynImage::from_bytes(Bytes::copy_from_slice(data)) {
+        if let Some(exif) = image.exif() {
+            fields.extend(exif_fields(&exif));
+        }
+        if let DynImage::Jpeg(jpeg) = &image {
+            for (name, value) in iptc_fields(jpeg) {
+                fields.entry(name).or_insert(value);
+            }
+        }
+    }
+
+    let xmp =
+        get_cailoader_handler(format).and_then(|loader| loader.read_xmp(&mut Cursor::new(data)));
+    if let Some(xmp) = xmp {
+        for (name, value) in xmp_fields(&xmp) {
+            fields.entry(name).or_insert(value);
+        }
+    }
+
+    fields.retain(|name, _| options.is_allowed(name));
+
+    let mut assertions = Vec::new();
+    for (label, context) in &[
+        (labels::EXIF, EXIF_CONTEXT),
+        (labels::IPTC_PHOTO_METADATA, IPTC_CONTEXT),
+    ] {
+        if let Some(json) = json_ld(&fields, context) {
+            assertions.push(ManifestAssertion::from_labeled_assertion(label, &json)?);
+        }
+    }
+    Ok(assertions)
+}
+
+// builds a JSON-LD object of the fields in the namespaces of the context
+fn json_ld(fields: &BTreeMap<String, Value>, context: &[(&str, &str)]) -> Option<Value> {
+    let mut object = Map::new();
+    for (name, value) in fields {
+        let prefix = name.split(':').next().unwrap_or_default();
+        if context.iter().any(|(p, _)| *p == prefix) {
+            object.insert(name.clone(), value.clone());
+        }
+    }
+    if object.is_empty() {
+        return None;
+    }
+
+    let context: Map<String, Value> = context
+        .iter()
+        .map(|(prefix, uri)| (prefix.to_string(), json!(uri)))
+        .collect();
+    object.insert("@context".to_owned(), Value::Object(context));
+    Some(Value::Object(object))
+}
+
+// a TIFF structure, the layout of EXIF blocks
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+// an entry of an IFD
+struct IfdEntry {
+    tag: u16,
+    field_type: u16,
+    count: usize,
+    offset: usize,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..4)? {
+            b"II*\0" => true,
+            b"MM\0*" => false,
+            _ => return None,
+        };
+        Some(Tiff {
+            data,
+            little_endian,
+        })
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = [*self.data.get(offset)?, *self.data.get(offset + 1)?];
+        Some(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let mut bytes = [0u8; 4];
+        bytes.copy_from_slice(self.data.get(offset..offset + 4)?);
+        Some(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn entries(&self, ifd_offset: usize) -> Vec<IfdEntry> {
+        let count = match self.u16(ifd_offset) {
+            Some(count) => (count as usize).min(MAX_IFD_ENTRIES),
+            None => return Vec::new(),
+        };
+        (0..count)
+            .filter_map(|i| {
+                let entry = ifd_offset + 2 + i * 12;
+                Some(IfdEntry {
+                    tag: self.u16(entry)?,
+                    field_type: self.u16(entry + 2)?,
+                    count: self.u32(entry + 4)? as usize,
+                    offset: entry + 8,
+                })
+            })
+            .collect()
+    }
+
+    // the bytes of an entry's value, which are stored in the entry itself when they fit
+    fn value_bytes(&self, entry: &IfdEntry) -> Option<&'a [u8]> {
+        let size = match entry.field_type {
+            1 | 2 | 6 | 7 => 1,
+            3 | 8 => 2,
+            4 | 9 => 4,
+            5 | 10 => 8,
+            _ => return None,
+        };
+        let len = entry.count.checked_mul(size)?;
+        let start = if len <= 4 {
+            entry.offset
+        } else {
+            self.u32(entry.offset)? as usize
+        };
+        self.data.get(start..start.checked_add(len)?)
+    }
+
+    fn rationals(&self, entry: &IfdEntry) -> Option<Vec<(u32, u32)>> {
+        if entry.field_type != 5 && entry.field_type != 10 {
+            return None;
+        }
+        let bytes = self.value_bytes(entry)?;
+        let tiff = Tiff {
+            data: bytes,
+            little_endian: self.little_endian,
+        };
+        (0..entry.count)
+            .map(|i| Some((tiff.u32(i * 8)?, tiff.u32(i * 8 + 4)?)))
+            .collect()
+    }
+
+    // the value of an entry as XMP represents it
+    fn value(&self, entry: &IfdEntry) -> Option<Value> {
+        let bytes = self.value_bytes(entry)?;
+        let values: Vec<Value> = match entry.field_type {
+            2 => {
+                let text = String::from_utf8_lossy(bytes);
+                return Some(json!(text.trim_end_matches('\0').trim()));
+            }
+            1 => bytes.iter().map(|b| json!(b)).collect(),
+            3 => (0..entry.count)
+                .filter_map(|i| self.value_u16(bytes, i))
+                .map(|v| json!(v))
+                .collect(),
+            4 => (0..entry.count)
+                .filter_map(|i| self.value_u32(bytes, i))
+                .map(|v| json!(v))
+                .collect(),
+            9 => (0..entry.count)
+                .filter_map(|i| self.value_u32(bytes, i))
+                .map(|v| json!(v as i32))
+                .collect(),
+            5 => self
+                .rationals(entry)?
+                .into_iter()
+                .map(|(n, d)| json!(format!("{}/{}", n, d)))
+                .collect(),
+            10 => self
+                .rationals(entry)?
+                .into_iter()
+                .map(|(n, d)| json!(format!("{}/{}", n as i32, d as i32)))
+                .collect(),
+            _ => return None,
+        };
+        match values.len() {
+            0 => None,
+            1 => values.into_iter().next(),
+            _ => Some(Value::Array(values)),
+        }
+    }
+
+    fn value_u16(&self, bytes: &[u8], index: usize) -> Option<u16> {
+        Tiff {
+            data: bytes,
+            little_endian: self.little_endian,
+        }
+        .u16(index * 2)
+    }
+
+    fn value_u32(&self, bytes: &[u8], index: usize) -> Option<u32> {
+        Tiff {
+            data: bytes,
+            little_endian: self.little_endian,
+        }
+        .u32(index * 4)
+    }
+}
+
+// the fields of an EXIF block, which is a TIFF structure
+fn exif_fields(exif: &[u8]) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    let tiff = match Tiff::new(exif) {
+        Some(tiff) => tiff,
+        None => return fields,
+    };
+    let ifd0 = match tiff.u32(4) {
+        Some(offset) => offset as usize,
+        None => return fields,
+    };
+
+    let mut exif_ifd = None;
+    let mut gps_ifd = None;
+    for entry in tiff.entries(ifd0) {
+        match entry.tag {
+            EXIF_IFD_POINTER => exif_ifd = tiff.u32(entry.offset),
+            GPS_IFD_POINTER => gps_ifd = tiff.u32(entry.offset),
+            tag => add_tag(&mut fields, &tiff, &entry, tag, IFD0_TAGS),
+        }
+    }
+
+    if let Some(offset) = exif_ifd {
+        for entry in tiff.entries(offset as usize) {
+            add_tag(&mut fields, &tiff, &entry, entry.tag, EXIF_IFD_TAGS);
+        }
+    }
+
+    if let Some(offset) = gps_ifd {
+        let entries = tiff.entries(offset as usize);
+        let gps_ref = |tag: u16| {
+            entries
+                .iter()
+                .find(|e| e.tag == tag)
+                .and_then(|e| tiff.value_bytes(e))
+                .and_then(|b| b.first())
+                .map(|b| *b as char)
+        };
+        let latitude_ref = gps_ref(0x0001);
+        let longitude_ref = gps_ref(0x0003);
+        for entry in &entries {
+            match entry.tag {
+                0x0000 => {
+                    // the version is written as dotted bytes
+                    if let Some(bytes) = tiff.value_bytes(entry) {
+                        let version: Vec<String> = bytes.iter().map(|b| b.to_string()).collect();
+                        fields.insert("exif:GPSVersionID".to_owned(), json!(version.join(".")));
+                    }
+                }
+                0x0002 | 0x0004 => {
+                    let (name, direction) = if entry.tag == 0x0002 {
+                        ("exif:GPSLatitude", latitude_ref)
+                    } else {
+                        ("exif:GPSLongitude", longitude_ref)
+                    };
+                    if let Some(coordinate) = tiff
+                        .rationals(entry)
+                        .and_then(|r| gps_coordinate(&r, direction?))
+                    {
+                        fields.insert(name.to_owned(), json!(coordinate));
+                    }
+                }
+                tag => add_tag(&mut fields, &tiff, entry, tag, GPS_IFD_TAGS),
+            }
+        }
+    }
+
+    fields
+}
+
+fn add_tag(
+    fields: &mut BTreeMap<String, Value>,
+    tiff: &Tiff,
+    entry: &IfdEntry,
+    tag: u16,
+    tags: &[(u16, &str)],
+) {
+    if let Some((_, name)) = tags.iter().find(|(t, _)| *t == tag) {
+        if let Some(value) = tiff.value(entry) {
+            fields.insert((*name).to_owned(), value);
+        }
+    }
+}
+
+// formats degrees, minutes and seconds as the XMP GPS coordinate "DDD,MM.mmmmk"
+fn gps_coordinate(dms: &[(u32, u32)], direction: char) -> Option<String> {
+    let part = |i: usize| {
+        dms.get(i)
+            .filter(|(_, d)| *d != 0)
+            .map(|(n, d)| *n as f64 / *d as f64)
+    };
+    let degrees = part(0)?;
+    let minutes = (degrees.fract() * 60.0) + part(1).unwrap_or(0.0) + part(2).unwrap_or(0.0) / 60.0;
+    Some(format!("{},{:.4}{}", degrees.trunc(), minutes, direction))
+}
+
+// the fields of the IPTC IIM block stored in the Photoshop resources of a JPEG
+fn iptc_fields(jpeg: &Jpeg) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    for segment in jpeg.segments() {
+        if segment.marker() != markers::APP13 {
+            continue;
+        }
+        let contents = segment.contents();
+        if let Some(iim) = contents
+            .strip_prefix(PHOTOSHOP_SIGNATURE)
+            .and_then(photoshop_iptc_resource)
+        {
+            add_iim_datasets(&mut fields, iim);
+        }
+    }
+    fields
+}
+
+// finds the IPTC resource in a list of Photoshop image resources
+fn photoshop_iptc_resource(mut resources: &[u8]) -> Option<&[u8]> {
+    while resources.len() >= 12 && resources.starts_with(b"8BIM") {
+        let id = u16::from_be_bytes([resources[4], resources[5]]);
+        // the name is a pascal string padded to an even length
+        let name_len = *resources.get(6)? as usize;
+        let name_size = (name_len + 2) & !1;
+        let size_at = 6 + name_size;
+        let size = u32::from_be_bytes([
+            *resources.get(size_at)?,
+            *resources.get(size_at + 1)?,
+            *resources.get(size_at + 2)?,
+            *resources.get(size_at + 3)?,
+        ]) as usize;
+        let data_at = size_at + 4;
+        let data = resources.get(data_at..data_at.checked_add(size)?)?;
+        if id == IPTC_RESOURCE_ID {
+            return Some(data);
+        }
+        let next = data_at + ((size + 1) & !1);
+        resources = resources.get(next..)?;
+    }
+    None
+}
+
+fn add_iim_datasets(fields: &mut BTreeMap<String, Value>, mut iim: &[u8]) {
+    while iim.len() >= 5 && iim[0] == 0x1c {
+        let (record, dataset) = (iim[1], iim[2]);
+        let size = u16::from_be_bytes([iim[3], iim[4]]) as usize;
+        // extended datasets are never used by the fields extracted
+        if size & 0x8000 != 0 {
+            return;
+        }
+        let data = match iim.get(5..5 + size) {
+            Some(data) => data,
+            None => return,
+        };
+        iim = &iim[5 + size..];
+
+        let (name, repeats) = match IIM_DATASETS.iter().find(|(d, _, _)| *d == dataset) {
+            Some((_, name, repeats)) if record == 2 => (*name, *repeats),
+            _ => continue,
+        };
+        let mut text = String::from_utf8_lossy(data).trim().to_owned();
+        if name == "photoshop:DateCreated" && text.len() == 8 && text.is_ascii() {
+            text = format!("{}-{}-{}", &text[0..4], &text[4..6], &text[6..8]);
+        }
+
+        if repeats {
+            match fields
+                .entry(name.to_owned())
+                .or_insert_with(|| Value::Array(Vec::new()))
+            {
+                Value::Array(values) => values.push(json!(text)),
+                _ => continue,
+            }
+        } else {
+            fields.insert(name.to_owned(), json!(text));
+        }
+    }
+}
+
+// the simple and array properties of an XMP packet, structures are skipped
+fn xmp_fields(xmp: &str) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+    let mut reader = Reader::from_str(xmp);
+    reader.trim_text(true);
+    let mut buf = Vec::new();
+
+    // the property being read, its text, its array items and whether it is an alternative
+    let mut property: Option<(String, Option<String>, Vec<String>, bool)> = None;
+    let mut in_item = false;
+    let mut is_struct = false;
+
+    loop {
+        match reader.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) if e.name() == b"rdf:Description" => {
+                // properties may be written as attributes
+                for attribute in e.attributes().flatten() {
+                    let key = String::from_utf8_lossy(attribute.key).into_owned();
+                    if is_property_name(&key) {
+                        if let Ok(value) = attribute.unescape_and_decode_value(&reader) {
+                            fields.insert(key, json!(value));
+                        }
+                    }
+                }
+            }
+            Ok(Event::Start(ref e)) => {
+                let name = String::from_utf8_lossy(e.name()).into_owned();
+                match property.as_mut() {
+                    None if is_property_name(&name) => {
+                        property = Some((name, None, Vec::new(), false));
+                        is_struct = false;
+                    }
+                    Some(p) if name == "rdf:Alt" => p.3 = true,
+                    Some(_) if name == "rdf:li" => in_item = true,
+                    Some(_) if name != "rdf:Seq" && name != "rdf:Bag" => is_struct = true,
+                    _ => {}
+                }
+            }
+            Ok(Event::Text(ref t)) => {
+                if let (Some(p), Ok(text)) = (property.as_mut(), t.unescape_and_decode(&reader)) {
+                    if in_item {
+                        p.2.push(text);
+                    } else {
+                        p.1 = Some(text);
+                    }
+                }
+            }
+            Ok(Event::End(ref e)) => {
+                if e.name() == b"rdf:li" {
+                    in_item = false;
+                } else if property.as_ref().map(|p| p.0.as_bytes()) == Some(e.name()) {
+                    if let Some((name, text, items, is_alt)) = property.take() {
+                        let value = if is_struct {
+                            None
+                        } else if is_alt {
+                            items.into_iter().next().map(Value::String)
+                        } else if !items.is_empty() {
+                            Some(json!(items))
+                        } else {
+                            text.map(Value::String)
+                        };
+                        if let Some(value) = value {
+                            fields.insert(name, value);
+                        }
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            // malformed xml, stop rather than risk looping on the same error
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    fields
+}
+
+// XMP properties are prefixed names outside of the RDF and XML namespaces
+fn is_property_name(name: &str) -> bool {
+    match name.split_once(':') {
+        Some((prefix, _)) => !matches!(prefix, "rdf" | "x" | "xmlns" | "xml"),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    // a little endian EXIF block with a make, an exif IFD with an f-number and a GPS IFD
+    fn make_exif() -> Vec<u8> {
+        let mut exif = b"II*\0".to_vec();
+        exif.extend(8u32.to_le_bytes());
+        // IFD0 at 8 with 3 entries, its values follow at 8 + 2 + 3 * 12 + 4 = 50
+        exif.extend(3u16.to_le_bytes());
+        exif.extend(0x010fu16.to_le_bytes());
+        exif.extend(2u16.to_le_bytes());
+        exif.extend(6u32.to_le_bytes());
+        exif.extend(50u32.to_le_bytes());
+        exif.extend(EXIF_IFD_POINTER.to_le_bytes());
+        exif.extend(4u16.to_le_bytes());
+        exif.extend(1u32.to_le_bytes());
+        exif.extend(56u32.to_le_bytes());
+        exif.extend(GPS_IFD_POINTER.to_le_bytes());
+        exif.extend(4u16.to_le_bytes());
+        exif.extend(1u32.to_le_bytes());
+        exif.extend(82u32.to_le_bytes());
+        exif.extend(0u32.to_le_bytes());
+        exif.extend(b"Canon\0");
+        // exif IFD at 56 with an f-number at 56 + 2 + 12 + 4 = 74
+        exif.extend(1u16.to_le_bytes());
+        exif.extend(0x829du16.to_le_bytes());
+        exif.extend(5u16.to_le_bytes());
+        exif.extend(1u32.to_le_bytes());
+        exif.extend(74u32.to_le_bytes());
+        exif.extend(0u32.to_le_bytes());
+        exif.extend(28u32.to_le_bytes());
+        exif.extend(10u32.to_le_bytes());
+        // GPS IFD at 82 with a latitude at 82 + 2 + 2 * 12 + 4 = 112
+        exif.extend(2u16.to_le_bytes());
+        exif.extend(0x0001u16.to_le_bytes());
+        exif.extend(2u16.to_le_bytes());
+        exif.extend(2u32.to_le_bytes());
+        exif.extend(b"N\0\0\0");
+        exif.extend(0x0002u16.to_le_bytes());
+        exif.extend(5u16.to_le_bytes());
+        exif.extend(3u32.to_le_bytes());
+        exif.extend(112u32.to_le_bytes());
+        exif.extend(0u32.to_le_bytes());
+        for (n, d) in &[(39u32, 1u32), (21, 1), (6, 1)] {
+            exif.extend(n.to_le_bytes());
+            exif.extend(d.to_le_bytes());
+        }
+        exif
+    }
+
+    #[test]
+    fn test_exif_fields() {
+        let fields = exif_fields(&make_exif());
+        assert_eq!(fields["tiff:Make"], "Canon");
+        assert_eq!(fields["exif:FNumber"], "28/10");
+        assert_eq!(fields["exif:GPSLatitude"], "39,21.1000N");
+
+        // malformed blocks are ignored
+        assert!(exif_fields(&make_exif()[..40]).len() <= 1);
+        assert!(exif_fields(b"not exif").is_empty());
+    }
+
+    #[test]
+    fn test_xmp_fields() {
+        let xmp = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+            <rdf:Description tiff:Make="Nikon" exif:FNumber="4/1">
+                <dc:title><rdf:Alt><rdf:li xml:lang="x-default">Dusk</rdf:li></rdf:Alt></dc:title>
+                <dc:subject><rdf:Bag><rdf:li>sea</rdf:li><rdf:li>light</rdf:li></rdf:Bag></dc:subject>
+                <exif:Flash><exif:Fired>True</exif:Fired></exif:Flash>
+            </rdf:Description></rdf:RDF></x:xmpmeta>"#;
+        let fields = xmp_fields(xmp);
+        assert_eq!(fields["tiff:Make"], "Nikon");
+        assert_eq!(fields["dc:title"], "Dusk");
+        assert_eq!(fields["dc:subject"], json!(["sea", "light"]));
+        assert!(!fields.contains_key("exif:Flash"));
+    }
+
+    #[test]
+    fn test_extract_metadata() {
+        let mut jpeg = Jpeg::from_bytes(Bytes::from_static(include_bytes!(
+            "../tests/fixtures/earth_apollo17.jpg"
+        )))
+        .unwrap();
+        jpeg.set_exif(Some(Bytes::from(make_exif())));
+        let mut data = Vec::new();
+        jpeg.encoder().write_to(&mut data).unwrap();
+
+        // the location is private unless allowed
+        let assertions =
+            extract_metadata("jpg", &data, &MetadataExtractionOptions::default()).unwrap();
+        let json = &assertions
+            .iter()
+            .find(|a| a.label == labels::EXIF)
+            .unwrap()
+            .data;
+        assert_eq!(json["tiff:Make"], "Canon");
+        assert_eq!(json["@context"]["exif"], "http://ns.adobe.com/exif/1.0/");
+        assert!(json.get("exif:GPSLatitude").is_none());
+
+        let mut options = MetadataExtractionOptions::new(&["exif:GPSLatitude"]);
+        options.allow("exif:FNumber");
+        let assertions = extract_metadata("jpg", &data, &options).unwrap();
+        assert_eq!(assertions.len(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Counters and timings for sign and verify operations.
+//!
+//! Install a [`MetricsRecorder`] once at startup with [`set_metrics_recorder`] to
+//! forward these measurements to a metrics system such as Prometheus.
+
+use std::time::Duration;
+
+use once_cell::sync::OnceCell;
+
+use crate::{Error, Result};
+
+/// Receives measurements from the SDK.
+///
+/// All methods have empty default implementations so recorders only need to
+/// implement the measurements they are interested in.
+/// Calls are made on the thread doing the work and should return quickly.
+pub trait MetricsRecorder: Send + Sync {
+    /// Asset data of `bytes` length was hashed in `elapsed` time.
+    fn hash_completed(&self, _bytes: u64, _elapsed: Duration) {}
+
+    /// A Time Stamp Authority request completed in `elapsed` time.
+    fn tsa_request(&self, _elapsed: Duration, _success: bool) {}
+
+    /// An OCSP responder request completed in `elapsed` time.
+    fn ocsp_request(&self, _elapsed: Duration, _success: bool) {}
+
+    /// Verification of a manifest store completed in `elapsed` time.
+    fn verify_completed(&self, _elapsed: Duration, _success: bool) {}
+}
+
+struct NoMetrics;
+
+impl MetricsRecorder for NoMetrics {}
+
+static RECORDER: OnceCell<Box<dyn MetricsRecorder>> = OnceCell::new();
+
+/// Install the global metrics recorder.
+///
+/// This can only be done once per process, subsequent calls return an error.
+pub fn set_metrics_recorder(recorder: Box<dyn MetricsRecorder>) -> Result<()> {
+    RECORDER
+        .set(recorder)
+        .map_err(|_recorder| Error::BadParam("metrics recorder already set".to_string()))
+}
+
+// returns the installed recorder or a recorder that does nothing
+pub(crate) fn metrics() -> &'static dyn MetricsRecorder {
+    match RECORDER.get() {
+        Some(recorder) => recorder.as_ref(),
+        None => &NoMetrics,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_default_recorder() {
+        // the default recorder accepts everything and does nothing
+        let recorder = metrics();
+        recorder.hash_completed(100, Duration::from_millis(1));
+        recorder.tsa_request(Duration::from_millis(1), true);
+        recorder.ocsp_request(Duration::from_millis(1), false);
+        recorder.verify_completed(Duration::from_millis(1), true);
+    }
+
+    #[test]
+    fn test_set_recorder() {
+        // the recorder is global, so it is only installed by this test
+        struct Recorder;
+        impl MetricsRecorder for Recorder {}
+
+        let installed = set_metrics_recorder(Box::new(Recorder));
+        let again = set_metrics_recorder(Box::new(Recorder));
+        assert!(installed.is_ok());
+        assert!(matches!(again, Err(Error::BadParam(_))));
+    }
+}
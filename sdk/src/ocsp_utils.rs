@@ -13,31 +13,128 @@
 
 use crate::error::{Error, Result};
 use crate::openssl::check_chain_order_der;
+use crate::signer::{CertChainPolicy, Signer, TimeStampPolicy};
 use crate::status_tracker::{log_item, StatusTracker};
-use crate::validation_status;
+use crate::validation_status::ValidationStatusCode;
+use crate::{CertificateAcceptancePolicy, DynamicAssertion, X5ChainPlacement};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use conv::ConvUtil;
+use once_cell::sync::Lazy;
 use openssl::ocsp::{self, OcspBasicResponse, OcspCertStatus, OcspRevokedStatus};
-use std::io::Read;
+use std::{
+    collections::HashMap,
+    io::Read,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
 
 const DATE_FMT: &str = "%b %d %H:%M:%S %Y %Z";
 
-/// OcspData - struct to contain the OCSPResponse DER and the time
-/// for the next OCSP check
-pub struct OcspData {
-    pub ocsp_der: Vec<u8>,
-    pub next_update: DateTime<Utc>,
+/// How long a fetched OCSP response is reused before it is refreshed
+const DEFAULT_OCSP_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How long a failed fetch is remembered before it is retried, doubled after each
+/// further failure up to the TTL
+const OCSP_RETRY_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Room reserved for an OCSP response, enough for responses that carry the
+/// responder's certificate chain
+const OCSP_RESPONSE_RESERVE: usize = 8 * 1024;
+
+/// Fetches OCSP responses from a responder.
+///
+/// Implement this to route OCSP requests through a proxy or a custom HTTP client
+/// and pass it to [`OcspCache::with_fetcher`].
+pub trait OcspFetcher: Send + Sync {
+    /// Send the DER encoded OCSPRequest to `responder_url` and return the DER encoded OCSPResponse.
+    fn fetch(&self, responder_url: &str, request_der: &[u8]) -> Result<Vec<u8>>;
 }
 
-impl OcspData {
+/// Default [`OcspFetcher`] sending HTTP GET requests (RFC 6960 Appendix A.1).
+#[derive(Clone, Debug, Default)]
+pub struct HttpOcspFetcher {
+    timeout: Option<Duration>,
+}
+
+impl HttpOcspFetcher {
     pub fn new() -> Self {
-        OcspData {
-            ocsp_der: Vec::new(),
-            next_update: Utc::now(),
+        Self::default()
+    }
+
+    /// Set the timeout of each request
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl OcspFetcher for HttpOcspFetcher {
+    fn fetch(&self, responder_url: &str, request_der: &[u8]) -> Result<Vec<u8>> {
+        let url = url::Url::parse(responder_url)
+            .map_err(|_e| Error::BadParam(format!("invalid OCSP responder: {}", responder_url)))?;
+        let req_url = url
+            .join(&base64::encode(request_der))
+            .map_err(|_e| Error::BadParam(format!("invalid OCSP responder: {}", responder_url)))?;
+
+        let mut request = ureq::get(req_url.as_str());
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
         }
+        if let Some(host) = url.host() {
+            request = request.set("Host", &host.to_string()); // for responders that don't support http 1.0
+        }
+        let response = request
+            .call()
+            .map_err(|e| Error::OtherError(format!("OCSP request failed: {}", e).into()))?;
+
+        if response.status() != 200 {
+            return Err(Error::OtherError(
+                format!("OCSP responder returned status {}", response.status()).into(),
+            ));
+        }
+
+        let len = response
+            .header("Content-Length")
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(2000);
+
+        let mut ocsp_rsp: Vec<u8> = Vec::with_capacity(len);
+        response
+            .into_reader()
+            .take(1000000)
+            .read_to_end(&mut ocsp_rsp)?;
+
+        Ok(ocsp_rsp)
     }
 }
 
+// the fetcher held by VerifyOptions, compared by identity
+#[derive(Clone)]
+pub(crate) struct SharedOcspFetcher(pub Arc<dyn OcspFetcher>);
+
+impl std::fmt::Debug for SharedOcspFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OcspFetcher")
+    }
+}
+
+impl PartialEq for SharedOcspFetcher {
+    fn eq(&self, other: &Self) -> bool {
+        // compare the data pointers only, vtables may be duplicated
+        Arc::as_ptr(&self.0) as *const u8 == Arc::as_ptr(&other.0) as *const u8
+    }
+}
+
+/// OcspData - struct to contain the OCSPResponse DER and the time
+/// for the next OCSP check
+pub struct OcspData {
+    pub ocsp_der: Vec<u8>,
+    pub next_update: DateTime<Utc>,
+}
+
 impl Default for OcspData {
     fn default() -> Self {
         Self {
@@ -47,6 +144,15 @@ impl Default for OcspData {
     }
 }
 
+// the room to reserve for the OCSP response of `certs`, none if the end-entity cert
+// does not name a responder
+pub(crate) fn ocsp_reserve_size(certs: &[Vec<u8>]) -> usize {
+    match certs.first().and_then(|cert| get_ocsp_responders(cert)) {
+        Some(responders) if !responders.is_empty() => OCSP_RESPONSE_RESERVE,
+        _ => 0,
+    }
+}
+
 fn get_ocsp_responders(cert_der: &[u8]) -> Option<Vec<String>> {
     let cert = openssl::x509::X509::from_der(cert_der).ok()?;
 
@@ -65,8 +171,8 @@ fn get_ocsp_responders(cert_der: &[u8]) -> Option<Vec<String>> {
 /// retrieve the OCSPResponse.
 /// If successful returns OcspData containing the DER encoded OCSPResponse and the DateTime for when this cached response should
 /// be refreshed.  None otherwise.
-pub fn get_ocsp_response(certs: &[Vec<u8>]) -> Option<OcspData> {
-    //} Option<DateTime<Utc>>) {
+/// The request is sent with `fetcher`.
+pub fn fetch_ocsp_response(certs: &[Vec<u8>], fetcher: &dyn OcspFetcher) -> Option<OcspData> {
     // must be in hierarchical order for this to work
     if certs.len() < 2 || !check_chain_order_der(certs) {
         return None;
@@ -74,7 +180,6 @@ pub fn get_ocsp_response(certs: &[Vec<u8>]) -> Option<OcspData> {
 
     if let Some(responders) = get_ocsp_responders(&certs[0]) {
         for r in responders {
-            let url = url::Url::parse(&r).ok()?;
             let subject = openssl::x509::X509::from_der(&certs[0]).ok()?;
             let issuer = openssl::x509::X509::from_der(&certs[1]).ok()?;
 
@@ -87,55 +192,40 @@ pub fn get_ocsp_response(certs: &[Vec<u8>]) -> Option<OcspData> {
 
             let mut ocsp_req = ocsp::OcspRequest::new().ok()?;
             ocsp_req.add_id(cert_id).ok()?;
-            let request_str = base64::encode(ocsp_req.to_der().ok()?);
-
-            let req_url = url.join(&request_str).ok()?;
-
-            let request = ureq::get(req_url.as_str());
-            let response = if let Some(host) = url.host() {
-                request.set("Host", &host.to_string()).call().ok()? // for responders that don't support http 1.0
-            } else {
-                request.call().ok()?
+            let request_der = ocsp_req.to_der().ok()?;
+
+            let start = instant::Instant::now();
+            let response = fetcher.fetch(&r, &request_der);
+            crate::metrics::metrics().ocsp_request(start.elapsed(), response.is_ok());
+            let ocsp_rsp = match response {
+                Ok(ocsp_rsp) => ocsp_rsp,
+                Err(_) => continue,
             };
 
-            if response.status() == 200 {
-                let len = response
-                    .header("Content-Length")
-                    .and_then(|s| s.parse::<usize>().ok())
-                    .unwrap_or(2000);
-
-                let mut ocsp_rsp: Vec<u8> = Vec::with_capacity(len);
-
-                response
-                    .into_reader()
-                    .take(1000000)
-                    .read_to_end(&mut ocsp_rsp)
-                    .ok()?;
-
-                // sanity check response
-                let ocsp_response = ocsp::OcspResponse::from_der(&ocsp_rsp).ok()?;
-                if ocsp_response.status() == ocsp::OcspResponseStatus::SUCCESSFUL {
-                    if let Ok(basic_response) = ocsp_response.basic() {
-                        if let Some(cert_status) =
-                            get_end_entity_cert_status(certs, &basic_response)
+            // sanity check response
+            let ocsp_response = match ocsp::OcspResponse::from_der(&ocsp_rsp) {
+                Ok(ocsp_response) => ocsp_response,
+                Err(_) => continue,
+            };
+            if ocsp_response.status() == ocsp::OcspResponseStatus::SUCCESSFUL {
+                if let Ok(basic_response) = ocsp_response.basic() {
+                    if let Some(cert_status) = get_end_entity_cert_status(certs, &basic_response) {
+                        if cert_status.status == OcspCertStatus::GOOD
+                            || cert_status.status == OcspCertStatus::REVOKED
+                                && cert_status.reason == OcspRevokedStatus::REMOVE_FROM_CRL
                         {
-                            if cert_status.status == OcspCertStatus::GOOD
-                                || cert_status.status == OcspCertStatus::REVOKED
-                                    && cert_status.reason == OcspRevokedStatus::REMOVE_FROM_CRL
-                            {
-                                let next_update = NaiveDateTime::parse_from_str(
-                                    &cert_status.next_update.to_string(),
-                                    DATE_FMT,
-                                )
-                                .ok()?;
+                            let next_update = NaiveDateTime::parse_from_str(
+                                &cert_status.next_update.to_string(),
+                                DATE_FMT,
+                            )
+                            .ok()?;
 
-                                let output = OcspData {
-                                    ocsp_der: ocsp_rsp,
-                                    next_update: DateTime::from_utc(next_update, chrono::Utc),
-                                };
+                            let output = OcspData {
+                                ocsp_der: ocsp_rsp,
+                                next_update: DateTime::from_utc(next_update, chrono::Utc),
+                            };
 
-                                return Some(output);
-                            }
+                            return Some(output);
                         }
                     }
                 }
@@ -145,6 +235,312 @@ pub fn get_ocsp_response(certs: &[Vec<u8>]) -> Option<OcspData> {
     None
 }
 
+struct OcspCacheEntry {
+    ocsp_der: Vec<u8>,
+    next_update: DateTime<Utc>,
+    fetched: Instant,
+    refreshing: bool,
+    failures: u32,
+}
+
+impl OcspCacheEntry {
+    // the response if it is still current
+    fn current(&self) -> Option<Vec<u8>> {
+        if !self.ocsp_der.is_empty() && Utc::now() < self.next_update {
+            Some(self.ocsp_der.clone())
+        } else {
+            None
+        }
+    }
+
+    // whether the response should be fetched again
+    fn stale(&self, ttl: Duration) -> bool {
+        if self.failures > 0 {
+            self.fetched.elapsed() >= retry_backoff(self.failures, ttl)
+        } else {
+            self.fetched.elapsed() >= ttl || Utc::now() >= self.next_update
+        }
+    }
+}
+
+struct OcspCacheState {
+    ttl: Duration,
+    entries: HashMap<Vec<u8>, OcspCacheEntry>,
+}
+
+struct OcspCacheInner {
+    fetcher: Box<dyn OcspFetcher>,
+    state: Mutex<OcspCacheState>,
+    refreshed: Condvar,
+}
+
+impl OcspCacheInner {
+    fn refresh(&self, certs: &[Vec<u8>]) -> Option<Vec<u8>> {
+        let key = certs.first()?.clone();
+
+        // fetch without holding the lock so readers are never blocked by the network
+        let ocsp_data = fetch_ocsp_response(certs, self.fetcher.as_ref());
+
+        let mut state = self.state.lock().ok()?;
+        let entry = state.entries.entry(key).or_insert_with(|| OcspCacheEntry {
+            ocsp_der: Vec::new(),
+            next_update: Utc::now(),
+            fetched: Instant::now(),
+            refreshing: false,
+            failures: 0,
+        });
+
+        match ocsp_data {
+            Some(ocsp_data) => {
+                entry.ocsp_der = ocsp_data.ocsp_der;
+                entry.next_update = ocsp_data.next_update;
+                entry.failures = 0;
+            }
+            None => {
+                // a failed refresh keeps the previous response until it is no longer current,
+                // and is not retried until the backoff has passed
+                entry.failures = entry.failures.saturating_add(1);
+            }
+        }
+        entry.fetched = Instant::now();
+        entry.refreshing = false;
+
+        let ocsp_der = entry.current();
+        self.refreshed.notify_all();
+        ocsp_der
+    }
+}
+
+// how long to wait before retrying after `failures` consecutive failed fetches
+fn retry_backoff(failures: u32, ttl: Duration) -> Duration {
+    let factor = 1u32
+        .checked_shl(failures.saturating_sub(1))
+        .unwrap_or(u32::MAX);
+    OCSP_RETRY_BACKOFF
+        .checked_mul(factor)
+        .map_or(ttl, |backoff| backoff.min(ttl))
+}
+
+/// Cache of OCSP responses keyed by signing certificate.
+///
+/// Lookups never block on the network: a missing or expired response is
+/// fetched on a background thread while the current one, if any, is returned.
+/// Responses are refreshed when their TTL expires or at the responder's
+/// `nextUpdate`, whichever comes first. A failed fetch is not retried for 30
+/// seconds, doubling after each further failure up to the TTL, so an unreachable
+/// responder is not queried on every lookup. Clones share the same cache, so one
+/// cache can serve any number of signers through [`OcspSigner`].
+#[derive(Clone)]
+pub struct OcspCache {
+    inner: Arc<OcspCacheInner>,
+}
+
+impl OcspCache {
+    /// Create a cache fetching responses over HTTP
+    pub fn new() -> Self {
+        Self::with_fetcher(Box::new(HttpOcspFetcher::new()))
+    }
+
+    /// The cache shared by the signers of the SDK, fetching responses over HTTP.
+    ///
+    /// Signers created for the same certificate reuse its response instead of each
+    /// fetching their own.
+    pub fn shared() -> Self {
+        static SHARED: Lazy<OcspCache> = Lazy::new(OcspCache::new);
+        SHARED.clone()
+    }
+
+    /// Create a cache fetching responses with `fetcher`
+    pub fn with_fetcher(fetcher: Box<dyn OcspFetcher>) -> Self {
+        OcspCache {
+            inner: Arc::new(OcspCacheInner {
+                fetcher,
+                state: Mutex::new(OcspCacheState {
+                    ttl: DEFAULT_OCSP_TTL,
+                    entries: HashMap::new(),
+                }),
+                refreshed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Set how long a response is reused before it is refreshed, one hour by default
+    pub fn set_ttl(&self, ttl: Duration) -> &Self {
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.ttl = ttl;
+        }
+        self
+    }
+
+    /// Returns the cached OCSP response for the end-entity cert of `certs`.
+    ///
+    /// Starts a background refresh when there is no response yet or it has expired.
+    pub fn get(&self, certs: &[Vec<u8>]) -> Option<Vec<u8>> {
+        let key = certs.first()?;
+
+        let (ocsp_der, stale) = {
+            let mut state = self.inner.state.lock().ok()?;
+            let ttl = state.ttl;
+            match state.entries.get_mut(key) {
+                Some(entry) => {
+                    let stale = !entry.refreshing && entry.stale(ttl);
+                    if stale {
+                        entry.refreshing = true;
+                    }
+                    (entry.current(), stale)
+                }
+                None => {
+                    state.entries.insert(
+                        key.clone(),
+                        OcspCacheEntry {
+                            ocsp_der: Vec::new(),
+                            next_update: Utc::now(),
+                            fetched: Instant::now(),
+                            refreshing: true,
+                            failures: 0,
+                        },
+                    );
+                    (None, true)
+                }
+            }
+        };
+
+        if stale {
+            let inner = self.inner.clone();
+            let certs = certs.to_vec();
+            std::thread::spawn(move || inner.refresh(&certs));
+        }
+
+        ocsp_der
+    }
+
+    /// Fetches the OCSP response for the end-entity cert of `certs`, blocking until done.
+    ///
+    /// Use this to populate the cache before signing.
+    pub fn refresh(&self, certs: &[Vec<u8>]) -> Option<Vec<u8>> {
+        self.inner.refresh(certs)
+    }
+
+    // waits until no refresh of the response for `certs` is in progress
+    #[cfg(test)]
+    fn wait_refreshed(&self, certs: &[Vec<u8>], timeout: Duration) -> bool {
+        let state = match self.inner.state.lock() {
+            Ok(state) => state,
+            Err(_) => return false,
+        };
+        self.inner
+            .refreshed
+            .wait_timeout_while(state, timeout, |state| {
+                certs
+                    .first()
+                    .and_then(|key| state.entries.get(key))
+                    .map_or(false, |entry| entry.refreshing)
+            })
+            .map_or(false, |(_state, result)| !result.timed_out())
+    }
+}
+
+impl Default for OcspCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wraps a [`Signer`] so its OCSP response comes from a shared [`OcspCache`].
+///
+/// The response is never fetched while signing, so signing does not wait on
+/// the OCSP responder.
+///
+/// ```ignore
+/// let cache = OcspCache::new();
+/// let signer = OcspSigner::new(get_signer_from_files(cert, key, "ps256", None)?, cache.clone());
+/// ```
+pub struct OcspSigner<S: Signer> {
+    signer: S,
+    cache: OcspCache,
+    ocsp_size: AtomicUsize,
+}
+
+impl<S: Signer> OcspSigner<S> {
+    /// Wrap `signer`, starting a background fetch of its OCSP response if it is not cached
+    ///
+    /// Room for the largest expected response is reserved up front, since the
+    /// response may only arrive after the manifest has been sized.
+    pub fn new(signer: S, cache: OcspCache) -> Self {
+        let ocsp_size = signer.certs().ok().map_or(0, |certs| {
+            let cached = cache.get(&certs).map_or(0, |ocsp| ocsp.len());
+            cached.max(ocsp_reserve_size(&certs))
+        });
+
+        OcspSigner {
+            signer,
+            cache,
+            ocsp_size: AtomicUsize::new(ocsp_size),
+        }
+    }
+}
+
+impl<S: Signer> Signer for OcspSigner<S> {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> Option<String> {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        self.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.signer.reserve_size() + self.ocsp_size.load(Ordering::Relaxed)
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.signer.time_authority_url()
+    }
+
+    fn time_stamp_policy(&self) -> TimeStampPolicy {
+        self.signer.time_stamp_policy()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        let ocsp = self
+            .signer
+            .certs()
+            .ok()
+            .and_then(|certs| self.cache.get(&certs))
+            .or_else(|| self.signer.ocsp_val())?;
+        self.ocsp_size.fetch_max(ocsp.len(), Ordering::Relaxed);
+        Some(ocsp)
+    }
+
+    fn cert_chain_policy(&self) -> CertChainPolicy {
+        self.signer.cert_chain_policy()
+    }
+
+    fn include_ocsp(&self) -> bool {
+        self.signer.include_ocsp()
+    }
+
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        self.signer.certificate_acceptance_policy()
+    }
+
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        self.signer.x5chain_placement()
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        self.signer.dynamic_assertions()
+    }
+
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        self.signer.cosigners()
+    }
+}
+
 // find the certificate to check
 fn get_end_entity_cert_status<'a>(
     certs: &[Vec<u8>],
@@ -169,7 +565,7 @@ fn get_end_entity_cert_status<'a>(
 
 // check to OCSP response against the supplied certs and signing time (if available)
 // Returns - empty result on success
-pub(crate) fn _check_ocsp_response(
+pub(crate) fn check_ocsp_response(
     ocsp_response_der: &[u8],
     certs: &[Vec<u8>],
     signing_time: Option<chrono::DateTime<chrono::Utc>>,
@@ -183,52 +579,9 @@ pub(crate) fn _check_ocsp_response(
         if ocsp_response.status() == ocsp::OcspResponseStatus::SUCCESSFUL {
             if let Ok(basic_response) = ocsp_response.basic() {
                 if let Some(cert_status) = get_end_entity_cert_status(certs, &basic_response) {
-                    if cert_status.status == OcspCertStatus::GOOD
-                        || cert_status.status == OcspCertStatus::REVOKED
-                            && cert_status.reason == OcspRevokedStatus::REMOVE_FROM_CRL
-                    {
-                        // check cert range against signing time
-                        let this_update = NaiveDateTime::parse_from_str(
-                            &cert_status.this_update.to_string(),
-                            DATE_FMT,
-                        )
-                        .map_err(|_e| Error::CoseInvalidCert)?
-                        .timestamp();
-                        let next_update = NaiveDateTime::parse_from_str(
-                            &cert_status.next_update.to_string(),
-                            DATE_FMT,
-                        )
-                        .map_err(|_e| Error::CoseInvalidCert)?
-                        .timestamp();
-
-                        // check to see if we are within range or current time within range
-                        let in_range = if let Some(st) = signing_time {
-                            println!("{}, {}, {}", this_update, next_update, st.timestamp());
-                            st.timestamp() >= this_update && st.timestamp() <= next_update
-                        } else {
-                            // no timestamp so check against current time
-                            // use instant to avoid wasm issues
-                            let now_f64 = instant::now() / 1000.0;
-                            let now: i64 = now_f64
-                                .approx_as::<i64>()
-                                .map_err(|_e| Error::BadParam("system time invalid".to_string()))?;
-
-                            now >= this_update && now <= next_update
-                        };
-
-                        if !in_range {
-                            let log_item = log_item!(
-                                "OCSP_RESPONSE",
-                                "certificate revoked",
-                                "check_ocsp_response"
-                            )
-                            .error(Error::CoseCertRevoked)
-                            .validation_status(validation_status::SIGNING_CREDENTIAL_REVOKED);
-                            validation_log.log_silent(log_item);
-
-                            return Err(Error::CoseCertRevoked);
-                        }
-                    } else if cert_status.status == OcspCertStatus::REVOKED
+                    // a good status holds for signatures made before the response was produced,
+                    // so only a revocation is compared with the signing time
+                    if cert_status.status == OcspCertStatus::REVOKED
                         && cert_status.reason != OcspRevokedStatus::REMOVE_FROM_CRL
                     {
                         // if it was revoked check if was revoked after signing time
@@ -262,7 +615,7 @@ pub(crate) fn _check_ocsp_response(
                                     "check_ocsp_response"
                                 )
                                 .error(Error::CoseCertRevoked)
-                                .validation_status(validation_status::SIGNING_CREDENTIAL_REVOKED);
+                                .validation_status(ValidationStatusCode::SigningCredentialRevoked);
                                 validation_log.log_silent(log_item);
 
                                 return Err(Error::CoseCertRevoked);
@@ -274,7 +627,7 @@ pub(crate) fn _check_ocsp_response(
                                 "check_ocsp_response"
                             )
                             .error(Error::CoseCertRevoked)
-                            .validation_status(validation_status::SIGNING_CREDENTIAL_REVOKED);
+                            .validation_status(ValidationStatusCode::SigningCredentialRevoked);
                             validation_log.log_silent(log_item);
 
                             return Err(Error::CoseCertRevoked);
@@ -288,3 +641,86 @@ pub(crate) fn _check_ocsp_response(
     // Per the spec if we cannot interpret the OCSP data treat it as if it did not exist
     Ok(())
 }
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::utils::test::TestCert;
+    use openssl::{pkey::PKey, rsa::Rsa};
+
+    struct CountingFetcher {
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl OcspFetcher for CountingFetcher {
+        fn fetch(&self, _responder_url: &str, _request_der: &[u8]) -> Result<Vec<u8>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(Error::BadParam("offline".to_string()))
+        }
+    }
+
+    fn make_chain() -> Vec<Vec<u8>> {
+        let ca_key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let ee_key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let ca = TestCert::new("Test CA", &ca_key).ca(None).build();
+        let ee = TestCert::new("Test Signer", &ee_key)
+            .issuer(&ca, &ca_key)
+            .ocsp_url("http://ocsp.example.com")
+            .build();
+        vec![ee.to_der().unwrap(), ca.to_der().unwrap()]
+    }
+
+    #[test]
+    fn test_ocsp_cache_shared() {
+        assert!(Arc::ptr_eq(
+            &OcspCache::shared().inner,
+            &OcspCache::shared().inner
+        ));
+    }
+
+    #[test]
+    fn test_ocsp_cache_refresh() {
+        let certs = make_chain();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let cache = OcspCache::with_fetcher(Box::new(CountingFetcher {
+            calls: calls.clone(),
+        }));
+
+        // blocking fetch goes to the responder named by the end-entity cert
+        assert!(cache.refresh(&certs).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // the failure is cached, so lookups, including those from clones, do not fetch again
+        assert!(cache.get(&certs).is_none());
+        assert!(cache.clone().get(&certs).is_none());
+        assert!(cache.wait_refreshed(&certs, Duration::from_secs(10)));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // once the backoff has passed a lookup refreshes in the background
+        cache.set_ttl(Duration::from_secs(0));
+        assert!(cache.get(&certs).is_none());
+        assert!(cache.wait_refreshed(&certs, Duration::from_secs(10)));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_ocsp_retry_backoff() {
+        let ttl = DEFAULT_OCSP_TTL;
+        assert_eq!(retry_backoff(1, ttl), OCSP_RETRY_BACKOFF);
+        assert_eq!(retry_backoff(2, ttl), OCSP_RETRY_BACKOFF * 2);
+        assert_eq!(retry_backoff(3, ttl), OCSP_RETRY_BACKOFF * 4);
+        assert_eq!(retry_backoff(20, ttl), ttl);
+        assert_eq!(retry_backoff(u32::MAX, ttl), ttl);
+    }
+
+    #[test]
+    fn test_ocsp_reserve_size() {
+        let certs = make_chain();
+        assert_eq!(ocsp_reserve_size(&certs), OCSP_RESPONSE_RESERVE);
+        // the CA names no responder
+        assert_eq!(ocsp_reserve_size(&certs[1..]), 0);
+        assert_eq!(ocsp_reserve_size(&[]), 0);
+    }
+}
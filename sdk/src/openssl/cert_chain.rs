@@ -0,0 +1,266 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::{io::Read, time::Duration};
+
+use openssl::{
+    nid::Nid,
+    pkey::{HasPublic, PKeyRef},
+    x509::{X509Ref, X509VerifyResult, X509},
+};
+
+use crate::error::{wrap_openssl_err, Error, Result};
+
+// longest chain that will be built, guards against issuer loops
+const MAX_CHAIN_LEN: usize = 10;
+
+// largest certificate accepted from an AIA CA Issuers location
+const MAX_AIA_CERT_SIZE: u64 = 64 * 1024;
+
+/// Assembles the x5chain for a signing certificate.
+///
+/// Starting from the end-entity certificate each issuer is looked up in the
+/// supplied intermediates and, if enabled, fetched from the Authority
+/// Information Access CA Issuers location of the certificate it issued.
+/// Self-signed roots found this way are left out of the chain as they belong
+/// in the validator's trust store.
+///
+/// ```ignore
+/// let mut builder = CertChainBuilder::new();
+/// builder.add_intermediates(&std::fs::read("intermediates.pem")?)?;
+/// let chain = builder.build(&std::fs::read("leaf.pem")?)?;
+/// let signer = get_signer(&chain, &key, "ps256", None)?;
+/// ```
+#[derive(Default)]
+pub struct CertChainBuilder {
+    intermediates: Vec<X509>,
+    fetch_aia: bool,
+    timeout: Option<Duration>,
+}
+
+impl CertChainBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the PEM encoded certificates of `pem` to the intermediates used to build chains
+    pub fn add_intermediates(&mut self, pem: &[u8]) -> Result<&mut Self> {
+        let certs = X509::stack_from_pem(pem).map_err(wrap_openssl_err)?;
+        self.intermediates.extend(certs);
+        Ok(self)
+    }
+
+    /// Set whether missing issuers are fetched from their AIA CA Issuers location
+    pub fn set_fetch_aia(&mut self, fetch_aia: bool) -> &mut Self {
+        self.fetch_aia = fetch_aia;
+        self
+    }
+
+    /// Set the timeout of each AIA request
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Build the ordered chain for the PEM encoded certificates of `signcert`,
+    /// returning it PEM encoded, end-entity certificate first.
+    ///
+    /// `signcert` may hold only the end-entity certificate or part of its chain in any order.
+    pub fn build(&self, signcert: &[u8]) -> Result<Vec<u8>> {
+        let certs = X509::stack_from_pem(signcert).map_err(wrap_openssl_err)?;
+        let leaf = find_leaf(&certs)?;
+        let chain = self.build_from(certs, leaf)?;
+
+        let mut pem = Vec::new();
+        for cert in chain {
+            pem.extend(cert.to_pem().map_err(wrap_openssl_err)?);
+        }
+        Ok(pem)
+    }
+
+    fn build_from(&self, mut certs: Vec<X509>, leaf: usize) -> Result<Vec<X509>> {
+        let mut chain = vec![certs.remove(leaf)];
+
+        while chain.len() < MAX_CHAIN_LEN {
+            let current = &chain[chain.len() - 1];
+            if is_self_signed(current) {
+                break;
+            }
+
+            // supplied certs are used as is, extra issuers are only added below the root
+            let issuer = if let Some(pos) = certs.iter().position(|c| is_issuer(c, current)) {
+                certs.remove(pos)
+            } else if let Some(issuer) = self
+                .intermediates
+                .iter()
+                .find(|c| is_issuer(c, current) && !is_self_signed(c))
+            {
+                issuer.clone()
+            } else if let Some(issuer) = self.fetch_issuer(current) {
+                issuer
+            } else {
+                break;
+            };
+
+            chain.push(issuer);
+        }
+
+        if !certs.is_empty() {
+            return Err(Error::BadParam(
+                "certificate chain contains certificates that are not issuers of the signing certificate"
+                    .to_string(),
+            ));
+        }
+
+        Ok(chain)
+    }
+
+    fn fetch_issuer(&self, cert: &X509Ref) -> Option<X509> {
+        if !self.fetch_aia {
+            return None;
+        }
+
+        let urls: Vec<String> = cert
+            .authority_info()?
+            .iter()
+            .filter(|ad| ad.method().nid() == Nid::AD_CA_ISSUERS)
+            .filter_map(|ad| ad.location().uri().map(|uri| uri.to_owned()))
+            .collect();
+
+        urls.iter()
+            .filter_map(|url| self.fetch_cert(url))
+            .find(|issuer| is_issuer(issuer, cert) && !is_self_signed(issuer))
+    }
+
+    fn fetch_cert(&self, url: &str) -> Option<X509> {
+        let mut request = ureq::get(url);
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        let response = request.call().ok()?;
+        if response.status() != 200 {
+            return None;
+        }
+
+        let mut data = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_AIA_CERT_SIZE)
+            .read_to_end(&mut data)
+            .ok()?;
+
+        // CA Issuers locations usually serve DER, but PEM is common too
+        X509::from_der(&data)
+            .or_else(|_| X509::from_pem(&data))
+            .ok()
+    }
+}
+
+/// Orders the certificates of `certs` into a chain starting with the certificate for `pkey`.
+///
+/// Used by the signers so a chain in the wrong order is repaired instead of rejected.
+pub(crate) fn order_chain<T: HasPublic>(certs: Vec<X509>, pkey: &PKeyRef<T>) -> Result<Vec<X509>> {
+    let leaf = certs
+        .iter()
+        .position(|c| c.public_key().map_or(false, |k| k.public_eq(pkey)))
+        .ok_or_else(|| Error::BadParam("no certificate matches the signing key".to_string()))?;
+
+    CertChainBuilder::new().build_from(certs, leaf)
+}
+
+// the end-entity cert is the one that did not issue any of the others
+fn find_leaf(certs: &[X509]) -> Result<usize> {
+    let mut leaves = certs.iter().enumerate().filter(|(i, c)| {
+        !certs
+            .iter()
+            .enumerate()
+            .any(|(j, other)| *i != j && is_issuer(c, other))
+    });
+
+    match (leaves.next(), leaves.next()) {
+        (Some((leaf, _)), None) => Ok(leaf),
+        (None, _) => Err(Error::CoseX5ChainMissing),
+        (Some(_), Some(_)) => Err(Error::BadParam(
+            "certificate chain has more than one end-entity certificate".to_string(),
+        )),
+    }
+}
+
+fn is_issuer(issuer: &X509Ref, subject: &X509Ref) -> bool {
+    issuer.issued(subject) == X509VerifyResult::OK
+        && issuer
+            .public_key()
+            .and_then(|k| subject.verify(&k))
+            .unwrap_or(false)
+}
+
+fn is_self_signed(cert: &X509Ref) -> bool {
+    is_issuer(cert, cert)
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::utils::test::TestCert;
+    use openssl::{
+        pkey::{PKey, Private},
+        rsa::Rsa,
+    };
+
+    #[test]
+    fn test_chain_building() {
+        let keys: Vec<PKey<Private>> = (0..3)
+            .map(|_| PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap())
+            .collect();
+        let root = TestCert::new("Root", &keys[0]).ca(None).build();
+        let intermediate = TestCert::new("Intermediate", &keys[1])
+            .issuer(&root, &keys[0])
+            .ca(None)
+            .build();
+        let leaf = TestCert::new("Leaf", &keys[2])
+            .issuer(&intermediate, &keys[1])
+            .build();
+
+        // out of order chains are repaired
+        let chain = order_chain(
+            vec![root.clone(), leaf.clone(), intermediate.clone()],
+            &keys[2],
+        )
+        .unwrap();
+        let subjects: Vec<Vec<u8>> = chain.iter().map(|c| c.to_der().unwrap()).collect();
+        assert_eq!(
+            subjects,
+            vec![
+                leaf.to_der().unwrap(),
+                intermediate.to_der().unwrap(),
+                root.to_der().unwrap()
+            ]
+        );
+
+        // certs outside the chain are rejected
+        let other = TestCert::new("Other", &keys[0]).ca(None).build();
+        assert!(order_chain(vec![leaf.clone(), other], &keys[2]).is_err());
+
+        // a leaf alone is completed from the intermediates, leaving out the root
+        let mut bundle = intermediate.to_pem().unwrap();
+        bundle.extend(root.to_pem().unwrap());
+        let mut builder = CertChainBuilder::new();
+        builder.add_intermediates(&bundle).unwrap();
+        let pem = builder.build(&leaf.to_pem().unwrap()).unwrap();
+        let chain = X509::stack_from_pem(&pem).unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[1].to_der().unwrap(), intermediate.to_der().unwrap());
+    }
+}
@@ -26,7 +26,7 @@ use x509_parser::der_parser::{
     der::{parse_der_integer, parse_der_sequence_defined_g},
 };
 
-use super::check_chain_order;
+use super::cert_chain::order_chain;
 
 /// Implements `Signer` trait using OpenSSL's implementation of
 /// ECDSA encryption.
@@ -64,12 +64,11 @@ impl ConfigurableSigner for EcSigner {
         let pkey = EcKey::private_key_from_pem(pkey).map_err(wrap_openssl_err)?;
         let signcerts = X509::stack_from_pem(signcert).map_err(wrap_openssl_err)?;
 
-        // make sure cert chains are in order
-        if !check_chain_order(&signcerts) {
-            return Err(Error::BadParam(
-                "certificate chain is not in correct order".to_string(),
-            ));
-        }
+        // put the chain in order starting with the cert for the signing key
+        let signcerts = order_chain(
+            signcerts,
+            &PKey::from_ec_key(pkey.clone()).map_err(wrap_openssl_err)?,
+        )?;
 
         Ok(EcSigner {
             signcerts,
@@ -87,7 +86,7 @@ impl Signer for EcSigner {
         let key = PKey::from_ec_key(self.pkey.clone()).map_err(wrap_openssl_err)?;
 
         let mut signer = match self.alg.as_ref() {
-            "es256" => openssl::sign::Signer::new(MessageDigest::sha256(), &key)?,
+            "es256" | "es256k" => openssl::sign::Signer::new(MessageDigest::sha256(), &key)?,
             "es384" => openssl::sign::Signer::new(MessageDigest::sha384(), &key)?,
             "es512" => openssl::sign::Signer::new(MessageDigest::sha512(), &key)?,
             _ => return Err(Error::UnsupportedType),
@@ -154,7 +153,7 @@ fn der_to_p1363(data: &[u8], alg: &str) -> Result<Vec<u8>> {
     let mut s = extfmt::Hexlify(p.s).to_string();
 
     let sig_len: usize = match alg {
-        "es256" => 64,
+        "es256" | "es256k" => 64,
         "es384" => 96,
         "es512" => 132,
         _ => return Err(Error::UnsupportedType),
@@ -256,4 +255,19 @@ mod tests {
         assert!(signature.len() >= 64);
         assert!(signature.len() <= signer.reserve_size());
     }
+
+    #[test]
+    fn es256k_signer() {
+        let temp_dir = tempdir().unwrap();
+
+        let (signer, _) = temp_signer::get_ec_signer(&temp_dir.path(), "es256k", None);
+
+        let data = b"some sample content to sign";
+        println!("data len = {}", data.len());
+
+        let signature = signer.sign(data).unwrap();
+        println!("signature.len = {}", signature.len());
+        assert!(signature.len() >= 64);
+        assert!(signature.len() <= signer.reserve_size());
+    }
 }
@@ -34,7 +34,7 @@ impl CoseValidator for EcValidator {
         let key = PKey::from_ec_key(public_key).map_err(wrap_openssl_err)?;
 
         let mut verifier = match self.alg.as_ref() {
-            "es256" => openssl::sign::Verifier::new(MessageDigest::sha256(), &key)?,
+            "es256" | "es256k" => openssl::sign::Verifier::new(MessageDigest::sha256(), &key)?,
             "es384" => openssl::sign::Verifier::new(MessageDigest::sha384(), &key)?,
             "es512" => openssl::sign::Verifier::new(MessageDigest::sha512(), &key)?,
             _ => return Err(Error::UnsupportedType),
@@ -43,7 +43,7 @@ impl CoseValidator for EcValidator {
         // is this an expected P1363 sig size
         if sig.len()
             != match self.alg.as_ref() {
-                "es256" => 64,
+                "es256" | "es256k" => 64,
                 "es384" => 96,
                 "es512" => 132,
                 _ => return Err(Error::UnsupportedType),
@@ -157,6 +157,29 @@ mod tests {
         assert!(validator.validate(&signature, data, &pub_key).unwrap());
     }
 
+    #[test]
+    fn sign_and_validate_es256k() {
+        let temp_dir = tempdir().unwrap();
+
+        let (signer, cert_path) = temp_signer::get_ec_signer(&temp_dir.path(), "es256k", None);
+
+        let data = b"some sample content to sign";
+        println!("data len = {}", data.len());
+
+        let signature = signer.sign(data).unwrap();
+        println!("signature.len = {}", signature.len());
+        assert!(signature.len() >= 64);
+        assert!(signature.len() <= signer.reserve_size());
+
+        let cert_bytes = std::fs::read(&cert_path).unwrap();
+
+        let signcert = openssl::x509::X509::from_pem(&cert_bytes).unwrap();
+        let pub_key = signcert.public_key().unwrap().public_key_to_der().unwrap();
+
+        let validator = EcValidator::new("es256k");
+        assert!(validator.validate(&signature, data, &pub_key).unwrap());
+    }
+
     #[test]
     fn bad_sig_es256() {
         let temp_dir = tempdir().unwrap();
@@ -20,7 +20,7 @@ use openssl::{
     x509::X509,
 };
 
-use super::check_chain_order;
+use super::cert_chain::order_chain;
 
 /// Implements `Signer` trait using OpenSSL's implementation of
 /// Edwards Curve encryption.
@@ -62,12 +62,8 @@ impl ConfigurableSigner for EdSigner {
             return Err(Error::UnsupportedType); // only ed25519 is supported by C2PA
         }
 
-        // make sure cert chains are in order
-        if !check_chain_order(&signcerts) {
-            return Err(Error::BadParam(
-                "certificate chain is not in correct order".to_string(),
-            ));
-        }
+        // put the chain in order starting with the cert for the signing key
+        let signcerts = order_chain(signcerts, &pkey)?;
 
         Ok(EdSigner {
             signcerts,
@@ -29,32 +29,14 @@ pub(crate) use ed_signer::EdSigner;
 mod ed_validator;
 pub(crate) use ed_validator::EdValidator;
 
+mod cert_chain;
+pub use cert_chain::CertChainBuilder;
+
 pub mod signer;
 pub mod temp_signer;
 
 use openssl::x509::X509;
 
-pub(crate) fn check_chain_order(certs: &[X509]) -> bool {
-    if certs.len() > 1 {
-        for (i, c) in certs.iter().enumerate() {
-            if let Some(next_c) = certs.get(i + 1) {
-                if let Ok(pkey) = next_c.public_key() {
-                    if let Ok(verified) = c.verify(&pkey) {
-                        if !verified {
-                            return false;
-                        }
-                    } else {
-                        return false;
-                    }
-                } else {
-                    return false;
-                }
-            }
-        }
-    }
-    true
-}
-
 pub(crate) fn check_chain_order_der(cert_ders: &[Vec<u8>]) -> bool {
     if cert_ders.len() > 1 {
         let mut certs: Vec<X509> = Vec::new();
@@ -12,11 +12,15 @@
 // each license.
 
 use crate::{
-    ocsp_utils::{get_ocsp_response, OcspData},
+    ocsp_utils::{ocsp_reserve_size, OcspCache},
     signer::ConfigurableSigner,
     Error, Result, Signer,
 };
-use std::{cell::Cell, fs, path::Path};
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 //use extfmt::Hexlify;
 use openssl::{
@@ -26,7 +30,7 @@ use openssl::{
     x509::X509,
 };
 
-use super::check_chain_order;
+use super::cert_chain::order_chain;
 
 /// Implements `Signer` trait using OpenSSL's implementation of
 /// SHA256 + RSA encryption.
@@ -36,30 +40,19 @@ pub struct RsaSigner {
 
     certs_size: usize,
     timestamp_size: usize,
-    ocsp_size: Cell<usize>,
+    ocsp_size: AtomicUsize,
 
     alg: String,
     tsa_url: Option<String>,
-    ocsp_rsp: Cell<OcspData>,
+    ocsp_cache: OcspCache,
 }
 
 impl RsaSigner {
+    /// Fetch the OCSP response now, blocking until done
     pub fn update_ocsp(&self) {
-        // do we need an update
-        let now = chrono::offset::Utc::now();
-
-        // is it time for an OCSP update
-        let ocsp_data = self.ocsp_rsp.take();
-        let next_update = ocsp_data.next_update;
-        self.ocsp_rsp.set(ocsp_data);
-        if now < next_update {
-            return;
-        }
-
         if let Ok(certs) = self.certs() {
-            if let Some(ocsp_rsp) = get_ocsp_response(&certs) {
-                self.ocsp_size.set(ocsp_rsp.ocsp_der.len());
-                self.ocsp_rsp.set(ocsp_rsp);
+            if let Some(ocsp_rsp) = self.ocsp_cache.refresh(&certs) {
+                self.ocsp_size.fetch_max(ocsp_rsp.len(), Ordering::Relaxed);
             }
         }
     }
@@ -88,26 +81,28 @@ impl ConfigurableSigner for RsaSigner {
         let rsa = Rsa::private_key_from_pem(pkey).map_err(wrap_openssl_err)?;
         let pkey = PKey::from_rsa(rsa).map_err(wrap_openssl_err)?;
 
-        // make sure cert chains are in order
-        if !check_chain_order(&signcerts) {
-            return Err(Error::BadParam(
-                "certificate chain is not in correct order".to_string(),
-            ));
-        }
+        // put the chain in order starting with the cert for the signing key
+        let signcerts = order_chain(signcerts, &pkey)?;
 
         let signer = RsaSigner {
             signcerts,
             pkey,
             certs_size: signcert.len(),
             timestamp_size: 4096, // todo: call out to TSA to get actual timestamp and use that size
-            ocsp_size: Cell::new(0),
+            ocsp_size: AtomicUsize::new(0),
             alg,
             tsa_url,
-            ocsp_rsp: Cell::new(OcspData::new()),
+            ocsp_cache: OcspCache::shared(),
         };
 
-        // get OCSP if possible
-        signer.update_ocsp();
+        // start fetching the OCSP response in the background if it is not cached yet,
+        // reserving room for the largest expected response since it may arrive later
+        if let Ok(certs) = signer.certs() {
+            let cached = signer.ocsp_cache.get(&certs).map_or(0, |ocsp| ocsp.len());
+            signer
+                .ocsp_size
+                .store(cached.max(ocsp_reserve_size(&certs)), Ordering::Relaxed);
+        }
 
         Ok(signer)
     }
@@ -160,7 +155,8 @@ impl Signer for RsaSigner {
     }
 
     fn reserve_size(&self) -> usize {
-        1024 + self.certs_size + self.timestamp_size + self.ocsp_size.get() // the Cose_Sign1 contains complete certs, timestamps and ocsp so account for size
+        1024 + self.certs_size + self.timestamp_size + self.ocsp_size.load(Ordering::Relaxed)
+        // the Cose_Sign1 contains complete certs, timestamps and ocsp so account for size
     }
 
     fn certs(&self) -> Result<Vec<Vec<u8>>> {
@@ -183,17 +179,10 @@ impl Signer for RsaSigner {
     }
 
     fn ocsp_val(&self) -> Option<Vec<u8>> {
-        // update OCSP if needed
-        self.update_ocsp();
-
-        let ocsp_data = self.ocsp_rsp.take();
-        let ocsp_rsp = ocsp_data.ocsp_der.clone();
-        self.ocsp_rsp.set(ocsp_data);
-        if !ocsp_rsp.is_empty() {
-            Some(ocsp_rsp)
-        } else {
-            None
-        }
+        // expired responses are refreshed in the background so signing never waits on the responder
+        let ocsp_rsp = self.ocsp_cache.get(&self.certs().ok()?)?;
+        self.ocsp_size.fetch_max(ocsp_rsp.len(), Ordering::Relaxed);
+        Some(ocsp_rsp)
     }
 }
 
@@ -16,27 +16,31 @@ use crate::{
 /// * `signcert` - A buffer containing a signcert
 /// * `pkey` - A buffer containing a public key file
 /// * `alg` - A format for signing. Must be one of (`rs256`, `rs384`, `rs512`,
-///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, or `ed25519`).
+///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, or `ed25519`,
+///   or `es256k` with the `es256k` feature).
 /// * `tsa_url` - Optional URL for a timestamp authority.
 ///
 /// # Returns
 ///
-/// Returns a [`Signer`] instance or Error
+/// Returns a [`Signer`] instance or Error. The signer can be shared across threads,
+/// see [`SignerPool`](crate::SignerPool).
 
 pub fn get_signer(
     signcert: &[u8],
     pkey: &[u8],
     alg: &str,
     tsa_url: Option<String>,
-) -> Result<Box<dyn Signer>> {
+) -> Result<Box<dyn Signer + Send + Sync>> {
     Ok(match alg {
+        // secp256k1 is not an algorithm C2PA allows, so it must be opted into
+        "es256k" if !cfg!(feature = "es256k") => return Err(Error::BadParam(alg.to_owned())),
         "ps256" | "ps384" | "ps512" => Box::new(RsaSigner::from_signcert_and_pkey(
             signcert,
             pkey,
             alg.to_owned(),
             tsa_url,
         )?),
-        "es256" | "es384" | "es512" => Box::new(EcSigner::from_signcert_and_pkey(
+        "es256" | "es384" | "es512" | "es256k" => Box::new(EcSigner::from_signcert_and_pkey(
             signcert,
             pkey,
             alg.to_owned(),
@@ -61,27 +65,31 @@ pub fn get_signer(
 /// * `signcert_path` - A path to the signing cert file
 /// * `pkey_path` - A path to the public key file
 /// * `alg` - A format for signing. Must be one of (`rs256`, `rs384`, `rs512`,
-///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, or `ed25519`).
+///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, or `ed25519`,
+///   or `es256k` with the `es256k` feature).
 /// * `tsa_url` - Optional URL for a timestamp authority.
 ///
 /// # Returns
 ///
-/// Returns a [`Signer`] instance or Error
+/// Returns a [`Signer`] instance or Error. The signer can be shared across threads,
+/// see [`SignerPool`](crate::SignerPool).
 
 pub fn get_signer_from_files<P: AsRef<Path>>(
     signcert_path: P,
     pkey_path: P,
     alg: &str,
     tsa_url: Option<String>,
-) -> Result<Box<dyn Signer>> {
+) -> Result<Box<dyn Signer + Send + Sync>> {
     Ok(match alg {
+        // secp256k1 is not an algorithm C2PA allows, so it must be opted into
+        "es256k" if !cfg!(feature = "es256k") => return Err(Error::BadParam(alg.to_owned())),
         "ps256" | "ps384" | "ps512" => Box::new(RsaSigner::from_files(
             &signcert_path,
             &pkey_path,
             alg.to_owned(),
             tsa_url,
         )?),
-        "es256" | "es384" | "es512" => Box::new(EcSigner::from_files(
+        "es256" | "es384" | "es512" | "es256k" => Box::new(EcSigner::from_files(
             &signcert_path,
             &pkey_path,
             alg.to_owned(),
@@ -24,7 +24,12 @@
 //! that is deleted upon completion of the test. (We recommend using
 //! the [tempfile](https://crates.io/crates/tempfile) crate.)
 //!
-//! This module should be used only for testing purposes.
+//! [`get_temp_chain_signer`] makes a signing certificate issued through an
+//! intermediate by a root, with validity periods that can be set to make
+//! expired certificates for negative tests.
+//!
+//! This module should be used only for testing purposes. Outside of this crate it
+//! is available with the `test_signing` feature.
 
 // Since this module is intended for testing purposes, all of
 // its functions are allowed to panic.
@@ -35,6 +40,24 @@ use std::{
     io::Write,
     path::{Path, PathBuf},
     process::{Child, Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use openssl::{
+    asn1::Asn1Time,
+    bn::{BigNum, MsbOption},
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    rsa::Rsa,
+    x509::{
+        extension::{
+            AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage,
+            SubjectKeyIdentifier,
+        },
+        X509Builder, X509NameBuilder, X509,
+    },
 };
 
 use crate::{
@@ -85,7 +108,7 @@ pub fn get_temp_signer<P: AsRef<Path>>(path: P) -> (RsaSigner, PathBuf) {
 ///
 /// * `path` - A directory (which must already exist) to receive the temporary
 ///   private key / certificate pair.
-/// * `alg` - A format for signing. Must be one of (`es256`, `es384`, or `es512`).
+/// * `alg` - A format for signing. Must be one of (`es256`, `es384`, `es512`, or `es256k`).
 /// * `tsa_url` - Optional URL for a timestamp authority.
 ///
 /// # Returns
@@ -106,6 +129,7 @@ pub fn get_ec_signer<P: AsRef<Path>>(
         "es256" => ("ec256_key", "prime256v1"),
         "es384" => ("ec384_key", "secp384r1"),
         "es512" => ("ec512_key", "secp521r1"),
+        "es256k" => ("ec256k_key", "secp256k1"),
         _ => {
             panic!("Unknown EC signer alg {:#?}", alg);
         }
@@ -250,7 +274,7 @@ pub fn get_rsa_signer<P: AsRef<Path>>(
 /// * `path` - A directory (which must already exist) to receive the temporary
 ///   private key / certificate pair.
 /// * `alg` - A format for signing. Must be one of (`rs256`, `rs384`, `rs512`,
-///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, or `ed25519`).
+///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, `es256k`, or `ed25519`).
 /// * `tsa_url` - Optional URL for a timestamp authority.
 ///
 /// # Returns
@@ -272,7 +296,7 @@ pub fn get_temp_signer_by_alg<P: AsRef<Path>>(
             let (signer, sign_cert_path) = get_rsa_signer(path, alg, tsa_url);
             (Box::new(signer), sign_cert_path)
         }
-        "es256" | "es384" | "es512" => {
+        "es256" | "es384" | "es512" | "es256k" => {
             let (signer, sign_cert_path) = get_ec_signer(path, alg, tsa_url);
             (Box::new(signer), sign_cert_path)
         }
@@ -288,6 +312,291 @@ pub fn get_temp_signer_by_alg<P: AsRef<Path>>(
     }
 }
 
+/// The validity period of a certificate made for testing purposes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Validity {
+    /// The start of the period, in seconds since the Unix epoch
+    pub not_before: i64,
+    /// The end of the period, in seconds since the Unix epoch
+    pub not_after: i64,
+}
+
+impl Validity {
+    /// A period starting now and lasting `days` days.
+    pub fn days_from_now(days: u32) -> Self {
+        let now = unix_now();
+        Validity {
+            not_before: now,
+            not_after: now + i64::from(days) * SECONDS_PER_DAY,
+        }
+    }
+
+    /// A period of 30 days that ended a day ago.
+    pub fn expired() -> Self {
+        let now = unix_now();
+        Validity {
+            not_before: now - 31 * SECONDS_PER_DAY,
+            not_after: now - SECONDS_PER_DAY,
+        }
+    }
+}
+
+impl Default for Validity {
+    /// The 180 days the certificates of the other temporary signers are valid for
+    fn default() -> Self {
+        Validity::days_from_now(180)
+    }
+}
+
+/// The validity periods of the certificates of a chain made by
+/// [`get_temp_chain_signer`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ChainValidity {
+    /// The validity of the self-signed root certificate
+    pub root: Validity,
+    /// The validity of the intermediate certificate
+    pub intermediate: Validity,
+    /// The validity of the signing certificate
+    pub leaf: Validity,
+}
+
+/// The files of a certificate chain made by [`get_temp_chain_signer`].
+#[derive(Clone, Debug)]
+pub struct TempCertChain {
+    /// The PEM encoded signing certificate followed by the intermediate certificate
+    pub chain_path: PathBuf,
+    /// The PEM encoded root certificate, to be added to the trust anchors of a test
+    pub root_path: PathBuf,
+    /// The PEM encoded private key of the signing certificate
+    pub key_path: PathBuf,
+}
+
+/// Create a signer whose certificate is issued by an intermediate certificate,
+/// itself issued by a self-signed root, for testing purposes.
+///
+/// Every key in the chain is of the type used by `alg`.
+///
+/// # Arguments
+///
+/// * `path` - A directory (which must already exist) to receive the temporary
+///   certificates and private key.
+/// * `alg` - A format for signing. Must be one of (`rs256`, `rs384`, `rs512`,
+///   `ps256`, `ps384`, `ps512`, `es256`, `es384`, `es512`, `es256k`, or `ed25519`).
+/// * `validity` - The validity periods of the certificates.
+/// * `tsa_url` - Optional URL for a timestamp authority.
+///
+/// # Returns
+///
+/// Returns a tuple of `(signer, chain)` where `signer` is the [`Signer`]
+/// instance and `chain` has the paths of the certificates and private key.
+///
+/// # Example
+///
+/// ```ignore
+/// use c2pa::temp_signer::{get_temp_chain_signer, ChainValidity, Validity};
+///
+/// let temp_dir = tempfile::tempdir().unwrap();
+/// let validity = ChainValidity {
+///     leaf: Validity::expired(),
+///     ..Default::default()
+/// };
+/// let (signer, chain) = get_temp_chain_signer(temp_dir.path(), "es256", &validity, None);
+/// ```
+///
+/// # Panics
+///
+/// Can panic if unable to generate the keys or certificates, or if `alg` is
+/// not a supported format.
+pub fn get_temp_chain_signer<P: AsRef<Path>>(
+    path: P,
+    alg: &str,
+    validity: &ChainValidity,
+    tsa_url: Option<String>,
+) -> (Box<dyn Signer>, TempCertChain) {
+    let alg = alg.to_lowercase();
+    let (chain_path, key_path) = make_key_path_pair(&path, &format!("{}_chain", alg));
+    let (root_path, _) = make_key_path_pair(&path, &format!("{}_chain_root", alg));
+
+    let (root_key, digest) = generate_key(&alg);
+    let root = make_cert(
+        "Test Root CA",
+        &root_key,
+        digest,
+        &validity.root,
+        true,
+        None,
+    );
+
+    let (intermediate_key, _) = generate_key(&alg);
+    let intermediate = make_cert(
+        "Test Intermediate CA",
+        &intermediate_key,
+        digest,
+        &validity.intermediate,
+        true,
+        Some((&root, &root_key)),
+    );
+
+    let (leaf_key, _) = generate_key(&alg);
+    let leaf = make_cert(
+        "Test Signer",
+        &leaf_key,
+        digest,
+        &validity.leaf,
+        false,
+        Some((&intermediate, &intermediate_key)),
+    );
+
+    let mut chain_pem = leaf.to_pem().unwrap();
+    chain_pem.extend(intermediate.to_pem().unwrap());
+    std::fs::write(&chain_path, chain_pem).unwrap();
+    std::fs::write(&root_path, root.to_pem().unwrap()).unwrap();
+    std::fs::write(&key_path, leaf_key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+
+    let signer: Box<dyn Signer> = match alg.as_str() {
+        "rs256" | "rs384" | "rs512" | "ps256" | "ps384" | "ps512" => {
+            Box::new(RsaSigner::from_files(&chain_path, &key_path, alg.clone(), tsa_url).unwrap())
+        }
+        "es256" | "es384" | "es512" | "es256k" => {
+            Box::new(EcSigner::from_files(&chain_path, &key_path, alg.clone(), tsa_url).unwrap())
+        }
+        _ => Box::new(EdSigner::from_files(&chain_path, &key_path, alg.clone(), tsa_url).unwrap()),
+    };
+
+    (
+        signer,
+        TempCertChain {
+            chain_path,
+            root_path,
+            key_path,
+        },
+    )
+}
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+// generate a key for `alg` and return it with the digest its certificates are signed with
+fn generate_key(alg: &str) -> (PKey<Private>, MessageDigest) {
+    let rsa_key = || PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    let ec_key = |curve: Nid| {
+        let group = EcGroup::from_curve_name(curve).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    };
+
+    match alg {
+        "rs256" | "ps256" => (rsa_key(), MessageDigest::sha256()),
+        "rs384" | "ps384" => (rsa_key(), MessageDigest::sha384()),
+        "rs512" | "ps512" => (rsa_key(), MessageDigest::sha512()),
+        "es256" => (ec_key(Nid::X9_62_PRIME256V1), MessageDigest::sha256()),
+        "es384" => (ec_key(Nid::SECP384R1), MessageDigest::sha384()),
+        "es512" => (ec_key(Nid::SECP521R1), MessageDigest::sha512()),
+        "es256k" => (ec_key(Nid::SECP256K1), MessageDigest::sha256()),
+        // Ed25519 signs the whole message without a separate digest
+        "ed25519" => (PKey::generate_ed25519().unwrap(), MessageDigest::null()),
+        _ => {
+            panic!("Unknown chain signer alg {:#?}", alg);
+        }
+    }
+}
+
+// make a certificate for `key` issued by the certificate and key of `issuer`,
+// self-signed when there is no issuer
+fn make_cert(
+    common_name: &str,
+    key: &PKey<Private>,
+    digest: MessageDigest,
+    validity: &Validity,
+    is_ca: bool,
+    issuer: Option<(&X509, &PKey<Private>)>,
+) -> X509 {
+    let mut name = X509NameBuilder::new().unwrap();
+    name.append_entry_by_nid(Nid::ORGANIZATIONNAME, "FOR TESTING ONLY")
+        .unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, common_name)
+        .unwrap();
+    let name = name.build();
+
+    let mut serial = BigNum::new().unwrap();
+    serial.rand(64, MsbOption::MAYBE_ZERO, false).unwrap();
+
+    let mut builder = X509Builder::new().unwrap();
+    builder.set_version(2).unwrap();
+    builder
+        .set_serial_number(&serial.to_asn1_integer().unwrap())
+        .unwrap();
+    builder.set_subject_name(&name).unwrap();
+    match issuer {
+        Some((cert, _)) => builder.set_issuer_name(cert.subject_name()).unwrap(),
+        None => builder.set_issuer_name(&name).unwrap(),
+    }
+    builder.set_pubkey(key).unwrap();
+    builder
+        .set_not_before(&Asn1Time::from_unix(validity.not_before).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::from_unix(validity.not_after).unwrap())
+        .unwrap();
+
+    if is_ca {
+        let mut basic_constraints = BasicConstraints::new();
+        basic_constraints.critical().ca();
+        if issuer.is_some() {
+            basic_constraints.pathlen(0);
+        }
+        builder
+            .append_extension(basic_constraints.build().unwrap())
+            .unwrap();
+        builder
+            .append_extension(
+                KeyUsage::new()
+                    .critical()
+                    .key_cert_sign()
+                    .crl_sign()
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+    } else {
+        builder
+            .append_extension(
+                KeyUsage::new()
+                    .critical()
+                    .digital_signature()
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        builder
+            .append_extension(ExtendedKeyUsage::new().email_protection().build().unwrap())
+            .unwrap();
+    }
+
+    let issuer_cert = issuer.map(|(cert, _)| &**cert);
+    let subject_key_id = SubjectKeyIdentifier::new()
+        .build(&builder.x509v3_context(issuer_cert, None))
+        .unwrap();
+    builder.append_extension(subject_key_id).unwrap();
+    if issuer_cert.is_some() {
+        let authority_key_id = AuthorityKeyIdentifier::new()
+            .keyid(true)
+            .build(&builder.x509v3_context(issuer_cert, None))
+            .unwrap();
+        builder.append_extension(authority_key_id).unwrap();
+    }
+
+    builder
+        .sign(issuer.map_or(key, |(_, issuer_key)| issuer_key), digest)
+        .unwrap();
+    builder.build()
+}
+
 fn make_key_path_pair<P: AsRef<Path>>(path: P, key_name: &str) -> (PathBuf, PathBuf) {
     let mut sign_cert_path = path.as_ref().to_path_buf();
     sign_cert_path.push(key_name);
@@ -329,7 +638,7 @@ fn create_x509_key_pair(
         .arg("-extensions")
         .arg("v3_ca")
         .arg("-addext")
-        .arg("keyUsage = digitalSignature")
+        .arg("keyUsage = critical, digitalSignature")
         .arg("-addext")
         .arg("extendedKeyUsage = emailProtection")
         .arg("-x509")
@@ -413,3 +722,37 @@ fn print_mac_openssl_warning() {
         eprintln!();
     }
 }
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{cert_profile::explain_cert_chain, CertRule};
+
+    #[test]
+    fn test_temp_chain_signer() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        for alg in ["ps256", "es384", "ed25519"].iter() {
+            let (signer, chain) =
+                get_temp_chain_signer(temp_dir.path(), alg, &ChainValidity::default(), None);
+            assert_eq!(signer.alg().as_deref(), Some(*alg));
+            assert_eq!(signer.certs().unwrap().len(), 2);
+            assert!(chain.root_path.exists());
+
+            let report = explain_cert_chain(&std::fs::read(&chain.chain_path).unwrap()).unwrap();
+            assert!(report.passed(), "{}", report);
+        }
+
+        // the signing certificate can be made already expired
+        let validity = ChainValidity {
+            leaf: Validity::expired(),
+            ..Default::default()
+        };
+        let (_signer, chain) = get_temp_chain_signer(temp_dir.path(), "es256", &validity, None);
+        let report = explain_cert_chain(&std::fs::read(&chain.chain_path).unwrap()).unwrap();
+        assert!(report
+            .signing_certificate
+            .failures()
+            .any(|r| r.rule == CertRule::Validity));
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Progress reporting and cancellation for long running operations.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{Error, Result};
+
+/// The phase of a sign or verify operation being reported
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProgressPhase {
+    /// Hashing the asset data
+    Hashing,
+    /// Signing the claim
+    Signing,
+    /// Writing the manifest store into the asset
+    Embedding,
+    /// Verifying claims, signatures and hashes. Progress advances in proportion
+    /// to the asset size as each check completes.
+    Verifying,
+}
+
+/// The `ProgressReporter` trait receives progress updates from sign and verify operations.
+///
+/// Implement this to drive a progress bar. Calls are made on the thread performing
+/// the operation, so implementations should return quickly.
+pub trait ProgressReporter {
+    /// Called as work progresses in the given phase.
+    /// `total_bytes` is 0 when the amount of work is not known.
+    fn report(&self, phase: ProgressPhase, bytes_processed: u64, total_bytes: u64);
+}
+
+/// A `ProgressReporter` that ignores all updates
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {
+    fn report(&self, _phase: ProgressPhase, _bytes_processed: u64, _total_bytes: u64) {}
+}
+
+/// A token that can be used to cancel a running operation from another thread.
+///
+/// Clones share the same state, so keep one clone and pass another to the operation.
+/// A cancelled operation returns [`Error::OperationCancelled`].
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a new token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of any operation using this token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+// Bundles a reporter and a cancellation token for passing through internal calls
+pub(crate) struct ProgressMonitor<'a> {
+    reporter: &'a dyn ProgressReporter,
+    cancel: Option<&'a CancellationToken>,
+}
+
+impl<'a> ProgressMonitor<'a> {
+    #[allow(dead_code)] // only used by file_io entry points
+    pub(crate) fn new(reporter: &'a dyn ProgressReporter, cancel: &'a CancellationToken) -> Self {
+        ProgressMonitor {
+            reporter,
+            cancel: Some(cancel),
+        }
+    }
+
+    // a monitor that does not report and can not be cancelled
+    pub(crate) fn none() -> ProgressMonitor<'static> {
+        ProgressMonitor {
+            reporter: &NoProgress,
+            cancel: None,
+        }
+    }
+
+    // report progress, returning an error if the operation has been cancelled
+    pub(crate) fn update(&self, phase: ProgressPhase, processed: u64, total: u64) -> Result<()> {
+        self.check_cancelled()?;
+        self.reporter.report(phase, processed, total);
+        Ok(())
+    }
+
+    pub(crate) fn check_cancelled(&self) -> Result<()> {
+        match self.cancel {
+            Some(token) if token.is_cancelled() => Err(Error::OperationCancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use std::cell::RefCell;
+
+    struct Recorder(RefCell<Vec<(ProgressPhase, u64, u64)>>);
+
+    impl ProgressReporter for Recorder {
+        fn report(&self, phase: ProgressPhase, bytes_processed: u64, total_bytes: u64) {
+            self.0
+                .borrow_mut()
+                .push((phase, bytes_processed, total_bytes));
+        }
+    }
+
+    #[test]
+    fn test_monitor_reports_and_cancels() {
+        let recorder = Recorder(RefCell::new(Vec::new()));
+        let token = CancellationToken::new();
+        let monitor = ProgressMonitor::new(&recorder, &token);
+
+        monitor.update(ProgressPhase::Hashing, 10, 100).unwrap();
+        assert_eq!(
+            recorder.0.borrow().as_slice(),
+            &[(ProgressPhase::Hashing, 10, 100)]
+        );
+
+        token.clone().cancel();
+        assert!(matches!(
+            monitor.update(ProgressPhase::Hashing, 20, 100),
+            Err(Error::OperationCancelled)
+        ));
+        assert_eq!(recorder.0.borrow().len(), 1);
+
+        assert!(ProgressMonitor::none().check_cancelled().is_ok());
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! The provenance of an asset as a graph.
+//!
+//! Manifests are the nodes of the graph and ingredients are its edges, pointing from the
+//! manifest that uses an ingredient to the manifest of that ingredient. Ingredients without
+//! a manifest are leaf nodes, so the graph shows every asset that went into the active one.
+
+use std::collections::{HashSet, VecDeque};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    jumbf::labels::manifest_label_from_uri, validation_status::ValidationStatus, ManifestStore,
+    Result,
+};
+
+/// How an ingredient was used by the manifest it belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum IngredientRelationship {
+    /// The asset was derived from the ingredient
+    #[serde(rename = "parentOf")]
+    ParentOf,
+    /// The ingredient was composed into the asset
+    #[serde(rename = "componentOf")]
+    ComponentOf,
+}
+
+impl IngredientRelationship {
+    /// The name of the relationship used in manifests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IngredientRelationship::ParentOf => "parentOf",
+            IngredientRelationship::ComponentOf => "componentOf",
+        }
+    }
+}
+
+/// A manifest, or an ingredient without one, in a provenance graph
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProvenanceNode {
+    /// Identifies the node in the graph. This is the manifest label, or for an
+    /// ingredient without a manifest, an id derived from the manifest using it.
+    pub id: String,
+    /// The label of the manifest, `None` for ingredients without a manifest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_label: Option<String>,
+    /// The title of the asset
+    pub title: String,
+    /// The format of the asset
+    pub format: String,
+    /// The issuer of the manifest's signing certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// Validation results for the manifest, found when it was validated or
+    /// recorded by the manifests using it as an ingredient
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub validation_status: Vec<ValidationStatus>,
+}
+
+impl ProvenanceNode {
+    /// Returns true if no validation failures were reported for the node
+    pub fn is_valid(&self) -> bool {
+        self.validation_status.iter().all(|s| s.passed())
+    }
+}
+
+/// An ingredient in a provenance graph
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProvenanceEdge {
+    /// The id of the node using the ingredient
+    pub from: String,
+    /// The id of the ingredient's node
+    pub to: String,
+    /// How the ingredient was used
+    pub relationship: IngredientRelationship,
+}
+
+/// The provenance graph of a manifest store
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct ProvenanceGraph {
+    /// The id of the active manifest's node
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<String>,
+    /// The nodes, starting with the active manifest in breadth first order
+    pub nodes: Vec<ProvenanceNode>,
+    /// The ingredient relationships between nodes
+    pub edges: Vec<ProvenanceEdge>,
+}
+
+impl ProvenanceGraph {
+    /// Build the provenance graph of a manifest store
+    pub fn from_manifest_store(manifest_store: &ManifestStore) -> Self {
+        let mut graph = ProvenanceGraph {
+            active: manifest_store.active_label().map(|l| l.to_owned()),
+            ..Default::default()
+        };
+
+        // visit manifests from the active one, then any that are not referenced from it
+        let mut labels: Vec<&String> = manifest_store.manifests().keys().collect();
+        labels.sort();
+        let mut queue: VecDeque<String> = graph.active.iter().cloned().collect();
+        queue.extend(labels.into_iter().cloned());
+
+        let mut visited = HashSet::new();
+        while let Some(label) = queue.pop_front() {
+            let manifest = match manifest_store.get(&label) {
+                Some(manifest) if visited.insert(label.clone()) => manifest,
+                _ => continue,
+            };
+
+            // statuses without a url come from loading the store, so belong to the active manifest
+            let is_active = graph.active.as_deref() == Some(label.as_str());
+            let validation_status = manifest_store
+                .validation_status()
+                .unwrap_or_default()
+                .iter()
+                .filter(|s| match s.url() {
+                    Some(url) => manifest_label_from_uri(url).as_deref() == Some(label.as_str()),
+                    None => is_active,
+                })
+                .cloned()
+                .collect();
+
+            let (title, format) = manifest
+                .asset()
+                .map(|a| (a.title().to_owned(), a.format().to_owned()))
+                .unwrap_or_default();
+            graph.add_node(ProvenanceNode {
+                id: label.clone(),
+                manifest_label: Some(label.clone()),
+                title,
+                format,
+                issuer: manifest.issuer(),
+                validation_status,
+            });
+
+            for (index, ingredient) in manifest.ingredients().iter().enumerate() {
+                let relationship = if ingredient.is_parent() {
+                    IngredientRelationship::ParentOf
+                } else {
+                    IngredientRelationship::ComponentOf
+                };
+                let recorded = ingredient.validation_status().unwrap_or_default();
+
+                let to = match ingredient.active_manifest() {
+                    Some(ingredient_label) => {
+                        queue.push_back(ingredient_label.to_owned());
+                        // the ingredient's node may not be built yet, so add the statuses to a stub
+                        graph.add_node(ProvenanceNode {
+                            id: ingredient_label.to_owned(),
+                            manifest_label: Some(ingredient_label.to_owned()),
+                            title: ingredient.title().to_owned(),
+                            format: ingredient.format().to_owned(),
+                            issuer: None,
+                            validation_status: recorded.to_vec(),
+                        });
+                        ingredient_label.to_owned()
+                    }
+                    None => {
+                        let id = format!("{}#ingredient{}", label, index);
+                        graph.add_node(ProvenanceNode {
+                            id: id.clone(),
+                            manifest_label: None,
+                            title: ingredient.title().to_owned(),
+                            format: ingredient.format().to_owned(),
+                            issuer: None,
+                            validation_status: recorded.to_vec(),
+                        });
+                        id
+                    }
+                };
+
+                graph.edges.push(ProvenanceEdge {
+                    from: label.clone(),
+                    to,
+                    relationship,
+                });
+            }
+        }
+
+        graph.order_nodes();
+        graph
+    }
+
+    /// Returns the node with the given id
+    pub fn node(&self, id: &str) -> Option<&ProvenanceNode> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    /// Returns the ingredient edges of the node with the given id
+    pub fn ingredients_of<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a ProvenanceEdge> {
+        self.edges.iter().filter(move |e| e.from == id)
+    }
+
+    /// Export the graph in the Graphviz DOT language.
+    ///
+    /// Nodes with validation failures are drawn in red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph provenance {\n");
+        for node in &self.nodes {
+            let mut attrs = format!("label={}", dot_string(&node_label(node)));
+            if node.manifest_label.is_none() {
+                attrs.push_str(", shape=box");
+            }
+            if !node.is_valid() {
+                attrs.push_str(", color=red");
+            }
+            if self.active.as_deref() == Some(node.id.as_str()) {
+                attrs.push_str(", penwidth=2");
+            }
+            dot.push_str(&format!("  {} [{}];\n", dot_string(&node.id), attrs));
+        }
+        for edge in &self.edges {
+            dot.push_str(&format!(
+                "  {} -> {} [label={}];\n",
+                dot_string(&edge.from),
+                dot_string(&edge.to),
+                dot_string(edge.relationship.as_str())
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Export the graph in the JSON Graph Format, see <https://jsongraphformat.info>
+    pub fn to_json_graph(&self) -> Result<String> {
+        let mut nodes = Map::new();
+        for node in &self.nodes {
+            nodes.insert(
+                node.id.clone(),
+                json!({
+                    "label": node_label(node),
+                    "metadata": serde_json::to_value(node)?,
+                }),
+            );
+        }
+        let edges: Vec<Value> = self
+            .edges
+            .iter()
+            .map(|e| {
+                json!({
+                    "source": e.from,
+                    "target": e.to,
+                    "relation": e.relationship.as_str(),
+                })
+            })
+            .collect();
+
+        let mut graph = json!({
+            "directed": true,
+            "nodes": nodes,
+            "edges": edges,
+        });
+        if let Some(active) = self.active.as_ref() {
+            graph["metadata"] = json!({ "active": active });
+        }
+
+        Ok(serde_json::to_string_pretty(&json!({ "graph": graph }))?)
+    }
+
+    // add a node, or merge it into the node already added with the same id
+    fn add_node(&mut self, node: ProvenanceNode) {
+        match self.nodes.iter_mut().find(|n| n.id == node.id) {
+            Some(existing) => {
+                if existing.issuer.is_none() {
+                    existing.issuer = node.issuer;
+                }
+                for status in node.validation_status {
+                    if !existing.validation_status.contains(&status) {
+                        existing.validation_status.push(status);
+                    }
+                }
+            }
+            None => self.nodes.push(node),
+        }
+    }
+
+    // stubs are added before the manifests they stand for, so order the nodes breadth first
+    fn order_nodes(&mut self) {
+        let mut order: Vec<String> = self.active.iter().cloned().collect();
+        let mut index = 0;
+        while index < order.len() {
+            for edge in self.edges.iter().filter(|e| e.from == order[index]) {
+                if !order.contains(&edge.to) {
+                    order.push(edge.to.clone());
+                }
+            }
+            index += 1;
+        }
+        let position = |id: &str| order.iter().position(|o| o == id).unwrap_or(order.len());
+        self.nodes.sort_by_key(|n| position(&n.id));
+    }
+}
+
+fn node_label(node: &ProvenanceNode) -> String {
+    if node.format.is_empty() {
+        node.title.clone()
+    } else {
+        format!("{} ({})", node.title, node.format)
+    }
+}
+
+// quote a string as a DOT identifier
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_provenance_graph() {
+        let image_bytes = include_bytes!("../tests/fixtures/CA.jpg");
+        let manifest_store =
+            ManifestStore::from_bytes("image/jpeg", image_bytes.to_vec(), true).unwrap();
+        let graph = manifest_store.provenance_graph();
+
+        // the active manifest comes first and is valid
+        let active = manifest_store.active_label().unwrap();
+        assert_eq!(graph.active.as_deref(), Some(active));
+        assert_eq!(graph.nodes[0].id, active);
+        assert!(graph.nodes[0].is_valid());
+        assert_eq!(
+            graph.ingredients_of(active).count(),
+            manifest_store.get_active().unwrap().ingredients().len()
+        );
+
+        // every manifest is a node and every edge joins two nodes
+        for label in manifest_store.manifests().keys() {
+            assert!(graph.node(label).is_some());
+        }
+        for edge in &graph.edges {
+            assert!(graph.node(&edge.from).is_some());
+            assert!(graph.node(&edge.to).is_some());
+        }
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph provenance {"));
+        assert_eq!(dot.matches(" -> ").count(), graph.edges.len());
+
+        let json: Value = serde_json::from_str(&graph.to_json_graph().unwrap()).unwrap();
+        assert_eq!(
+            json["graph"]["nodes"].as_object().unwrap().len(),
+            graph.nodes.len()
+        );
+        assert_eq!(json["graph"]["metadata"]["active"], active);
+    }
+}
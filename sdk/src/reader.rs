@@ -0,0 +1,295 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Reads a manifest store together with a structured report of its validation.
+
+use std::collections::HashMap;
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use c2pa_core::cose::{parse_sign1, x5chain};
+
+#[cfg(feature = "file_io")]
+use crate::progress::ProgressMonitor;
+use crate::{
+    cose_validator::get_sigtst,
+    status_tracker::DetailedStatusTracker,
+    store::Store,
+    time_stamp::{stored_timestamp_token_der, TstContainer},
+    Error, Manifest, ManifestStore, Result, ValidationResults, VerifyOptions,
+};
+
+/// Reads and validates the manifest store of an asset.
+///
+/// Along with the [`ManifestStore`], a `Reader` keeps the [`ValidationResults`] of
+/// every manifest that was validated, including the checks that passed, so callers
+/// do not have to interpret validation logs themselves. The results are also rolled
+/// up through the ingredients into a [`ValidationTree`](crate::ValidationTree).
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::Reader;
+/// # fn main() -> Result<()> {
+/// let reader = Reader::from_file("tests/fixtures/CA.jpg")?;
+/// if let Some(codes) = reader.validation_results().active_manifest_codes() {
+///     println!("valid: {}", codes.is_valid());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Reader {
+    manifest_store: ManifestStore,
+    validation_results: ValidationResults,
+    validation_log: DetailedStatusTracker,
+    signatures: HashMap<String, ManifestSignature>,
+}
+
+impl Reader {
+    /// Reads the manifest store of an asset held in memory.
+    /// `format` is the MIME type or extension of the asset.
+    pub fn from_bytes(format: &str, data: &[u8]) -> Result<Self> {
+        Self::from_bytes_with_options(format, data, &VerifyOptions::default())
+    }
+
+    /// Reads the manifest store of an asset held in memory, verifying it with the supplied options
+    pub fn from_bytes_with_options(
+        format: &str,
+        data: &[u8],
+        options: &VerifyOptions,
+    ) -> Result<Self> {
+        let mut validation_log = DetailedStatusTracker::new();
+        let store =
+            Store::load_from_memory_with_options(format, data, true, options, &mut validation_log)?;
+        Ok(Self::from_store(&store, validation_log))
+    }
+
+    /// Reads the manifest store of the asset at `path`
+    #[cfg(feature = "file_io")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_file_with_options(path, &VerifyOptions::default())
+    }
+
+    /// Reads the manifest store of the asset at `path`, verifying it with the supplied options
+    #[cfg(feature = "file_io")]
+    pub fn from_file_with_options<P: AsRef<Path>>(
+        path: P,
+        options: &VerifyOptions,
+    ) -> Result<Self> {
+        let mut validation_log = DetailedStatusTracker::new();
+        let store = Store::load_from_asset_with_progress(
+            path.as_ref(),
+            true,
+            options,
+            &mut validation_log,
+            &ProgressMonitor::none(),
+        )?;
+        Ok(Self::from_store(&store, validation_log))
+    }
+
+    fn from_store(store: &Store, mut validation_log: DetailedStatusTracker) -> Self {
+        let mut validation_results =
+            ValidationResults::from_log(store.provenance_label(), &validation_log);
+        let manifest_store = ManifestStore::from_store(store, &mut validation_log);
+
+        // manifests that could not be read are only reported by the manifest store
+        for status in manifest_store.validation_status().unwrap_or_default() {
+            validation_results.add(status.clone());
+        }
+        validation_results.build_tree(&manifest_store);
+
+        let signatures = store
+            .claims()
+            .iter()
+            .map(|claim| {
+                (
+                    claim.label().to_owned(),
+                    ManifestSignature {
+                        cose_sign1: claim.signature_val().clone(),
+                    },
+                )
+            })
+            .collect();
+
+        Reader {
+            manifest_store,
+            validation_results,
+            validation_log,
+            signatures,
+        }
+    }
+
+    /// Returns the manifest store
+    pub fn manifest_store(&self) -> &ManifestStore {
+        &self.manifest_store
+    }
+
+    /// Returns the active manifest
+    pub fn active_manifest(&self) -> Option<&Manifest> {
+        self.manifest_store.get_active()
+    }
+
+    /// Returns the validation results, grouped by manifest
+    pub fn validation_results(&self) -> &ValidationResults {
+        &self.validation_results
+    }
+
+    /// Returns the log of every check made while validating, which serializes to JSON
+    pub fn validation_log(&self) -> &DetailedStatusTracker {
+        &self.validation_log
+    }
+
+    /// Returns the manifest store as JSON
+    pub fn json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.manifest_store)?)
+    }
+
+    /// Returns the claim signature of the manifest with the given label
+    pub fn signature(&self, label: &str) -> Option<&ManifestSignature> {
+        self.signatures.get(label)
+    }
+
+    /// Returns the claim signature of the active manifest
+    pub fn active_signature(&self) -> Option<&ManifestSignature> {
+        self.manifest_store
+            .active_label()
+            .and_then(|label| self.signature(label))
+    }
+}
+
+/// The claim signature of a manifest, for archival and audit pipelines that keep the
+/// signature and its certificates apart from the asset.
+///
+/// The signature is exported as it is stored, it is not validated again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ManifestSignature {
+    cose_sign1: Vec<u8>,
+}
+
+impl ManifestSignature {
+    /// Returns the tagged COSE_Sign1 bytes, as stored in the manifest
+    pub fn cose_sign1(&self) -> &[u8] {
+        &self.cose_sign1
+    }
+
+    /// Returns the DER encoded certificate chain from the `x5chain` header, starting
+    /// with the signing certificate
+    pub fn cert_chain(&self) -> Result<Vec<Vec<u8>>> {
+        let sign1 = parse_sign1(&self.cose_sign1)?;
+        Ok(x5chain(&sign1)?)
+    }
+
+    /// Returns the DER encoded RFC 3161 `TimeStampToken`s of the signature, from its
+    /// `sigTst2` or `sigTst` header. Headers that store the whole `TimeStampResp` have
+    /// the token extracted, so every entry is a CMS `ContentInfo` whichever header it
+    /// came from. Returns an empty list if the signature is not time-stamped.
+    pub fn time_stamp_tokens(&self) -> Result<Vec<Vec<u8>>> {
+        let sign1 = parse_sign1(&self.cose_sign1)?;
+        let value = match get_sigtst(&sign1) {
+            Some((value, _tss)) => value,
+            None => return Ok(Vec::new()),
+        };
+
+        let container: TstContainer = serde_cbor::to_vec(&value)
+            .and_then(|cbor| serde_cbor::from_slice(&cbor))
+            .map_err(|_err| Error::CoseInvalidTimeStamp)?;
+        container
+            .tst_tokens
+            .iter()
+            .map(|token| stored_timestamp_token_der(&token.val))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        asn1::rfc5652::{ContentInfo, OID_ID_SIGNED_DATA},
+        validation_status::ValidationStatusCode,
+    };
+
+    #[test]
+    fn test_reader() {
+        let image_bytes = include_bytes!("../tests/fixtures/CA.jpg");
+        let reader = Reader::from_bytes("image/jpeg", image_bytes).unwrap();
+
+        let results = reader.validation_results();
+        assert_eq!(
+            results.active_manifest.as_deref(),
+            reader.manifest_store().active_label()
+        );
+        let codes = results.active_manifest_codes().unwrap();
+        assert!(codes.is_valid());
+        assert!(codes
+            .success
+            .iter()
+            .any(|s| s.code() == &ValidationStatusCode::ClaimSignatureValidated));
+        assert!(reader.active_manifest().is_some());
+        assert!(reader.json().unwrap().contains("active_manifest"));
+
+        let json = serde_json::to_value(results).unwrap();
+        assert!(json["manifests"].is_object());
+
+        let log = serde_json::to_value(reader.validation_log()).unwrap();
+        assert!(!log.as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_reader_signature_export() {
+        let image_bytes = include_bytes!("../tests/fixtures/CA.jpg");
+        let reader = Reader::from_bytes("image/jpeg", image_bytes).unwrap();
+
+        let signature = reader.active_signature().unwrap();
+        assert!(parse_sign1(signature.cose_sign1()).is_ok());
+
+        // each certificate of the chain is a DER sequence
+        let chain = signature.cert_chain().unwrap();
+        assert!(!chain.is_empty());
+        assert!(chain.iter().all(|cert| cert[0] == 0x30));
+
+        // the fixture is time-stamped, each token is a CMS SignedData ContentInfo
+        let tokens = signature.time_stamp_tokens().unwrap();
+        assert!(!tokens.is_empty());
+        for token in tokens {
+            let content_info =
+                bcder::decode::Constructed::decode(token.as_slice(), bcder::Mode::Der, |cons| {
+                    cons.take_sequence(ContentInfo::from_sequence)
+                })
+                .unwrap();
+            assert_eq!(content_info.content_type, OID_ID_SIGNED_DATA);
+        }
+
+        // every manifest of the store has its signature
+        for label in reader.manifest_store().manifests().keys() {
+            assert!(reader.signature(label).is_some());
+        }
+        assert!(reader.signature("urn:uuid:missing").is_none());
+    }
+
+    #[test]
+    fn test_reader_tampered() {
+        // change a byte of the image data, leaving the manifest in place
+        let mut image_bytes = include_bytes!("../tests/fixtures/CA.jpg").to_vec();
+        let pos = image_bytes.len() - 10;
+        image_bytes[pos] ^= 0xff;
+
+        let reader = Reader::from_bytes("image/jpeg", &image_bytes).unwrap();
+        let results = reader.validation_results();
+        assert!(!results.is_valid());
+        assert!(!results.active_manifest_codes().unwrap().failure.is_empty());
+    }
+}
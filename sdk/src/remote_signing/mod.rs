@@ -0,0 +1,342 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! A JSON over HTTP protocol for signing claims with a remote signing service.
+//!
+//! `GET /info` returns a [`RemoteSignerInfo`] describing the signer of the service, and
+//! `POST /sign` takes a [`SignRequest`] with the claim bytes and returns a [`SignResponse`]
+//! with the COSE_Sign1 signing them. Byte strings are base64 encoded.
+//!
+//! [`RemoteSignerClient`] is the SDK side of the protocol, and with the
+//! `remote_signing_server` feature [`server::router`] serves it with a local [`Signer`].
+//! Requests are authenticated by a [`RequestAuthenticator`] on the client and checked by
+//! a [`RequestVerifier`] on the server, such as a shared [`BearerToken`].
+//!
+//! [`Signer`]: crate::Signer
+
+use std::io::Read;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, RemoteSigner, Result, TimeStampStorage};
+
+#[cfg(feature = "remote_signing_server")]
+pub mod server;
+
+/// Path of the request for the information about the signer
+pub const INFO_PATH: &str = "/info";
+/// Path of the request to sign a claim
+pub const SIGN_PATH: &str = "/sign";
+
+// the largest response read from a signing service
+const MAX_RESPONSE_SIZE: u64 = 1024 * 1024;
+
+/// The signer of a remote signing service, the response to `GET /info`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RemoteSignerInfo {
+    /// The C2PA name of the signing algorithm, such as "es256"
+    pub alg: String,
+    /// The size in bytes of the largest COSE_Sign1 the service returns
+    pub reserve_size: usize,
+}
+
+/// The body of `POST /sign`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SignRequest {
+    /// The base64 encoded claim bytes
+    pub claim: String,
+    /// The header the time-stamp is stored in, `sigTst` or `sigTst2`
+    pub timestamp_storage: String,
+}
+
+impl SignRequest {
+    /// Creates a request to sign `claim_bytes` with the time-stamp stored as `tss`
+    pub fn new(claim_bytes: &[u8], tss: TimeStampStorage) -> Self {
+        SignRequest {
+            claim: base64::encode(claim_bytes),
+            timestamp_storage: tss.header_label().to_owned(),
+        }
+    }
+
+    /// Returns the claim bytes to sign
+    pub fn claim_bytes(&self) -> Result<Vec<u8>> {
+        base64::decode(&self.claim).map_err(|_e| Error::BadParam("invalid claim".to_string()))
+    }
+
+    /// Returns how the time-stamp is stored
+    pub fn timestamp_storage(&self) -> Result<TimeStampStorage> {
+        [TimeStampStorage::V1SigTst, TimeStampStorage::V2SigTst2]
+            .iter()
+            .find(|tss| tss.header_label() == self.timestamp_storage)
+            .copied()
+            .ok_or_else(|| Error::BadParam("invalid time-stamp storage".to_string()))
+    }
+}
+
+/// The response to `POST /sign`
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SignResponse {
+    /// The base64 encoded COSE_Sign1
+    pub cose: String,
+}
+
+impl SignResponse {
+    /// Creates a response with the COSE_Sign1 `cose_bytes`
+    pub fn new(cose_bytes: &[u8]) -> Self {
+        SignResponse {
+            cose: base64::encode(cose_bytes),
+        }
+    }
+
+    /// Returns the COSE_Sign1 bytes
+    pub fn cose_bytes(&self) -> Result<Vec<u8>> {
+        base64::decode(&self.cose).map_err(|_e| Error::CoseSignature)
+    }
+}
+
+/// Adds authentication to the requests sent to a remote signing service
+pub trait RequestAuthenticator: Send + Sync {
+    /// Returns the headers to add to a request to `path` with `body`, empty for `GET`
+    fn headers(&self, path: &str, body: &[u8]) -> Result<Vec<(String, String)>>;
+}
+
+/// Checks the authentication of the requests received by a remote signing service
+pub trait RequestVerifier: Send + Sync {
+    /// Returns true if `headers` authenticate a request to `path` with `body`
+    fn verify(&self, path: &str, headers: &[(String, String)], body: &[u8]) -> bool;
+}
+
+/// Authenticates requests with a token shared by the client and the service, sent in
+/// the `Authorization` header
+#[derive(Clone)]
+pub struct BearerToken {
+    token: String,
+}
+
+impl BearerToken {
+    /// Creates an authenticator sending or expecting `token`
+    pub fn new(token: &str) -> Self {
+        BearerToken {
+            token: token.to_owned(),
+        }
+    }
+
+    fn header_value(&self) -> String {
+        format!("Bearer {}", self.token)
+    }
+}
+
+impl RequestAuthenticator for BearerToken {
+    fn headers(&self, _path: &str, _body: &[u8]) -> Result<Vec<(String, String)>> {
+        Ok(vec![("Authorization".to_owned(), self.header_value())])
+    }
+}
+
+impl RequestVerifier for BearerToken {
+    fn verify(&self, _path: &str, headers: &[(String, String)], _body: &[u8]) -> bool {
+        let expected = self.header_value();
+        headers.iter().any(|(name, value)| {
+            name.eq_ignore_ascii_case("Authorization")
+                && constant_time_eq(value.as_bytes(), expected.as_bytes())
+        })
+    }
+}
+
+// compare without leaking how much of the token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A [`RemoteSigner`] signing claims with a service speaking the remote signing protocol
+pub struct RemoteSignerClient {
+    url: String,
+    info: RemoteSignerInfo,
+    authenticator: Option<Box<dyn RequestAuthenticator>>,
+}
+
+impl RemoteSignerClient {
+    /// Connects to the signing service at `url`, reading the information about its signer.
+    /// Requests are authenticated by `authenticator` if it is set.
+    pub fn connect(
+        url: &str,
+        authenticator: Option<Box<dyn RequestAuthenticator>>,
+    ) -> Result<Self> {
+        let mut client = RemoteSignerClient {
+            url: url.trim_end_matches('/').to_owned(),
+            info: RemoteSignerInfo {
+                alg: String::new(),
+                reserve_size: 0,
+            },
+            authenticator,
+        };
+        client.info = serde_json::from_slice(&client.send(INFO_PATH, None)?)?;
+        Ok(client)
+    }
+
+    /// Returns the information about the signer of the service
+    pub fn info(&self) -> &RemoteSignerInfo {
+        &self.info
+    }
+
+    // send a request to `path`, a POST if it has a `body`, returning the response body
+    fn send(&self, path: &str, body: Option<&[u8]>) -> Result<Vec<u8>> {
+        let url = format!("{}{}", self.url, path);
+        let mut request = match body {
+            Some(_) => ureq::post(&url).set("Content-Type", "application/json"),
+            None => ureq::get(&url),
+        };
+        if let Some(authenticator) = &self.authenticator {
+            for (name, value) in authenticator.headers(path, body.unwrap_or_default())? {
+                request = request.set(&name, &value);
+            }
+        }
+
+        let response = match body {
+            Some(body) => request.send_bytes(body),
+            None => request.call(),
+        }
+        .map_err(|e| Error::RemoteSigning(e.to_string()))?;
+
+        let mut response_bytes = Vec::new();
+        response
+            .into_reader()
+            .take(MAX_RESPONSE_SIZE)
+            .read_to_end(&mut response_bytes)?;
+        Ok(response_bytes)
+    }
+}
+
+impl RemoteSigner for RemoteSignerClient {
+    fn sign_claim(&self, claim_bytes: &[u8], tss: TimeStampStorage) -> Result<Vec<u8>> {
+        let request = serde_json::to_vec(&SignRequest::new(claim_bytes, tss))?;
+        let response: SignResponse =
+            serde_json::from_slice(&self.send(SIGN_PATH, Some(&request))?)?;
+        response.cose_bytes()
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.info.reserve_size
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        openssl::temp_signer::get_temp_signer_by_alg,
+        sign_detached,
+        utils::test::{fixture_path, temp_dir_path},
+        Manifest, ManifestStore, Signer,
+    };
+
+    // a signing service called in process, through the protocol types
+    struct LocalService {
+        signer: Box<dyn Signer>,
+    }
+
+    impl RemoteSigner for LocalService {
+        fn sign_claim(&self, claim_bytes: &[u8], tss: TimeStampStorage) -> Result<Vec<u8>> {
+            let request: SignRequest =
+                serde_json::from_slice(&serde_json::to_vec(&SignRequest::new(claim_bytes, tss))?)?;
+            let cose = sign_detached(
+                &request.claim_bytes()?,
+                self.signer.as_ref(),
+                request.timestamp_storage()?,
+            )?;
+            SignResponse::new(&cose).cose_bytes()
+        }
+
+        fn reserve_size(&self) -> usize {
+            self.signer.reserve_size()
+        }
+    }
+
+    #[test]
+    fn test_remote_signed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, _) = get_temp_signer_by_alg(&temp_dir.path(), "es256", None);
+        let service = LocalService { signer };
+
+        let source = fixture_path("earth_apollo17.jpg");
+        let output = temp_dir_path(&temp_dir, "remote.jpg");
+
+        let mut manifest = Manifest::new("test".to_owned());
+        manifest
+            .embed_remote_signed(&source, &output, &service)
+            .unwrap();
+
+        let manifest_store = ManifestStore::from_file(&output).unwrap();
+        assert!(manifest_store.validation_status().is_none());
+        assert!(manifest_store.get_active().unwrap().issuer().is_some());
+    }
+
+    #[test]
+    fn test_remote_signed_rejected_certificate() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let signer = crate::get_signer_from_files(
+            fixture_path("rsa-pss256_key-expired.pub"),
+            fixture_path("rsa-pss256-expired.pem"),
+            "ps256",
+            None,
+        )
+        .unwrap();
+        // the service itself signs with the expired certificate
+        let mut signer = crate::PolicySigner::new(signer);
+        signer.set_certificate_acceptance_policy(crate::CertificateAcceptancePolicy::accept_any());
+        let service = LocalService {
+            signer: Box::new(signer),
+        };
+
+        let source = fixture_path("earth_apollo17.jpg");
+        let output = temp_dir_path(&temp_dir, "remote.jpg");
+
+        let mut manifest = Manifest::new("test".to_owned());
+        assert!(matches!(
+            manifest.embed_remote_signed(&source, &output, &service),
+            Err(Error::SigningCertificateRejected(_))
+        ));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let token = BearerToken::new("secret");
+        let headers = token.headers(SIGN_PATH, b"{}").unwrap();
+        assert!(token.verify(SIGN_PATH, &headers, b"{}"));
+
+        let lowercase = vec![("authorization".to_owned(), "Bearer secret".to_owned())];
+        assert!(token.verify(SIGN_PATH, &lowercase, b"{}"));
+        assert!(!BearerToken::new("other").verify(SIGN_PATH, &headers, b"{}"));
+        assert!(!token.verify(SIGN_PATH, &[], b"{}"));
+    }
+
+    #[test]
+    fn test_sign_request() {
+        let request = SignRequest::new(b"claim", TimeStampStorage::V2SigTst2);
+        assert_eq!(request.timestamp_storage, "sigTst2");
+        assert_eq!(request.claim_bytes().unwrap(), b"claim");
+        assert_eq!(
+            request.timestamp_storage().unwrap(),
+            TimeStampStorage::V2SigTst2
+        );
+
+        let request = SignRequest {
+            claim: "not base64!".to_owned(),
+            timestamp_storage: "sigTst3".to_owned(),
+        };
+        assert!(request.claim_bytes().is_err());
+        assert!(request.timestamp_storage().is_err());
+    }
+}
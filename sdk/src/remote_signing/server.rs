@@ -0,0 +1,206 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! A reference server for the remote signing protocol, signing with a local [`Signer`].
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! use c2pa::{
+//!     get_signer_from_files,
+//!     remote_signing::{server, BearerToken},
+//! };
+//! # async fn serve() -> c2pa::Result<()> {
+//! let signer = get_signer_from_files("es256.pub", "es256.pem", "es256", None)?;
+//! let app = server::router(Arc::from(signer), Arc::new(BearerToken::new("secret")));
+//! let addr = std::net::SocketAddr::from(([0, 0, 0, 0], 3000));
+//! axum::Server::bind(&addr)
+//!     .serve(app.into_make_service())
+//!     .await
+//!     .map_err(|e| c2pa::Error::OtherError(Box::new(e)))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{ContentLengthLimit, Extension},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+
+use super::{RemoteSignerInfo, RequestVerifier, SignRequest, SignResponse, INFO_PATH, SIGN_PATH};
+use crate::{sign_detached, Signer};
+
+type HandlerError = (StatusCode, String);
+
+/// The largest sign request served, claims are far smaller
+pub const MAX_REQUEST_SIZE: u64 = 1024 * 1024;
+
+struct ServerState {
+    signer: Arc<dyn Signer + Send + Sync>,
+    verifier: Arc<dyn RequestVerifier>,
+}
+
+impl ServerState {
+    // requests are only served if the verifier authenticates them
+    fn authenticate(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<(), HandlerError> {
+        let headers: Vec<(String, String)> = headers
+            .iter()
+            .filter_map(|(name, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (name.as_str().to_owned(), value.to_owned()))
+            })
+            .collect();
+        if self.verifier.verify(path, &headers, body) {
+            Ok(())
+        } else {
+            Err((StatusCode::UNAUTHORIZED, "unauthorized".to_owned()))
+        }
+    }
+}
+
+/// Returns a router serving the remote signing protocol, signing claims with `signer`.
+///
+/// Every request must be authenticated by `verifier`, since the service signs whatever
+/// it is sent with the signer's private key. Sign requests larger than
+/// [`MAX_REQUEST_SIZE`] or without a `Content-Length` are rejected.
+pub fn router(signer: Arc<dyn Signer + Send + Sync>, verifier: Arc<dyn RequestVerifier>) -> Router {
+    let state = Arc::new(ServerState { signer, verifier });
+    Router::new()
+        .route(INFO_PATH, get(info))
+        .route(SIGN_PATH, post(sign))
+        .layer(Extension(state))
+}
+
+async fn info(
+    Extension(state): Extension<Arc<ServerState>>,
+    headers: HeaderMap,
+) -> Result<Json<RemoteSignerInfo>, HandlerError> {
+    state.authenticate(INFO_PATH, &headers, b"")?;
+
+    let alg = state.signer.alg().ok_or_else(|| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "signer has no algorithm".to_owned(),
+        )
+    })?;
+    Ok(Json(RemoteSignerInfo {
+        alg,
+        reserve_size: state.signer.reserve_size(),
+    }))
+}
+
+async fn sign(
+    Extension(state): Extension<Arc<ServerState>>,
+    headers: HeaderMap,
+    ContentLengthLimit(body): ContentLengthLimit<Bytes, MAX_REQUEST_SIZE>,
+) -> Result<Json<SignResponse>, HandlerError> {
+    state.authenticate(SIGN_PATH, &headers, &body)?;
+
+    let bad_request = |e: crate::Error| (StatusCode::BAD_REQUEST, e.to_string());
+    let request: SignRequest = serde_json::from_slice(&body).map_err(|e| bad_request(e.into()))?;
+    let claim_bytes = request.claim_bytes().map_err(bad_request)?;
+    let tss = request.timestamp_storage().map_err(bad_request)?;
+
+    // signing can wait on a time-stamp authority, so it is kept off the async workers
+    let signer = state.signer.clone();
+    let cose =
+        tokio::task::spawn_blocking(move || sign_detached(&claim_bytes, signer.as_ref(), tss))
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(SignResponse::new(&cose)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        get_signer_from_files,
+        openssl::temp_signer,
+        remote_signing::{BearerToken, RemoteSignerClient},
+        utils::test::{fixture_path, temp_dir_path},
+        Manifest, ManifestStore,
+    };
+
+    // serves the router on a local port from its own runtime, returning the service url
+    fn serve(app: Router) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            runtime
+                .block_on(
+                    axum::Server::from_tcp(listener)
+                        .unwrap()
+                        .serve(app.into_make_service()),
+                )
+                .unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn test_http_round_trip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (_, cert) = temp_signer::get_ec_signer(temp_dir.path(), "es256", None);
+        let key = cert.with_extension("pem");
+        let signer = get_signer_from_files(&cert, &key, "es256", None).unwrap();
+        let url = serve(router(
+            Arc::from(signer),
+            Arc::new(BearerToken::new("secret")),
+        ));
+
+        let client =
+            RemoteSignerClient::connect(&url, Some(Box::new(BearerToken::new("secret")))).unwrap();
+
+        let source = fixture_path("earth_apollo17.jpg");
+        let output = temp_dir_path(&temp_dir, "remote.jpg");
+        let mut manifest = Manifest::new("test".to_owned());
+        manifest
+            .embed_remote_signed(&source, &output, &client)
+            .unwrap();
+
+        let manifest_store = ManifestStore::from_file(&output).unwrap();
+        assert!(manifest_store.validation_status().is_none());
+        assert!(manifest_store.get_active().unwrap().issuer().is_some());
+
+        // requests without the shared token are refused
+        assert!(RemoteSignerClient::connect(&url, None).is_err());
+        assert!(
+            RemoteSignerClient::connect(&url, Some(Box::new(BearerToken::new("other")))).is_err()
+        );
+
+        // oversized sign requests are refused before they are authenticated or parsed
+        let response = ureq::post(&format!("{}{}", url, SIGN_PATH))
+            .set("Authorization", "Bearer secret")
+            .send_bytes(&vec![b' '; MAX_REQUEST_SIZE as usize + 1]);
+        assert!(matches!(response, Err(ureq::Error::Status(413, _))));
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Limits on the resources used when parsing untrusted manifest stores.
+
+use crate::jumbf::boxes::MAX_SUPER_BOX_DEPTH;
+
+/// Limits enforced while a manifest store is parsed, so a malicious asset cannot
+/// exhaust memory. A store exceeding any of them fails to load with
+/// [`Error::ResourceLimitExceeded`](crate::Error::ResourceLimitExceeded).
+///
+/// The defaults are far above what real assets use.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceLimits {
+    /// The maximum size in bytes of the JUMBF manifest store, which also bounds the
+    /// total size its compressed manifests expand to
+    pub max_manifest_size: usize,
+    /// The maximum number of assertions in a claim
+    pub max_assertions: usize,
+    /// The maximum depth of the ingredient tree below the active manifest
+    pub max_ingredient_depth: usize,
+    /// The maximum size in bytes of a thumbnail assertion
+    pub max_thumbnail_size: usize,
+    /// The maximum nesting depth of JUMBF superboxes in the manifest store
+    pub max_nesting: usize,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        ResourceLimits {
+            max_manifest_size: 256 * 1024 * 1024,
+            max_assertions: 10_000,
+            max_ingredient_depth: 128,
+            max_thumbnail_size: 32 * 1024 * 1024,
+            max_nesting: MAX_SUPER_BOX_DEPTH,
+        }
+    }
+}
+
+impl ResourceLimits {
+    /// Create the default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits that are never exceeded, for trusted input only
+    pub fn unlimited() -> Self {
+        ResourceLimits {
+            max_manifest_size: usize::MAX,
+            max_assertions: usize::MAX,
+            max_ingredient_depth: usize::MAX,
+            max_thumbnail_size: usize::MAX,
+            max_nesting: usize::MAX,
+        }
+    }
+
+    /// Set the maximum size in bytes of the JUMBF manifest store
+    pub fn set_max_manifest_size(&mut self, size: usize) -> &mut Self {
+        self.max_manifest_size = size;
+        self
+    }
+
+    /// Set the maximum number of assertions in a claim
+    pub fn set_max_assertions(&mut self, count: usize) -> &mut Self {
+        self.max_assertions = count;
+        self
+    }
+
+    /// Set the maximum depth of the ingredient tree below the active manifest
+    pub fn set_max_ingredient_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_ingredient_depth = depth;
+        self
+    }
+
+    /// Set the maximum size in bytes of a thumbnail assertion
+    pub fn set_max_thumbnail_size(&mut self, size: usize) -> &mut Self {
+        self.max_thumbnail_size = size;
+        self
+    }
+
+    /// Set the maximum nesting depth of JUMBF superboxes in the manifest store
+    pub fn set_max_nesting(&mut self, depth: usize) -> &mut Self {
+        self.max_nesting = depth;
+        self
+    }
+}
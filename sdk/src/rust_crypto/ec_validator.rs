@@ -0,0 +1,65 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::convert::TryFrom;
+
+use crate::{validator::CoseValidator, Error, Result};
+
+pub struct EcValidator {
+    alg: String,
+}
+
+impl EcValidator {
+    pub fn new(alg: &str) -> Self {
+        EcValidator {
+            alg: alg.to_owned(),
+        }
+    }
+}
+
+impl CoseValidator for EcValidator {
+    fn validate(&self, sig: &[u8], data: &[u8], pkey: &[u8]) -> Result<bool> {
+        // the signatures are in P1363 format and the key is a SubjectPublicKeyInfo
+        match self.alg.as_ref() {
+            "es256" => {
+                use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+                use p256::pkcs8::DecodePublicKey;
+
+                let public_key =
+                    VerifyingKey::from_public_key_der(pkey).map_err(|_err| Error::CoseSignature)?;
+                let signature = Signature::try_from(sig).map_err(|_err| Error::CoseSignature)?;
+                Ok(public_key.verify(data, &signature).is_ok())
+            }
+            "es384" => {
+                use p384::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+                use p384::pkcs8::DecodePublicKey;
+
+                let public_key =
+                    VerifyingKey::from_public_key_der(pkey).map_err(|_err| Error::CoseSignature)?;
+                let signature = Signature::try_from(sig).map_err(|_err| Error::CoseSignature)?;
+                Ok(public_key.verify(data, &signature).is_ok())
+            }
+            "es256k" => {
+                use k256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+                use k256::pkcs8::DecodePublicKey;
+
+                let public_key =
+                    VerifyingKey::from_public_key_der(pkey).map_err(|_err| Error::CoseSignature)?;
+                let signature = Signature::try_from(sig).map_err(|_err| Error::CoseSignature)?;
+                Ok(public_key.verify(data, &signature).is_ok())
+            }
+            // there is no Rust implementation of P-521 ECDSA yet
+            _ => Err(Error::UnsupportedType),
+        }
+    }
+}
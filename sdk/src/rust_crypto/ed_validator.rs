@@ -0,0 +1,41 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use std::convert::TryFrom;
+
+use ed25519_dalek::{PublicKey, Signature, Verifier};
+
+use super::public_key_bits;
+use crate::{validator::CoseValidator, Error, Result};
+
+pub struct EdValidator {
+    _alg: String,
+}
+
+impl EdValidator {
+    pub fn new(alg: &str) -> Self {
+        EdValidator {
+            _alg: alg.to_owned(),
+        }
+    }
+}
+
+impl CoseValidator for EdValidator {
+    fn validate(&self, sig: &[u8], data: &[u8], pkey: &[u8]) -> Result<bool> {
+        let public_key =
+            PublicKey::from_bytes(public_key_bits(pkey)?).map_err(|_err| Error::CoseSignature)?;
+        let signature = Signature::try_from(sig).map_err(|_err| Error::CoseSignature)?;
+
+        Ok(public_key.verify(data, &signature).is_ok())
+    }
+}
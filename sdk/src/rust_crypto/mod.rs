@@ -0,0 +1,73 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Signature validators written in Rust, for targets where OpenSSL and WebCrypto
+//! are not available.
+
+mod ec_validator;
+pub(crate) use ec_validator::EcValidator;
+
+mod ed_validator;
+pub(crate) use ed_validator::EdValidator;
+
+mod rsa_validator;
+pub(crate) use rsa_validator::RsaValidator;
+
+use x509_parser::prelude::*;
+
+use crate::{Error, Result};
+
+// the public key bits of a DER encoded SubjectPublicKeyInfo
+fn public_key_bits(pkey: &[u8]) -> Result<&[u8]> {
+    let (_, spki) = SubjectPublicKeyInfo::from_der(pkey).map_err(|_err| Error::CoseSignature)?;
+    Ok(spki.subject_public_key.data)
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    #[cfg(not(target_os = "wasi"))]
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::validator::CoseValidator;
+
+    #[cfg_attr(target_os = "wasi", test)]
+    #[cfg_attr(not(target_os = "wasi"), wasm_bindgen_test)]
+    fn test_rust_validators() {
+        let sig = include_bytes!("../../tests/fixtures/sig_es256.data");
+        let data = include_bytes!("../../tests/fixtures/data_es256.data");
+        let key = include_bytes!("../../tests/fixtures/key_es256.data");
+        let validator = EcValidator::new("es256");
+        assert!(validator.validate(sig, data, key).unwrap());
+        assert!(!validator.validate(sig, b"other content", key).unwrap());
+
+        let sig = include_bytes!("../../tests/fixtures/sig_es384.data");
+        let data = include_bytes!("../../tests/fixtures/data_es384.data");
+        let key = include_bytes!("../../tests/fixtures/key_es384.data");
+        assert!(EcValidator::new("es384").validate(sig, data, key).unwrap());
+
+        let sig = include_bytes!("../../tests/fixtures/sig.data");
+        let data = include_bytes!("../../tests/fixtures/data.data");
+        let key = include_bytes!("../../tests/fixtures/key.data");
+        let validator = RsaValidator::new("ps256");
+        assert!(validator.validate(sig, data, key).unwrap());
+        assert!(!validator.validate(sig, b"other content", key).unwrap());
+
+        assert!(matches!(
+            EcValidator::new("es512").validate(sig, data, key),
+            Err(Error::UnsupportedType)
+        ));
+    }
+}
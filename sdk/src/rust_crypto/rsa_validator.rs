@@ -0,0 +1,70 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use rsa::{pkcs1::FromRsaPublicKey, Hash, PaddingScheme, PublicKey, RsaPublicKey};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+use super::public_key_bits;
+use crate::{validator::CoseValidator, Error, Result};
+
+pub struct RsaValidator {
+    alg: String,
+}
+
+impl RsaValidator {
+    pub fn new(alg: &str) -> Self {
+        RsaValidator {
+            alg: alg.to_owned(),
+        }
+    }
+}
+
+impl CoseValidator for RsaValidator {
+    fn validate(&self, sig: &[u8], data: &[u8], pkey: &[u8]) -> Result<bool> {
+        // parse the PKCS#1 key directly, so keys with the RSASSA-PSS algorithm identifier are accepted
+        let public_key = RsaPublicKey::from_pkcs1_der(public_key_bits(pkey)?)
+            .map_err(|_err| Error::CoseSignature)?;
+
+        // the PSS salt length is recovered from the signature
+        let rng = rand_core::OsRng;
+        let (padding, hashed) = match self.alg.as_ref() {
+            "ps256" => (
+                PaddingScheme::new_pss::<Sha256, _>(rng),
+                Sha256::digest(data).to_vec(),
+            ),
+            "ps384" => (
+                PaddingScheme::new_pss::<Sha384, _>(rng),
+                Sha384::digest(data).to_vec(),
+            ),
+            "ps512" => (
+                PaddingScheme::new_pss::<Sha512, _>(rng),
+                Sha512::digest(data).to_vec(),
+            ),
+            "rs256" => (
+                PaddingScheme::new_pkcs1v15_sign(Some(Hash::SHA2_256)),
+                Sha256::digest(data).to_vec(),
+            ),
+            "rs384" => (
+                PaddingScheme::new_pkcs1v15_sign(Some(Hash::SHA2_384)),
+                Sha384::digest(data).to_vec(),
+            ),
+            "rs512" => (
+                PaddingScheme::new_pkcs1v15_sign(Some(Hash::SHA2_512)),
+                Sha512::digest(data).to_vec(),
+            ),
+            _ => return Err(Error::UnsupportedType),
+        };
+
+        Ok(public_key.verify(padding, &hashed, sig).is_ok())
+    }
+}
@@ -11,6 +11,8 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use crate::utils::hash_utils::hash_by_alg;
+
 /// The Saltgenerator trait always the caller to supply
 /// a funtion to generate a salt value used when hashing
 /// data.  Providing a unique salt ensures a unique hash for
@@ -18,6 +20,11 @@
 pub trait SaltGenerator {
     /// generate a salt vector
     fn generate_salt(&self) -> Option<Vec<u8>>;
+
+    /// generate a salt vector for the assertion with this label, including any instance
+    fn generate_salt_for(&self, _label: &str) -> Option<Vec<u8>> {
+        self.generate_salt()
+    }
 }
 
 /// NoSalt return a no salt option to a function
@@ -32,6 +39,9 @@ impl SaltGenerator for NoSalt {
 /// const NoSalt instance that can be used when no salting is required
 pub const NO_SALT: &NoSalt = &NoSalt {};
 
+// the minimum salt length required by the specification
+const MIN_SALT_LEN: usize = 16;
+
 /// Default salt generator
 /// This generator uses OpenSSL to generate a
 /// salt of the specified length (default 16 bytes)
@@ -55,15 +65,206 @@ impl Default for DefaultSalt {
 
 impl SaltGenerator for DefaultSalt {
     fn generate_salt(&self) -> Option<Vec<u8>> {
-        #[cfg(feature = "file_io")] // auto generation not supported on wasm
+        #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+        // auto generation not supported in browsers
         {
             let mut salt = vec![0; self.salt_len];
             openssl::rand::rand_bytes(&mut salt).ok()?;
             Some(salt)
         }
+        #[cfg(all(feature = "file_io", target_os = "wasi"))]
+        {
+            let mut salt = vec![0; self.salt_len];
+            getrandom::getrandom(&mut salt).ok()?;
+            Some(salt)
+        }
         #[cfg(not(feature = "file_io"))]
         {
             None
         }
     }
 }
+
+// which assertions a salt policy applies to
+#[derive(Clone, Debug, PartialEq)]
+enum SaltScope {
+    None,
+    All,
+    Labels(Vec<String>),
+}
+
+/// Controls which assertions of a claim are salted and how the salts are made.
+///
+/// Salting an assertion makes its hash unique, so the content of the assertion can not be
+/// guessed from its hash once it is redacted. No assertions are salted by default.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SaltPolicy {
+    scope: SaltScope,
+    salt_len: usize,
+    seed: Option<Vec<u8>>,
+    instance_id: String,
+}
+
+impl SaltPolicy {
+    /// A policy that salts no assertions
+    pub fn none() -> Self {
+        SaltPolicy {
+            scope: SaltScope::None,
+            salt_len: MIN_SALT_LEN,
+            seed: None,
+            instance_id: String::new(),
+        }
+    }
+
+    /// A policy that salts every assertion
+    pub fn all() -> Self {
+        SaltPolicy {
+            scope: SaltScope::All,
+            ..Self::none()
+        }
+    }
+
+    /// A policy that salts the assertions with these labels.
+    /// A label also matches the versioned, numbered instance and thumbnail format forms of
+    /// the label, so `c2pa.thumbnail.ingredient` matches `c2pa.thumbnail.ingredient__1.jpeg`.
+    pub fn labels<S: AsRef<str>>(labels: &[S]) -> Self {
+        SaltPolicy {
+            scope: SaltScope::Labels(labels.iter().map(|l| l.as_ref().to_owned()).collect()),
+            ..Self::none()
+        }
+    }
+
+    /// Set the length of the salts in bytes.
+    /// Lengths shorter than the 16 bytes required by the specification are raised to 16.
+    pub fn set_salt_length(&mut self, len: usize) -> &mut Self {
+        self.salt_len = len.max(MIN_SALT_LEN);
+        self
+    }
+
+    /// Derive the salts from `seed`, the instance ID of the asset and the assertion labels
+    /// instead of generating them randomly. The same seed, asset and assertions always give
+    /// the same salts, so builds are reproducible, while the salts of other assets differ.
+    /// The seed must be kept secret for salts to protect redacted assertions.
+    pub fn set_deterministic(&mut self, seed: &[u8]) -> &mut Self {
+        self.seed = Some(seed.to_vec());
+        self
+    }
+
+    /// Returns true if assertions with this label are salted
+    pub fn applies_to(&self, label: &str) -> bool {
+        match &self.scope {
+            SaltScope::None => false,
+            SaltScope::All => true,
+            SaltScope::Labels(labels) => labels.iter().any(|l| {
+                label.strip_prefix(l.as_str()).map_or(false, |rest| {
+                    rest.is_empty() || rest.starts_with('.') || rest.starts_with("__")
+                })
+            }),
+        }
+    }
+
+    /// Returns this policy deriving salts for the asset with `instance_id`
+    pub(crate) fn for_instance(&self, instance_id: &str) -> Self {
+        SaltPolicy {
+            instance_id: instance_id.to_owned(),
+            ..self.clone()
+        }
+    }
+
+    // derives a salt from the seed, instance ID and label, hashing with a counter until long enough
+    fn derive_salt(&self, seed: &[u8], label: &str) -> Vec<u8> {
+        let mut salt = Vec::with_capacity(self.salt_len);
+        let mut counter: u32 = 0;
+        while salt.len() < self.salt_len {
+            let mut data =
+                Vec::with_capacity(seed.len() + self.instance_id.len() + label.len() + 6);
+            data.extend_from_slice(seed);
+            data.push(0);
+            data.extend_from_slice(self.instance_id.as_bytes());
+            data.push(0);
+            data.extend_from_slice(label.as_bytes());
+            data.extend_from_slice(&counter.to_be_bytes());
+            salt.extend(hash_by_alg("sha256", &data, None));
+            counter += 1;
+        }
+        salt.truncate(self.salt_len);
+        salt
+    }
+}
+
+impl Default for SaltPolicy {
+    fn default() -> Self {
+        SaltPolicy::none()
+    }
+}
+
+impl SaltGenerator for SaltPolicy {
+    fn generate_salt(&self) -> Option<Vec<u8>> {
+        self.generate_salt_for("")
+    }
+
+    fn generate_salt_for(&self, label: &str) -> Option<Vec<u8>> {
+        if !self.applies_to(label) {
+            return None;
+        }
+        match self.seed.as_ref() {
+            Some(seed) => Some(self.derive_salt(seed, label)),
+            None => DefaultSalt {
+                salt_len: self.salt_len,
+            }
+            .generate_salt(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_salt_policy() {
+        let policy = SaltPolicy::none();
+        assert!(policy.generate_salt_for("c2pa.actions").is_none());
+
+        let mut policy = SaltPolicy::labels(&["c2pa.ingredient", "c2pa.thumbnail.ingredient"]);
+        policy.set_salt_length(40).set_deterministic(b"secret");
+        assert!(policy.applies_to("c2pa.ingredient"));
+        assert!(policy.applies_to("c2pa.ingredient.v2__1"));
+        assert!(policy.applies_to("c2pa.thumbnail.ingredient__1.jpeg"));
+        assert!(!policy.applies_to("c2pa.ingredients"));
+        assert!(!policy.applies_to("c2pa.actions"));
+
+        let salt = policy.generate_salt_for("c2pa.ingredient").unwrap();
+        assert_eq!(salt.len(), 40);
+        assert_eq!(
+            policy.generate_salt_for("c2pa.ingredient"),
+            Some(salt.clone())
+        );
+        assert_ne!(
+            policy.generate_salt_for("c2pa.ingredient__1"),
+            Some(salt.clone())
+        );
+
+        // the salts of each asset differ
+        let asset_policy = policy.for_instance("xmp:iid:1234");
+        let asset_salt = asset_policy.generate_salt_for("c2pa.ingredient").unwrap();
+        assert_ne!(asset_salt, salt);
+        assert_eq!(
+            asset_policy.generate_salt_for("c2pa.ingredient"),
+            Some(asset_salt)
+        );
+        assert_ne!(
+            policy
+                .for_instance("xmp:iid:5678")
+                .generate_salt_for("c2pa.ingredient"),
+            asset_policy.generate_salt_for("c2pa.ingredient")
+        );
+        assert!(policy.generate_salt_for("c2pa.actions").is_none());
+
+        let mut policy = SaltPolicy::all();
+        policy.set_salt_length(4).set_deterministic(b"secret");
+        assert_eq!(policy.generate_salt_for("c2pa.actions").unwrap().len(), 16);
+    }
+}
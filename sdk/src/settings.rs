@@ -0,0 +1,526 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! SDK settings read from a TOML or JSON file.
+//!
+//! A deployment can change its trust lists, signing credentials, thumbnails and
+//! validation checks by editing a settings file instead of recompiling. Every field
+//! is optional and missing fields keep the SDK defaults.
+//!
+//! ```toml
+//! [trust]
+//! trust_anchors = ["https://example.com/C2PA-TRUST-LIST.pem", "user-anchors.pem"]
+//! reload_interval = 3600
+//!
+//! [signer]
+//! alg = "ps256"
+//! sign_cert = "certs/ps256.pub"
+//! private_key = "certs/ps256.pem"
+//! tsa_url = "http://timestamp.digicert.com"
+//!
+//! [thumbnail]
+//! longest_edge = 512
+//! format = "jpeg"
+//!
+//! [verify]
+//! verify_after_sign = true
+//! ocsp_fetch = true
+//! ```
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+use crate::Signer;
+#[cfg(feature = "file_io")]
+use crate::{
+    utils::thumbnail::{ImageThumbnailGenerator, ThumbnailFormat, ThumbnailOptions},
+    Manifest, ThumbnailGenerator,
+};
+use crate::{CertCheckMode, Error, Result, TrustConfig, TrustSource, VerifyOptions};
+
+/// Trust lists the signing and time-stamp certificates are checked against.
+///
+/// Each entry is an https URL, PEM text or the path of a file. Relative paths of settings
+/// read with [`Settings::from_file`] are relative to the directory of the settings file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrustSettings {
+    /// PEM encoded trust anchors, such as the C2PA trust list
+    pub trust_anchors: Vec<String>,
+    /// End-entity certificates and certificate hashes trusted without their chain
+    pub allowed_list: Vec<String>,
+    /// The EKUs accepted for signing certificates, one dotted OID per line
+    pub eku_config: Vec<String>,
+    /// PEM encoded certificates trusted to issue time-stamps
+    pub tsa_trust_anchors: Vec<String>,
+    /// Seconds between checks for modified trust lists, never checked when `None`
+    pub reload_interval: Option<u64>,
+}
+
+/// The credentials and algorithm used to sign manifests
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerSettings {
+    /// The signing algorithm, such as `es256` or `ps256`
+    pub alg: String,
+    /// Path of the PEM encoded signing certificate chain, relative to the settings file
+    pub sign_cert: Option<PathBuf>,
+    /// Path of the PEM encoded private key, relative to the settings file
+    pub private_key: Option<PathBuf>,
+    /// URL of the time-stamp authority, signatures are not time-stamped when `None`
+    pub tsa_url: Option<String>,
+}
+
+impl Default for SignerSettings {
+    fn default() -> Self {
+        SignerSettings {
+            alg: "es256".to_string(),
+            sign_cert: None,
+            private_key: None,
+            tsa_url: None,
+        }
+    }
+}
+
+/// How the thumbnails of claims and ingredients are generated
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThumbnailSettings {
+    /// Generate thumbnails, claims and ingredients have none when `false`
+    pub enabled: bool,
+    /// Maximum edge size of thumbnails in pixels
+    pub longest_edge: u32,
+    /// The image format of thumbnails: `auto`, `jpeg` or `png`
+    pub format: String,
+    /// JPEG quality from 1 to 100
+    pub jpeg_quality: u8,
+}
+
+impl Default for ThumbnailSettings {
+    fn default() -> Self {
+        ThumbnailSettings {
+            enabled: true,
+            longest_edge: 1024,
+            format: "auto".to_string(),
+            jpeg_quality: 80,
+        }
+    }
+}
+
+/// Which checks are made when manifest stores are verified
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct VerifySettings {
+    /// Verify the output after signing
+    pub verify_after_sign: bool,
+    /// Check certificates against the trust lists
+    pub verify_trust: bool,
+    /// Fetch manifest stores referenced by a remote URL
+    pub fetch_remote_manifests: bool,
+    /// Fetch the OCSP status of signing certificates
+    pub ocsp_fetch: bool,
+    /// Reject signing certificates with findings the specification only recommends against
+    pub strict_cert_checks: bool,
+    /// The maximum size in bytes of a manifest store, the SDK default when `None`
+    pub max_manifest_size: Option<usize>,
+    /// The maximum nesting depth of JUMBF boxes, the SDK default when `None`
+    pub max_nesting: Option<usize>,
+}
+
+impl Default for VerifySettings {
+    fn default() -> Self {
+        VerifySettings {
+            verify_after_sign: false,
+            verify_trust: true,
+            fetch_remote_manifests: false,
+            ocsp_fetch: false,
+            strict_cert_checks: false,
+            max_manifest_size: None,
+            max_nesting: None,
+        }
+    }
+}
+
+/// Settings controlling the defaults of the SDK, read from a TOML or JSON file.
+///
+/// The settings are applied explicitly, by building [`VerifyOptions`] or a signer from
+/// them, so each request of a service can use the settings of its tenant.
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::Settings;
+/// # fn main() -> Result<()> {
+/// let settings = Settings::from_toml("[verify]\nverify_after_sign = true")?;
+/// let options = settings.verify_options()?;
+/// assert!(options.verify_after_sign());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Trust lists the signing and time-stamp certificates are checked against
+    pub trust: TrustSettings,
+    /// The credentials and algorithm used to sign manifests
+    pub signer: SignerSettings,
+    /// How thumbnails are generated
+    pub thumbnail: ThumbnailSettings,
+    /// Which checks are made when manifest stores are verified
+    pub verify: VerifySettings,
+
+    // the directory of the settings file, relative paths are resolved against it
+    #[serde(skip)]
+    base_dir: Option<PathBuf>,
+
+    #[serde(skip)]
+    trust_cache: TrustCache,
+}
+
+// the trust configuration built from the trust settings, so the trust lists are read and
+// watched once however many options are built from the same settings
+#[derive(Clone, Debug, Default)]
+struct TrustCache(Arc<Mutex<Option<CachedTrust>>>);
+
+#[derive(Debug)]
+struct CachedTrust {
+    // the settings the configuration was built from, rebuilt when they change
+    trust: TrustSettings,
+    base_dir: Option<PathBuf>,
+    trust_config: Option<TrustConfig>,
+    tsa_trust_anchors: Vec<Vec<u8>>,
+}
+
+// the cache is not part of the value of the settings
+impl PartialEq for TrustCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Settings {
+    /// Read settings from TOML text
+    pub fn from_toml(toml: &str) -> Result<Self> {
+        toml::from_str(toml).map_err(|e| Error::BadParam(format!("invalid settings: {}", e)))
+    }
+
+    /// Read settings from JSON text
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|e| Error::BadParam(format!("invalid settings: {}", e)))
+    }
+
+    /// Read settings from a file, JSON if its extension is `.json`, otherwise TOML.
+    ///
+    /// Relative paths in the settings are resolved against the directory of the file.
+    #[cfg(feature = "file_io")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let mut settings = match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Settings::from_json(&text)?,
+            _ => Settings::from_toml(&text)?,
+        };
+        settings.base_dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf);
+        Ok(settings)
+    }
+
+    /// Returns the trust configuration of the trust lists, `None` if no trust list is set.
+    ///
+    /// Trust lists that are URLs are fetched. The configuration is built once and shared by
+    /// later calls and clones of the settings, so at most one watcher checks the trust lists
+    /// for changes. It is built again if the trust settings are changed.
+    pub fn trust_config(&self) -> Result<Option<TrustConfig>> {
+        Ok(self.cached_trust()?.0)
+    }
+
+    // the trust configuration and time-stamp trust anchors, built once for the trust settings
+    fn cached_trust(&self) -> Result<(Option<TrustConfig>, Vec<Vec<u8>>)> {
+        let mut cache = self
+            .trust_cache
+            .0
+            .lock()
+            .map_err(|_e| Error::BadParam("trust configuration unavailable".to_string()))?;
+        if let Some(cached) = cache.as_ref() {
+            if cached.trust == self.trust && cached.base_dir == self.base_dir {
+                return Ok((
+                    cached.trust_config.clone(),
+                    cached.tsa_trust_anchors.clone(),
+                ));
+            }
+        }
+
+        let trust_config = self.build_trust_config()?;
+        let mut tsa_trust_anchors = Vec::new();
+        if !self.trust.tsa_trust_anchors.is_empty() {
+            let tsa_trust = TrustConfig::new();
+            for anchors in &self.trust.tsa_trust_anchors {
+                tsa_trust.add_trust_anchors(self.trust_source(anchors))?;
+            }
+            tsa_trust_anchors = tsa_trust.trust_anchors();
+        }
+
+        *cache = Some(CachedTrust {
+            trust: self.trust.clone(),
+            base_dir: self.base_dir.clone(),
+            trust_config: trust_config.clone(),
+            tsa_trust_anchors: tsa_trust_anchors.clone(),
+        });
+        Ok((trust_config, tsa_trust_anchors))
+    }
+
+    fn build_trust_config(&self) -> Result<Option<TrustConfig>> {
+        let trust = &self.trust;
+        if trust.trust_anchors.is_empty()
+            && trust.allowed_list.is_empty()
+            && trust.eku_config.is_empty()
+        {
+            return Ok(None);
+        }
+
+        let trust_config = TrustConfig::new();
+        for anchors in &trust.trust_anchors {
+            trust_config.add_trust_anchors(self.trust_source(anchors))?;
+        }
+        for allowed in &trust.allowed_list {
+            trust_config.add_allowed_list(self.trust_source(allowed))?;
+        }
+        for ekus in &trust.eku_config {
+            trust_config.add_eku_config(self.trust_source(ekus))?;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(interval) = trust.reload_interval {
+            trust_config.watch(std::time::Duration::from_secs(interval));
+        }
+
+        Ok(Some(trust_config))
+    }
+
+    // an entry of a trust list is a URL, PEM text or a file path, plain HTTP URLs are kept
+    // as URLs so they are rejected rather than read as file paths
+    fn trust_source(&self, entry: &str) -> TrustSource {
+        if entry.starts_with("https://") || entry.starts_with("http://") {
+            TrustSource::Url(entry.to_string())
+        } else if entry.trim_start().starts_with("-----BEGIN") {
+            TrustSource::Bytes(entry.as_bytes().to_vec())
+        } else {
+            TrustSource::File(self.resolve_path(Path::new(entry)))
+        }
+    }
+
+    // relative paths are relative to the directory of the settings file
+    fn resolve_path(&self, path: &Path) -> PathBuf {
+        match &self.base_dir {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path.to_path_buf(),
+        }
+    }
+
+    /// Build the options used to verify manifest stores
+    pub fn verify_options(&self) -> Result<VerifyOptions> {
+        let verify = &self.verify;
+        let mut options = VerifyOptions::new();
+        options
+            .set_verify_after_sign(verify.verify_after_sign)
+            .set_verify_trust(verify.verify_trust)
+            .set_fetch_remote_manifests(verify.fetch_remote_manifests)
+            .set_ocsp_fetch(verify.ocsp_fetch);
+
+        if verify.strict_cert_checks {
+            options.set_cert_check_mode(CertCheckMode::Strict);
+        }
+        if let Some(size) = verify.max_manifest_size {
+            options.resource_limits.set_max_manifest_size(size);
+        }
+        if let Some(depth) = verify.max_nesting {
+            options.resource_limits.set_max_nesting(depth);
+        }
+
+        let (trust_config, tsa_trust_anchors) = self.cached_trust()?;
+        if let Some(trust_config) = trust_config {
+            options.set_trust_config(trust_config);
+        }
+        if !tsa_trust_anchors.is_empty() {
+            options.set_tsa_trust_anchors(tsa_trust_anchors);
+        }
+
+        Ok(options)
+    }
+
+    /// Build the generator of claim and ingredient thumbnails
+    #[cfg(feature = "file_io")]
+    pub fn thumbnail_generator(&self) -> Result<Arc<dyn ThumbnailGenerator>> {
+        let thumbnail = &self.thumbnail;
+        if !thumbnail.enabled {
+            return Ok(Arc::new(NoThumbnail));
+        }
+
+        let format = match thumbnail.format.to_ascii_lowercase().as_str() {
+            "auto" => ThumbnailFormat::Auto,
+            "jpeg" | "jpg" => ThumbnailFormat::Jpeg,
+            "png" => ThumbnailFormat::Png,
+            other => {
+                return Err(Error::BadParam(format!(
+                    "invalid thumbnail format {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Arc::new(ImageThumbnailGenerator::new(ThumbnailOptions {
+            longest_edge: thumbnail.longest_edge,
+            format,
+            jpeg_quality: thumbnail.jpeg_quality,
+        })))
+    }
+
+    /// Build the signer of the signing credentials.
+    ///
+    /// Returns [`Error::BadParam`] if the signing certificate or private key is not set.
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    pub fn signer(&self) -> Result<Box<dyn Signer + Send + Sync>> {
+        let signer = &self.signer;
+        match (&signer.sign_cert, &signer.private_key) {
+            (Some(sign_cert), Some(private_key)) => crate::get_signer_from_files(
+                self.resolve_path(sign_cert),
+                self.resolve_path(private_key),
+                &signer.alg,
+                signer.tsa_url.clone(),
+            ),
+            _ => Err(Error::BadParam(
+                "signer settings need a sign_cert and a private_key".to_string(),
+            )),
+        }
+    }
+
+    /// Apply the thumbnail and verification settings to a manifest before it is signed
+    #[cfg(feature = "file_io")]
+    pub fn apply_to_manifest(&self, manifest: &mut Manifest) -> Result<()> {
+        manifest.set_thumbnail_generator(self.thumbnail_generator()?);
+        manifest.set_verify_options(self.verify_options()?);
+        Ok(())
+    }
+}
+
+// generates no thumbnails, used when thumbnails are disabled
+#[cfg(feature = "file_io")]
+struct NoThumbnail;
+
+#[cfg(feature = "file_io")]
+impl ThumbnailGenerator for NoThumbnail {
+    fn generate(&self, _format: &str, _data: &[u8]) -> Result<Option<(String, Vec<u8>)>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_settings_formats() {
+        let toml = r#"
+            [signer]
+            alg = "ps256"
+            tsa_url = "http://timestamp.digicert.com"
+
+            [thumbnail]
+            enabled = false
+
+            [verify]
+            verify_after_sign = true
+            max_nesting = 16
+        "#;
+        let json = r#"{
+            "signer": { "alg": "ps256", "tsa_url": "http://timestamp.digicert.com" },
+            "thumbnail": { "enabled": false },
+            "verify": { "verify_after_sign": true, "max_nesting": 16 }
+        }"#;
+
+        let settings = Settings::from_toml(toml).unwrap();
+        assert_eq!(settings, Settings::from_json(json).unwrap());
+        assert_eq!(settings.signer.alg, "ps256");
+        assert!(!settings.thumbnail.enabled);
+        // missing fields keep their defaults
+        assert_eq!(settings.thumbnail.longest_edge, 1024);
+        assert!(settings.verify.verify_trust);
+
+        let options = settings.verify_options().unwrap();
+        assert!(options.verify_after_sign);
+        assert_eq!(options.resource_limits.max_nesting, 16);
+        assert!(options.trust_config.is_none());
+
+        assert_eq!(Settings::from_toml("").unwrap(), Settings::default());
+        assert!(Settings::from_toml("[verify]\nverify_afer_sign = true").is_err());
+        assert!(Settings::from_json(r#"{"verify": {"ocsp_fetch": "yes"}}"#).is_err());
+    }
+
+    #[test]
+    fn test_settings_trust() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("store.cfg");
+        std::fs::write(&path, "1.3.6.1.5.5.7.3.4\n").unwrap();
+
+        let toml = format!("[trust]\neku_config = [{:?}]", path.to_str().unwrap());
+        let options = Settings::from_toml(&toml)
+            .unwrap()
+            .verify_options()
+            .unwrap();
+        let trust_config = options.trust_config.unwrap();
+        assert_eq!(trust_config.ekus(), vec!["1.3.6.1.5.5.7.3.4".to_string()]);
+
+        let missing = Settings::from_toml("[trust]\ntrust_anchors = [\"missing.pem\"]").unwrap();
+        assert!(missing.verify_options().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_settings_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("store.cfg"), "1.3.6.1.5.5.7.3.4\n").unwrap();
+
+        // relative paths are relative to the settings file, and the trust configuration
+        // is built once for the settings
+        let settings_path = temp_dir.path().join("settings.toml");
+        std::fs::write(
+            &settings_path,
+            "[trust]\neku_config = [\"store.cfg\"]\nreload_interval = 3600",
+        )
+        .unwrap();
+        let settings = Settings::from_file(&settings_path).unwrap();
+        let trust_config = settings.trust_config().unwrap().unwrap();
+        assert_eq!(trust_config.ekus(), vec!["1.3.6.1.5.5.7.3.4".to_string()]);
+        assert_eq!(
+            settings.verify_options().unwrap().trust_config,
+            Some(trust_config.clone())
+        );
+        assert_eq!(
+            settings.clone().trust_config().unwrap(),
+            Some(trust_config.clone())
+        );
+
+        // changed trust settings build a new configuration
+        let mut changed = settings.clone();
+        changed.trust.reload_interval = None;
+        assert_ne!(changed.trust_config().unwrap(), Some(trust_config));
+    }
+}
@@ -11,7 +11,19 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use crate::Result;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use tracing::warn;
+use x509_parser::prelude::*;
+
+use crate::{CertificateAcceptancePolicy, DynamicAssertion, Error, Result, X5ChainPlacement};
 
 /// The `Signer` trait generates a cryptographic signature over a byte array.
 ///
@@ -28,7 +40,8 @@ pub trait Signer {
 
     /// Returns the size in bytes of the largest possible expected signature.
     /// Signing will fail if the result of the `sign` function is larger
-    /// than this value.
+    /// than this value, unless the asset is saved to a new file, in which case
+    /// the save is retried with a larger placeholder.
     fn reserve_size(&self) -> usize;
 
     /// URL for time authority to time stamp the signature
@@ -36,6 +49,12 @@ pub trait Signer {
         None
     }
 
+    /// The time authorities to time stamp the signature with and how they are retried.
+    /// Defaults to a single attempt with `time_authority_url`.
+    fn time_stamp_policy(&self) -> TimeStampPolicy {
+        TimeStampPolicy::new(self.time_authority_url().into_iter().collect())
+    }
+
     /// OCSP response for the signing cert if available
     /// This is the only C2PA supported cert revocation method.
     /// By pre-querying the value for a your signing cert the value can
@@ -43,6 +62,740 @@ pub trait Signer {
     fn ocsp_val(&self) -> Option<Vec<u8>> {
         None
     }
+
+    /// Which of the certificates from `certs` are embedded in the manifest
+    fn cert_chain_policy(&self) -> CertChainPolicy {
+        CertChainPolicy::Full
+    }
+
+    /// Whether the OCSP response from `ocsp_val` is embedded in the manifest
+    fn include_ocsp(&self) -> bool {
+        true
+    }
+
+    /// Which rules of the signing certificate profile the certificate from `certs` must
+    /// follow before signing. Defaults to every rule that is not soft.
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        CertificateAcceptancePolicy::default()
+    }
+
+    /// Whether the certificate chain goes in the protected header (C2PA 2.x) or the
+    /// unprotected header (C2PA 1.x)
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        X5ChainPlacement::Unprotected
+    }
+
+    /// Assertions generated while signing, added to the claim being signed
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        Vec::new()
+    }
+
+    /// Signers that co-sign the claim, each adding a countersignature over the claim
+    /// and this signer's signature. `reserve_size` must include their signatures.
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        &[]
+    }
+
+    /// The signer making the signature of one signing operation.
+    ///
+    /// Signers choosing among several credentials return the one chosen for the
+    /// operation, so its certificates, signature and every other value come from
+    /// the same credential. Defaults to `None`, signing with this signer.
+    fn signer_for_operation(&self) -> Result<Option<&dyn Signer>> {
+        Ok(None)
+    }
+}
+
+// the signer making one signature, with the credential of signers that choose one pinned
+pub(crate) fn operation_signer(signer: &dyn Signer) -> Result<&dyn Signer> {
+    Ok(signer.signer_for_operation()?.unwrap_or(signer))
+}
+
+impl<T: Signer + ?Sized> Signer for Box<T> {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        (**self).sign(data)
+    }
+
+    fn alg(&self) -> Option<String> {
+        (**self).alg()
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        (**self).certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        (**self).reserve_size()
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        (**self).time_authority_url()
+    }
+
+    fn time_stamp_policy(&self) -> TimeStampPolicy {
+        (**self).time_stamp_policy()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        (**self).ocsp_val()
+    }
+
+    fn cert_chain_policy(&self) -> CertChainPolicy {
+        (**self).cert_chain_policy()
+    }
+
+    fn include_ocsp(&self) -> bool {
+        (**self).include_ocsp()
+    }
+
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        (**self).certificate_acceptance_policy()
+    }
+
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        (**self).x5chain_placement()
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        (**self).dynamic_assertions()
+    }
+
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        (**self).cosigners()
+    }
+
+    fn signer_for_operation(&self) -> Result<Option<&dyn Signer>> {
+        (**self).signer_for_operation()
+    }
+}
+
+/// Controls how much of the signing certificate chain is embedded in the manifest.
+///
+/// Embedding less of the chain makes the manifest smaller, but verifiers will need
+/// the missing certificates from another source to build a trust path.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CertChainPolicy {
+    /// Embed every certificate returned by the signer
+    Full,
+    /// Embed the signing certificate and the first intermediate certificates,
+    /// up to this total number of certificates
+    Partial(usize),
+    /// Embed only the signing certificate
+    LeafOnly,
+}
+
+impl CertChainPolicy {
+    // returns the certificates to embed, the signing certificate is always kept
+    pub(crate) fn apply(&self, mut certs: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+        match self {
+            CertChainPolicy::Full => (),
+            CertChainPolicy::Partial(count) => certs.truncate(std::cmp::max(*count, 1)),
+            CertChainPolicy::LeafOnly => certs.truncate(1),
+        }
+        certs
+    }
+}
+
+/// Controls which time authorities time stamp a signature.
+///
+/// Authorities are tried in order. A failed request is retried `retries` times
+/// before falling back to the next authority, and signing only fails if every
+/// authority fails.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimeStampPolicy {
+    /// URLs of the time authorities, in the order they are tried
+    pub urls: Vec<String>,
+    /// Timeout of each request, no timeout if `None`
+    pub timeout: Option<Duration>,
+    /// Number of times a failed request to an authority is retried
+    pub retries: u32,
+}
+
+impl TimeStampPolicy {
+    /// Try each of `urls` once, without a timeout
+    pub fn new(urls: Vec<String>) -> Self {
+        TimeStampPolicy {
+            urls,
+            timeout: None,
+            retries: 0,
+        }
+    }
+
+    /// Set the timeout of each request
+    pub fn set_timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the number of times a failed request to an authority is retried
+    pub fn set_retries(&mut self, retries: u32) -> &mut Self {
+        self.retries = retries;
+        self
+    }
+}
+
+/// Wraps a [`Signer`] to set the certificate chain, OCSP inclusion, time stamp and
+/// certificate acceptance policy, and where the certificate chain is placed.
+///
+/// ```ignore
+/// let mut signer = PolicySigner::new(get_signer_from_files(cert, key, "ps256", None)?);
+/// signer.set_cert_chain_policy(CertChainPolicy::LeafOnly).set_include_ocsp(false);
+/// ```
+pub struct PolicySigner<S: Signer> {
+    signer: S,
+    cert_chain_policy: CertChainPolicy,
+    include_ocsp: bool,
+    time_stamp_policy: Option<TimeStampPolicy>,
+    x5chain_placement: Option<X5ChainPlacement>,
+    certificate_acceptance_policy: Option<CertificateAcceptancePolicy>,
+}
+
+impl<S: Signer> PolicySigner<S> {
+    /// Wrap `signer`, initially embedding the full chain and any OCSP response
+    pub fn new(signer: S) -> Self {
+        PolicySigner {
+            signer,
+            cert_chain_policy: CertChainPolicy::Full,
+            include_ocsp: true,
+            time_stamp_policy: None,
+            x5chain_placement: None,
+            certificate_acceptance_policy: None,
+        }
+    }
+
+    /// Set how much of the certificate chain is embedded
+    pub fn set_cert_chain_policy(&mut self, policy: CertChainPolicy) -> &mut Self {
+        self.cert_chain_policy = policy;
+        self
+    }
+
+    /// Set whether the OCSP response is embedded
+    pub fn set_include_ocsp(&mut self, include_ocsp: bool) -> &mut Self {
+        self.include_ocsp = include_ocsp;
+        self
+    }
+
+    /// Set the time authorities used instead of those of the wrapped signer
+    pub fn set_time_stamp_policy(&mut self, policy: TimeStampPolicy) -> &mut Self {
+        self.time_stamp_policy = Some(policy);
+        self
+    }
+
+    /// Set where the certificate chain is placed instead of where the wrapped signer places it
+    pub fn set_x5chain_placement(&mut self, placement: X5ChainPlacement) -> &mut Self {
+        self.x5chain_placement = Some(placement);
+        self
+    }
+
+    /// Set the rules the signing certificate must follow instead of those of the wrapped signer
+    pub fn set_certificate_acceptance_policy(
+        &mut self,
+        policy: CertificateAcceptancePolicy,
+    ) -> &mut Self {
+        self.certificate_acceptance_policy = Some(policy);
+        self
+    }
+}
+
+impl<S: Signer> Signer for PolicySigner<S> {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> Option<String> {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        self.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        // do not reserve space for certificates that will not be embedded
+        let mut reserve_size = self.signer.reserve_size();
+        if let Ok(certs) = self.signer.certs() {
+            let certs_size: usize = certs.iter().map(|c| c.len()).sum();
+            let kept_size: usize = self
+                .cert_chain_policy
+                .apply(certs)
+                .iter()
+                .map(|c| c.len())
+                .sum();
+            reserve_size = reserve_size.saturating_sub(certs_size - kept_size);
+        }
+
+        // nor for an OCSP response that is dropped, but do for one that is added
+        if self.include_ocsp != self.signer.include_ocsp() {
+            let ocsp_size = self.signer.ocsp_val().map_or(0, |ocsp| ocsp.len());
+            reserve_size = if self.include_ocsp {
+                reserve_size + ocsp_size
+            } else {
+                reserve_size.saturating_sub(ocsp_size)
+            };
+        }
+
+        reserve_size
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        match &self.time_stamp_policy {
+            Some(policy) => policy.urls.first().cloned(),
+            None => self.signer.time_authority_url(),
+        }
+    }
+
+    fn time_stamp_policy(&self) -> TimeStampPolicy {
+        match &self.time_stamp_policy {
+            Some(policy) => policy.clone(),
+            None => self.signer.time_stamp_policy(),
+        }
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.signer.ocsp_val()
+    }
+
+    fn cert_chain_policy(&self) -> CertChainPolicy {
+        self.cert_chain_policy
+    }
+
+    fn include_ocsp(&self) -> bool {
+        self.include_ocsp
+    }
+
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        match &self.certificate_acceptance_policy {
+            Some(policy) => policy.clone(),
+            None => self.signer.certificate_acceptance_policy(),
+        }
+    }
+
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        self.x5chain_placement
+            .unwrap_or_else(|| self.signer.x5chain_placement())
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        self.signer.dynamic_assertions()
+    }
+
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        self.signer.cosigners()
+    }
+}
+
+/// Shares configured signers across threads.
+///
+/// Keys are read once when the signers are created, then each request borrows one
+/// of them in turn, so a server signs concurrently without reloading keys.
+///
+/// ```ignore
+/// let pool = Arc::new(SignerPool::new(get_signer_from_files(cert, key, "ps256", None)?));
+/// // on each request thread
+/// manifest.embed(&source, &dest, &*pool.get())?;
+/// ```
+pub struct SignerPool {
+    signers: Vec<Arc<dyn Signer + Send + Sync>>,
+    next: AtomicUsize,
+}
+
+impl SignerPool {
+    /// Create a pool sharing `signer`
+    pub fn new(signer: Box<dyn Signer + Send + Sync>) -> Self {
+        SignerPool {
+            signers: vec![Arc::from(signer)],
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Add another signer, e.g. one per hardware key slot, to spread requests over
+    pub fn add_signer(&mut self, signer: Box<dyn Signer + Send + Sync>) -> &mut Self {
+        self.signers.push(Arc::from(signer));
+        self
+    }
+
+    /// Number of signers in the pool
+    pub fn len(&self) -> usize {
+        self.signers.len()
+    }
+
+    /// Always false, a pool holds at least one signer
+    pub fn is_empty(&self) -> bool {
+        self.signers.is_empty()
+    }
+
+    /// Returns the next signer, going round the pool
+    pub fn get(&self) -> Arc<dyn Signer + Send + Sync> {
+        let index = self.next.fetch_add(1, Ordering::Relaxed) % self.signers.len();
+        self.signers[index].clone()
+    }
+}
+
+fn format_time(time: i64) -> String {
+    Utc.timestamp_opt(time, 0)
+        .single()
+        .map_or_else(|| time.to_string(), |t| t.to_rfc3339())
+}
+
+struct Credential {
+    signer: Box<dyn Signer + Send + Sync>,
+    not_before: i64,
+    not_after: i64,
+}
+
+/// Signs with whichever of several credentials is valid at the signing time.
+///
+/// Credentials are selected by the validity window of their signing certificate,
+/// preferring the most recently issued one when windows overlap, so a new
+/// credential can be added before the current one expires and takes over without
+/// a restart. Signing fails with [`Error::SigningCredentialNotValid`] when no
+/// credential is valid, and [`RotatingSigner::check_renewal`] reports a credential
+/// expiring within the renewal window that nothing replaces.
+///
+/// The credential is chosen once per signing operation and used for all of it.
+/// Wrap each credential, rather than the `RotatingSigner`, in signers such as
+/// [`PolicySigner`], since a wrapper queries the `RotatingSigner` for each value.
+///
+/// ```ignore
+/// let mut signer = RotatingSigner::new();
+/// signer
+///     .add_credential(get_signer_from_files("2022.pub", "2022.pem", "ps256", None)?)?
+///     .add_credential(get_signer_from_files("2023.pub", "2023.pem", "ps256", None)?)?;
+/// ```
+pub struct RotatingSigner {
+    credentials: Vec<Credential>,
+    signing_time: Option<DateTime<Utc>>,
+    renewal_window: Duration,
+}
+
+impl RotatingSigner {
+    /// Create a signer without credentials and a renewal window of 30 days
+    pub fn new() -> Self {
+        RotatingSigner {
+            credentials: Vec::new(),
+            signing_time: None,
+            renewal_window: Duration::from_secs(30 * 24 * 60 * 60),
+        }
+    }
+
+    /// Add a credential, its validity is read from the signer's end-entity certificate
+    pub fn add_credential(&mut self, signer: Box<dyn Signer + Send + Sync>) -> Result<&mut Self> {
+        let certs = signer.certs()?;
+        let der = certs.first().ok_or(Error::CoseX5ChainMissing)?;
+        let (_, cert) = X509Certificate::from_der(der).map_err(|_err| Error::CoseInvalidCert)?;
+        let validity = cert.validity();
+
+        self.credentials.push(Credential {
+            not_before: validity.not_before.timestamp(),
+            not_after: validity.not_after.timestamp(),
+            signer,
+        });
+        Ok(self)
+    }
+
+    /// Select credentials for `time` instead of the current time, e.g. to back-date a batch
+    pub fn set_signing_time(&mut self, time: DateTime<Utc>) -> &mut Self {
+        self.signing_time = Some(time);
+        self
+    }
+
+    /// Set how long before expiry a credential without a successor is reported
+    pub fn set_renewal_window(&mut self, window: Duration) -> &mut Self {
+        self.renewal_window = window;
+        self
+    }
+
+    /// Number of credentials held
+    pub fn len(&self) -> usize {
+        self.credentials.len()
+    }
+
+    /// Returns true if no credential has been added
+    pub fn is_empty(&self) -> bool {
+        self.credentials.is_empty()
+    }
+
+    fn time(&self) -> i64 {
+        self.signing_time.unwrap_or_else(Utc::now).timestamp()
+    }
+
+    fn select_at(&self, time: i64) -> Option<&Credential> {
+        self.credentials
+            .iter()
+            .filter(|c| c.not_before <= time && time <= c.not_after)
+            .max_by_key(|c| c.not_before)
+    }
+
+    fn credential(&self) -> Result<&Credential> {
+        let time = self.time();
+        self.select_at(time)
+            .ok_or_else(|| Error::SigningCredentialNotValid(format_time(time)))
+    }
+
+    /// Returns an error if the selected credential expires within the renewal window
+    /// and no other credential is valid after it.
+    pub fn check_renewal(&self) -> Result<()> {
+        let credential = self.credential()?;
+        let window = self.renewal_window.as_secs() as i64;
+
+        if credential.not_after - self.time() <= window
+            && self.select_at(credential.not_after + 1).is_none()
+        {
+            return Err(Error::SigningCredentialExpiring(format_time(
+                credential.not_after,
+            )));
+        }
+        Ok(())
+    }
+
+    // the signer to use for this signature, warning when it should be renewed
+    fn signer(&self) -> Result<&(dyn Signer + Send + Sync)> {
+        if let Err(Error::SigningCredentialExpiring(expiry)) = self.check_renewal() {
+            warn!(
+                "signing credential expires at {} and no credential replaces it",
+                expiry
+            );
+        }
+        Ok(self.credential()?.signer.as_ref())
+    }
+}
+
+impl Default for RotatingSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Signer for RotatingSigner {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.signer()?.sign(data)
+    }
+
+    fn alg(&self) -> Option<String> {
+        self.credential().ok()?.signer.alg()
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        self.credential()?.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        // reserve for the largest credential in case selection changes while signing
+        self.credentials
+            .iter()
+            .map(|c| c.signer.reserve_size())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.credential().ok()?.signer.time_authority_url()
+    }
+
+    fn time_stamp_policy(&self) -> TimeStampPolicy {
+        match self.credential() {
+            Ok(credential) => credential.signer.time_stamp_policy(),
+            Err(_) => TimeStampPolicy::new(Vec::new()),
+        }
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.credential().ok()?.signer.ocsp_val()
+    }
+
+    fn cert_chain_policy(&self) -> CertChainPolicy {
+        self.credential()
+            .map_or(CertChainPolicy::Full, |c| c.signer.cert_chain_policy())
+    }
+
+    fn include_ocsp(&self) -> bool {
+        self.credential().map_or(true, |c| c.signer.include_ocsp())
+    }
+
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        self.credential()
+            .map(|c| c.signer.certificate_acceptance_policy())
+            .unwrap_or_default()
+    }
+
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        self.credential()
+            .map_or(X5ChainPlacement::Unprotected, |c| {
+                c.signer.x5chain_placement()
+            })
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        self.credential()
+            .map(|c| c.signer.dynamic_assertions())
+            .unwrap_or_default()
+    }
+
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        match self.credential() {
+            Ok(credential) => credential.signer.cosigners(),
+            Err(_) => &[],
+        }
+    }
+
+    fn signer_for_operation(&self) -> Result<Option<&dyn Signer>> {
+        let signer: &dyn Signer = self.signer()?;
+        Ok(Some(signer))
+    }
+}
+
+/// Wraps a [`Signer`] so other signers co-sign the claims it signs.
+///
+/// Each co-signer adds a countersignature covering the claim and the signature of the
+/// wrapped signer, so the claim is signed by all of them. Only the wrapped signer
+/// time-stamps its signature.
+///
+/// ```ignore
+/// let mut signer = CosignedSigner::new(Box::new(vendor_signer));
+/// signer.add_cosigner(Box::new(publisher_signer));
+/// ```
+pub struct CosignedSigner {
+    signer: Box<dyn Signer>,
+    cosigners: Vec<Box<dyn Signer>>,
+}
+
+impl CosignedSigner {
+    /// Wrap the signer making the claim signature
+    pub fn new(signer: Box<dyn Signer>) -> Self {
+        CosignedSigner {
+            signer,
+            cosigners: Vec::new(),
+        }
+    }
+
+    /// Add a signer that co-signs the claim
+    pub fn add_cosigner(&mut self, cosigner: Box<dyn Signer>) -> &mut Self {
+        self.cosigners.push(cosigner);
+        self
+    }
+}
+
+impl Signer for CosignedSigner {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.signer.sign(data)
+    }
+
+    fn alg(&self) -> Option<String> {
+        self.signer.alg()
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        self.signer.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        // the countersignatures are stored in the same signature box
+        self.cosigners
+            .iter()
+            .fold(self.signer.reserve_size(), |size, c| {
+                size + c.reserve_size()
+            })
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.signer.time_authority_url()
+    }
+
+    fn time_stamp_policy(&self) -> TimeStampPolicy {
+        self.signer.time_stamp_policy()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.signer.ocsp_val()
+    }
+
+    fn cert_chain_policy(&self) -> CertChainPolicy {
+        self.signer.cert_chain_policy()
+    }
+
+    fn include_ocsp(&self) -> bool {
+        self.signer.include_ocsp()
+    }
+
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        self.signer.certificate_acceptance_policy()
+    }
+
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        self.signer.x5chain_placement()
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        self.signer.dynamic_assertions()
+    }
+
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        &self.cosigners
+    }
+}
+
+/// A [`Signer`] that signs by calling a function, for keys held outside the SDK such as
+/// in a platform key store, a hardware security module or another language's crypto
+/// library.
+///
+/// The function is called with the bytes to sign and returns the raw signature. ECDSA
+/// signatures must be in the P1363 (`r || s`) format COSE uses.
+///
+/// ```ignore
+/// let signer = CallbackSigner::new(|data: &[u8]| hsm.sign(data), "es256", &certs_pem, 10000, None)?;
+/// ```
+pub struct CallbackSigner<F = Box<dyn Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync>> {
+    callback: F,
+    alg: String,
+    certs: Vec<Vec<u8>>,
+    reserve_size: usize,
+    tsa_url: Option<String>,
+}
+
+impl<F: Fn(&[u8]) -> Result<Vec<u8>>> CallbackSigner<F> {
+    /// Creates a signer calling `callback`. `alg` is the signing algorithm, such as
+    /// "es256", and `certs` the PEM or concatenated DER encoded certificate chain,
+    /// starting with the signing certificate. `reserve_size` is the space reserved for
+    /// the signature, certificates and any time-stamp.
+    ///
+    /// Returns [`Error::CoseInvalidCert`] if `certs` holds no certificates.
+    pub fn new(
+        callback: F,
+        alg: &str,
+        certs: &[u8],
+        reserve_size: usize,
+        tsa_url: Option<String>,
+    ) -> Result<Self> {
+        Ok(CallbackSigner {
+            callback,
+            alg: alg.to_lowercase(),
+            certs: crate::cert_profile::split_certs(certs)?,
+            reserve_size,
+            tsa_url,
+        })
+    }
+}
+
+impl<F: Fn(&[u8]) -> Result<Vec<u8>>> Signer for CallbackSigner<F> {
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        (self.callback)(data)
+    }
+
+    fn alg(&self) -> Option<String> {
+        Some(self.alg.clone())
+    }
+
+    fn certs(&self) -> Result<Vec<Vec<u8>>> {
+        Ok(self.certs.clone())
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.reserve_size
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.tsa_url.clone()
+    }
 }
 
 /// Trait to allow loading of signing credential from external sources
@@ -71,9 +824,11 @@ use async_trait::async_trait;
 ///
 /// This trait exists to allow the signature mechanism to be extended.
 ///
-/// Use this when the implementation is asynchronous.
+/// Use this when the implementation is asynchronous. In browsers the returned future
+/// does not need to be `Send`, so it can await browser APIs.
 #[cfg(feature = "async_signer")]
-#[async_trait]
+#[cfg_attr(any(not(target_arch = "wasm32"), target_os = "wasi"), async_trait)]
+#[cfg_attr(all(target_arch = "wasm32", not(target_os = "wasi")), async_trait(?Send))]
 pub trait AsyncSigner: Sync {
     /// Returns a new byte array which is a signature over the original.
     async fn sign(&self, data: &[u8]) -> Result<Vec<u8>>;
@@ -82,4 +837,308 @@ pub trait AsyncSigner: Sync {
     /// Signing will fail if the result of the `sign` function is larger
     /// than this value.
     fn reserve_size(&self) -> usize;
+
+    /// Whether the signatures from `sign` place the certificate chain in the protected
+    /// header (C2PA 2.x) or the unprotected header (C2PA 1.x). Signatures placing it
+    /// elsewhere are rejected.
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        X5ChainPlacement::Unprotected
+    }
+
+    /// Which rules of the signing certificate profile the certificate of the signatures
+    /// from `sign` must follow before they are embedded. Defaults to every rule that is
+    /// not soft.
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        CertificateAcceptancePolicy::default()
+    }
+
+    /// Assertions generated while signing, added to the claim being signed
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        Vec::new()
+    }
+
+    /// Signers that co-sign the claim once `sign` returns, each adding a countersignature
+    /// over the claim and this signer's signature. The protected header of the signature
+    /// must commit to them and `reserve_size` must include their signatures.
+    fn cosigners(&self) -> &[Box<dyn Signer>] {
+        &[]
+    }
+}
+
+/// The `RemoteSigner` trait signs claims with a signing service that returns the whole
+/// COSE_Sign1, such as one reached with a [`RemoteSignerClient`](crate::RemoteSignerClient).
+///
+/// Unlike a [`Signer`], which signs the COSE Sig_structure built by the SDK, the service
+/// builds the signature headers itself from its own credentials.
+#[cfg(feature = "file_io")]
+pub trait RemoteSigner: Sync {
+    /// Returns a COSE_Sign1 with a detached payload signing `claim_bytes`, with its
+    /// time-stamp stored as described by `tss`.
+    fn sign_claim(&self, claim_bytes: &[u8], tss: crate::TimeStampStorage) -> Result<Vec<u8>>;
+
+    /// Returns the size in bytes of the largest possible COSE_Sign1.
+    /// Signing will fail if the result of `sign_claim` is larger than this value.
+    fn reserve_size(&self) -> usize;
+
+    /// Which rules of the signing certificate profile the certificate of the service
+    /// must follow before its signatures are embedded. Defaults to every rule that is
+    /// not soft.
+    fn certificate_acceptance_policy(&self) -> CertificateAcceptancePolicy {
+        CertificateAcceptancePolicy::default()
+    }
+
+    /// Assertions generated while signing, added to the claim before it is sent to the service
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cert_chain_policy() {
+        let chain = vec![vec![1u8; 10], vec![2u8; 20], vec![3u8; 30]];
+
+        assert_eq!(CertChainPolicy::Full.apply(chain.clone()).len(), 3);
+        assert_eq!(CertChainPolicy::Partial(2).apply(chain.clone()).len(), 2);
+        assert_eq!(CertChainPolicy::Partial(0).apply(chain.clone()).len(), 1);
+        assert_eq!(
+            CertChainPolicy::LeafOnly.apply(chain.clone()),
+            vec![vec![1u8; 10]]
+        );
+    }
+
+    struct OcspSigner;
+
+    impl Signer for OcspSigner {
+        fn sign(&self, _data: &[u8]) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        fn alg(&self) -> Option<String> {
+            None
+        }
+
+        fn certs(&self) -> Result<Vec<Vec<u8>>> {
+            Ok(vec![vec![1u8; 10], vec![2u8; 20], vec![3u8; 30]])
+        }
+
+        fn reserve_size(&self) -> usize {
+            1000
+        }
+
+        fn ocsp_val(&self) -> Option<Vec<u8>> {
+            Some(vec![4u8; 100])
+        }
+    }
+
+    #[test]
+    fn test_policy_signer_reserve_size() {
+        let mut signer = PolicySigner::new(OcspSigner);
+        assert_eq!(signer.reserve_size(), 1000);
+
+        signer.set_cert_chain_policy(CertChainPolicy::LeafOnly);
+        assert_eq!(signer.reserve_size(), 950);
+
+        // the dropped OCSP response is not reserved for
+        signer.set_include_ocsp(false);
+        assert_eq!(signer.reserve_size(), 850);
+    }
+
+    struct TsaSigner;
+
+    impl Signer for TsaSigner {
+        fn sign(&self, _data: &[u8]) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        fn alg(&self) -> Option<String> {
+            None
+        }
+
+        fn certs(&self) -> Result<Vec<Vec<u8>>> {
+            Ok(Vec::new())
+        }
+
+        fn reserve_size(&self) -> usize {
+            0
+        }
+
+        fn time_authority_url(&self) -> Option<String> {
+            Some("http://127.0.0.1:1/tsa".to_owned())
+        }
+    }
+
+    #[test]
+    fn test_time_stamp_policy() {
+        assert_eq!(
+            TsaSigner.time_stamp_policy(),
+            TimeStampPolicy::new(vec!["http://127.0.0.1:1/tsa".to_owned()])
+        );
+
+        let mut policy = TimeStampPolicy::new(vec![
+            "http://127.0.0.1:1/first".to_owned(),
+            "http://127.0.0.1:1/second".to_owned(),
+        ]);
+        policy.set_timeout(Duration::from_secs(1)).set_retries(1);
+
+        let mut signer = PolicySigner::new(TsaSigner);
+        signer.set_time_stamp_policy(policy.clone());
+        assert_eq!(signer.time_stamp_policy(), policy);
+        assert_eq!(
+            signer.time_authority_url(),
+            Some("http://127.0.0.1:1/first".to_owned())
+        );
+
+        // every authority is unreachable so every attempt fails
+        assert!(crate::time_stamp::timestamp_data_with_policy(&policy, b"data").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    fn test_signer_pool() {
+        #![allow(clippy::unwrap_used)]
+        use crate::{get_signer_from_files, openssl::temp_signer};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (_, rsa_cert) = temp_signer::get_temp_signer(temp_dir.path());
+        let rsa_key = rsa_cert.with_extension("pem");
+        let (_, ec_cert) = temp_signer::get_ec_signer(temp_dir.path(), "es256", None);
+        let ec_key = ec_cert.with_extension("pem");
+
+        let mut pool =
+            SignerPool::new(get_signer_from_files(&rsa_cert, &rsa_key, "ps256", None).unwrap());
+        pool.add_signer(get_signer_from_files(&ec_cert, &ec_key, "es256", None).unwrap());
+        assert_eq!(pool.len(), 2);
+
+        // requests on different threads share the configured signers
+        let pool = Arc::new(pool);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                std::thread::spawn(move || {
+                    let signer = pool.get();
+                    (signer.alg().unwrap(), signer.sign(b"data").unwrap())
+                })
+            })
+            .collect();
+
+        let mut algs: Vec<String> = handles
+            .into_iter()
+            .map(|h| {
+                let (alg, signature) = h.join().unwrap();
+                assert!(!signature.is_empty());
+                alg
+            })
+            .collect();
+        algs.sort();
+        assert_eq!(algs, vec!["es256", "es256", "ps256", "ps256"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    fn test_rotating_signer() {
+        #![allow(clippy::unwrap_used)]
+        use crate::{get_signer_from_files, openssl::temp_signer};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (_, rsa_cert) = temp_signer::get_temp_signer(temp_dir.path());
+        let (_, ec_cert) = temp_signer::get_ec_signer(temp_dir.path(), "es256", None);
+
+        let mut signer = RotatingSigner::new();
+        assert!(matches!(
+            signer.sign(b"data"),
+            Err(Error::SigningCredentialNotValid(_))
+        ));
+
+        signer
+            .add_credential(
+                get_signer_from_files(&rsa_cert, &rsa_cert.with_extension("pem"), "ps256", None)
+                    .unwrap(),
+            )
+            .unwrap()
+            .add_credential(
+                get_signer_from_files(&ec_cert, &ec_cert.with_extension("pem"), "es256", None)
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(signer.len(), 2);
+
+        // the temp credentials are valid for 180 days
+        let now = Utc::now();
+        signer.set_signing_time(now);
+        assert!(signer.sign(b"data").is_ok());
+        assert!(signer.check_renewal().is_ok());
+
+        // a signing operation uses the credential selected when it starts
+        let pinned = operation_signer(&signer).unwrap();
+        assert_eq!(pinned.certs().unwrap(), signer.certs().unwrap());
+        assert_eq!(pinned.alg(), signer.alg());
+        assert!(pinned.signer_for_operation().unwrap().is_none());
+
+        signer.set_renewal_window(Duration::from_secs(365 * 24 * 60 * 60));
+        assert!(matches!(
+            signer.check_renewal(),
+            Err(Error::SigningCredentialExpiring(_))
+        ));
+
+        signer.set_signing_time(now + chrono::Duration::days(200));
+        assert!(matches!(
+            signer.sign(b"data"),
+            Err(Error::SigningCredentialNotValid(_))
+        ));
+        assert!(matches!(
+            operation_signer(&signer),
+            Err(Error::SigningCredentialNotValid(_))
+        ));
+        assert!(signer.alg().is_none());
+    }
+
+    #[test]
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    fn test_callback_signer() {
+        #![allow(clippy::unwrap_used)]
+        use crate::openssl::temp_signer;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ec_signer, ec_cert) = temp_signer::get_ec_signer(temp_dir.path(), "es256", None);
+        let certs_pem = std::fs::read(&ec_cert).unwrap();
+
+        let signer = CallbackSigner::new(
+            |data: &[u8]| ec_signer.sign(data),
+            "ES256",
+            &certs_pem,
+            ec_signer.reserve_size(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(signer.alg().as_deref(), Some("es256"));
+        assert_eq!(signer.certs().unwrap(), ec_signer.certs().unwrap());
+        assert!(!signer.sign(b"data").unwrap().is_empty());
+
+        // concatenated DER certificates are accepted too
+        let certs_der = ec_signer.certs().unwrap().concat();
+        let signer = CallbackSigner::new(
+            |data: &[u8]| ec_signer.sign(data),
+            "es256",
+            &certs_der,
+            1024,
+            None,
+        )
+        .unwrap();
+        assert_eq!(signer.certs().unwrap(), ec_signer.certs().unwrap());
+
+        assert!(matches!(
+            CallbackSigner::new(
+                |data: &[u8]| ec_signer.sign(data),
+                "es256",
+                b"not a certificate",
+                1024,
+                None
+            ),
+            Err(Error::CoseInvalidCert)
+        ));
+    }
 }
@@ -0,0 +1,201 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Reads who signed an asset and when, without validating it.
+//!
+//! Only the manifest store and the claim signature of the active manifest are parsed.
+//! The asset is not hashed and neither the signature nor its certificates are verified,
+//! so listing views over many files can show the signer cheaply, but nothing read this
+//! way is trusted until the asset is validated.
+
+#[cfg(feature = "file_io")]
+use std::path::Path;
+
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+use x509_parser::prelude::*;
+
+use crate::{
+    cose_validator::get_signature_details,
+    jumbf_io::load_jumbf_from_slice,
+    status_tracker::{DetailedStatusTracker, OneShotStatusTracker},
+    store::Store,
+    Error, Result,
+};
+
+/// A certificate of a signing certificate chain
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CertSummary {
+    /// The subject of the certificate
+    pub subject: String,
+    /// The issuer of the certificate
+    pub issuer: String,
+    /// The serial number of the certificate, in hexadecimal
+    pub serial_number: String,
+    /// The start of the validity of the certificate, in RFC 3339 format
+    pub not_before: String,
+    /// The end of the validity of the certificate, in RFC 3339 format
+    pub not_after: String,
+}
+
+impl CertSummary {
+    fn from_der(der: &[u8]) -> Option<Self> {
+        let (_rem, cert) = X509Certificate::from_der(der).ok()?;
+        Some(CertSummary {
+            subject: cert.subject().to_string(),
+            issuer: cert.issuer().to_string(),
+            serial_number: cert.tbs_certificate.serial.to_str_radix(16),
+            not_before: format_time(cert.validity().not_before.timestamp()),
+            not_after: format_time(cert.validity().not_after.timestamp()),
+        })
+    }
+}
+
+// format a time in seconds as RFC 3339
+fn format_time(time: i64) -> String {
+    Utc.timestamp_opt(time, 0)
+        .single()
+        .map_or_else(|| time.to_string(), |t| t.to_rfc3339())
+}
+
+/// The signer of the active manifest of an asset, read without validating the asset.
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::SigningInfo;
+/// # fn main() -> Result<()> {
+/// let info = SigningInfo::from_file("tests/fixtures/CA.jpg")?;
+/// println!("{:?} signed at {:?}", info.issuer, info.time);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SigningInfo {
+    /// The label of the active manifest
+    pub manifest_label: String,
+    /// The C2PA name of the signing algorithm, `None` if it is missing or not supported
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alg: Option<String>,
+    /// The organization of the signing certificate's subject, the issuer shown for
+    /// the manifest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub issuer: Option<String>,
+    /// The serial number of the signing certificate, in hexadecimal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cert_serial_number: Option<String>,
+    /// The time of the signature in RFC 3339 format, from its time-stamp or else the
+    /// time recorded by the signer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    /// Whether the time was read from a time-stamp
+    pub time_stamped: bool,
+    /// The certificates of the signing certificate chain that could be parsed,
+    /// starting with the signing certificate
+    pub cert_chain: Vec<CertSummary>,
+}
+
+impl SigningInfo {
+    /// Reads the signer of an asset held in memory.
+    /// `format` is the MIME type or extension of the asset.
+    ///
+    /// Returns an error if the asset has no manifest store, the store cannot be parsed
+    /// or the claim signature is not a COSE_Sign1.
+    pub fn from_bytes(format: &str, data: &[u8]) -> Result<Self> {
+        let jumbf = load_jumbf_from_slice(format, data)?;
+        Self::from_jumbf(&jumbf)
+    }
+
+    /// Reads the signer of the asset at `path`.
+    /// Only the manifest store is read from the asset.
+    #[cfg(feature = "file_io")]
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let jumbf = crate::jumbf_io::load_jumbf_from_file(path.as_ref())?;
+        if jumbf.is_empty() {
+            return Err(Error::JumbfNotFound);
+        }
+        Self::from_jumbf(&jumbf)
+    }
+
+    // parse the store within the resource limits, keeping any findings out of the result
+    fn from_jumbf(jumbf: &[u8]) -> Result<Self> {
+        let store = Store::from_jumbf(jumbf, &mut DetailedStatusTracker::new())?;
+        let claim = store.provenance_claim().ok_or(Error::ProvenanceMissing)?;
+
+        // the issuer is only read from the signing certificate, never from the rest of the chain
+        let details = get_signature_details(
+            claim.signature_val(),
+            &claim.data()?,
+            &mut OneShotStatusTracker::new(),
+        )?;
+        let cert_serial_number = details
+            .certs
+            .first()
+            .and_then(|der| CertSummary::from_der(der))
+            .map(|cert| cert.serial_number);
+        let cert_chain: Vec<CertSummary> = details
+            .certs
+            .iter()
+            .filter_map(|der| CertSummary::from_der(der))
+            .collect();
+
+        Ok(SigningInfo {
+            manifest_label: claim.label().to_owned(),
+            alg: details.alg,
+            issuer: details.issuer_org,
+            cert_serial_number,
+            time: details.time.map(|(time, _)| time.to_rfc3339()),
+            time_stamped: details.time.map_or(false, |(_, time_stamped)| time_stamped),
+            cert_chain,
+        })
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "file_io")]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{utils::test::fixture_path, ManifestStore};
+
+    #[test]
+    fn test_signing_info() {
+        let info = SigningInfo::from_file(fixture_path("CA.jpg")).unwrap();
+        assert!(info.alg.is_some());
+        assert!(!info.cert_chain.is_empty());
+        assert_eq!(
+            info.cert_serial_number.as_deref(),
+            Some(info.cert_chain[0].serial_number.as_str())
+        );
+
+        // the same signer is read as when the asset is validated
+        let manifest_store = ManifestStore::from_file(fixture_path("CA.jpg")).unwrap();
+        assert_eq!(
+            manifest_store.active_label(),
+            Some(info.manifest_label.as_str())
+        );
+        let manifest = manifest_store.get_active().unwrap();
+        assert_eq!(manifest.issuer(), info.issuer);
+        assert!(info.time.is_some());
+
+        let data = std::fs::read(fixture_path("CA.jpg")).unwrap();
+        assert_eq!(SigningInfo::from_bytes("jpg", &data).unwrap(), info);
+
+        assert!(matches!(
+            SigningInfo::from_file(fixture_path("earth_apollo17.jpg")),
+            Err(Error::JumbfNotFound)
+        ));
+    }
+}
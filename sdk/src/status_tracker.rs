@@ -11,22 +11,71 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use crate::error::{Error, Result};
+//! Logs the checks made while validating a manifest store.
+//!
+//! Each [`LogItem`] records the JUMBF URI of the element that was checked, the C2PA
+//! status code of the result and its [`Severity`]. Logs serialize to JSON, so
+//! validation outcomes can be stored and compared later.
 
 use std::fmt;
 
-#[derive(Debug)]
+use serde::{Serialize, Serializer};
+use tracing::{debug, info, warn};
+
+use crate::{
+    assertions::DataHashMismatch,
+    error::{Error, Result},
+    validation_status::ValidationStatusCode,
+};
+
+/// How serious a logged finding is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// A check passed, or could not be made
+    Info,
+    /// A finding that does not make the manifest invalid
+    Warning,
+    /// A check failed
+    Error,
+}
+
+impl Default for Severity {
+    fn default() -> Self {
+        Severity::Info
+    }
+}
+
+/// A check made while validating a manifest store
+#[derive(Debug, Serialize)]
 pub struct LogItem {
-    pub label: String, // JUBMF label of the item if available, or other descriptive label
-    pub file: String,  // File where failure occurred
-    pub function: String, // Function where failure occurred
-    pub line: String,  // Line number for error
-    pub description: String, // Description of the failure
-    pub err_val: Option<Error>, // Actual error code
-    pub validation_status: Option<String>, // C2PA code if available
+    /// JUMBF URI of the element checked if available, or other descriptive label
+    pub label: String,
+    /// Source file where the item was logged
+    pub file: String,
+    /// Function where the item was logged
+    pub function: String,
+    /// Line number where the item was logged
+    pub line: String,
+    /// Description of the check
+    pub description: String,
+    /// The error found, if any
+    #[serde(rename = "error", serialize_with = "serialize_error")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub err_val: Option<Error>,
+    /// C2PA status code if available
+    #[serde(rename = "code", skip_serializing_if = "Option::is_none")]
+    pub validation_status: Option<ValidationStatusCode>,
+    /// How serious the finding is
+    pub severity: Severity,
+    /// Where the asset departs from the asset a data hash was made for, if the item
+    /// reports a data hash mismatch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_mismatch: Option<DataHashMismatch>,
 }
 
 impl LogItem {
+    /// Create an item for the check of the element at `label`
     pub fn new(label: &str, description: &str, function: &str, file: &str, line: u32) -> Self {
         LogItem {
             label: label.to_string(),
@@ -36,24 +85,72 @@ impl LogItem {
             description: description.to_string(),
             err_val: None,
             validation_status: None,
+            severity: Severity::Info,
+            hash_mismatch: None,
         }
     }
 
-    // add an error value
+    /// Add an error value, making the item an error
     pub fn error(self, err: Error) -> Self {
         LogItem {
             err_val: Some(err),
+            severity: Severity::Error,
             ..self
         }
     }
 
-    // add an error value
-    pub fn validation_status(self, status: &str) -> Self {
+    /// Add a C2PA status code. Failure codes make the item an error.
+    pub fn validation_status(self, status: ValidationStatusCode) -> Self {
+        let severity = if status.is_success() || status.is_informational() {
+            self.severity
+        } else {
+            Severity::Error
+        };
         LogItem {
-            validation_status: Some(status.to_string()),
+            validation_status: Some(status),
+            severity,
             ..self
         }
     }
+
+    /// Set the severity of the item
+    pub fn severity(self, severity: Severity) -> Self {
+        LogItem { severity, ..self }
+    }
+
+    /// Add the location of a data hash mismatch
+    pub fn hash_mismatch(self, mismatch: DataHashMismatch) -> Self {
+        LogItem {
+            hash_mismatch: Some(mismatch),
+            ..self
+        }
+    }
+
+    // report the item as a tracing event in the current span, findings are about the
+    // asset rather than the SDK so errors are only warnings
+    fn trace(&self) {
+        let code = self
+            .validation_status
+            .as_ref()
+            .map_or("", ValidationStatusCode::as_str);
+        let error = self.err_val.as_ref().map(|e| e.to_string());
+        match self.severity {
+            Severity::Error => warn!(label = %self.label, code, ?error, "{}", self.description),
+            Severity::Warning => info!(label = %self.label, code, "{}", self.description),
+            Severity::Info => debug!(label = %self.label, code, "{}", self.description),
+        }
+    }
+}
+
+// errors are recorded by their description
+fn serialize_error<S: Serializer>(
+    err: &Option<Error>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    match err {
+        Some(err) => serializer.serialize_str(&err.to_string()),
+        None => serializer.serialize_none(),
+    }
 }
 
 pub trait StatusTracker {
@@ -77,6 +174,19 @@ pub trait StatusTracker {
 
     // Log an item. No special consideration are given to the contents of the log item.
     fn log_silent(&mut self, log_item: LogItem);
+
+    /// Returns the items of the log with the given severity
+    fn items_with_severity(&self, severity: Severity) -> Vec<&LogItem> {
+        self.get_log()
+            .iter()
+            .filter(|item| item.severity == severity)
+            .collect()
+    }
+
+    /// Returns the log as JSON
+    fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self.get_log())?)
+    }
 }
 
 impl fmt::Display for dyn StatusTracker {
@@ -91,7 +201,7 @@ impl fmt::Debug for dyn StatusTracker {
     }
 }
 
-// Logger that returns success regardless of if LogItem was for an error condition
+/// Logger that returns success regardless of if LogItem was for an error condition
 #[derive(Default, Debug)]
 pub struct DetailedStatusTracker {
     logged_items: Vec<LogItem>,
@@ -121,6 +231,7 @@ impl StatusTracker for DetailedStatusTracker {
     }
 
     fn log(&mut self, log_item: LogItem, err: Option<Error>) -> Result<()> {
+        log_item.trace();
         let item_has_err = log_item.err_val.is_some();
         self.logged_items.push(log_item);
         if self.stop_on_error && item_has_err {
@@ -131,11 +242,12 @@ impl StatusTracker for DetailedStatusTracker {
     }
 
     fn log_silent(&mut self, log_item: LogItem) {
+        log_item.trace();
         self.logged_items.push(log_item);
     }
 }
 
-// Logger that will returns error values on LogItems with error
+/// Logger that will returns error values on LogItems with error
 #[derive(Default, Debug)]
 pub struct OneShotStatusTracker {
     logged_items: Vec<LogItem>,
@@ -165,6 +277,7 @@ impl StatusTracker for OneShotStatusTracker {
     }
 
     fn log(&mut self, log_item: LogItem, err: Option<Error>) -> Result<()> {
+        log_item.trace();
         let item_has_err = log_item.err_val.is_some();
         self.logged_items.push(log_item);
         if self.stop_on_error && item_has_err {
@@ -175,15 +288,29 @@ impl StatusTracker for OneShotStatusTracker {
     }
 
     fn log_silent(&mut self, log_item: LogItem) {
+        log_item.trace();
         self.logged_items.push(log_item);
     }
 }
+// trackers serialize as their log
+impl Serialize for DetailedStatusTracker {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.logged_items)
+    }
+}
+
+impl Serialize for OneShotStatusTracker {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.logged_items)
+    }
+}
+
 /// Check to see if report contains a specific C2PA status code
 #[allow(dead_code)] // in case we make use of these or export this
-pub fn report_has_status(report: &[LogItem], val: &str) -> bool {
+pub fn report_has_status(report: &[LogItem], val: ValidationStatusCode) -> bool {
     report.iter().any(|vi| {
         if let Some(vs) = &vi.validation_status {
-            vs == val
+            *vs == val
         } else {
             false
         }
@@ -263,7 +390,7 @@ pub mod tests {
         // item with error with caller specified error response, testing macro for generation
         let item3 = log_item!("test3", "test item 3 from macro", "test func")
             .error(Error::UnsupportedType)
-            .validation_status(validation_status::ALGORITHM_UNSUPPORTED);
+            .validation_status(ValidationStatusCode::AlgorithmUnsupported);
         assert!(matches!(
             tracker.log(item3, Some(Error::NotFound)),
             Err(Error::NotFound)
@@ -291,11 +418,56 @@ pub mod tests {
         // item with error with caller specified error response, testing macro for generation, test validation_status
         let item4 = log_item!("test3", "test item 3 from macro", "test func")
             .error(Error::UnsupportedType)
-            .validation_status(validation_status::ALGORITHM_UNSUPPORTED);
+            .validation_status(ValidationStatusCode::AlgorithmUnsupported);
         assert!(tracker.log(item4, None).is_ok());
 
         // there should be two items with error
         let errors = report_split_errors(tracker.get_log_mut());
         assert_eq!(errors.len(), 3);
     }
+
+    #[test]
+    fn test_severity_and_json() {
+        let mut tracker = DetailedStatusTracker::new();
+        tracker.log_silent(
+            log_item!(
+                "self#jumbf=/c2pa/urn:uuid:1/c2pa.signature",
+                "valid",
+                "test func"
+            )
+            .validation_status(ValidationStatusCode::ClaimSignatureValidated),
+        );
+        tracker.log_silent(
+            log_item!(
+                "self#jumbf=/c2pa/urn:uuid:1/c2pa.signature",
+                "legacy",
+                "test func"
+            )
+            .severity(Severity::Warning),
+        );
+        tracker.log_silent(
+            log_item!(
+                "self#jumbf=/c2pa/urn:uuid:1/c2pa.signature",
+                "expired",
+                "test func"
+            )
+            .validation_status(ValidationStatusCode::SigningCredentialExpired),
+        );
+        tracker.log_silent(log_item!("test", "not found", "test func").error(Error::NotFound));
+
+        assert_eq!(tracker.items_with_severity(Severity::Info).len(), 1);
+        assert_eq!(tracker.items_with_severity(Severity::Warning).len(), 1);
+        assert_eq!(tracker.items_with_severity(Severity::Error).len(), 2);
+
+        let json: serde_json::Value = serde_json::from_str(&tracker.to_json().unwrap()).unwrap();
+        assert_eq!(
+            json[0]["code"],
+            validation_status::CLAIM_SIGNATURE_VALIDATED
+        );
+        assert_eq!(json[0]["severity"], "info");
+        assert_eq!(json[1]["severity"], "warning");
+        assert_eq!(json[2]["severity"], "error");
+        assert!(json[3]["error"].is_string());
+        assert_eq!(serde_json::to_value(&tracker).unwrap(), json);
+    }
 }
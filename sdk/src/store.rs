@@ -12,44 +12,93 @@
 // each license.
 
 use crate::{
-    assertion::{Assertion, AssertionBase, AssertionDecodeError, AssertionDecodeErrorCause},
-    assertions::{labels, Ingredient, Relationship},
-    claim::{Claim, ClaimAssertion},
+    assertion::{
+        Assertion, AssertionBase, AssertionData, AssertionDecodeError, AssertionDecodeErrorCause,
+    },
+    assertions::{labels, CollectionHash, Ingredient, Relationship},
+    binding_check::{self, BindingState},
+    claim::{Claim, ClaimAssertion, ClaimVersion},
+    claim_binding::ClaimBinding,
+    cose_validator::{get_signature_binding, CertCheckOptions},
     error::{Error, Result},
     hash_utils::{hash_by_alg, vec_compare, verify_by_alg},
+    hashed_uri::HashedUri,
     jumbf::{self, boxes::*},
-    jumbf_io::{get_cailoader_handler, load_cai_from_memory},
+    jumbf_io::{get_cailoader_handler, load_cai_from_memory_with_limits},
+    manifest_repository::ManifestRepository,
+    metrics::metrics,
+    progress::{ProgressMonitor, ProgressPhase},
     status_tracker::{log_item, OneShotStatusTracker, StatusTracker},
-    validation_status,
+    utils::compression::expand_compressed_manifests,
+    validation_status::ValidationStatusCode,
+    verify_options::{IngredientValidation, ValidationScope, VerifyOptions},
     xmp_inmemory_utils::extract_provenance,
+    ResourceLimits,
 };
 
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+use crate::cose_validator::check_signing_revocation;
 #[cfg(feature = "file_io")]
 use crate::{
-    assertion::AssertionData,
-    assertions::DataHash,
+    assertions::{c2pa_action, Action, Actions, UserCbor},
     asset_io::{HashBlockObjectType, HashObjectPositions},
     cose_sign::cose_sign,
-    cose_validator::verify_cose,
-    embedded_xmp,
+    cose_validator::{check_signing_trust, check_timestamp_trust, verify_cose},
+    dynamic_assertion::{self, DynamicAssertion, PartialClaim},
+    external_signing::{ExternalSigner, PendingSignature},
+    jumbf::writer::SizeCounter,
     jumbf_io::{
-        get_supported_file_extension, load_cai_from_file, object_locations, save_jumbf_to_file,
+        get_assetio_handler, get_file_extension, get_supported_file_extension, load_cai_from_file,
+        load_cai_from_file_with_limits, object_locations, save_jumbf_to_file,
     },
+    signer::operation_signer,
+    time_stamp::TimeStampStorage,
     utils::{
-        hash_utils::{hash256, Exclusion},
-        patch::patch_bytes,
+        atomic_file::{write_atomically, FsyncPolicy},
+        hash_utils::{Exclusion, HashingOptions},
+        patch::{patch_bytes, patch_stream},
     },
-    Signer,
+    RemoteSigner, Signer,
 };
 
-#[cfg(feature = "async_signer")]
-use crate::AsyncSigner;
+#[cfg(all(feature = "file_io", feature = "async_signer"))]
+use crate::progress::{CancellationToken, NoProgress, ProgressReporter};
 use crate::ManifestStoreReport;
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use crate::{
+    assertions::DataHash,
+    cert_profile::check_signing_certs,
+    jumbf::writer::JumbfStreamWriter,
+    utils::{
+        compression::{compress_manifest, ManifestCompression},
+        hash_utils::hash256,
+    },
+};
+#[cfg(feature = "async_signer")]
+use crate::{cose_sign::cose_add_cosignatures, AsyncSigner};
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use std::io::{Seek, Write};
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+};
+#[cfg(feature = "file_io")]
+use std::{fs, io::BufWriter, path::Path};
+use tracing::instrument;
+#[cfg(feature = "file_io")]
+use tracing::{error, warn};
+
+// number of times a save is retried with a larger signature placeholder
 #[cfg(feature = "file_io")]
-use log::error;
-use std::{collections::HashMap, io::Cursor};
+const MAX_RESERVE_RETRIES: usize = 3;
+
+// room reserved in a compressed manifest for the data hashes that replace their placeholders
 #[cfg(feature = "file_io")]
-use std::{fs, path::Path};
+const COMPRESSED_HASH_RESERVE: usize = 1024;
+
+// time allowed for fetching a remote manifest store
+#[cfg(not(target_arch = "wasm32"))]
+const REMOTE_MANIFEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 /// A `Store` maintains a list of `Claim` structs.
 ///
@@ -60,6 +109,21 @@ pub struct Store {
     claims: Vec<Claim>,
     label: String,
     provenance_path: Option<String>,
+    #[cfg(feature = "file_io")]
+    hashing_options: HashingOptions,
+    #[cfg(feature = "file_io")]
+    timestamp_storage: Option<TimeStampStorage>,
+    #[cfg(feature = "file_io")]
+    fsync_policy: FsyncPolicy,
+    verify_options: VerifyOptions,
+    adjusted_reserve_size: Option<usize>,
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    manifest_compression: ManifestCompression,
+    // the size reserved for the compressed manifest being signed
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    compressed_manifest_size: Option<usize>,
+    // the store was recovered for an asset its manifest store was removed from
+    asset_stripped: bool,
 }
 
 struct ManifestInfo<'a> {
@@ -102,9 +166,58 @@ impl Store {
             claims: Vec::new(),
             label: label.to_string(),
             provenance_path: None,
+            #[cfg(feature = "file_io")]
+            hashing_options: HashingOptions::default(),
+            #[cfg(feature = "file_io")]
+            timestamp_storage: None,
+            #[cfg(feature = "file_io")]
+            fsync_policy: FsyncPolicy::default(),
+            verify_options: VerifyOptions::default(),
+            adjusted_reserve_size: None,
+            #[cfg(any(feature = "file_io", feature = "async_signer"))]
+            manifest_compression: ManifestCompression::default(),
+            #[cfg(any(feature = "file_io", feature = "async_signer"))]
+            compressed_manifest_size: None,
+            asset_stripped: false,
         }
     }
 
+    /// Set the options used when hashing assets during save
+    #[cfg(feature = "file_io")]
+    pub fn set_hashing_options(&mut self, options: HashingOptions) {
+        self.hashing_options = options;
+    }
+
+    /// Set how the signature time-stamp is stored when signing.
+    /// When `None` 1.x claims use `sigTst` and 2.x claims use `sigTst2`.
+    #[cfg(feature = "file_io")]
+    pub fn set_timestamp_storage(&mut self, storage: Option<TimeStampStorage>) {
+        self.timestamp_storage = storage;
+    }
+
+    /// Set how saved assets are flushed to storage before they replace the output
+    #[cfg(feature = "file_io")]
+    pub fn set_fsync_policy(&mut self, policy: FsyncPolicy) {
+        self.fsync_policy = policy;
+    }
+
+    /// Set how manifests are compressed when the store is written
+    #[cfg(feature = "file_io")]
+    pub fn set_manifest_compression(&mut self, compression: ManifestCompression) {
+        self.manifest_compression = compression;
+    }
+
+    /// Returns the signature reserve size used by the last save if the signer's
+    /// `reserve_size` was too small and the save was retried with a larger placeholder
+    pub fn adjusted_reserve_size(&self) -> Option<usize> {
+        self.adjusted_reserve_size
+    }
+
+    /// Set the options used when verifying this store
+    pub fn set_verify_options(&mut self, options: VerifyOptions) {
+        self.verify_options = options;
+    }
+
     /// Return label for the store
     pub fn label(&self) -> &str {
         &self.label
@@ -144,8 +257,11 @@ impl Store {
 
         // load the claim ingredients
         // preparse first to make sure we can load them
+        // in label order so colliding labels are always renamed the same way
+        let mut ingredient_stores: Vec<_> = claim.claim_ingredient_store().iter().collect();
+        ingredient_stores.sort_by(|a, b| a.0.cmp(b.0));
         let mut ingredient_claims: Vec<Claim> = Vec::new();
-        for (pc, claims) in claim.claim_ingredient_store() {
+        for (pc, claims) in ingredient_stores {
             let mut valid_pc = false;
 
             // expand for flat list insertion
@@ -168,13 +284,21 @@ impl Store {
         let claim_label = claim.label().to_string();
 
         // insert ingredients if needed
-        for ingredient_claim in ingredient_claims {
-            let label = ingredient_claim.label().to_owned();
-
-            if let std::collections::hash_map::Entry::Vacant(e) = self.claims_map.entry(label) {
-                let index = self.claims.push_get_index(ingredient_claim);
-                e.insert(index);
+        for mut ingredient_claim in ingredient_claims {
+            match self.get_claim(ingredient_claim.label()) {
+                // the same manifest is only stored once
+                Some(existing) if existing.data()? == ingredient_claim.data()? => continue,
+                // another manifest with the same label, possibly from a different generator
+                Some(_) => {
+                    let label = self.unused_label(ingredient_claim.label());
+                    ingredient_claim.set_label(&label);
+                }
+                None => (),
             }
+
+            let label = ingredient_claim.label().to_owned();
+            let index = self.claims.push_get_index(ingredient_claim);
+            self.claims_map.insert(label, index);
         }
 
         // add claim to store after ingredients
@@ -192,11 +316,8 @@ impl Store {
         claim.set_update_manifest(true);
 
         // check for disallowed assertions
-        if claim.has_assertion_type(labels::DATA_HASH)
-            || claim.has_assertion_type(labels::ACTIONS)
-            || claim.has_assertion_type(labels::BMFF_HASH)
-        {
-            return Err(Error::ClaimInvalidContent);
+        if claim.check_update_manifest_assertions().is_err() {
+            return Err(Error::UpdateManifestInvalid);
         }
 
         // must have exactly one ingredient
@@ -309,9 +430,32 @@ impl Store {
         }
     }
 
+    /// Recompute the claim hash and signature binding of a manifest,
+    /// the active manifest is used if `manifest_label` is None
+    pub fn claim_binding(&self, manifest_label: Option<&str>) -> Result<ClaimBinding> {
+        let claim = match manifest_label {
+            Some(label) => self.get_claim(label),
+            None => self.provenance_claim(),
+        }
+        .ok_or_else(|| Error::ClaimMissing {
+            label: manifest_label.unwrap_or_default().to_owned(),
+        })?;
+
+        let claim_bytes = claim.data()?;
+        let signature = get_signature_binding(claim.signature_val(), &claim_bytes)?;
+
+        Ok(ClaimBinding {
+            manifest_label: claim.label().to_owned(),
+            claim_hash_alg: claim.alg().to_owned(),
+            claim_hash: hash_by_alg(claim.alg(), &claim_bytes, None),
+            claim_bytes,
+            signature,
+        })
+    }
+
     // Returns placeholder that will be searched for and replaced
     // with actual signature data.
-    #[cfg(feature = "file_io")]
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
     fn sign_claim_placeholder(&self, claim: &Claim, min_reserve_size: usize) -> Vec<u8> {
         let placeholder_str = format!("signature placeholder:{}", claim.label());
         let mut placeholder = hash256(placeholder_str.as_bytes()).as_bytes().to_vec();
@@ -324,19 +468,42 @@ impl Store {
 
     /// Sign the claim and return signature.
     #[cfg(feature = "file_io")]
+    #[instrument(skip_all, fields(alg = ?signer.alg(), box_size = box_size))]
     pub fn sign_claim(
         &self,
         claim: &Claim,
         signer: &dyn Signer,
         box_size: usize,
     ) -> Result<Vec<u8>> {
+        let signer = operation_signer(signer)?;
+
+        // C2PA 2.x restricts the signature algorithms that may be used
+        let alg = signer.alg().unwrap_or_default();
+        if !claim.claim_version().allows_signing_alg(&alg) {
+            return Err(Error::CoseSignatureAlgorithmNotSupported);
+        }
+
+        // fail before signing if verifiers would reject the certificate
+        let policy = signer.certificate_acceptance_policy();
+        check_signing_certs(&policy, &signer.certs()?, signer.cosigners())?;
+
+        let tss = self.claim_timestamp_storage(claim);
         let claim_bytes = claim.data()?;
 
-        cose_sign(signer, &claim_bytes, box_size).and_then(|sig| {
+        cose_sign(signer, &claim_bytes, box_size, tss).and_then(|sig| {
             // Sanity check: Ensure that this signature is valid.
+            // Certificates the policy accepts against the profile only have their
+            // signature checked.
 
             let mut cose_log = OneShotStatusTracker::new();
-            match verify_cose(&sig, &claim_bytes, b"", false, &mut cose_log) {
+            match verify_cose(
+                &sig,
+                &claim_bytes,
+                b"",
+                policy.relaxes_validation(),
+                CertCheckOptions::default(),
+                &mut cose_log,
+            ) {
                 Ok(_) => Ok(sig),
                 Err(err) => {
                     error!(
@@ -349,15 +516,73 @@ impl Store {
         })
     }
 
+    // where the time-stamp of the signature of `claim` is stored
+    #[cfg(feature = "file_io")]
+    fn claim_timestamp_storage(&self, claim: &Claim) -> TimeStampStorage {
+        self.timestamp_storage
+            .unwrap_or(match claim.claim_version() {
+                ClaimVersion::V1 => TimeStampStorage::V1SigTst,
+                ClaimVersion::V2 => TimeStampStorage::V2SigTst2,
+            })
+    }
+
+    /// Sign the claim with a remote signing service and return signature.
+    #[cfg(feature = "file_io")]
+    pub fn sign_claim_remote(
+        &self,
+        claim: &Claim,
+        signer: &dyn RemoteSigner,
+        box_size: usize,
+    ) -> Result<Vec<u8>> {
+        let claim_bytes = claim.data()?;
+        let cose = signer.sign_claim(&claim_bytes, self.claim_timestamp_storage(claim))?;
+
+        // the service may not pad the signature to the reserved size
+        let mut sign1 = c2pa_core::cose::parse_sign1(&cose)?;
+        check_signing_certs(
+            &signer.certificate_acceptance_policy(),
+            &c2pa_core::cose::x5chain(&sign1)?,
+            &[],
+        )?;
+        sign1.payload = None;
+        let sig = c2pa_core::cose::pad_sign1(&mut sign1, box_size)?;
+
+        // Sanity check: Ensure that this signature is valid.
+        let mut cose_log = OneShotStatusTracker::new();
+        crate::verify_detached(&sig, &claim_bytes, &VerifyOptions::default(), &mut cose_log)?;
+        Ok(sig)
+    }
+
     /// Sign the claim asynchronously and return signature.
     #[cfg(feature = "async_signer")]
+    #[instrument(skip_all, fields(alg = ?signer.alg()))]
     pub async fn sign_claim_async(
         &self,
         claim: &Claim,
         signer: &dyn AsyncSigner,
     ) -> Result<Vec<u8>> {
         let claim_bytes = claim.data()?;
-        signer.sign(&claim_bytes).await
+        let sig = signer.sign(&claim_bytes).await?;
+
+        // the certificate is only known from the signature
+        let sign1 = c2pa_core::cose::parse_sign1(&sig)?;
+        let placement = signer.x5chain_placement();
+        if c2pa_core::cose::x5chain_placement(&sign1) != Some(placement) {
+            return Err(Error::BadParam(format!(
+                "the signature does not have an x5chain placed as {:?}",
+                placement
+            )));
+        }
+        check_signing_certs(
+            &signer.certificate_acceptance_policy(),
+            &c2pa_core::cose::x5chain(&sign1)?,
+            signer.cosigners(),
+        )?;
+
+        match signer.cosigners() {
+            [] => Ok(sig),
+            cosigners => cose_add_cosignatures(&sig, cosigners, &claim_bytes),
+        }
     }
 
     /// return the current provenance claim label if available
@@ -394,22 +619,60 @@ impl Store {
         self.claims_map.insert(label, index);
     }
 
-    #[cfg(feature = "file_io")]
-    fn add_assertion_to_jumbf_store(
-        store: &mut CAIAssertionStore,
-        claim_assertion: &ClaimAssertion,
-    ) -> Result<()> {
+    // the first of `label__1`, `label__2`... not used by a manifest of the store
+    fn unused_label(&self, label: &str) -> String {
+        let mut n = 1;
+        loop {
+            let candidate = format!("{}__{}", label, n);
+            if !self.claims_map.contains_key(&candidate) {
+                return candidate;
+            }
+            n += 1;
+        }
+    }
+
+    /// Find the manifest an ingredient's `c2pa_manifest` refers to.
+    ///
+    /// Manifests from different generators can share a label, the ones stored later are
+    /// renamed `label__1`, `label__2` and so on. The manifest with the label or a renamed
+    /// one whose hash matches is returned, otherwise the manifest with the label, if any.
+    pub(crate) fn resolve_ingredient(&self, c2pa_manifest: &HashedUri) -> Option<&Claim> {
+        let label = Store::manifest_label_from_path(&c2pa_manifest.url());
+        let hash_matches = |claim: &Claim| {
+            let alg = c2pa_manifest
+                .alg()
+                .unwrap_or_else(|| claim.alg().to_owned());
+            claim
+                .data()
+                .map(|data| verify_by_alg(&alg, &c2pa_manifest.hash(), &data, None))
+                .unwrap_or(false)
+        };
+
+        let claim = self.get_claim(&label);
+        if claim.map_or(true, hash_matches) {
+            return claim;
+        }
+
+        (1..)
+            .map_while(|n| self.get_claim(&format!("{}__{}", label, n)))
+            .find(|renamed| hash_matches(renamed))
+            .or(claim)
+    }
+
+    // Create the JUMBF box of an assertion
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    fn assertion_box(claim_assertion: &ClaimAssertion) -> Result<Box<dyn BMFFBox>> {
         // Grab assertion data object.
         let d = claim_assertion.assertion().decode_data();
 
-        match d {
+        let assertion_box: Box<dyn BMFFBox> = match d {
             AssertionData::Json(_) => {
                 let mut json_data = CAIJSONAssertionBox::new(&claim_assertion.label());
                 json_data.add_json(claim_assertion.assertion().data().to_vec());
                 if let Some(salt) = claim_assertion.salt() {
                     json_data.set_salt(salt.clone())?;
                 }
-                store.add_assertion(Box::new(json_data));
+                Box::new(json_data)
             }
             AssertionData::Binary(_) => {
                 // TODO: Handle other binary box types if needed.
@@ -422,7 +685,7 @@ impl Store {
                 if let Some(salt) = claim_assertion.salt() {
                     data.set_salt(salt.clone())?;
                 }
-                store.add_assertion(Box::new(data));
+                Box::new(data)
             }
             AssertionData::Cbor(_) => {
                 let mut cbor_data = CAICBORAssertionBox::new(&claim_assertion.label());
@@ -430,7 +693,7 @@ impl Store {
                 if let Some(salt) = claim_assertion.salt() {
                     cbor_data.set_salt(salt.clone())?;
                 }
-                store.add_assertion(Box::new(cbor_data));
+                Box::new(cbor_data)
             }
             AssertionData::Uuid(s, _) => {
                 let mut uuid_data = CAIUUIDAssertionBox::new(&claim_assertion.label());
@@ -438,10 +701,10 @@ impl Store {
                 if let Some(salt) = claim_assertion.salt() {
                     uuid_data.set_salt(salt.clone())?;
                 }
-                store.add_assertion(Box::new(uuid_data));
+                Box::new(uuid_data)
             }
-        }
-        Ok(())
+        };
+        Ok(assertion_box)
     }
 
     // look for old style hashing to determine if this is a pre 1.0 claim
@@ -545,95 +808,208 @@ impl Store {
     }
 
     /// Convert this claims store to a JUMBF box.
+    ///
+    /// Nothing is signed, so the dynamic assertions of `signer` are only part of the
+    /// store once it has been signed with it. Returns [`Error::BadParam`] if the signer
+    /// has dynamic assertions and the provenance claim is not signed yet.
     #[cfg(feature = "file_io")]
     pub fn to_jumbf(&self, signer: &dyn Signer) -> Result<Vec<u8>> {
+        self.check_dynamic_assertions_signed(signer.dynamic_assertions().len())?;
         self.to_jumbf_internal(signer.reserve_size())
     }
 
     /// Convert this claims store to a JUMBF box.
-    #[cfg(feature = "async_signer")]
+    ///
+    /// Like [`Store::to_jumbf`], fails if the signer has dynamic assertions and the
+    /// provenance claim is not signed yet.
+    #[cfg(all(feature = "file_io", feature = "async_signer"))]
     pub fn to_jumbf_async(&self, signer: &dyn AsyncSigner) -> Result<Vec<u8>> {
+        self.check_dynamic_assertions_signed(signer.dynamic_assertions().len())?;
         self.to_jumbf_internal(signer.reserve_size())
     }
 
+    // dynamic assertions are only generated when signing, so an unsigned store cannot have them
+    #[cfg(feature = "file_io")]
+    fn check_dynamic_assertions_signed(&self, dynamic_assertions: usize) -> Result<()> {
+        let unsigned = self
+            .provenance_claim()
+            .map_or(false, |pc| pc.signature_val().is_empty());
+        if dynamic_assertions > 0 && unsigned {
+            return Err(Error::BadParam(
+                "dynamic assertions are only added when the claim is signed".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Write this claims store as a JUMBF box to `writer`.
+    ///
+    /// The boxes are written to the stream as they are created, one assertion at a time,
+    /// instead of assembling the whole manifest store in memory first. This bounds memory
+    /// use when manifests hold large embedded resources.
     #[cfg(feature = "file_io")]
+    pub fn write_jumbf<W: Write + Seek>(&self, signer: &dyn Signer, writer: &mut W) -> Result<()> {
+        self.check_dynamic_assertions_signed(signer.dynamic_assertions().len())?;
+        self.write_jumbf_with_signature(signer.reserve_size(), None, writer)
+    }
+
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
     fn to_jumbf_internal(&self, min_reserve_size: usize) -> Result<Vec<u8>> {
+        self.to_jumbf_with_signature(min_reserve_size, None)
+    }
+
+    // the size of the JUMBF of this store, found without writing it to memory
+    #[cfg(feature = "file_io")]
+    fn jumbf_size(&self, min_reserve_size: usize) -> Result<usize> {
+        let mut counter = SizeCounter::default();
+        self.write_jumbf_with_signature(min_reserve_size, None, &mut counter)?;
+        Ok(counter.size() as usize)
+    }
+
+    // Write the store with `signature` for the claim being signed instead of a placeholder
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    fn to_jumbf_with_signature(
+        &self,
+        min_reserve_size: usize,
+        signature: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        let mut mem_box = Cursor::new(Vec::new());
+        self.write_jumbf_with_signature(min_reserve_size, signature, &mut mem_box)?;
+
+        let mem_box = mem_box.into_inner();
+        if mem_box.is_empty() {
+            Err(Error::JumbfCreationError)
+        } else {
+            Ok(mem_box)
+        }
+    }
+
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    fn write_jumbf_with_signature<W: Write + Seek>(
+        &self,
+        min_reserve_size: usize,
+        signature: Option<&[u8]>,
+        writer: &mut W,
+    ) -> Result<()> {
+        let mut jumbf_writer = JumbfStreamWriter::new(writer);
+
         // Create the CAI block.
-        let mut cai_block = Cai::new();
+        jumbf_writer.start_superbox(jumbf::labels::MANIFEST_STORE, Some(CAI_BLOCK_UUID))?;
 
         // Add claims and assertions in this store to the JUMBF store.
         for claim in &self.claims {
-            let label = claim.label();
-
-            let mut cai_store = CAIStore::new(label, claim.update_manifest());
+            match self.manifest_compression {
+                ManifestCompression::None => {
+                    self.write_manifest(&mut jumbf_writer, claim, min_reserve_size, signature)?
+                }
+                ManifestCompression::Brotli { quality } => {
+                    // only the claim being signed is written again with its signature
+                    let padded_size = match claim.signature_val().is_empty() {
+                        true => self.compressed_manifest_size,
+                        false => None,
+                    };
+                    let manifest = self.manifest_bytes(claim, min_reserve_size, signature)?;
+                    let compressed =
+                        compress_manifest(claim.label(), &manifest, quality, padded_size)?;
+                    jumbf_writer.write_box(&compressed)?;
+                }
+            }
+        }
 
-            // Add claim box. Note the order of the boxes are set by the spec
-            let mut cb = CAIClaimBox::new();
+        jumbf_writer.end_superbox()?;
+        jumbf_writer.finish()
+    }
 
-            // Create the CAI assertion store.
-            let mut a_store = CAIAssertionStore::new();
+    // Serialize the manifest of a claim on its own
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    fn manifest_bytes(
+        &self,
+        claim: &Claim,
+        min_reserve_size: usize,
+        signature: Option<&[u8]>,
+    ) -> Result<Vec<u8>> {
+        let mut manifest = Cursor::new(Vec::new());
+        let mut jumbf_writer = JumbfStreamWriter::new(&mut manifest);
+        self.write_manifest(&mut jumbf_writer, claim, min_reserve_size, signature)?;
+        jumbf_writer.finish()?;
+        Ok(manifest.into_inner())
+    }
 
-            // Add assertions to CAI assertion store.
-            let cas = claim.claim_assertion_store();
-            for assertion in cas {
-                Store::add_assertion_to_jumbf_store(&mut a_store, assertion)?;
-            }
+    // Write the JUMBF box of the manifest of a claim
+    #[cfg(any(feature = "file_io", feature = "async_signer"))]
+    fn write_manifest<W: Write + Seek>(
+        &self,
+        jumbf_writer: &mut JumbfStreamWriter<W>,
+        claim: &Claim,
+        min_reserve_size: usize,
+        signature: Option<&[u8]>,
+    ) -> Result<()> {
+        let manifest_uuid = match claim.update_manifest() {
+            true => CAI_UPDATE_MANIFEST_UUID,
+            false => CAI_STORE_UUID,
+        };
+        jumbf_writer.start_superbox(claim.label(), Some(manifest_uuid))?;
 
-            // Add the CAI assertion store to the CAI store.
-            cai_store.add_box(Box::new(a_store));
+        // Write the CAI assertion store, one assertion at a time.
+        // Note the order of the boxes are set by the spec
+        jumbf_writer.start_superbox(jumbf::labels::ASSERTIONS, Some(CAI_ASSERTION_STORE_UUID))?;
+        for assertion in claim.claim_assertion_store() {
+            jumbf_writer.write_box(Store::assertion_box(assertion)?.as_ref())?;
+        }
+        jumbf_writer.end_superbox()?;
+
+        // Add the Claim json
+        let mut cb = CAIClaimBox::new_with_label(claim.claim_version().box_label());
+        let claim_cbor_bytes = claim.data()?;
+        let c_cbor = JUMBFCBORContentBox::new(claim_cbor_bytes);
+        cb.add_claim(Box::new(c_cbor));
+        jumbf_writer.write_box(&cb)?;
+
+        // Create a signature and add placeholder data to the CAI store.
+        let mut sigb = CAISignatureBox::new();
+        let signed_data = match (claim.signature_val().is_empty(), signature) {
+            (false, _) => claim.signature_val().clone(), // existing claims have sig values
+            (true, Some(sig)) => sig.to_vec(), // the new sig when the store is written again
+            (true, None) => self.sign_claim_placeholder(claim, min_reserve_size), // empty is the new sig to be replaced
+        };
 
-            // Add the Claim json
-            let claim_cbor_bytes = claim.data()?;
-            let c_cbor = JUMBFCBORContentBox::new(claim_cbor_bytes);
-            cb.add_claim(Box::new(c_cbor));
-            cai_store.add_box(Box::new(cb));
+        let sigc = JUMBFCBORContentBox::new(signed_data);
+        sigb.add_signature(Box::new(sigc));
+        jumbf_writer.write_box(&sigb)?;
 
-            // Create a signature and add placeholder data to the CAI store.
-            let mut sigb = CAISignatureBox::new();
-            let signed_data = match claim.signature_val().is_empty() {
-                false => claim.signature_val().clone(), // existing claims have sig values
-                true => self.sign_claim_placeholder(claim, min_reserve_size), // empty is the new sig to be replaced
-            };
+        // add vc_store if needed
+        if !claim.get_verifiable_credentials().is_empty() {
+            // Create VC store.
+            let mut vc_store = CAIVerifiableCredentialStore::new();
 
-            let sigc = JUMBFCBORContentBox::new(signed_data);
-            sigb.add_signature(Box::new(sigc));
-            cai_store.add_box(Box::new(sigb));
-
-            // add vc_store if needed
-            if !claim.get_verifiable_credentials().is_empty() {
-                // Create VC store.
-                let mut vc_store = CAIVerifiableCredentialStore::new();
-
-                // Add assertions to CAI assertion store.
-                let vcs = claim.get_verifiable_credentials();
-                for assertion_data in vcs {
-                    if let AssertionData::Json(j) = assertion_data {
-                        let id = Claim::vc_id(j)?;
-                        let mut json_data = CAIJSONAssertionBox::new(&id);
-                        json_data.add_json(j.as_bytes().to_vec());
-                        vc_store.add_credential(Box::new(json_data));
-                    } else {
-                        return Err(Error::BadParam("VC data must be JSON".to_string()));
-                    }
+            // Add assertions to CAI assertion store.
+            let vcs = claim.get_verifiable_credentials();
+            for assertion_data in vcs {
+                if let AssertionData::Json(j) = assertion_data {
+                    let id = Claim::vc_id(j)?;
+                    let mut json_data = CAIJSONAssertionBox::new(&id);
+                    json_data.add_json(j.as_bytes().to_vec());
+                    vc_store.add_credential(Box::new(json_data));
+                } else {
+                    return Err(Error::BadParam("VC data must be JSON".to_string()));
                 }
-
-                // Add the CAI assertion store to the CAI store.
-                cai_store.add_box(Box::new(vc_store));
             }
 
-            // Finally add the completed CAI store into the CAI block.
-            cai_block.add_box(Box::new(cai_store));
+            jumbf_writer.write_box(&vc_store)?;
         }
 
-        // Write it to memory.
-        let mut mem_box: Vec<u8> = Vec::new();
-        cai_block.write_box(&mut mem_box)?;
-
-        if mem_box.is_empty() {
-            Err(Error::JumbfCreationError)
-        } else {
-            Ok(mem_box)
+        // add databox store if needed, one databox at a time
+        if !claim.databox_store().is_empty() {
+            jumbf_writer.start_superbox(jumbf::labels::DATABOXES, Some(CAI_DATABOX_STORE_UUID))?;
+            for databox in claim.databox_store() {
+                let mut cbor_data = CAICBORAssertionBox::new(&databox.label());
+                cbor_data.add_cbor(databox.assertion().data().to_vec());
+                jumbf_writer.write_box(&cbor_data)?;
+            }
+            jumbf_writer.end_superbox()?;
         }
+
+        jumbf_writer.end_superbox()
     }
 
     fn manifest_map<'a>(sb: &'a JUMBFSuperBox) -> Result<HashMap<String, ManifestInfo<'a>>> {
@@ -668,13 +1044,47 @@ impl Store {
     }
 
     pub fn from_jumbf(buffer: &[u8], validation_log: &mut impl StatusTracker) -> Result<Store> {
+        Store::from_jumbf_with_limits(buffer, &ResourceLimits::default(), validation_log)
+    }
+
+    /// Load a store from JUMBF, failing if it exceeds any of the resource limits
+    #[instrument(skip_all, fields(len = buffer.len()))]
+    pub fn from_jumbf_with_limits(
+        buffer: &[u8],
+        limits: &ResourceLimits,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Store> {
+        if buffer.len() > limits.max_manifest_size {
+            return Err(Store::resource_limit_exceeded(
+                "JUMBF",
+                format!(
+                    "manifest store of {} bytes exceeds the maximum of {}",
+                    buffer.len(),
+                    limits.max_manifest_size
+                ),
+                validation_log,
+            ));
+        }
+
         let mut store = Store::new();
 
         // setup a cursor for reading the buffer...
         let mut buf_reader = Cursor::new(buffer);
 
-        // this loads up all the boxes...
-        let super_box = BoxReader::read_super_box(&mut buf_reader)?;
+        // this loads up all the boxes, stopping at the first exceeded limit...
+        let limit_exceeded = |e: Error, validation_log: &mut _| match e {
+            Error::ResourceLimitExceeded(limit)
+            | Error::JumbfParseError(JumbfParseError::ResourceLimitExceeded(limit)) => {
+                Store::resource_limit_exceeded("JUMBF", limit, validation_log)
+            }
+            e => e,
+        };
+        let mut super_box = BoxReader::read_super_box_with_limits(&mut buf_reader, limits)
+            .map_err(|e| limit_exceeded(e.into(), validation_log))?;
+
+        // compressed manifests are expanded so they are read like any other manifest
+        expand_compressed_manifests(&mut super_box, limits)
+            .map_err(|e| limit_exceeded(e, validation_log))?;
 
         // this loads up all the boxes...
         let cai_block = Cai::from(super_box);
@@ -724,7 +1134,7 @@ impl Store {
                             .error(Error::InvalidClaim(
                                 InvalidClaimError::C2paMultipleClaimBoxes,
                             ))
-                            .validation_status(validation_status::CLAIM_MULTIPLE);
+                            .validation_status(ValidationStatusCode::ClaimMultiple);
                     validation_log.log(
                         log_item,
                         Some(Error::InvalidClaim(
@@ -841,7 +1251,11 @@ impl Store {
             let cbor_box = claim_superbox
                 .data_box_as_cbor_box(0)
                 .ok_or(Error::JumbfBoxNotFound)?;
-            let mut claim = Claim::from_data(&cai_store_desc_box.label(), cbor_box.cbor())?;
+            let mut claim = Claim::from_data_with_version(
+                &cai_store_desc_box.label(),
+                cbor_box.cbor(),
+                ClaimVersion::from_box_label(&claim_box_ver),
+            )?;
 
             // set the  type of manifest
             claim.set_update_manifest(is_update_manifest);
@@ -921,13 +1335,109 @@ impl Store {
                 }
             }
 
+            // load databox store if available
+            if let Some(mi) = manifest_boxes.get(CAI_DATABOX_STORE_UUID) {
+                let databox_store = mi.sbox;
+                for idx in 0..databox_store.data_box_count() {
+                    let databox_box = databox_store
+                        .data_box_as_superbox(idx)
+                        .ok_or(Error::JumbfBoxNotFound)?;
+                    let cbor_box = databox_box
+                        .data_box_as_cbor_box(0)
+                        .ok_or(Error::JumbfBoxNotFound)?;
+                    let label = databox_box.desc_box().label();
+
+                    let (raw_label, instance) = Claim::assertion_label_from_link(&label);
+                    let assertion = Assertion::from_data_cbor(&raw_label, cbor_box.cbor());
+                    let hash = Claim::calc_box_hash(&label, &assertion, None, claim.alg())?;
+                    let alg = claim.alg().to_string();
+                    claim.put_databox_store(ClaimAssertion::new(
+                        assertion, instance, &hash, &alg, None,
+                    ));
+                }
+            }
+
             // add claim to store
             store.insert_restored_claim(cai_store_desc_box.label(), claim);
         }
 
+        // let claims know which of their assertions were redacted by later claims
+        let redactions: Vec<String> = store
+            .claims
+            .iter()
+            .filter_map(|c| c.redactions())
+            .flatten()
+            .cloned()
+            .collect();
+        for uri in redactions {
+            if let Some(claim) = jumbf::labels::manifest_label_from_uri(&uri)
+                .and_then(|label| store.get_claim_mut(&label))
+            {
+                claim.add_redaction_by_other(&uri);
+            }
+        }
+
+        let depth = store.ingredient_depth();
+        if depth > limits.max_ingredient_depth {
+            return Err(Store::resource_limit_exceeded(
+                &store.provenance_label().unwrap_or_default(),
+                format!(
+                    "ingredient depth of {} exceeds the maximum of {}",
+                    depth, limits.max_ingredient_depth
+                ),
+                validation_log,
+            ));
+        }
+
         Ok(store)
     }
 
+    // log a resource limit being exceeded and return the error, the store is never
+    // loaded so this stops validation whatever the tracker
+    fn resource_limit_exceeded(
+        label: &str,
+        limit: String,
+        validation_log: &mut impl StatusTracker,
+    ) -> Error {
+        let log_item = log_item!(label, "resource limit exceeded", "from_jumbf")
+            .error(Error::ResourceLimitExceeded(limit.clone()))
+            .validation_status(ValidationStatusCode::ResourceLimitExceeded);
+        validation_log.log_silent(log_item);
+        Error::ResourceLimitExceeded(limit)
+    }
+
+    // the depth of the ingredient tree below the active manifest, ingredients
+    // referenced more than once are only counted at their shallowest depth
+    fn ingredient_depth(&self) -> usize {
+        let mut level: Vec<&Claim> = self.provenance_claim().into_iter().collect();
+        let mut visited: HashSet<&str> = level.iter().map(|c| c.label()).collect();
+        let mut depth = 0;
+        loop {
+            let mut next = Vec::new();
+            for claim in level {
+                for assertion in claim.ingredient_assertions() {
+                    let c2pa_manifest = match Ingredient::from_assertion(&assertion) {
+                        Ok(ingredient) => ingredient.c2pa_manifest,
+                        Err(_) => None,
+                    };
+                    let ingredient_claim = c2pa_manifest
+                        .as_ref()
+                        .and_then(|c2pa_manifest| self.resolve_ingredient(c2pa_manifest));
+                    if let Some(ingredient_claim) = ingredient_claim {
+                        if visited.insert(ingredient_claim.label()) {
+                            next.push(ingredient_claim);
+                        }
+                    }
+                }
+            }
+            if next.is_empty() {
+                return depth;
+            }
+            depth += 1;
+            level = next;
+        }
+    }
+
     // Get the store label from jumbf path
     pub fn manifest_label_from_path(claim_path: &str) -> String {
         if let Some(s) = jumbf::labels::manifest_label_from_uri(claim_path) {
@@ -966,7 +1476,7 @@ impl Store {
                     "verify_store"
                 )
                 .error(Error::ProvenanceMissing)
-                .validation_status(validation_status::CLAIM_MISSING);
+                .validation_status(ValidationStatusCode::ClaimMissing);
                 validation_log.log(log_item, Some(Error::ProvenanceMissing))?;
 
                 return Err(Error::ProvenanceMissing);
@@ -976,68 +1486,436 @@ impl Store {
         Ok(claim)
     }
 
-    // wake the ingredients and validate
-    fn ingredient_checks(
+    // verify the manifest with `label` on its own, it is not bound to the asset being read
+    // so only the presence of its hard bindings is checked
+    fn manifest_checks(
         store: &Store,
-        claim: &Claim,
-        asset_bytes: &[u8],
+        label: &str,
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
-        let mut num_parent_ofs = 0;
+        let claim = match store.get_claim(label) {
+            Some(c) => c,
+            None => {
+                let log_item = log_item!(label, "could not find manifest", "manifest_checks")
+                    .error(Error::ClaimVerification(format!(
+                        "manifest: {} is missing",
+                        label
+                    )))
+                    .validation_status(ValidationStatusCode::ClaimMissing);
+                validation_log.log_silent(log_item);
 
-        // walk the ingredients
-        for i in claim.ingredient_assertions() {
-            let ingredient_assertion = Ingredient::from_assertion(&i)?;
+                return Err(Error::ClaimVerification(format!(
+                    "manifest: {} is missing",
+                    label
+                )));
+            }
+        };
 
-            // is this an ingredient
-            if let Some(ref c2pa_manifest) = &ingredient_assertion.c2pa_manifest {
-                let label = Store::manifest_label_from_path(&c2pa_manifest.url());
+        let cert_check = CertCheckOptions::from(&store.verify_options);
+        Claim::verify_claim_without_asset(claim, true, cert_check, validation_log)?;
 
-                // check for parentOf relationships
-                if ingredient_assertion.relationship == Relationship::ParentOf {
-                    num_parent_ofs += 1;
-                }
+        #[cfg(feature = "file_io")]
+        Store::timestamp_checks(store, claim, validation_log)?;
+        #[cfg(feature = "file_io")]
+        Store::trust_checks(store, claim, validation_log)?;
+        #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+        Store::revocation_checks(store, claim, validation_log)?;
 
-                if let Some(ingredient) = store.get_claim(&label) {
-                    let alg = match c2pa_manifest.alg() {
-                        Some(a) => a,
-                        None => ingredient.alg().to_owned(),
-                    };
-                    if !verify_by_alg(&alg, &c2pa_manifest.hash(), &ingredient.data()?, None) {
+        Store::vc_checks(store, claim, validation_log);
+        Store::redaction_checks(store, claim, validation_log)
+    }
+
+    // verify the proofs of the verifiable credentials in a claim if a verifier is configured
+    fn vc_checks(store: &Store, claim: &Claim, validation_log: &mut impl StatusTracker) {
+        let verifier = match store.verify_options.credential_verifier.as_ref() {
+            Some(verifier) => verifier,
+            None => return,
+        };
+
+        // each credential is checked on its own, a bad one does not stop the others
+        for vc in claim.get_verifiable_credentials() {
+            if let AssertionData::Json(vc_json) = vc {
+                let id = match Claim::vc_id(vc_json) {
+                    Ok(id) => id,
+                    Err(e) => {
                         let log_item = log_item!(
-                            &c2pa_manifest.url(),
-                            "ingredient hash incorrect",
-                            "ingredient_checks"
+                            claim.label(),
+                            "verifiable credential has no subject id",
+                            "vc_checks"
                         )
-                        .error(Error::HashMismatch(
-                            "ingredient hash does not match found ingredient".to_string(),
-                        ))
-                        .validation_status(validation_status::INGREDIENT_HASHEDURI_MISMATCH);
-                        validation_log.log(
-                            log_item,
-                            Some(Error::HashMismatch(
-                                "ingredient hash does not match found ingredient".to_string(),
-                            )),
-                        )?;
+                        .error(e)
+                        .validation_status(ValidationStatusCode::VcProofUnverified);
+                        validation_log.log_silent(log_item);
+                        continue;
+                    }
+                };
+                let vc_uri = jumbf::labels::to_verifiable_credential_uri(claim.label(), &id);
+
+                match verifier.verify(vc_json) {
+                    Ok(()) => {
+                        let log_item =
+                            log_item!(&vc_uri, "verifiable credential proof valid", "vc_checks")
+                                .validation_status(ValidationStatusCode::VcProofValidated);
+                        validation_log.log_silent(log_item);
+                    }
+                    Err(Error::VerifiableCredentialProofMismatch) => {
+                        let log_item = log_item!(
+                            &vc_uri,
+                            "verifiable credential proof does not match",
+                            "vc_checks"
+                        )
+                        .error(Error::VerifiableCredentialProofMismatch)
+                        .validation_status(ValidationStatusCode::VcProofMismatch);
+                        validation_log.log_silent(log_item);
+                    }
+                    Err(e) => {
+                        // the proof could not be checked, this does not stop validation
+                        let log_item = log_item!(
+                            &vc_uri,
+                            "verifiable credential proof could not be verified",
+                            "vc_checks"
+                        )
+                        .error(e)
+                        .validation_status(ValidationStatusCode::VcProofUnverified);
+                        validation_log.log_silent(log_item);
                     }
+                }
+            }
+        }
+    }
 
-                    // make sure
-                    // verify the ingredient claim
-                    Claim::verify_claim(ingredient, asset_bytes, false, validation_log)?;
-                } else {
-                    let log_item = log_item!(
-                        &c2pa_manifest.url(),
-                        "ingredient not found",
-                        "ingredient_checks"
-                    )
-                    .error(Error::ClaimVerification(format!(
-                        "ingredient: {} is missing",
-                        label
-                    )))
-                    .validation_status(validation_status::CLAIM_MISSING);
-                    validation_log.log(
-                        log_item,
-                        Some(Error::ClaimVerification(format!(
+    // check that assertions redacted by a claim were removed from the ingredient manifests
+    fn redaction_checks(
+        store: &Store,
+        claim: &Claim,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        for uri in claim.redactions().into_iter().flatten() {
+            let ingredient = match jumbf::labels::manifest_label_from_uri(uri)
+                .and_then(|label| store.get_claim(&label))
+            {
+                Some(ingredient) => ingredient,
+                None => continue, // self redactions and missing manifests are reported elsewhere
+            };
+
+            let (label, instance) = Claim::assertion_label_from_link(uri);
+            if ingredient.get_claim_assertion(&label, instance).is_some() {
+                let log_item =
+                    log_item!(uri, "redacted assertion still present", "redaction_checks")
+                        .error(Error::AssertionInvalidRedaction)
+                        .validation_status(ValidationStatusCode::AssertionNotRedacted);
+                validation_log.log(log_item, Some(Error::AssertionInvalidRedaction))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // check the time-stamp of the claim signature against the trusted TSA certificates
+    #[cfg(feature = "file_io")]
+    fn timestamp_checks(
+        store: &Store,
+        claim: &Claim,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        let trust_anchors = &store.verify_options.tsa_trust_anchors;
+        if !store.verify_options.verify_trust || trust_anchors.is_empty() {
+            return Ok(());
+        }
+
+        check_timestamp_trust(
+            claim.signature_val(),
+            &claim.data()?,
+            trust_anchors,
+            validation_log,
+        )
+    }
+
+    // check the signing certificate of the claim signature against the trust configuration
+    #[cfg(feature = "file_io")]
+    fn trust_checks(
+        store: &Store,
+        claim: &Claim,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        let trust_config = match &store.verify_options.trust_config {
+            Some(trust_config) if store.verify_options.verify_trust => trust_config,
+            _ => return Ok(()),
+        };
+
+        check_signing_trust(
+            claim.signature_val(),
+            &claim.data()?,
+            trust_config,
+            CertCheckOptions::from(&store.verify_options),
+            validation_log,
+        )
+    }
+
+    // check the OCSP status of the signing certificate of the claim signature
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    fn revocation_checks(
+        store: &Store,
+        claim: &Claim,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        if !store.verify_options.ocsp_fetch {
+            return Ok(());
+        }
+
+        check_signing_revocation(
+            claim.signature_val(),
+            &claim.data()?,
+            store.verify_options.ocsp_fetcher().as_ref(),
+            CertCheckOptions::from(&store.verify_options),
+            validation_log,
+        )
+    }
+
+    // check soft binding assertions against the asset using the registered algorithms.
+    // A soft binding that does not match is informational, the asset is bound by its hashes.
+    fn soft_binding_checks(
+        store: &Store,
+        claim: &Claim,
+        asset_bytes: &[u8],
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        if store.verify_options.soft_bindings.is_empty() {
+            return Ok(());
+        }
+
+        let checks = binding_check::soft_binding_checks(claim, asset_bytes, &store.verify_options)?;
+        for check in checks {
+            let log_item = match check.state {
+                BindingState::Survived => log_item!(
+                    &check.assertion_uri,
+                    "soft binding matches",
+                    "soft_binding_checks"
+                )
+                .validation_status(ValidationStatusCode::SoftBindingMatch),
+                BindingState::Broken(reason) => log_item!(
+                    &check.assertion_uri,
+                    format!("soft binding does not match asset: {}", reason),
+                    "soft_binding_checks"
+                )
+                .validation_status(ValidationStatusCode::SoftBindingMismatch),
+                // unknown algorithms can not be checked
+                BindingState::Unchecked(_) => continue,
+            };
+            validation_log.log_silent(log_item);
+        }
+
+        Ok(())
+    }
+
+    // check the assertions of the claim that have a registered schema
+    fn custom_assertion_checks(
+        store: &Store,
+        claim: &Claim,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        let registry = &store.verify_options.custom_assertions;
+        if registry.is_empty() {
+            return Ok(());
+        }
+
+        for claim_assertion in claim.claim_assertion_store() {
+            let assertion = claim_assertion.assertion();
+            let schema = match registry.get(&assertion.label_root()) {
+                Some(schema) => schema,
+                None => continue,
+            };
+
+            // undecodable data does not match the schema either
+            let result = match assertion.as_json_object() {
+                Ok(data) => schema.validate(&data).map_err(|e| match e {
+                    Error::AssertionSchemaMismatch(reason) => reason,
+                    e => e.to_string(),
+                }),
+                Err(e) => Err(e.to_string()),
+            };
+
+            if let Err(reason) = result {
+                let assertion_uri =
+                    jumbf::labels::to_assertion_uri(claim.label(), &claim_assertion.label());
+                let log_item = log_item!(
+                    &assertion_uri,
+                    "custom assertion does not match its schema",
+                    "custom_assertion_checks"
+                )
+                .error(Error::AssertionSchemaMismatch(reason.clone()))
+                .validation_status(ValidationStatusCode::AssertionMalformed);
+                validation_log.log(log_item, Some(Error::AssertionSchemaMismatch(reason)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // walk the ingredient tree to the depth set by the ingredient validation policy.
+    // The tree is walked iteratively so deep trees cannot exhaust the stack, each
+    // ingredient is checked once and a reference back to an ancestor is logged as a cycle.
+    fn ingredient_checks(
+        store: &Store,
+        claim: &Claim,
+        asset_bytes: &[u8],
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        let max_depth = store.verify_options.ingredient_validation.max_depth();
+        // ingredients are checked with the certificate options of the caller
+        let cert_check = CertCheckOptions::from(&store.verify_options);
+
+        let mut visited = HashSet::new();
+        visited.insert(claim.label());
+
+        // claims whose ingredients are checked next, with the labels of their ancestors
+        let mut pending: Vec<(&Claim, Vec<&str>)> = vec![(claim, Vec::new())];
+        while let Some((parent, ancestors)) = pending.pop() {
+            let depth = ancestors.len() + 1;
+            let found = Store::claim_ingredient_checks(
+                store,
+                parent,
+                asset_bytes,
+                depth <= max_depth,
+                validation_log,
+            )?;
+            if depth >= max_depth {
+                continue;
+            }
+
+            let mut path = ancestors;
+            path.push(parent.label());
+            for ingredient in found {
+                if path.contains(&ingredient.label()) {
+                    Store::log_ingredient_cycle(ingredient, validation_log)?;
+                } else if visited.insert(ingredient.label()) {
+                    // an ingredient may be referenced more than once
+                    pending.push((ingredient, path.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // log an ingredient referring back to one of the manifests it is an ingredient of
+    fn log_ingredient_cycle(
+        ingredient: &Claim,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        let log_item = log_item!(&ingredient.uri(), "ingredient cycle", "ingredient_checks")
+            .error(Error::ClaimVerification(format!(
+                "ingredient: {} is its own ancestor",
+                ingredient.label()
+            )))
+            .validation_status(ValidationStatusCode::IngredientCycle);
+        validation_log.log(
+            log_item,
+            Some(Error::ClaimVerification(format!(
+                "ingredient: {} is its own ancestor",
+                ingredient.label()
+            ))),
+        )
+    }
+
+    // report the failures recorded in an ingredient assertion, which are trusted in place
+    // of verifying the ingredient claim, without failing the parent
+    fn log_recorded_statuses(
+        ingredient_assertion: &Ingredient,
+        manifest_url: &str,
+        validation_log: &mut impl StatusTracker,
+    ) {
+        let recorded = ingredient_assertion.validation_status.iter().flatten();
+        for status in recorded.filter(|s| !s.passed() && !s.code().is_informational()) {
+            let log_item = log_item!(
+                status.url().unwrap_or(manifest_url),
+                "recorded ingredient validation status",
+                "ingredient_checks"
+            )
+            .validation_status(status.code().clone());
+            validation_log.log_silent(log_item);
+        }
+    }
+
+    // wake the ingredients of a claim and validate, returning the ingredient claims found
+    fn claim_ingredient_checks<'a>(
+        store: &'a Store,
+        claim: &Claim,
+        asset_bytes: &[u8],
+        verify_claims: bool,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Vec<&'a Claim>> {
+        let mut num_parent_ofs = 0;
+        let mut found = Vec::new();
+
+        // walk the ingredients
+        for i in claim.ingredient_assertions() {
+            let ingredient_assertion = Ingredient::from_assertion(&i)?;
+
+            // is this an ingredient
+            if let Some(ref c2pa_manifest) = &ingredient_assertion.c2pa_manifest {
+                let label = Store::manifest_label_from_path(&c2pa_manifest.url());
+
+                // check for parentOf relationships
+                if ingredient_assertion.relationship == Relationship::ParentOf {
+                    num_parent_ofs += 1;
+                }
+
+                if let Some(ingredient) = store.resolve_ingredient(c2pa_manifest) {
+                    let alg = match c2pa_manifest.alg() {
+                        Some(a) => a,
+                        None => ingredient.alg().to_owned(),
+                    };
+                    if !verify_by_alg(&alg, &c2pa_manifest.hash(), &ingredient.data()?, None) {
+                        let log_item = log_item!(
+                            &c2pa_manifest.url(),
+                            "ingredient hash incorrect",
+                            "ingredient_checks"
+                        )
+                        .error(Error::HashMismatch(
+                            "ingredient hash does not match found ingredient".to_string(),
+                        ))
+                        .validation_status(ValidationStatusCode::IngredientHashedUriMismatch);
+                        validation_log.log(
+                            log_item,
+                            Some(Error::HashMismatch(
+                                "ingredient hash does not match found ingredient".to_string(),
+                            )),
+                        )?;
+                    }
+
+                    // make sure
+                    // verify the ingredient claim
+                    if verify_claims {
+                        Claim::verify_claim(
+                            ingredient,
+                            asset_bytes,
+                            false,
+                            cert_check,
+                            validation_log,
+                        )?;
+                    } else {
+                        Store::log_recorded_statuses(
+                            &ingredient_assertion,
+                            &c2pa_manifest.url(),
+                            validation_log,
+                        );
+                    }
+                    found.push(ingredient);
+                } else {
+                    let log_item = log_item!(
+                        &c2pa_manifest.url(),
+                        "ingredient not found",
+                        "ingredient_checks"
+                    )
+                    .error(Error::ClaimVerification(format!(
+                        "ingredient: {} is missing",
+                        label
+                    )))
+                    .validation_status(ValidationStatusCode::ClaimMissing);
+                    validation_log.log(
+                        log_item,
+                        Some(Error::ClaimVerification(format!(
                             "ingredient: {} is missing",
                             label
                         ))),
@@ -1057,7 +1935,7 @@ impl Store {
                 .error(Error::ClaimVerification(
                     "update manifest must have one parent".to_string(),
                 ))
-                .validation_status(validation_status::MANIFEST_UPDATE_WRONG_PARENTS);
+                .validation_status(ValidationStatusCode::ManifestUpdateWrongParents);
                 validation_log.log(
                     log_item,
                     Some(Error::ClaimVerification(
@@ -1074,7 +1952,7 @@ impl Store {
             .error(Error::ClaimVerification(
                 "ingredient has more than one parent".to_string(),
             ))
-            .validation_status(validation_status::MANIFEST_MULTIPLE_PARENTS);
+            .validation_status(ValidationStatusCode::ManifestMultipleParents);
             validation_log.log(
                 log_item,
                 Some(Error::ClaimVerification(
@@ -1083,16 +1961,66 @@ impl Store {
             )?;
         }
 
-        Ok(())
+        Ok(found)
     }
 
-    // wake the ingredients and validate
+    // walk the ingredient tree to the depth set by the ingredient validation policy.
+    // The tree is walked iteratively so deep trees cannot exhaust the stack, each
+    // ingredient is checked once and a reference back to an ancestor is logged as a cycle.
     async fn ingredient_checks_async(
         store: &Store,
         claim: &Claim,
         asset_bytes: &[u8],
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
+        let max_depth = store.verify_options.ingredient_validation.max_depth();
+        // ingredients are checked with the certificate options of the caller
+        let cert_check = CertCheckOptions::from(&store.verify_options);
+
+        let mut visited = HashSet::new();
+        visited.insert(claim.label());
+
+        // claims whose ingredients are checked next, with the labels of their ancestors
+        let mut pending: Vec<(&Claim, Vec<&str>)> = vec![(claim, Vec::new())];
+        while let Some((parent, ancestors)) = pending.pop() {
+            let depth = ancestors.len() + 1;
+            let found = Store::claim_ingredient_checks_async(
+                store,
+                parent,
+                asset_bytes,
+                depth <= max_depth,
+                validation_log,
+            )
+            .await?;
+            if depth >= max_depth {
+                continue;
+            }
+
+            let mut path = ancestors;
+            path.push(parent.label());
+            for ingredient in found {
+                if path.contains(&ingredient.label()) {
+                    Store::log_ingredient_cycle(ingredient, validation_log)?;
+                } else if visited.insert(ingredient.label()) {
+                    // an ingredient may be referenced more than once
+                    pending.push((ingredient, path.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // wake the ingredients of a claim and validate, returning the ingredient claims found
+    async fn claim_ingredient_checks_async<'a>(
+        store: &'a Store,
+        claim: &Claim,
+        asset_bytes: &[u8],
+        verify_claims: bool,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Vec<&'a Claim>> {
+        let mut found = Vec::new();
+
         // walk the ingredients
         for i in claim.ingredient_assertions() {
             let ingredient_assertion = Ingredient::from_assertion(&i)?;
@@ -1101,7 +2029,7 @@ impl Store {
             if let Some(ref c2pa_manifest) = &ingredient_assertion.c2pa_manifest {
                 let label = Store::manifest_label_from_path(&c2pa_manifest.url());
 
-                if let Some(ingredient) = store.get_claim(&label) {
+                if let Some(ingredient) = store.resolve_ingredient(c2pa_manifest) {
                     if !verify_by_alg(
                         ingredient.alg(),
                         &c2pa_manifest.hash(),
@@ -1116,7 +2044,7 @@ impl Store {
                         .error(Error::HashMismatch(
                             "ingredient hash does not match found ingredient".to_string(),
                         ))
-                        .validation_status(validation_status::INGREDIENT_HASHEDURI_MISMATCH);
+                        .validation_status(ValidationStatusCode::IngredientHashedUriMismatch);
                         validation_log.log(
                             log_item,
                             Some(Error::HashMismatch(
@@ -1125,8 +2053,23 @@ impl Store {
                         )?;
                     }
                     // verify the ingredient claim
-                    Claim::verify_claim_async(ingredient, asset_bytes, false, validation_log)
+                    if verify_claims {
+                        Claim::verify_claim_async(
+                            ingredient,
+                            asset_bytes,
+                            false,
+                            cert_check,
+                            validation_log,
+                        )
                         .await?;
+                    } else {
+                        Store::log_recorded_statuses(
+                            &ingredient_assertion,
+                            &c2pa_manifest.url(),
+                            validation_log,
+                        );
+                    }
+                    found.push(ingredient);
                 } else {
                     let log_item = log_item!(
                         &c2pa_manifest.url(),
@@ -1137,7 +2080,7 @@ impl Store {
                         "ingredient: {} is missing",
                         label
                     )))
-                    .validation_status(validation_status::CLAIM_MISSING);
+                    .validation_status(ValidationStatusCode::ClaimMissing);
                     validation_log.log(
                         log_item,
                         Some(Error::ClaimVerification(format!(
@@ -1149,7 +2092,7 @@ impl Store {
             }
         }
 
-        Ok(())
+        Ok(found)
     }
 
     /// Verify Store
@@ -1157,18 +2100,47 @@ impl Store {
     /// xmp_str: String containing entire XMP block of the asset
     /// asset_bytes: bytes of the asset to be verified
     /// validation_log: If present all found errors are logged and returned, other wise first error causes exit and is returned  
+    #[instrument(skip_all, fields(manifest = ?store.provenance_label()))]
     pub async fn verify_store_async(
         store: &Store,
         xmp_opt: Option<String>,
         asset_bytes: &[u8],
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
+        match &store.verify_options.scope {
+            ValidationScope::Manifest(label) => {
+                return Store::manifest_checks(store, label, validation_log)
+            }
+            ValidationScope::HardBindings => {
+                let claim = Store::provenance_checks(store, xmp_opt, validation_log)?;
+                return Claim::verify_hard_bindings(claim, Some(asset_bytes), validation_log);
+            }
+            ValidationScope::Full | ValidationScope::ActiveManifest => (),
+        }
+
         let claim = Store::provenance_checks(store, xmp_opt, validation_log)?;
 
         // verify the provenance claim
-        Claim::verify_claim_async(claim, asset_bytes, true, validation_log).await?;
+        let cert_check = CertCheckOptions::from(&store.verify_options);
+        Claim::verify_claim_async(claim, asset_bytes, true, cert_check, validation_log).await?;
 
-        Store::ingredient_checks_async(store, claim, asset_bytes, validation_log).await?;
+        #[cfg(feature = "file_io")]
+        Store::timestamp_checks(store, claim, validation_log)?;
+        #[cfg(feature = "file_io")]
+        Store::trust_checks(store, claim, validation_log)?;
+        #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+        Store::revocation_checks(store, claim, validation_log)?;
+
+        Store::vc_checks(store, claim, validation_log);
+
+        Store::redaction_checks(store, claim, validation_log)?;
+
+        Store::soft_binding_checks(store, claim, asset_bytes, validation_log)?;
+        Store::custom_assertion_checks(store, claim, validation_log)?;
+
+        if store.verify_options.scope == ValidationScope::Full {
+            Store::ingredient_checks_async(store, claim, asset_bytes, validation_log).await?;
+        }
 
         Ok(())
     }
@@ -1184,14 +2156,105 @@ impl Store {
         asset_bytes: &[u8],
         validation_log: &mut impl StatusTracker,
     ) -> Result<()> {
+        Store::verify_store_with_progress(
+            store,
+            xmp_opt,
+            asset_bytes,
+            validation_log,
+            &ProgressMonitor::none(),
+        )
+    }
+
+    // verify store, reporting progress and checking for cancellation between stages
+    #[instrument(skip_all, fields(manifest = ?store.provenance_label()))]
+    pub(crate) fn verify_store_with_progress(
+        store: &Store,
+        xmp_opt: Option<String>,
+        asset_bytes: &[u8],
+        validation_log: &mut impl StatusTracker,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        let start = instant::Instant::now();
+        let result =
+            Store::verify_store_stages(store, xmp_opt, asset_bytes, validation_log, monitor);
+        metrics().verify_completed(start.elapsed(), result.is_ok());
+
+        result
+    }
+
+    fn verify_store_stages(
+        store: &Store,
+        xmp_opt: Option<String>,
+        asset_bytes: &[u8],
+        validation_log: &mut impl StatusTracker,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        let total = asset_bytes.len() as u64;
+        monitor.update(ProgressPhase::Verifying, 0, total)?;
+
+        match &store.verify_options.scope {
+            ValidationScope::Manifest(label) => {
+                Store::manifest_checks(store, label, validation_log)?;
+                return monitor.update(ProgressPhase::Verifying, total, total);
+            }
+            ValidationScope::HardBindings => {
+                let claim = Store::provenance_checks(store, xmp_opt, validation_log)?;
+                if store.asset_stripped {
+                    Claim::verify_hard_bindings(claim, None, validation_log)?;
+                    Claim::verify_binding_hashes(claim, asset_bytes, true, validation_log)?;
+                } else {
+                    Claim::verify_hard_bindings(claim, Some(asset_bytes), validation_log)?;
+                }
+                return monitor.update(ProgressPhase::Verifying, total, total);
+            }
+            ValidationScope::Full | ValidationScope::ActiveManifest => (),
+        }
+
+        // progress advances in proportion to the asset size as each step completes
+        const STEPS: u64 = 8;
+        let mut steps_done = 0;
+        let mut step_done = || {
+            steps_done += 1;
+            monitor.update(ProgressPhase::Verifying, total * steps_done / STEPS, total)
+        };
+
         let claim = Store::provenance_checks(store, xmp_opt, validation_log)?;
+        step_done()?;
 
         // verify the provenance claim
-        Claim::verify_claim(claim, asset_bytes, true, validation_log)?;
+        let cert_check = CertCheckOptions::from(&store.verify_options);
+        if store.asset_stripped {
+            Claim::verify_claim_without_asset(claim, true, cert_check, validation_log)?;
+            Claim::verify_binding_hashes(claim, asset_bytes, true, validation_log)?;
+        } else {
+            Claim::verify_claim(claim, asset_bytes, true, cert_check, validation_log)?;
+        }
+        step_done()?;
 
-        Store::ingredient_checks(store, claim, asset_bytes, validation_log)?;
+        #[cfg(feature = "file_io")]
+        Store::timestamp_checks(store, claim, validation_log)?;
+        #[cfg(feature = "file_io")]
+        Store::trust_checks(store, claim, validation_log)?;
+        #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+        Store::revocation_checks(store, claim, validation_log)?;
+        step_done()?;
 
-        Ok(())
+        Store::vc_checks(store, claim, validation_log);
+        step_done()?;
+
+        Store::redaction_checks(store, claim, validation_log)?;
+        step_done()?;
+
+        Store::soft_binding_checks(store, claim, asset_bytes, validation_log)?;
+        step_done()?;
+
+        Store::custom_assertion_checks(store, claim, validation_log)?;
+        step_done()?;
+
+        if store.verify_options.scope == ValidationScope::Full {
+            Store::ingredient_checks(store, claim, asset_bytes, validation_log)?;
+        }
+        step_done()
     }
 
     // generate a list of AssetHashes based on the location of objects in the file
@@ -1201,6 +2264,7 @@ impl Store {
         alg: &str,
         block_locations: &mut Vec<HashObjectPositions>,
         calc_hashes: bool,
+        options: &HashingOptions,
     ) -> Result<Vec<DataHash>> {
         if block_locations.is_empty() {
             return Err(Error::BadParam(
@@ -1245,7 +2309,7 @@ impl Store {
             let mut dh = DataHash::new("jumbf manifest", alg, None);
             dh.add_exclusion(Exclusion::new(block_start, block_end - block_start));
             if calc_hashes {
-                dh.gen_hash(asset_path)?;
+                dh.gen_hash_with_options(asset_path, options)?;
             } else {
                 match alg {
                     "sha256" => dh.set_hash([0u8; 32].to_vec()),
@@ -1260,6 +2324,29 @@ impl Store {
         Ok(hashes)
     }
 
+    /// Estimate the size of the JUMBF manifest store that `save_to_asset` would embed
+    /// when the provenance claim is signed with a signature of `reserve_size` bytes.
+    /// The store is consumed since placeholder data hashes are added to the provenance claim.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn estimate_jumbf_size(mut self, reserve_size: usize) -> Result<usize> {
+        let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+
+        // update manifests do not carry data hashes
+        if !pc.update_manifest() {
+            let alg = pc.alg().to_owned();
+            let mut dh = DataHash::new("jumbf manifest", &alg, None);
+            // large values so the encoded exclusion is never smaller than the real one
+            dh.add_exclusion(Exclusion::new(u32::MAX as usize, u32::MAX as usize));
+            dh.set_hash(hash_by_alg(&alg, &[], None));
+            // same padding start_save reserves for cbor expansion
+            dh.add_padding(vec![0x0; 10]);
+            pc.add_assertion(&dh)?;
+        }
+
+        self.compressed_manifest_size = self.compressed_manifest_reserve(reserve_size, 0)?;
+        self.jumbf_size(reserve_size)
+    }
+
     /// Embed the claims store as jumbf into an asset. Updates XMP with provenance record.
     #[cfg(feature = "file_io")]
     pub fn save_to_asset(
@@ -1268,46 +2355,442 @@ impl Store {
         signer: &dyn Signer,
         output_path: &Path,
     ) -> Result<()> {
-        let jumbf_bytes = self.start_save(asset_path, output_path, signer.reserve_size())?;
+        self.save_to_asset_with_progress(asset_path, signer, output_path, &ProgressMonitor::none())
+    }
+
+    /// Sign the claims store as a standalone manifest store for the asset at `asset_path`,
+    /// returning the JUMBF of a `.c2pa` archive.
+    ///
+    /// The asset is not changed, so its data hash covers the whole asset.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn save_to_archive(
+        &mut self,
+        asset_path: &Path,
+        signer: &dyn Signer,
+    ) -> Result<Vec<u8>> {
+        let hashing_options = self.hashing_options.clone();
+        let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+
+        let mut dh = DataHash::new("jumbf manifest", pc.alg(), None);
+        dh.gen_hash_with_options(asset_path, &hashing_options)?;
+        pc.add_assertion(&dh)?;
+
+        self.sign_standalone(signer)
+    }
+
+    /// Sign the provenance claim as a standalone manifest store bound to the members of a
+    /// collection, such as the segments of a live recording, and return its JUMBF.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn save_to_collection(
+        &mut self,
+        collection_hash: &CollectionHash,
+        signer: &dyn Signer,
+    ) -> Result<Vec<u8>> {
+        let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+        pc.add_assertion(collection_hash)?;
+
+        self.sign_standalone(signer)
+    }
+
+    // sign the provenance claim of a store that is not embedded in an asset
+    #[cfg(feature = "file_io")]
+    fn sign_standalone(&mut self, signer: &dyn Signer) -> Result<Vec<u8>> {
+        let signer = operation_signer(signer)?;
+
+        // the hard binding is already final, so the dynamic assertions can be generated at once
+        let dynamic_assertions = signer.dynamic_assertions();
+        let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+        let dynamic_labels = Store::reserve_dynamic_assertions(pc, &dynamic_assertions)?;
+        Store::generate_dynamic_assertions(pc, &dynamic_assertions, &dynamic_labels)?;
 
         let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
         let sig = self.sign_claim(pc, signer, signer.reserve_size())?;
-        let sig_placeholder = self.sign_claim_placeholder(pc, signer.reserve_size());
+        let pc_mut = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+        pc_mut.set_signature_val(sig);
+
+        self.to_jumbf_internal(signer.reserve_size())
+    }
+
+    /// Sign the claims store with an async signer as a standalone manifest store for
+    /// `asset`, returning the JUMBF of a `.c2pa` archive.
+    ///
+    /// The asset is not changed, so its data hash covers the whole asset. Only memory is
+    /// used, so this works without file access, such as in browsers.
+    #[cfg(feature = "async_signer")]
+    pub(crate) async fn save_to_archive_async(
+        &mut self,
+        asset: &[u8],
+        signer: &dyn AsyncSigner,
+    ) -> Result<Vec<u8>> {
+        // dynamic assertions are generated by the file based signing paths
+        if !signer.dynamic_assertions().is_empty() {
+            return Err(Error::BadParam(
+                "dynamic assertions are not supported when signing an archive asynchronously"
+                    .to_string(),
+            ));
+        }
+
+        let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+        let alg = pc.alg().to_owned();
+        let mut dh = DataHash::new("jumbf manifest", &alg, None);
+        dh.set_hash(hash_by_alg(&alg, asset, None));
+        pc.add_assertion(&dh)?;
+
+        let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
+        let sig = self.sign_claim_async(pc, signer).await?;
+        let pc_mut = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+        pc_mut.set_signature_val(sig);
+
+        self.to_jumbf_internal(signer.reserve_size())
+    }
+
+    /// Load a store from the JUMBF of a `.c2pa` archive, verifying it against the asset it describes
+    pub(crate) fn load_from_archive(
+        archive: &[u8],
+        asset: &[u8],
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Store> {
+        let mut store =
+            Store::from_jumbf_with_limits(archive, &options.resource_limits, validation_log)?;
+        store.set_verify_options(options.clone());
+
+        // archives are not embedded, so there is no XMP to check
+        Store::verify_store(&store, None, asset, validation_log)?;
+
+        Ok(store)
+    }
+
+    /// Load a store from the JUMBF of a `.c2pa` archive, verifying its collection hashes
+    /// against the members of the collection it describes
+    pub(crate) fn load_from_collection(
+        archive: &[u8],
+        members: &[(&str, &[u8])],
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Store> {
+        let mut store =
+            Store::from_jumbf_with_limits(archive, &options.resource_limits, validation_log)?;
+        store.set_verify_options(options.clone());
+
+        let claim = Store::provenance_checks(&store, None, validation_log)?;
+        let cert_check = CertCheckOptions::from(&store.verify_options);
+        Claim::verify_claim_without_asset(claim, true, cert_check, validation_log)?;
+
+        // a data hash cannot bind the members, so a collection hash is required
+        if claim.collection_hash_assertions().is_empty() {
+            let log_item = log_item!(
+                &claim.uri(),
+                "claim missing collection hash binding",
+                "load_from_collection"
+            )
+            .error(Error::ClaimMissingHardBinding)
+            .validation_status(ValidationStatusCode::HardBindingsMissing);
+            validation_log.log(log_item, Some(Error::ClaimMissingHardBinding))?;
+        }
+
+        for ch_assertion in claim.collection_hash_assertions() {
+            let ch = CollectionHash::from_assertion(&ch_assertion)?;
+            match ch.verify_parts(members.iter().copied(), Some(claim.alg())) {
+                Ok(()) => {
+                    let log_item = log_item!(
+                        claim.assertion_uri(&ch_assertion.label()),
+                        "collection hash valid",
+                        "load_from_collection"
+                    )
+                    .validation_status(ValidationStatusCode::AssertionCollectionHashMatch);
+                    validation_log.log_silent(log_item);
+                }
+                Err(e) => {
+                    let log_item = log_item!(
+                        claim.assertion_uri(&ch_assertion.label()),
+                        format!("collection hash error: {}", e),
+                        "load_from_collection"
+                    )
+                    .error(Error::HashMismatch(format!(
+                        "Collection hash failure: {}",
+                        e
+                    )))
+                    .validation_status(ValidationStatusCode::AssertionCollectionHashMismatch);
+                    validation_log.log(
+                        log_item,
+                        Some(Error::HashMismatch(format!(
+                            "Collection hash failure: {}",
+                            e
+                        ))),
+                    )?;
+                }
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Transfer the provenance of an asset to a re-encoded rendition of it.
+    ///
+    /// The active manifest of this store, which should already be validated, becomes the
+    /// parent ingredient of a new manifest recording a `c2pa.transcoded` action. The new
+    /// manifest is signed and embedded in the asset at `rendition_path`, written to
+    /// `output_path`, and the store of the output is returned.
+    #[cfg(feature = "file_io")]
+    pub fn rebind(
+        &self,
+        rendition_path: &Path,
+        output_path: &Path,
+        claim_generator: &str,
+        signer: &dyn Signer,
+    ) -> Result<Store> {
+        let pc = self.provenance_claim().ok_or(Error::ProvenanceMissing)?;
+        let pc_label = pc.label().to_owned();
+
+        // the new claim describes the rendition
+        let rendition = crate::Ingredient::from_file_info(rendition_path);
+        let mut claim = Claim::new(claim_generator, None);
+        claim.set_claim_version(pc.claim_version());
+        claim.set_title(Some(rendition.title().to_owned()));
+        claim.format = rendition.format().to_owned();
+        claim.instance_id = rendition.instance_id().to_owned();
+
+        // carry over the original manifests as the parent ingredient
+        claim.add_ingredient_data(&pc_label, self.claims.clone(), None)?;
+        let mut ingredient = Ingredient::new(
+            pc.title().map(String::as_str).unwrap_or("untitled"),
+            pc.format(),
+            pc.instance_id(),
+            None,
+        );
+        ingredient.relationship = Relationship::ParentOf;
+        ingredient.c2pa_manifest = Some(HashedUri::new(
+            jumbf::labels::to_manifest_uri(&pc_label),
+            Some(pc.alg().to_owned()),
+            &pc.hash(),
+        ));
+        let ingredient_uri = claim.add_assertion(&ingredient)?;
+
+        let mut actions = Actions::new();
+        actions.add_action(
+            Action::new(c2pa_action::TRANSCODED).set_ingredients(vec![ingredient_uri])?,
+        );
+        claim.add_assertion(&actions)?;
+
+        let mut store = Store::new();
+        store.hashing_options = self.hashing_options.clone();
+        store.fsync_policy = self.fsync_policy;
+        store.commit_claim(claim)?;
+        store.save_to_asset(rendition_path, signer, output_path)?;
+        Ok(store)
+    }
+
+    /// Embed the claims store as jumbf into an asset, reporting progress to the monitor.
+    /// Returns Error::OperationCancelled if the monitor's token is cancelled.
+    #[cfg(feature = "file_io")]
+    #[instrument(skip_all, fields(asset = %asset_path.display(), output = %output_path.display()))]
+    pub(crate) fn save_to_asset_with_progress(
+        &mut self,
+        asset_path: &Path,
+        signer: &dyn Signer,
+        output_path: &Path,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        self.adjusted_reserve_size = None;
+        let signer = operation_signer(signer)?;
+
+        // the output only replaces the asset once it is complete, so attempts can always be retried
+        let fsync_policy = self.fsync_policy;
+        write_atomically(output_path, fsync_policy, |temp_path| {
+            self.save_to_asset_with_retries(asset_path, signer, temp_path, monitor)?;
+            self.verify_after_sign(temp_path)
+        })
+    }
+
+    // reload and verify a signed output when the options ask for it, so a store
+    // that does not validate never replaces the output
+    #[cfg(feature = "file_io")]
+    fn verify_after_sign(&self, output_path: &Path) -> Result<()> {
+        if !self.verify_options.verify_after_sign {
+            return Ok(());
+        }
+
+        let mut validation_log = OneShotStatusTracker::new();
+        let mut written = load_cai_from_file_with_limits(
+            output_path,
+            &self.verify_options.resource_limits,
+            &mut validation_log,
+        )?;
+        written.set_verify_options(self.verify_options.clone());
+        written.verify_from_path(output_path, &mut validation_log)
+    }
+
+    #[cfg(feature = "file_io")]
+    fn save_to_asset_with_retries(
+        &mut self,
+        asset_path: &Path,
+        signer: &dyn Signer,
+        output_path: &Path,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        // start_save adds the hash assertions, so keep the claim to restore before a retry
+        let original_claim = self.provenance_claim().ok_or(Error::ClaimEncoding)?.clone();
+        let mut reserve_size = signer.reserve_size();
+        let mut retries = 0;
+        loop {
+            match self.save_to_asset_attempt(asset_path, signer, output_path, reserve_size, monitor)
+            {
+                Err(Error::CoseSigboxTooSmall) if retries < MAX_RESERVE_RETRIES => {
+                    let new_size = reserve_size.max(1) * 2;
+                    warn!(
+                        "signature did not fit in {} bytes, retrying with {} bytes",
+                        reserve_size, new_size
+                    );
+                    reserve_size = new_size;
+                    retries += 1;
+                    let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+                    *pc = original_claim.clone();
+                }
+                Ok(()) => {
+                    if reserve_size != signer.reserve_size() {
+                        self.adjusted_reserve_size = Some(reserve_size);
+                    }
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    #[cfg(feature = "file_io")]
+    fn save_to_asset_attempt(
+        &mut self,
+        asset_path: &Path,
+        signer: &dyn Signer,
+        output_path: &Path,
+        reserve_size: usize,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        let jumbf_bytes = self.start_save(
+            asset_path,
+            output_path,
+            reserve_size,
+            &signer.dynamic_assertions(),
+            monitor,
+        )?;
+
+        monitor.update(ProgressPhase::Signing, 0, 0)?;
+        let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
+        let sig = self.sign_claim(pc, signer, reserve_size)?;
+        let sig_placeholder = self.sign_claim_placeholder(pc, reserve_size);
 
+        monitor.update(ProgressPhase::Embedding, 0, jumbf_bytes.len() as u64)?;
         match self.finish_save(jumbf_bytes, output_path, sig, &sig_placeholder) {
             Ok(v) => {
                 // save sig so store is up to date
                 let pc_mut = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+                let len = v.len() as u64;
                 pc_mut.set_signature_val(v);
+                monitor.update(ProgressPhase::Embedding, len, len)?;
                 Ok(())
             }
             Err(e) => Err(e),
         }
     }
 
+    /// Embed the claims store as jumbf into an asset, signed by a remote signing service.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn save_to_asset_remote(
+        &mut self,
+        asset_path: &Path,
+        signer: &dyn RemoteSigner,
+        output_path: &Path,
+    ) -> Result<()> {
+        let fsync_policy = self.fsync_policy;
+        write_atomically(output_path, fsync_policy, |temp_path| {
+            let reserve_size = signer.reserve_size();
+            let jumbf_bytes = self.start_save(
+                asset_path,
+                temp_path,
+                reserve_size,
+                &signer.dynamic_assertions(),
+                &ProgressMonitor::none(),
+            )?;
+
+            let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
+            let sig = self.sign_claim_remote(pc, signer, reserve_size)?;
+            let sig_placeholder = self.sign_claim_placeholder(pc, reserve_size);
+
+            let sig = self.finish_save(jumbf_bytes, temp_path, sig, &sig_placeholder)?;
+            self.verify_after_sign(temp_path)?;
+
+            // save sig so store is up to date
+            let pc_mut = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+            pc_mut.set_signature_val(sig);
+            Ok(())
+        })
+    }
+
     /// Embed the claims store as jumbf into an asset using an async signer. Updates XMP with provenance record.
-    #[cfg(feature = "async_signer")]
+    #[cfg(all(feature = "file_io", feature = "async_signer"))]
     pub async fn save_to_asset_async(
         &mut self,
         asset_path: &Path,
         signer: &dyn AsyncSigner,
         output_path: &Path,
     ) -> Result<()> {
-        let jumbf_bytes = self.start_save(asset_path, output_path, signer.reserve_size())?;
+        self.save_to_asset_async_with_progress(
+            asset_path,
+            signer,
+            output_path,
+            &NoProgress,
+            &CancellationToken::new(),
+        )
+        .await
+    }
+
+    /// Embed the claims store as jumbf into an asset using an async signer, reporting
+    /// progress to `reporter`. Returns Error::OperationCancelled if `cancel` is cancelled.
+    #[cfg(all(feature = "file_io", feature = "async_signer"))]
+    #[instrument(skip_all, fields(asset = %asset_path.display(), output = %output_path.display()))]
+    pub(crate) async fn save_to_asset_async_with_progress(
+        &mut self,
+        asset_path: &Path,
+        signer: &dyn AsyncSigner,
+        output_path: &Path,
+        reporter: &(dyn ProgressReporter + Sync),
+        cancel: &CancellationToken,
+    ) -> Result<()> {
+        // the output only replaces the asset once it is complete
+        let output_file = crate::utils::atomic_file::AtomicFile::new(output_path)?;
+        let output_path = output_file.path();
+
+        // monitors are not Send, so none is held while the signer is awaited
+        let jumbf_bytes = {
+            let monitor = ProgressMonitor::new(reporter, cancel);
+            let jumbf_bytes = self.start_save(
+                asset_path,
+                output_path,
+                signer.reserve_size(),
+                &signer.dynamic_assertions(),
+                &monitor,
+            )?;
+            monitor.update(ProgressPhase::Signing, 0, 0)?;
+            jumbf_bytes
+        };
 
         let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
         let sig = self.sign_claim_async(pc, signer).await?;
         let sig_placeholder = self.sign_claim_placeholder(pc, signer.reserve_size());
 
-        match self.finish_save(jumbf_bytes, output_path, sig, &sig_placeholder) {
-            Ok(v) => {
-                // save sig so store is up to date
-                let pc_mut = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
-                pc_mut.set_signature_val(v);
-                Ok(())
-            }
-            Err(e) => Err(e),
-        }
+        let monitor = ProgressMonitor::new(reporter, cancel);
+        monitor.update(ProgressPhase::Embedding, 0, jumbf_bytes.len() as u64)?;
+        let sig = self.finish_save(jumbf_bytes, output_path, sig, &sig_placeholder)?;
+        self.verify_after_sign(output_path)?;
+        monitor.check_cancelled()?;
+        output_file.commit(self.fsync_policy)?;
+
+        // save sig so store is up to date
+        let len = sig.len() as u64;
+        let pc_mut = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
+        pc_mut.set_signature_val(sig);
+        monitor.update(ProgressPhase::Embedding, len, len)
     }
 
     #[cfg(feature = "file_io")]
@@ -1316,35 +2799,61 @@ impl Store {
         asset_path: &Path,
         output_path: &Path,
         reserve_size: usize,
+        dynamic_assertions: &[Box<dyn DynamicAssertion>],
+        monitor: &ProgressMonitor,
     ) -> Result<Vec<u8>> {
         // clone the source to working copy if requested
         get_supported_file_extension(asset_path).ok_or(Error::UnsupportedType)?; // verify extensions
-        let _ext = get_supported_file_extension(output_path).ok_or(Error::UnsupportedType)?;
+        let ext = get_supported_file_extension(output_path).ok_or(Error::UnsupportedType)?;
         if asset_path != output_path {
             fs::copy(&asset_path, &output_path).map_err(Error::IoError)?;
         }
 
         // get the provenance claim
         let pp = self.provenance_path();
+        let hashing_options = self.hashing_options.clone();
         let pc = self.provenance_claim_mut().ok_or(Error::ClaimEncoding)?;
 
         //  update file following the steps outlined in CAI spec
 
         // 1) Add DC provenance XMP
         // update XMP info & add xmp hash to provenance claim
-        if let Some(provenance) = pp {
-            embedded_xmp::add_manifest_uri_to_file(output_path, &provenance)
+        #[cfg_attr(target_os = "wasi", allow(unused_variables))]
+        let provenance = pp.ok_or(Error::XmpWriteError)?;
+        // the XMP toolkit is not available on WASI, readers find the manifest in the JUMBF instead
+        #[cfg(not(target_os = "wasi"))]
+        if get_assetio_handler(&ext).map_or(false, |h| h.supports_xmp()) {
+            crate::embedded_xmp::add_manifest_uri_to_file(output_path, &provenance)
                 .map_err(|_err| Error::XmpWriteError)?;
-        } else {
-            return Err(Error::XmpWriteError);
+        }
+
+        // reserve space for the dynamic assertions, their content is added once the other hashes are final
+        let dynamic_labels = Store::reserve_dynamic_assertions(pc, dynamic_assertions)?;
+
+        // formats made of separately hashed members are bound by a collection hash instead
+        let collection_hash = match get_assetio_handler(&ext) {
+            Some(handler) if !pc.update_manifest() => {
+                handler.collection_hash(output_path, pc.alg())?
+            }
+            _ => None,
+        };
+        let bound_by_collection = collection_hash.is_some();
+        if let Some(collection_hash) = collection_hash {
+            pc.add_assertion(&collection_hash)?;
         }
 
         // 2) Get hash ranges if needed, do not generate for update manifests
         let mut hash_ranges = object_locations(output_path)?;
-        let hashes: Vec<DataHash> = if pc.update_manifest() {
+        let hashes: Vec<DataHash> = if pc.update_manifest() || bound_by_collection {
             Vec::new()
         } else {
-            Store::generate_data_hashes(output_path, pc.alg(), &mut hash_ranges, false)?
+            Store::generate_data_hashes(
+                output_path,
+                pc.alg(),
+                &mut hash_ranges,
+                false,
+                &hashing_options,
+            )?
         };
 
         // add the placeholder data hashes to provenance claim so that the required space is reserved
@@ -1359,6 +2868,9 @@ impl Store {
         // 3) Generate in memory CAI jumbf block
         // and write preliminary jumbf store to file
         // source and dest the same so save_jumbf_to_file will use the same file since we have already cloned
+        let dynamic_size: usize = dynamic_assertions.iter().map(|da| da.reserve_size()).sum();
+        self.compressed_manifest_size =
+            self.compressed_manifest_reserve(reserve_size, dynamic_size)?;
         let mut data = self.to_jumbf_internal(reserve_size)?;
         let jumbf_size = data.len();
         save_jumbf_to_file(&data, output_path, Some(output_path))?;
@@ -1369,18 +2881,27 @@ impl Store {
 
         // get the final hash ranges, but not for update manifests
         let mut new_hash_ranges = object_locations(output_path)?;
-        let updated_hashes = if pc.update_manifest() {
+        let updated_hashes = if pc.update_manifest() || bound_by_collection {
             Vec::new()
         } else {
-            Store::generate_data_hashes(output_path, pc.alg(), &mut new_hash_ranges, true)?
+            Store::generate_data_hashes(
+                output_path,
+                pc.alg(),
+                &mut new_hash_ranges,
+                true,
+                &hashing_options,
+            )?
         };
 
         // patch existing claim hash with updated data
         for mut hash in updated_hashes {
-            hash.gen_hash(output_path)?; // generate
+            hash.gen_hash_with_progress(output_path, &hashing_options, monitor)?; // generate
             pc.update_data_hash(hash)?;
         }
 
+        // generate the dynamic assertions now that all other assertion hashes are known
+        Store::generate_dynamic_assertions(pc, dynamic_assertions, &dynamic_labels)?;
+
         // regenerate the jumbf because the cbor changed
         data = self.to_jumbf_internal(reserve_size)?;
         if jumbf_size != data.len() {
@@ -1390,6 +2911,86 @@ impl Store {
         Ok(data) // return JUMBF data
     }
 
+    // add placeholders reserving the space of the dynamic assertions, returning their labels
+    #[cfg(feature = "file_io")]
+    fn reserve_dynamic_assertions(
+        pc: &mut Claim,
+        dynamic_assertions: &[Box<dyn DynamicAssertion>],
+    ) -> Result<Vec<String>> {
+        let mut dynamic_labels = Vec::new();
+        for da in dynamic_assertions {
+            let placeholder = UserCbor::new(
+                &da.label(),
+                dynamic_assertion::placeholder(da.reserve_size())?,
+            );
+            let hashed_uri = pc.add_assertion(&placeholder)?;
+            let label = jumbf::labels::assertion_label_from_uri(&hashed_uri.url())
+                .ok_or(Error::ClaimEncoding)?;
+            dynamic_labels.push(label);
+        }
+        Ok(dynamic_labels)
+    }
+
+    // replace the placeholders of the dynamic assertions with their content, which can only
+    // be generated once the hashes of every other assertion of the claim are final
+    #[cfg(feature = "file_io")]
+    fn generate_dynamic_assertions(
+        pc: &mut Claim,
+        dynamic_assertions: &[Box<dyn DynamicAssertion>],
+        dynamic_labels: &[String],
+    ) -> Result<()> {
+        if dynamic_labels.is_empty() {
+            return Ok(());
+        }
+
+        let claim_bytes = pc.data()?;
+        let partial_claim = PartialClaim {
+            label: pc.label().to_owned(),
+            alg: pc.alg().to_owned(),
+            hash: hash_by_alg(pc.alg(), &claim_bytes, None),
+            claim_bytes,
+            assertions: pc
+                .assertions()
+                .iter()
+                .filter(|a| {
+                    jumbf::labels::assertion_label_from_uri(&a.url())
+                        .map_or(true, |l| !dynamic_labels.contains(&l))
+                })
+                .cloned()
+                .collect(),
+        };
+        for (da, label) in dynamic_assertions.iter().zip(dynamic_labels.iter()) {
+            let size = dynamic_assertion::placeholder(da.reserve_size())?.len();
+            let content = da.content(label, size, &partial_claim)?;
+            let content = dynamic_assertion::pad_content(content, size)?;
+            pc.replace_assertion(label, UserCbor::new(&da.label(), content).to_assertion()?)?;
+        }
+        Ok(())
+    }
+
+    // Reserve the size of the compressed manifest of the claim being signed. Placeholders
+    // compress far better than the content replacing them, so the compressed manifest is
+    // given room for the signature and the `dynamic_size` bytes of dynamic assertions.
+    #[cfg(feature = "file_io")]
+    fn compressed_manifest_reserve(
+        &self,
+        reserve_size: usize,
+        dynamic_size: usize,
+    ) -> Result<Option<usize>> {
+        let quality = match self.manifest_compression {
+            ManifestCompression::None => return Ok(None),
+            ManifestCompression::Brotli { quality } => quality,
+        };
+        let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
+
+        let manifest = self.manifest_bytes(pc, reserve_size, None)?;
+        let compressed = compress_manifest(pc.label(), &manifest, quality, None)?;
+
+        Ok(Some(
+            compressed.box_size()? as usize + reserve_size + dynamic_size + COMPRESSED_HASH_RESERVE,
+        ))
+    }
+
     #[cfg(feature = "file_io")]
     fn finish_save(
         &self,
@@ -1402,8 +3003,30 @@ impl Store {
             return Err(Error::CoseSigboxTooSmall);
         }
 
-        patch_bytes(&mut jumbf_bytes, sig_placeholder, &sig)
-            .map_err(|_| Error::JumbfCreationError)?;
+        // a standalone manifest store is only the JUMBF, so it is streamed to the file
+        if get_file_extension(output_path).as_deref() == Some("c2pa") {
+            let mut file = BufWriter::new(fs::File::create(output_path)?);
+            self.write_jumbf_with_signature(sig.len(), Some(&sig), &mut file)?;
+            if file.stream_position()? != jumbf_bytes.len() as u64 {
+                return Err(Error::JumbfCreationError);
+            }
+            return Ok(sig);
+        }
+
+        match self.manifest_compression {
+            ManifestCompression::None => {
+                patch_bytes(&mut jumbf_bytes, sig_placeholder, &sig)
+                    .map_err(|_| Error::JumbfCreationError)?;
+            }
+            ManifestCompression::Brotli { .. } => {
+                // the placeholder is compressed, so the store is written again with the signature
+                let signed = self.to_jumbf_with_signature(sig.len(), Some(&sig))?;
+                if signed.len() != jumbf_bytes.len() {
+                    return Err(Error::JumbfCreationError);
+                }
+                jumbf_bytes = signed;
+            }
+        }
 
         // re-save to file
         save_jumbf_to_file(&jumbf_bytes, output_path, Some(output_path))?;
@@ -1411,21 +3034,31 @@ impl Store {
         Ok(sig)
     }
 
-    /// Verify Store from an existing asset
-    /// asset_path: path to input asset
-    /// validation_log: If present all found errors are logged and returned, otherwise first error causes exit and is returned  
+    /// Verify Store from an existing asset
+    /// asset_path: path to input asset
+    /// validation_log: If present all found errors are logged and returned, otherwise first error causes exit and is returned  
+    #[cfg(feature = "file_io")]
+    pub fn verify_from_path(
+        &mut self,
+        asset_path: &Path,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        self.verify_from_path_with_progress(asset_path, validation_log, &ProgressMonitor::none())
+    }
+
     #[cfg(feature = "file_io")]
-    pub fn verify_from_path(
+    fn verify_from_path_with_progress(
         &mut self,
         asset_path: &Path,
         validation_log: &mut impl StatusTracker,
+        monitor: &ProgressMonitor,
     ) -> Result<()> {
         let ext = get_supported_file_extension(asset_path).ok_or(Error::UnsupportedType)?;
 
         // load the bytes
         let buf = fs::read(asset_path).map_err(crate::error::wrap_io_err)?;
 
-        self.verify_from_buffer(&buf, &ext, validation_log)
+        self.verify_from_buffer_with_progress(&buf, &ext, validation_log, monitor)
     }
 
     // verify from a buffer without file i/o
@@ -1434,6 +3067,21 @@ impl Store {
         buf: &[u8],
         asset_type: &str,
         validation_log: &mut impl StatusTracker,
+    ) -> Result<()> {
+        self.verify_from_buffer_with_progress(
+            buf,
+            asset_type,
+            validation_log,
+            &ProgressMonitor::none(),
+        )
+    }
+
+    fn verify_from_buffer_with_progress(
+        &mut self,
+        buf: &[u8],
+        asset_type: &str,
+        validation_log: &mut impl StatusTracker,
+        monitor: &ProgressMonitor,
     ) -> Result<()> {
         let mut buf_reader = Cursor::new(buf);
 
@@ -1444,7 +3092,13 @@ impl Store {
 
         let xmp_copy = xmp_opt.clone();
 
-        Store::verify_store(self, xmp_opt, buf_reader.get_ref(), validation_log)?;
+        Store::verify_store_with_progress(
+            self,
+            xmp_opt,
+            buf_reader.get_ref(),
+            validation_log,
+            monitor,
+        )?;
 
         // set the provenance if there is xmp otherwise it will default to active manifest
         if let Some(xmp) = xmp_copy {
@@ -1467,31 +3121,292 @@ impl Store {
         verify: bool,
         validation_log: &mut impl StatusTracker,
     ) -> Result<Store> {
-        // load jumbf if available
-        load_cai_from_file(asset_path, validation_log)
-            .and_then(|mut store| {
+        Store::load_from_asset_with_progress(
+            asset_path,
+            verify,
+            &VerifyOptions::default(),
+            validation_log,
+            &ProgressMonitor::none(),
+        )
+    }
+
+    /// Load Store from claims in an existing asset, verifying with the supplied options
+    /// and reporting verification progress to the monitor
+    #[cfg(feature = "file_io")]
+    #[instrument(skip_all, fields(asset = %asset_path.display(), verify = verify))]
+    pub(crate) fn load_from_asset_with_progress(
+        asset_path: &Path,
+        verify: bool,
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+        monitor: &ProgressMonitor,
+    ) -> Result<Store> {
+        // load jumbf if available, or the remote manifest store the asset references
+        match load_cai_from_file_with_limits(asset_path, &options.resource_limits, validation_log) {
+            Err(Error::JumbfNotFound) if options.fetch_remote_manifests => {
+                get_supported_file_extension(asset_path)
+                    .ok_or(Error::UnsupportedType)
+                    .and_then(|ext| {
+                        let buf = fs::read(asset_path).map_err(crate::error::wrap_io_err)?;
+                        Store::load_remote_manifest(
+                            &ext,
+                            &buf,
+                            verify,
+                            options,
+                            validation_log,
+                            monitor,
+                        )
+                    })
+            }
+            loaded => loaded.and_then(|mut store| {
+                store.set_verify_options(options.clone());
+
                 // verify the store
                 if verify {
-                    store.verify_from_path(asset_path, validation_log)?;
+                    store.verify_from_path_with_progress(asset_path, validation_log, monitor)?;
                 }
 
                 Ok(store)
-            })
-            .map_err(|e| {
-                let err = match e {
-                    Error::PrereleaseError => Error::PrereleaseError,
-                    Error::JumbfNotFound => Error::JumbfNotFound,
-                    _ => Error::LogStop,
-                };
-                let log_item = log_item!("asset", "error loading file", "load_from_asset").error(e);
-                validation_log.log_silent(log_item);
-                err
-            })
+            }),
+        }
+        .map_err(|e| {
+            let err = match e {
+                Error::PrereleaseError => Error::PrereleaseError,
+                Error::JumbfNotFound => Error::JumbfNotFound,
+                Error::ResourceLimitExceeded(ref limit) => {
+                    Error::ResourceLimitExceeded(limit.clone())
+                }
+                Error::OperationCancelled => Error::OperationCancelled,
+                _ => Error::LogStop,
+            };
+            let log_item = log_item!("asset", "error loading file", "load_from_asset").error(e);
+            validation_log.log_silent(log_item);
+            err
+        })
+    }
+
+    /// Verify the active manifest of an asset file, stopping at the first hard failure.
+    ///
+    /// The claim signature and assertion hashes are checked before any asset data is read,
+    /// then each data hash is computed by streaming the file and each collection hash is
+    /// checked against the decoded members of the asset. Ingredient manifests are not
+    /// re-validated. Returns the error that stopped verification.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn verify_asset_fail_fast(
+        asset_path: &Path,
+        options: &HashingOptions,
+        monitor: &ProgressMonitor,
+    ) -> Result<()> {
+        let mut validation_log = OneShotStatusTracker::new();
+
+        let store = load_cai_from_file(asset_path, &mut validation_log)?;
+        let claim = store.provenance_claim().ok_or(Error::ProvenanceMissing)?;
+
+        Claim::verify_claim_without_asset(
+            claim,
+            true,
+            CertCheckOptions::default(),
+            &mut validation_log,
+        )?;
+
+        for dh_assertion in claim.data_hash_assertions() {
+            monitor.check_cancelled()?;
+
+            let dh = DataHash::from_assertion(&dh_assertion)?;
+            if dh.is_remote_hash() {
+                continue;
+            }
+
+            if let Err(e) =
+                dh.verify_stream_hash(asset_path, Some(claim.alg().to_string()), options, monitor)
+            {
+                if let Error::OperationCancelled = e {
+                    return Err(e);
+                }
+                let log_item = log_item!(
+                    claim.assertion_uri(&dh_assertion.label()),
+                    format!("asset hash error: {}", e),
+                    "verify_asset_fail_fast"
+                )
+                .error(Error::HashMismatch(format!("Asset hash failure: {}", e)))
+                .validation_status(ValidationStatusCode::AssertionDataHashMismatch);
+                validation_log.log(
+                    log_item,
+                    Some(Error::HashMismatch(format!("Asset hash failure: {}", e))),
+                )?;
+            }
+        }
+
+        let collection_hashes = claim.collection_hash_assertions();
+        if !collection_hashes.is_empty() {
+            let asset_bytes = fs::read(asset_path).map_err(crate::error::wrap_io_err)?;
+            for ch_assertion in collection_hashes {
+                monitor.check_cancelled()?;
+
+                let ch = CollectionHash::from_assertion(&ch_assertion)?;
+                if let Err(e) = Claim::verify_collection_hash(&ch, &asset_bytes, claim.alg()) {
+                    let log_item = log_item!(
+                        claim.assertion_uri(&ch_assertion.label()),
+                        format!("collection hash error: {}", e),
+                        "verify_asset_fail_fast"
+                    )
+                    .error(Error::HashMismatch(format!(
+                        "Collection hash failure: {}",
+                        e
+                    )))
+                    .validation_status(ValidationStatusCode::AssertionCollectionHashMismatch);
+                    validation_log.log(
+                        log_item,
+                        Some(Error::HashMismatch(format!(
+                            "Collection hash failure: {}",
+                            e
+                        ))),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Embed the claims store into an asset with a placeholder for a signature made outside
+    /// the SDK by `signer`. Returns the pending signature with the bytes to be signed.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn save_to_asset_for_external_signing(
+        &mut self,
+        asset_path: &Path,
+        signer: &ExternalSigner,
+        output_path: &Path,
+    ) -> Result<PendingSignature> {
+        // a compressed placeholder cannot be patched
+        if self.manifest_compression != ManifestCompression::None {
+            return Err(Error::BadParam(
+                "compressed manifests cannot be signed externally".to_string(),
+            ));
+        }
+
+        let fsync_policy = self.fsync_policy;
+        let mut pending = None;
+        write_atomically(output_path, fsync_policy, |temp_path| {
+            let jumbf_bytes = self.start_save(
+                asset_path,
+                temp_path,
+                signer.reserve_size(),
+                &[],
+                &ProgressMonitor::none(),
+            )?;
+            let pc = self.provenance_claim().ok_or(Error::ClaimEncoding)?;
+            pending = Some(PendingSignature::new(pc, signer)?);
+
+            save_jumbf_to_file(&jumbf_bytes, temp_path, Some(temp_path))
+        })?;
+        pending.ok_or(Error::ClaimEncoding)
+    }
+
+    /// Embed a signature made outside the SDK into an asset file written with a
+    /// placeholder for it. The manifest store keeps its size, so the asset stays valid.
+    ///
+    /// With `in_place` only the placeholder is overwritten in the file, otherwise the
+    /// file is rewritten and replaced atomically. Assets whose format splits the
+    /// placeholder across segments are always rewritten.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn embed_external_signature(
+        asset_path: &Path,
+        pending: &PendingSignature,
+        cose_bytes: &[u8],
+        in_place: bool,
+    ) -> Result<()> {
+        let mut validation_log = OneShotStatusTracker::new();
+
+        let mut jumbf = crate::jumbf_io::load_jumbf_from_file(asset_path)?;
+        let store = Store::from_jumbf(&jumbf, &mut validation_log)?;
+        let claim =
+            store
+                .get_claim(pending.manifest_label())
+                .ok_or_else(|| Error::ClaimMissing {
+                    label: pending.manifest_label().to_owned(),
+                })?;
+
+        // the claim must be the one signed and must still have its placeholder
+        let placeholder = store.sign_claim_placeholder(claim, claim.signature_val().len());
+        if claim.data()? != pending.claim_bytes() || *claim.signature_val() != placeholder {
+            return Err(Error::BadParam(
+                "the asset does not have the pending signature".to_string(),
+            ));
+        }
+
+        let sig = pending.padded_signature(cose_bytes, placeholder.len())?;
+        crate::verify_detached(
+            &sig,
+            pending.claim_bytes(),
+            &VerifyOptions::default(),
+            &mut validation_log,
+        )?;
+
+        if in_place {
+            let mut file = fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(asset_path)?;
+            match patch_stream(&mut file, &placeholder, &sig) {
+                Ok(_) => {
+                    if store.fsync_policy != FsyncPolicy::None {
+                        file.sync_all()?;
+                    }
+                    return Ok(());
+                }
+                Err(Error::NotFound) => (), // the placeholder is not contiguous in the file
+                Err(e) => return Err(e),
+            }
+        }
+
+        patch_bytes(&mut jumbf, &placeholder, &sig)?;
+        write_atomically(asset_path, store.fsync_policy, |temp_path| {
+            save_jumbf_to_file(&jumbf, asset_path, Some(temp_path))
+        })
+    }
+
+    /// Add a time-stamp to the signature of the active manifest of an asset file that was
+    /// signed without one. The manifest store keeps its size, so the asset stays valid, but
+    /// the signature must have been reserved enough space for the time-stamp.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn add_timestamp_to_asset(
+        asset_path: &Path,
+        policy: &crate::TimeStampPolicy,
+    ) -> Result<()> {
+        let mut validation_log = OneShotStatusTracker::new();
+
+        let mut jumbf = crate::jumbf_io::load_jumbf_from_file(asset_path)?;
+        let store = Store::from_jumbf(&jumbf, &mut validation_log)?;
+        let claim = store.provenance_claim().ok_or(Error::ProvenanceMissing)?;
+
+        let tss = match claim.claim_version() {
+            ClaimVersion::V1 => TimeStampStorage::V1SigTst,
+            ClaimVersion::V2 => TimeStampStorage::V2SigTst2,
+        };
+        let claim_bytes = claim.data()?;
+        let sig =
+            crate::cose_sign::cose_add_timestamp(claim.signature_val(), &claim_bytes, tss, policy)?;
+
+        // Sanity check: Ensure that this signature is valid.
+        verify_cose(
+            &sig,
+            &claim_bytes,
+            b"",
+            false,
+            CertCheckOptions::default(),
+            &mut validation_log,
+        )?;
+
+        patch_bytes(&mut jumbf, claim.signature_val(), &sig)?;
+        write_atomically(asset_path, FsyncPolicy::default(), |temp_path| {
+            save_jumbf_to_file(&jumbf, asset_path, Some(temp_path))
+        })
     }
 
     fn get_store_from_memory(
         asset_type: &str,
         data: &[u8],
+        limits: &ResourceLimits,
         validation_log: &mut impl StatusTracker,
     ) -> Result<(Store, Option<String>)> {
         let cai_loader = get_cailoader_handler(asset_type).ok_or(Error::UnsupportedType)?;
@@ -1502,12 +3417,15 @@ impl Store {
         let xmp = cai_loader.read_xmp(&mut buf_reader);
 
         // load jumbf if available
-        load_cai_from_memory(asset_type, data, validation_log)
+        load_cai_from_memory_with_limits(asset_type, data, limits, validation_log)
             .map(|store| (store, xmp))
             .map_err(|e| {
                 let err = match e {
                     Error::PrereleaseError => Error::PrereleaseError,
                     Error::JumbfNotFound => Error::JumbfNotFound,
+                    Error::ResourceLimitExceeded(ref limit) => {
+                        Error::ResourceLimitExceeded(limit.clone())
+                    }
                     _ => Error::LogStop,
                 };
                 let log_item =
@@ -1528,29 +3446,96 @@ impl Store {
         verify: bool,
         validation_log: &mut impl StatusTracker,
     ) -> Result<Store> {
-        Store::get_store_from_memory(asset_type, data, validation_log).and_then(
-            |(mut store, xmp_opt)| {
-                let buf_reader = Cursor::new(data);
+        Store::load_from_memory_with_options(
+            asset_type,
+            data,
+            verify,
+            &VerifyOptions::default(),
+            validation_log,
+        )
+    }
 
-                // verify the store
-                if verify {
-                    let xmp_copy = xmp_opt.clone();
+    // Load the manifest store referenced by an https URL in the XMP provenance of an
+    // asset without an embedded manifest store. The remote store is not bound to a
+    // manifest label in the asset, so its active manifest is verified against the asset.
+    fn load_remote_manifest(
+        asset_type: &str,
+        data: &[u8],
+        verify: bool,
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+        monitor: &ProgressMonitor,
+    ) -> Result<Store> {
+        let cai_loader = get_cailoader_handler(asset_type).ok_or(Error::UnsupportedType)?;
+        let url = cai_loader
+            .read_xmp(&mut Cursor::new(data))
+            .and_then(|xmp| extract_provenance(&xmp))
+            // plain http would let anyone on the path substitute the manifest store
+            .filter(|provenance| provenance.starts_with("https://"))
+            .ok_or(Error::JumbfNotFound)?;
+
+        let jumbf = fetch_remote_manifest(&url, &options.resource_limits)?;
+        let mut store =
+            Store::from_jumbf_with_limits(&jumbf, &options.resource_limits, validation_log)?;
+        store.set_verify_options(options.clone());
 
-                    // verify store and claims
-                    Store::verify_store(&store, xmp_opt, buf_reader.get_ref(), validation_log)?;
+        if verify {
+            Store::verify_store_with_progress(&store, None, data, validation_log, monitor)?;
+        }
 
-                    // set the provenance if checks pass & has xmp, otherwise default to active manifest
-                    if let Some(xmp) = xmp_copy {
-                        if let Some(xmp_provenance) = extract_provenance(&xmp) {
-                            let claim_label = Store::manifest_label_from_path(&xmp_provenance);
-                            store.set_provenance_path(&claim_label);
-                        }
+        Ok(store)
+    }
+
+    /// Load Store from a in-memory asset, verifying with the supplied options
+    #[instrument(skip_all, fields(format = asset_type, len = data.len(), verify = verify))]
+    pub(crate) fn load_from_memory_with_options(
+        asset_type: &str,
+        data: &[u8],
+        verify: bool,
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Store> {
+        let loaded = Store::get_store_from_memory(
+            asset_type,
+            data,
+            &options.resource_limits,
+            validation_log,
+        );
+        if let Err(Error::JumbfNotFound) = loaded {
+            if options.fetch_remote_manifests {
+                return Store::load_remote_manifest(
+                    asset_type,
+                    data,
+                    verify,
+                    options,
+                    validation_log,
+                    &ProgressMonitor::none(),
+                );
+            }
+        }
+
+        loaded.and_then(|(mut store, xmp_opt)| {
+            store.set_verify_options(options.clone());
+            let buf_reader = Cursor::new(data);
+
+            // verify the store
+            if verify {
+                let xmp_copy = xmp_opt.clone();
+
+                // verify store and claims
+                Store::verify_store(&store, xmp_opt, buf_reader.get_ref(), validation_log)?;
+
+                // set the provenance if checks pass & has xmp, otherwise default to active manifest
+                if let Some(xmp) = xmp_copy {
+                    if let Some(xmp_provenance) = extract_provenance(&xmp) {
+                        let claim_label = Store::manifest_label_from_path(&xmp_provenance);
+                        store.set_provenance_path(&claim_label);
                     }
                 }
+            }
 
-                Ok(store)
-            },
-        )
+            Ok(store)
+        })
     }
 
     /// Load Store from a in-memory asset asychronously validating
@@ -1558,13 +3543,39 @@ impl Store {
     /// data: reference to bytes of the the file
     /// verify: if true will run verification checks when loading
     /// validation_log: If present all found errors are logged and returned, otherwise first error causes exit and is returned
+    #[instrument(skip_all, fields(format = asset_type, len = data.len(), verify = verify))]
     pub async fn load_from_memory_async(
         asset_type: &str,
         data: &[u8],
         verify: bool,
         validation_log: &mut impl StatusTracker,
     ) -> Result<Store> {
-        let (mut store, xmp_opt) = Store::get_store_from_memory(asset_type, data, validation_log)?;
+        Store::load_from_memory_async_with_options(
+            asset_type,
+            data,
+            verify,
+            &VerifyOptions::default(),
+            validation_log,
+        )
+        .await
+    }
+
+    /// Load Store from a in-memory asset asynchronously, verifying with the supplied options
+    #[instrument(skip_all, fields(format = asset_type, len = data.len(), verify = verify))]
+    pub(crate) async fn load_from_memory_async_with_options(
+        asset_type: &str,
+        data: &[u8],
+        verify: bool,
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Store> {
+        let (mut store, xmp_opt) = Store::get_store_from_memory(
+            asset_type,
+            data,
+            &options.resource_limits,
+            validation_log,
+        )?;
+        store.set_verify_options(options.clone());
 
         let buf_reader = Cursor::new(data);
 
@@ -1588,6 +3599,61 @@ impl Store {
         Ok(store)
     }
 
+    /// Recover the manifest of an asset whose embedded manifest was removed.
+    /// data: bytes of the asset
+    /// repository: repository queried by the soft bindings found in the asset and by content hash
+    /// options: verify options, the soft binding algorithms in these are used for the lookup
+    /// validation_log: receives the validation results of the recovered manifest
+    ///
+    /// Returns [`Error::JumbfNotFound`] if the repository has no matching manifest.
+    pub fn recover_manifest(
+        data: &[u8],
+        repository: &dyn ManifestRepository,
+        options: &VerifyOptions,
+        validation_log: &mut impl StatusTracker,
+    ) -> Result<Store> {
+        // soft bindings survive transcoding so try them before the content hash
+        let mut candidates = Vec::new();
+        for (alg, value) in options.soft_bindings.extract_all(data) {
+            if let Some(jumbf) = repository.find_by_soft_binding(&alg, &value)? {
+                candidates.push(jumbf);
+            }
+        }
+        let content_hash = hash_by_alg("sha256", data, None);
+        if let Some(jumbf) = repository.find_by_content_hash("sha256", &content_hash)? {
+            candidates.push(jumbf);
+        }
+
+        // use the first candidate that validates, otherwise the first one that loads
+        let mut recovered: Option<Store> = None;
+        for jumbf in candidates {
+            if let Ok(mut store) = Store::from_jumbf_with_limits(
+                &jumbf,
+                &options.resource_limits,
+                &mut OneShotStatusTracker::new(),
+            ) {
+                store.set_verify_options(options.clone());
+                // the manifest store was removed, along with the ranges its data hashes exclude
+                store.asset_stripped = true;
+                let valid =
+                    Store::verify_store(&store, None, data, &mut OneShotStatusTracker::new())
+                        .is_ok();
+                if valid || recovered.is_none() {
+                    recovered = Some(store);
+                }
+                if valid {
+                    break;
+                }
+            }
+        }
+        let store = recovered.ok_or(Error::JumbfNotFound)?;
+
+        // re-validate to record the results of the recovered manifest
+        Store::verify_store(&store, None, data, validation_log)?;
+
+        Ok(store)
+    }
+
     /// Load Store from memory and add its content as a claim ingredient
     /// claim: claim to add an ingredient
     /// provenance_label: label of the provenance claim used as key into ingredient map
@@ -1672,8 +3738,35 @@ pub enum InvalidClaimError {
     AssertionCountMismatch { expected: usize, found: usize },
 }
 
+// fetch a remote manifest store, reading at most one byte more than the limit so
+// an oversized store is still reported as exceeding it
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_remote_manifest(url: &str, limits: &ResourceLimits) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let response = ureq::get(url)
+        .timeout(REMOTE_MANIFEST_TIMEOUT)
+        .call()
+        .map_err(|e| Error::OtherError(Box::new(e)))?;
+
+    let mut jumbf = Vec::new();
+    response
+        .into_reader()
+        .take((limits.max_manifest_size as u64).saturating_add(1))
+        .read_to_end(&mut jumbf)?;
+    Ok(jumbf)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn fetch_remote_manifest(url: &str, _limits: &ResourceLimits) -> Result<Vec<u8>> {
+    Err(Error::BadParam(format!(
+        "remote manifest store cannot be fetched from {}",
+        url
+    )))
+}
+
 #[cfg(test)]
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
 pub mod tests {
     #![allow(clippy::expect_used)]
     #![allow(clippy::panic)]
@@ -1807,78 +3900,425 @@ pub mod tests {
                     .expect("could not restore from json")
             );
 
-            for hashed_uri in claim.assertions() {
-                let (label, instance) = Claim::assertion_label_from_link(&hashed_uri.url());
-                claim.get_claim_assertion(&label, instance).unwrap();
-            }
+            for hashed_uri in claim.assertions() {
+                let (label, instance) = Claim::assertion_label_from_link(&hashed_uri.url());
+                claim.get_claim_assertion(&label, instance).unwrap();
+            }
+        }
+
+        // test patch file - bytes should be same so error should not be detected
+        let mut splice_point =
+            patch_file(&op, "thumbnail".as_bytes(), "testme".as_bytes()).unwrap();
+
+        let mut restore_point =
+            patch_file(&op, "testme".as_bytes(), "thumbnail".as_bytes()).unwrap();
+
+        assert_eq!(splice_point, restore_point);
+
+        Store::load_from_asset(&op, true, &mut OneShotStatusTracker::new())
+            .expect("Should still verify");
+
+        // test patching jumbf - error should be detected
+
+        splice_point = update_file_jumbf(&op, "thumbnail".as_bytes(), "testme".as_bytes()).unwrap();
+        restore_point =
+            update_file_jumbf(&op, "testme".as_bytes(), "thumbnail.v1".as_bytes()).unwrap();
+
+        assert_eq!(splice_point, restore_point);
+
+        Store::load_from_asset(&op, true, &mut OneShotStatusTracker::new())
+            .expect_err("Should not verify");
+    }
+
+    struct BadSigner {}
+
+    impl crate::Signer for BadSigner {
+        fn sign(&self, _data: &[u8]) -> Result<Vec<u8>> {
+            Ok(b"not a valid signature".to_vec())
+        }
+
+        fn alg(&self) -> Option<String> {
+            None
+        }
+
+        fn certs(&self) -> Result<Vec<Vec<u8>>> {
+            Ok(Vec::new())
+        }
+
+        fn reserve_size(&self) -> usize {
+            42
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_detects_unverifiable_signature() {
+        // test adding to actual image
+        let ap = fixture_path("earth_apollo17.jpg");
+        let temp_dir = tempdir().expect("temp dir");
+        let op = temp_dir_path(&temp_dir, "test-image-unverified.jpg");
+
+        let mut store = Store::new();
+
+        let claim = create_test_claim().unwrap();
+
+        let signer = BadSigner {};
+
+        // JUMBF generation should fail because this signature won't validate.
+        store.commit_claim(claim).unwrap();
+
+        // TO DO: This generates a log spew when running this test.
+        // I don't have time to fix this right now.
+        // [(date) ERROR c2pa::store] Signature that was just generated does not validate: CoseCbor
+
+        store.save_to_asset(&ap, &signer, &op).unwrap_err();
+    }
+
+    // a signer that underestimates the size of its signatures
+    struct SmallReserveSigner(Box<dyn crate::Signer>);
+
+    impl crate::Signer for SmallReserveSigner {
+        fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.0.sign(data)
+        }
+
+        fn alg(&self) -> Option<String> {
+            self.0.alg()
+        }
+
+        fn certs(&self) -> Result<Vec<Vec<u8>>> {
+            self.0.certs()
         }
 
-        // test patch file - bytes should be same so error should not be detected
-        let mut splice_point =
-            patch_file(&op, "thumbnail".as_bytes(), "testme".as_bytes()).unwrap();
+        fn reserve_size(&self) -> usize {
+            2048
+        }
+    }
 
-        let mut restore_point =
-            patch_file(&op, "testme".as_bytes(), "thumbnail".as_bytes()).unwrap();
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_retry_with_larger_reserve_size() {
+        let ap = fixture_path("earth_apollo17.jpg");
+        let temp_dir = tempdir().expect("temp dir");
+        let op = temp_dir_path(&temp_dir, "test-image-reserve.jpg");
 
-        assert_eq!(splice_point, restore_point);
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let signer = SmallReserveSigner(Box::new(signer));
 
-        Store::load_from_asset(&op, true, &mut OneShotStatusTracker::new())
-            .expect("Should still verify");
+        let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        store.save_to_asset(&ap, &signer, &op).unwrap();
 
-        // test patching jumbf - error should be detected
+        let reserve_size = store.adjusted_reserve_size().unwrap();
+        assert!(reserve_size > 2048);
 
-        splice_point = update_file_jumbf(&op, "thumbnail".as_bytes(), "testme".as_bytes()).unwrap();
-        restore_point =
-            update_file_jumbf(&op, "testme".as_bytes(), "thumbnail.v1".as_bytes()).unwrap();
+        let mut report = DetailedStatusTracker::new();
+        Store::load_from_asset(&op, true, &mut report).unwrap();
+        assert!(report.get_log().is_empty());
+    }
 
-        assert_eq!(splice_point, restore_point);
+    // records the number of other assertions in the claim when it is signed
+    struct AssertionCount;
 
-        Store::load_from_asset(&op, true, &mut OneShotStatusTracker::new())
-            .expect_err("Should not verify");
+    impl crate::DynamicAssertion for AssertionCount {
+        fn label(&self) -> String {
+            "com.example.count".to_owned()
+        }
+
+        fn reserve_size(&self) -> usize {
+            32
+        }
+
+        fn content(
+            &self,
+            _label: &str,
+            _size: usize,
+            claim: &crate::PartialClaim,
+        ) -> Result<Vec<u8>> {
+            assert_eq!(
+                claim.hash(),
+                hash_by_alg(claim.alg(), claim.claim_bytes(), None).as_slice()
+            );
+            let mut map = std::collections::BTreeMap::new();
+            map.insert("count", claim.assertions().count());
+            Ok(serde_cbor::to_vec(&map)?)
+        }
     }
 
-    struct BadSigner {}
+    struct DynamicSigner(Box<dyn crate::Signer>);
 
-    impl crate::Signer for BadSigner {
-        fn sign(&self, _data: &[u8]) -> Result<Vec<u8>> {
-            Ok(b"not a valid signature".to_vec())
+    impl crate::Signer for DynamicSigner {
+        fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+            self.0.sign(data)
         }
 
         fn alg(&self) -> Option<String> {
-            None
+            self.0.alg()
         }
 
         fn certs(&self) -> Result<Vec<Vec<u8>>> {
-            Ok(Vec::new())
+            self.0.certs()
         }
 
         fn reserve_size(&self) -> usize {
-            42
+            self.0.reserve_size()
+        }
+
+        fn dynamic_assertions(&self) -> Vec<Box<dyn crate::DynamicAssertion>> {
+            vec![Box::new(AssertionCount)]
         }
     }
 
     #[test]
     #[cfg(feature = "file_io")]
-    fn test_detects_unverifiable_signature() {
-        // test adding to actual image
+    fn test_rebind() {
+        let temp_dir = tempdir().expect("temp dir");
+        let op = temp_dir_path(&temp_dir, "test-image-rebind.jpg");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+
+        let mut report = DetailedStatusTracker::new();
+        let original = Store::load_from_asset(&fixture_path("CA.jpg"), true, &mut report).unwrap();
+        let original_label = original.provenance_label().unwrap();
+
+        // a rendition of the asset that lost its manifest when it was re-encoded
+        original
+            .rebind(
+                &fixture_path("earth_apollo17.jpg"),
+                &op,
+                "rebind_test",
+                &signer,
+            )
+            .unwrap();
+
+        let mut report = DetailedStatusTracker::new();
+        let store = Store::load_from_asset(&op, true, &mut report).unwrap();
+        assert!(report.get_log().iter().all(|item| item.err_val.is_none()));
+
+        let pc = store.provenance_claim().unwrap();
+        let ingredient = Ingredient::from_assertion(
+            pc.get_claim_assertion(labels::INGREDIENT, 0)
+                .unwrap()
+                .assertion(),
+        )
+        .unwrap();
+        assert_eq!(ingredient.relationship, Relationship::ParentOf);
+        assert_eq!(
+            ingredient
+                .c2pa_manifest
+                .and_then(|uri| jumbf::labels::manifest_label_from_uri(&uri.url())),
+            Some(original_label)
+        );
+
+        let actions = Actions::from_assertion(
+            pc.claim_assertion_store()
+                .iter()
+                .find(|ca| ca.assertion().label_root() == labels::ACTIONS)
+                .unwrap()
+                .assertion(),
+        )
+        .unwrap();
+        assert_eq!(actions.actions()[0].action(), c2pa_action::TRANSCODED);
+        assert_eq!(actions.actions()[0].ingredients().unwrap().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_dynamic_assertion() {
         let ap = fixture_path("earth_apollo17.jpg");
         let temp_dir = tempdir().expect("temp dir");
-        let op = temp_dir_path(&temp_dir, "test-image-unverified.jpg");
+        let op = temp_dir_path(&temp_dir, "test-image-dynamic.jpg");
+
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let signer = DynamicSigner(Box::new(signer));
 
         let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        store.save_to_asset(&ap, &signer, &op).unwrap();
 
-        let claim = create_test_claim().unwrap();
+        let mut report = DetailedStatusTracker::new();
+        let store = Store::load_from_asset(&op, true, &mut report).unwrap();
+        assert!(report.get_log().is_empty());
 
-        let signer = BadSigner {};
+        let pc = store.provenance_claim().unwrap();
+        let assertion = pc.get_claim_assertion("com.example.count", 0).unwrap();
+        let value: std::collections::BTreeMap<String, serde_cbor::Value> =
+            serde_cbor::from_slice(assertion.assertion().data()).unwrap();
+        let expected = pc.assertions().len() - 1;
+        assert_eq!(value["count"], serde_cbor::Value::Integer(expected as i128));
+    }
 
-        // JUMBF generation should fail because this signature won't validate.
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_dynamic_assertion_standalone() {
+        let ap = fixture_path("earth_apollo17.jpg");
+        let temp_dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let signer = DynamicSigner(Box::new(signer));
+
+        // an unsigned store cannot hold the dynamic assertions yet
+        let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        assert!(matches!(store.to_jumbf(&signer), Err(Error::BadParam(_))));
+
+        let archive = store.save_to_archive(&ap, &signer).unwrap();
+        let asset = std::fs::read(&ap).unwrap();
+        let mut report = DetailedStatusTracker::new();
+        let store =
+            Store::load_from_archive(&archive, &asset, &VerifyOptions::default(), &mut report)
+                .unwrap();
+        assert!(report.get_log().is_empty());
+        let pc = store.provenance_claim().unwrap();
+        assert!(pc.get_claim_assertion("com.example.count", 0).is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_custom_assertion_schema() {
+        use crate::assertions::{AssertionRegistry, JsonSchema};
+
+        let ap = fixture_path("earth_apollo17.jpg");
+        let temp_dir = tempdir().expect("temp dir");
+        let op = temp_dir_path(&temp_dir, "test-image-custom.jpg");
+
+        let mut claim = create_test_claim().unwrap();
+        let rating = serde_cbor::to_vec(&serde_json::json!({ "stars": 7 })).unwrap();
+        claim
+            .add_assertion(&UserCbor::new("com.example.rating", rating))
+            .unwrap();
+
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let mut store = Store::new();
         store.commit_claim(claim).unwrap();
+        store.save_to_asset(&ap, &signer, &op).unwrap();
 
-        // TO DO: This generates a log spew when running this test.
-        // I don't have time to fix this right now.
-        // [(date) ERROR c2pa::store] Signature that was just generated does not validate: CoseCbor
+        let load = |maximum: u32| {
+            let mut registry = AssertionRegistry::new();
+            registry.register(std::sync::Arc::new(JsonSchema::new(
+                "com.example.rating",
+                serde_json::json!({
+                    "type": "object",
+                    "required": ["stars"],
+                    "properties": { "stars": { "type": "integer", "maximum": maximum } }
+                }),
+            )));
+            let mut options = VerifyOptions::new();
+            options.set_custom_assertions(registry);
+
+            let mut report = DetailedStatusTracker::new();
+            Store::load_from_asset_with_progress(
+                &op,
+                true,
+                &options,
+                &mut report,
+                &ProgressMonitor::none(),
+            )
+            .unwrap();
+            report_split_errors(report.get_log_mut())
+        };
 
-        store.save_to_asset(&ap, &signer, &op).unwrap_err();
+        assert!(load(10).is_empty());
+
+        let errors = load(5);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].validation_status.as_ref(),
+            Some(&ValidationStatusCode::AssertionMalformed)
+        );
+        assert!(errors[0].label.ends_with("com.example.rating"));
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_soft_binding_mismatch() {
+        use crate::assertions::{
+            SoftBinding, SoftBindingAlgorithm, SoftBindingRegistry, SoftBindingScope,
+        };
+
+        // a toy algorithm that uses the last 4 bytes of the asset as the watermark
+        struct TailMark;
+
+        impl SoftBindingAlgorithm for TailMark {
+            fn alg(&self) -> &str {
+                "com.example.tailmark"
+            }
+
+            fn extract(&self, asset_data: &[u8]) -> Result<Option<Vec<u8>>> {
+                Ok(asset_data
+                    .len()
+                    .checked_sub(4)
+                    .map(|start| asset_data[start..].to_vec()))
+            }
+        }
+
+        let ap = fixture_path("earth_apollo17.jpg");
+        let temp_dir = tempdir().expect("temp dir");
+        let op = temp_dir_path(&temp_dir, "test-image-soft-binding.jpg");
+
+        let mut claim = create_test_claim().unwrap();
+        let mut soft_binding = SoftBinding::new(TailMark.alg());
+        soft_binding.add_block(SoftBindingScope::default(), b"none".to_vec());
+        claim.add_assertion(&soft_binding).unwrap();
+
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let mut store = Store::new();
+        store.commit_claim(claim).unwrap();
+        store.save_to_asset(&ap, &signer, &op).unwrap();
+
+        let mut registry = SoftBindingRegistry::new();
+        registry.register(std::sync::Arc::new(TailMark));
+        let mut options = VerifyOptions::new();
+        options.set_soft_bindings(registry);
+
+        // the mismatch is reported without failing validation
+        let mut report = DetailedStatusTracker::new();
+        Store::load_from_asset_with_progress(
+            &op,
+            true,
+            &options,
+            &mut report,
+            &ProgressMonitor::none(),
+        )
+        .unwrap();
+        assert!(report.get_log().iter().any(|i| {
+            i.validation_status.as_ref() == Some(&ValidationStatusCode::SoftBindingMismatch)
+        }));
+        assert!(report_split_errors(report.get_log_mut()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_verify_progress() {
+        use crate::progress::{CancellationToken, ProgressReporter};
+        use std::cell::RefCell;
+
+        struct Recorder(RefCell<Vec<(u64, u64)>>);
+
+        impl ProgressReporter for Recorder {
+            fn report(&self, phase: ProgressPhase, bytes_processed: u64, total_bytes: u64) {
+                if phase == ProgressPhase::Verifying {
+                    self.0.borrow_mut().push((bytes_processed, total_bytes));
+                }
+            }
+        }
+
+        let recorder = Recorder(RefCell::new(Vec::new()));
+        let token = CancellationToken::new();
+        let mut report = DetailedStatusTracker::new();
+        Store::load_from_asset_with_progress(
+            &fixture_path("CA.jpg"),
+            true,
+            &VerifyOptions::new(),
+            &mut report,
+            &ProgressMonitor::new(&recorder, &token),
+        )
+        .unwrap();
+
+        // progress is reported as each check completes, ending at the total
+        let updates = recorder.0.borrow();
+        assert!(updates.len() > 2);
+        assert!(updates.windows(2).all(|w| w[0].0 < w[1].0));
+        let (processed, total) = updates[updates.len() - 1];
+        assert_eq!(processed, total);
     }
 
     #[test]
@@ -1905,7 +4345,19 @@ pub mod tests {
         // JUMBF generation should fail because the certificate won't validate.
         let r = store.save_to_asset(&ap, &signer, &op);
         assert!(r.is_err());
-        assert_eq!(r.err().unwrap().to_string(), "COSE certificate has expired");
+        assert_eq!(
+            r.err().unwrap().to_string(),
+            "signing certificate rejected: certificate expired"
+        );
+
+        // unless the policy of the signer accepts expired certificates
+        let mut signer = crate::PolicySigner::new(signer);
+        let mut policy = crate::CertificateAcceptancePolicy::new();
+        policy.relax(crate::CertRule::Validity);
+        signer.set_certificate_acceptance_policy(policy);
+        let mut store = Store::new();
+        store.commit_claim(create_test_claim().unwrap()).unwrap();
+        store.save_to_asset(&ap, &signer, &op).unwrap();
     }
 
     #[test]
@@ -2147,7 +4599,7 @@ pub mod tests {
         let errs = report_split_errors(report.get_log_mut());
         assert!(report_has_status(
             &errs,
-            validation_status::ASSERTION_DATAHASH_MISMATCH
+            ValidationStatusCode::AssertionDataHashMismatch
         ));
     }
 
@@ -2308,6 +4760,62 @@ pub mod tests {
         assert!(um.update_manifest());
     }
 
+    #[test]
+    fn test_manifest_compression() {
+        use crate::utils::test::create_test_store;
+
+        let temp_dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let ap = fixture_path("earth_apollo17.jpg");
+        let op = temp_dir_path(&temp_dir, "compressed.jpg");
+
+        let mut store = create_test_store().unwrap();
+        store.set_manifest_compression(ManifestCompression::Brotli { quality: 9 });
+        store
+            .save_to_asset(ap.as_path(), &signer, op.as_path())
+            .unwrap();
+
+        // the manifest is stored in a compressed manifest box
+        let jumbf = load_jumbf_from_file(&op).unwrap();
+        let cai_block = BoxReader::read_super_box(&mut Cursor::new(jumbf)).unwrap();
+        let manifest = cai_block.data_box_as_superbox(0).unwrap();
+        assert_eq!(manifest.desc_box().uuid(), CAI_COMPRESSED_MANIFEST_UUID);
+
+        // and is expanded and validated when read
+        let mut report = OneShotStatusTracker::default();
+        let restored = Store::load_from_asset(op.as_path(), true, &mut report).unwrap();
+        assert_eq!(
+            restored.provenance_claim().unwrap().label(),
+            store.provenance_claim().unwrap().label()
+        );
+    }
+
+    #[test]
+    fn test_write_jumbf() {
+        use crate::utils::test::create_test_store;
+
+        let temp_dir = tempdir().expect("temp dir");
+        let (signer, _) = get_temp_signer(&temp_dir.path());
+        let op = temp_dir_path(&temp_dir, "streamed.c2pa");
+
+        let store = create_test_store().unwrap();
+        let mut file = std::fs::File::create(&op).unwrap();
+        store.write_jumbf(&signer, &mut file).unwrap();
+        drop(file);
+
+        // the streamed store matches the one written to memory
+        let streamed = std::fs::read(&op).unwrap();
+        assert_eq!(streamed, store.to_jumbf(&signer).unwrap());
+
+        let cai_block = BoxReader::read_super_box(&mut Cursor::new(streamed)).unwrap();
+        assert_eq!(cai_block.desc_box().uuid(), CAI_BLOCK_UUID);
+        let manifest = cai_block.data_box_as_superbox(0).unwrap();
+        assert_eq!(
+            manifest.desc_box().label(),
+            store.provenance_claim().unwrap().label()
+        );
+    }
+
     #[test]
     fn test_claim_decoding() {
         // modify a required field label in the claim - causes failure to read claim from cbor
@@ -2333,8 +4841,8 @@ pub mod tests {
 
         assert!(matches!(errors[0].err_val, Some(Error::HashMismatch(_))));
         assert_eq!(
-            errors[0].validation_status.as_deref(),
-            Some(validation_status::ASSERTION_DATAHASH_MISMATCH)
+            errors[0].validation_status.as_ref(),
+            Some(&ValidationStatusCode::AssertionDataHashMismatch)
         ); // what validation status should we have for this?
     }
 
@@ -2350,11 +4858,11 @@ pub mod tests {
 
         assert!(report_has_status(
             &errors,
-            validation_status::CLAIM_SIGNATURE_MISMATCH
+            ValidationStatusCode::ClaimSignatureMismatch
         ));
         assert!(report_has_status(
             &errors,
-            validation_status::TIMESTAMP_MISMATCH
+            ValidationStatusCode::TimeStampMismatch
         ));
     }
 
@@ -2365,8 +4873,8 @@ pub mod tests {
         let errors = report_split_errors(report.get_log_mut());
 
         assert_eq!(
-            errors[0].validation_status.as_deref(),
-            Some(validation_status::ASSERTION_HASHEDURI_MISMATCH)
+            errors[0].validation_status.as_ref(),
+            Some(&ValidationStatusCode::AssertionHashedUriMismatch)
         );
     }
 
@@ -2381,13 +4889,303 @@ pub mod tests {
         let mut report = patch_and_report("CIE-sig-CA.jpg", SEARCH_BYTES, REPLACE_BYTES);
         let errors = report_split_errors(report.get_log_mut());
         assert_eq!(
-            errors[0].validation_status.as_deref(),
-            Some(validation_status::ASSERTION_HASHEDURI_MISMATCH)
+            errors[0].validation_status.as_ref(),
+            Some(&ValidationStatusCode::AssertionHashedUriMismatch)
+        );
+        assert_eq!(
+            errors[1].validation_status.as_ref(),
+            Some(&ValidationStatusCode::ClaimMissing)
+        );
+    }
+
+    #[test]
+    fn test_ingredient_validation_policy() {
+        // the manifest of the ingredient of this asset has a bad signature
+        let ap = fixture_path("CIE-sig-CA.jpg");
+
+        let options = |policy: IngredientValidation| {
+            let mut options = VerifyOptions::new();
+            options.set_ingredient_validation(policy);
+            options
+        };
+        let is_mismatch = |i: &LogItem| {
+            i.validation_status.as_ref() == Some(&ValidationStatusCode::ClaimSignatureMismatch)
+        };
+
+        // policies that verify the ingredient claim fail the parent
+        for policy in [
+            IngredientValidation::OneLevel,
+            IngredientValidation::Levels(2),
+            IngredientValidation::Full,
+        ] {
+            let mut report = DetailedStatusTracker::new();
+            let _ = Store::load_from_asset_with_progress(
+                &ap,
+                true,
+                &options(policy),
+                &mut report,
+                &ProgressMonitor::none(),
+            );
+            let errors = report_split_errors(report.get_log_mut());
+            assert!(errors.iter().any(is_mismatch));
+
+            assert!(Store::load_from_asset_with_progress(
+                &ap,
+                true,
+                &options(policy),
+                &mut OneShotStatusTracker::new(),
+                &ProgressMonitor::none(),
+            )
+            .is_err());
+        }
+
+        // trusting the recorded results only reports the status of the ingredient
+        let mut report = DetailedStatusTracker::new();
+        Store::load_from_asset_with_progress(
+            &ap,
+            true,
+            &options(IngredientValidation::Recorded),
+            &mut report,
+            &ProgressMonitor::none(),
+        )
+        .expect("load_from_asset");
+        assert!(report.get_log().iter().any(is_mismatch));
+        assert!(report_split_errors(report.get_log_mut()).is_empty());
+
+        assert!(Store::load_from_asset_with_progress(
+            &ap,
+            true,
+            &options(IngredientValidation::Recorded),
+            &mut OneShotStatusTracker::new(),
+            &ProgressMonitor::none(),
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_ingredient_label_collision() {
+        // two manifests from different generators with the same label
+        let first = Claim::new("generator one", Some("c2pa"));
+        let mut second = Claim::new("generator two", Some("c2pa"));
+        let label = first.label().to_owned();
+        second.set_label(&label);
+
+        let hashed_uri = |claim: &Claim| {
+            HashedUri::new(claim.uri(), Some(claim.alg().to_owned()), &claim.hash())
+        };
+        let (first_uri, second_uri) = (hashed_uri(&first), hashed_uri(&second));
+
+        let mut parent = Claim::new("adobe unit test", Some("c2pa"));
+        parent
+            .add_ingredient_data(&label, vec![first.clone(), second, first], None)
+            .unwrap();
+        let mut store = Store::new();
+        store.commit_claim(parent).unwrap();
+
+        // the same manifest is stored once, the other one is renamed
+        assert_eq!(store.claims().len(), 3);
+        let renamed = format!("{}__1", label);
+        assert_eq!(store.resolve_ingredient(&first_uri).unwrap().label(), label);
+        assert_eq!(
+            store.resolve_ingredient(&second_uri).unwrap().label(),
+            renamed
+        );
+    }
+
+    #[test]
+    fn test_validation_scope() {
+        let ap = fixture_path("CIE-sig-CA.jpg");
+
+        let load = |scope: ValidationScope| {
+            let mut options = VerifyOptions::new();
+            options.set_scope(scope);
+            let mut report = DetailedStatusTracker::new();
+            let result = Store::load_from_asset_with_progress(
+                &ap,
+                true,
+                &options,
+                &mut report,
+                &ProgressMonitor::none(),
+            );
+            (result, report)
+        };
+        let labels_with =
+            |report: &DetailedStatusTracker, code: ValidationStatusCode| -> Vec<String> {
+                report
+                    .get_log()
+                    .iter()
+                    .filter(|i| i.validation_status.as_ref() == Some(&code))
+                    .filter_map(|i| jumbf::labels::manifest_label_from_uri(&i.label))
+                    .collect()
+            };
+
+        let (store, report) = load(ValidationScope::Full);
+        let store = store.expect("load_from_asset");
+        let active = store.provenance_label().expect("active manifest");
+        let signed = labels_with(&report, ValidationStatusCode::ClaimSignatureValidated);
+        assert_eq!(signed, vec![active.clone()]);
+        // the manifest of the ingredient has a bad signature
+        let mismatched = labels_with(&report, ValidationStatusCode::ClaimSignatureMismatch);
+        let ingredient = mismatched.first().cloned().expect("ingredient manifest");
+        assert_ne!(ingredient, active);
+
+        // the manifests of ingredients are not checked
+        let (result, report) = load(ValidationScope::ActiveManifest);
+        assert!(result.is_ok());
+        let signed = labels_with(&report, ValidationStatusCode::ClaimSignatureValidated);
+        assert_eq!(signed, vec![active.clone()]);
+        assert!(labels_with(&report, ValidationStatusCode::ClaimSignatureMismatch).is_empty());
+
+        // only the ingredient manifest is checked, without its data hash
+        let (_, report) = load(ValidationScope::Manifest(ingredient.clone()));
+        assert!(labels_with(&report, ValidationStatusCode::ClaimSignatureValidated).is_empty());
+        assert_eq!(
+            labels_with(&report, ValidationStatusCode::ClaimSignatureMismatch),
+            vec![ingredient]
         );
+        assert!(labels_with(&report, ValidationStatusCode::AssertionDataHashMatch).is_empty());
+
+        let (result, report) = load(ValidationScope::Manifest("missing".to_owned()));
+        assert!(result.is_err());
+        assert!(report_has_status(
+            report.get_log(),
+            ValidationStatusCode::ClaimMissing
+        ));
+
+        // only the data hash is checked, not the signature
+        let (result, report) = load(ValidationScope::HardBindings);
+        assert!(result.is_ok());
+        assert!(labels_with(&report, ValidationStatusCode::ClaimSignatureValidated).is_empty());
         assert_eq!(
-            errors[1].validation_status.as_deref(),
-            Some(validation_status::CLAIM_MISSING)
+            labels_with(&report, ValidationStatusCode::AssertionDataHashMatch),
+            vec![active]
+        );
+    }
+
+    #[test]
+    fn test_recover_manifest() {
+        struct TestRepository(Vec<u8>);
+
+        impl ManifestRepository for TestRepository {
+            fn find_by_content_hash(&self, _alg: &str, _hash: &[u8]) -> Result<Option<Vec<u8>>> {
+                Ok(Some(self.0.clone()))
+            }
+        }
+
+        struct EmptyRepository;
+
+        impl ManifestRepository for EmptyRepository {}
+
+        let jumbf = load_jumbf_from_file(&fixture_path("CA.jpg")).expect("load_jumbf_from_file");
+        let asset = std::fs::read(fixture_path("earth_apollo17.jpg")).expect("read asset");
+        let options = VerifyOptions::new();
+
+        // the manifest is recovered but the asset does not match its hard binding
+        let mut report = DetailedStatusTracker::new();
+        let store = Store::recover_manifest(&asset, &TestRepository(jumbf), &options, &mut report)
+            .expect("recover_manifest");
+        assert!(store.provenance_claim().is_some());
+        assert!(!report_split_errors(report.get_log_mut()).is_empty());
+
+        // an asset whose manifest store was stripped matches the hard binding of its manifest
+        let signed = std::fs::read(fixture_path("CA.jpg")).expect("read asset");
+        let mut jpeg = img_parts::jpeg::Jpeg::from_bytes(signed.into()).expect("parse jpeg");
+        jpeg.segments_mut()
+            .retain(|segment| segment.marker() != img_parts::jpeg::markers::APP11);
+        let mut stripped = Vec::new();
+        jpeg.encoder().write_to(&mut stripped).expect("write jpeg");
+        assert!(matches!(
+            crate::jumbf_io::load_jumbf_from_memory("jpg", &stripped),
+            Err(Error::JumbfNotFound)
+        ));
+
+        let jumbf = load_jumbf_from_file(&fixture_path("CA.jpg")).expect("load_jumbf_from_file");
+        let mut report = DetailedStatusTracker::new();
+        let store =
+            Store::recover_manifest(&stripped, &TestRepository(jumbf), &options, &mut report)
+                .expect("recover_manifest");
+        assert!(store.provenance_claim().is_some());
+        assert!(report_split_errors(report.get_log_mut()).is_empty());
+        assert!(report
+            .get_log()
+            .iter()
+            .any(|item| item.validation_status.as_ref()
+                == Some(&ValidationStatusCode::AssertionDataHashMatch)));
+
+        let result = Store::recover_manifest(
+            &asset,
+            &EmptyRepository,
+            &options,
+            &mut DetailedStatusTracker::new(),
         );
+        assert!(matches!(result, Err(Error::JumbfNotFound)));
+    }
+
+    #[test]
+    fn test_resource_limits() {
+        let jumbf =
+            load_jumbf_from_file(&fixture_path("CIE-sig-CA.jpg")).expect("load_jumbf_from_file");
+        let store = Store::from_jumbf_with_limits(
+            &jumbf,
+            &ResourceLimits::default(),
+            &mut DetailedStatusTracker::new(),
+        )
+        .expect("from_jumbf_with_limits");
+        let depth = store.ingredient_depth();
+        assert!(depth > 0);
+
+        let mut too_large = ResourceLimits::new();
+        too_large.set_max_manifest_size(jumbf.len() - 1);
+        let mut too_many = ResourceLimits::new();
+        too_many.set_max_assertions(0);
+        let mut too_deep = ResourceLimits::new();
+        too_deep.set_max_ingredient_depth(depth - 1);
+        let mut thumbnail_too_large = ResourceLimits::new();
+        thumbnail_too_large.set_max_thumbnail_size(1024);
+
+        for limits in [too_large, too_many, too_deep, thumbnail_too_large] {
+            let mut report = DetailedStatusTracker::new();
+            let result = Store::from_jumbf_with_limits(&jumbf, &limits, &mut report);
+            assert!(matches!(result, Err(Error::ResourceLimitExceeded(_))));
+            assert_eq!(
+                report.get_log()[0].validation_status.as_ref(),
+                Some(&ValidationStatusCode::ResourceLimitExceeded)
+            );
+        }
+
+        let mut too_nested = ResourceLimits::new();
+        too_nested.set_max_nesting(1);
+        let result =
+            Store::from_jumbf_with_limits(&jumbf, &too_nested, &mut DetailedStatusTracker::new());
+        assert!(matches!(result, Err(Error::JumbfParseError(_))));
+    }
+
+    #[test]
+    fn test_verify_trust() {
+        let ap = fixture_path("CIE-sig-CA.jpg");
+
+        let untrusted = |verify_trust: bool| {
+            let mut options = VerifyOptions::new();
+            options
+                .set_trust_config(crate::TrustConfig::new())
+                .set_verify_trust(verify_trust);
+            let mut report = DetailedStatusTracker::new();
+            let _result = Store::load_from_asset_with_progress(
+                &ap,
+                true,
+                &options,
+                &mut report,
+                &ProgressMonitor::none(),
+            );
+            report.get_log().iter().any(|i| {
+                i.validation_status.as_ref()
+                    == Some(&ValidationStatusCode::SigningCredentialUntrusted)
+            })
+        };
+
+        // an empty trust configuration trusts no signing credential
+        assert!(untrusted(true));
+        assert!(!untrusted(false));
     }
 
     /* enable when we enable OCSP validation
@@ -12,7 +12,7 @@
 // each license.
 
 use crate::asn1::rfc5652::CertificateChoices::Certificate;
-use crate::asn1::rfc5652::{SignedData, OID_ID_SIGNED_DATA};
+use crate::asn1::rfc5652::{ContentInfo, SignedData, OID_ID_SIGNED_DATA};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 
@@ -28,8 +28,27 @@ use x509_certificate::DigestAlgorithm::{self};
 
 use coset::{iana, sig_structure_data, HeaderBuilder, ProtectedHeader};
 
-#[allow(dead_code)]
-pub(crate) fn cose_countersign_data(data: &[u8], alg: &str) -> Vec<u8> {
+/// How the time-stamp of a claim signature is stored in its COSE_Sign1
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TimeStampStorage {
+    /// C2PA 1.x `sigTst` header, the time-stamp covers the claim with only the algorithm header
+    V1SigTst,
+    /// C2PA 2.x `sigTst2` header, the time-stamp covers the claim with the complete protected header
+    V2SigTst2,
+}
+
+impl TimeStampStorage {
+    // the label of the unprotected header holding the time-stamp
+    pub(crate) fn header_label(&self) -> &'static str {
+        match self {
+            TimeStampStorage::V1SigTst => "sigTst",
+            TimeStampStorage::V2SigTst2 => "sigTst2",
+        }
+    }
+}
+
+// the protected header v1 time-stamps are computed with, holding only the algorithm
+pub(crate) fn v1_countersign_header(alg: &str) -> ProtectedHeader {
     let alg_id = match alg {
         "ps256" => HeaderBuilder::new()
             .algorithm(iana::Algorithm::PS256)
@@ -49,6 +68,10 @@ pub(crate) fn cose_countersign_data(data: &[u8], alg: &str) -> Vec<u8> {
         "es512" => HeaderBuilder::new()
             .algorithm(iana::Algorithm::ES512)
             .build(),
+        #[cfg(feature = "es256k")]
+        "es256k" => HeaderBuilder::new()
+            .algorithm(iana::Algorithm::ES256K)
+            .build(),
         "ed25519" => HeaderBuilder::new()
             .algorithm(iana::Algorithm::EdDSA)
             .build(),
@@ -57,24 +80,31 @@ pub(crate) fn cose_countersign_data(data: &[u8], alg: &str) -> Vec<u8> {
             .build(),
     };
 
-    let p_header = ProtectedHeader {
+    ProtectedHeader {
         original_data: None,
         header: alg_id,
-    };
+    }
+}
+
+pub(crate) fn cose_countersign_data(data: &[u8], p_header: &ProtectedHeader) -> Vec<u8> {
     let aad: Vec<u8> = Vec::new();
 
     // create sig_structure_data to be signed
     sig_structure_data(
         coset::SignatureContext::CounterSignature,
-        p_header,
+        p_header.clone(),
         None,
         &aad,
         data,
     )
 }
 
-#[allow(dead_code)]
-pub(crate) fn cose_timestamp_countersign(data: &[u8], alg: &str, tsa_url: &str) -> Result<Vec<u8>> {
+#[cfg(feature = "file_io")]
+pub(crate) fn cose_timestamp_countersign(
+    data: &[u8],
+    p_header: &ProtectedHeader,
+    policy: &crate::TimeStampPolicy,
+) -> Result<Vec<u8>> {
     // create countersignature with TimeStampReq parameters
     // payload: data
     // context "CounterSigner"
@@ -82,16 +112,15 @@ pub(crate) fn cose_timestamp_countersign(data: &[u8], alg: &str, tsa_url: &str)
     // algorithm sha256
 
     // create sig data structure to be time stamped
-    let sd = cose_countersign_data(data, alg);
+    let sd = cose_countersign_data(data, p_header);
 
-    timestamp_data(tsa_url, &sd)
+    timestamp_data_with_policy(policy, &sd)
 }
 
-#[allow(dead_code)]
 pub(crate) fn cose_sigtst_to_tstinfos(
     sigtst_cbor: &[u8],
     data: &[u8],
-    alg: &str,
+    p_header: &ProtectedHeader,
 ) -> Result<Vec<TstInfo>> {
     let tst_container: TstContainer =
         serde_cbor::from_slice(sigtst_cbor).map_err(|_err| Error::CoseTimeStampGeneration)?;
@@ -99,7 +128,7 @@ pub(crate) fn cose_sigtst_to_tstinfos(
     let mut tstinfos: Vec<TstInfo> = Vec::new();
 
     for token in &tst_container.tst_tokens {
-        let tbs = cose_countersign_data(data, alg);
+        let tbs = cose_countersign_data(data, p_header);
         let tst_info = verify_timestamp(&token.val, &tbs)?;
         tstinfos.push(tst_info);
     }
@@ -124,10 +153,11 @@ pub fn get_ta_url() -> Option<String> {
 
 /// internal only function to work around bug in serialization of TimeStampResponse
 /// so we just return the data directly
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 fn time_stamp_request_http(
     url: &str,
     request: &crate::asn1::rfc3161::TimeStampReq,
+    timeout: Option<std::time::Duration>,
 ) -> Result<Vec<u8>> {
     use bcder::encode::Values;
     use std::io::Read;
@@ -142,8 +172,11 @@ fn time_stamp_request_http(
 
     let body_reader = std::io::Cursor::new(body);
 
-    let response = ureq::post(url)
-        .set("Content-Type", HTTP_CONTENT_TYPE_REQUEST)
+    let mut request_builder = ureq::post(url).set("Content-Type", HTTP_CONTENT_TYPE_REQUEST);
+    if let Some(timeout) = timeout {
+        request_builder = request_builder.timeout(timeout);
+    }
+    let response = request_builder
         .send(body_reader)
         .map_err(|_err| Error::CoseTimeStampGeneration)?;
 
@@ -195,17 +228,22 @@ fn time_stamp_message_http(
     url: &str,
     message: &[u8],
     digest_algorithm: DigestAlgorithm,
+    timeout: Option<std::time::Duration>,
 ) -> Result<Vec<u8>> {
-    use ring::rand::SecureRandom;
-
     let mut h = digest_algorithm.digester();
     h.update(message);
     let digest = h.finish();
 
     let mut random = [0u8; 8];
-    ring::rand::SystemRandom::new()
-        .fill(&mut random)
-        .map_err(|_| Error::CoseTimeStampGeneration)?;
+    #[cfg(not(target_os = "wasi"))]
+    {
+        use ring::rand::SecureRandom;
+        ring::rand::SystemRandom::new()
+            .fill(&mut random)
+            .map_err(|_| Error::CoseTimeStampGeneration)?;
+    }
+    #[cfg(target_os = "wasi")]
+    getrandom::getrandom(&mut random).map_err(|_| Error::CoseTimeStampGeneration)?;
 
     let request = crate::asn1::rfc3161::TimeStampReq {
         version: bcder::Integer::from(1_u8),
@@ -219,7 +257,21 @@ fn time_stamp_message_http(
         extensions: None,
     };
 
-    time_stamp_request_http(url, &request)
+    let start = instant::Instant::now();
+    let result = time_stamp_request_http(url, &request, timeout);
+    crate::metrics::metrics().tsa_request(start.elapsed(), result.is_ok());
+
+    result
+}
+
+// there is no HTTP client on WASI, so time-stamps cannot be requested
+#[cfg(all(feature = "file_io", target_os = "wasi"))]
+fn time_stamp_request_http(
+    _url: &str,
+    _request: &crate::asn1::rfc3161::TimeStampReq,
+    _timeout: Option<std::time::Duration>,
+) -> Result<Vec<u8>> {
+    Err(Error::CoseTimeStampGeneration)
 }
 
 pub struct TimeStampResponse(TimeStampResp);
@@ -287,12 +339,7 @@ impl TimeStampResponse {
 pub fn timestamp_data(url: &str, data: &[u8]) -> Result<Vec<u8>> {
     #[cfg(feature = "file_io")]
     {
-        let ts = time_stamp_message_http(url, data, x509_certificate::DigestAlgorithm::Sha256)?;
-
-        // sanity check
-        verify_timestamp(&ts, data)?;
-
-        Ok(ts)
+        timestamp_data_with_timeout(url, data, None)
     }
     #[cfg(not(feature = "file_io"))]
     {
@@ -300,6 +347,44 @@ pub fn timestamp_data(url: &str, data: &[u8]) -> Result<Vec<u8>> {
     }
 }
 
+#[cfg(feature = "file_io")]
+fn timestamp_data_with_timeout(
+    url: &str,
+    data: &[u8],
+    timeout: Option<std::time::Duration>,
+) -> Result<Vec<u8>> {
+    let ts = time_stamp_message_http(
+        url,
+        data,
+        x509_certificate::DigestAlgorithm::Sha256,
+        timeout,
+    )?;
+
+    // sanity check
+    verify_timestamp(&ts, data)?;
+
+    Ok(ts)
+}
+
+/// Generate TimeStamp for "data" from the first time authority of `policy` that returns one,
+/// retrying each authority as the policy allows before falling back to the next
+#[cfg(feature = "file_io")]
+pub(crate) fn timestamp_data_with_policy(
+    policy: &crate::TimeStampPolicy,
+    data: &[u8],
+) -> Result<Vec<u8>> {
+    let mut last_err = Error::CoseTimeStampAuthority;
+    for url in &policy.urls {
+        for _attempt in 0..=policy.retries {
+            match timestamp_data_with_timeout(url, data, policy.timeout) {
+                Ok(ts) => return Ok(ts),
+                Err(err) => last_err = err,
+            }
+        }
+    }
+    Err(last_err)
+}
+
 pub fn gt_to_datetime(
     gt: x509_certificate::asn1time::GeneralizedTime,
 ) -> chrono::DateTime<chrono::Utc> {
@@ -357,6 +442,91 @@ pub fn verify_timestamp(ts: &[u8], data: &[u8]) -> Result<TstInfo> {
     Ok(tst)
 }
 
+// DER bytes of the TimeStampToken, a CMS ContentInfo holding the signed TSTInfo
+pub(crate) fn timestamp_token_der(ts: &[u8]) -> Result<Vec<u8>> {
+    use bcder::encode::{self, Values};
+
+    let ts_resp = get_timestamp_response(ts)?;
+    let token = ts_resp
+        .time_stamp_token
+        .as_ref()
+        .ok_or(Error::CoseInvalidTimeStamp)?;
+
+    let mut der = Vec::new();
+    encode::sequence((
+        token.content_type.encode_ref(),
+        encode::sequence_as(bcder::Tag::CTX_0, &token.content),
+    ))
+    .write_encoded(bcder::Mode::Der, &mut der)
+    .map_err(|_e| Error::CoseInvalidTimeStamp)?;
+
+    Ok(der)
+}
+
+// DER bytes of the TimeStampToken stored in a time-stamp header, which may hold the
+// whole TimeStampResp or only its token
+pub(crate) fn stored_timestamp_token_der(val: &[u8]) -> Result<Vec<u8>> {
+    if get_timestamp_response(val).is_ok() {
+        return timestamp_token_der(val);
+    }
+
+    Constructed::decode(val, bcder::Mode::Der, |cons| {
+        cons.take_sequence(ContentInfo::from_sequence)
+    })
+    .map_err(|_e| Error::CoseInvalidTimeStamp)?;
+    Ok(val.to_vec())
+}
+
+/// Verifies the TSA signature of a TimeStampResp and that the TSA certificate chains
+/// to one of `trust_anchors`, DER encoded certificates, at the time of stamping
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub(crate) fn verify_timestamp_trust(ts: &[u8], trust_anchors: &[Vec<u8>]) -> Result<()> {
+    use openssl::{
+        cms::{CMSOptions, CmsContentInfo},
+        x509::{
+            store::X509StoreBuilder,
+            verify::{X509VerifyFlags, X509VerifyParam},
+            X509PurposeId, X509,
+        },
+    };
+
+    use crate::error::wrap_openssl_err;
+
+    let tst = get_timestamp_response(ts)?
+        .tst_info()?
+        .ok_or(Error::CoseInvalidTimeStamp)?;
+    let mut cms = CmsContentInfo::from_der(&timestamp_token_der(ts)?)
+        .map_err(|_e| Error::CoseInvalidTimeStamp)?;
+
+    let mut builder = X509StoreBuilder::new().map_err(wrap_openssl_err)?;
+    for anchor in trust_anchors {
+        let cert = X509::from_der(anchor).map_err(wrap_openssl_err)?;
+        builder.add_cert(cert).map_err(wrap_openssl_err)?;
+    }
+
+    // the TSA certificate may have expired since, so check the chain when the stamp was made;
+    // the trust list may hold the TSA certificates themselves rather than their roots
+    let mut param = X509VerifyParam::new().map_err(wrap_openssl_err)?;
+    param.set_time(gt_to_datetime(tst.gen_time).timestamp() as _);
+    param
+        .set_flags(X509VerifyFlags::PARTIAL_CHAIN)
+        .map_err(wrap_openssl_err)?;
+    builder.set_param(&param).map_err(wrap_openssl_err)?;
+    builder
+        .set_purpose(X509PurposeId::TIMESTAMP_SIGN)
+        .map_err(wrap_openssl_err)?;
+    let store = builder.build();
+
+    cms.verify(None, Some(&store), None, None, CMSOptions::BINARY)
+        .map_err(|_e| Error::CoseTimeStampUntrusted)
+}
+
+// checking the TSA signature needs OpenSSL, which is not available on WASI
+#[cfg(all(feature = "file_io", target_os = "wasi"))]
+pub(crate) fn verify_timestamp_trust(_ts: &[u8], _trust_anchors: &[Vec<u8>]) -> Result<()> {
+    Err(Error::CoseTimeStampUntrusted)
+}
+
 /// Get TimeStampResponse from DER TimeStampResp bytes
 pub fn get_timestamp_response(tsresp: &[u8]) -> Result<TimeStampResponse> {
     let ts = TimeStampResponse(
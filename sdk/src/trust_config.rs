@@ -0,0 +1,636 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Trust configuration used to decide whether a signing credential is trusted.
+//!
+//! A [`TrustConfig`] combines the files a verifier is configured with: PEM bundles of
+//! trust anchors such as the C2PA trust list and user trust bundles, an allowed list of
+//! end-entity certificates trusted on their own, and an extended key usage (EKU)
+//! configuration listing the EKUs a signing certificate must carry one of.
+
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, SystemTime},
+};
+
+use x509_parser::prelude::*;
+
+use crate::{hash_utils::hash_by_alg, Error, Result};
+
+// time allowed for fetching a trust configuration file from a URL
+#[cfg(not(target_arch = "wasm32"))]
+const URL_TIMEOUT: Duration = Duration::from_secs(30);
+
+// largest trust configuration file accepted from a URL
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_URL_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Where a trust configuration file is read from
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrustSource {
+    /// The contents of the file
+    Bytes(Vec<u8>),
+    /// A local file, re-read when it is modified
+    File(PathBuf),
+    /// A file fetched over HTTPS, re-fetched on every reload. Other schemes are rejected
+    /// since trust lists must not be open to tampering in transit.
+    Url(String),
+}
+
+impl TrustSource {
+    fn read(&self) -> Result<Vec<u8>> {
+        match self {
+            TrustSource::Bytes(bytes) => Ok(bytes.clone()),
+            TrustSource::File(path) => Ok(std::fs::read(path)?),
+            TrustSource::Url(url) => {
+                check_https(url)?;
+                fetch_url(url)
+            }
+        }
+    }
+
+    // the modification time of a file source, `None` for other sources
+    fn modified(&self) -> Option<SystemTime> {
+        match self {
+            TrustSource::File(path) => std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+            _ => None,
+        }
+    }
+}
+
+// trust configuration is only fetched over HTTPS
+fn check_https(url: &str) -> Result<()> {
+    if url
+        .get(..8)
+        .map_or(false, |scheme| scheme.eq_ignore_ascii_case("https://"))
+    {
+        Ok(())
+    } else {
+        Err(Error::BadParam(format!(
+            "trust configuration must be fetched over HTTPS: {}",
+            url
+        )))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn fetch_url(url: &str) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let response = ureq::get(url)
+        .timeout(URL_TIMEOUT)
+        .call()
+        .map_err(|e| Error::OtherError(Box::new(e)))?;
+    // a redirect must not leave HTTPS either
+    check_https(response.get_url())?;
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_URL_SIZE)
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn fetch_url(url: &str) -> Result<Vec<u8>> {
+    Err(Error::BadParam(format!(
+        "trust configuration cannot be fetched from {}",
+        url
+    )))
+}
+
+// which part of the configuration a source provides
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TrustKind {
+    Anchors,
+    AllowedList,
+    EkuConfig,
+}
+
+// the parsed contents of the configured sources
+#[derive(Clone, Debug, Default)]
+struct TrustData {
+    // DER encoded trust anchors
+    anchors: Vec<Vec<u8>>,
+    // SHA-256 hashes of the DER encoded certificates of the allowed list
+    allowed: HashSet<Vec<u8>>,
+    // dotted OIDs, any EKU is accepted when empty
+    ekus: Vec<String>,
+}
+
+impl TrustData {
+    fn add(&mut self, kind: TrustKind, bytes: &[u8]) -> Result<()> {
+        let (certs, lines) = parse_lines(bytes, kind)?;
+
+        match kind {
+            TrustKind::Anchors => self.anchors.extend(certs),
+            TrustKind::AllowedList => {
+                for der in certs {
+                    self.allowed.insert(hash_by_alg("sha256", &der, None));
+                }
+                for line in lines {
+                    let hash = base64::decode(&line)
+                        .ok()
+                        .filter(|hash| hash.len() == 32)
+                        .ok_or_else(|| config_error(kind, &line))?;
+                    self.allowed.insert(hash);
+                }
+            }
+            TrustKind::EkuConfig => {
+                for line in lines {
+                    if !is_oid(&line) {
+                        return Err(config_error(kind, &line));
+                    }
+                    self.ekus.push(line);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn config_error(kind: TrustKind, found: &str) -> Error {
+    let file = match kind {
+        TrustKind::Anchors => "trust anchors",
+        TrustKind::AllowedList => "allowed list",
+        TrustKind::EkuConfig => "EKU configuration",
+    };
+    Error::BadParam(format!("{}: invalid entry {}", file, found))
+}
+
+// split a configuration file into the DER of its PEM certificates and its other lines,
+// skipping blank lines and comments starting with `#` or `//`
+fn parse_lines(bytes: &[u8], kind: TrustKind) -> Result<(Vec<Vec<u8>>, Vec<String>)> {
+    let text = std::str::from_utf8(bytes).map_err(|_e| config_error(kind, "not UTF-8 text"))?;
+
+    let mut certs = Vec::new();
+    let mut lines = Vec::new();
+    let mut pem: Option<String> = None;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with("-----BEGIN") {
+            pem = Some(String::new());
+        } else if line.starts_with("-----END") {
+            let body = pem.take().ok_or_else(|| config_error(kind, line))?;
+            let der = base64::decode(&body).map_err(|_e| config_error(kind, line))?;
+            X509Certificate::from_der(&der).map_err(|_e| config_error(kind, line))?;
+            certs.push(der);
+        } else if let Some(body) = pem.as_mut() {
+            body.push_str(line);
+        } else if !(line.is_empty() || line.starts_with('#') || line.starts_with("//")) {
+            // text around PEM blocks in a bundle of anchors is descriptive only
+            if kind != TrustKind::Anchors {
+                lines.push(line.to_string());
+            }
+        }
+    }
+    if pem.is_some() {
+        return Err(config_error(kind, "unterminated PEM block"));
+    }
+
+    Ok((certs, lines))
+}
+
+fn is_oid(s: &str) -> bool {
+    let arcs: Vec<&str> = s.split('.').collect();
+    arcs.len() >= 2
+        && arcs
+            .iter()
+            .all(|arc| !arc.is_empty() && arc.bytes().all(|b| b.is_ascii_digit()))
+}
+
+// the dotted OIDs of the EKUs of a certificate
+#[cfg(feature = "file_io")]
+fn cert_ekus(cert: &X509Certificate) -> Vec<String> {
+    // the EKUs x509_parser reports as flags
+    const EKU_ANY: &str = "2.5.29.37.0";
+    const EKU_SERVER_AUTH: &str = "1.3.6.1.5.5.7.3.1";
+    const EKU_CLIENT_AUTH: &str = "1.3.6.1.5.5.7.3.2";
+    const EKU_CODE_SIGNING: &str = "1.3.6.1.5.5.7.3.3";
+    const EKU_EMAIL_PROTECTION: &str = "1.3.6.1.5.5.7.3.4";
+    const EKU_TIME_STAMPING: &str = "1.3.6.1.5.5.7.3.8";
+    const EKU_OCSP_SIGNING: &str = "1.3.6.1.5.5.7.3.9";
+
+    let eku = match cert.tbs_certificate.extended_key_usage() {
+        Some((_critical, eku)) => eku,
+        None => return Vec::new(),
+    };
+
+    let flags = [
+        (eku.any, EKU_ANY),
+        (eku.server_auth, EKU_SERVER_AUTH),
+        (eku.client_auth, EKU_CLIENT_AUTH),
+        (eku.code_signing, EKU_CODE_SIGNING),
+        (eku.email_protection, EKU_EMAIL_PROTECTION),
+        (eku.time_stamping, EKU_TIME_STAMPING),
+        (eku.ocsp_signing, EKU_OCSP_SIGNING),
+    ];
+    flags
+        .iter()
+        .filter(|(set, _oid)| *set)
+        .map(|(_set, oid)| oid.to_string())
+        .chain(eku.other.iter().map(|oid| oid.to_id_string()))
+        .collect()
+}
+
+struct TrustSources {
+    sources: Vec<(TrustKind, TrustSource)>,
+    // modification times of the file sources when they were last read
+    modified: Vec<Option<SystemTime>>,
+}
+
+struct TrustConfigInner {
+    sources: Mutex<TrustSources>,
+    data: RwLock<Arc<TrustData>>,
+}
+
+/// Trust anchors, allowed list and EKU configuration signing credentials are checked against.
+///
+/// Each part is loaded from [`TrustSource`]s: bytes, files or URLs. The configuration can be
+/// reloaded while in use, so a long-running verifier picks up updates of the trust list
+/// without restarting; a reload that fails keeps the previous configuration. Clones share
+/// the same configuration.
+///
+/// ```ignore
+/// let trust = TrustConfig::new();
+/// trust.add_trust_anchors(TrustSource::File("C2PA-TRUST-LIST.pem".into()))?;
+/// trust.add_trust_anchors(TrustSource::Bytes(std::fs::read("user-anchors.pem")?))?;
+/// trust.add_allowed_list(TrustSource::File("allowed.pem".into()))?;
+/// trust.add_eku_config(TrustSource::File("store.cfg".into()))?;
+/// trust.watch(std::time::Duration::from_secs(300));
+///
+/// let mut options = VerifyOptions::new();
+/// options.set_trust_config(trust);
+/// ```
+#[derive(Clone)]
+pub struct TrustConfig {
+    inner: Arc<TrustConfigInner>,
+}
+
+impl TrustConfig {
+    /// Create an empty configuration, which trusts no signing credential
+    pub fn new() -> Self {
+        TrustConfig {
+            inner: Arc::new(TrustConfigInner {
+                sources: Mutex::new(TrustSources {
+                    sources: Vec::new(),
+                    modified: Vec::new(),
+                }),
+                data: RwLock::new(Arc::new(TrustData::default())),
+            }),
+        }
+    }
+
+    /// Add the PEM encoded trust anchors of `source`, such as the C2PA trust list or a
+    /// user trust bundle. Signing certificates chaining to one of them are trusted.
+    pub fn add_trust_anchors(&self, source: TrustSource) -> Result<&Self> {
+        self.add_source(TrustKind::Anchors, source)
+    }
+
+    /// Add the allowed list of `source`: PEM encoded end-entity certificates and base64
+    /// encoded SHA-256 hashes of DER encoded certificates, one per line. These certificates
+    /// are trusted without checking their chain.
+    pub fn add_allowed_list(&self, source: TrustSource) -> Result<&Self> {
+        self.add_source(TrustKind::AllowedList, source)
+    }
+
+    /// Add the EKU configuration of `source`, one dotted OID per line. Signing certificates
+    /// that chain to a trust anchor must have one of these EKUs, any EKU is accepted when
+    /// no configuration is added.
+    pub fn add_eku_config(&self, source: TrustSource) -> Result<&Self> {
+        self.add_source(TrustKind::EkuConfig, source)
+    }
+
+    fn add_source(&self, kind: TrustKind, source: TrustSource) -> Result<&Self> {
+        let modified = source.modified();
+        let bytes = source.read()?;
+
+        let mut state = self
+            .inner
+            .sources
+            .lock()
+            .map_err(|_e| Error::BadParam("trust configuration unavailable".to_string()))?;
+        let mut data = self
+            .inner
+            .data
+            .write()
+            .map_err(|_e| Error::BadParam("trust configuration unavailable".to_string()))?;
+
+        let mut updated = (**data).clone();
+        updated.add(kind, &bytes)?;
+        *data = Arc::new(updated);
+
+        state.sources.push((kind, source));
+        state.modified.push(modified);
+        Ok(self)
+    }
+
+    /// Re-read every source and replace the configuration.
+    ///
+    /// The previous configuration is kept if a source cannot be read or parsed.
+    pub fn reload(&self) -> Result<()> {
+        let sources = match self.inner.sources.lock() {
+            Ok(state) => state.sources.clone(),
+            Err(_) => return Ok(()),
+        };
+
+        // read without holding the locks so validation is never blocked by the network
+        let mut modified = Vec::new();
+        let mut updated = TrustData::default();
+        for (kind, source) in &sources {
+            modified.push(source.modified());
+            updated.add(*kind, &source.read()?)?;
+        }
+
+        if let (Ok(mut state), Ok(mut data)) = (self.inner.sources.lock(), self.inner.data.write())
+        {
+            // sources added meanwhile are picked up by the next reload
+            if state.sources.len() == sources.len() {
+                state.modified = modified;
+                *data = Arc::new(updated);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reload the configuration if a file source was modified since it was read or if it
+    /// has URL sources. Returns whether the configuration was reloaded.
+    pub fn reload_if_modified(&self) -> Result<bool> {
+        let changed = match self.inner.sources.lock() {
+            Ok(state) => state.sources.iter().zip(state.modified.iter()).any(
+                |((_kind, source), modified)| {
+                    matches!(source, TrustSource::Url(_)) || source.modified() != *modified
+                },
+            ),
+            Err(_) => false,
+        };
+
+        if changed {
+            self.reload()?;
+        }
+        Ok(changed)
+    }
+
+    /// Check for modified sources every `interval` on a background thread, reloading the
+    /// configuration when they change. The thread stops once every clone of the
+    /// configuration is dropped.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn watch(&self, interval: Duration) {
+        let inner = Arc::downgrade(&self.inner);
+        std::thread::spawn(move || loop {
+            std::thread::sleep(interval);
+            let config = match inner.upgrade() {
+                Some(inner) => TrustConfig { inner },
+                None => break,
+            };
+            if let Err(e) = config.reload_if_modified() {
+                tracing::warn!("trust configuration reload failed: {}", e);
+            }
+        });
+    }
+
+    fn data(&self) -> Arc<TrustData> {
+        match self.inner.data.read() {
+            Ok(data) => data.clone(),
+            Err(_) => Arc::new(TrustData::default()),
+        }
+    }
+
+    /// The DER encoded trust anchors
+    pub fn trust_anchors(&self) -> Vec<Vec<u8>> {
+        self.data().anchors.clone()
+    }
+
+    /// The dotted OIDs of the EKUs accepted for signing certificates
+    pub fn ekus(&self) -> Vec<String> {
+        self.data().ekus.clone()
+    }
+
+    /// Returns whether the DER encoded certificate `cert` is on the allowed list
+    pub fn is_allowed(&self, cert: &[u8]) -> bool {
+        self.data()
+            .allowed
+            .contains(&hash_by_alg("sha256", cert, None))
+    }
+
+    /// Check that the signing certificate of `certs`, an x5chain starting with the signing
+    /// certificate, is trusted at `time` in seconds.
+    ///
+    /// Returns [`Error::CoseCertUntrusted`] if the certificate is not on the allowed list and
+    /// either does not chain to a trust anchor or lacks the configured EKUs.
+    #[cfg(feature = "file_io")]
+    pub(crate) fn check_certs(&self, certs: &[Vec<u8>], time: i64) -> Result<()> {
+        let data = self.data();
+        let signcert = certs.first().ok_or(Error::CoseX5ChainMissing)?;
+
+        if data
+            .allowed
+            .contains(&hash_by_alg("sha256", signcert, None))
+        {
+            return Ok(());
+        }
+
+        if !data.ekus.is_empty() {
+            let (_rem, cert) =
+                X509Certificate::from_der(signcert).map_err(|_e| Error::CoseInvalidCert)?;
+            if !cert_ekus(&cert).iter().any(|eku| data.ekus.contains(eku)) {
+                return Err(Error::CoseCertUntrusted);
+            }
+        }
+
+        verify_chain(certs, &data.anchors, time)
+    }
+}
+
+impl Default for TrustConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for TrustConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let data = self.data();
+        f.debug_struct("TrustConfig")
+            .field("trust_anchors", &data.anchors.len())
+            .field("allowed_list", &data.allowed.len())
+            .field("ekus", &data.ekus)
+            .finish()
+    }
+}
+
+impl PartialEq for TrustConfig {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+// verify that the signing certificate chains to one of `anchors` at `time`,
+// the anchors may be intermediate certificates
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+fn verify_chain(certs: &[Vec<u8>], anchors: &[Vec<u8>], time: i64) -> Result<()> {
+    use openssl::{
+        stack::Stack,
+        x509::{
+            store::X509StoreBuilder,
+            verify::{X509VerifyFlags, X509VerifyParam},
+            X509StoreContext, X509,
+        },
+    };
+
+    use crate::error::wrap_openssl_err;
+
+    let signcert = certs.first().ok_or(Error::CoseX5ChainMissing)?;
+    let signcert = X509::from_der(signcert).map_err(wrap_openssl_err)?;
+
+    let mut chain = Stack::new().map_err(wrap_openssl_err)?;
+    for der in certs.iter().skip(1) {
+        chain
+            .push(X509::from_der(der).map_err(wrap_openssl_err)?)
+            .map_err(wrap_openssl_err)?;
+    }
+
+    let mut builder = X509StoreBuilder::new().map_err(wrap_openssl_err)?;
+    for anchor in anchors {
+        builder
+            .add_cert(X509::from_der(anchor).map_err(wrap_openssl_err)?)
+            .map_err(wrap_openssl_err)?;
+    }
+    let mut param = X509VerifyParam::new().map_err(wrap_openssl_err)?;
+    param.set_time(time as _);
+    param
+        .set_flags(X509VerifyFlags::PARTIAL_CHAIN)
+        .map_err(wrap_openssl_err)?;
+    builder.set_param(&param).map_err(wrap_openssl_err)?;
+    let store = builder.build();
+
+    let mut context = X509StoreContext::new().map_err(wrap_openssl_err)?;
+    let verified = context
+        .init(&store, &signcert, &chain, |c| c.verify_cert())
+        .map_err(wrap_openssl_err)?;
+    if verified {
+        Ok(())
+    } else {
+        Err(Error::CoseCertUntrusted)
+    }
+}
+
+// checking the chain needs OpenSSL, which is not available on WASI
+#[cfg(all(feature = "file_io", target_os = "wasi"))]
+fn verify_chain(_certs: &[Vec<u8>], _anchors: &[Vec<u8>], _time: i64) -> Result<()> {
+    Err(Error::CoseCertUntrusted)
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test certificates use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use openssl::{pkey::PKey, rsa::Rsa};
+
+    use super::*;
+    use crate::utils::test::TestCert;
+
+    #[test]
+    fn test_trust_config() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let root = TestCert::new("Root", &key).ca(None).build();
+        let leaf = TestCert::new("Leaf", &key)
+            .issuer(&root, &key)
+            .email_protection()
+            .build();
+        let certs = vec![leaf.to_der().unwrap(), root.to_der().unwrap()];
+        let now = crate::cert_profile::now().unwrap();
+
+        // nothing is trusted by an empty configuration
+        let trust = TrustConfig::new();
+        assert!(matches!(
+            trust.check_certs(&certs, now),
+            Err(Error::CoseCertUntrusted)
+        ));
+
+        // trust anchors with descriptive text around them
+        let mut bundle = b"# C2PA trust list\nRoot\n".to_vec();
+        bundle.extend(root.to_pem().unwrap());
+        trust.add_trust_anchors(TrustSource::Bytes(bundle)).unwrap();
+        assert_eq!(trust.trust_anchors(), vec![root.to_der().unwrap()]);
+        trust.check_certs(&certs, now).unwrap();
+        assert!(trust
+            .check_certs(&certs[..1], now + 2 * 24 * 60 * 60)
+            .is_err());
+
+        // the signing certificate must have one of the configured EKUs
+        trust
+            .add_eku_config(TrustSource::Bytes(
+                b"// c2pa\n1.3.6.1.4.1.62558.2.1\n".to_vec(),
+            ))
+            .unwrap();
+        assert!(trust.check_certs(&certs, now).is_err());
+        trust
+            .add_eku_config(TrustSource::Bytes(b"1.3.6.1.5.5.7.3.4".to_vec()))
+            .unwrap();
+        assert_eq!(trust.ekus().len(), 2);
+        trust.check_certs(&certs, now).unwrap();
+        assert!(trust
+            .add_eku_config(TrustSource::Bytes(b"email".to_vec()))
+            .is_err());
+
+        // the allowed list trusts the certificate on its own
+        let allowed = TrustConfig::new();
+        let hash = base64::encode(hash_by_alg("sha256", &certs[0], None));
+        allowed
+            .add_allowed_list(TrustSource::Bytes(hash.into_bytes()))
+            .unwrap();
+        assert!(allowed.is_allowed(&certs[0]));
+        allowed.check_certs(&certs[..1], now).unwrap();
+        assert!(allowed
+            .add_allowed_list(TrustSource::Bytes(b"not a hash".to_vec()))
+            .is_err());
+
+        // trust lists are not fetched over plain HTTP
+        assert!(matches!(
+            allowed.add_trust_anchors(TrustSource::Url("http://example.com/anchors.pem".into())),
+            Err(Error::BadParam(_))
+        ));
+    }
+
+    #[test]
+    fn test_trust_config_reload() {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+        let root = TestCert::new("Root", &key).ca(None).build();
+        let other_root = TestCert::new("Other Root", &key).ca(None).build();
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("anchors.pem");
+        std::fs::write(&path, root.to_pem().unwrap()).unwrap();
+
+        let trust = TrustConfig::new();
+        trust
+            .add_trust_anchors(TrustSource::File(path.clone()))
+            .unwrap();
+        let shared = trust.clone();
+        assert_eq!(shared, trust);
+        assert!(!trust.reload_if_modified().unwrap());
+
+        std::fs::write(&path, other_root.to_pem().unwrap()).unwrap();
+        trust.reload().unwrap();
+        assert_eq!(shared.trust_anchors(), vec![other_root.to_der().unwrap()]);
+
+        // a source that no longer parses keeps the previous configuration
+        std::fs::write(&path, b"-----BEGIN CERTIFICATE-----\nbad\n").unwrap();
+        assert!(trust.reload().is_err());
+        assert_eq!(shared.trust_anchors(), vec![other_root.to_der().unwrap()]);
+    }
+}
@@ -0,0 +1,281 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Atomic replacement of output files.
+//!
+//! Outputs are written to a temporary file next to them and renamed into place once
+//! complete, so a failed or interrupted save never leaves a partial output behind.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use crate::{Error, Result};
+
+// marks the temporary files of a save, followed by a unique suffix and the extension
+const TEMP_MARKER: &str = ".c2pa-tmp-";
+
+// distinguishes the temporary files of concurrent saves within a process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// How a saved file is flushed to storage before it replaces the output
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FsyncPolicy {
+    /// Leave flushing to the operating system
+    None,
+    /// Flush the file contents before renaming it to the output
+    File,
+    /// Also flush the directory, so the rename itself survives a crash
+    FileAndDirectory,
+}
+
+impl Default for FsyncPolicy {
+    fn default() -> Self {
+        FsyncPolicy::File
+    }
+}
+
+/// A temporary file that atomically replaces an output file once it is complete.
+///
+/// The temporary file is removed if it is dropped without being committed.
+pub(crate) struct AtomicFile {
+    temp_path: PathBuf,
+    output_path: PathBuf,
+    committed: bool,
+}
+
+impl AtomicFile {
+    /// Create a temporary file in the directory of `output_path`.
+    ///
+    /// The file is created like any other new file, so it gets the default permissions
+    /// of the process rather than the private ones of a temporary file.
+    pub(crate) fn new(output_path: &Path) -> Result<Self> {
+        let stem = output_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| Error::BadParam("invalid output path".to_string()))?;
+        // keep the extension so the asset type is still recognized
+        let suffix = output_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|ext| format!(".{}", ext))
+            .unwrap_or_default();
+        let prefix = temp_prefix(stem);
+        let dir = parent_dir(output_path);
+
+        // a name can still be taken by a file left from a crashed process with the same id
+        let mut attempts = 0;
+        let temp_path = loop {
+            let nanos = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.subsec_nanos())
+                .unwrap_or_default();
+            let name = format!(
+                "{}{}-{}-{}{}",
+                prefix,
+                process::id(),
+                TEMP_COUNTER.fetch_add(1, Ordering::Relaxed),
+                nanos,
+                suffix
+            );
+            let path = dir.join(name);
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => break path,
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempts < 16 => {
+                    attempts += 1
+                }
+                Err(e) => return Err(Error::IoError(e)),
+            }
+        };
+
+        Ok(AtomicFile {
+            temp_path,
+            output_path: output_path.to_path_buf(),
+            committed: false,
+        })
+    }
+
+    /// The path of the temporary file to write
+    pub(crate) fn path(&self) -> &Path {
+        &self.temp_path
+    }
+
+    /// Flush the temporary file according to `policy` and rename it to the output.
+    /// An existing output keeps its permissions.
+    pub(crate) fn commit(mut self, policy: FsyncPolicy) -> Result<()> {
+        if let Ok(metadata) = fs::metadata(&self.output_path) {
+            fs::set_permissions(&self.temp_path, metadata.permissions())?;
+        }
+
+        if policy != FsyncPolicy::None {
+            fs::File::open(&self.temp_path)?.sync_all()?;
+        }
+
+        fs::rename(&self.temp_path, &self.output_path)?;
+        self.committed = true;
+
+        // directories cannot be opened for syncing on windows
+        if cfg!(unix) && policy == FsyncPolicy::FileAndDirectory {
+            fs::File::open(parent_dir(&self.output_path))?.sync_all()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Write `output_path` by calling `write` with the path of a temporary file, then
+/// renaming it to the output. The temporary file is removed if `write` fails.
+pub(crate) fn write_atomically<F>(output_path: &Path, policy: FsyncPolicy, write: F) -> Result<()>
+where
+    F: FnOnce(&Path) -> Result<()>,
+{
+    let file = AtomicFile::new(output_path)?;
+    write(file.path())?;
+    file.commit(policy)
+}
+
+/// Remove temporary files left in `dir` by saves that crashed or were killed.
+///
+/// Saves only ever remove their own temporary file, so this is left to applications
+/// to call, for instance when they start. Only files older than `older_than` are
+/// removed, so saves still in progress are not affected. Returns the number of files
+/// removed.
+pub fn remove_orphaned_temp_files<P: AsRef<Path>>(dir: P, older_than: Duration) -> Result<usize> {
+    let now = SystemTime::now();
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let is_temp = name.to_str().map_or(false, |name| {
+            name.starts_with('.') && name.contains(TEMP_MARKER)
+        });
+        if !is_temp {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        if metadata.is_file() && age >= older_than {
+            // another process may have cleaned it up already
+            if fs::remove_file(entry.path()).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    Ok(removed)
+}
+
+fn parent_dir(path: &Path) -> &Path {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    }
+}
+
+fn temp_prefix(stem: &str) -> String {
+    format!(".{}{}", stem, TEMP_MARKER)
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_write_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("image.jpg");
+        fs::write(&output, b"original").unwrap();
+
+        // a failed write leaves the output and no temporary file
+        let result = write_atomically(&output, FsyncPolicy::File, |temp| {
+            assert_eq!(temp.extension().unwrap(), "jpg");
+            fs::write(temp, b"partial")?;
+            Err(Error::OperationCancelled)
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read(&output).unwrap(), b"original");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        write_atomically(&output, FsyncPolicy::FileAndDirectory, |temp| {
+            fs::write(temp, b"updated").map_err(Error::IoError)
+        })
+        .unwrap();
+        assert_eq!(fs::read(&output).unwrap(), b"updated");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_write_atomically_keeps_output() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("image.jpg");
+        fs::write(&output, b"original").unwrap();
+        fs::set_permissions(&output, fs::Permissions::from_mode(0o640)).unwrap();
+        let permissions = fs::metadata(&output).unwrap().permissions();
+
+        // temporary files of other saves are left alone
+        let other = dir.path().join(format!("{}abc.jpg", temp_prefix("image")));
+        fs::write(&other, b"partial").unwrap();
+
+        write_atomically(&output, FsyncPolicy::File, |temp| {
+            fs::write(temp, b"updated").map_err(Error::IoError)
+        })
+        .unwrap();
+        assert_eq!(fs::metadata(&output).unwrap().permissions(), permissions);
+        assert!(other.exists());
+    }
+
+    #[test]
+    fn test_remove_orphaned_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let orphan = dir.path().join(format!("{}abc.jpg", temp_prefix("image")));
+        fs::write(&orphan, b"partial").unwrap();
+        fs::write(dir.path().join("image.jpg"), b"image").unwrap();
+
+        // recent temporary files may belong to a save in progress
+        assert_eq!(
+            remove_orphaned_temp_files(dir.path(), Duration::from_secs(60 * 60)).unwrap(),
+            0
+        );
+        assert_eq!(
+            remove_orphaned_temp_files(dir.path(), Duration::from_secs(0)).unwrap(),
+            1
+        );
+        assert!(!orphan.exists());
+        assert!(dir.path().join("image.jpg").exists());
+    }
+}
@@ -0,0 +1,252 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use std::io::Write;
+use std::io::{Cursor, Read};
+
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+use crate::jumbf::boxes::{BMFFBox, BrotliContentBox, JUMBFPaddingContentBox};
+use crate::{
+    error::{Error, Result},
+    jumbf::boxes::{
+        BoxReader, JUMBFSuperBox, JumbfParseError, CAI_COMPRESSED_MANIFEST_UUID, CAI_STORE_UUID,
+        CAI_UPDATE_MANIFEST_UUID,
+    },
+    ResourceLimits,
+};
+
+// size of the header of a box
+const BOX_HEADER_SIZE: usize = 8;
+
+// size of the buffers used while compressing and expanding
+const BUFFER_SIZE: usize = 4096;
+
+// log2 of the Brotli window size used to compress manifests
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+const BROTLI_LG_WINDOW: u32 = 22;
+
+/// How the manifests of a manifest store are compressed when it is written.
+///
+/// Compressed manifests are stored in the compressed manifest boxes of the C2PA
+/// specification, which only defines Brotli compression. They are expanded
+/// transparently when a manifest store is read.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ManifestCompression {
+    /// Manifests are not compressed
+    None,
+    /// Manifests are compressed with Brotli at `quality`, from 0 (fastest) to 11 (smallest)
+    Brotli { quality: u32 },
+}
+
+impl Default for ManifestCompression {
+    fn default() -> Self {
+        ManifestCompression::None
+    }
+}
+
+// Compress the bytes of a manifest box into a compressed manifest box with the same label.
+// When `padded_size` is set the box is padded to that size, so it can be written
+// again with a signature in place of the placeholder, and Error::CoseSigboxTooSmall
+// is returned if the compressed manifest does not fit.
+#[cfg(any(feature = "file_io", feature = "async_signer"))]
+pub(crate) fn compress_manifest(
+    label: &str,
+    manifest: &[u8],
+    quality: u32,
+    padded_size: Option<usize>,
+) -> Result<JUMBFSuperBox> {
+    // the payload is compressed and the brob box records its type
+    let payload = manifest
+        .get(BOX_HEADER_SIZE..)
+        .ok_or(Error::JumbfCreationError)?;
+    let mut writer =
+        brotli::CompressorWriter::new(Vec::new(), BUFFER_SIZE, quality, BROTLI_LG_WINDOW);
+    writer.write_all(payload)?;
+    let compressed = writer.into_inner();
+
+    let mut sbox = JUMBFSuperBox::new(label, Some(CAI_COMPRESSED_MANIFEST_UUID));
+    sbox.add_data_box(Box::new(BrotliContentBox::new(b"jumb", compressed)));
+
+    if let Some(padded_size) = padded_size {
+        // the padding box needs room for at least its header
+        let size = sbox.box_size()? as usize + BOX_HEADER_SIZE;
+        let padding = padded_size
+            .checked_sub(size)
+            .ok_or(Error::CoseSigboxTooSmall)?;
+        sbox.add_data_box(Box::new(JUMBFPaddingContentBox::new(padding)));
+    }
+
+    Ok(sbox)
+}
+
+// Replace the compressed manifest boxes in a manifest store with the manifests they hold.
+// Together the manifests can expand to at most the maximum manifest size of `limits`, so a
+// store of many small compressed boxes cannot expand further than a single one, and the
+// expanded manifests are read within the other limits.
+pub(crate) fn expand_compressed_manifests(
+    cai_block: &mut JUMBFSuperBox,
+    limits: &ResourceLimits,
+) -> Result<()> {
+    // the manifests are one level below the manifest store
+    let mut manifest_limits = *limits;
+    manifest_limits.set_max_nesting(limits.max_nesting.saturating_sub(1));
+
+    let mut remaining = limits.max_manifest_size;
+    for index in 0..cai_block.data_box_count() {
+        let manifest = match cai_block.data_box_as_superbox(index) {
+            Some(sbox) if sbox.desc_box().uuid() == CAI_COMPRESSED_MANIFEST_UUID => {
+                expand_manifest(sbox, &mut remaining, &manifest_limits)?
+            }
+            _ => continue,
+        };
+        cai_block.replace_data_box(index, Box::new(manifest));
+    }
+
+    Ok(())
+}
+
+// expand the manifest held by a compressed manifest box, taking its size from `remaining`
+fn expand_manifest(
+    sbox: &JUMBFSuperBox,
+    remaining: &mut usize,
+    limits: &ResourceLimits,
+) -> Result<JUMBFSuperBox> {
+    let brob = (0..sbox.data_box_count())
+        .find_map(|index| sbox.data_box_as_brob_box(index))
+        .ok_or(Error::JumbfBoxNotFound)?;
+    if brob.original_type() != b"jumb" {
+        return Err(JumbfParseError::InvalidBrobBox.into());
+    }
+
+    // restore the header of the manifest box once its size is known
+    let mut manifest_bytes = vec![0u8; BOX_HEADER_SIZE];
+    brotli::Decompressor::new(brob.data().as_slice(), BUFFER_SIZE)
+        .take((*remaining as u64).saturating_add(1))
+        .read_to_end(&mut manifest_bytes)
+        .map_err(|_| JumbfParseError::InvalidBrobBox)?;
+    *remaining = remaining.checked_sub(manifest_bytes.len()).ok_or_else(|| {
+        Error::ResourceLimitExceeded(
+            "compressed manifests expand to more than the maximum manifest size".to_string(),
+        )
+    })?;
+    let size = (manifest_bytes.len() as u32).to_be_bytes();
+    manifest_bytes.splice(0..BOX_HEADER_SIZE, size.iter().chain(b"jumb").copied());
+
+    let manifest = BoxReader::read_super_box_with_limits(&mut Cursor::new(manifest_bytes), limits)?;
+
+    // the compressed manifest must hold the manifest it is labeled with
+    let desc_box = manifest.desc_box();
+    if (desc_box.uuid() != CAI_STORE_UUID && desc_box.uuid() != CAI_UPDATE_MANIFEST_UUID)
+        || desc_box.label() != sbox.desc_box().label()
+    {
+        return Err(JumbfParseError::InvalidBrobBox.into());
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+#[cfg(feature = "file_io")]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::jumbf::boxes::{CAIStore, JUMBFCBORContentBox};
+
+    fn test_manifest(label: &str) -> Vec<u8> {
+        let mut manifest = CAIStore::new(label, false);
+        let mut claim = JUMBFSuperBox::new("c2pa.claim", None);
+        claim.add_data_box(Box::new(JUMBFCBORContentBox::new(vec![0x61; 4096])));
+        manifest.add_box(Box::new(claim));
+
+        let mut manifest_bytes = Vec::new();
+        manifest.write_box(&mut manifest_bytes).unwrap();
+        manifest_bytes
+    }
+
+    fn limits(max_manifest_size: usize) -> ResourceLimits {
+        let mut limits = ResourceLimits::new();
+        limits.set_max_manifest_size(max_manifest_size);
+        limits
+    }
+
+    fn manifest_store(manifest: JUMBFSuperBox) -> JUMBFSuperBox {
+        let mut cai_block = JUMBFSuperBox::new("c2pa", None);
+        cai_block.add_data_box(Box::new(manifest));
+        cai_block
+    }
+
+    #[test]
+    fn test_compress_and_expand() {
+        let original = test_manifest("test:urn:uuid:1");
+
+        let compressed = compress_manifest("test:urn:uuid:1", &original, 9, None).unwrap();
+        assert!((compressed.box_size().unwrap() as usize) < original.len() / 4);
+
+        let mut cai_block = manifest_store(compressed);
+        expand_compressed_manifests(&mut cai_block, &limits(1024 * 1024)).unwrap();
+
+        let mut expanded = Vec::new();
+        cai_block
+            .data_box_as_superbox(0)
+            .unwrap()
+            .write_box(&mut expanded)
+            .unwrap();
+        assert_eq!(expanded, original);
+    }
+
+    #[test]
+    fn test_padded_size() {
+        let manifest = test_manifest("test:urn:uuid:1");
+        let compressed = compress_manifest("test:urn:uuid:1", &manifest, 9, Some(1000)).unwrap();
+        assert_eq!(compressed.box_size().unwrap(), 1000);
+
+        assert!(matches!(
+            compress_manifest("test:urn:uuid:1", &manifest, 9, Some(20)),
+            Err(Error::CoseSigboxTooSmall)
+        ));
+    }
+
+    #[test]
+    fn test_expand_limits() {
+        let manifest = test_manifest("test:urn:uuid:1");
+        let compressed = compress_manifest("test:urn:uuid:1", &manifest, 9, None).unwrap();
+        let mut cai_block = manifest_store(compressed);
+        assert!(matches!(
+            expand_compressed_manifests(&mut cai_block, &limits(1024)),
+            Err(Error::ResourceLimitExceeded(_))
+        ));
+
+        // the limit applies to all the manifests of the store together
+        let two_manifests = || {
+            let mut cai_block =
+                manifest_store(compress_manifest("test:urn:uuid:1", &manifest, 9, None).unwrap());
+            let second = test_manifest("test:urn:uuid:2");
+            cai_block.add_data_box(Box::new(
+                compress_manifest("test:urn:uuid:2", &second, 9, None).unwrap(),
+            ));
+            cai_block
+        };
+        assert!(matches!(
+            expand_compressed_manifests(&mut two_manifests(), &limits(manifest.len() * 3 / 2)),
+            Err(Error::ResourceLimitExceeded(_))
+        ));
+        expand_compressed_manifests(&mut two_manifests(), &limits(manifest.len() * 2)).unwrap();
+
+        // the label must match the manifest it holds
+        let compressed = compress_manifest("test:urn:uuid:2", &manifest, 9, None).unwrap();
+        let mut cai_block = manifest_store(compressed);
+        assert!(expand_compressed_manifests(&mut cai_block, &limits(1024 * 1024)).is_err());
+    }
+}
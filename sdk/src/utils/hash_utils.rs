@@ -11,9 +11,22 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use std::ops::RangeInclusive;
-
-use log::{debug, warn};
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    ops::RangeInclusive,
+    path::Path,
+    sync::mpsc::sync_channel,
+    thread,
+};
+
+use tracing::{debug, warn};
+
+use crate::{
+    error::{wrap_io_err, Error, Result},
+    metrics::metrics,
+    progress::{ProgressMonitor, ProgressPhase},
+};
 use serde::{Deserialize, Serialize};
 
 // multihash versions
@@ -76,6 +89,29 @@ pub fn hash_by_type(hash_type: u8, data: &[u8]) -> Option<Multihash> {
     }
 }
 
+/// Options controlling how asset data is hashed.
+///
+/// SHA-2 digests are inherently sequential, so additional threads are used
+/// to overlap reading the asset with hashing rather than splitting the digest.
+/// Enable the `sha2_asm` feature for assembly/SIMD accelerated SHA-2.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HashingOptions {
+    /// Number of threads used while hashing a file.  When greater than one the
+    /// asset is read on a separate thread, with up to `threads - 1` chunks buffered.
+    pub threads: usize,
+    /// Size in bytes of each chunk read from the asset.
+    pub chunk_size: usize,
+}
+
+impl Default for HashingOptions {
+    fn default() -> Self {
+        HashingOptions {
+            threads: 1,
+            chunk_size: 256 * 1024,
+        }
+    }
+}
+
 enum Hasher {
     SHA256(Sha256),
     SHA384(Sha384),
@@ -83,6 +119,23 @@ enum Hasher {
 }
 
 impl Hasher {
+    // create a hasher for the desired algorithm
+    fn new(alg: &str) -> Hasher {
+        use Hasher::*;
+        match alg {
+            "sha256" => SHA256(Sha256::new()),
+            "sha384" => SHA384(Sha384::new()),
+            "sha512" => SHA512(Sha512::new()),
+            _ => {
+                warn!(
+                    "Unsupported hashing algorithm: {}, substituting sha256",
+                    alg
+                );
+                SHA256(Sha256::new())
+            }
+        }
+    }
+
     // update hash value with new data
     fn update(&mut self, data: &[u8]) {
         use Hasher::*;
@@ -106,33 +159,19 @@ impl Hasher {
     }
 }
 
-// return hash bytes for desired hashing algoritm
-pub fn hash_by_alg(alg: &str, data: &[u8], exclusions: Option<Vec<Exclusion>>) -> Vec<u8> {
-    use Hasher::*;
-    let mut hasher_enum = match alg {
-        "sha256" => SHA256(Sha256::new()),
-        "sha384" => SHA384(Sha384::new()),
-        "sha512" => SHA512(Sha512::new()),
-        _ => {
-            warn!(
-                "Unsupported hashing algorithm: {}, substituting sha256",
-                alg
-            );
-            SHA256(Sha256::new())
-        }
-    };
+// return the inclusive ranges of data_len bytes to be hashed after removing exclusions,
+// or None if the exclusions exceed the data length
+fn hash_ranges(
+    data_len: usize,
+    exclusions: Option<Vec<Exclusion>>,
+) -> Option<Vec<RangeInclusive<usize>>> {
+    if data_len == 0 {
+        return Some(Vec::new());
+    }
+    let data_end = data_len - 1;
 
     match exclusions {
-        Some(mut e) => {
-            // hash all content
-            if e.is_empty() {
-                // add the data
-                hasher_enum.update(data);
-
-                // return the hash
-                return Hasher::finalize(hasher_enum);
-            }
-
+        Some(mut e) if !e.is_empty() => {
             // hash data skipping excluded regions
             // sort the exclusions
             e.sort_by_key(|a| a.start());
@@ -140,38 +179,157 @@ pub fn hash_by_alg(alg: &str, data: &[u8], exclusions: Option<Vec<Exclusion>>) -
             // verify structure of blocks
             let num_blocks = e.len();
             let exclusion_end = e[num_blocks - 1].start() + e[num_blocks - 1].length();
-            let data_len = data.len();
-            let data_end = data_len - 1;
 
             // if not enough range we will just cacl to the end
             if data_len < exclusion_end {
                 debug!("the exclusion range exceed the data length");
-                return Vec::new();
+                return None;
             }
 
             //build final ranges
             let mut ranges = RangeSet::<[RangeInclusive<usize>; 1]>::from(0..=data_end);
             for exclusion in e {
+                if exclusion.length() == 0 {
+                    continue;
+                }
                 let end = exclusion.start() + exclusion.length() - 1;
                 ranges.remove_range(exclusion.start()..=end);
             }
 
-            // hash the data for ranges
-            for r in ranges.into_smallvec() {
-                hasher_enum.update(&data[r]);
-            }
+            Some(ranges.into_smallvec().into_vec())
+        }
+        // hash all content
+        _ => Some(vec![0..=data_end]),
+    }
+}
+
+// return hash bytes for desired hashing algoritm
+pub fn hash_by_alg(alg: &str, data: &[u8], exclusions: Option<Vec<Exclusion>>) -> Vec<u8> {
+    let mut hasher_enum = Hasher::new(alg);
+
+    let ranges = match hash_ranges(data.len(), exclusions) {
+        Some(r) => r,
+        None => return Vec::new(),
+    };
+
+    // hash the data for ranges
+    for r in ranges {
+        hasher_enum.update(&data[r]);
+    }
 
-            // return the hash
-            Hasher::finalize(hasher_enum)
+    // return the hash
+    Hasher::finalize(hasher_enum)
+}
+
+// read the requested ranges from a file in chunks, passing each chunk to f
+fn read_ranges<F>(
+    file: &mut File,
+    ranges: &[RangeInclusive<usize>],
+    chunk_size: usize,
+    mut f: F,
+) -> std::io::Result<()>
+where
+    F: FnMut(Vec<u8>) -> std::io::Result<()>,
+{
+    let chunk_size = std::cmp::max(chunk_size, 1);
+    for r in ranges {
+        file.seek(SeekFrom::Start(*r.start() as u64))?;
+        let mut remaining = r.end() - r.start() + 1;
+        while remaining > 0 {
+            let mut chunk = vec![0u8; std::cmp::min(remaining, chunk_size)];
+            file.read_exact(&mut chunk)?;
+            remaining -= chunk.len();
+            f(chunk)?;
         }
-        None => {
-            // add the data
-            hasher_enum.update(data);
+    }
+    Ok(())
+}
 
-            // return the hash
-            Hasher::finalize(hasher_enum)
+/// Return hash bytes for a file using the desired hashing algorithm.
+///
+/// The file is read in `options.chunk_size` blocks so large assets never need to be
+/// held in memory. If `options.threads` is greater than one, reading happens on a
+/// background thread so that file I/O overlaps with hashing.
+/// Progress is reported to `monitor` after each block, which may also cancel the operation.
+/// Returns an empty Vec if the exclusions exceed the file length.
+pub(crate) fn hash_file_by_alg(
+    alg: &str,
+    path: &Path,
+    exclusions: Option<Vec<Exclusion>>,
+    options: &HashingOptions,
+    monitor: &ProgressMonitor,
+) -> Result<Vec<u8>> {
+    let mut file = File::open(path).map_err(wrap_io_err)?;
+    let file_len = file.metadata().map_err(wrap_io_err)?.len() as usize;
+
+    let ranges = match hash_ranges(file_len, exclusions) {
+        Some(r) => r,
+        None => return Ok(Vec::new()),
+    };
+    let total = ranges
+        .iter()
+        .map(|r| (r.end() - r.start() + 1) as u64)
+        .sum();
+    let mut processed: u64 = 0;
+
+    let mut hasher_enum = Hasher::new(alg);
+    let start = instant::Instant::now();
+
+    monitor.update(ProgressPhase::Hashing, processed, total)?;
+
+    if options.threads <= 1 {
+        let mut result = Ok(());
+        let read_result = read_ranges(&mut file, &ranges, options.chunk_size, |chunk| {
+            hasher_enum.update(&chunk);
+            processed += chunk.len() as u64;
+            result = monitor.update(ProgressPhase::Hashing, processed, total);
+            match result {
+                Ok(_) => Ok(()),
+                Err(_) => Err(std::io::Error::new(
+                    std::io::ErrorKind::Interrupted,
+                    "hashing cancelled",
+                )),
+            }
+        });
+        // a cancellation takes precedence over the read error it causes
+        result?;
+        read_result.map_err(wrap_io_err)?;
+    } else {
+        // bounded so that the reader cannot get too far ahead of the hasher
+        let (tx, rx) = sync_channel::<Vec<u8>>(options.threads - 1);
+        let chunk_size = options.chunk_size;
+
+        let reader = thread::spawn(move || {
+            read_ranges(&mut file, &ranges, chunk_size, |chunk| {
+                tx.send(chunk).map_err(|_e| {
+                    std::io::Error::new(std::io::ErrorKind::BrokenPipe, "hasher stopped")
+                })
+            })
+        });
+
+        let mut result = Ok(());
+        for chunk in rx.iter() {
+            hasher_enum.update(&chunk);
+            processed += chunk.len() as u64;
+            result = monitor.update(ProgressPhase::Hashing, processed, total);
+            if result.is_err() {
+                // dropping the receiver stops the reader thread
+                break;
+            }
         }
+        drop(rx);
+
+        let read_result = reader
+            .join()
+            .map_err(|_e| Error::OtherError("hashing thread panicked".into()))?;
+        // a cancellation takes precedence over the reader error it causes
+        result?;
+        read_result.map_err(wrap_io_err)?;
     }
+
+    metrics().hash_completed(total, start.elapsed());
+
+    Ok(Hasher::finalize(hasher_enum))
 }
 
 // verify the hash using the specifiied alogrithm
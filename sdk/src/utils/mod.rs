@@ -11,12 +11,15 @@
 // specific language governing permissions and limitations under
 // each license.
 
+#[cfg(feature = "file_io")]
+pub(crate) mod atomic_file;
 pub(crate) mod cbor_types;
+pub(crate) mod compression;
 #[allow(dead_code)]
 pub(crate) mod hash_utils;
 #[allow(dead_code)] // for wasm build
 pub(crate) mod patch;
-#[cfg(feature = "file_io")]
+#[cfg_attr(not(feature = "file_io"), allow(dead_code))]
 pub(crate) mod thumbnail;
 pub(crate) mod time_it;
 #[allow(dead_code)] // for wasm builds
@@ -11,6 +11,9 @@
 // specific language governing permissions and limitations under
 // each license.
 
+#[cfg(feature = "file_io")]
+use std::io::{Read, Seek, SeekFrom, Write};
+
 use crate::error::{Error, Result};
 use twoway::find_bytes;
 
@@ -57,6 +60,59 @@ pub fn patch_file(
     Ok(splice_point)
 }
 
+// size of the chunks a stream is searched in
+#[cfg(feature = "file_io")]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/**
+Patch a sequence of bytes in a stream in place, without rewriting the rest of the stream
+stream - stream to be patched
+search_bytes - bytes to be replaced
+replace_bytes - replacement bytes, the same length as search_bytes
+This function only patches the first occurance
+returns the position where the patch was written
+*/
+#[cfg(feature = "file_io")]
+pub fn patch_stream<S: Read + Write + Seek + ?Sized>(
+    stream: &mut S,
+    search_bytes: &[u8],
+    replace_bytes: &[u8],
+) -> Result<u64> {
+    if search_bytes.is_empty() || search_bytes.len() != replace_bytes.len() {
+        return Err(Error::BadParam(
+            "patch must be the same length as the bytes it replaces".to_string(),
+        ));
+    }
+
+    stream.seek(SeekFrom::Start(0))?;
+    let mut window: Vec<u8> = Vec::with_capacity(CHUNK_SIZE + search_bytes.len());
+    let mut window_start = 0u64;
+    let mut chunk = vec![0u8; CHUNK_SIZE];
+    loop {
+        let len = stream.read(&mut chunk)?;
+        if len == 0 {
+            return Err(Error::NotFound);
+        }
+        window.extend_from_slice(&chunk[..len]);
+
+        if let Some(pos) = find_bytes(&window, search_bytes) {
+            let patch_start = window_start + pos as u64;
+            stream.seek(SeekFrom::Start(patch_start))?;
+            stream.write_all(replace_bytes)?;
+            stream.flush()?;
+            return Ok(patch_start);
+        }
+
+        // keep the end of the window, a match may start there
+        let keep = search_bytes.len() - 1;
+        if window.len() > keep {
+            let consumed = window.len() - keep;
+            window.drain(..consumed);
+            window_start += consumed as u64;
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     #![allow(clippy::unwrap_used)]
@@ -96,4 +152,29 @@ pub mod tests {
             String::from_utf8_lossy(&source_vec)
         );
     }
+
+    #[test]
+    #[cfg(feature = "file_io")]
+    fn test_patch_stream() {
+        // the bytes to patch straddle two chunks
+        let mut data = vec![0u8; CHUNK_SIZE * 2];
+        let start = CHUNK_SIZE - 3;
+        data[start..start + 8].copy_from_slice(b"sig hole");
+        let mut stream = std::io::Cursor::new(data);
+
+        assert_eq!(
+            patch_stream(&mut stream, b"sig hole", b"sig here").unwrap(),
+            start as u64
+        );
+        let data = stream.into_inner();
+        assert_eq!(data.len(), CHUNK_SIZE * 2);
+        assert_eq!(&data[start..start + 8], b"sig here");
+
+        let mut stream = std::io::Cursor::new(data);
+        assert!(matches!(
+            patch_stream(&mut stream, b"sig hole", b"sig here"),
+            Err(Error::NotFound)
+        ));
+        assert!(patch_stream(&mut stream, b"sig here", b"longer sig").is_err());
+    }
 }
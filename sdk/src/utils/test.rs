@@ -20,7 +20,27 @@ use crate::{
     store::Store,
     Result,
 };
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+use openssl::{
+    asn1::Asn1Time,
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{PKey, Private},
+    x509::{
+        extension::{BasicConstraints, ExtendedKeyUsage, KeyUsage},
+        X509Builder, X509Extension, X509NameBuilder, X509,
+    },
+};
 use std::path::PathBuf;
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    process::Command,
+};
 use tempfile::TempDir;
 
 pub const TEST_SMALL_JPEG: &str = "earth_apollo17.jpg";
@@ -165,6 +185,269 @@ pub fn temp_fixture_path(temp_dir: &TempDir, file_name: &str) -> PathBuf {
     fixture_copy
 }
 
+/// Builds certificates for tests, valid for a day and self-signed unless an issuer is set.
+/// Extensions are only added when asked for, so tests control exactly what a certificate has.
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub struct TestCert<'a> {
+    cn: &'a str,
+    key: &'a PKey<Private>,
+    issuer: Option<(&'a X509, &'a PKey<Private>)>,
+    ca: Option<Option<u32>>,
+    key_cert_sign: bool,
+    email_protection: bool,
+    time_stamping: bool,
+    ocsp_url: Option<&'a str>,
+}
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+impl<'a> TestCert<'a> {
+    /// A certificate for the common name `cn` and the public part of `key`
+    pub fn new(cn: &'a str, key: &'a PKey<Private>) -> Self {
+        TestCert {
+            cn,
+            key,
+            issuer: None,
+            ca: None,
+            key_cert_sign: false,
+            email_protection: false,
+            time_stamping: false,
+            ocsp_url: None,
+        }
+    }
+
+    /// Sign with `issuer_key`, naming `issuer` as the issuer
+    pub fn issuer(mut self, issuer: &'a X509, issuer_key: &'a PKey<Private>) -> Self {
+        self.issuer = Some((issuer, issuer_key));
+        self
+    }
+
+    /// Make a CA with critical basic constraints, limited to `path_len` if set
+    pub fn ca(mut self, path_len: Option<u32>) -> Self {
+        self.ca = Some(path_len);
+        self
+    }
+
+    /// Add a key usage allowing certificate signing
+    pub fn key_cert_sign(mut self) -> Self {
+        self.key_cert_sign = true;
+        self
+    }
+
+    /// Add the email protection extended key usage
+    pub fn email_protection(mut self) -> Self {
+        self.email_protection = true;
+        self
+    }
+
+    /// Add the critical time stamping extended key usage a time authority requires
+    pub fn time_stamping(mut self) -> Self {
+        self.time_stamping = true;
+        self
+    }
+
+    /// Add an authority information access extension naming an OCSP responder
+    pub fn ocsp_url(mut self, url: &'a str) -> Self {
+        self.ocsp_url = Some(url);
+        self
+    }
+
+    /// Sign the certificate
+    pub fn build(self) -> X509 {
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, self.cn).unwrap();
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        match self.issuer {
+            Some((cert, _)) => builder.set_issuer_name(cert.subject_name()).unwrap(),
+            None => builder.set_issuer_name(&name).unwrap(),
+        }
+        builder.set_pubkey(self.key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+
+        if let Some(path_len) = self.ca {
+            let mut basic_constraints = BasicConstraints::new();
+            basic_constraints.critical().ca();
+            if let Some(len) = path_len {
+                basic_constraints.pathlen(len);
+            }
+            builder
+                .append_extension(basic_constraints.build().unwrap())
+                .unwrap();
+        }
+        if self.key_cert_sign {
+            builder
+                .append_extension(KeyUsage::new().key_cert_sign().build().unwrap())
+                .unwrap();
+        }
+        if self.email_protection {
+            builder
+                .append_extension(ExtendedKeyUsage::new().email_protection().build().unwrap())
+                .unwrap();
+        }
+        if self.time_stamping {
+            builder
+                .append_extension(
+                    ExtendedKeyUsage::new()
+                        .critical()
+                        .time_stamping()
+                        .build()
+                        .unwrap(),
+                )
+                .unwrap();
+        }
+        if let Some(url) = self.ocsp_url {
+            #[allow(deprecated)]
+            let aia =
+                X509Extension::new_nid(None, None, Nid::INFO_ACCESS, &format!("OCSP;URI:{}", url))
+                    .unwrap();
+            builder.append_extension(aia).unwrap();
+        }
+
+        builder
+            .sign(
+                self.issuer.map_or(self.key, |(_, key)| key),
+                MessageDigest::sha256(),
+            )
+            .unwrap();
+        builder.build()
+    }
+}
+
+/// A time authority on a local port for tests, answering RFC 3161 requests
+/// with responses made by `openssl ts -reply`.
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+pub struct TestTsa {
+    url: String,
+    _dir: TempDir,
+}
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+impl TestTsa {
+    /// Start a time authority that answers up to `requests` requests
+    pub fn start(requests: usize) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let cert = TestCert::new("test tsa", &key).time_stamping().build();
+        std::fs::write(dir.path().join("tsa.pem"), cert.to_pem().unwrap()).unwrap();
+        std::fs::write(
+            dir.path().join("tsa.key"),
+            key.private_key_to_pem_pkcs8().unwrap(),
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("tsa.cnf"),
+            format!(
+                "[ tsa ]\ndefault_tsa = tsa_config\n[ tsa_config ]\nserial = {}\n\
+                signer_digest = sha256\ndefault_policy = 1.2.3.4.1\ndigests = sha256\n",
+                dir.path().join("serial").display()
+            ),
+        )
+        .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tsa", listener.local_addr().unwrap());
+        let dir_path = dir.path().to_path_buf();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(requests).flatten() {
+                // a failed answer drops the connection, which fails the request
+                let _ = answer_tsa_request(stream, &dir_path);
+            }
+        });
+
+        TestTsa { url, _dir: dir }
+    }
+
+    /// The URL to request time-stamps from
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+fn answer_tsa_request(mut stream: TcpStream, dir: &Path) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut content_length = 0;
+    let mut chunked = false;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end().to_ascii_lowercase();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(len) = header.strip_prefix("content-length:") {
+            content_length = len.trim().parse().unwrap_or(0);
+        } else if header.starts_with("transfer-encoding:") && header.contains("chunked") {
+            chunked = true;
+        }
+    }
+
+    let mut query = Vec::new();
+    if chunked {
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line)?;
+            let size = line.trim_end().split(';').next().unwrap_or_default();
+            let size = usize::from_str_radix(size, 16).unwrap_or(0);
+            if size == 0 {
+                break;
+            }
+            let mut chunk = vec![0; size + 2]; // the chunk ends with a CRLF
+            reader.read_exact(&mut chunk)?;
+            query.extend_from_slice(&chunk[..size]);
+        }
+    } else {
+        query.resize(content_length, 0);
+        reader.read_exact(&mut query)?;
+    }
+
+    let query_path = dir.join("query.tsq");
+    let reply_path = dir.join("reply.tsr");
+    std::fs::write(&query_path, &query)?;
+    let output = Command::new("openssl")
+        .arg("ts")
+        .arg("-reply")
+        .arg("-config")
+        .arg(dir.join("tsa.cnf"))
+        .arg("-queryfile")
+        .arg(&query_path)
+        .arg("-signer")
+        .arg(dir.join("tsa.pem"))
+        .arg("-inkey")
+        .arg(dir.join("tsa.key"))
+        .arg("-out")
+        .arg(&reply_path)
+        .output()?;
+    if !output.status.success() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+
+    let reply = std::fs::read(&reply_path)?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/timestamp-reply\r\n\
+        Content-Length: {}\r\nConnection: close\r\n\r\n",
+        reply.len()
+    )?;
+    stream.write_all(&reply)
+}
+
 #[test]
 fn test_create_test_store() {
     #[allow(clippy::expect_used)]
@@ -11,20 +11,153 @@
 // specific language governing permissions and limitations under
 // each license.
 
-use crate::Result;
+use std::io::{BufReader, Read};
+
+use crate::{asset_io::CAIRead, Error, Result};
 use image::{GenericImageView, ImageFormat};
 
-///  utility to generate a thumbnail from a file at path
+/// The image format of generated thumbnails
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ThumbnailFormat {
+    /// PNG for PNG sources to keep transparency, otherwise JPEG
+    Auto,
+    /// Always generate JPEG thumbnails
+    Jpeg,
+    /// Always generate PNG thumbnails
+    Png,
+}
+
+/// Options for generating thumbnails
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThumbnailOptions {
+    /// Maximum edge size of the thumbnail in pixels, larger images are scaled down
+    pub longest_edge: u32,
+    /// The image format of the thumbnail
+    pub format: ThumbnailFormat,
+    /// JPEG quality from 1 to 100
+    pub jpeg_quality: u8,
+}
+
+impl Default for ThumbnailOptions {
+    fn default() -> Self {
+        ThumbnailOptions {
+            longest_edge: 1024,
+            format: ThumbnailFormat::Auto,
+            jpeg_quality: 80,
+        }
+    }
+}
+
+/// Generates the thumbnails stored in claims and ingredients.
+///
+/// Implement this to render thumbnails with a GPU, an external service
+/// or for formats the built in [`ImageThumbnailGenerator`] does not handle.
+pub trait ThumbnailGenerator: Send + Sync {
+    /// Generate a thumbnail for an asset of `format`, a MIME type or extension.
+    ///
+    /// Returns the MIME type and bytes of the thumbnail, or `None` if the format is not supported.
+    fn generate(&self, format: &str, data: &[u8]) -> Result<Option<(String, Vec<u8>)>>;
+
+    /// Generate a thumbnail for an asset of `format` read from `stream`.
+    ///
+    /// The default reads the whole asset into memory and calls [`ThumbnailGenerator::generate`],
+    /// implement this to decode large assets without buffering them.
+    fn generate_from_stream(
+        &self,
+        format: &str,
+        stream: &mut dyn CAIRead,
+    ) -> Result<Option<(String, Vec<u8>)>> {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data)?;
+        self.generate(format, &data)
+    }
+}
+
+impl std::fmt::Debug for dyn ThumbnailGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ThumbnailGenerator")
+    }
+}
+
+/// The default thumbnail generator, scales images down and encodes them as JPEG or PNG
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageThumbnailGenerator {
+    /// Size and format of the generated thumbnails
+    pub options: ThumbnailOptions,
+}
+
+impl ImageThumbnailGenerator {
+    /// Create a generator using `options`
+    pub fn new(options: ThumbnailOptions) -> Self {
+        ImageThumbnailGenerator { options }
+    }
+}
+
+impl ThumbnailGenerator for ImageThumbnailGenerator {
+    fn generate(&self, format: &str, data: &[u8]) -> Result<Option<(String, Vec<u8>)>> {
+        unsupported_as_none(make_thumbnail_from_memory(format, data, &self.options))
+    }
+
+    fn generate_from_stream(
+        &self,
+        format: &str,
+        stream: &mut dyn CAIRead,
+    ) -> Result<Option<(String, Vec<u8>)>> {
+        unsupported_as_none(make_thumbnail_from_stream(format, stream, &self.options))
+    }
+}
+
+// formats that cannot be decoded do not get a thumbnail
+fn unsupported_as_none(thumbnail: Result<(String, Vec<u8>)>) -> Result<Option<(String, Vec<u8>)>> {
+    match thumbnail {
+        Ok(thumbnail) => Ok(Some(thumbnail)),
+        Err(Error::UnsupportedType) | Err(Error::ImageError(image::ImageError::Unsupported(_))) => {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+// the image format of an asset `format`, a MIME type or extension
+fn image_format(format: &str) -> Result<ImageFormat> {
+    let extension = match format {
+        "image/vnd.microsoft.icon" => "ico",
+        f => f.trim_start_matches("image/"),
+    };
+    ImageFormat::from_extension(extension).ok_or(Error::UnsupportedType)
+}
+
+/// Generate a thumbnail from an in memory asset of `format`, a MIME type or extension
 /// returns Result (format, image_bits) if successful, otherwise Error
-pub fn make_thumbnail(path: &std::path::Path) -> Result<(String, Vec<u8>)> {
-    let format = ImageFormat::from_path(path)?;
+pub fn make_thumbnail_from_memory(
+    format: &str,
+    data: &[u8],
+    options: &ThumbnailOptions,
+) -> Result<(String, Vec<u8>)> {
+    let format = image_format(format)?;
+    let img = image::load_from_memory_with_format(data, format)?;
+    encode_thumbnail(img, format, options)
+}
 
-    // max edge size allowed in pixels for thumbnail creation
-    const THUMBNAIL_LONGEST_EDGE: u32 = 1024;
-    const THUMBNAIL_JPEG_QUALITY: u8 = 80; // JPEG quality 1-100
+/// Generate a thumbnail from an asset of `format`, a MIME type or extension, decoding it
+/// from `stream` without reading it into memory first.
+/// Nothing is read if the format is not supported.
+pub fn make_thumbnail_from_stream(
+    format: &str,
+    stream: &mut dyn CAIRead,
+    options: &ThumbnailOptions,
+) -> Result<(String, Vec<u8>)> {
+    let format = image_format(format)?;
+    let img = image::load(BufReader::new(stream), format)?;
+    encode_thumbnail(img, format, options)
+}
 
-    let mut img = image::open(path)?;
-    let longest_edge = THUMBNAIL_LONGEST_EDGE;
+fn encode_thumbnail(
+    mut img: image::DynamicImage,
+    format: ImageFormat,
+    options: &ThumbnailOptions,
+) -> Result<(String, Vec<u8>)> {
+    let longest_edge = options.longest_edge;
 
     // generate a thumbnail image scaled down and in jpeg format
     if img.width() > longest_edge || img.height() > longest_edge {
@@ -33,10 +166,15 @@ pub fn make_thumbnail(path: &std::path::Path) -> Result<(String, Vec<u8>)> {
 
     // for png files, use png thumbnails for transparency
     // for other supported types try a jpeg thumbnail
-    let (output_format, content_type) = match format {
-        ImageFormat::Png => (image::ImageOutputFormat::Png, "image/png"),
-        _ => (
-            image::ImageOutputFormat::Jpeg(THUMBNAIL_JPEG_QUALITY),
+    let use_png = match options.format {
+        ThumbnailFormat::Auto => format == ImageFormat::Png,
+        ThumbnailFormat::Jpeg => false,
+        ThumbnailFormat::Png => true,
+    };
+    let (output_format, content_type) = match use_png {
+        true => (image::ImageOutputFormat::Png, "image/png"),
+        false => (
+            image::ImageOutputFormat::Jpeg(options.jpeg_quality),
             "image/jpeg",
         ),
     };
@@ -15,16 +15,67 @@ use crate::{
     asset_io::CAIRead, jumbf_io::get_cailoader_handler, utils::hash_utils::vec_compare, Error,
     Result,
 };
-use log::error;
 use quick_xml::{
     events::{BytesEnd, BytesStart, Event},
     Reader, Writer,
 };
 use std::io::Cursor;
+use tracing::error;
 
 const RDF_DESCRIPTION: &[u8] = b"rdf:Description";
 
-#[derive(Default)]
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16_BE_BOM: &[u8] = &[0xfe, 0xff];
+const UTF16_LE_BOM: &[u8] = &[0xff, 0xfe];
+
+/// Decode a raw XMP packet into a String.
+///
+/// XMP packets may be written as UTF-8 or UTF-16 (either endianness), with or without
+/// a byte order mark, and are often followed by NUL or whitespace padding.  Some writers
+/// also produce invalid UTF-8 sequences in values that are unrelated to provenance, so
+/// those are replaced rather than rejecting the whole packet.
+pub fn decode_xmp_packet(bytes: &[u8]) -> String {
+    let decoded = if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        String::from_utf8_lossy(rest).into_owned()
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_BE_BOM) {
+        decode_utf16(rest, true)
+    } else if let Some(rest) = bytes.strip_prefix(UTF16_LE_BOM) {
+        decode_utf16(rest, false)
+    } else if bytes.len() >= 2 && bytes[0] == 0 && bytes[1] != 0 {
+        // no BOM, but the leading '<' is encoded as 16 bit big endian
+        decode_utf16(bytes, true)
+    } else if bytes.len() >= 2 && bytes[0] != 0 && bytes[1] == 0 {
+        // no BOM, but the leading '<' is encoded as 16 bit little endian
+        decode_utf16(bytes, false)
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    };
+
+    // remove the BOM if it was encoded in the packet itself and any trailing padding
+    let trimmed = decoded
+        .trim_start_matches('\u{feff}')
+        .trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+
+    // skip anything that precedes the start of the packet
+    let start = trimmed.find('<').unwrap_or(0);
+    trimmed[start..].to_string()
+}
+
+// decode UTF-16 bytes, ignoring a trailing odd byte and replacing invalid sequences
+fn decode_utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| {
+        if big_endian {
+            u16::from_be_bytes([pair[0], pair[1]])
+        } else {
+            u16::from_le_bytes([pair[0], pair[1]])
+        }
+    });
+    std::char::decode_utf16(units)
+        .map(|r| r.unwrap_or(std::char::REPLACEMENT_CHARACTER))
+        .collect()
+}
+
+#[derive(Clone, Default)]
 pub struct XmpInfo {
     pub document_id: Option<String>,
     pub instance_id: Option<String>,
@@ -53,6 +104,10 @@ impl XmpInfo {
 
 /// Extract an a value from XMP using a key
 fn extract_xmp_key(xmp: &str, key: &str) -> Option<String> {
+    // tolerate a BOM or leading garbage before the packet
+    let xmp = xmp.trim_start_matches('\u{feff}');
+    let xmp = &xmp[xmp.find('<')?..];
+
     let mut reader = Reader::from_str(xmp);
     reader.trim_text(true);
     let mut buf = Vec::new();
@@ -83,6 +138,8 @@ fn extract_xmp_key(xmp: &str, key: &str) -> Option<String> {
                 }
             }
             Ok(Event::Eof) => break,
+            // malformed xml, stop rather than risk looping on the same error
+            Err(_) => break,
             _ => {}
         }
         buf.clear();
@@ -217,6 +274,76 @@ mod tests {
         assert_eq!(bad_xmp, None);
     }
 
+    #[test]
+    fn read_xmp_encodings() {
+        // UTF-8 with BOM and NUL padding
+        let mut packet = UTF8_BOM.to_vec();
+        packet.extend_from_slice(XMP_DATA.as_bytes());
+        packet.extend_from_slice(&[0u8; 16]);
+        let xmp = decode_xmp_packet(&packet);
+        assert_eq!(extract_provenance(&xmp), Some(PROVENANCE.to_owned()));
+
+        // UTF-16 in both byte orders, with and without a BOM
+        let units: Vec<u16> = XMP_DATA.encode_utf16().collect();
+        let be: Vec<u8> = units.iter().flat_map(|u| u.to_be_bytes()).collect();
+        let le: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+        for (bom, body) in [
+            (UTF16_BE_BOM, &be),
+            (UTF16_LE_BOM, &le),
+            (&[][..], &be),
+            (&[][..], &le),
+        ] {
+            let mut packet = bom.to_vec();
+            packet.extend_from_slice(body);
+            let xmp = decode_xmp_packet(&packet);
+            assert_eq!(extract_provenance(&xmp), Some(PROVENANCE.to_owned()));
+        }
+
+        // leading junk before the packet and provenance stored as an element
+        let element_xmp = r#"  junk<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+            <rdf:Description rdf:about="" xmlns:dcterms="http://purl.org/dc/terms/">
+            <dcterms:provenance>self#jumbf=c2pa</dcterms:provenance>
+            </rdf:Description></rdf:RDF></x:xmpmeta>"#;
+        let xmp = decode_xmp_packet(element_xmp.as_bytes());
+        assert_eq!(extract_provenance(&xmp), Some("self#jumbf=c2pa".to_owned()));
+        assert_eq!(
+            extract_provenance(element_xmp),
+            Some("self#jumbf=c2pa".to_owned())
+        );
+    }
+
+    #[test]
+    fn fuzz_xmp_no_panic() {
+        // simple deterministic xorshift so the test does not need extra dependencies
+        let mut seed: u32 = 0x2545_f491;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            seed
+        };
+
+        let source = XMP_DATA.as_bytes();
+        for _ in 0..2000 {
+            let mut packet = source.to_vec();
+
+            // truncate and corrupt random bytes
+            let len = (next() as usize) % (packet.len() + 1);
+            packet.truncate(len);
+            for _ in 0..(next() % 8) {
+                if !packet.is_empty() {
+                    let pos = (next() as usize) % packet.len();
+                    packet[pos] = next() as u8;
+                }
+            }
+
+            let xmp = decode_xmp_packet(&packet);
+            let _ = extract_provenance(&xmp);
+            let _ = extract_document_id(&xmp);
+            let _ = extract_instance_id(&xmp);
+        }
+    }
+
     #[test]
     fn add_xmp() {
         let xmp = add_provenance(XMP_DATA, PROVENANCE).expect("adding provenance");
@@ -0,0 +1,226 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! An asset that is read and validated once, so the results can be reused.
+
+use std::{io::Read, path::Path};
+
+use tracing::debug;
+
+use crate::{
+    error::wrap_io_err,
+    jumbf_io::load_jumbf_from_memory,
+    status_tracker::{DetailedStatusTracker, StatusTracker},
+    store::Store,
+    validation_status::{status_for_store, ValidationStatus},
+    xmp_inmemory_utils::XmpInfo,
+    Error, Ingredient, ManifestStore, Result,
+};
+
+/// An asset that has been read and validated.
+///
+/// The hash of the asset, its parsed manifest store and the validation status are
+/// computed once and carried forward, so an asset that is verified before it is added
+/// as an ingredient is not hashed and validated a second time.
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use c2pa::{Ingredient, IngredientOptions, ValidatedAsset};
+/// # fn main() -> Result<()> {
+/// let asset = ValidatedAsset::from_file("tests/fixtures/CA.jpg")?;
+/// if let Some(manifest_store) = asset.manifest_store() {
+///     println!("{}", manifest_store);
+/// }
+/// let parent = Ingredient::from_validated_asset(&asset, &IngredientOptions::default());
+/// # Ok(())
+/// # }
+/// ```
+pub struct ValidatedAsset {
+    title: String,
+    format: String,
+    data: Vec<u8>,
+    hash: Option<String>,
+    xmp_info: XmpInfo,
+    store: Option<Store>,
+    manifest_store: Option<ManifestStore>,
+    manifest_data: Option<Vec<u8>>,
+    validation_status: Option<Vec<ValidationStatus>>,
+}
+
+impl ValidatedAsset {
+    /// Reads and validates the asset at `path`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let (title, _, format) = Ingredient::get_path_info(path);
+        if !path.exists() {
+            return Err(Error::FileNotFound(title));
+        }
+
+        let data = std::fs::read(path).map_err(wrap_io_err)?;
+        Ok(Self::new(&title, &format, data, true))
+    }
+
+    /// Reads and validates an asset held in memory.
+    /// `format` is the MIME type or extension of the asset.
+    pub fn from_bytes(format: &str, data: Vec<u8>) -> Self {
+        Self::new("untitled", format, data, true)
+    }
+
+    /// Reads and validates the asset in `stream`.
+    /// `format` is the MIME type or extension of the asset.
+    pub fn from_stream(format: &str, stream: &mut dyn Read) -> Result<Self> {
+        let mut data = Vec::new();
+        stream.read_to_end(&mut data).map_err(wrap_io_err)?;
+        Ok(Self::from_bytes(format, data))
+    }
+
+    // validate the asset in `data`, hashing it only if `make_hash` is set
+    pub(crate) fn new(title: &str, format: &str, data: Vec<u8>, make_hash: bool) -> Self {
+        let format = Ingredient::format_from_extension(format);
+
+        let hash = make_hash.then(|| blake3::hash(&data).to_hex().as_str().to_owned());
+        let xmp_info = XmpInfo::from_source(&mut std::io::Cursor::new(data.as_slice()), &format);
+
+        let mut asset = ValidatedAsset {
+            title: title.to_owned(),
+            format,
+            data,
+            hash,
+            xmp_info,
+            store: None,
+            manifest_store: None,
+            manifest_data: None,
+            validation_status: None,
+        };
+
+        let mut report = DetailedStatusTracker::new();
+        match Store::load_from_memory(&asset.format, &asset.data, true, &mut report) {
+            Ok(store) => {
+                // generate ValidationStatus from ValidationItems filtering for only errors
+                let statuses = status_for_store(&store, &mut report);
+                if !statuses.is_empty() {
+                    asset.validation_status = Some(statuses);
+                }
+                asset.manifest_data = load_jumbf_from_memory(&asset.format, &asset.data).ok();
+                asset.manifest_store = Some(ManifestStore::from_store(&store, &mut report));
+                asset.store = Some(store);
+            }
+            Err(Error::JumbfNotFound)
+            | Err(Error::ProvenanceMissing)
+            | Err(Error::UnsupportedType) => {} // no claims but valid file
+            Err(Error::BadParam(desc)) if desc == *"unrecognized file type" => {}
+            Err(e) => {
+                // we can ignore the error here because it should have a log entry corresponding to it
+                debug!("validated asset {:?}", e);
+                // convert any other error to a validation status
+                let statuses: Vec<ValidationStatus> = report
+                    .get_log()
+                    .iter()
+                    .filter_map(ValidationStatus::from_validation_item)
+                    .filter(|s| !s.code().is_success())
+                    .collect();
+                if !statuses.is_empty() {
+                    asset.validation_status = Some(statuses);
+                }
+            }
+        }
+
+        asset
+    }
+
+    /// Returns the title of the asset, its file name if it was read from a file
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the MIME type of the asset
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    /// Returns the bytes of the asset
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the Blake3 hash of the asset
+    pub fn hash(&self) -> Option<&str> {
+        self.hash.as_deref()
+    }
+
+    /// Returns the manifest store of the asset, if it has one that could be read
+    pub fn manifest_store(&self) -> Option<&ManifestStore> {
+        self.manifest_store.as_ref()
+    }
+
+    /// Returns the validation errors found in the asset, if any
+    pub fn validation_status(&self) -> Option<&[ValidationStatus]> {
+        self.validation_status.as_deref()
+    }
+
+    pub(crate) fn store(&self) -> Option<&Store> {
+        self.store.as_ref()
+    }
+
+    pub(crate) fn manifest_data(&self) -> Option<&[u8]> {
+        self.manifest_data.as_deref()
+    }
+
+    pub(crate) fn xmp_info(&self) -> &XmpInfo {
+        &self.xmp_info
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{utils::test::fixture_path, IngredientOptions};
+
+    #[test]
+    fn test_validated_asset() {
+        let asset = ValidatedAsset::from_file(fixture_path("CA.jpg")).unwrap();
+        assert_eq!(asset.title(), "CA.jpg");
+        assert_eq!(asset.format(), "image/jpeg");
+        assert!(asset.validation_status().is_none());
+        let manifest_store = asset.manifest_store().unwrap();
+        assert!(manifest_store.get_active().is_some());
+
+        // the ingredient reuses the results of the validation
+        let ingredient = Ingredient::from_validated_asset(&asset, &IngredientOptions::default());
+        let from_file = Ingredient::from_file(fixture_path("CA.jpg")).unwrap();
+        assert_eq!(ingredient.title(), from_file.title());
+        assert_eq!(ingredient.hash(), asset.hash());
+        assert_eq!(ingredient.hash(), from_file.hash());
+        assert_eq!(ingredient.active_manifest(), manifest_store.active_label());
+        assert_eq!(ingredient.manifest_data(), from_file.manifest_data());
+        assert!(ingredient.validation_status().is_none());
+    }
+
+    #[test]
+    fn test_validated_asset_no_manifest() {
+        let data = std::fs::read(fixture_path("earth_apollo17.jpg")).unwrap();
+        let asset = ValidatedAsset::from_bytes("jpg", data);
+        assert_eq!(asset.title(), "untitled");
+        assert!(asset.manifest_store().is_none());
+        assert!(asset.validation_status().is_none());
+
+        assert!(matches!(
+            ValidatedAsset::from_file(fixture_path("missing.jpg")),
+            Err(Error::FileNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,411 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Acceptance policies evaluated after validation.
+//!
+//! Validation decides whether the manifests of an asset are intact and correctly signed.
+//! Whether an integrator accepts the asset can depend on more, such as the actions it
+//! discloses or who signed it. A [`ValidationPolicy`] holds the [`PolicyRule`]s of such a
+//! decision and combines them with the validation results into a [`PolicyDecision`].
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use x509_parser::prelude::*;
+
+use crate::{assertions::Actions, utils::hash_utils::hash_by_alg, Manifest, Reader};
+
+/// A rule deciding whether an asset is accepted.
+///
+/// Integrators implement this trait for their own rules and add them to a
+/// [`ValidationPolicy`].
+pub trait PolicyRule: Send + Sync {
+    /// The name reported with the reason of a rejection
+    fn name(&self) -> &str;
+
+    /// Evaluate the rule against a validated asset, returning why it is rejected, if it is
+    fn evaluate(&self, reader: &Reader) -> std::result::Result<(), String>;
+}
+
+/// Why a rule rejected an asset
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PolicyReason {
+    /// The name of the rule
+    pub rule: String,
+    /// Why the rule rejected the asset
+    pub reason: String,
+}
+
+/// The combined decision of a [`ValidationPolicy`]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PolicyDecision {
+    /// The reasons the asset is rejected, one for each rule that rejected it
+    pub reasons: Vec<PolicyReason>,
+}
+
+impl PolicyDecision {
+    /// Returns true if no rule rejected the asset
+    pub fn is_accepted(&self) -> bool {
+        self.reasons.is_empty()
+    }
+}
+
+// name of the reason reported when validation failed
+const VALIDATION_RULE: &str = "validation";
+
+/// A set of rules deciding whether validated assets are accepted.
+///
+/// Assets that fail validation are rejected with a reason from the `validation` rule unless
+/// [`set_allow_invalid`](Self::set_allow_invalid) is set. Every rule is evaluated, so the
+/// decision lists all the reasons an asset is rejected.
+///
+/// # Example
+///
+/// ```
+/// # use c2pa::Result;
+/// use std::sync::Arc;
+///
+/// use c2pa::{
+///     assertions::digital_source_type, Reader, RequireActions, RequireDigitalSourceType,
+///     ValidationPolicy,
+/// };
+/// # fn main() -> Result<()> {
+/// let mut policy = ValidationPolicy::new();
+/// policy
+///     .add_rule(Arc::new(RequireActions))
+///     .add_rule(Arc::new(RequireDigitalSourceType::new(&[
+///         digital_source_type::TRAINED_ALGORITHMIC_MEDIA,
+///     ])));
+///
+/// let reader = Reader::from_file("tests/fixtures/CA.jpg")?;
+/// for reason in policy.evaluate(&reader).reasons {
+///     println!("{}: {}", reason.rule, reason.reason);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ValidationPolicy {
+    rules: Vec<Arc<dyn PolicyRule>>,
+    allow_invalid: bool,
+}
+
+impl ValidationPolicy {
+    /// Create a policy accepting every valid asset
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a rule, evaluated after the rules added before it
+    pub fn add_rule(&mut self, rule: Arc<dyn PolicyRule>) -> &mut Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Set whether assets that fail validation are left to the rules to decide on
+    pub fn set_allow_invalid(&mut self, allow_invalid: bool) -> &mut Self {
+        self.allow_invalid = allow_invalid;
+        self
+    }
+
+    /// Returns true if no rules are added
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Decide whether the asset read by `reader` is accepted
+    pub fn evaluate(&self, reader: &Reader) -> PolicyDecision {
+        let mut decision = PolicyDecision::default();
+
+        if !self.allow_invalid && !reader.validation_results().is_valid() {
+            let codes: Vec<&str> = reader
+                .validation_results()
+                .manifests
+                .values()
+                .flat_map(|codes| codes.failure.iter().map(|status| status.code().as_str()))
+                .collect();
+            decision.reasons.push(PolicyReason {
+                rule: VALIDATION_RULE.to_string(),
+                reason: format!("validation failed: {}", codes.join(", ")),
+            });
+        }
+
+        for rule in &self.rules {
+            if let Err(reason) = rule.evaluate(reader) {
+                decision.reasons.push(PolicyReason {
+                    rule: rule.name().to_string(),
+                    reason,
+                });
+            }
+        }
+
+        decision
+    }
+}
+
+impl std::fmt::Debug for ValidationPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationPolicy")
+            .field(
+                "rules",
+                &self.rules.iter().map(|r| r.name()).collect::<Vec<_>>(),
+            )
+            .field("allow_invalid", &self.allow_invalid)
+            .finish()
+    }
+}
+
+// the active manifest, or why a rule on it cannot be met
+fn active_manifest(reader: &Reader) -> std::result::Result<&Manifest, String> {
+    reader
+        .active_manifest()
+        .ok_or_else(|| "no active manifest".to_string())
+}
+
+// the actions assertions of a manifest
+fn manifest_actions(manifest: &Manifest) -> Vec<Actions> {
+    manifest
+        .assertions()
+        .iter()
+        .filter(|a| a.label.starts_with(Actions::LABEL))
+        .filter_map(|a| a.to_assertion::<Actions>().ok())
+        .collect()
+}
+
+/// Rejects assets whose active manifest records no actions
+#[derive(Clone, Debug, Default)]
+pub struct RequireActions;
+
+impl PolicyRule for RequireActions {
+    fn name(&self) -> &str {
+        "require_actions"
+    }
+
+    fn evaluate(&self, reader: &Reader) -> std::result::Result<(), String> {
+        let manifest = active_manifest(reader)?;
+        if manifest_actions(manifest)
+            .iter()
+            .any(|actions| !actions.actions().is_empty())
+        {
+            Ok(())
+        } else {
+            Err("manifest has no actions".to_string())
+        }
+    }
+}
+
+/// Rejects assets whose active manifest has no action disclosing one of a set of
+/// digital source types, such as
+/// [`TRAINED_ALGORITHMIC_MEDIA`](crate::assertions::digital_source_type::TRAINED_ALGORITHMIC_MEDIA)
+/// for platforms requiring generated content to be disclosed.
+#[derive(Clone, Debug)]
+pub struct RequireDigitalSourceType {
+    source_types: Vec<String>,
+}
+
+impl RequireDigitalSourceType {
+    /// Require one of `source_types`
+    pub fn new(source_types: &[&str]) -> Self {
+        RequireDigitalSourceType {
+            source_types: source_types.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+impl PolicyRule for RequireDigitalSourceType {
+    fn name(&self) -> &str {
+        "require_digital_source_type"
+    }
+
+    fn evaluate(&self, reader: &Reader) -> std::result::Result<(), String> {
+        let manifest = active_manifest(reader)?;
+        let disclosed = manifest_actions(manifest).iter().any(|actions| {
+            actions.actions().iter().any(|action| {
+                action
+                    .digital_source_type()
+                    .map_or(false, |t| self.source_types.iter().any(|s| s == t))
+            })
+        });
+        if disclosed {
+            Ok(())
+        } else {
+            Err(format!(
+                "no action discloses a digital source type of {}",
+                self.source_types.join(", ")
+            ))
+        }
+    }
+}
+
+/// Rejects assets whose active manifest was not signed with one of a set of certificates.
+///
+/// Signers are identified by the SHA-256 hash of their DER signing certificate, or of the
+/// DER subjectPublicKeyInfo of the certificate to also allow certificates renewed with the
+/// same key. Names in a certificate are not used since any CA can issue a certificate with them.
+#[derive(Clone, Debug, Default)]
+pub struct SignerAllowList {
+    cert_hashes: Vec<Vec<u8>>,
+    spki_hashes: Vec<Vec<u8>>,
+}
+
+impl SignerAllowList {
+    /// Create an allow list that allows no signer
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the signer of the DER encoded signing certificate `cert`
+    pub fn add_cert(&mut self, cert: &[u8]) -> &mut Self {
+        self.add_cert_hash(&hash_by_alg("sha256", cert, None))
+    }
+
+    /// Allow a signing certificate by the SHA-256 hash of its DER encoding
+    pub fn add_cert_hash(&mut self, hash: &[u8]) -> &mut Self {
+        self.cert_hashes.push(hash.to_vec());
+        self
+    }
+
+    /// Allow signing certificates by the SHA-256 hash of their DER subjectPublicKeyInfo
+    pub fn add_spki_hash(&mut self, hash: &[u8]) -> &mut Self {
+        self.spki_hashes.push(hash.to_vec());
+        self
+    }
+
+    // whether the DER signing certificate `cert` is allowed
+    fn allows(&self, cert: &[u8]) -> bool {
+        let cert_hash = hash_by_alg("sha256", cert, None);
+        if self.cert_hashes.contains(&cert_hash) {
+            return true;
+        }
+        X509Certificate::from_der(cert).map_or(false, |(_rem, cert)| {
+            let spki_hash = hash_by_alg("sha256", cert.public_key().raw, None);
+            self.spki_hashes.contains(&spki_hash)
+        })
+    }
+}
+
+impl PolicyRule for SignerAllowList {
+    fn name(&self) -> &str {
+        "signer_allow_list"
+    }
+
+    fn evaluate(&self, reader: &Reader) -> std::result::Result<(), String> {
+        let manifest = active_manifest(reader)?;
+        let cert = reader
+            .active_signature()
+            .and_then(|signature| signature.cert_chain().ok())
+            .and_then(|chain| chain.into_iter().next())
+            .ok_or_else(|| "signer is unknown".to_string())?;
+        if self.allows(&cert) {
+            Ok(())
+        } else {
+            let issuer = manifest.issuer().unwrap_or_else(|| "unknown".to_string());
+            Err(format!("signer {} is not allowed", issuer))
+        }
+    }
+}
+
+/// A rule evaluated by a closure
+pub struct FnRule<F> {
+    name: String,
+    f: F,
+}
+
+impl<F> FnRule<F>
+where
+    F: Fn(&Reader) -> std::result::Result<(), String> + Send + Sync,
+{
+    /// Create a rule named `name` evaluated by `f`
+    pub fn new(name: &str, f: F) -> Self {
+        FnRule {
+            name: name.to_string(),
+            f,
+        }
+    }
+}
+
+impl<F> PolicyRule for FnRule<F>
+where
+    F: Fn(&Reader) -> std::result::Result<(), String> + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn evaluate(&self, reader: &Reader) -> std::result::Result<(), String> {
+        (self.f)(reader)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::assertions::digital_source_type;
+
+    #[test]
+    fn test_validation_policy() {
+        let image_bytes = include_bytes!("../tests/fixtures/CA.jpg");
+        let reader = Reader::from_bytes("image/jpeg", image_bytes).unwrap();
+        let issuer = reader.active_manifest().unwrap().issuer().unwrap();
+        let cert_chain = reader.active_signature().unwrap().cert_chain().unwrap();
+
+        // a valid asset is accepted by an empty policy
+        let mut policy = ValidationPolicy::new();
+        assert!(policy.is_empty());
+        assert!(policy.evaluate(&reader).is_accepted());
+
+        let mut allowed = SignerAllowList::new();
+        allowed.add_cert(&cert_chain[0]);
+        policy
+            .add_rule(Arc::new(RequireActions))
+            .add_rule(Arc::new(allowed));
+        assert!(policy.evaluate(&reader).is_accepted());
+
+        // the signer is also allowed by the hash of its public key
+        let (_rem, cert) = X509Certificate::from_der(&cert_chain[0]).unwrap();
+        let mut same_key = SignerAllowList::new();
+        same_key.add_spki_hash(&hash_by_alg("sha256", cert.public_key().raw, None));
+        assert!(same_key.evaluate(&reader).is_ok());
+
+        // a certificate of the issuer's CA is not the signing certificate
+        let mut ca_only = SignerAllowList::new();
+        for ca in &cert_chain[1..] {
+            ca_only.add_cert(ca);
+        }
+        assert!(ca_only.evaluate(&reader).is_err());
+
+        // every rejecting rule is reported
+        policy
+            .add_rule(Arc::new(RequireDigitalSourceType::new(&[
+                digital_source_type::TRAINED_ALGORITHMIC_MEDIA,
+            ])))
+            .add_rule(Arc::new(SignerAllowList::new()))
+            .add_rule(Arc::new(FnRule::new(
+                "custom",
+                |reader: &Reader| match reader.active_manifest() {
+                    Some(manifest) if manifest.ingredients().len() > 100 => Ok(()),
+                    _ => Err("too few ingredients".to_string()),
+                },
+            )));
+        let decision = policy.evaluate(&reader);
+        assert!(!decision.is_accepted());
+        let rules: Vec<&str> = decision.reasons.iter().map(|r| r.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            vec!["require_digital_source_type", "signer_allow_list", "custom"]
+        );
+        assert!(decision.reasons[1].reason.contains(&issuer));
+    }
+}
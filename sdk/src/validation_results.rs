@@ -0,0 +1,371 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Validation results grouped by manifest.
+//!
+//! Validation logs every check it makes against the JUMBF URI of the part it checked.
+//! [`ValidationResults`] sorts those into success, informational and failure codes for
+//! each manifest, which is what a user interface showing content credentials needs.
+//! [`ValidationTree`] rolls those up through the ingredients of the active manifest,
+//! so a manifest whose own checks pass but whose ingredients had issues can be shown
+//! in a state of its own.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use crate::{
+    jumbf::labels::manifest_label_from_uri, status_tracker::StatusTracker,
+    validation_status::ValidationStatus, Ingredient, ManifestStore,
+};
+
+/// The validation codes reported for one manifest
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct StatusCodes {
+    /// Checks that passed
+    pub success: Vec<ValidationStatus>,
+    /// Checks that could not be made
+    pub informational: Vec<ValidationStatus>,
+    /// Checks that failed
+    pub failure: Vec<ValidationStatus>,
+}
+
+impl StatusCodes {
+    /// Returns true if no check failed
+    pub fn is_valid(&self) -> bool {
+        self.failure.is_empty()
+    }
+
+    // add a status to its category, once
+    fn add(&mut self, status: ValidationStatus) {
+        let codes = if status.code().is_success() {
+            &mut self.success
+        } else if status.code().is_informational() {
+            &mut self.informational
+        } else {
+            &mut self.failure
+        };
+        if !codes.contains(&status) {
+            codes.push(status);
+        }
+    }
+}
+
+/// The results of validating a manifest store, grouped by manifest
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct ValidationResults {
+    /// The label of the active manifest
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_manifest: Option<String>,
+    /// The codes reported for each manifest that was validated, by manifest label
+    pub manifests: HashMap<String, StatusCodes>,
+    /// The validation state of the active manifest and its ingredients
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation_tree: Option<ValidationTree>,
+}
+
+impl ValidationResults {
+    /// Group the items of a validation log by the manifest they refer to.
+    ///
+    /// Items that do not refer to a manifest come from loading the store, so they
+    /// belong to the active manifest.
+    pub(crate) fn from_log(active_manifest: Option<String>, log: &impl StatusTracker) -> Self {
+        let mut results = ValidationResults {
+            active_manifest,
+            manifests: HashMap::new(),
+            validation_tree: None,
+        };
+        for status in log
+            .get_log()
+            .iter()
+            .filter_map(ValidationStatus::from_validation_item)
+        {
+            results.add(status);
+        }
+        results
+    }
+
+    /// Add a status to the manifest it refers to, or to the active manifest
+    pub(crate) fn add(&mut self, status: ValidationStatus) {
+        let label = status
+            .url()
+            .and_then(manifest_label_from_uri)
+            .or_else(|| self.active_manifest.clone());
+        if let Some(label) = label {
+            self.manifests.entry(label).or_default().add(status);
+        }
+    }
+
+    /// Returns the codes reported for the active manifest
+    pub fn active_manifest_codes(&self) -> Option<&StatusCodes> {
+        self.active_manifest
+            .as_ref()
+            .and_then(|label| self.manifests.get(label))
+    }
+
+    /// Returns the codes reported for the manifest with the given label
+    pub fn manifest_codes(&self, label: &str) -> Option<&StatusCodes> {
+        self.manifests.get(label)
+    }
+
+    /// Returns true if no check failed in any manifest
+    pub fn is_valid(&self) -> bool {
+        self.manifests.values().all(|codes| codes.is_valid())
+    }
+
+    /// Roll the results up through the ingredients of the active manifest of `manifest_store`
+    pub(crate) fn build_tree(&mut self, manifest_store: &ManifestStore) {
+        let tree = self.active_manifest.as_deref().map(|label| {
+            let title = manifest_store
+                .get(label)
+                .and_then(|m| m.asset())
+                .map(|a| a.title().to_owned())
+                .unwrap_or_default();
+            self.tree_node(
+                manifest_store,
+                Some(label),
+                title,
+                Vec::new(),
+                &mut Vec::new(),
+            )
+        });
+        self.validation_tree = tree;
+    }
+
+    // the node of the manifest with `label`, with the failures recorded for it by the
+    // manifest using it as an ingredient. `ancestors` guards against cycles.
+    fn tree_node<'a>(
+        &self,
+        manifest_store: &'a ManifestStore,
+        label: Option<&'a str>,
+        title: String,
+        recorded: Vec<ValidationStatus>,
+        ancestors: &mut Vec<&'a str>,
+    ) -> ValidationTree {
+        let mut failure = label
+            .and_then(|label| self.manifest_codes(label))
+            .map(|codes| codes.failure.clone())
+            .unwrap_or_default();
+        for status in recorded {
+            if !failure.contains(&status) {
+                failure.push(status);
+            }
+        }
+
+        let mut ingredients = Vec::new();
+        if let Some(label) = label.filter(|label| !ancestors.contains(label)) {
+            ancestors.push(label);
+            for ingredient in manifest_store
+                .get(label)
+                .map(|m| m.ingredients())
+                .unwrap_or_default()
+            {
+                ingredients.push(self.tree_node(
+                    manifest_store,
+                    ingredient.active_manifest(),
+                    ingredient.title().to_owned(),
+                    recorded_failures(ingredient),
+                    ancestors,
+                ));
+            }
+            ancestors.pop();
+        }
+
+        let mut node = ValidationTree {
+            manifest_label: label.map(str::to_owned),
+            title,
+            state: ValidationState::Valid,
+            failure,
+            issues_from: Vec::new(),
+            ingredients,
+        };
+        node.issues_from = node.descendant_issues();
+        node.state = if !node.failure.is_empty() {
+            ValidationState::Invalid
+        } else if !node.issues_from.is_empty() {
+            ValidationState::IngredientIssues
+        } else {
+            ValidationState::Valid
+        };
+        node
+    }
+}
+
+// the failures recorded by an ingredient assertion when the ingredient was added
+fn recorded_failures(ingredient: &Ingredient) -> Vec<ValidationStatus> {
+    ingredient
+        .validation_status()
+        .unwrap_or_default()
+        .iter()
+        .filter(|s| !s.passed())
+        .cloned()
+        .collect()
+}
+
+/// The validation state of a manifest, taking its ingredients into account
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationState {
+    /// No check failed in the manifest or in the manifests of its ingredients
+    Valid,
+    /// No check failed in the manifest, but one failed in the manifest of one of its
+    /// ingredients, the state shown as "ingredient had earlier issues" (otgp)
+    #[serde(rename = "otgp")]
+    IngredientIssues,
+    /// A check failed in the manifest
+    Invalid,
+}
+
+/// A manifest and its ingredients, with the validation state of each
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
+pub struct ValidationTree {
+    /// The label of the manifest, `None` for an ingredient without one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manifest_label: Option<String>,
+    /// The title of the asset
+    pub title: String,
+    /// The state of the manifest, taking its ingredients into account
+    pub state: ValidationState,
+    /// The checks that failed in the manifest, found when it was validated or
+    /// recorded by the manifest using it as an ingredient
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failure: Vec<ValidationStatus>,
+    /// The descendants whose checks failed, nearest first. They are identified by their
+    /// manifest label, or by their title for ingredients without a manifest.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub issues_from: Vec<String>,
+    /// The ingredients of the manifest
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ingredients: Vec<ValidationTree>,
+}
+
+impl ValidationTree {
+    /// Returns the node of the manifest with the given label, searching the whole tree
+    pub fn find(&self, label: &str) -> Option<&ValidationTree> {
+        if self.manifest_label.as_deref() == Some(label) {
+            return Some(self);
+        }
+        self.ingredients.iter().find_map(|i| i.find(label))
+    }
+
+    // the descendants with failures, breadth first so the nearest come first
+    fn descendant_issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let mut queue: VecDeque<&ValidationTree> = self.ingredients.iter().collect();
+        while let Some(node) = queue.pop_front() {
+            if !node.failure.is_empty() {
+                let id = node.manifest_label.as_ref().unwrap_or(&node.title);
+                if !issues.contains(id) {
+                    issues.push(id.clone());
+                }
+            }
+            queue.extend(node.ingredients.iter());
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use crate::{
+        status_tracker::{DetailedStatusTracker, LogItem},
+        validation_status::ValidationStatusCode,
+    };
+
+    #[test]
+    fn test_validation_results() {
+        let active = "urn:uuid:active";
+        let ingredient = "urn:uuid:ingredient";
+        let mut log = DetailedStatusTracker::new();
+        log.log_silent(
+            LogItem::new(
+                &format!("self#jumbf=/c2pa/{}/c2pa.signature", active),
+                "claim signature valid",
+                "test",
+                file!(),
+                line!(),
+            )
+            .validation_status(ValidationStatusCode::ClaimSignatureValidated),
+        );
+        log.log_silent(
+            LogItem::new(
+                &format!("self#jumbf=/c2pa/{}/c2pa.credentials", active),
+                "proof not checked",
+                "test",
+                file!(),
+                line!(),
+            )
+            .validation_status(ValidationStatusCode::VcProofUnverified),
+        );
+        log.log_silent(
+            LogItem::new(
+                &format!(
+                    "self#jumbf=/c2pa/{}/c2pa.assertions/c2pa.hash.data",
+                    ingredient
+                ),
+                "hash mismatch",
+                "test",
+                file!(),
+                line!(),
+            )
+            .validation_status(ValidationStatusCode::AssertionDataHashMismatch),
+        );
+
+        let results = ValidationResults::from_log(Some(active.to_owned()), &log);
+        let active_codes = results.active_manifest_codes().unwrap();
+        assert_eq!(active_codes.success.len(), 1);
+        assert_eq!(active_codes.informational.len(), 1);
+        assert!(active_codes.is_valid());
+
+        let ingredient_codes = results.manifest_codes(ingredient).unwrap();
+        assert_eq!(
+            ingredient_codes.failure[0].code(),
+            &ValidationStatusCode::AssertionDataHashMismatch
+        );
+        assert!(!results.is_valid());
+    }
+
+    #[test]
+    fn test_validation_tree() {
+        use crate::Reader;
+
+        // the active manifest is valid, its ingredient has a bad signature
+        let image_bytes = include_bytes!("../tests/fixtures/CIE-sig-CA.jpg");
+        let reader = Reader::from_bytes("image/jpeg", image_bytes).unwrap();
+        let results = reader.validation_results();
+        let tree = results.validation_tree.as_ref().unwrap();
+        assert_eq!(tree.manifest_label, results.active_manifest);
+        assert_eq!(tree.state, ValidationState::IngredientIssues);
+        assert!(tree.failure.is_empty());
+
+        // the issues are traced to the manifest that introduced them
+        let source = tree.find(&tree.issues_from[0]).unwrap();
+        assert_eq!(source.state, ValidationState::Invalid);
+
+        let json = serde_json::to_value(results).unwrap();
+        assert_eq!(json["validation_tree"]["state"], "otgp");
+
+        let image_bytes = include_bytes!("../tests/fixtures/CA.jpg");
+        let reader = Reader::from_bytes("image/jpeg", image_bytes).unwrap();
+        let tree = reader.validation_results().validation_tree.clone().unwrap();
+        assert_eq!(tree.state, ValidationState::Valid);
+        assert!(tree.issues_from.is_empty());
+    }
+}
@@ -13,12 +13,17 @@
 
 //! Implements validation status for specific parts of a manifest.
 //!
+//! Status codes are [`ValidationStatusCode`]s, and the strings written in manifests
+//! and reports are also defined as constants.
+//!
 //! See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_existing_manifests>.
 
 #![deny(missing_docs)]
 
-use log::debug;
-use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use tracing::debug;
 
 use crate::{
     assertion::AssertionBase,
@@ -34,8 +39,10 @@ use crate::{
 ///
 /// See <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_existing_manifests>.
 #[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(feature = "json_schema", derive(schemars::JsonSchema))]
 pub struct ValidationStatus {
-    code: String,
+    #[cfg_attr(feature = "json_schema", schemars(with = "String"))]
+    code: ValidationStatusCode,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     url: Option<String>,
@@ -45,7 +52,7 @@ pub struct ValidationStatus {
 }
 
 impl ValidationStatus {
-    pub(crate) fn new(code: String) -> Self {
+    pub(crate) fn new(code: ValidationStatusCode) -> Self {
         Self {
             code,
             url: None,
@@ -58,9 +65,9 @@ impl ValidationStatus {
     /// Validation status codes are the labels from the "Value"
     /// column in <https://c2pa.org/specifications/specifications/1.0/specs/C2PA_Specification.html#_existing_manifests>.
     ///
-    /// These are also defined as constants in the
+    /// The strings are also defined as constants in the
     /// [`validation_status`](crate::validation_status) mod.
-    pub fn code(&self) -> &str {
+    pub fn code(&self) -> &ValidationStatusCode {
         &self.code
     }
 
@@ -88,18 +95,20 @@ impl ValidationStatus {
 
     /// Returns `true` if this has a successful validation code.
     pub fn passed(&self) -> bool {
-        is_success(&self.code)
+        self.code.is_success()
     }
 
     // Maps errors into validation_status codes.
-    fn code_from_error(error: &Error) -> &str {
+    fn code_from_error(error: &Error) -> ValidationStatusCode {
         match error {
-            Error::ClaimMissing { .. } => CLAIM_MISSING,
-            Error::AssertionMissing { .. } => ASSERTION_MISSING,
-            Error::AssertionDecoding(_code) => STATUS_ASSERTION_MALFORMED, // todo: no code for invalid assertion format
-            Error::HashMismatch(_) => ASSERTION_DATAHASH_MATCH,
-            Error::PrereleaseError => STATUS_PRERELEASE,
-            _ => STATUS_OTHER,
+            Error::ClaimMissing { .. } => ValidationStatusCode::ClaimMissing,
+            Error::AssertionMissing { .. } => ValidationStatusCode::AssertionMissing,
+            Error::AssertionDecoding(_code) => ValidationStatusCode::AssertionMalformed, // todo: no code for invalid assertion format
+            Error::AssertionActionsInvalid(_) => ValidationStatusCode::AssertionActionMalformed,
+            Error::AssertionSchemaMismatch(_) => ValidationStatusCode::AssertionMalformed,
+            Error::HashMismatch(_) => ValidationStatusCode::AssertionDataHashMatch,
+            Error::PrereleaseError => ValidationStatusCode::Prerelease,
+            _ => ValidationStatusCode::Other,
         }
     }
 
@@ -108,14 +117,14 @@ impl ValidationStatus {
         // We need to create error codes here for client processing.
         let code = Self::code_from_error(error);
         debug!("ValidationStatus {} from error {:#?}", code, error);
-        Self::new(code.to_string()).set_explanation(error.to_string())
+        Self::new(code).set_explanation(error.to_string())
     }
 
     /// Creates a ValidationStatus from a validation_log item.
     pub(crate) fn from_validation_item(item: &LogItem) -> Option<Self> {
         match item.validation_status.as_ref() {
             Some(status) => Some(
-                Self::new(status.to_string())
+                Self::new(status.clone())
                     .set_url(item.label.to_string())
                     .set_explanation(item.description.to_string()),
             ),
@@ -123,7 +132,7 @@ impl ValidationStatus {
             // using the description plus error text explanation.
             None => item.err_val.as_ref().map(|e| {
                 let code = Self::code_from_error(e);
-                Self::new(code.to_string())
+                Self::new(code)
                     .set_url(item.label.to_string())
                     .set_explanation(format!("{}: {}", item.description, e))
             }),
@@ -150,7 +159,7 @@ pub fn status_for_store(
         .get_log()
         .iter()
         .filter_map(ValidationStatus::from_validation_item)
-        .filter(|s| !is_success(&s.code))
+        .filter(|s| !s.code.is_success())
         .collect();
 
     // Filter out any status that is already captured in an ingredient assertion.
@@ -228,6 +237,12 @@ pub const ASSERTION_DATAHASH_MATCH: &str = "assertion.dataHash.match";
 /// `ValidationStatus.url()` will point to a C2PA assertion.
 pub const ASSERTION_BMFFHASH_MATCH: &str = "assertion.bmffHash.match";
 
+/// The members of a collection match the hashes declared in the
+/// collection data hash assertion.
+///
+/// `ValidationStatus.url()` will point to a C2PA assertion.
+pub const ASSERTION_COLLECTIONHASH_MATCH: &str = "assertion.collectionHash.match";
+
 /// A non-embedded (remote) assertion was accessible at the time of
 /// validation.
 ///
@@ -364,6 +379,13 @@ pub const ASSERTION_SELF_REDACTED: &str = "assertion.selfRedacted";
 /// `ValidationStatus.url()` will point to a C2PA assertion.
 pub const ACTION_ASSERTION_REDACTED: &str = "assertion.action.redacted";
 
+/// An actions assertion does not follow the actions schema, such as listing
+/// an action label that is not in reverse-domain format or a `c2pa.created`
+/// action that is not the first.
+///
+/// `ValidationStatus.url()` will point to a C2PA assertion.
+pub const ASSERTION_ACTION_MALFORMED: &str = "assertion.action.malformed";
+
 /// The hash of a byte range of the asset does not match the
 /// hash declared in the data hash assertion.
 ///
@@ -376,6 +398,12 @@ pub const ASSERTION_DATAHASH_MISMATCH: &str = "assertion.dataHash.mismatch";
 /// `ValidationStatus.url()` will point to a C2PA assertion.
 pub const ASSERTION_BMFFHASH_MISMATCH: &str = "assertion.bmffHash.mismatch";
 
+/// A member of a collection is missing, unexpected or does not match
+/// the hash declared in the collection data hash assertion.
+///
+/// `ValidationStatus.url()` will point to a C2PA assertion.
+pub const ASSERTION_COLLECTIONHASH_MISMATCH: &str = "assertion.collectionHash.mismatch";
+
 /// A hard binding assertion is in a cloud data assertion.
 ///
 /// `ValidationStatus.url()` will point to a C2PA assertion.
@@ -394,16 +422,226 @@ pub const ASSERTION_CLOUDDATA_ACTIONS: &str = "assertion.clouddata.actions";
 /// `ValidationStatus.url()` will point to a C2PA claim box or C2PA assertion.
 pub const ALGORITHM_UNSUPPORTED: &str = "algorithm.unsupported";
 
+/// The claim is signed with an algorithm the specification deprecates,
+/// such as RSASSA-PKCS1-v1_5. This is an informational code.
+///
+/// `ValidationStatus.url()` will point to a C2PA claim signature box.
+pub const ALGORITHM_DEPRECATED: &str = "algorithm.deprecated";
+
 // -- unofficial status codes --
 
 pub(crate) const STATUS_OTHER: &str = "com.adobe.other";
 pub(crate) const STATUS_PRERELEASE: &str = "com.adobe.prerelease";
 pub(crate) const STATUS_ASSERTION_MALFORMED: &str = "com.adobe.assertion.malformed";
 
+/// The soft binding value extracted from the asset matches the soft binding assertion.
+///
+/// `ValidationStatus.url()` will point to a C2PA assertion.
+pub const SOFT_BINDING_MATCH: &str = "com.adobe.softBinding.match";
+
+/// The soft binding value extracted from the asset does not match the soft binding assertion.
+/// This is an informational code, soft bindings are expected to break more easily
+/// than the hard binding of the asset.
+///
+/// `ValidationStatus.url()` will point to a C2PA assertion.
+pub const SOFT_BINDING_MISMATCH: &str = "com.adobe.softBinding.mismatch";
+
+/// The key usage extension of the signing certificate is not marked critical.
+/// This is an informational code.
+///
+/// `ValidationStatus.url()` will point to a C2PA claim signature box.
+pub const SIGNING_CREDENTIAL_KEY_USAGE_NOT_CRITICAL: &str =
+    "com.adobe.signingCredential.keyUsage.notCritical";
+
+/// A co-signature countersigning the claim signature validated.
+///
+/// `ValidationStatus.url()` will point to a C2PA claim signature box.
+pub const COSIGNATURE_VALIDATED: &str = "com.adobe.cosignature.validated";
+
+/// A co-signature countersigning the claim signature failed to validate.
+///
+/// `ValidationStatus.url()` will point to a C2PA claim signature box.
+pub const COSIGNATURE_MISMATCH: &str = "com.adobe.cosignature.mismatch";
+
+/// The manifest store exceeded one of the configured resource limits and was not loaded.
+///
+/// `ValidationStatus.url()` will point to the C2PA manifest that exceeded the limit, if any.
+pub const RESOURCE_LIMIT_EXCEEDED: &str = "com.adobe.resourceLimit.exceeded";
+
+/// An ingredient manifest refers back to one of the manifests it is an ingredient of.
+///
+/// `ValidationStatus.url()` will point to the C2PA manifest closing the cycle.
+pub const INGREDIENT_CYCLE: &str = "com.adobe.ingredient.cycle";
+
+/// The proof of a Verifiable Credential in the claim validated.
+///
+/// `ValidationStatus.url()` will point to a C2PA verifiable credential.
+pub const VC_PROOF_VALIDATED: &str = "com.adobe.vc.proof.validated";
+
+/// The proof of a Verifiable Credential in the claim failed to validate.
+///
+/// `ValidationStatus.url()` will point to a C2PA verifiable credential.
+pub const VC_PROOF_MISMATCH: &str = "com.adobe.vc.proof.mismatch";
+
+/// The proof of a Verifiable Credential in the claim could not be checked,
+/// because it is missing, of an unsupported type or its key could not be resolved.
+///
+/// `ValidationStatus.url()` will point to a C2PA verifiable credential.
+pub const VC_PROOF_UNVERIFIED: &str = "com.adobe.vc.proof.unverified";
+
+macro_rules! status_codes {
+    ($($variant:ident => $code:ident,)*) => {
+        /// A validation status code.
+        ///
+        /// Each code defined by this crate is a variant, so codes can be matched
+        /// exhaustively. Codes from manifests or reports written by other
+        /// implementations that this crate does not know are kept as `Unknown`.
+        /// Codes serialize as the strings of the specification.
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum ValidationStatusCode {
+            $(
+                #[doc = concat!("The code `", stringify!($code), "`")]
+                $variant,
+            )*
+            /// A code this crate does not know
+            Unknown(String),
+        }
+
+        impl ValidationStatusCode {
+            /// Returns the code as it is written in manifests and reports
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(ValidationStatusCode::$variant => $code,)*
+                    ValidationStatusCode::Unknown(code) => code,
+                }
+            }
+        }
+
+        impl FromStr for ValidationStatusCode {
+            type Err = Infallible;
+
+            fn from_str(code: &str) -> std::result::Result<Self, Self::Err> {
+                Ok(match code {
+                    $($code => ValidationStatusCode::$variant,)*
+                    _ => ValidationStatusCode::Unknown(code.to_owned()),
+                })
+            }
+        }
+    };
+}
+
+status_codes! {
+    ClaimSignatureValidated => CLAIM_SIGNATURE_VALIDATED,
+    SigningCredentialTrusted => SIGNING_CREDENTIAL_TRUSTED,
+    TimeStampTrusted => TIMESTAMP_TRUSTED,
+    AssertionHashedUriMatch => ASSERTION_HASHEDURI_MATCH,
+    AssertionDataHashMatch => ASSERTION_DATAHASH_MATCH,
+    AssertionBmffHashMatch => ASSERTION_BMFFHASH_MATCH,
+    AssertionCollectionHashMatch => ASSERTION_COLLECTIONHASH_MATCH,
+    AssertionAccessible => ASSERTION_ACCESSIBLE,
+    ClaimMissing => CLAIM_MISSING,
+    ClaimMultiple => CLAIM_MULTIPLE,
+    HardBindingsMissing => HARD_BINDINGS_MISSING,
+    IngredientHashedUriMismatch => INGREDIENT_HASHEDURI_MISMATCH,
+    ClaimSignatureMissing => CLAIM_SIGNATURE_MISSING,
+    ClaimSignatureMismatch => CLAIM_SIGNATURE_MISMATCH,
+    ManifestMultipleParents => MANIFEST_MULTIPLE_PARENTS,
+    ManifestUpdateInvalid => MANIFEST_UPDATE_INVALID,
+    ManifestUpdateWrongParents => MANIFEST_UPDATE_WRONG_PARENTS,
+    SigningCredentialUntrusted => SIGNING_CREDENTIAL_UNTRUSTED,
+    SigningCredentialInvalid => SIGNING_CREDENTIAL_INVALID,
+    SigningCredentialRevoked => SIGNING_CREDENTIAL_REVOKED,
+    SigningCredentialExpired => SIGNING_CREDENTIAL_EXPIRED,
+    TimeStampMismatch => TIMESTAMP_MISMATCH,
+    TimeStampUntrusted => TIMESTAMP_UNTRUSTED,
+    TimeStampOutsideValidity => TIMESTAMP_OUTSIDE_VALIDITY,
+    AssertionHashedUriMismatch => ASSERTION_HASHEDURI_MISMATCH,
+    AssertionMissing => ASSERTION_MISSING,
+    AssertionUndeclared => ASSERTION_UNDECLARED,
+    AssertionInaccessible => ASSERTION_INACCESSIBLE,
+    AssertionNotRedacted => ASSERTION_NOT_REDACTED,
+    AssertionSelfRedacted => ASSERTION_SELF_REDACTED,
+    ActionAssertionRedacted => ACTION_ASSERTION_REDACTED,
+    AssertionActionMalformed => ASSERTION_ACTION_MALFORMED,
+    AssertionDataHashMismatch => ASSERTION_DATAHASH_MISMATCH,
+    AssertionBmffHashMismatch => ASSERTION_BMFFHASH_MISMATCH,
+    AssertionCollectionHashMismatch => ASSERTION_COLLECTIONHASH_MISMATCH,
+    AssertionCloudDataHardBinding => ASSERTION_CLOUDDATA_HARD_BINDING,
+    AssertionCloudDataActions => ASSERTION_CLOUDDATA_ACTIONS,
+    AlgorithmUnsupported => ALGORITHM_UNSUPPORTED,
+    AlgorithmDeprecated => ALGORITHM_DEPRECATED,
+    Other => STATUS_OTHER,
+    Prerelease => STATUS_PRERELEASE,
+    AssertionMalformed => STATUS_ASSERTION_MALFORMED,
+    SoftBindingMatch => SOFT_BINDING_MATCH,
+    SoftBindingMismatch => SOFT_BINDING_MISMATCH,
+    SigningCredentialKeyUsageNotCritical => SIGNING_CREDENTIAL_KEY_USAGE_NOT_CRITICAL,
+    CosignatureValidated => COSIGNATURE_VALIDATED,
+    CosignatureMismatch => COSIGNATURE_MISMATCH,
+    ResourceLimitExceeded => RESOURCE_LIMIT_EXCEEDED,
+    IngredientCycle => INGREDIENT_CYCLE,
+    VcProofValidated => VC_PROOF_VALIDATED,
+    VcProofMismatch => VC_PROOF_MISMATCH,
+    VcProofUnverified => VC_PROOF_UNVERIFIED,
+}
+
+impl ValidationStatusCode {
+    /// Returns `true` if the code is a known C2PA success status code
+    pub fn is_success(&self) -> bool {
+        is_success(self.as_str())
+    }
+
+    /// Returns `true` if the code reports something that could not be checked,
+    /// rather than a success or a failure
+    pub fn is_informational(&self) -> bool {
+        is_informational(self.as_str())
+    }
+}
+
+impl From<&str> for ValidationStatusCode {
+    fn from(code: &str) -> Self {
+        match code.parse() {
+            Ok(code) => code,
+            Err(never) => match never {},
+        }
+    }
+}
+
+impl fmt::Display for ValidationStatusCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for ValidationStatusCode {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ValidationStatusCode {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Serialize for ValidationStatusCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for ValidationStatusCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Ok(ValidationStatusCode::from(code.as_str()))
+    }
+}
+
 /// Returns `true` if the status code is a known C2PA success status code.
 ///
-/// Returns `false` if the status code is a known C2PA failure status
-/// code or is unknown.
+/// Returns `false` if the status code is a known C2PA failure or informational
+/// status code or is unknown.
 ///
 /// # Examples
 ///
@@ -422,6 +660,73 @@ pub fn is_success(status_code: &str) -> bool {
             | ASSERTION_HASHEDURI_MATCH
             | ASSERTION_DATAHASH_MATCH
             | ASSERTION_BMFFHASH_MATCH
+            | ASSERTION_COLLECTIONHASH_MATCH
             | ASSERTION_ACCESSIBLE
+            | VC_PROOF_VALIDATED
+            | SOFT_BINDING_MATCH
+            | COSIGNATURE_VALIDATED
+    )
+}
+
+/// Returns `true` if the status code reports something that could not be checked,
+/// rather than a success or a failure.
+///
+/// # Examples
+///
+/// ```
+/// use c2pa::validation_status::*;
+///
+/// assert!(is_informational(VC_PROOF_UNVERIFIED));
+/// assert!(!is_informational(CLAIM_SIGNATURE_VALIDATED));
+/// ```
+pub fn is_informational(status_code: &str) -> bool {
+    matches!(
+        status_code,
+        VC_PROOF_UNVERIFIED
+            | ALGORITHM_DEPRECATED
+            | SIGNING_CREDENTIAL_KEY_USAGE_NOT_CRITICAL
+            | SOFT_BINDING_MISMATCH
     )
 }
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[test]
+    fn test_status_code() {
+        let code: ValidationStatusCode = ASSERTION_DATAHASH_MISMATCH.parse().unwrap();
+        assert_eq!(code, ValidationStatusCode::AssertionDataHashMismatch);
+        assert_eq!(code.to_string(), ASSERTION_DATAHASH_MISMATCH);
+        assert_eq!(code, ASSERTION_DATAHASH_MISMATCH);
+        assert!(!code.is_success());
+
+        assert!(ValidationStatusCode::ClaimSignatureValidated.is_success());
+        assert!(ValidationStatusCode::AlgorithmDeprecated.is_informational());
+        assert!(ValidationStatusCode::SoftBindingMismatch.is_informational());
+
+        // codes this crate does not know are kept
+        let code = ValidationStatusCode::from("example.code");
+        assert_eq!(
+            code,
+            ValidationStatusCode::Unknown("example.code".to_owned())
+        );
+        assert_eq!(code.as_str(), "example.code");
+    }
+
+    #[test]
+    fn test_status_code_json() {
+        let status = ValidationStatus::new(ValidationStatusCode::ClaimSignatureMismatch)
+            .set_url("self#jumbf=c2pa.signature".to_owned());
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["code"], CLAIM_SIGNATURE_MISMATCH);
+
+        let status: ValidationStatus = serde_json::from_str(r#"{"code": "example.code"}"#).unwrap();
+        assert_eq!(
+            status.code(),
+            &ValidationStatusCode::Unknown("example.code".to_owned())
+        );
+    }
+}
@@ -11,8 +11,10 @@
 // specific language governing permissions and limitations under
 // each license.
 
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 use crate::openssl::{EcValidator, EdValidator, RsaValidator};
+#[cfg(all(feature = "file_io", target_os = "wasi"))]
+use crate::rust_crypto::{EcValidator, EdValidator, RsaValidator};
 use crate::Result;
 
 use chrono::{DateTime, Utc};
@@ -22,7 +24,8 @@ pub struct ValidationInfo {
     pub alg: String, // validation algorithm
     pub date: Option<DateTime<Utc>>,
     pub issuer_org: Option<String>,
-    pub validated: bool, // claim signature is valid
+    pub validated: bool,                // claim signature is valid
+    pub cosigners: Vec<ValidationInfo>, // co-signatures countersigning the claim signature
 }
 
 impl Default for ValidationInfo {
@@ -32,6 +35,7 @@ impl Default for ValidationInfo {
             date: None,
             issuer_org: None,
             validated: false,
+            cosigners: Vec::new(),
         }
     }
 }
@@ -54,6 +58,7 @@ impl CoseValidator for DummyValidator {
 // • ES256 (ECDSA using P-256 and SHA-256)
 // • ES384 (ECDSA using P-384 and SHA-384)
 // • ES512 (ECDSA using P-521 and SHA-512)
+// • ES256K (ECDSA using secp256k1 and SHA-256), with the `es256k` feature
 // • PS256 (RSASSA-PSS using SHA-256 and MGF1 with SHA-256)
 // • PS384 (RSASSA-PSS using SHA-384 and MGF1 with SHA-384)
 // • PS512 (RSASSA-PSS using SHA-512 and MGF1 with SHA-512)
@@ -68,7 +73,11 @@ pub(crate) fn get_validator(alg: &str) -> Option<Box<dyn CoseValidator>> {
     match alg.to_lowercase().as_str() {
         "es256" => Some(Box::new(EcValidator::new("es256"))),
         "es384" => Some(Box::new(EcValidator::new("es384"))),
+        // the Rust validators used on WASI do not support P-521
+        #[cfg(not(target_os = "wasi"))]
         "es512" => Some(Box::new(EcValidator::new("es512"))),
+        #[cfg(feature = "es256k")]
+        "es256k" => Some(Box::new(EcValidator::new("es256k"))),
         "ps256" => Some(Box::new(RsaValidator::new("ps256"))),
         "ps384" => Some(Box::new(RsaValidator::new("ps384"))),
         "ps512" => Some(Box::new(RsaValidator::new("ps512"))),
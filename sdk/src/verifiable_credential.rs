@@ -0,0 +1,457 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Verification of the proofs of W3C Verifiable Credentials stored in a claim.
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::{utils::hash_utils::hash_by_alg, Error, Result};
+
+/// A public key used to verify the proof of a Verifiable Credential
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerificationKey {
+    /// The signature algorithm the key is used with, e.g. "ps256", "es256" or "ed25519"
+    pub alg: String,
+    /// The DER encoded SubjectPublicKeyInfo of the key
+    pub public_key: Vec<u8>,
+}
+
+/// Resolves the `verificationMethod` of a proof, usually a DID URL, to a public key.
+pub trait DidResolver: Send + Sync {
+    /// Return the key for `verification_method` or an error if it cannot be resolved
+    fn resolve(&self, verification_method: &str) -> Result<VerificationKey>;
+}
+
+/// Converts a JSON-LD document to the canonical bytes that are hashed when creating a proof.
+///
+/// Linked Data proofs are created over URDNA2015 canonicalized RDF.
+/// Implement this trait to plug in a canonicalization library for those credentials.
+pub trait VcCanonicalizer: Send + Sync {
+    /// Return the canonical form of `document`
+    fn canonicalize(&self, document: &Value) -> Result<Vec<u8>>;
+}
+
+/// Canonicalizes documents using the JSON Canonicalization Scheme (RFC 8785)
+pub struct JcsCanonicalizer;
+
+impl VcCanonicalizer for JcsCanonicalizer {
+    fn canonicalize(&self, document: &Value) -> Result<Vec<u8>> {
+        let mut canonical = Vec::new();
+        write_jcs(document, &mut canonical)?;
+        Ok(canonical)
+    }
+}
+
+// write `value` in its JCS form, members sorted and numbers formatted as ECMAScript does
+fn write_jcs(value: &Value, out: &mut Vec<u8>) -> Result<()> {
+    match value {
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_jcs(item, out)?;
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            // members are sorted by the UTF-16 code units of their names, not by code points
+            let mut members: Vec<(&String, &Value)> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+            out.push(b'{');
+            for (i, (name, item)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                serde_json::to_writer(&mut *out, name)?;
+                out.push(b':');
+                write_jcs(item, out)?;
+            }
+            out.push(b'}');
+        }
+        Value::Number(number) => {
+            // all numbers are IEEE 754 doubles, integers included
+            let number = number.as_f64().ok_or(Error::VerifiableCredentialInvalid)?;
+            out.extend(jcs_number(number)?.as_bytes());
+        }
+        // serde_json escapes strings the way JCS requires
+        _ => serde_json::to_writer(&mut *out, value)?,
+    }
+    Ok(())
+}
+
+// format a number as ECMAScript's Number.prototype.toString does
+fn jcs_number(number: f64) -> Result<String> {
+    if !number.is_finite() {
+        return Err(Error::VerifiableCredentialInvalid);
+    }
+    if number == 0.0 {
+        return Ok("0".to_owned()); // -0 too
+    }
+
+    // the shortest digits that round trip and the position of the decimal point
+    let scientific = format!("{:e}", number.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .ok_or(Error::VerifiableCredentialInvalid)?;
+    let exponent: i32 = exponent
+        .parse()
+        .map_err(|_err| Error::VerifiableCredentialInvalid)?;
+    let digits = mantissa.replace('.', "");
+    let len = digits.len() as i32;
+    let point = exponent + 1;
+
+    let formatted = if len <= point && point <= 21 {
+        format!("{}{}", digits, "0".repeat((point - len) as usize))
+    } else if 0 < point && point <= 21 {
+        let (integer, fraction) = digits.split_at(point as usize);
+        format!("{}.{}", integer, fraction)
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let sign = if point > 0 { "+" } else { "-" };
+        match rest {
+            "" => format!("{}e{}{}", first, sign, (point - 1).abs()),
+            _ => format!("{}.{}e{}{}", first, rest, sign, (point - 1).abs()),
+        }
+    };
+
+    if number < 0.0 {
+        Ok(format!("-{}", formatted))
+    } else {
+        Ok(formatted)
+    }
+}
+
+/// Verifies the proofs of Verifiable Credentials.
+///
+/// Supports `DataIntegrityProof` proofs with the `eddsa-jcs-2022` cryptosuite, which are
+/// canonicalized with JCS. The Linked Data proofs `jws` (`RsaSignature2018`,
+/// `Ed25519Signature2018`, `JsonWebSignature2020`) and `proofValue`
+/// (`Ed25519Signature2020`) are canonicalized with URDNA2015, so they are only verified
+/// once a canonicalizer for them is set with
+/// [`set_canonicalizer`](CredentialVerifier::set_canonicalizer).
+#[derive(Clone)]
+pub struct CredentialVerifier {
+    resolver: Arc<dyn DidResolver>,
+    canonicalizer: Option<Arc<dyn VcCanonicalizer>>,
+}
+
+impl CredentialVerifier {
+    /// Create a verifier that resolves keys with `resolver`
+    pub fn new(resolver: Arc<dyn DidResolver>) -> Self {
+        CredentialVerifier {
+            resolver,
+            canonicalizer: None,
+        }
+    }
+
+    /// Set the URDNA2015 canonicalization used to compute the input of Linked Data proofs.
+    /// Without one these proofs are rejected with [`Error::UnsupportedType`].
+    pub fn set_canonicalizer(&mut self, canonicalizer: Arc<dyn VcCanonicalizer>) -> &mut Self {
+        self.canonicalizer = Some(canonicalizer);
+        self
+    }
+
+    /// Verify the proof of the Verifiable Credential in `vc_json`.
+    ///
+    /// Returns [`Error::VerifiableCredentialProofMismatch`] if the proof does not validate
+    /// and [`Error::UnsupportedType`] if the proof cannot be checked.
+    pub fn verify(&self, vc_json: &str) -> Result<()> {
+        let mut vc: Value =
+            serde_json::from_str(vc_json).map_err(|_err| Error::VerifiableCredentialInvalid)?;
+        let vc_obj = vc
+            .as_object_mut()
+            .ok_or(Error::VerifiableCredentialInvalid)?;
+
+        let proof = vc_obj
+            .remove("proof")
+            .ok_or(Error::VerifiableCredentialInvalid)?;
+        let mut proof_options = proof
+            .as_object()
+            .ok_or(Error::VerifiableCredentialInvalid)?
+            .clone();
+        let proof_type = get_str(&proof_options, "type")?;
+        let method = get_str(&proof_options, "verificationMethod")?;
+
+        // JCS cannot reproduce the input of proofs made over canonicalized RDF
+        let canonicalizer: &dyn VcCanonicalizer = match proof_type.as_str() {
+            "DataIntegrityProof" => match get_str(&proof_options, "cryptosuite")?.as_str() {
+                "eddsa-jcs-2022" => &JcsCanonicalizer,
+                _ => return Err(Error::UnsupportedType),
+            },
+            "RsaSignature2018"
+            | "Ed25519Signature2018"
+            | "JsonWebSignature2020"
+            | "Ed25519Signature2020" => self
+                .canonicalizer
+                .as_deref()
+                .ok_or(Error::UnsupportedType)?,
+            _ => return Err(Error::UnsupportedType),
+        };
+
+        let jws = proof_options.remove("jws");
+        let proof_value = proof_options.remove("proofValue");
+
+        // the proof options are hashed with the context of the credential
+        if let Some(context) = vc_obj.get("@context") {
+            proof_options.insert("@context".to_owned(), context.clone());
+        }
+
+        let options_bytes = canonicalizer.canonicalize(&Value::Object(proof_options))?;
+        let doc_bytes = canonicalizer.canonicalize(&vc)?;
+        let mut data = hash_by_alg("sha256", &options_bytes, None);
+        data.append(&mut hash_by_alg("sha256", &doc_bytes, None));
+
+        let key = self.resolver.resolve(&method)?;
+
+        let (alg, signing_input, signature) = match (proof_type.as_str(), jws, proof_value) {
+            (
+                "RsaSignature2018" | "Ed25519Signature2018" | "JsonWebSignature2020",
+                Some(Value::String(jws)),
+                _,
+            ) => jws_signing_input(&jws, data)?,
+            (
+                "Ed25519Signature2020" | "DataIntegrityProof",
+                _,
+                Some(Value::String(proof_value)),
+            ) => {
+                let (_base, signature) = multibase::decode(proof_value)
+                    .map_err(|_err| Error::VerifiableCredentialInvalid)?;
+                ("ed25519".to_owned(), data, signature)
+            }
+            _ => return Err(Error::VerifiableCredentialInvalid),
+        };
+
+        // do not let the proof select a different algorithm than the key is used with
+        if !key.alg.eq_ignore_ascii_case(&alg) {
+            return Err(Error::VerifiableCredentialProofMismatch);
+        }
+
+        validate_signature(&alg, &signature, &signing_input, &key.public_key)
+    }
+}
+
+impl std::fmt::Debug for CredentialVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CredentialVerifier")
+    }
+}
+
+impl PartialEq for CredentialVerifier {
+    fn eq(&self, other: &Self) -> bool {
+        // compare the data pointers only, vtables may be duplicated
+        let canonicalizer = |verifier: &Self| {
+            verifier
+                .canonicalizer
+                .as_ref()
+                .map(|c| Arc::as_ptr(c) as *const u8)
+        };
+        Arc::as_ptr(&self.resolver) as *const u8 == Arc::as_ptr(&other.resolver) as *const u8
+            && canonicalizer(self) == canonicalizer(other)
+    }
+}
+
+#[cfg(feature = "file_io")]
+fn validate_signature(alg: &str, signature: &[u8], data: &[u8], public_key: &[u8]) -> Result<()> {
+    let validator = crate::validator::get_validator(alg).ok_or(Error::UnsupportedType)?;
+    match validator.validate(signature, data, public_key) {
+        Ok(true) => Ok(()),
+        _ => Err(Error::VerifiableCredentialProofMismatch),
+    }
+}
+
+// signature validation needs the openssl validators
+#[cfg(not(feature = "file_io"))]
+fn validate_signature(
+    _alg: &str,
+    _signature: &[u8],
+    _data: &[u8],
+    _public_key: &[u8],
+) -> Result<()> {
+    Err(Error::UnsupportedType)
+}
+
+fn get_str(obj: &serde_json::Map<String, Value>, key: &str) -> Result<String> {
+    obj.get(key)
+        .and_then(Value::as_str)
+        .map(|s| s.to_owned())
+        .ok_or(Error::VerifiableCredentialInvalid)
+}
+
+// returns the algorithm, signing input and signature of a detached JWS (RFC 7797)
+fn jws_signing_input(jws: &str, payload: Vec<u8>) -> Result<(String, Vec<u8>, Vec<u8>)> {
+    let parts: Vec<&str> = jws.split('.').collect();
+    if parts.len() != 3 || !parts[1].is_empty() {
+        return Err(Error::VerifiableCredentialInvalid);
+    }
+
+    let header_bytes = base64::decode_config(parts[0], base64::URL_SAFE_NO_PAD)
+        .map_err(|_err| Error::VerifiableCredentialInvalid)?;
+    let header: Value =
+        serde_json::from_slice(&header_bytes).map_err(|_err| Error::VerifiableCredentialInvalid)?;
+    let alg = header
+        .get("alg")
+        .and_then(Value::as_str)
+        .ok_or(Error::VerifiableCredentialInvalid)?;
+    let alg = match alg {
+        "EdDSA" => "ed25519".to_owned(),
+        _ => alg.to_lowercase(),
+    };
+
+    // the payload is not encoded when the b64 header parameter is false
+    let encode_payload = header.get("b64").and_then(Value::as_bool).unwrap_or(true);
+    let mut signing_input = format!("{}.", parts[0]).into_bytes();
+    if encode_payload {
+        signing_input.extend(base64::encode_config(&payload, base64::URL_SAFE_NO_PAD).as_bytes());
+    } else {
+        signing_input.extend(payload);
+    }
+
+    let signature = base64::decode_config(parts[2], base64::URL_SAFE_NO_PAD)
+        .map_err(|_err| Error::VerifiableCredentialInvalid)?;
+
+    Ok((alg, signing_input, signature))
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))] // the test signers use openssl
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    use crate::{openssl::temp_signer, utils::test::TEST_VC, Signer};
+
+    struct TestResolver(VerificationKey);
+
+    impl DidResolver for TestResolver {
+        fn resolve(&self, _verification_method: &str) -> Result<VerificationKey> {
+            Ok(self.0.clone())
+        }
+    }
+
+    // sign TEST_VC with an ed25519 `proofValue` proof using the temp signer
+    fn signed_vc(signer: &dyn Signer, proof_type: &str, cryptosuite: Option<&str>) -> String {
+        let mut vc: Value = serde_json::from_str(TEST_VC).unwrap();
+        let mut proof = vc["proof"].as_object().unwrap().clone();
+        proof.insert("type".to_owned(), proof_type.into());
+        if let Some(cryptosuite) = cryptosuite {
+            proof.insert("cryptosuite".to_owned(), cryptosuite.into());
+        }
+        proof.remove("jws");
+
+        let mut options = proof.clone();
+        options.insert("@context".to_owned(), vc["@context"].clone());
+        vc.as_object_mut().unwrap().remove("proof");
+
+        let canon = JcsCanonicalizer;
+        let mut data = hash_by_alg(
+            "sha256",
+            &canon.canonicalize(&Value::Object(options)).unwrap(),
+            None,
+        );
+        data.append(&mut hash_by_alg(
+            "sha256",
+            &canon.canonicalize(&vc).unwrap(),
+            None,
+        ));
+
+        let signature = signer.sign(&data).unwrap();
+        proof.insert(
+            "proofValue".to_owned(),
+            multibase::encode(multibase::Base::Base58Btc, &signature).into(),
+        );
+        vc.as_object_mut()
+            .unwrap()
+            .insert("proof".to_owned(), Value::Object(proof));
+        vc.to_string()
+    }
+
+    #[test]
+    fn test_ed25519_proof() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (signer, cert_path) = temp_signer::get_ed_signer(&temp_dir.path(), "ed25519", None);
+        let cert = openssl::x509::X509::from_pem(&std::fs::read(&cert_path).unwrap()).unwrap();
+        let key = VerificationKey {
+            alg: "ed25519".to_owned(),
+            public_key: cert.public_key().unwrap().public_key_to_der().unwrap(),
+        };
+        let mut verifier = CredentialVerifier::new(Arc::new(TestResolver(key)));
+
+        let vc_json = signed_vc(&signer, "DataIntegrityProof", Some("eddsa-jcs-2022"));
+        verifier.verify(&vc_json).unwrap();
+
+        // changing the credential must invalidate the proof
+        let tampered = vc_json.replace("Bob Ross", "Bob Rosa");
+        assert!(matches!(
+            verifier.verify(&tampered),
+            Err(Error::VerifiableCredentialProofMismatch)
+        ));
+
+        // Linked Data proofs need a URDNA2015 canonicalizer, JCS stands in for one here
+        let vc_json = signed_vc(&signer, "Ed25519Signature2020", None);
+        assert!(matches!(
+            verifier.verify(&vc_json),
+            Err(Error::UnsupportedType)
+        ));
+        verifier.set_canonicalizer(Arc::new(JcsCanonicalizer));
+        verifier.verify(&vc_json).unwrap();
+    }
+
+    #[test]
+    fn test_jcs() {
+        // the examples of RFC 8785
+        let canonical = |json: &str| {
+            let value: Value = serde_json::from_str(json).unwrap();
+            String::from_utf8(JcsCanonicalizer.canonicalize(&value).unwrap()).unwrap()
+        };
+        assert_eq!(
+            canonical(
+                r#"{
+                    "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                    "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+                    "literals": [null, true, false]
+                }"#
+            ),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+        assert_eq!(
+            canonical(
+                r#"{"€": 1, "\r": 2, "\ufb33": 3, "1": 4, "\ud83d\ude00": 5, "\u0080": 6, "ö": 7}"#
+            ),
+            "{\"\\r\":2,\"1\":4,\"\u{80}\":6,\"ö\":7,\"€\":1,\"😀\":5,\"\u{fb33}\":3}"
+        );
+
+        for (number, expected) in [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (-1.0, "-1"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (-1.5e-7, "-1.5e-7"),
+            (9007199254740993.0, "9007199254740992"),
+            (5e-324, "5e-324"),
+            (f64::MAX, "1.7976931348623157e+308"),
+        ] {
+            assert_eq!(jcs_number(number).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,351 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+//! Options controlling how manifest stores are verified.
+
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    assertions::{AssertionRegistry, SoftBindingRegistry},
+    CredentialVerifier, ResourceLimits, TrustConfig,
+};
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+use crate::{ocsp_utils::SharedOcspFetcher, HttpOcspFetcher, OcspFetcher};
+
+/// How deeply the manifests of ingredients are re-validated
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IngredientValidation {
+    /// Only check that ingredient manifests match the hashes recorded by the parent
+    /// and trust the validation results recorded in the ingredient assertions.
+    /// Recorded failures are reported as statuses without failing the parent.
+    Recorded,
+    /// Also verify the claims of ingredients referenced directly by the active manifest.
+    OneLevel,
+    /// Verify the claims of ingredient manifests up to this many levels below the active manifest.
+    Levels(usize),
+    /// Verify the claims of every ingredient manifest in the ingredient tree.
+    Full,
+}
+
+impl Default for IngredientValidation {
+    fn default() -> Self {
+        IngredientValidation::OneLevel
+    }
+}
+
+impl IngredientValidation {
+    // the number of levels below the active manifest whose claims are verified
+    pub(crate) fn max_depth(self) -> usize {
+        match self {
+            IngredientValidation::Recorded => 0,
+            IngredientValidation::OneLevel => 1,
+            IngredientValidation::Levels(levels) => levels,
+            IngredientValidation::Full => usize::MAX,
+        }
+    }
+}
+
+/// Which parts of a manifest store are validated
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationScope {
+    /// The active manifest and the manifests of its ingredients, as deep as
+    /// [`VerifyOptions::ingredient_validation`] sets
+    Full,
+    /// Only the active manifest, the manifests of its ingredients are not checked
+    ActiveManifest,
+    /// Only the manifest with this label, usually the manifest of an ingredient.
+    /// Its signature and assertions are checked, but not its hard bindings since
+    /// it is not bound to the asset being read.
+    Manifest(String),
+    /// Only the hard bindings of the active manifest to the asset. The signature is
+    /// not checked, so this detects changes to the asset but not who signed it.
+    HardBindings,
+}
+
+impl Default for ValidationScope {
+    fn default() -> Self {
+        ValidationScope::Full
+    }
+}
+
+/// How findings about the signing certificate that the specification only recommends
+/// against are treated, such as a key usage extension that is not marked critical or
+/// a legacy RSASSA-PKCS1-v1_5 signature
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CertCheckMode {
+    /// Findings are errors and the signature is rejected
+    Strict,
+    /// Findings are logged as warnings with an informational status code
+    Lenient,
+}
+
+impl Default for CertCheckMode {
+    fn default() -> Self {
+        CertCheckMode::Lenient
+    }
+}
+
+/// Options used when verifying a manifest store.
+///
+/// The options are passed explicitly to each load or sign, so different requests
+/// can use different policies in the same process.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifyOptions {
+    /// Which manifests are validated and which checks are made
+    pub(crate) scope: ValidationScope,
+    /// Controls how deeply ingredient manifests are re-validated
+    pub(crate) ingredient_validation: IngredientValidation,
+    /// Verifies the proofs of Verifiable Credentials in the active manifest.
+    /// Proofs are not checked when this is `None`.
+    pub(crate) credential_verifier: Option<CredentialVerifier>,
+    /// Algorithms used to check the soft binding assertions of the active manifest
+    pub(crate) soft_bindings: SoftBindingRegistry,
+    /// Schemas of custom assertions. Assertions of the active manifest with a registered
+    /// label are checked against their schema.
+    pub(crate) custom_assertions: AssertionRegistry,
+    /// DER encoded certificates trusted to issue time-stamps. When set, the signature and
+    /// certificate chain of the time-stamp in the active manifest's signature are checked
+    /// against them. Time-stamps are not checked for trust when this is empty.
+    pub(crate) tsa_trust_anchors: Vec<Vec<u8>>,
+    /// Trust anchors, allowed list and EKU configuration the signing certificate of the
+    /// active manifest is checked against. The signing certificate is not checked for
+    /// trust when this is `None`.
+    pub(crate) trust_config: Option<TrustConfig>,
+    /// The time the signing certificate of the active manifest is checked at. When `None`
+    /// it is checked at the time-stamp of the signature, or the current time if there is
+    /// no time-stamp. Setting a time makes results reproducible for audits of archived assets.
+    pub(crate) validation_time: Option<DateTime<Utc>>,
+    /// Whether soft certificate findings reject the signature or are only warnings
+    pub(crate) cert_check_mode: CertCheckMode,
+    /// Limits enforced while the manifest store is parsed, including the maximum
+    /// nesting of JUMBF boxes
+    pub(crate) resource_limits: ResourceLimits,
+    /// Verifies the output after signing, so an asset whose manifest store does not
+    /// validate is never written
+    pub(crate) verify_after_sign: bool,
+    /// Checks the signing and time-stamp certificates against the configured trust
+    /// anchors. When `false` the trust configuration is ignored.
+    pub(crate) verify_trust: bool,
+    /// Fetches the manifest store referenced by the XMP provenance of an asset without
+    /// an embedded manifest store, when the reference is an https URL
+    pub(crate) fetch_remote_manifests: bool,
+    /// Fetches the OCSP status of the signing certificate of the active manifest from
+    /// the responder named in the certificate, and rejects revoked certificates
+    pub(crate) ocsp_fetch: bool,
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    ocsp_fetcher: Option<SharedOcspFetcher>,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        VerifyOptions {
+            scope: ValidationScope::default(),
+            ingredient_validation: IngredientValidation::default(),
+            credential_verifier: None,
+            soft_bindings: SoftBindingRegistry::default(),
+            custom_assertions: AssertionRegistry::default(),
+            tsa_trust_anchors: Vec::new(),
+            trust_config: None,
+            validation_time: None,
+            cert_check_mode: CertCheckMode::default(),
+            resource_limits: ResourceLimits::default(),
+            verify_after_sign: false,
+            verify_trust: true,
+            fetch_remote_manifests: false,
+            ocsp_fetch: false,
+            #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+            ocsp_fetcher: None,
+        }
+    }
+}
+
+impl VerifyOptions {
+    /// Create options with the default verification behavior
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set which parts of the manifest store are validated
+    pub fn set_scope(&mut self, scope: ValidationScope) -> &mut Self {
+        self.scope = scope;
+        self
+    }
+
+    /// Set how deeply ingredient manifests are re-validated
+    pub fn set_ingredient_validation(&mut self, policy: IngredientValidation) -> &mut Self {
+        self.ingredient_validation = policy;
+        self
+    }
+
+    /// Set the verifier used to check Verifiable Credential proofs
+    pub fn set_credential_verifier(&mut self, verifier: CredentialVerifier) -> &mut Self {
+        self.credential_verifier = Some(verifier);
+        self
+    }
+
+    /// Set the soft binding algorithms used to check soft binding assertions
+    pub fn set_soft_bindings(&mut self, registry: SoftBindingRegistry) -> &mut Self {
+        self.soft_bindings = registry;
+        self
+    }
+
+    /// Set the schemas custom assertions are checked against
+    pub fn set_custom_assertions(&mut self, registry: AssertionRegistry) -> &mut Self {
+        self.custom_assertions = registry;
+        self
+    }
+
+    /// Set the DER encoded certificates trusted to issue time-stamps
+    pub fn set_tsa_trust_anchors(&mut self, trust_anchors: Vec<Vec<u8>>) -> &mut Self {
+        self.tsa_trust_anchors = trust_anchors;
+        self
+    }
+
+    /// Set the trust configuration the signing certificate is checked against
+    pub fn set_trust_config(&mut self, trust_config: TrustConfig) -> &mut Self {
+        self.trust_config = Some(trust_config);
+        self
+    }
+
+    /// Set the time the signing certificate is checked at
+    pub fn set_validation_time(&mut self, time: DateTime<Utc>) -> &mut Self {
+        self.validation_time = Some(time);
+        self
+    }
+
+    /// Set whether soft certificate findings reject the signature or are only warnings
+    pub fn set_cert_check_mode(&mut self, mode: CertCheckMode) -> &mut Self {
+        self.cert_check_mode = mode;
+        self
+    }
+
+    /// Set the limits enforced while the manifest store is parsed
+    pub fn set_resource_limits(&mut self, limits: ResourceLimits) -> &mut Self {
+        self.resource_limits = limits;
+        self
+    }
+
+    /// Set whether the output is verified after signing
+    pub fn set_verify_after_sign(&mut self, verify: bool) -> &mut Self {
+        self.verify_after_sign = verify;
+        self
+    }
+
+    /// Set whether certificates are checked against the trust configuration
+    pub fn set_verify_trust(&mut self, verify: bool) -> &mut Self {
+        self.verify_trust = verify;
+        self
+    }
+
+    /// Set whether manifest stores referenced by a remote URL are fetched
+    pub fn set_fetch_remote_manifests(&mut self, fetch: bool) -> &mut Self {
+        self.fetch_remote_manifests = fetch;
+        self
+    }
+
+    /// Set whether the OCSP status of the signing certificate is fetched
+    pub fn set_ocsp_fetch(&mut self, fetch: bool) -> &mut Self {
+        self.ocsp_fetch = fetch;
+        self
+    }
+
+    /// Returns which manifests are validated and which checks are made
+    pub fn scope(&self) -> &ValidationScope {
+        &self.scope
+    }
+
+    /// Returns how deeply ingredient manifests are re-validated
+    pub fn ingredient_validation(&self) -> IngredientValidation {
+        self.ingredient_validation
+    }
+
+    /// Returns the verifier used to check Verifiable Credential proofs, if set
+    pub fn credential_verifier(&self) -> Option<&CredentialVerifier> {
+        self.credential_verifier.as_ref()
+    }
+
+    /// Returns the soft binding algorithms used to check soft binding assertions
+    pub fn soft_bindings(&self) -> &SoftBindingRegistry {
+        &self.soft_bindings
+    }
+
+    /// Returns the schemas custom assertions are checked against
+    pub fn custom_assertions(&self) -> &AssertionRegistry {
+        &self.custom_assertions
+    }
+
+    /// Returns the DER encoded certificates trusted to issue time-stamps
+    pub fn tsa_trust_anchors(&self) -> &[Vec<u8>] {
+        &self.tsa_trust_anchors
+    }
+
+    /// Returns the trust configuration the signing certificate is checked against, if set
+    pub fn trust_config(&self) -> Option<&TrustConfig> {
+        self.trust_config.as_ref()
+    }
+
+    /// Returns the time the signing certificate is checked at, if set
+    pub fn validation_time(&self) -> Option<DateTime<Utc>> {
+        self.validation_time
+    }
+
+    /// Returns whether soft certificate findings reject the signature or are only warnings
+    pub fn cert_check_mode(&self) -> CertCheckMode {
+        self.cert_check_mode
+    }
+
+    /// Returns the limits enforced while the manifest store is parsed
+    pub fn resource_limits(&self) -> ResourceLimits {
+        self.resource_limits
+    }
+
+    /// Returns whether the output is verified after signing
+    pub fn verify_after_sign(&self) -> bool {
+        self.verify_after_sign
+    }
+
+    /// Returns whether certificates are checked against the trust configuration
+    pub fn verify_trust(&self) -> bool {
+        self.verify_trust
+    }
+
+    /// Returns whether manifest stores referenced by a remote URL are fetched
+    pub fn fetch_remote_manifests(&self) -> bool {
+        self.fetch_remote_manifests
+    }
+
+    /// Returns whether the OCSP status of the signing certificate is fetched
+    pub fn ocsp_fetch(&self) -> bool {
+        self.ocsp_fetch
+    }
+
+    /// Set the fetcher OCSP responses are requested with, in place of
+    /// [`HttpOcspFetcher`](crate::HttpOcspFetcher)
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    pub fn set_ocsp_fetcher(&mut self, fetcher: Arc<dyn OcspFetcher>) -> &mut Self {
+        self.ocsp_fetcher = Some(SharedOcspFetcher(fetcher));
+        self
+    }
+
+    // the fetcher OCSP responses are requested with
+    #[cfg(all(feature = "file_io", not(target_os = "wasi")))]
+    pub(crate) fn ocsp_fetcher(&self) -> Arc<dyn OcspFetcher> {
+        match &self.ocsp_fetcher {
+            Some(fetcher) => fetcher.0.clone(),
+            None => Arc::new(HttpOcspFetcher::new()),
+        }
+    }
+}
@@ -13,6 +13,10 @@
 
 #[cfg(target_arch = "wasm32")]
 pub(crate) mod context;
+#[cfg(all(target_arch = "wasm32", feature = "async_signer"))]
+pub(crate) mod webcrypto_signer;
+#[cfg(all(target_arch = "wasm32", feature = "async_signer"))]
+pub use webcrypto_signer::WebCryptoSigner;
 #[cfg(target_arch = "wasm32")]
 pub(crate) mod webcrypto_validator;
 #[cfg(target_arch = "wasm32")]
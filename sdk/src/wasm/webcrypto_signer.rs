@@ -0,0 +1,201 @@
+// Copyright 2022 Adobe. All rights reserved.
+// This file is licensed to you under the Apache License,
+// Version 2.0 (http://www.apache.org/licenses/LICENSE-2.0)
+// or the MIT license (http://opensource.org/licenses/MIT),
+// at your option.
+
+// Unless required by applicable law or agreed to in writing,
+// this software is distributed on an "AS IS" BASIS, WITHOUT
+// WARRANTIES OR REPRESENTATIONS OF ANY KIND, either express or
+// implied. See the LICENSE-MIT and LICENSE-APACHE files for the
+// specific language governing permissions and limitations under
+// each license.
+
+use async_trait::async_trait;
+use js_sys::{Array, Object, Uint8Array};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::CryptoKey;
+
+use c2pa_core::cose::pad_sign1;
+
+use crate::cose_sign::cose_sign1_to_sign;
+use crate::wasm::context::WindowOrWorker;
+use crate::wasm::webcrypto_validator::{
+    data_as_array_buffer, EcKeyImportParams, EcdsaParams, RsaHashedImportParams, RsaPssParams,
+};
+use crate::{AsyncSigner, Error, Result, X5ChainPlacement};
+
+/// Signs claims in the browser with the WebCrypto API.
+///
+/// Supports the es256, es384 and ps256 algorithms. The private key is imported
+/// from PKCS#8 and is never extractable from WebCrypto.
+///
+/// Browsers have no file access, so manifests are signed with
+/// [`Manifest::sign_archive_async`](crate::Manifest::sign_archive_async).
+pub struct WebCryptoSigner {
+    alg: String,
+    pkcs8_key: Vec<u8>,
+    certs: Vec<Vec<u8>>,
+    certs_size: usize,
+    x5chain_placement: X5ChainPlacement,
+}
+
+impl WebCryptoSigner {
+    /// Create a signer from a DER encoded PKCS#8 private key and the DER encoded
+    /// certificate chain, starting with the signing certificate.
+    pub fn new(alg: &str, pkcs8_key: &[u8], certs: Vec<Vec<u8>>) -> Result<Self> {
+        if !matches!(alg, "es256" | "es384" | "ps256") {
+            return Err(Error::UnsupportedType);
+        }
+        if certs.is_empty() {
+            return Err(Error::CoseMissingKey);
+        }
+
+        Ok(WebCryptoSigner {
+            alg: alg.to_owned(),
+            pkcs8_key: pkcs8_key.to_vec(),
+            certs_size: certs.iter().map(|c| c.len()).sum(),
+            certs,
+            x5chain_placement: X5ChainPlacement::default(),
+        })
+    }
+
+    /// Set where the certificate chain is placed, in the unprotected header by default
+    pub fn set_x5chain_placement(&mut self, placement: X5ChainPlacement) -> &mut Self {
+        self.x5chain_placement = placement;
+        self
+    }
+
+    /// Create a signer from a PEM encoded PKCS#8 private key and a PEM encoded
+    /// certificate chain, starting with the signing certificate.
+    pub fn from_pem(alg: &str, pkcs8_pem: &[u8], certs_pem: &[u8]) -> Result<Self> {
+        let (_rem, key) =
+            x509_parser::pem::parse_x509_pem(pkcs8_pem).map_err(|_err| Error::WasmKey)?;
+
+        let mut certs = Vec::new();
+        let mut rem = certs_pem;
+        while let Ok((next, pem)) = x509_parser::pem::parse_x509_pem(rem) {
+            certs.push(pem.contents);
+            rem = next;
+        }
+
+        Self::new(alg, &key.contents, certs)
+    }
+
+    // the WebCrypto import and sign parameters for the algorithm
+    fn webcrypto_params(&self) -> Result<(Object, Object)> {
+        match self.alg.as_ref() {
+            "es256" => Ok((
+                EcKeyImportParams::new("ECDSA", "SHA-256", "P-256").as_js_object(),
+                EcdsaParams::new("ECDSA", "SHA-256").as_js_object(),
+            )),
+            "es384" => Ok((
+                EcKeyImportParams::new("ECDSA", "SHA-384", "P-384").as_js_object(),
+                EcdsaParams::new("ECDSA", "SHA-384").as_js_object(),
+            )),
+            "ps256" => Ok((
+                RsaHashedImportParams::new("RSA-PSS", "SHA-256").as_js_object(),
+                RsaPssParams::new("RSA-PSS", 32).as_js_object(),
+            )),
+            _ => Err(Error::UnsupportedType),
+        }
+    }
+
+    // sign the bytes to be signed, WebCrypto returns ECDSA signatures in the P1363 format COSE uses
+    async fn sign_raw(&self, tbs: &[u8]) -> Result<Vec<u8>> {
+        let subtle_crypto = WindowOrWorker::new()?.subtle_crypto()?;
+        let (import_params, sign_params) = self.webcrypto_params()?;
+
+        let usages = Array::new();
+        usages.push(&"sign".into());
+        let promise = subtle_crypto
+            .import_key_with_object(
+                "pkcs8",
+                &data_as_array_buffer(&self.pkcs8_key),
+                &import_params,
+                false,
+                &usages,
+            )
+            .map_err(|_err| Error::WasmKey)?;
+        let crypto_key: CryptoKey = JsFuture::from(promise)
+            .await
+            .map_err(|_err| Error::WasmKey)?
+            .into();
+
+        let promise = subtle_crypto
+            .sign_with_object_and_buffer_source(
+                &sign_params,
+                &crypto_key,
+                &data_as_array_buffer(tbs),
+            )
+            .map_err(|_err| Error::WasmSigner)?;
+        let signature = JsFuture::from(promise)
+            .await
+            .map_err(|_err| Error::WasmSigner)?;
+
+        Ok(Uint8Array::new(&signature).to_vec())
+    }
+}
+
+#[async_trait(?Send)]
+impl AsyncSigner for WebCryptoSigner {
+    async fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let (mut sign1, tbs) = cose_sign1_to_sign(
+            &self.alg,
+            self.certs.clone(),
+            self.x5chain_placement,
+            self.cosigners(),
+            data,
+        )?;
+        sign1.signature = self.sign_raw(&tbs).await?;
+        Ok(pad_sign1(&mut sign1, self.reserve_size())?)
+    }
+
+    fn reserve_size(&self) -> usize {
+        1024 + self.certs_size // the Cose_Sign1 contains complete certs so account for size
+    }
+
+    fn x5chain_placement(&self) -> X5ChainPlacement {
+        self.x5chain_placement
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use wasm_bindgen_test::*;
+
+    use super::*;
+    use crate::{cose_validator::verify_cose_async, status_tracker::OneShotStatusTracker};
+
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn test_webcrypto_signer() {
+        let key = include_bytes!("../../tests/fixtures/rsa-pss256-expired.pem");
+        let cert = include_bytes!("../../tests/fixtures/rsa-pss256_key-expired.pub");
+        let signer = WebCryptoSigner::from_pem("ps256", key, cert).unwrap();
+
+        let data = b"some sample content to sign";
+        let cose = signer.sign(data).await.unwrap();
+        assert_eq!(cose.len(), signer.reserve_size());
+
+        // the certificate has expired, so only check the signature
+        let mut validation_log = OneShotStatusTracker::new();
+        let info = verify_cose_async(
+            cose,
+            data.to_vec(),
+            b"".to_vec(),
+            true,
+            Default::default(),
+            &mut validation_log,
+        )
+        .await
+        .unwrap();
+        assert!(info.validated);
+
+        assert!(WebCryptoSigner::new("es512", &[], signer.certs.clone()).is_err());
+        assert!(WebCryptoSigner::new("es256", &[], Vec::new()).is_err());
+    }
+}
@@ -11,9 +11,12 @@
 // specific language governing permissions and limitations under
 // each license.
 
+use crate::rust_crypto::{EcValidator, EdValidator, RsaValidator};
+use crate::validator::CoseValidator;
 use crate::wasm::context::WindowOrWorker;
 use crate::{Error, Result};
 use js_sys::{Array, ArrayBuffer, Object, Reflect, Uint8Array};
+use tracing::debug;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{CryptoKey, SubtleCrypto};
@@ -122,7 +125,7 @@ impl EcdsaParams {
     }
 }
 
-fn data_as_array_buffer(data: &[u8]) -> ArrayBuffer {
+pub(crate) fn data_as_array_buffer(data: &[u8]) -> ArrayBuffer {
     let typed_array = Uint8Array::new_with_length(data.len() as u32);
     typed_array.copy_from(data);
     typed_array.buffer()
@@ -159,7 +162,7 @@ async fn crypto_is_verified(
         .map_err(|_err| Error::WasmVerifier)?
         .into();
     let result = verified.is_truthy();
-    web_sys::console::debug_2(&"verified".into(), &result.into());
+    debug!(verified = result, "webcrypto verify");
     Ok(result)
 }
 
@@ -184,14 +187,22 @@ async fn async_validate(
             let usages = Array::new();
             usages.push(&"verify".into());
 
-            let promise = subtle_crypto
-                .import_key_with_object("spki", &key_array_buf, &algorithm, true, &usages)
-                .map_err(|_err| Error::WasmKey)?;
-            let crypto_key: CryptoKey = JsFuture::from(promise)
-                .await
-                .map_err(|_err| Error::WasmKey)?
-                .into();
-            web_sys::console::debug_2(&"CryptoKey".into(), &crypto_key);
+            // Browsers reject keys with the RSASSA-PSS algorithm identifier, so verify those in Rust
+            let promise = match subtle_crypto.import_key_with_object(
+                "spki",
+                &key_array_buf,
+                &algorithm,
+                true,
+                &usages,
+            ) {
+                Ok(promise) => promise,
+                Err(_err) => return validate_rsa_pss(&hash, &sig, &data, &pkey),
+            };
+            let crypto_key: CryptoKey = match JsFuture::from(promise).await {
+                Ok(crypto_key) => crypto_key.into(),
+                Err(_err) => return validate_rsa_pss(&hash, &sig, &data, &pkey),
+            };
+            debug!(?crypto_key, "imported key");
 
             // Create verifier
             // WebCrypto requires us to pass in the salt length to validate the signature unlike some other implementations.
@@ -199,10 +210,7 @@ async fn async_validate(
             // the length of the output of the hash function in bytes.
             // First, let's try to validate with the conventional salt length:
             algorithm = RsaPssParams::new(&algo, salt_len).as_js_object();
-            web_sys::console::debug_2(
-                &"Attempting verification with salt length".into(),
-                &salt_len.into(),
-            );
+            debug!(salt_len, "attempting verification");
             let verified = crypto_is_verified(
                 &subtle_crypto,
                 &algorithm,
@@ -216,10 +224,7 @@ async fn async_validate(
             } else {
                 // If this doesn't work, we can try validating against an alternate salt length:
                 let salt_len = alternate_salt_length(&crypto_key, &salt_len)?;
-                web_sys::console::debug_2(
-                    &"Attempting fallback verification with salt length".into(),
-                    &salt_len.into(),
-                );
+                debug!(salt_len, "attempting fallback verification");
                 algorithm = RsaPssParams::new(&algo, salt_len).as_js_object();
                 crypto_is_verified(
                     &subtle_crypto,
@@ -245,7 +250,7 @@ async fn async_validate(
                 .await
                 .map_err(|_err| Error::WasmKey)?
                 .into();
-            web_sys::console::debug_2(&"CryptoKey".into(), &crypto_key);
+            debug!(?crypto_key, "imported key");
 
             // Create verifier
             crypto_is_verified(
@@ -273,8 +278,11 @@ async fn async_validate(
             let promise = subtle_crypto
                 .import_key_with_object("spki", &key_array_buf, &algorithm, true, &usages)
                 .map_err(|_err| Error::WasmKey)?;
-            let crypto_key: CryptoKey = JsFuture::from(promise).await.unwrap().into();
-            web_sys::console::debug_2(&"CryptoKey".into(), &crypto_key);
+            let crypto_key: CryptoKey = JsFuture::from(promise)
+                .await
+                .map_err(|_err| Error::WasmKey)?
+                .into();
+            debug!(?crypto_key, "imported key");
 
             // Create verifier
             algorithm = EcdsaParams::new(&algo, &hash).as_js_object();
@@ -292,10 +300,7 @@ async fn async_validate(
 }
 
 pub async fn validate_async(alg: &str, sig: &[u8], data: &[u8], pkey: &[u8]) -> Result<bool> {
-    web_sys::console::debug_2(
-        &"Validating with algorithm".into(),
-        &String::from(alg).into(),
-    );
+    debug!(alg, "validating");
 
     match alg {
         "ps256" => {
@@ -397,10 +402,27 @@ pub async fn validate_async(alg: &str, sig: &[u8], data: &[u8], pkey: &[u8]) ->
             )
             .await
         }
+        // WebCrypto does not support secp256k1 so verify it in Rust
+        #[cfg(feature = "es256k")]
+        "es256k" => EcValidator::new("es256k").validate(sig, data, pkey),
+        // WebCrypto does not support Ed25519 in every browser so verify it in Rust
+        "ed25519" => EdValidator::new("ed25519").validate(sig, data, pkey),
         _ => return Err(Error::UnsupportedType),
     }
 }
 
+// the salt length is recovered from the signature, so signatures using either
+// salt length accepted by async_validate are verified
+fn validate_rsa_pss(hash: &str, sig: &[u8], data: &[u8], pkey: &[u8]) -> Result<bool> {
+    let alg = match hash {
+        "SHA-256" => "ps256",
+        "SHA-384" => "ps384",
+        "SHA-512" => "ps512",
+        _ => return Err(Error::UnsupportedType),
+    };
+    RsaValidator::new(alg).validate(sig, data, pkey)
+}
+
 #[cfg(test)]
 pub mod tests {
     #![allow(clippy::unwrap_used)]
@@ -481,4 +503,37 @@ pub mod tests {
 
         assert_eq!(validated, false);
     }
+
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[wasm_bindgen_test]
+    async fn test_rust_fallbacks() {
+        use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey};
+
+        let secret = SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let public = PublicKey::from(&secret);
+        let data = b"some sample content to sign";
+        let sig = ExpandedSecretKey::from(&secret).sign(data, &public);
+
+        // SubjectPublicKeyInfo for an Ed25519 key
+        let mut spki = vec![
+            0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+        ];
+        spki.extend_from_slice(public.as_bytes());
+
+        assert!(validate_async("ed25519", &sig.to_bytes(), data, &spki)
+            .await
+            .unwrap());
+        assert!(
+            !validate_async("ed25519", &sig.to_bytes(), b"other content", &spki)
+                .await
+                .unwrap()
+        );
+
+        let sig_bytes = include_bytes!("../../tests/fixtures/sig.data");
+        let data_bytes = include_bytes!("../../tests/fixtures/data.data");
+        let key_bytes = include_bytes!("../../tests/fixtures/key.data");
+        assert!(validate_rsa_pss("SHA-256", sig_bytes, data_bytes, key_bytes).unwrap());
+        assert!(!validate_rsa_pss("SHA-256", sig_bytes, b"other content", key_bytes).unwrap());
+    }
 }
@@ -13,7 +13,7 @@
 
 /// complete functional integration test with acquisitions and ingredients
 // isolate from wasm by wrapping in module
-#[cfg(feature = "file_io")]
+#[cfg(all(feature = "file_io", not(target_os = "wasi")))]
 mod integration_1 {
 
     use c2pa::{